@@ -37,10 +37,13 @@ impl MessageProcessor {
     ) -> Self {
         let outgoing = Arc::new(outgoing);
         let auth_manager = AuthManager::shared(
-            config.codex_home.clone(),
+            config.auth_storage_settings(),
             false,
             config.cli_auth_credentials_store_mode,
         );
+        if let Err(err) = auth_manager.watch_auth_files() {
+            tracing::warn!("failed to watch auth files: {err}");
+        }
         let conversation_manager = Arc::new(ConversationManager::new(
             auth_manager.clone(),
             SessionSource::VSCode,
@@ -6,8 +6,10 @@ use base64::Engine;
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use chrono::DateTime;
 use chrono::Utc;
+use codex_core::auth::AccountMetadata;
 use codex_core::auth::AuthCredentialsStoreMode;
 use codex_core::auth::AuthDotJson;
+use codex_core::auth::AuthStorageSettings;
 use codex_core::auth::save_auth;
 use codex_core::token_data::TokenData;
 use codex_core::token_data::parse_id_token;
@@ -130,7 +132,13 @@ pub fn write_chatgpt_auth(
         tokens: Some(tokens),
         last_refresh,
         account_state: None,
+        metadata: AccountMetadata::default(),
     };
 
-    save_auth(codex_home, &auth, cli_auth_credentials_store_mode).context("write auth.json")
+    save_auth(
+        &AuthStorageSettings::new(codex_home.to_path_buf()),
+        &auth,
+        cli_auth_credentials_store_mode,
+    )
+    .context("write auth.json")
 }
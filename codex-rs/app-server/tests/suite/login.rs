@@ -11,6 +11,7 @@ use codex_app_server_protocol::LoginChatGptResponse;
 use codex_app_server_protocol::LogoutChatGptResponse;
 use codex_app_server_protocol::RequestId;
 use codex_core::auth::AuthCredentialsStoreMode;
+use codex_core::auth::AuthStorageSettings;
 use codex_login::login_with_api_key;
 use serial_test::serial;
 use std::path::Path;
@@ -47,7 +48,7 @@ async fn logout_chatgpt_removes_auth() -> Result<()> {
     let codex_home = TempDir::new()?;
     create_config_toml(codex_home.path())?;
     login_with_api_key(
-        codex_home.path(),
+        &AuthStorageSettings::new(codex_home.path().to_path_buf()),
         "sk-test-key",
         AuthCredentialsStoreMode::File,
     )?;
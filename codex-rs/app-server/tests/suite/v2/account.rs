@@ -18,6 +18,7 @@ use codex_app_server_protocol::LogoutAccountResponse;
 use codex_app_server_protocol::RequestId;
 use codex_app_server_protocol::ServerNotification;
 use codex_core::auth::AuthCredentialsStoreMode;
+use codex_core::auth::AuthStorageSettings;
 use codex_login::login_with_api_key;
 use codex_protocol::account::PlanType as AccountPlanType;
 use pretty_assertions::assert_eq;
@@ -82,7 +83,7 @@ async fn logout_account_removes_auth_and_notifies() -> Result<()> {
     create_config_toml(codex_home.path(), CreateConfigTomlParams::default())?;
 
     login_with_api_key(
-        codex_home.path(),
+        &AuthStorageSettings::new(codex_home.path().to_path_buf()),
         "sk-test-key",
         AuthCredentialsStoreMode::File,
     )?;
@@ -1,6 +1,7 @@
 mod parser;
 mod seek_sequence;
 mod standalone_executable;
+mod verify;
 
 use std::collections::HashMap;
 use std::path::Path;
@@ -25,6 +26,9 @@ use tree_sitter::StreamingIterator;
 use tree_sitter_bash::LANGUAGE as BASH;
 
 pub use standalone_executable::main;
+pub use verify::ChunkCheck;
+pub use verify::NearestLines;
+pub use verify::verify_update_chunks;
 
 /// Detailed instructions for gpt-4.1 on how to use the `apply_patch` tool.
 pub const APPLY_PATCH_TOOL_INSTRUCTIONS: &str = include_str!("../apply_patch_tool_instructions.md");
@@ -638,6 +642,24 @@ fn derive_new_contents_from_chunks(
     path: &Path,
     chunks: &[UpdateFileChunk],
 ) -> std::result::Result<AppliedPatch, ApplyPatchError> {
+    let (original_contents, original_lines) = read_original_lines(path)?;
+    let crlf = uses_crlf(&original_contents);
+    let replacements = compute_replacements(&original_lines, path, chunks)?;
+    let new_lines = apply_replacements(original_lines, &replacements);
+    let mut new_lines = new_lines;
+    if !new_lines.last().is_some_and(String::is_empty) {
+        new_lines.push(String::new());
+    }
+    let new_contents = new_lines.join(if crlf { "\r\n" } else { "\n" });
+    Ok(AppliedPatch {
+        original_contents,
+        new_contents,
+    })
+}
+
+/// Read the file at `path`, returning its contents along with the lines that
+/// chunks are matched against.
+fn read_original_lines(path: &Path) -> std::result::Result<(String, Vec<String>), ApplyPatchError> {
     let original_contents = match std::fs::read_to_string(path) {
         Ok(contents) => contents,
         Err(err) => {
@@ -648,25 +670,30 @@ fn derive_new_contents_from_chunks(
         }
     };
 
-    let mut original_lines: Vec<String> = original_contents.split('\n').map(String::from).collect();
+    // Files that use CRLF are matched and rebuilt line by line without the
+    // `\r`, then written back with CRLF, so patches (whose lines never carry
+    // `\r`) keep the file's convention instead of mixing endings.
+    let crlf = uses_crlf(&original_contents);
+    let mut original_lines: Vec<String> = original_contents
+        .split('\n')
+        .map(|line| match line.strip_suffix('\r') {
+            Some(stripped) if crlf => stripped.to_string(),
+            _ => line.to_string(),
+        })
+        .collect();
 
     // Drop the trailing empty element that results from the final newline so
     // that line counts match the behaviour of standard `diff`.
     if original_lines.last().is_some_and(String::is_empty) {
         original_lines.pop();
     }
+    Ok((original_contents, original_lines))
+}
 
-    let replacements = compute_replacements(&original_lines, path, chunks)?;
-    let new_lines = apply_replacements(original_lines, &replacements);
-    let mut new_lines = new_lines;
-    if !new_lines.last().is_some_and(String::is_empty) {
-        new_lines.push(String::new());
-    }
-    let new_contents = new_lines.join("\n");
-    Ok(AppliedPatch {
-        original_contents,
-        new_contents,
-    })
+/// Whether most line breaks in `contents` are CRLF.
+fn uses_crlf(contents: &str) -> bool {
+    let crlf = contents.matches("\r\n").count();
+    crlf > 0 && crlf * 2 >= contents.matches('\n').count()
 }
 
 /// Compute a list of replacements needed to transform `original_lines` into the
@@ -712,40 +739,8 @@ fn compute_replacements(
             continue;
         }
 
-        // Otherwise, try to match the existing lines in the file with the old lines
-        // from the chunk. If found, schedule that region for replacement.
-        // Attempt to locate the `old_lines` verbatim within the file.  In many
-        // real‑world diffs the last element of `old_lines` is an *empty* string
-        // representing the terminating newline of the region being replaced.
-        // This sentinel is not present in `original_lines` because we strip the
-        // trailing empty slice emitted by `split('\n')`.  If a direct search
-        // fails and the pattern ends with an empty string, retry without that
-        // final element so that modifications touching the end‑of‑file can be
-        // located reliably.
-
-        let mut pattern: &[String] = &chunk.old_lines;
-        let mut found =
-            seek_sequence::seek_sequence(original_lines, pattern, line_index, chunk.is_end_of_file);
-
-        let mut new_slice: &[String] = &chunk.new_lines;
-
-        if found.is_none() && pattern.last().is_some_and(String::is_empty) {
-            // Retry without the trailing empty line which represents the final
-            // newline in the file.
-            pattern = &pattern[..pattern.len() - 1];
-            if new_slice.last().is_some_and(String::is_empty) {
-                new_slice = &new_slice[..new_slice.len() - 1];
-            }
-
-            found = seek_sequence::seek_sequence(
-                original_lines,
-                pattern,
-                line_index,
-                chunk.is_end_of_file,
-            );
-        }
-
-        if let Some(start_idx) = found {
+        if let Some((start_idx, pattern, new_slice)) = seek_chunk(original_lines, chunk, line_index)
+        {
             replacements.push((start_idx, pattern.len(), new_slice.to_vec()));
             line_index = start_idx + pattern.len();
         } else {
@@ -762,6 +757,43 @@ fn compute_replacements(
     Ok(replacements)
 }
 
+/// Locate a chunk's `old_lines` in `original_lines` at or after `line_index`,
+/// returning the start index together with the old and new lines to use.
+fn seek_chunk<'a>(
+    original_lines: &[String],
+    chunk: &'a UpdateFileChunk,
+    line_index: usize,
+) -> Option<(usize, &'a [String], &'a [String])> {
+    // Attempt to locate the `old_lines` verbatim within the file.  In many
+    // real‑world diffs the last element of `old_lines` is an *empty* string
+    // representing the terminating newline of the region being replaced.
+    // This sentinel is not present in `original_lines` because we strip the
+    // trailing empty slice emitted by `split('\n')`.  If a direct search
+    // fails and the pattern ends with an empty string, retry without that
+    // final element so that modifications touching the end‑of‑file can be
+    // located reliably.
+
+    let mut pattern: &[String] = &chunk.old_lines;
+    let mut found =
+        seek_sequence::seek_sequence(original_lines, pattern, line_index, chunk.is_end_of_file);
+
+    let mut new_slice: &[String] = &chunk.new_lines;
+
+    if found.is_none() && pattern.last().is_some_and(String::is_empty) {
+        // Retry without the trailing empty line which represents the final
+        // newline in the file.
+        pattern = &pattern[..pattern.len() - 1];
+        if new_slice.last().is_some_and(String::is_empty) {
+            new_slice = &new_slice[..new_slice.len() - 1];
+        }
+
+        found =
+            seek_sequence::seek_sequence(original_lines, pattern, line_index, chunk.is_end_of_file);
+    }
+
+    found.map(|start_idx| (start_idx, pattern, new_slice))
+}
+
 /// Apply the `(start_index, old_len, new_lines)` replacements to `original_lines`,
 /// returning the modified file contents as a vector of lines.
 fn apply_replacements(
@@ -1144,6 +1176,26 @@ PATCH"#,
         assert_eq!(contents, "foo\nbaz\n");
     }
 
+    #[test]
+    fn test_update_file_hunk_preserves_crlf_line_endings() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("update.txt");
+        fs::write(&path, "foo\r\nbar\r\nqux\r\n").unwrap();
+        let patch = wrap_patch(&format!(
+            r#"*** Update File: {}
+@@
+ foo
+-bar
++baz"#,
+            path.display()
+        ));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents, "foo\r\nbaz\r\nqux\r\n");
+    }
+
     #[test]
     fn test_update_file_hunk_can_move_file() {
         let dir = tempdir().unwrap();
@@ -0,0 +1,200 @@
+use std::path::Path;
+
+use similar::TextDiff;
+
+use crate::ApplyPatchError;
+use crate::UpdateFileChunk;
+use crate::read_original_lines;
+use crate::seek_chunk;
+use crate::seek_sequence::seek_sequence;
+
+/// A lone line must be at least this similar to a pattern line to be offered
+/// as the nearest match when no pattern line matches exactly.
+const MIN_LINE_SIMILARITY: f32 = 0.6;
+
+/// How a single `@@` chunk of an `*** Update File` hunk would fare against
+/// the file on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChunkCheck {
+    /// The chunk's old lines were found starting at this 1-based line.
+    Applies { line: usize },
+    /// The `@@` context line was not found after the previous chunk.
+    ContextNotFound {
+        context: String,
+        nearest: Option<NearestLines>,
+    },
+    /// The chunk's old lines were not found after its context.
+    LinesNotFound { nearest: Option<NearestLines> },
+}
+
+/// The region of the file that most resembles lines a chunk expected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NearestLines {
+    /// 1-based line number of the first entry in `lines`.
+    pub line: usize,
+    pub lines: Vec<String>,
+}
+
+/// Check each chunk against the file at `path` the way `apply_patch` would
+/// locate it, without writing anything. Unlike a real application, checking
+/// continues past a chunk that does not apply.
+pub fn verify_update_chunks(
+    path: &Path,
+    chunks: &[UpdateFileChunk],
+) -> Result<Vec<ChunkCheck>, ApplyPatchError> {
+    let (_, original_lines) = read_original_lines(path)?;
+    Ok(check_chunks(&original_lines, chunks))
+}
+
+fn check_chunks(original_lines: &[String], chunks: &[UpdateFileChunk]) -> Vec<ChunkCheck> {
+    let mut line_index = 0;
+    let mut checks = Vec::with_capacity(chunks.len());
+    for chunk in chunks {
+        let mut search_from = line_index;
+        if let Some(ctx_line) = &chunk.change_context {
+            let context = std::slice::from_ref(ctx_line);
+            match seek_sequence(original_lines, context, line_index, false) {
+                Some(idx) => {
+                    search_from = idx + 1;
+                    line_index = search_from;
+                }
+                None => {
+                    checks.push(ChunkCheck::ContextNotFound {
+                        context: ctx_line.clone(),
+                        nearest: nearest_lines(original_lines, context),
+                    });
+                    continue;
+                }
+            }
+        }
+
+        if chunk.old_lines.is_empty() {
+            // Pure additions go at the end of the file.
+            checks.push(ChunkCheck::Applies {
+                line: original_lines.len() + 1,
+            });
+            continue;
+        }
+
+        match seek_chunk(original_lines, chunk, search_from) {
+            Some((start_idx, pattern, _)) => {
+                line_index = start_idx + pattern.len();
+                checks.push(ChunkCheck::Applies {
+                    line: start_idx + 1,
+                });
+            }
+            None => checks.push(ChunkCheck::LinesNotFound {
+                nearest: nearest_lines(original_lines, &chunk.old_lines),
+            }),
+        }
+    }
+    checks
+}
+
+/// Find the window of `original_lines` sharing the most non-blank lines with
+/// `pattern` (ignoring surrounding whitespace). When no line matches at all,
+/// fall back to the single line most similar to the first non-blank pattern
+/// line.
+fn nearest_lines(original_lines: &[String], pattern: &[String]) -> Option<NearestLines> {
+    let window_at = |start: usize| {
+        let end = (start + pattern.len()).min(original_lines.len());
+        Some(NearestLines {
+            line: start + 1,
+            lines: original_lines[start..end].to_vec(),
+        })
+    };
+
+    let mut best: Option<(usize, usize)> = None;
+    for start in 0..original_lines.len() {
+        let score = original_lines[start..]
+            .iter()
+            .zip(pattern)
+            .filter(|(line, expected)| {
+                let expected = expected.trim();
+                !expected.is_empty() && line.trim() == expected
+            })
+            .count();
+        if score > 0 && best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((start, score));
+        }
+    }
+    if let Some((start, _)) = best {
+        return window_at(start);
+    }
+
+    let (offset, expected) = pattern
+        .iter()
+        .enumerate()
+        .find(|(_, line)| !line.trim().is_empty())?;
+    let expected = expected.trim();
+    let (index, similarity) = original_lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let similarity = TextDiff::from_chars(line.trim(), expected).ratio();
+            (index, similarity)
+        })
+        .fold(None, |best: Option<(usize, f32)>, candidate| match best {
+            Some(best) if best.1 >= candidate.1 => Some(best),
+            _ => Some(candidate),
+        })?;
+    if similarity < MIN_LINE_SIMILARITY {
+        return None;
+    }
+    window_at(index.saturating_sub(offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    fn chunk(context: Option<&str>, old_lines: &str, new_lines: &str) -> UpdateFileChunk {
+        UpdateFileChunk {
+            change_context: context.map(str::to_string),
+            old_lines: lines(old_lines),
+            new_lines: lines(new_lines),
+            is_end_of_file: false,
+        }
+    }
+
+    #[test]
+    fn check_chunks_reports_each_chunk_and_nearest_lines() {
+        let original = lines("fn main() {\n    let x = 1;\n    println!(\"{x}\");\n}\n");
+        let chunks = vec![
+            chunk(None, "    let x = 1;", "    let x = 2;"),
+            chunk(
+                None,
+                "    let y = 1;\n    println!(\"{x}\");",
+                "    println!(\"{x}!\");",
+            ),
+            chunk(Some("fn mian() {"), "}", "}\n"),
+            chunk(None, "", "// end"),
+        ];
+
+        assert_eq!(
+            check_chunks(&original, &chunks),
+            vec![
+                ChunkCheck::Applies { line: 2 },
+                ChunkCheck::LinesNotFound {
+                    nearest: Some(NearestLines {
+                        line: 2,
+                        lines: lines("    let x = 1;\n    println!(\"{x}\");"),
+                    }),
+                },
+                ChunkCheck::ContextNotFound {
+                    context: "fn mian() {".to_string(),
+                    nearest: Some(NearestLines {
+                        line: 1,
+                        lines: lines("fn main() {"),
+                    }),
+                },
+                ChunkCheck::Applies { line: 5 },
+            ]
+        );
+    }
+}
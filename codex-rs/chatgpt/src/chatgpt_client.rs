@@ -13,8 +13,11 @@ pub(crate) async fn chatgpt_get_request<T: DeserializeOwned>(
     path: String,
 ) -> anyhow::Result<T> {
     let chatgpt_base_url = &config.chatgpt_base_url;
-    init_chatgpt_token_from_auth(&config.codex_home, config.cli_auth_credentials_store_mode)
-        .await?;
+    init_chatgpt_token_from_auth(
+        &config.auth_storage_settings(),
+        config.cli_auth_credentials_store_mode,
+    )
+    .await?;
 
     // Make direct HTTP request to ChatGPT backend API with the token
     let client = create_client();
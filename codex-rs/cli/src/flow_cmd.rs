@@ -1,103 +1,64 @@
-use std::path::PathBuf;
+use std::path::Path;
 
 use anyhow::Result;
-use clap::ArgAction;
-use clap::Args;
+use anyhow::bail;
 use clap::Parser;
-use clap::Subcommand;
-use codex_flow::config;
-use codex_flow::runner;
-use codex_flow::runner::RunSummary;
-use codex_flow::scaffold;
+use codex_core::coco_flow::CocoFlowRunner;
+use codex_flow::cli::args::Command as FlowCommand;
+use codex_flow::cli::args::GlobalArgs;
 
+/// `codex flow` mirrors the standalone `codex-flow` binary, including the
+/// persistence-aware `run`, `resume`, and `state` commands.
 #[derive(Debug, Parser)]
 pub struct FlowCli {
+    #[command(flatten)]
+    global: GlobalArgs,
+
     #[command(subcommand)]
     command: FlowCommand,
 }
 
 impl FlowCli {
+    /// Runs the flow command and exits with its failure-class exit code, so
+    /// `codex flow` and `codex-flow` report failures identically.
     pub fn run(self) -> Result<()> {
-        match self.command {
-            FlowCommand::Init(args) => handle_init(args),
-            FlowCommand::Run(args) => handle_run(args),
+        let code = codex_flow::cli::execute(self.global, self.command);
+        if code != 0 {
+            std::process::exit(code);
         }
+        Ok(())
     }
 }
 
-#[derive(Debug, Subcommand)]
-pub enum FlowCommand {
-    /// Initialize a .codex-flow workspace with sample prompts and workflows.
-    Init(FlowInitArgs),
-
-    /// Execute a workflow definition.
-    Run(FlowRunArgs),
-}
-
-#[derive(Debug, Args)]
-pub struct FlowInitArgs {
-    /// Target directory to place .codex-flow (defaults to current directory).
-    #[arg(long)]
-    dir: Option<PathBuf>,
-
-    /// Force overwriting existing files.
-    #[arg(long)]
-    force: bool,
-
-    /// Templates source directory (default: embedded prompts bundled in the binary).
-    #[arg(long, value_name = "DIR")]
-    templates_dir: Option<PathBuf>,
-}
-
-#[derive(Debug, Args)]
-pub struct FlowRunArgs {
-    /// Path to workflow TOML file.
-    #[arg(value_name = "FILE")]
-    file: PathBuf,
-
-    /// Force mock execution (overrides defaults.mock).
-    #[arg(long, action = ArgAction::SetTrue, conflicts_with = "no_mock")]
-    mock: bool,
-
-    /// Disable mock execution (overrides defaults.mock).
-    #[arg(long, action = ArgAction::SetTrue, conflicts_with = "mock")]
-    no_mock: bool,
-
-    /// Enable verbose logs.
-    #[arg(long)]
-    verbose: bool,
-}
-
-fn handle_init(args: FlowInitArgs) -> Result<()> {
-    let dir = args.dir.unwrap_or(std::env::current_dir()?);
-    let templates = args.templates_dir.as_deref();
-    scaffold::init_scaffold(&dir, templates, args.force)
-}
-
-fn handle_run(args: FlowRunArgs) -> Result<()> {
-    let verbose = args.verbose;
-    let mock_override = if args.mock {
-        Some(true)
-    } else if args.no_mock {
-        Some(false)
-    } else {
-        None
-    };
-
-    if let Ok(wf) = config::WorkflowFile::load(&args.file) {
-        let mock = mock_override.unwrap_or_else(|| wf.defaults.mock.unwrap_or(true));
-        runner::run_workflow_file(&wf, runner::RunOptions { mock, verbose }, None);
-    } else {
-        let cfg = config::FlowConfig::load(&args.file)?;
-        let mock = mock_override.unwrap_or_else(|| cfg.defaults.mock.unwrap_or(true));
-        let name = cfg
-            .workflows
-            .keys()
-            .next()
-            .cloned()
-            .unwrap_or_else(|| "main".to_string());
-        runner::run_workflow(&cfg, &name, runner::RunOptions { mock, verbose }, None);
+/// Runs `coco flow run <workflow.toml>` for sessions started by this binary.
+pub struct CocoFlowAdapter;
+
+impl CocoFlowRunner for CocoFlowAdapter {
+    fn run(
+        &self,
+        workflow: &Path,
+        cwd: &Path,
+        mut progress: Box<dyn FnMut(String) + Send>,
+    ) -> Result<String> {
+        // Workflow paths and the `.codex-flow` runtime tree resolve against
+        // the process directory, which cannot change under a running session.
+        let current_dir = std::env::current_dir()?;
+        if current_dir != cwd {
+            bail!(
+                "coco flow run must be started from {}",
+                current_dir.display()
+            );
+        }
+        let (name, summary) = codex_flow::cli::run_embedded(workflow, move |event| {
+            progress(event.describe());
+        })?;
+        let mut text = format!(
+            "workflow `{name}` finished: {} step(s) executed, {} skipped",
+            summary.executed_steps, summary.skipped_steps
+        );
+        if let Some(run_id) = summary.run_id {
+            text.push_str(&format!(", run id {run_id}"));
+        }
+        Ok(text)
     }
-
-    Ok(())
 }
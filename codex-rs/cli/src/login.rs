@@ -1,10 +1,30 @@
+use chrono::DateTime;
+use chrono::Utc;
 use codex_app_server_protocol::AuthMode;
 use codex_common::CliConfigOverrides;
 use codex_core::CodexAuth;
+use codex_core::auth::AUTH_AUDIT_FILE;
+use codex_core::auth::AccountCheck;
+use codex_core::auth::AccountHealth;
+use codex_core::auth::AccountMetadata;
+use codex_core::auth::AccountSummary;
+use codex_core::auth::AuthAuditEntry;
+use codex_core::auth::AuthAuditEvent;
 use codex_core::auth::AuthCredentialsStoreMode;
+use codex_core::auth::AuthStorageSettings;
 use codex_core::auth::CLIENT_ID;
+use codex_core::auth::check_accounts;
+use codex_core::auth::clear_account_issues;
+use codex_core::auth::export_credentials;
+use codex_core::auth::import_credentials;
+use codex_core::auth::list_accounts;
 use codex_core::auth::login_with_api_key;
 use codex_core::auth::logout;
+use codex_core::auth::migrate_credentials;
+use codex_core::auth::pin_account;
+use codex_core::auth::read_auth_audit;
+use codex_core::auth::tag_account;
+use codex_core::auth::unpin_account;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_login::ServerOptions;
@@ -13,15 +33,17 @@ use codex_login::run_login_server;
 use codex_protocol::config_types::ForcedLoginMethod;
 use std::io::IsTerminal;
 use std::io::Read;
+use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
 
 pub async fn login_with_chatgpt(
-    codex_home: PathBuf,
+    storage_settings: AuthStorageSettings,
     forced_chatgpt_workspace_id: Option<String>,
     cli_auth_credentials_store_mode: AuthCredentialsStoreMode,
 ) -> std::io::Result<()> {
     let opts = ServerOptions::new(
-        codex_home,
+        storage_settings,
         CLIENT_ID.to_string(),
         forced_chatgpt_workspace_id,
         cli_auth_credentials_store_mode,
@@ -47,7 +69,7 @@ pub async fn run_login_with_chatgpt(cli_config_overrides: CliConfigOverrides) ->
     let forced_chatgpt_workspace_id = config.forced_chatgpt_workspace_id.clone();
 
     match login_with_chatgpt(
-        config.codex_home,
+        config.auth_storage_settings(),
         forced_chatgpt_workspace_id,
         config.cli_auth_credentials_store_mode,
     )
@@ -76,7 +98,7 @@ pub async fn run_login_with_api_key(
     }
 
     match login_with_api_key(
-        &config.codex_home,
+        &config.auth_storage_settings(),
         &api_key,
         config.cli_auth_credentials_store_mode,
     ) {
@@ -131,7 +153,7 @@ pub async fn run_login_with_device_code(
     }
     let forced_chatgpt_workspace_id = config.forced_chatgpt_workspace_id.clone();
     let mut opts = ServerOptions::new(
-        config.codex_home,
+        config.auth_storage_settings(),
         client_id.unwrap_or(CLIENT_ID.to_string()),
         forced_chatgpt_workspace_id,
         config.cli_auth_credentials_store_mode,
@@ -154,7 +176,10 @@ pub async fn run_login_with_device_code(
 pub async fn run_login_status(cli_config_overrides: CliConfigOverrides) -> ! {
     let config = load_config_or_exit(cli_config_overrides).await;
 
-    match CodexAuth::from_auth_storage(&config.codex_home, config.cli_auth_credentials_store_mode) {
+    match CodexAuth::from_auth_storage(
+        &config.auth_storage_settings(),
+        config.cli_auth_credentials_store_mode,
+    ) {
         Ok(Some(auth)) => match auth.mode {
             AuthMode::ApiKey => match auth.get_token().await {
                 Ok(api_key) => {
@@ -185,7 +210,10 @@ pub async fn run_login_status(cli_config_overrides: CliConfigOverrides) -> ! {
 pub async fn run_logout(cli_config_overrides: CliConfigOverrides) -> ! {
     let config = load_config_or_exit(cli_config_overrides).await;
 
-    match logout(&config.codex_home, config.cli_auth_credentials_store_mode) {
+    match logout(
+        &config.auth_storage_settings(),
+        config.cli_auth_credentials_store_mode,
+    ) {
         Ok(true) => {
             eprintln!("Successfully logged out");
             std::process::exit(0);
@@ -201,6 +229,497 @@ pub async fn run_logout(cli_config_overrides: CliConfigOverrides) -> ! {
     }
 }
 
+pub async fn run_auth_accounts(cli_config_overrides: CliConfigOverrides) -> ! {
+    let config = load_config_or_exit(cli_config_overrides).await;
+
+    match list_accounts(&config.auth_storage_settings()) {
+        Ok(accounts) if accounts.is_empty() => {
+            eprintln!(
+                "No accounts under {}; logins are stored in auth.json",
+                config.accounts_dir.display()
+            );
+            std::process::exit(0);
+        }
+        Ok(accounts) => {
+            print!("{}", format_accounts(&accounts, Utc::now()));
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error listing accounts: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+pub async fn run_auth_usage(cli_config_overrides: CliConfigOverrides) -> ! {
+    let config = load_config_or_exit(cli_config_overrides).await;
+
+    match list_accounts(&config.auth_storage_settings()) {
+        Ok(accounts) if accounts.is_empty() => {
+            eprintln!(
+                "No accounts under {}; usage is only tracked per stored account",
+                config.accounts_dir.display()
+            );
+            std::process::exit(0);
+        }
+        Ok(accounts) => {
+            print!("{}", format_usage(&accounts));
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error reading account usage: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+pub async fn run_auth_check(cli_config_overrides: CliConfigOverrides, invalidate: bool) -> ! {
+    let config = load_config_or_exit(cli_config_overrides).await;
+
+    match check_accounts(
+        &config.auth_storage_settings(),
+        &config.chatgpt_base_url,
+        invalidate,
+    )
+    .await
+    {
+        Ok(checks) if checks.is_empty() => {
+            eprintln!(
+                "No accounts under {} to check",
+                config.accounts_dir.display()
+            );
+            std::process::exit(0);
+        }
+        Ok(checks) => {
+            print!("{}", format_checks(&checks));
+            let dead = checks
+                .iter()
+                .filter(|check| check.health.is_dead() && check.invalidated.is_none())
+                .count();
+            if dead > 0 && !invalidate {
+                eprintln!(
+                    "Run `codex auth check --invalidate` to stop rotating through {dead} dead account(s)"
+                );
+            }
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error checking accounts: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+pub async fn run_auth_audit(
+    cli_config_overrides: CliConfigOverrides,
+    account: Option<String>,
+    limit: Option<usize>,
+) -> ! {
+    let config = load_config_or_exit(cli_config_overrides).await;
+
+    match read_auth_audit(&config.codex_home) {
+        Ok(mut entries) => {
+            if let Some(account) = &account {
+                entries.retain(|entry| &entry.account == account);
+            }
+            if let Some(limit) = limit {
+                entries.drain(..entries.len().saturating_sub(limit));
+            }
+            if entries.is_empty() {
+                eprintln!(
+                    "No entries in {}",
+                    config.codex_home.join(AUTH_AUDIT_FILE).display()
+                );
+            } else {
+                print!("{}", format_audit(&entries));
+            }
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error reading {AUTH_AUDIT_FILE}: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+pub async fn run_auth_clear_issues(
+    cli_config_overrides: CliConfigOverrides,
+    email: Option<String>,
+) -> ! {
+    let config = load_config_or_exit(cli_config_overrides).await;
+
+    match clear_account_issues(&config.auth_storage_settings(), email.as_deref()) {
+        Ok(cleared) if cleared.is_empty() => {
+            eprintln!("No recorded issues to clear");
+            std::process::exit(0);
+        }
+        Ok(cleared) => {
+            for email in cleared {
+                eprintln!("Cleared recorded issue for {email}");
+            }
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error clearing account issues: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+pub async fn run_auth_use(cli_config_overrides: CliConfigOverrides, email: String) -> ! {
+    let config = load_config_or_exit(cli_config_overrides).await;
+
+    match pin_account(&config.auth_storage_settings(), &email) {
+        Ok(path) => {
+            eprintln!(
+                "Pinned {email}; Codex will use {} until you run `codex auth unpin`",
+                path.display()
+            );
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error pinning account: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+pub async fn run_auth_tag(
+    cli_config_overrides: CliConfigOverrides,
+    account: String,
+    labels: Vec<String>,
+    remove: bool,
+    org_id: Option<String>,
+    project_id: Option<String>,
+) -> ! {
+    let config = load_config_or_exit(cli_config_overrides).await;
+
+    let (add, remove) = if remove {
+        (Vec::new(), labels)
+    } else {
+        (labels, Vec::new())
+    };
+    match tag_account(
+        &config.auth_storage_settings(),
+        &account,
+        &add,
+        &remove,
+        org_id,
+        project_id,
+    ) {
+        Ok(metadata) => {
+            eprintln!("Tagged {account}: {}", format_metadata(&metadata));
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error tagging account: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+pub async fn run_auth_unpin(cli_config_overrides: CliConfigOverrides) -> ! {
+    let config = load_config_or_exit(cli_config_overrides).await;
+
+    match unpin_account(&config.auth_storage_settings()) {
+        Ok(true) => {
+            eprintln!("Unpinned account; Codex rotates between stored accounts again");
+            std::process::exit(0);
+        }
+        Ok(false) => {
+            eprintln!("No account is pinned");
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error unpinning account: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+pub async fn run_auth_migrate(
+    cli_config_overrides: CliConfigOverrides,
+    to: AuthCredentialsStoreMode,
+) -> ! {
+    let config = load_config_or_exit(cli_config_overrides).await;
+    let store = if to == AuthCredentialsStoreMode::Keyring {
+        "keyring"
+    } else {
+        "file"
+    };
+
+    match migrate_credentials(&config.auth_storage_settings(), to) {
+        Ok(count) => {
+            eprintln!("Moved {count} credential(s) to the {store} store");
+            let configured = config.cli_auth_credentials_store_mode;
+            if configured != to && configured != AuthCredentialsStoreMode::Auto {
+                eprintln!(
+                    "Set `cli_auth_credentials_store = \"{store}\"` in config.toml so Codex reads them from there"
+                );
+            }
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error migrating credentials: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+pub async fn run_auth_export(cli_config_overrides: CliConfigOverrides, output: PathBuf) -> ! {
+    let config = load_config_or_exit(cli_config_overrides).await;
+    let passphrase = read_passphrase_from_stdin("codex auth export -o codex-auth.bundle");
+
+    let (bundle, count) = match export_credentials(
+        &config.auth_storage_settings(),
+        config.cli_auth_credentials_store_mode,
+        &passphrase,
+    ) {
+        Ok(exported) => exported,
+        Err(e) => {
+            eprintln!("Error exporting credentials: {e}");
+            std::process::exit(1);
+        }
+    };
+    match write_private_file(&output, &bundle) {
+        Ok(()) => {
+            eprintln!(
+                "Exported {count} credential(s) to {}; import them with `codex auth import`",
+                output.display()
+            );
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error writing {}: {e}", output.display());
+            std::process::exit(1);
+        }
+    }
+}
+
+pub async fn run_auth_import(cli_config_overrides: CliConfigOverrides, input: PathBuf) -> ! {
+    let config = load_config_or_exit(cli_config_overrides).await;
+    let bundle = match std::fs::read_to_string(&input) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            eprintln!("Error reading {}: {e}", input.display());
+            std::process::exit(1);
+        }
+    };
+    let passphrase = read_passphrase_from_stdin("codex auth import codex-auth.bundle");
+
+    match import_credentials(
+        &config.auth_storage_settings(),
+        config.cli_auth_credentials_store_mode,
+        &bundle,
+        &passphrase,
+    ) {
+        Ok(count) => {
+            eprintln!("Imported {count} credential(s) from {}", input.display());
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error importing credentials: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Reads the bundle passphrase from stdin so it never appears in shell
+/// history or process listings.
+fn read_passphrase_from_stdin(example: &str) -> String {
+    let mut stdin = std::io::stdin();
+
+    if stdin.is_terminal() {
+        eprintln!(
+            "Expected the bundle passphrase on stdin. Try piping it, e.g. `printenv CODEX_BUNDLE_PASSPHRASE | {example}`."
+        );
+        std::process::exit(1);
+    }
+
+    let mut buffer = String::new();
+    if let Err(err) = stdin.read_to_string(&mut buffer) {
+        eprintln!("Failed to read passphrase from stdin: {err}");
+        std::process::exit(1);
+    }
+
+    let passphrase = buffer.trim_end_matches(['\n', '\r']).to_string();
+    if passphrase.is_empty() {
+        eprintln!("No passphrase provided via stdin.");
+        std::process::exit(1);
+    }
+
+    passphrase
+}
+
+/// Writes `contents` readable only by the current user.
+fn write_private_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(contents.as_bytes())?;
+    file.flush()
+}
+
+/// Renders accounts in rotation order; `*` marks the account used next and
+/// the pinned account's status says so.
+fn format_accounts(accounts: &[AccountSummary], now: DateTime<Utc>) -> String {
+    let rows: Vec<[String; 6]> = accounts
+        .iter()
+        .map(|account| {
+            let mut status = match (&account.usage_limit, &account.unexpected_response) {
+                (Some(limit), _) if limit.is_active(now) => format!(
+                    "usage limit until {}",
+                    limit.next_retry_at().format("%Y-%m-%d %H:%M UTC")
+                ),
+                (_, Some(response)) if response.is_active(now) => format!(
+                    "blocked after HTTP {} until {}",
+                    response.status,
+                    response.next_retry_at().format("%Y-%m-%d %H:%M UTC")
+                ),
+                _ => "available".to_string(),
+            };
+            if account.is_pinned {
+                status.push_str(" (pinned)");
+            }
+            [
+                if account.is_next { "*" } else { "" }.to_string(),
+                account.email.clone(),
+                account.plan_type.clone().unwrap_or_else(|| "-".to_string()),
+                if account.metadata.labels.is_empty() {
+                    "-".to_string()
+                } else {
+                    account.metadata.labels.join(",")
+                },
+                account
+                    .last_used
+                    .map(|time| time.format("%Y-%m-%d %H:%M UTC").to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                status,
+            ]
+        })
+        .collect();
+    format_table(
+        ["NEXT", "EMAIL", "PLAN", "LABELS", "LAST USED", "STATUS"],
+        &rows,
+    )
+}
+
+/// Summarizes an account's labels and organization after `codex auth tag`.
+fn format_metadata(metadata: &AccountMetadata) -> String {
+    let mut parts = vec![if metadata.labels.is_empty() {
+        "no labels".to_string()
+    } else {
+        format!("labels {}", metadata.labels.join(", "))
+    }];
+    if let Some(org_id) = &metadata.org_id {
+        parts.push(format!("organization {org_id}"));
+    }
+    if let Some(project_id) = &metadata.project_id {
+        parts.push(format!("project {project_id}"));
+    }
+    parts.join("; ")
+}
+
+/// Renders audit entries oldest first, noting the previously active account
+/// for selections.
+fn format_audit(entries: &[AuthAuditEntry]) -> String {
+    let rows: Vec<[String; 4]> = entries
+        .iter()
+        .map(|entry| {
+            let event = match entry.event {
+                AuthAuditEvent::Selected => "selected",
+                AuthAuditEvent::UsageLimit => "usage limit",
+                AuthAuditEvent::Invalidated => "invalidated",
+            };
+            let mut details = entry.reason.clone();
+            if let Some(previous) = &entry.previous_account {
+                details.push_str(&format!(" (was {previous})"));
+            }
+            [
+                entry.timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+                event.to_string(),
+                entry.account.clone(),
+                details,
+            ]
+        })
+        .collect();
+    format_table(["TIME", "EVENT", "ACCOUNT", "DETAILS"], &rows)
+}
+
+/// Renders each account's total tokens for today and the current week (UTC,
+/// starting Monday), with the week's input and output split out.
+fn format_usage(accounts: &[AccountSummary]) -> String {
+    let rows: Vec<[String; 5]> = accounts
+        .iter()
+        .map(|account| {
+            [
+                account.email.clone(),
+                account.usage_today.total_tokens.to_string(),
+                account.usage_this_week.total_tokens.to_string(),
+                account.usage_this_week.input_tokens.to_string(),
+                account.usage_this_week.output_tokens.to_string(),
+            ]
+        })
+        .collect();
+    format_table(
+        ["EMAIL", "TODAY", "THIS WEEK", "WEEK INPUT", "WEEK OUTPUT"],
+        &rows,
+    )
+}
+
+/// Renders each probed account with its verdict and, when it was
+/// invalidated, where its file was moved.
+fn format_checks(checks: &[AccountCheck]) -> String {
+    let rows: Vec<[String; 2]> = checks
+        .iter()
+        .map(|check| {
+            let mut status = match &check.health {
+                AccountHealth::Valid => "valid".to_string(),
+                AccountHealth::Limited { until: Some(until) } => {
+                    format!("limited until {}", until.format("%Y-%m-%d %H:%M UTC"))
+                }
+                AccountHealth::Limited { until: None } => "limited".to_string(),
+                AccountHealth::Expired => "expired".to_string(),
+                AccountHealth::Invalid { reason } => format!("invalid: {reason}"),
+                AccountHealth::Unknown { error } => format!("unknown: {error}"),
+            };
+            if let Some(name) = check.invalidated.as_ref().and_then(|path| path.file_name()) {
+                status.push_str(&format!(" (moved to {})", name.to_string_lossy()));
+            }
+            [check.email.clone(), status]
+        })
+        .collect();
+    format_table(["EMAIL", "STATUS"], &rows)
+}
+
+/// Left-aligns `rows` under `headers` in columns two spaces apart.
+fn format_table<const N: usize>(headers: [&str; N], rows: &[[String; N]]) -> String {
+    let headers = headers.map(str::to_string);
+    let mut widths = headers.clone().map(|header| header.len());
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let mut out = String::new();
+    for row in std::iter::once(&headers).chain(rows) {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
 async fn load_config_or_exit(cli_config_overrides: CliConfigOverrides) -> Config {
     let cli_overrides = match cli_config_overrides.parse_overrides() {
         Ok(v) => v,
@@ -231,7 +750,194 @@ fn safe_format_key(key: &str) -> String {
 
 #[cfg(test)]
 mod tests {
+    use super::AccountCheck;
+    use super::AccountHealth;
+    use super::AccountMetadata;
+    use super::AccountSummary;
+    use super::AuthAuditEntry;
+    use super::AuthAuditEvent;
+    use super::format_accounts;
+    use super::format_audit;
+    use super::format_checks;
+    use super::format_usage;
     use super::safe_format_key;
+    use chrono::DateTime;
+    use chrono::Duration;
+    use codex_core::auth::AccountTokenUsage;
+    use codex_core::auth::UnexpectedResponseStatus;
+    use codex_core::auth::UsageLimitStatus;
+    use std::path::PathBuf;
+
+    #[test]
+    fn formats_accounts_with_next_marker_and_limits() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).expect("valid timestamp");
+        let account =
+            |email: &str, is_next: bool, usage_limit: Option<UsageLimitStatus>| AccountSummary {
+                email: email.to_string(),
+                path: PathBuf::from(format!("{email}.json")),
+                plan_type: Some("Plus".to_string()),
+                last_used: Some(now - Duration::hours(2)),
+                usage_limit,
+                last_usage_limit_at: None,
+                unexpected_response: None,
+                usage_today: AccountTokenUsage::default(),
+                usage_this_week: AccountTokenUsage::default(),
+                metadata: AccountMetadata {
+                    labels: vec!["prod".to_string(), "team-a".to_string()],
+                    ..AccountMetadata::default()
+                },
+                is_pinned: is_next,
+                is_next,
+            };
+        let limit: UsageLimitStatus = serde_json::from_value(serde_json::json!({
+            "resets_at": (now + Duration::hours(1)).to_rfc3339(),
+            "recorded_at": now.to_rfc3339(),
+        }))
+        .expect("valid usage limit");
+        let blocked: UnexpectedResponseStatus = serde_json::from_value(serde_json::json!({
+            "recorded_at": now.to_rfc3339(),
+            "status": 403,
+            "blocked_until": (now + Duration::minutes(30)).to_rfc3339(),
+        }))
+        .expect("valid unexpected response");
+        let accounts = [
+            account("alice@example.com", false, Some(limit)),
+            account("bob@example.com", true, None),
+            AccountSummary {
+                unexpected_response: Some(blocked),
+                metadata: AccountMetadata::default(),
+                ..account("carol@example.com", false, None)
+            },
+        ];
+
+        let expected = [
+            "NEXT  EMAIL              PLAN  LABELS       LAST USED             STATUS",
+            "      alice@example.com  Plus  prod,team-a  2023-11-14 20:13 UTC  usage limit until 2023-11-14 23:13 UTC",
+            "*     bob@example.com    Plus  prod,team-a  2023-11-14 20:13 UTC  available (pinned)",
+            "      carol@example.com  Plus  -            2023-11-14 20:13 UTC  blocked after HTTP 403 until 2023-11-14 22:43 UTC",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(format_accounts(&accounts, now), expected);
+    }
+
+    #[test]
+    fn formats_usage_per_account() {
+        let account = |email: &str, today: i64, week: i64| AccountSummary {
+            email: email.to_string(),
+            path: PathBuf::from(format!("{email}.json")),
+            plan_type: None,
+            last_used: None,
+            usage_limit: None,
+            last_usage_limit_at: None,
+            unexpected_response: None,
+            usage_today: AccountTokenUsage {
+                total_tokens: today,
+                ..AccountTokenUsage::default()
+            },
+            usage_this_week: AccountTokenUsage {
+                input_tokens: week - 100,
+                output_tokens: 100,
+                total_tokens: week,
+                ..AccountTokenUsage::default()
+            },
+            metadata: AccountMetadata::default(),
+            is_pinned: false,
+            is_next: false,
+        };
+        let accounts = [
+            account("alice@example.com", 1200, 45000),
+            account("bob@example.com", 0, 300),
+        ];
+
+        let expected = [
+            "EMAIL              TODAY  THIS WEEK  WEEK INPUT  WEEK OUTPUT",
+            "alice@example.com  1200   45000      44900       100",
+            "bob@example.com    0      300        200         100",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(format_usage(&accounts), expected);
+    }
+
+    #[test]
+    fn formats_check_results() {
+        let check = |email: &str, health: AccountHealth, invalidated: Option<&str>| AccountCheck {
+            email: email.to_string(),
+            path: PathBuf::from(format!("{email}.json")),
+            health,
+            invalidated: invalidated.map(PathBuf::from),
+        };
+        let until = DateTime::from_timestamp(1_700_000_000, 0).expect("valid timestamp");
+        let checks = [
+            check("alice@example.com", AccountHealth::Valid, None),
+            check(
+                "bob@example.com",
+                AccountHealth::Limited { until: Some(until) },
+                None,
+            ),
+            check(
+                "carol@example.com",
+                AccountHealth::Expired,
+                Some("auth/invalid-carol@example.com.json"),
+            ),
+        ];
+
+        let expected = [
+            "EMAIL              STATUS",
+            "alice@example.com  valid",
+            "bob@example.com    limited until 2023-11-14 22:13 UTC",
+            "carol@example.com  expired (moved to invalid-carol@example.com.json)",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(format_checks(&checks), expected);
+    }
+
+    #[test]
+    fn formats_audit_entries() {
+        let timestamp = DateTime::from_timestamp(1_700_000_000, 0).expect("valid timestamp");
+        let entry = |event: AuthAuditEvent, account: &str, reason: &str| AuthAuditEntry {
+            timestamp,
+            ..AuthAuditEntry::new(event, account.to_string(), reason)
+        };
+        let entries = [
+            entry(
+                AuthAuditEvent::Selected,
+                "alice@example.com",
+                "session started",
+            ),
+            entry(
+                AuthAuditEvent::UsageLimit,
+                "alice@example.com",
+                "usage limit reached",
+            ),
+            AuthAuditEntry {
+                previous_account: Some("alice@example.com".to_string()),
+                ..entry(
+                    AuthAuditEvent::Selected,
+                    "bob@example.com",
+                    "rotated after usage limit",
+                )
+            },
+            entry(
+                AuthAuditEvent::Invalidated,
+                "bob@example.com",
+                "token refresh failed",
+            ),
+        ];
+
+        let expected = [
+            "TIME                     EVENT        ACCOUNT            DETAILS",
+            "2023-11-14 22:13:20 UTC  selected     alice@example.com  session started",
+            "2023-11-14 22:13:20 UTC  usage limit  alice@example.com  usage limit reached",
+            "2023-11-14 22:13:20 UTC  selected     bob@example.com    rotated after usage limit (was alice@example.com)",
+            "2023-11-14 22:13:20 UTC  invalidated  bob@example.com    token refresh failed",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(format_audit(&entries), expected);
+    }
 
     #[test]
     fn formats_long_key() {
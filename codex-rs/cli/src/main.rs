@@ -10,6 +10,17 @@ use codex_cli::LandlockCommand;
 use codex_cli::SeatbeltCommand;
 use codex_cli::WindowsCommand;
 use codex_cli::login::read_api_key_from_stdin;
+use codex_cli::login::run_auth_accounts;
+use codex_cli::login::run_auth_audit;
+use codex_cli::login::run_auth_check;
+use codex_cli::login::run_auth_clear_issues;
+use codex_cli::login::run_auth_export;
+use codex_cli::login::run_auth_import;
+use codex_cli::login::run_auth_migrate;
+use codex_cli::login::run_auth_tag;
+use codex_cli::login::run_auth_unpin;
+use codex_cli::login::run_auth_usage;
+use codex_cli::login::run_auth_use;
 use codex_cli::login::run_login_status;
 use codex_cli::login::run_login_with_api_key;
 use codex_cli::login::run_login_with_chatgpt;
@@ -24,6 +35,7 @@ use codex_tui::Cli as TuiCli;
 use codex_tui::update_action::UpdateAction;
 use owo_colors::OwoColorize;
 use std::path::PathBuf;
+use std::sync::Arc;
 use supports_color::Stream;
 
 mod flow_cmd;
@@ -31,9 +43,11 @@ mod mcp_cmd;
 #[cfg(not(windows))]
 mod wsl_paths;
 
+use crate::flow_cmd::CocoFlowAdapter;
 use crate::flow_cmd::FlowCli;
 use crate::mcp_cmd::McpCli;
 
+use codex_core::auth::AuthCredentialsStoreMode;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::features::is_known_feature_key;
@@ -60,6 +74,10 @@ struct MultitoolCli {
     #[clap(flatten)]
     pub feature_toggles: FeatureToggles,
 
+    /// Only use stored accounts tagged with this label (see `codex auth tag`).
+    #[arg(long = "account-label", value_name = "LABEL", global = true)]
+    account_label: Option<String>,
+
     #[clap(flatten)]
     interactive: TuiCli,
 
@@ -82,6 +100,9 @@ enum Subcommand {
     /// Remove stored authentication credentials.
     Logout(LogoutCommand),
 
+    /// Inspect the ChatGPT accounts stored under CODEX_HOME/auth (or `accounts_dir`).
+    Auth(AuthCommand),
+
     /// [experimental] Run Codex as an MCP server and manage MCP servers.
     Mcp(McpCli),
 
@@ -214,6 +235,120 @@ struct LogoutCommand {
     config_overrides: CliConfigOverrides,
 }
 
+#[derive(Debug, Parser)]
+struct AuthCommand {
+    #[clap(skip)]
+    config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    action: AuthSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum AuthSubcommand {
+    /// List stored accounts with plan, last use, usage-limit status, and which one is used next.
+    Accounts,
+
+    /// Show tokens each stored account consumed today and this week.
+    Usage,
+
+    /// Probe every stored account with a cheap authenticated request and report whether it still works.
+    Check {
+        /// Move expired and invalid account files aside so rotation stops picking them.
+        #[arg(long)]
+        invalidate: bool,
+    },
+
+    /// Show which account was selected, hit its usage limit, or was invalidated, and when.
+    Audit {
+        /// Only show entries for this account.
+        #[arg(long, value_name = "EMAIL")]
+        account: Option<String>,
+
+        /// Only show the most recent N entries.
+        #[arg(long, short = 'n', value_name = "N")]
+        limit: Option<usize>,
+    },
+
+    /// Clear recorded usage limits and unexpected-response blocks so rotation tries accounts again.
+    ClearIssues {
+        /// Only clear the account stored as CODEX_HOME/auth/<EMAIL>.json.
+        #[arg(value_name = "EMAIL")]
+        email: Option<String>,
+    },
+
+    /// Always use the given account instead of rotating between stored accounts.
+    Use {
+        /// Email (or `apikey-<label>`) of an account stored as CODEX_HOME/auth/<EMAIL>.json.
+        #[arg(value_name = "EMAIL")]
+        email: String,
+    },
+
+    /// Remove the pinned account and return to automatic rotation.
+    Unpin,
+
+    /// Add labels and organization details to a stored account, for `codex --account-label`.
+    Tag {
+        /// Email (or `apikey-<label>`) of an account stored as CODEX_HOME/auth/<EMAIL>.json.
+        #[arg(value_name = "EMAIL")]
+        email: String,
+
+        /// Labels to add to the account.
+        #[arg(value_name = "LABEL")]
+        labels: Vec<String>,
+
+        /// Remove the given labels instead of adding them.
+        #[arg(long)]
+        remove: bool,
+
+        /// Organization the account's credentials belong to.
+        #[arg(long, value_name = "ID")]
+        org_id: Option<String>,
+
+        /// Project the account's credentials belong to.
+        #[arg(long, value_name = "ID")]
+        project_id: Option<String>,
+    },
+
+    /// Move every stored credential between plaintext files and the OS keyring.
+    Migrate {
+        /// Credential store to move `auth.json` and the stored accounts into.
+        #[arg(long, value_enum, value_name = "STORE")]
+        to: MigrationTarget,
+    },
+
+    /// Write every stored credential to a passphrase-encrypted bundle read from stdin.
+    Export {
+        /// File to write the encrypted bundle to.
+        #[arg(long, short = 'o', value_name = "FILE")]
+        output: PathBuf,
+    },
+
+    /// Store the credentials from a bundle created by `codex auth export`.
+    Import {
+        /// Encrypted bundle to import; the passphrase is read from stdin.
+        #[arg(value_name = "FILE")]
+        input: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum MigrationTarget {
+    /// CODEX_HOME/auth.json and account files.
+    File,
+    /// The OS keyring.
+    Keyring,
+}
+
+impl From<MigrationTarget> for AuthCredentialsStoreMode {
+    fn from(target: MigrationTarget) -> Self {
+        match target {
+            MigrationTarget::File => AuthCredentialsStoreMode::File,
+            MigrationTarget::Keyring => AuthCredentialsStoreMode::Keyring,
+        }
+    }
+}
+
 #[derive(Debug, Parser)]
 struct AppServerCommand {
     /// Omit to run the app server; specify a subcommand for tooling.
@@ -408,13 +543,21 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
     let MultitoolCli {
         config_overrides: mut root_config_overrides,
         feature_toggles,
+        account_label,
         mut interactive,
         subcommand,
     } = MultitoolCli::parse();
 
+    codex_core::coco_flow::set_coco_flow_runner(Arc::new(CocoFlowAdapter));
+
     // Fold --enable/--disable into config overrides so they flow to all subcommands.
     let toggle_overrides = feature_toggles.to_overrides()?;
     root_config_overrides.raw_overrides.extend(toggle_overrides);
+    if let Some(label) = account_label {
+        root_config_overrides
+            .raw_overrides
+            .push(format!("account_label={}", toml::Value::String(label)));
+    }
 
     match subcommand {
         None => {
@@ -512,6 +655,55 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             );
             run_logout(logout_cli.config_overrides).await;
         }
+        Some(Subcommand::Auth(mut auth_cli)) => {
+            prepend_config_flags(
+                &mut auth_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            match auth_cli.action {
+                AuthSubcommand::Accounts => run_auth_accounts(auth_cli.config_overrides).await,
+                AuthSubcommand::Usage => run_auth_usage(auth_cli.config_overrides).await,
+                AuthSubcommand::Check { invalidate } => {
+                    run_auth_check(auth_cli.config_overrides, invalidate).await
+                }
+                AuthSubcommand::Audit { account, limit } => {
+                    run_auth_audit(auth_cli.config_overrides, account, limit).await
+                }
+                AuthSubcommand::ClearIssues { email } => {
+                    run_auth_clear_issues(auth_cli.config_overrides, email).await
+                }
+                AuthSubcommand::Use { email } => {
+                    run_auth_use(auth_cli.config_overrides, email).await
+                }
+                AuthSubcommand::Unpin => run_auth_unpin(auth_cli.config_overrides).await,
+                AuthSubcommand::Tag {
+                    email,
+                    labels,
+                    remove,
+                    org_id,
+                    project_id,
+                } => {
+                    run_auth_tag(
+                        auth_cli.config_overrides,
+                        email,
+                        labels,
+                        remove,
+                        org_id,
+                        project_id,
+                    )
+                    .await
+                }
+                AuthSubcommand::Migrate { to } => {
+                    run_auth_migrate(auth_cli.config_overrides, to.into()).await
+                }
+                AuthSubcommand::Export { output } => {
+                    run_auth_export(auth_cli.config_overrides, output).await
+                }
+                AuthSubcommand::Import { input } => {
+                    run_auth_import(auth_cli.config_overrides, input).await
+                }
+            }
+        }
         Some(Subcommand::Completion(completion_cli)) => {
             print_completion(completion_cli);
         }
@@ -713,6 +905,7 @@ mod tests {
             config_overrides: root_overrides,
             subcommand,
             feature_toggles: _,
+            account_label: _,
         } = cli;
 
         let Subcommand::Resume(ResumeCommand {
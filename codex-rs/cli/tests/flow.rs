@@ -28,3 +28,23 @@ fn flow_init_creates_scaffold() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn flow_exposes_state_and_resume_commands() -> Result<()> {
+    let temp = TempDir::new()?;
+
+    Command::cargo_bin("cocos")?
+        .current_dir(temp.path())
+        .args(["flow", "state", "prune", "--days", "7"])
+        .assert()
+        .success();
+    assert!(temp.path().join(".codex-flow/runtime/state").is_dir());
+
+    Command::cargo_bin("cocos")?
+        .current_dir(temp.path())
+        .args(["flow", "resume", "--help"])
+        .assert()
+        .success();
+
+    Ok(())
+}
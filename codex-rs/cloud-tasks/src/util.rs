@@ -64,7 +64,7 @@ pub async fn load_auth_manager() -> Option<AuthManager> {
         .await
         .ok()?;
     Some(AuthManager::new(
-        config.codex_home,
+        config.auth_storage_settings(),
         false,
         config.cli_auth_credentials_store_mode,
     ))
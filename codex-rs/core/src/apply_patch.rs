@@ -39,6 +39,18 @@ pub(crate) async fn apply_patch(
     call_id: &str,
     action: ApplyPatchAction,
 ) -> InternalApplyPatchInvocation {
+    if let Err(conflict) = sess
+        .services
+        .file_reads
+        .lock()
+        .await
+        .check_action(&action)
+        .await
+    {
+        return InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(
+            conflict,
+        )));
+    }
     match assess_patch_safety(
         &action,
         turn_context.approval_policy,
@@ -1,3 +1,6 @@
+mod audit;
+mod bundle;
+mod health;
 mod storage;
 
 use chrono::Utc;
@@ -9,7 +12,6 @@ use serial_test::serial;
 use std::env;
 use std::fmt::Debug;
 use std::io::ErrorKind;
-use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -19,15 +21,51 @@ use std::time::Duration;
 use codex_app_server_protocol::AuthMode;
 use codex_protocol::account::PlanType as AccountPlanType;
 use codex_protocol::config_types::ForcedLoginMethod;
-
+use codex_protocol::protocol::TokenUsage;
+use notify::Event;
+use notify::EventKind;
+use notify::RecommendedWatcher;
+use notify::RecursiveMode;
+use notify::Watcher;
+use notify::event::ModifyKind;
+
+pub use crate::auth::audit::AUTH_AUDIT_FILE;
+pub use crate::auth::audit::AuthAuditEntry;
+pub use crate::auth::audit::AuthAuditEvent;
+pub use crate::auth::audit::read_auth_audit;
+use crate::auth::audit::record_auth_event;
+use crate::auth::audit::record_selected_account;
+pub use crate::auth::bundle::export_credentials;
+pub use crate::auth::bundle::import_credentials;
+pub use crate::auth::health::AccountCheck;
+pub use crate::auth::health::AccountHealth;
+pub use crate::auth::health::check_accounts;
 use crate::auth::storage::AccountIssue;
+pub use crate::auth::storage::AccountMetadata;
 use crate::auth::storage::AccountState;
+pub use crate::auth::storage::AccountSummary;
+pub use crate::auth::storage::AccountTokenUsage;
 pub use crate::auth::storage::AuthCredentialsStoreMode;
 pub use crate::auth::storage::AuthDotJson;
-use crate::auth::storage::AuthStorageBackend;
-use crate::auth::storage::UnexpectedResponseStatus;
-use crate::auth::storage::UsageLimitStatus;
+pub use crate::auth::storage::AuthStorageBackend;
+pub use crate::auth::storage::AuthStorageSettings;
+pub use crate::auth::storage::DEFAULT_UNEXPECTED_RESPONSE_COOLDOWN;
+pub use crate::auth::storage::UnexpectedResponseStatus;
+pub use crate::auth::storage::UsageLimitStatus;
+pub use crate::auth::storage::accounts_limited_until;
+pub use crate::auth::storage::clear_account_issues;
+use crate::auth::storage::cooldown_end;
 use crate::auth::storage::create_auth_storage;
+use crate::auth::storage::ensure_writable;
+use crate::auth::storage::is_credential_file;
+pub use crate::auth::storage::list_accounts;
+pub use crate::auth::storage::migrate_credentials;
+pub use crate::auth::storage::pin_account;
+pub(crate) use crate::auth::storage::resolve_account_label;
+pub(crate) use crate::auth::storage::resolve_accounts_dir;
+pub(crate) use crate::auth::storage::resolve_auth_read_only;
+pub use crate::auth::storage::tag_account;
+pub use crate::auth::storage::unpin_account;
 use crate::config::Config;
 use crate::default_client::CodexHttpClient;
 use crate::error::RefreshTokenFailedError;
@@ -137,10 +175,10 @@ impl CodexAuth {
 
     /// Loads the available auth information from auth storage.
     pub fn from_auth_storage(
-        codex_home: &Path,
+        settings: &AuthStorageSettings,
         auth_credentials_store_mode: AuthCredentialsStoreMode,
     ) -> std::io::Result<Option<CodexAuth>> {
-        load_auth(codex_home, false, auth_credentials_store_mode)
+        load_auth(settings, false, auth_credentials_store_mode)
     }
 
     pub async fn get_token_data(&self) -> Result<TokenData, std::io::Error> {
@@ -212,6 +250,13 @@ impl CodexAuth {
         self.get_current_token_data().and_then(|t| t.id_token.email)
     }
 
+    /// Identifies the account for rotation: the ChatGPT email, or the label
+    /// (`apikey-<label>`) of an API-key account file.
+    pub fn account_label(&self) -> Option<String> {
+        self.get_account_email()
+            .or_else(|| self.storage.active_account_label())
+    }
+
     /// Account-facing plan classification derived from the current token.
     /// Returns a high-level `AccountPlanType` (e.g., Free/Plus/Pro/Team/…)
     /// mapped from the ID token's internal plan value. Prefer this when you
@@ -263,10 +308,6 @@ impl CodexAuth {
     where
         F: FnOnce(&mut AccountState),
     {
-        if self.mode != AuthMode::ChatGPT {
-            return;
-        }
-
         let current = {
             let guard = self.lock_auth_json();
             (*guard).clone()
@@ -302,8 +343,37 @@ impl CodexAuth {
         });
     }
 
-    pub(crate) fn record_unexpected_response(&self, error: &UnexpectedResponseError) {
+    /// Records an exhausted API-key quota on the active API-key account so
+    /// rotation skips it like a usage-limited ChatGPT account.
+    pub(crate) fn record_quota_exceeded(&self) {
+        if self.mode != AuthMode::ApiKey {
+            return;
+        }
         let recorded_at = Utc::now();
+        self.update_account_state(|state| {
+            state.record_issue(AccountIssue::UsageLimit(UsageLimitStatus {
+                plan_type: None,
+                resets_at: None,
+                recorded_at,
+            }));
+        });
+    }
+
+    /// Attributes one response's token usage to the active account.
+    pub(crate) fn record_token_usage(&self, usage: &TokenUsage) {
+        let now = Utc::now();
+        self.update_account_state(|state| state.record_token_usage(usage, now));
+    }
+
+    /// Records `error` on the active account, which rotation then
+    /// skips for `cooldown`.
+    pub(crate) fn record_unexpected_response(
+        &self,
+        error: &UnexpectedResponseError,
+        cooldown: std::time::Duration,
+    ) {
+        let recorded_at = Utc::now();
+        let blocked_until = Some(cooldown_end(recorded_at, cooldown));
         let status = i32::from(error.status.as_u16());
         let request_id = error.request_id.clone();
         let body = error.body.clone();
@@ -313,6 +383,7 @@ impl CodexAuth {
                 status,
                 request_id,
                 body,
+                blocked_until,
             }));
         });
     }
@@ -348,13 +419,17 @@ impl CodexAuth {
             }),
             last_refresh: Some(Utc::now()),
             account_state: None,
+            metadata: AccountMetadata::default(),
         };
 
         let auth_dot_json = Arc::new(Mutex::new(Some(auth_dot_json)));
         Self {
             api_key: None,
             mode: AuthMode::ChatGPT,
-            storage: create_auth_storage(PathBuf::new(), AuthCredentialsStoreMode::File),
+            storage: create_auth_storage(
+                &AuthStorageSettings::new(PathBuf::new()),
+                AuthCredentialsStoreMode::File,
+            ),
             auth_dot_json,
             client: crate::default_client::create_client(),
         }
@@ -364,7 +439,10 @@ impl CodexAuth {
         Self {
             api_key: Some(api_key.to_owned()),
             mode: AuthMode::ApiKey,
-            storage: create_auth_storage(PathBuf::new(), AuthCredentialsStoreMode::File),
+            storage: create_auth_storage(
+                &AuthStorageSettings::new(PathBuf::new()),
+                AuthCredentialsStoreMode::File,
+            ),
             auth_dot_json: Arc::new(Mutex::new(None)),
             client,
         }
@@ -377,6 +455,19 @@ impl CodexAuth {
 
 pub const OPENAI_API_KEY_ENV_VAR: &str = "OPENAI_API_KEY";
 pub const CODEX_API_KEY_ENV_VAR: &str = "CODEX_API_KEY";
+/// ChatGPT access token read by the `auto` credential store; requires
+/// [`CODEX_REFRESH_TOKEN_ENV_VAR`] as well.
+pub const CODEX_ACCESS_TOKEN_ENV_VAR: &str = "CODEX_ACCESS_TOKEN";
+pub const CODEX_REFRESH_TOKEN_ENV_VAR: &str = "CODEX_REFRESH_TOKEN";
+/// Moves the rotation accounts directory away from `CODEX_HOME/auth`, e.g. to
+/// a shared team directory; overrides `accounts_dir` in config.toml.
+pub const CODEX_ACCOUNTS_DIR_ENV_VAR: &str = "CODEX_ACCOUNTS_DIR";
+/// Restricts account selection to accounts carrying this label, like
+/// `codex --account-label`.
+pub const CODEX_ACCOUNT_LABEL_ENV_VAR: &str = "CODEX_ACCOUNT_LABEL";
+/// Set to `1` or `true` to keep credential storage read-only, e.g. when
+/// credentials are mounted read-only into a CI container.
+pub const CODEX_AUTH_READ_ONLY_ENV_VAR: &str = "CODEX_AUTH_READ_ONLY";
 
 pub fn read_openai_api_key_from_env() -> Option<String> {
     env::var(OPENAI_API_KEY_ENV_VAR)
@@ -392,19 +483,19 @@ pub fn read_codex_api_key_from_env() -> Option<String> {
         .filter(|value| !value.is_empty())
 }
 
-/// Delete the auth.json file inside `codex_home` if it exists. Returns `Ok(true)`
+/// Delete the auth.json file inside `CODEX_HOME` if it exists. Returns `Ok(true)`
 /// if a file was removed, `Ok(false)` if no auth file was present.
 pub fn logout(
-    codex_home: &Path,
+    settings: &AuthStorageSettings,
     auth_credentials_store_mode: AuthCredentialsStoreMode,
 ) -> std::io::Result<bool> {
-    let storage = create_auth_storage(codex_home.to_path_buf(), auth_credentials_store_mode);
+    let storage = create_auth_storage(settings, auth_credentials_store_mode);
     storage.delete()
 }
 
 /// Writes an `auth.json` that contains only the API key.
 pub fn login_with_api_key(
-    codex_home: &Path,
+    settings: &AuthStorageSettings,
     api_key: &str,
     auth_credentials_store_mode: AuthCredentialsStoreMode,
 ) -> std::io::Result<()> {
@@ -413,17 +504,19 @@ pub fn login_with_api_key(
         tokens: None,
         last_refresh: None,
         account_state: None,
+        metadata: AccountMetadata::default(),
     };
-    save_auth(codex_home, &auth_dot_json, auth_credentials_store_mode)
+    save_auth(settings, &auth_dot_json, auth_credentials_store_mode)
 }
 
 /// Persist the provided auth payload using the specified backend.
 pub fn save_auth(
-    codex_home: &Path,
+    settings: &AuthStorageSettings,
     auth: &AuthDotJson,
     auth_credentials_store_mode: AuthCredentialsStoreMode,
 ) -> std::io::Result<()> {
-    let storage = create_auth_storage(codex_home.to_path_buf(), auth_credentials_store_mode);
+    ensure_writable(settings)?;
+    let storage = create_auth_storage(settings, auth_credentials_store_mode);
     storage.save(auth)
 }
 
@@ -433,20 +526,16 @@ pub fn save_auth(
 /// from the auth.json storage. It should use the AuthManager abstraction
 /// instead.
 pub fn load_auth_dot_json(
-    codex_home: &Path,
+    settings: &AuthStorageSettings,
     auth_credentials_store_mode: AuthCredentialsStoreMode,
 ) -> std::io::Result<Option<AuthDotJson>> {
-    let storage = create_auth_storage(codex_home.to_path_buf(), auth_credentials_store_mode);
+    let storage = create_auth_storage(settings, auth_credentials_store_mode);
     storage.load()
 }
 
 pub async fn enforce_login_restrictions(config: &Config) -> std::io::Result<()> {
-    let Some(auth) = load_auth(
-        &config.codex_home,
-        true,
-        config.cli_auth_credentials_store_mode,
-    )?
-    else {
+    let settings = config.auth_storage_settings();
+    let Some(auth) = load_auth(&settings, true, config.cli_auth_credentials_store_mode)? else {
         return Ok(());
     };
 
@@ -465,11 +554,7 @@ pub async fn enforce_login_restrictions(config: &Config) -> std::io::Result<()>
         };
 
         if let Some(message) = method_violation {
-            return logout_with_message(
-                &config.codex_home,
-                message,
-                config.cli_auth_credentials_store_mode,
-            );
+            return logout_with_message(&settings, message, config.cli_auth_credentials_store_mode);
         }
     }
 
@@ -482,7 +567,7 @@ pub async fn enforce_login_restrictions(config: &Config) -> std::io::Result<()>
             Ok(data) => data,
             Err(err) => {
                 return logout_with_message(
-                    &config.codex_home,
+                    &settings,
                     format!(
                         "Failed to load ChatGPT credentials while enforcing workspace restrictions: {err}. Logging out."
                     ),
@@ -502,11 +587,7 @@ pub async fn enforce_login_restrictions(config: &Config) -> std::io::Result<()>
                     "Login is restricted to workspace {expected_account_id}, but current credentials lack a workspace identifier. Logging out."
                 ),
             };
-            return logout_with_message(
-                &config.codex_home,
-                message,
-                config.cli_auth_credentials_store_mode,
-            );
+            return logout_with_message(&settings, message, config.cli_auth_credentials_store_mode);
         }
     }
 
@@ -514,11 +595,11 @@ pub async fn enforce_login_restrictions(config: &Config) -> std::io::Result<()>
 }
 
 fn logout_with_message(
-    codex_home: &Path,
+    settings: &AuthStorageSettings,
     message: String,
     auth_credentials_store_mode: AuthCredentialsStoreMode,
 ) -> std::io::Result<()> {
-    match logout(codex_home, auth_credentials_store_mode) {
+    match logout(settings, auth_credentials_store_mode) {
         Ok(_) => Err(std::io::Error::other(message)),
         Err(err) => Err(std::io::Error::other(format!(
             "{message}. Failed to remove auth.json: {err}"
@@ -527,9 +608,17 @@ fn logout_with_message(
 }
 
 fn load_auth(
-    codex_home: &Path,
+    settings: &AuthStorageSettings,
     enable_codex_api_key_env: bool,
     auth_credentials_store_mode: AuthCredentialsStoreMode,
+) -> std::io::Result<Option<CodexAuth>> {
+    let storage = create_auth_storage(settings, auth_credentials_store_mode);
+    load_auth_from_storage(storage, enable_codex_api_key_env)
+}
+
+fn load_auth_from_storage(
+    storage: Arc<dyn AuthStorageBackend>,
+    enable_codex_api_key_env: bool,
 ) -> std::io::Result<Option<CodexAuth>> {
     if enable_codex_api_key_env && let Some(api_key) = read_codex_api_key_from_env() {
         let client = crate::default_client::create_client();
@@ -539,36 +628,30 @@ fn load_auth(
         )));
     }
 
-    let storage = create_auth_storage(codex_home.to_path_buf(), auth_credentials_store_mode);
-
     let client = crate::default_client::create_client();
     let auth_dot_json = match storage.load()? {
         Some(auth) => auth,
         None => return Ok(None),
     };
 
-    let AuthDotJson {
-        openai_api_key: auth_json_api_key,
-        tokens,
-        last_refresh,
-        account_state,
-    } = auth_dot_json;
-
-    // Prefer AuthMode.ApiKey if it's set in the auth.json.
-    if let Some(api_key) = &auth_json_api_key {
-        return Ok(Some(CodexAuth::from_api_key_with_client(api_key, client)));
+    // Prefer AuthMode.ApiKey if it's set in the auth.json. The stored
+    // credentials stay attached so API-key accounts track their own usage
+    // limits and usage like ChatGPT accounts.
+    if let Some(api_key) = auth_dot_json.openai_api_key.clone() {
+        return Ok(Some(CodexAuth {
+            api_key: Some(api_key),
+            mode: AuthMode::ApiKey,
+            storage,
+            auth_dot_json: Arc::new(Mutex::new(Some(auth_dot_json))),
+            client,
+        }));
     }
 
     Ok(Some(CodexAuth {
         api_key: None,
         mode: AuthMode::ChatGPT,
-        storage: storage.clone(),
-        auth_dot_json: Arc::new(Mutex::new(Some(AuthDotJson {
-            openai_api_key: None,
-            tokens,
-            last_refresh,
-            account_state,
-        }))),
+        storage,
+        auth_dot_json: Arc::new(Mutex::new(Some(auth_dot_json))),
         client,
     }))
 }
@@ -754,6 +837,7 @@ mod tests {
     use reqwest::StatusCode;
     use serde::Serialize;
     use serde_json::json;
+    use std::path::Path;
     use tempfile::tempdir;
 
     #[tokio::test]
@@ -769,10 +853,8 @@ mod tests {
         )
         .expect("failed to write auth file");
 
-        let storage = create_auth_storage(
-            codex_home.path().to_path_buf(),
-            AuthCredentialsStoreMode::File,
-        );
+        let storage =
+            create_auth_storage(&settings(codex_home.path()), AuthCredentialsStoreMode::File);
         let updated = super::update_tokens(
             &storage,
             None,
@@ -807,10 +889,14 @@ mod tests {
         )
         .unwrap();
 
-        super::login_with_api_key(dir.path(), "sk-new", AuthCredentialsStoreMode::File)
-            .expect("login_with_api_key should succeed");
+        super::login_with_api_key(
+            &settings(dir.path()),
+            "sk-new",
+            AuthCredentialsStoreMode::File,
+        )
+        .expect("login_with_api_key should succeed");
 
-        let storage = FileAuthStorage::new(dir.path().to_path_buf());
+        let storage = FileAuthStorage::new(&settings(dir.path()));
         let auth = storage
             .try_read_auth_json(&auth_path)
             .expect("auth.json should parse");
@@ -821,8 +907,9 @@ mod tests {
     #[test]
     fn missing_auth_json_returns_none() {
         let dir = tempdir().unwrap();
-        let auth = CodexAuth::from_auth_storage(dir.path(), AuthCredentialsStoreMode::File)
-            .expect("call should succeed");
+        let auth =
+            CodexAuth::from_auth_storage(&settings(dir.path()), AuthCredentialsStoreMode::File)
+                .expect("call should succeed");
         assert_eq!(auth, None);
     }
 
@@ -870,9 +957,13 @@ mod tests {
             auth_dot_json,
             storage: _,
             ..
-        } = super::load_auth(codex_home.path(), false, AuthCredentialsStoreMode::File)
-            .unwrap()
-            .unwrap();
+        } = super::load_auth(
+            &settings(codex_home.path()),
+            false,
+            AuthCredentialsStoreMode::File,
+        )
+        .unwrap()
+        .unwrap();
         assert_eq!(None, api_key);
         assert_eq!(AuthMode::ChatGPT, mode);
 
@@ -898,6 +989,7 @@ mod tests {
                 }),
                 last_refresh: Some(last_refresh),
                 account_state: None,
+                metadata: AccountMetadata::default(),
             },
             auth_dot_json
         );
@@ -914,7 +1006,7 @@ mod tests {
         )
         .unwrap();
 
-        let auth = super::load_auth(dir.path(), false, AuthCredentialsStoreMode::File)
+        let auth = super::load_auth(&settings(dir.path()), false, AuthCredentialsStoreMode::File)
             .unwrap()
             .unwrap();
         assert_eq!(auth.mode, AuthMode::ApiKey);
@@ -931,24 +1023,301 @@ mod tests {
             tokens: None,
             last_refresh: None,
             account_state: None,
+            metadata: AccountMetadata::default(),
         };
-        super::save_auth(dir.path(), &auth_dot_json, AuthCredentialsStoreMode::File)?;
+        super::save_auth(
+            &settings(dir.path()),
+            &auth_dot_json,
+            AuthCredentialsStoreMode::File,
+        )?;
         let auth_file = get_auth_file(dir.path());
         assert!(auth_file.exists());
-        assert!(logout(dir.path(), AuthCredentialsStoreMode::File)?);
+        assert!(logout(
+            &settings(dir.path()),
+            AuthCredentialsStoreMode::File
+        )?);
         assert!(!auth_file.exists());
         Ok(())
     }
 
+    #[derive(Debug, Default)]
+    struct InMemoryAuthStorage {
+        auth: Mutex<Option<AuthDotJson>>,
+    }
+
+    impl AuthStorageBackend for InMemoryAuthStorage {
+        fn load(&self) -> std::io::Result<Option<AuthDotJson>> {
+            Ok(self.auth.lock().expect("lock").clone())
+        }
+
+        fn save(&self, auth: &AuthDotJson) -> std::io::Result<()> {
+            *self.auth.lock().expect("lock") = Some(auth.clone());
+            Ok(())
+        }
+
+        fn delete(&self) -> std::io::Result<bool> {
+            Ok(self.auth.lock().expect("lock").take().is_some())
+        }
+    }
+
+    #[test]
+    fn auth_manager_uses_registered_storage_backend() -> anyhow::Result<()> {
+        let storage = Arc::new(InMemoryAuthStorage::default());
+        storage.save(&AuthDotJson {
+            openai_api_key: None,
+            tokens: Some(token_data_for_tests()),
+            last_refresh: Some(Utc::now()),
+            account_state: None,
+            metadata: AccountMetadata::default(),
+        })?;
+
+        let manager = AuthManager::with_storage_backend(
+            AuthStorageSettings::new(PathBuf::new()),
+            false,
+            storage.clone(),
+        );
+        let auth = manager.auth().expect("auth loaded from registered backend");
+        assert_eq!(auth.mode, AuthMode::ChatGPT);
+        assert_eq!(
+            auth.get_account_email(),
+            Some("user@example.com".to_string())
+        );
+
+        assert!(manager.logout()?);
+        assert_eq!(storage.load()?, None);
+        assert!(manager.auth().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn watcher_moves_to_next_account_when_active_file_is_removed() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let accounts_dir = codex_home.path().join("auth");
+        std::fs::create_dir_all(&accounts_dir)?;
+        for (name, mtime) in [("alice", 1), ("bob", 5)] {
+            let mut tokens = token_data_for_tests();
+            tokens.access_token = format!("{name}-access");
+            let path = accounts_dir.join(format!("{name}@example.com.json"));
+            let auth = AuthDotJson {
+                openai_api_key: None,
+                tokens: Some(tokens),
+                last_refresh: Some(Utc::now()),
+                account_state: None,
+                metadata: AccountMetadata::default(),
+            };
+            std::fs::write(&path, serde_json::to_string_pretty(&auth)?)?;
+            filetime::set_file_mtime(&path, filetime::FileTime::from_unix_time(mtime, 0))?;
+        }
+        let access_token = |manager: &AuthManager| {
+            manager
+                .auth()
+                .and_then(|auth| auth.get_current_token_data())
+                .map(|tokens| tokens.access_token)
+        };
+
+        let manager = AuthManager::shared(
+            settings(codex_home.path()),
+            false,
+            AuthCredentialsStoreMode::File,
+        );
+        assert_eq!(access_token(&manager).as_deref(), Some("alice-access"));
+        manager.watch_auth_files()?;
+        assert!(!manager.reevaluate_active_account());
+        assert_eq!(access_token(&manager).as_deref(), Some("alice-access"));
+
+        std::fs::remove_file(accounts_dir.join("alice@example.com.json"))?;
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while access_token(&manager).as_deref() != Some("bob-access")
+            && std::time::Instant::now() < deadline
+        {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(access_token(&manager).as_deref(), Some("bob-access"));
+        Ok(())
+    }
+
+    #[test]
+    fn read_only_watcher_does_not_create_accounts_dir() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let manager = AuthManager::shared(
+            settings(codex_home.path()).with_read_only(true),
+            false,
+            AuthCredentialsStoreMode::File,
+        );
+
+        manager.watch_auth_files()?;
+
+        assert!(!codex_home.path().join("auth").exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn check_accounts_classifies_and_invalidates_dead_accounts() -> anyhow::Result<()> {
+        use wiremock::Mock;
+        use wiremock::MockServer;
+        use wiremock::ResponseTemplate;
+        use wiremock::matchers::header;
+        use wiremock::matchers::method;
+        use wiremock::matchers::path;
+
+        let server = MockServer::start().await;
+        let usage = |limit_reached: bool| {
+            json!({
+                "plan_type": "plus",
+                "rate_limit": {
+                    "allowed": !limit_reached,
+                    "limit_reached": limit_reached,
+                    "primary_window": {
+                        "used_percent": if limit_reached { 100 } else { 10 },
+                        "limit_window_seconds": 18000,
+                        "reset_after_seconds": 600,
+                        "reset_at": 1_900_000_000,
+                    },
+                },
+            })
+        };
+        for (token, response) in [
+            (
+                "alice",
+                ResponseTemplate::new(200).set_body_json(usage(false)),
+            ),
+            ("bob", ResponseTemplate::new(200).set_body_json(usage(true))),
+            ("carol", ResponseTemplate::new(403)),
+        ] {
+            Mock::given(method("GET"))
+                .and(path("/backend-api/wham/usage"))
+                .and(header("authorization", format!("Bearer {token}-access")))
+                .respond_with(response)
+                .mount(&server)
+                .await;
+        }
+
+        let codex_home = tempdir()?;
+        let accounts_dir = codex_home.path().join("auth");
+        std::fs::create_dir_all(&accounts_dir)?;
+        for (name, mtime) in [("alice", 1), ("bob", 2), ("carol", 3)] {
+            let mut tokens = token_data_for_tests();
+            tokens.access_token = format!("{name}-access");
+            let path = accounts_dir.join(format!("{name}@example.com.json"));
+            let auth = AuthDotJson {
+                openai_api_key: None,
+                tokens: Some(tokens),
+                last_refresh: Some(Utc::now()),
+                account_state: None,
+                metadata: AccountMetadata::default(),
+            };
+            std::fs::write(&path, serde_json::to_string_pretty(&auth)?)?;
+            filetime::set_file_mtime(&path, filetime::FileTime::from_unix_time(mtime, 0))?;
+        }
+
+        let base_url = format!("{}/backend-api/", server.uri());
+        let checks = check_accounts(&settings(codex_home.path()), &base_url, true).await?;
+        let health: Vec<AccountHealth> = checks.iter().map(|check| check.health.clone()).collect();
+        assert_eq!(
+            health,
+            vec![
+                AccountHealth::Valid,
+                AccountHealth::Limited {
+                    until: chrono::DateTime::from_timestamp(1_900_000_000, 0),
+                },
+                AccountHealth::Invalid {
+                    reason: "HTTP 403 Forbidden".to_string(),
+                },
+            ]
+        );
+        let invalid_path = accounts_dir.join("invalid-carol@example.com.json");
+        assert_eq!(checks[2].invalidated.as_ref(), Some(&invalid_path));
+        assert!(invalid_path.is_file());
+        assert!(accounts_dir.join("alice@example.com.json").is_file());
+        assert!(accounts_dir.join("bob@example.com.json").is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn audit_log_records_selection_usage_limit_and_rotation() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let accounts_dir = codex_home.path().join("auth");
+        std::fs::create_dir_all(&accounts_dir)?;
+        for (name, mtime) in [("alice", 1), ("bob", 5)] {
+            let tokens = token_data_for_email(&format!("{name}@example.com"));
+            let path = accounts_dir.join(format!("{name}@example.com.json"));
+            let auth = AuthDotJson {
+                openai_api_key: None,
+                tokens: Some(tokens),
+                last_refresh: Some(Utc::now()),
+                account_state: None,
+                metadata: AccountMetadata::default(),
+            };
+            std::fs::write(&path, serde_json::to_string_pretty(&auth)?)?;
+            filetime::set_file_mtime(&path, filetime::FileTime::from_unix_time(mtime, 0))?;
+        }
+
+        let manager = AuthManager::new(
+            settings(codex_home.path()),
+            false,
+            AuthCredentialsStoreMode::File,
+        );
+        let limited = manager.auth().context("expected an active account")?;
+        limited.record_usage_limit(&UsageLimitReachedError {
+            plan_type: None,
+            resets_at: chrono::DateTime::from_timestamp(1_900_000_000, 0),
+            rate_limits: None,
+        });
+        let next = manager
+            .rotate_after_usage_limit()
+            .context("expected rotation to bob")?;
+        assert_eq!(next.account_label().as_deref(), Some("bob@example.com"));
+        // Reloading the same account is not a new selection.
+        assert!(!manager.reload());
+
+        let entries: Vec<(AuthAuditEvent, String, Option<String>, String)> =
+            read_auth_audit(codex_home.path())?
+                .into_iter()
+                .map(|entry| {
+                    (
+                        entry.event,
+                        entry.account,
+                        entry.previous_account,
+                        entry.reason,
+                    )
+                })
+                .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (
+                    AuthAuditEvent::Selected,
+                    "alice@example.com".to_string(),
+                    None,
+                    "session started".to_string(),
+                ),
+                (
+                    AuthAuditEvent::UsageLimit,
+                    "alice@example.com".to_string(),
+                    None,
+                    "usage limit reached; resets at 2030-03-17 17:46 UTC".to_string(),
+                ),
+                (
+                    AuthAuditEvent::Selected,
+                    "bob@example.com".to_string(),
+                    Some("alice@example.com".to_string()),
+                    "rotated after usage limit".to_string(),
+                ),
+            ]
+        );
+        Ok(())
+    }
+
     #[test]
     fn record_usage_limit_persists_account_state() -> anyhow::Result<()> {
         let dir = tempdir()?;
-        let storage = Arc::new(FileAuthStorage::new(dir.path().to_path_buf()));
+        let storage = Arc::new(FileAuthStorage::new(&settings(dir.path())));
         let auth = AuthDotJson {
             openai_api_key: None,
             tokens: Some(token_data_for_tests()),
             last_refresh: Some(Utc::now()),
             account_state: None,
+            metadata: AccountMetadata::default(),
         };
         AuthStorageBackend::save(&*storage, &auth)?;
         let storage_arc: Arc<dyn AuthStorageBackend> = storage;
@@ -985,12 +1354,13 @@ mod tests {
     #[test]
     fn record_unexpected_response_tracks_issue() -> anyhow::Result<()> {
         let dir = tempdir()?;
-        let storage = Arc::new(FileAuthStorage::new(dir.path().to_path_buf()));
+        let storage = Arc::new(FileAuthStorage::new(&settings(dir.path())));
         let auth = AuthDotJson {
             openai_api_key: None,
             tokens: Some(token_data_for_tests()),
             last_refresh: Some(Utc::now()),
             account_state: None,
+            metadata: AccountMetadata::default(),
         };
         AuthStorageBackend::save(&*storage, &auth)?;
         let storage_arc: Arc<dyn AuthStorageBackend> = storage;
@@ -1008,7 +1378,7 @@ mod tests {
             request_id: Some("req_123".to_string()),
         };
 
-        codex_auth.record_unexpected_response(&error);
+        codex_auth.record_unexpected_response(&error, DEFAULT_UNEXPECTED_RESPONSE_COOLDOWN);
 
         let persisted = storage_arc
             .load()
@@ -1027,12 +1397,23 @@ mod tests {
             }
             other => panic!("expected unexpected response issue, got {other:?}"),
         }
+        let after_cooldown = issue.next_retry_at() + chrono::Duration::seconds(1);
+        assert!(
+            persisted
+                .account_state
+                .as_ref()
+                .is_some_and(|state| state.is_available(after_cooldown))
+        );
 
         Ok(())
     }
 
     #[cfg(test)]
     fn token_data_for_tests() -> TokenData {
+        token_data_for_email("user@example.com")
+    }
+
+    fn token_data_for_email(email: &str) -> TokenData {
         use base64::Engine;
         use serde_json::json;
 
@@ -1047,7 +1428,7 @@ mod tests {
             typ: "JWT",
         };
         let payload = json!({
-            "email": "user@example.com",
+            "email": email,
             "https://api.openai.com/auth": {
                 "chatgpt_plan_type": "plus",
             },
@@ -1120,6 +1501,10 @@ mod tests {
         Ok(fake_jwt)
     }
 
+    fn settings(codex_home: &Path) -> AuthStorageSettings {
+        AuthStorageSettings::new(codex_home.to_path_buf())
+    }
+
     fn build_config(
         codex_home: &Path,
         forced_login_method: Option<ForcedLoginMethod>,
@@ -1170,8 +1555,12 @@ mod tests {
     #[tokio::test]
     async fn enforce_login_restrictions_logs_out_for_method_mismatch() {
         let codex_home = tempdir().unwrap();
-        login_with_api_key(codex_home.path(), "sk-test", AuthCredentialsStoreMode::File)
-            .expect("seed api key");
+        login_with_api_key(
+            &settings(codex_home.path()),
+            "sk-test",
+            AuthCredentialsStoreMode::File,
+        )
+        .expect("seed api key");
 
         let config = build_config(codex_home.path(), Some(ForcedLoginMethod::Chatgpt), None);
 
@@ -1240,8 +1629,12 @@ mod tests {
     async fn enforce_login_restrictions_allows_api_key_if_login_method_not_set_but_forced_chatgpt_workspace_id_is_set()
      {
         let codex_home = tempdir().unwrap();
-        login_with_api_key(codex_home.path(), "sk-test", AuthCredentialsStoreMode::File)
-            .expect("seed api key");
+        login_with_api_key(
+            &settings(codex_home.path()),
+            "sk-test",
+            AuthCredentialsStoreMode::File,
+        )
+        .expect("seed api key");
 
         let config = build_config(codex_home.path(), None, Some("org_mine".to_string()));
 
@@ -1284,9 +1677,13 @@ mod tests {
         )
         .expect("failed to write auth file");
 
-        let auth = super::load_auth(codex_home.path(), false, AuthCredentialsStoreMode::File)
-            .expect("load auth")
-            .expect("auth available");
+        let auth = super::load_auth(
+            &settings(codex_home.path()),
+            false,
+            AuthCredentialsStoreMode::File,
+        )
+        .expect("load auth")
+        .expect("auth available");
 
         pretty_assertions::assert_eq!(auth.account_plan_type(), Some(AccountPlanType::Pro));
         pretty_assertions::assert_eq!(
@@ -1308,9 +1705,13 @@ mod tests {
         )
         .expect("failed to write auth file");
 
-        let auth = super::load_auth(codex_home.path(), false, AuthCredentialsStoreMode::File)
-            .expect("load auth")
-            .expect("auth available");
+        let auth = super::load_auth(
+            &settings(codex_home.path()),
+            false,
+            AuthCredentialsStoreMode::File,
+        )
+        .expect("load auth")
+        .expect("auth available");
 
         pretty_assertions::assert_eq!(auth.account_plan_type(), Some(AccountPlanType::Unknown));
         pretty_assertions::assert_eq!(
@@ -1328,12 +1729,20 @@ mod tests {
 /// External modifications to `auth.json` will NOT be observed until
 /// `reload()` is called explicitly. This matches the design goal of avoiding
 /// different parts of the program seeing inconsistent auth data mid‑run.
+/// Long-lived processes can opt into following account files on disk with
+/// [`AuthManager::watch_auth_files`].
 #[derive(Debug)]
 pub struct AuthManager {
-    codex_home: PathBuf,
+    storage_settings: AuthStorageSettings,
     inner: RwLock<CachedAuth>,
     enable_codex_api_key_env: bool,
     auth_credentials_store_mode: AuthCredentialsStoreMode,
+    /// Embedder-supplied backend; replaces the one selected by
+    /// `auth_credentials_store_mode` when set.
+    storage_backend: Option<Arc<dyn AuthStorageBackend>>,
+    /// Watcher started by [`AuthManager::watch_auth_files`]; dropped with the
+    /// manager.
+    auth_watcher: Mutex<Option<RecommendedWatcher>>,
 }
 
 impl AuthManager {
@@ -1342,22 +1751,49 @@ impl AuthManager {
     /// simply return `None` in that case so callers can treat it as an
     /// unauthenticated state.
     pub fn new(
-        codex_home: PathBuf,
+        storage_settings: AuthStorageSettings,
         enable_codex_api_key_env: bool,
         auth_credentials_store_mode: AuthCredentialsStoreMode,
     ) -> Self {
         let auth = load_auth(
-            &codex_home,
+            &storage_settings,
             enable_codex_api_key_env,
             auth_credentials_store_mode,
         )
         .ok()
         .flatten();
+        record_selected_account(&storage_settings, None, auth.as_ref(), "session started");
         Self {
-            codex_home,
+            storage_settings,
             inner: RwLock::new(CachedAuth { auth }),
             enable_codex_api_key_env,
             auth_credentials_store_mode,
+            storage_backend: None,
+            auth_watcher: Mutex::new(None),
+        }
+    }
+
+    /// Create a manager that loads, refreshes, and deletes credentials
+    /// through `storage_backend` instead of one of the built-in backends.
+    /// This is the hook for embedders keeping credentials in an external
+    /// secret store; `storage_settings` still locates `CODEX_HOME` and the
+    /// accounts directory for the audit log and file watching.
+    pub fn with_storage_backend(
+        storage_settings: AuthStorageSettings,
+        enable_codex_api_key_env: bool,
+        storage_backend: Arc<dyn AuthStorageBackend>,
+    ) -> Self {
+        let auth = load_auth_from_storage(storage_backend.clone(), enable_codex_api_key_env)
+            .ok()
+            .flatten();
+        record_selected_account(&storage_settings, None, auth.as_ref(), "session started");
+        Self {
+            storage_settings,
+            inner: RwLock::new(CachedAuth { auth }),
+            enable_codex_api_key_env,
+            auth_credentials_store_mode: AuthCredentialsStoreMode::default(),
+            storage_backend: Some(storage_backend),
+            auth_watcher: Mutex::new(None),
         }
     }
 
@@ -1365,10 +1801,12 @@ impl AuthManager {
     pub fn from_auth_for_testing(auth: CodexAuth) -> Arc<Self> {
         let cached = CachedAuth { auth: Some(auth) };
         Arc::new(Self {
-            codex_home: PathBuf::new(),
+            storage_settings: AuthStorageSettings::new(PathBuf::new()),
             inner: RwLock::new(cached),
             enable_codex_api_key_env: false,
             auth_credentials_store_mode: AuthCredentialsStoreMode::File,
+            storage_backend: None,
+            auth_watcher: Mutex::new(None),
         })
     }
 
@@ -1380,15 +1818,29 @@ impl AuthManager {
     /// Force a reload of the auth information from auth.json. Returns
     /// whether the auth value changed.
     pub fn reload(&self) -> bool {
-        let new_auth = load_auth(
-            &self.codex_home,
-            self.enable_codex_api_key_env,
-            self.auth_credentials_store_mode,
-        )
-        .ok()
-        .flatten();
+        self.reload_because("credentials reloaded")
+    }
+
+    /// [`AuthManager::reload`], auditing a newly selected account with
+    /// `reason`.
+    fn reload_because(&self, reason: &str) -> bool {
+        let new_auth = load_auth_from_storage(self.storage(), self.enable_codex_api_key_env)
+            .ok()
+            .flatten();
+        self.replace_auth(new_auth, reason)
+    }
+
+    /// Caches `new_auth` and returns whether the auth value changed. Switching
+    /// to a different account is recorded in the audit log with `reason`.
+    fn replace_auth(&self, new_auth: Option<CodexAuth>, reason: &str) -> bool {
         if let Ok(mut guard) = self.inner.write() {
             let changed = !AuthManager::auths_equal(&guard.auth, &new_auth);
+            record_selected_account(
+                &self.storage_settings,
+                guard.auth.as_ref(),
+                new_auth.as_ref(),
+                reason,
+            );
             guard.auth = new_auth;
             changed
         } else {
@@ -1396,6 +1848,82 @@ impl AuthManager {
         }
     }
 
+    /// Re-evaluates the active account after credential files changed on
+    /// disk. Unlike [`AuthManager::reload`], the current account is kept while
+    /// its file is still usable; otherwise the next available account,
+    /// including newly added ones, takes over. Returns whether the auth
+    /// value changed.
+    pub fn reevaluate_active_account(&self) -> bool {
+        // The active auth's storage remembers which account file it came
+        // from, so loading through it prefers that account.
+        let storage = self
+            .auth()
+            .filter(|auth| auth.mode == AuthMode::ChatGPT)
+            .map_or_else(|| self.storage(), |auth| auth.storage.clone());
+        let new_auth = load_auth_from_storage(storage, self.enable_codex_api_key_env)
+            .ok()
+            .flatten();
+        self.replace_auth(new_auth, "credential files changed")
+    }
+
+    /// Watches `auth.json` and the account files in the accounts directory and
+    /// re-evaluates the active account whenever one is added, replaced, or
+    /// removed, so long-running sessions pick up account changes without a
+    /// restart. The watcher stops when the manager is dropped. Read-only
+    /// storage gets no accounts directory created, so only the directories
+    /// that already exist are watched.
+    pub fn watch_auth_files(self: &Arc<Self>) -> std::io::Result<()> {
+        let accounts_dir = self.storage_settings.accounts_dir().to_path_buf();
+        if !self.storage_settings.read_only() {
+            std::fs::create_dir_all(&accounts_dir)?;
+        }
+        let manager = Arc::downgrade(self);
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let event = match event {
+                Ok(event) => event,
+                Err(err) => {
+                    warn!("auth file watcher error: {err}");
+                    return;
+                }
+            };
+            // Loading an account touches its mtime; reacting to metadata
+            // changes would reload forever.
+            if matches!(
+                event.kind,
+                EventKind::Access(_) | EventKind::Modify(ModifyKind::Metadata(_))
+            ) || !event.paths.iter().any(|path| is_credential_file(path))
+            {
+                return;
+            }
+            if let Some(manager) = manager.upgrade()
+                && manager.reevaluate_active_account()
+            {
+                tracing::info!("auth files changed on disk; switched active credentials");
+            }
+        })
+        .map_err(std::io::Error::other)?;
+        for dir in [self.storage_settings.codex_home(), accounts_dir.as_path()] {
+            if dir.is_dir() {
+                watcher
+                    .watch(dir, RecursiveMode::NonRecursive)
+                    .map_err(std::io::Error::other)?;
+            }
+        }
+        if let Ok(mut guard) = self.auth_watcher.lock() {
+            *guard = Some(watcher);
+        }
+        Ok(())
+    }
+
+    /// The registered backend, or a fresh built-in one for the configured
+    /// store mode so file rotation re-evaluates accounts on every load.
+    fn storage(&self) -> Arc<dyn AuthStorageBackend> {
+        match &self.storage_backend {
+            Some(storage) => storage.clone(),
+            None => create_auth_storage(&self.storage_settings, self.auth_credentials_store_mode),
+        }
+    }
+
     fn auths_equal(a: &Option<CodexAuth>, b: &Option<CodexAuth>) -> bool {
         match (a, b) {
             (None, None) => true,
@@ -1404,14 +1932,28 @@ impl AuthManager {
         }
     }
 
+    /// Convenience constructor returning an `Arc` wrapper around
+    /// [`AuthManager::with_storage_backend`].
+    pub fn shared_with_storage_backend(
+        storage_settings: AuthStorageSettings,
+        enable_codex_api_key_env: bool,
+        storage_backend: Arc<dyn AuthStorageBackend>,
+    ) -> Arc<Self> {
+        Arc::new(Self::with_storage_backend(
+            storage_settings,
+            enable_codex_api_key_env,
+            storage_backend,
+        ))
+    }
+
     /// Convenience constructor returning an `Arc` wrapper.
     pub fn shared(
-        codex_home: PathBuf,
+        storage_settings: AuthStorageSettings,
         enable_codex_api_key_env: bool,
         auth_credentials_store_mode: AuthCredentialsStoreMode,
     ) -> Arc<Self> {
         Arc::new(Self::new(
-            codex_home,
+            storage_settings,
             enable_codex_api_key_env,
             auth_credentials_store_mode,
         ))
@@ -1446,13 +1988,24 @@ impl AuthManager {
                     tracing::error!("Failed to refresh token: {}", err);
 
                     if should_rotate_accounts(&err) {
+                        let account = auth.account_label();
                         match auth.invalidate_current_account() {
                             Ok(Some(path)) => {
                                 tracing::warn!(
                                     "Marked account {} as invalid after refresh failure",
                                     path.display()
                                 );
-                                self.reload();
+                                if let Some(account) = account {
+                                    record_auth_event(
+                                        &self.storage_settings,
+                                        &AuthAuditEntry::new(
+                                            AuthAuditEvent::Invalidated,
+                                            account,
+                                            format!("token refresh failed: {err}"),
+                                        ),
+                                    );
+                                }
+                                self.reload_because("previous account invalidated");
                                 last_failure = Some(err);
                                 continue;
                             }
@@ -1476,12 +2029,45 @@ impl AuthManager {
         }
     }
 
+    /// Reload after the current account hit its usage limit (already
+    /// recorded on that account) and return the newly selected account when
+    /// storage switched to a different one that is not limited itself.
+    pub fn rotate_after_usage_limit(&self) -> Option<CodexAuth> {
+        let limited = self.auth()?;
+        let now = Utc::now();
+        if let Some(account) = limited.account_label() {
+            let reason = match limited
+                .get_current_auth_json()
+                .and_then(|auth| auth.current_usage_limit(now).cloned())
+            {
+                Some(UsageLimitStatus {
+                    resets_at: Some(resets_at),
+                    ..
+                }) => format!(
+                    "usage limit reached; resets at {}",
+                    resets_at.format("%Y-%m-%d %H:%M UTC")
+                ),
+                _ => "usage limit reached".to_string(),
+            };
+            record_auth_event(
+                &self.storage_settings,
+                &AuthAuditEntry::new(AuthAuditEvent::UsageLimit, account, reason),
+            );
+        }
+        self.reload_because("rotated after usage limit");
+        let next = self.auth()?;
+        let next_is_available = next
+            .get_current_auth_json()
+            .is_some_and(|auth| auth.current_usage_limit(now).is_none());
+        (next_is_available && next.account_label() != limited.account_label()).then_some(next)
+    }
+
     /// Log out by deleting the on‑disk auth.json (if present). Returns Ok(true)
     /// if a file was removed, Ok(false) if no auth file existed. On success,
     /// reloads the in‑memory auth cache so callers immediately observe the
     /// unauthenticated state.
     pub fn logout(&self) -> std::io::Result<bool> {
-        let removed = super::auth::logout(&self.codex_home, self.auth_credentials_store_mode)?;
+        let removed = self.storage().delete()?;
         // Always reload to clear any cached auth (even if file absent).
         self.reload();
         Ok(removed)
@@ -0,0 +1,135 @@
+//! Append-only log of account selection behind `codex auth audit`.
+//!
+//! Every time a session picks an account, an account hits its usage limit,
+//! or an account is invalidated, one JSON line is appended to
+//! `CODEX_HOME/auth-audit.jsonl` so teams can reconstruct which account
+//! performed which run.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::warn;
+
+use super::AuthStorageSettings;
+use super::CodexAuth;
+
+/// File under CODEX_HOME the audit log is appended to.
+pub const AUTH_AUDIT_FILE: &str = "auth-audit.jsonl";
+
+/// What happened to an account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthAuditEvent {
+    /// The account became the one requests are sent with.
+    Selected,
+    /// The account hit its usage limit or exhausted its API-key quota.
+    UsageLimit,
+    /// The account was marked unusable, e.g. after its refresh token was
+    /// revoked.
+    Invalidated,
+}
+
+/// One line of `auth-audit.jsonl`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthAuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub event: AuthAuditEvent,
+    /// Email (or `apikey-<label>`) of the account.
+    pub account: String,
+    /// Account that was active before a `selected` event, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_account: Option<String>,
+    pub reason: String,
+}
+
+impl AuthAuditEntry {
+    pub fn new(event: AuthAuditEvent, account: String, reason: impl Into<String>) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            event,
+            account,
+            previous_account: None,
+            reason: reason.into(),
+        }
+    }
+}
+
+fn auth_audit_file(codex_home: &Path) -> PathBuf {
+    codex_home.join(AUTH_AUDIT_FILE)
+}
+
+/// Appends `entry` to the audit log. Failures are logged rather than
+/// returned so auditing never interrupts a session; nothing is written while
+/// credential storage is read-only.
+pub(super) fn record_auth_event(settings: &AuthStorageSettings, entry: &AuthAuditEntry) {
+    let codex_home = settings.codex_home();
+    if codex_home.as_os_str().is_empty() || settings.read_only() {
+        return;
+    }
+    if let Err(err) = append_entry(codex_home, entry) {
+        warn!("failed to append to {AUTH_AUDIT_FILE}: {err}");
+    }
+}
+
+/// Records a `selected` event when `next` is a different account than
+/// `previous`.
+pub(super) fn record_selected_account(
+    settings: &AuthStorageSettings,
+    previous: Option<&CodexAuth>,
+    next: Option<&CodexAuth>,
+    reason: &str,
+) {
+    let Some(account) = next.and_then(CodexAuth::account_label) else {
+        return;
+    };
+    let previous_account = previous.and_then(CodexAuth::account_label);
+    if previous_account.as_ref() == Some(&account) {
+        return;
+    }
+    record_auth_event(
+        settings,
+        &AuthAuditEntry {
+            previous_account,
+            ..AuthAuditEntry::new(AuthAuditEvent::Selected, account, reason)
+        },
+    );
+}
+
+fn append_entry(codex_home: &Path, entry: &AuthAuditEntry) -> std::io::Result<()> {
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    std::fs::create_dir_all(codex_home)?;
+    let mut file = OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(auth_audit_file(codex_home))?;
+    // One write per line keeps concurrent sessions from interleaving entries.
+    file.write_all(line.as_bytes())
+}
+
+/// Reads every entry of the audit log, oldest first. A missing log reads as
+/// empty; lines that do not parse are skipped.
+pub fn read_auth_audit(codex_home: &Path) -> std::io::Result<Vec<AuthAuditEntry>> {
+    let contents = match std::fs::read_to_string(auth_audit_file(codex_home)) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    Ok(contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(err) => {
+                warn!("skipping malformed {AUTH_AUDIT_FILE} line: {err}");
+                None
+            }
+        })
+        .collect())
+}
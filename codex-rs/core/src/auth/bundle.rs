@@ -0,0 +1,414 @@
+//! Passphrase-encrypted credential bundles behind `codex auth export` and
+//! `codex auth import`.
+//!
+//! The key is derived from the passphrase with Argon2id and the serialized
+//! credentials are sealed with XChaCha20-Poly1305. KDF parameters travel with
+//! the bundle so they can be raised later without breaking old bundles.
+
+use std::sync::Arc;
+
+use argon2::Algorithm;
+use argon2::Argon2;
+use argon2::Params;
+use argon2::Version;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chacha20poly1305::KeyInit;
+use chacha20poly1305::XChaCha20Poly1305;
+use chacha20poly1305::XNonce;
+use chacha20poly1305::aead::Aead;
+use chacha20poly1305::aead::Payload;
+use codex_keyring_store::DefaultKeyringStore;
+use codex_keyring_store::KeyringStore;
+use rand::RngCore;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::storage::AuthCredentialsStoreMode;
+use super::storage::AuthStorageSettings;
+use super::storage::StoredCredential;
+use super::storage::restore_credentials;
+use super::storage::stored_credentials;
+
+const BUNDLE_FORMAT: &str = "codex-auth-bundle";
+const BUNDLE_VERSION: u32 = 1;
+const KDF_ALGORITHM: &str = "argon2id";
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+/// Upper bounds on the key derivation parameters read from a bundle, so a
+/// crafted bundle cannot make import allocate gigabytes or spin for minutes
+/// before the passphrase is even checked.
+const MAX_KDF_MEMORY_KIB: u32 = 1024 * 1024;
+const MAX_KDF_ITERATIONS: u32 = 64;
+const MAX_KDF_PARALLELISM: u32 = 16;
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedBundle {
+    format: String,
+    version: u32,
+    kdf: KdfParams,
+    /// Base64 XChaCha20-Poly1305 nonce.
+    nonce: String,
+    /// Base64 ciphertext of the JSON-encoded [`BundlePayload`].
+    ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KdfParams {
+    algorithm: String,
+    /// Base64 salt.
+    salt: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BundlePayload {
+    credentials: Vec<StoredCredential>,
+}
+
+/// Encrypts every credential stored for `mode` — the primary credential and
+/// all rotation accounts, including their usage-limit state — with
+/// `passphrase`. Returns the bundle and how many credentials it holds.
+pub fn export_credentials(
+    settings: &AuthStorageSettings,
+    mode: AuthCredentialsStoreMode,
+    passphrase: &str,
+) -> std::io::Result<(String, usize)> {
+    export_credentials_with_keyring_store(settings, mode, Arc::new(DefaultKeyringStore), passphrase)
+}
+
+/// Decrypts a bundle produced by [`export_credentials`] and stores its
+/// credentials for `mode`. Returns how many credentials were imported.
+pub fn import_credentials(
+    settings: &AuthStorageSettings,
+    mode: AuthCredentialsStoreMode,
+    bundle: &str,
+    passphrase: &str,
+) -> std::io::Result<usize> {
+    import_credentials_with_keyring_store(
+        settings,
+        mode,
+        Arc::new(DefaultKeyringStore),
+        bundle,
+        passphrase,
+    )
+}
+
+fn export_credentials_with_keyring_store(
+    settings: &AuthStorageSettings,
+    mode: AuthCredentialsStoreMode,
+    keyring_store: Arc<dyn KeyringStore>,
+    passphrase: &str,
+) -> std::io::Result<(String, usize)> {
+    let credentials = stored_credentials(settings, mode, keyring_store)?;
+    if credentials.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "no stored credentials to export",
+        ));
+    }
+    let count = credentials.len();
+    let plaintext = serde_json::to_vec(&BundlePayload { credentials })?;
+    Ok((seal(&plaintext, passphrase)?, count))
+}
+
+fn import_credentials_with_keyring_store(
+    settings: &AuthStorageSettings,
+    mode: AuthCredentialsStoreMode,
+    keyring_store: Arc<dyn KeyringStore>,
+    bundle: &str,
+    passphrase: &str,
+) -> std::io::Result<usize> {
+    let plaintext = open(bundle, passphrase)?;
+    let BundlePayload { credentials } = serde_json::from_slice(&plaintext)?;
+    restore_credentials(settings, mode, keyring_store, &credentials)?;
+    Ok(credentials.len())
+}
+
+fn seal(plaintext: &[u8], passphrase: &str) -> std::io::Result<String> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = XNonce::default();
+    let mut rng = rand::rng();
+    rng.fill_bytes(&mut salt);
+    rng.fill_bytes(&mut nonce);
+
+    let params = Params::default();
+    let kdf = KdfParams {
+        algorithm: KDF_ALGORITHM.to_string(),
+        salt: BASE64.encode(salt),
+        memory_kib: params.m_cost(),
+        iterations: params.t_cost(),
+        parallelism: params.p_cost(),
+    };
+    let cipher = cipher_for(passphrase, &kdf)?;
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad: associated_data().as_bytes(),
+            },
+        )
+        .map_err(|_| std::io::Error::other("failed to encrypt credential bundle"))?;
+
+    let bundle = EncryptedBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        kdf,
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    };
+    Ok(serde_json::to_string_pretty(&bundle)?)
+}
+
+fn open(bundle: &str, passphrase: &str) -> std::io::Result<Vec<u8>> {
+    let bundle: EncryptedBundle = serde_json::from_str(bundle)
+        .map_err(|err| invalid_bundle(format!("not a codex credential bundle: {err}")))?;
+    if bundle.format != BUNDLE_FORMAT || bundle.version != BUNDLE_VERSION {
+        return Err(invalid_bundle(format!(
+            "unsupported credential bundle {} v{}",
+            bundle.format, bundle.version
+        )));
+    }
+    let nonce = decode(&bundle.nonce)?;
+    if nonce.len() != XNonce::default().len() {
+        return Err(invalid_bundle(
+            "credential bundle nonce has the wrong length",
+        ));
+    }
+    let ciphertext = decode(&bundle.ciphertext)?;
+    cipher_for(passphrase, &bundle.kdf)?
+        .decrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: associated_data().as_bytes(),
+            },
+        )
+        .map_err(|_| invalid_bundle("wrong passphrase or corrupted credential bundle"))
+}
+
+fn cipher_for(passphrase: &str, kdf: &KdfParams) -> std::io::Result<XChaCha20Poly1305> {
+    if kdf.algorithm != KDF_ALGORITHM {
+        return Err(invalid_bundle(format!(
+            "unsupported key derivation `{}`",
+            kdf.algorithm
+        )));
+    }
+    if kdf.memory_kib > MAX_KDF_MEMORY_KIB
+        || kdf.iterations > MAX_KDF_ITERATIONS
+        || kdf.parallelism > MAX_KDF_PARALLELISM
+    {
+        return Err(invalid_bundle(format!(
+            "key derivation parameters exceed the supported limits \
+             ({MAX_KDF_MEMORY_KIB} KiB, {MAX_KDF_ITERATIONS} iterations, \
+             {MAX_KDF_PARALLELISM} lanes)"
+        )));
+    }
+    let params = Params::new(
+        kdf.memory_kib,
+        kdf.iterations,
+        kdf.parallelism,
+        Some(KEY_LEN),
+    )
+    .map_err(|err| invalid_bundle(format!("invalid key derivation parameters: {err}")))?;
+    let mut key = [0u8; KEY_LEN];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &decode(&kdf.salt)?, &mut key)
+        .map_err(|err| std::io::Error::other(format!("failed to derive bundle key: {err}")))?;
+    XChaCha20Poly1305::new_from_slice(&key)
+        .map_err(|_| std::io::Error::other("failed to initialize bundle cipher"))
+}
+
+/// Binds the ciphertext to the bundle format and version.
+fn associated_data() -> String {
+    format!("{BUNDLE_FORMAT}/v{BUNDLE_VERSION}")
+}
+
+fn decode(value: &str) -> std::io::Result<Vec<u8>> {
+    BASE64
+        .decode(value)
+        .map_err(|err| invalid_bundle(format!("invalid base64 in credential bundle: {err}")))
+}
+
+fn invalid_bundle(message: impl Into<String>) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::storage::AuthDotJson;
+    use codex_keyring_store::tests::MockKeyringStore;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn auth_with_usage_limit(api_key: &str) -> anyhow::Result<AuthDotJson> {
+        Ok(serde_json::from_value(json!({
+            "OPENAI_API_KEY": api_key,
+            "account_state": {
+                "last_issue": {
+                    "type": "usage_limit",
+                    "resets_at": "2099-01-01T00:00:00Z",
+                    "recorded_at": "2025-01-01T00:00:00Z"
+                }
+            }
+        }))?)
+    }
+
+    #[test]
+    fn export_import_round_trips_accounts_and_account_state() -> anyhow::Result<()> {
+        let source = tempdir()?;
+        let keyring = MockKeyringStore::default();
+        let primary: AuthDotJson =
+            serde_json::from_value(json!({ "OPENAI_API_KEY": "sk-primary" }))?;
+        restore_credentials(
+            &AuthStorageSettings::new(source.path().to_path_buf()),
+            AuthCredentialsStoreMode::Keyring,
+            Arc::new(keyring.clone()),
+            &[
+                StoredCredential {
+                    account_file: None,
+                    last_used_millis: None,
+                    auth: primary.clone(),
+                },
+                StoredCredential {
+                    account_file: Some("alice@example.com.json".to_string()),
+                    last_used_millis: Some(5_000),
+                    auth: auth_with_usage_limit("sk-alice")?,
+                },
+            ],
+        )?;
+
+        let (bundle, exported) = export_credentials_with_keyring_store(
+            &AuthStorageSettings::new(source.path().to_path_buf()),
+            AuthCredentialsStoreMode::Keyring,
+            Arc::new(keyring),
+            "correct horse",
+        )?;
+        assert_eq!(exported, 2);
+        assert!(!bundle.contains("sk-alice"));
+
+        let target = tempdir()?;
+        let wrong = import_credentials_with_keyring_store(
+            &AuthStorageSettings::new(target.path().to_path_buf()),
+            AuthCredentialsStoreMode::File,
+            Arc::new(MockKeyringStore::default()),
+            &bundle,
+            "battery staple",
+        );
+        assert_eq!(
+            wrong.map_err(|err| err.kind()).err(),
+            Some(std::io::ErrorKind::InvalidData)
+        );
+
+        let imported = import_credentials_with_keyring_store(
+            &AuthStorageSettings::new(target.path().to_path_buf()),
+            AuthCredentialsStoreMode::File,
+            Arc::new(MockKeyringStore::default()),
+            &bundle,
+            "correct horse",
+        )?;
+        assert_eq!(imported, 2);
+        let restored = stored_credentials(
+            &AuthStorageSettings::new(target.path().to_path_buf()),
+            AuthCredentialsStoreMode::File,
+            Arc::new(MockKeyringStore::default()),
+        )?;
+        assert_eq!(
+            restored,
+            vec![
+                StoredCredential {
+                    account_file: None,
+                    last_used_millis: None,
+                    auth: primary,
+                },
+                StoredCredential {
+                    account_file: Some("alice@example.com.json".to_string()),
+                    last_used_millis: Some(5_000),
+                    auth: auth_with_usage_limit("sk-alice")?,
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn keyring_import_keeps_accounts_out_of_plaintext_files() -> anyhow::Result<()> {
+        let source = tempdir()?;
+        let source_keyring = MockKeyringStore::default();
+        let credentials = vec![
+            StoredCredential {
+                account_file: None,
+                last_used_millis: None,
+                auth: serde_json::from_value(json!({ "OPENAI_API_KEY": "sk-primary" }))?,
+            },
+            StoredCredential {
+                account_file: Some("alice@example.com.json".to_string()),
+                last_used_millis: Some(5_000),
+                auth: auth_with_usage_limit("sk-alice")?,
+            },
+        ];
+        restore_credentials(
+            &AuthStorageSettings::new(source.path().to_path_buf()),
+            AuthCredentialsStoreMode::Keyring,
+            Arc::new(source_keyring.clone()),
+            &credentials,
+        )?;
+        let (bundle, _) = export_credentials_with_keyring_store(
+            &AuthStorageSettings::new(source.path().to_path_buf()),
+            AuthCredentialsStoreMode::Keyring,
+            Arc::new(source_keyring),
+            "correct horse",
+        )?;
+
+        let target = tempdir()?;
+        let target_settings = AuthStorageSettings::new(target.path().to_path_buf());
+        let target_keyring = MockKeyringStore::default();
+        let imported = import_credentials_with_keyring_store(
+            &target_settings,
+            AuthCredentialsStoreMode::Keyring,
+            Arc::new(target_keyring.clone()),
+            &bundle,
+            "correct horse",
+        )?;
+        assert_eq!(imported, 2);
+
+        let account_files: Vec<_> = match std::fs::read_dir(target_settings.accounts_dir()) {
+            Ok(entries) => entries.collect::<Result<_, _>>()?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+        assert!(account_files.is_empty(), "{account_files:?}");
+        assert!(!target.path().join("auth.json").exists());
+        let restored = stored_credentials(
+            &target_settings,
+            AuthCredentialsStoreMode::Keyring,
+            Arc::new(target_keyring),
+        )?;
+        assert_eq!(restored, credentials);
+        Ok(())
+    }
+
+    #[test]
+    fn open_rejects_oversized_key_derivation_parameters() -> anyhow::Result<()> {
+        let bundle = seal(b"{}", "correct horse")?;
+        for (field, value) in [
+            ("memory_kib", u32::MAX),
+            ("iterations", u32::MAX),
+            ("parallelism", MAX_KDF_PARALLELISM + 1),
+        ] {
+            let mut crafted: serde_json::Value = serde_json::from_str(&bundle)?;
+            crafted["kdf"][field] = json!(value);
+            let err = open(&crafted.to_string(), "correct horse")
+                .err()
+                .ok_or_else(|| anyhow::anyhow!("{field} = {value} was accepted"))?;
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        }
+        assert_eq!(open(&bundle, "correct horse")?, b"{}");
+        Ok(())
+    }
+}
@@ -0,0 +1,251 @@
+//! Account health probe behind `codex auth check`.
+//!
+//! Each stored account makes the same cheap authenticated request as the
+//! usage view (`GET /wham/usage`). A rejected access token gets one refresh
+//! attempt, so an account is only reported dead when its refresh token is
+//! rejected too.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use chrono::DateTime;
+use chrono::Utc;
+use codex_app_server_protocol::AuthMode;
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use super::CodexAuth;
+use super::RefreshTokenError;
+use super::audit::AuthAuditEntry;
+use super::audit::AuthAuditEvent;
+use super::audit::record_auth_event;
+use super::storage::AccountFileStorage;
+use super::storage::AuthStorageBackend;
+use super::storage::AuthStorageSettings;
+use super::storage::UsageLimitStatus;
+use super::storage::list_accounts;
+use crate::error::RefreshTokenFailedReason;
+
+/// Verdict for one stored account.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccountHealth {
+    /// The backend accepted the account's credentials.
+    Valid,
+    /// The credentials work but the account hit its usage limit.
+    Limited { until: Option<DateTime<Utc>> },
+    /// The refresh token expired; signing in again revives the account.
+    Expired,
+    /// The backend rejected the credentials for good, e.g. a revoked token.
+    Invalid { reason: String },
+    /// The probe could not reach a verdict, e.g. because of a network error.
+    Unknown { error: String },
+}
+
+impl AccountHealth {
+    /// Whether the account is unusable until someone signs in again.
+    pub fn is_dead(&self) -> bool {
+        matches!(self, Self::Expired | Self::Invalid { .. })
+    }
+}
+
+/// Outcome of probing one account with [`check_accounts`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountCheck {
+    pub email: String,
+    pub path: PathBuf,
+    pub health: AccountHealth,
+    /// Where the account file was moved when dead accounts are invalidated.
+    pub invalidated: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct UsagePayload {
+    #[serde(default)]
+    rate_limit: Option<RateLimit>,
+}
+
+#[derive(Deserialize)]
+struct RateLimit {
+    #[serde(default)]
+    limit_reached: bool,
+    #[serde(default)]
+    primary_window: Option<RateLimitWindow>,
+    #[serde(default)]
+    secondary_window: Option<RateLimitWindow>,
+}
+
+impl RateLimit {
+    /// When the last exhausted window resets.
+    fn resets_at(&self) -> Option<DateTime<Utc>> {
+        [&self.primary_window, &self.secondary_window]
+            .into_iter()
+            .flatten()
+            .filter(|window| window.used_percent >= 100.0)
+            .filter_map(|window| DateTime::from_timestamp(window.reset_at, 0))
+            .max()
+    }
+}
+
+#[derive(Deserialize)]
+struct RateLimitWindow {
+    used_percent: f64,
+    reset_at: i64,
+}
+
+/// Probes every account under `CODEX_HOME/auth` in rotation order. With
+/// `invalidate_dead`, expired and invalid accounts are moved aside through
+/// [`AuthStorageBackend::invalidate_active_account`] so rotation stops
+/// picking them.
+pub async fn check_accounts(
+    settings: &AuthStorageSettings,
+    chatgpt_base_url: &str,
+    invalidate_dead: bool,
+) -> std::io::Result<Vec<AccountCheck>> {
+    let url = usage_url(chatgpt_base_url);
+    let mut checks = Vec::new();
+    for account in list_accounts(settings)? {
+        let storage: Arc<dyn AuthStorageBackend> =
+            Arc::new(AccountFileStorage::new(settings, account.path.clone()));
+        let health = match storage.load() {
+            Ok(Some(auth_dot_json)) => {
+                let auth = CodexAuth {
+                    mode: AuthMode::ChatGPT,
+                    api_key: None,
+                    auth_dot_json: Arc::new(Mutex::new(Some(auth_dot_json))),
+                    storage: Arc::clone(&storage),
+                    client: crate::default_client::create_client(),
+                };
+                let recorded_limit = account
+                    .usage_limit
+                    .as_ref()
+                    .map(UsageLimitStatus::next_retry_at);
+                check_account(&auth, &url, recorded_limit).await
+            }
+            // Removed or quarantined since it was listed.
+            Ok(None) => continue,
+            Err(err) => AccountHealth::Unknown {
+                error: err.to_string(),
+            },
+        };
+        let invalidated = if invalidate_dead && health.is_dead() {
+            storage.invalidate_active_account()?
+        } else {
+            None
+        };
+        if invalidated.is_some() {
+            let reason = match &health {
+                AccountHealth::Invalid { reason } => {
+                    format!("codex auth check: credentials rejected: {reason}")
+                }
+                _ => "codex auth check: refresh token expired".to_string(),
+            };
+            record_auth_event(
+                settings,
+                &AuthAuditEntry::new(AuthAuditEvent::Invalidated, account.email.clone(), reason),
+            );
+        }
+        checks.push(AccountCheck {
+            email: account.email,
+            path: account.path,
+            health,
+            invalidated,
+        });
+    }
+    Ok(checks)
+}
+
+fn usage_url(chatgpt_base_url: &str) -> String {
+    let base_url = chatgpt_base_url.trim_end_matches('/');
+    if base_url.contains("/backend-api") {
+        format!("{base_url}/wham/usage")
+    } else {
+        format!("{base_url}/api/codex/usage")
+    }
+}
+
+async fn check_account(
+    auth: &CodexAuth,
+    url: &str,
+    recorded_limit: Option<DateTime<Utc>>,
+) -> AccountHealth {
+    if let Some(health) = probe(auth, url, recorded_limit).await {
+        return health;
+    }
+    match auth.refresh_token().await {
+        Ok(_) => probe(auth, url, recorded_limit)
+            .await
+            .unwrap_or_else(|| AccountHealth::Invalid {
+                reason: "access token rejected after refresh".to_string(),
+            }),
+        Err(err) if err.failed_reason() == Some(RefreshTokenFailedReason::Expired) => {
+            AccountHealth::Expired
+        }
+        Err(RefreshTokenError::Permanent(err)) => AccountHealth::Invalid {
+            reason: err.to_string(),
+        },
+        Err(RefreshTokenError::Transient(err)) => AccountHealth::Unknown {
+            error: err.to_string(),
+        },
+    }
+}
+
+/// Sends one usage request with the account's current access token. Returns
+/// `None` when the token was rejected and is worth refreshing.
+async fn probe(
+    auth: &CodexAuth,
+    url: &str,
+    recorded_limit: Option<DateTime<Utc>>,
+) -> Option<AccountHealth> {
+    let Some(tokens) = auth.get_current_token_data() else {
+        let is_api_key = auth
+            .get_current_auth_json()
+            .is_some_and(|auth| auth.openai_api_key.is_some());
+        let error = if is_api_key {
+            "API-key accounts are not probed"
+        } else {
+            "no ChatGPT tokens stored"
+        };
+        return Some(AccountHealth::Unknown {
+            error: error.to_string(),
+        });
+    };
+    let mut request = auth.client.get(url).bearer_auth(&tokens.access_token);
+    if let Some(account_id) = &tokens.account_id {
+        request = request.header("ChatGPT-Account-Id", account_id);
+    }
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(err) => {
+            return Some(AccountHealth::Unknown {
+                error: err.to_string(),
+            });
+        }
+    };
+    let health = match response.status() {
+        StatusCode::UNAUTHORIZED => return None,
+        StatusCode::TOO_MANY_REQUESTS => AccountHealth::Limited {
+            until: recorded_limit,
+        },
+        StatusCode::FORBIDDEN => AccountHealth::Invalid {
+            reason: "HTTP 403 Forbidden".to_string(),
+        },
+        status if status.is_success() => {
+            let rate_limit = response
+                .json::<UsagePayload>()
+                .await
+                .ok()
+                .and_then(|payload| payload.rate_limit);
+            match rate_limit.filter(|limit| limit.limit_reached) {
+                Some(limit) => AccountHealth::Limited {
+                    until: limit.resets_at().or(recorded_limit),
+                },
+                None => AccountHealth::Valid,
+            }
+        }
+        status => AccountHealth::Unknown {
+            error: format!("unexpected HTTP {status}"),
+        },
+    };
+    Some(health)
+}
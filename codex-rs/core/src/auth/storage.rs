@@ -1,11 +1,15 @@
 use chrono::DateTime;
+use chrono::Datelike;
 use chrono::Duration as ChronoDuration;
+use chrono::NaiveDate;
 use chrono::Utc;
 use filetime::FileTime;
 use serde::Deserialize;
 use serde::Serialize;
 use sha2::Digest;
 use sha2::Sha256;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::ffi::OsStr;
 use std::fmt::Debug;
 use std::fs::File;
@@ -18,16 +22,25 @@ use std::os::unix::fs::OpenOptionsExt;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::LazyLock;
 use std::sync::Mutex;
 use std::sync::MutexGuard;
 use std::time::Duration;
 use std::time::SystemTime;
 use tracing::warn;
 
+use crate::auth::CODEX_ACCESS_TOKEN_ENV_VAR;
+use crate::auth::CODEX_ACCOUNT_LABEL_ENV_VAR;
+use crate::auth::CODEX_ACCOUNTS_DIR_ENV_VAR;
+use crate::auth::CODEX_AUTH_READ_ONLY_ENV_VAR;
+use crate::auth::CODEX_REFRESH_TOKEN_ENV_VAR;
+use crate::auth::OPENAI_API_KEY_ENV_VAR;
 use crate::token_data::PlanType;
 use crate::token_data::TokenData;
+use crate::token_data::parse_id_token;
 use codex_keyring_store::DefaultKeyringStore;
 use codex_keyring_store::KeyringStore;
+use codex_protocol::protocol::TokenUsage;
 
 /// Determine where Codex should store CLI auth credentials.
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -38,7 +51,8 @@ pub enum AuthCredentialsStoreMode {
     File,
     /// Persist credentials in the keyring. Fail if unavailable.
     Keyring,
-    /// Use keyring when available; otherwise, fall back to a file in CODEX_HOME.
+    /// Use credentials from the environment when set, then the keyring when
+    /// available; otherwise, fall back to a file in CODEX_HOME.
     Auto,
 }
 
@@ -46,23 +60,48 @@ pub enum AuthCredentialsStoreMode {
 pub struct AccountState {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     last_issue: Option<AccountIssue>,
+    /// When the account last hit its usage limit. Unlike `last_issue`, it
+    /// survives later issues and `codex auth clear-issues`, so rotation can
+    /// prefer the least recently limited account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_usage_limit_at: Option<DateTime<Utc>>,
+    /// Tokens consumed per UTC day, oldest first, covering the last
+    /// [`USAGE_HISTORY_DAYS`] days.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    usage: Vec<DailyUsage>,
 }
 
 impl AccountState {
     pub fn record_issue(&mut self, issue: AccountIssue) {
+        if let AccountIssue::UsageLimit(status) = &issue {
+            self.last_usage_limit_at = Some(status.recorded_at);
+        }
         self.last_issue = Some(issue);
     }
 
-    pub fn current_issue(&self, now: DateTime<Utc>) -> Option<&AccountIssue> {
-        match self.last_issue.as_ref() {
-            Some(AccountIssue::UsageLimit(status)) if status.is_active(now) => {
-                self.last_issue.as_ref()
+    /// When the account last hit its usage limit, even if the limit has
+    /// since reset or was cleared.
+    pub fn last_usage_limit_at(&self) -> Option<DateTime<Utc>> {
+        match &self.last_issue {
+            Some(AccountIssue::UsageLimit(status)) if self.last_usage_limit_at.is_none() => {
+                Some(status.recorded_at)
             }
-            Some(AccountIssue::UsageLimit(_)) => None,
-            other => other,
+            _ => self.last_usage_limit_at,
         }
     }
 
+    /// The recorded issue while it still blocks the account.
+    pub fn current_issue(&self, now: DateTime<Utc>) -> Option<&AccountIssue> {
+        self.last_issue
+            .as_ref()
+            .filter(|issue| issue.next_retry_at() > now)
+    }
+
+    /// Forgets the recorded issue; returns whether there was one.
+    pub fn clear_issue(&mut self) -> bool {
+        self.last_issue.take().is_some()
+    }
+
     pub fn current_usage_limit(&self, now: DateTime<Utc>) -> Option<&UsageLimitStatus> {
         match self.current_issue(now) {
             Some(AccountIssue::UsageLimit(status)) => Some(status),
@@ -70,8 +109,86 @@ impl AccountState {
         }
     }
 
+    pub fn current_unexpected_response(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Option<&UnexpectedResponseStatus> {
+        match self.current_issue(now) {
+            Some(AccountIssue::UnexpectedResponse(status)) => Some(status),
+            _ => None,
+        }
+    }
+
     pub fn is_available(&self, now: DateTime<Utc>) -> bool {
-        self.current_usage_limit(now).is_none()
+        self.current_issue(now).is_none()
+    }
+
+    /// Adds one response's token usage to today's bucket and drops buckets
+    /// older than [`USAGE_HISTORY_DAYS`].
+    pub fn record_token_usage(&mut self, usage: &TokenUsage, now: DateTime<Utc>) {
+        let today = now.date_naive();
+        match self.usage.last_mut() {
+            Some(day) if day.date == today => day.tokens.add(usage),
+            _ => {
+                let mut tokens = AccountTokenUsage::default();
+                tokens.add(usage);
+                self.usage.push(DailyUsage {
+                    date: today,
+                    tokens,
+                });
+            }
+        }
+        let oldest = today - ChronoDuration::days(USAGE_HISTORY_DAYS - 1);
+        self.usage.retain(|day| day.date >= oldest);
+    }
+
+    /// Total tokens recorded on or after `since`.
+    pub fn usage_since(&self, since: NaiveDate) -> AccountTokenUsage {
+        let mut total = AccountTokenUsage::default();
+        for day in self.usage.iter().filter(|day| day.date >= since) {
+            total.merge(&day.tokens);
+        }
+        total
+    }
+}
+
+/// Days of per-account token usage kept in [`AccountState`]; enough to
+/// report the current week.
+const USAGE_HISTORY_DAYS: i64 = 7;
+
+/// Tokens one account consumed on one UTC day.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+struct DailyUsage {
+    date: NaiveDate,
+    #[serde(flatten)]
+    tokens: AccountTokenUsage,
+}
+
+/// Token counts attributed to an account, mirroring [`TokenUsage`].
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct AccountTokenUsage {
+    pub input_tokens: i64,
+    pub cached_input_tokens: i64,
+    pub output_tokens: i64,
+    pub reasoning_output_tokens: i64,
+    pub total_tokens: i64,
+}
+
+impl AccountTokenUsage {
+    fn add(&mut self, usage: &TokenUsage) {
+        self.input_tokens += usage.input_tokens;
+        self.cached_input_tokens += usage.cached_input_tokens;
+        self.output_tokens += usage.output_tokens;
+        self.reasoning_output_tokens += usage.reasoning_output_tokens;
+        self.total_tokens += usage.total_tokens;
+    }
+
+    fn merge(&mut self, other: &AccountTokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.cached_input_tokens += other.cached_input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.reasoning_output_tokens += other.reasoning_output_tokens;
+        self.total_tokens += other.total_tokens;
     }
 }
 
@@ -82,6 +199,16 @@ pub enum AccountIssue {
     UnexpectedResponse(UnexpectedResponseStatus),
 }
 
+impl AccountIssue {
+    /// When rotation may pick the account again.
+    pub fn next_retry_at(&self) -> DateTime<Utc> {
+        match self {
+            AccountIssue::UsageLimit(status) => status.next_retry_at(),
+            AccountIssue::UnexpectedResponse(status) => status.next_retry_at(),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct UsageLimitStatus {
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -110,6 +237,33 @@ pub struct UnexpectedResponseStatus {
     pub request_id: Option<String>,
     #[serde(default, skip_serializing_if = "String::is_empty")]
     pub body: String,
+    /// End of the cooldown configured when the response was recorded; older
+    /// records use [`DEFAULT_UNEXPECTED_RESPONSE_COOLDOWN`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked_until: Option<DateTime<Utc>>,
+}
+
+/// How long an account is skipped after an unexpected API response unless
+/// `unexpected_response_cooldown_minutes` says otherwise.
+pub const DEFAULT_UNEXPECTED_RESPONSE_COOLDOWN: Duration = Duration::from_secs(30 * 60);
+
+impl UnexpectedResponseStatus {
+    pub fn next_retry_at(&self) -> DateTime<Utc> {
+        self.blocked_until
+            .unwrap_or_else(|| cooldown_end(self.recorded_at, DEFAULT_UNEXPECTED_RESPONSE_COOLDOWN))
+    }
+
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.next_retry_at() > now
+    }
+}
+
+/// `start + cooldown`, saturating instead of overflowing.
+pub(crate) fn cooldown_end(start: DateTime<Utc>, cooldown: Duration) -> DateTime<Utc> {
+    ChronoDuration::from_std(cooldown)
+        .ok()
+        .and_then(|cooldown| start.checked_add_signed(cooldown))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
 }
 
 /// Expected structure for $CODEX_HOME/auth.json.
@@ -126,6 +280,22 @@ pub struct AuthDotJson {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub account_state: Option<AccountState>,
+
+    #[serde(flatten)]
+    pub metadata: AccountMetadata,
+}
+
+/// Organization details and free-form labels set with `codex auth tag`, so
+/// one machine can hold accounts for several organizations and select them
+/// with `codex --account-label`.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountMetadata {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub org_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
 }
 
 impl AuthDotJson {
@@ -135,21 +305,635 @@ impl AuthDotJson {
             .is_none_or(|state| state.is_available(now))
     }
 
+    pub fn current_issue(&self, now: DateTime<Utc>) -> Option<&AccountIssue> {
+        self.account_state
+            .as_ref()
+            .and_then(|state| state.current_issue(now))
+    }
+
     pub fn current_usage_limit(&self, now: DateTime<Utc>) -> Option<&UsageLimitStatus> {
         self.account_state
             .as_ref()
             .and_then(|state| state.current_usage_limit(now))
     }
+
+    pub fn last_usage_limit_at(&self) -> Option<DateTime<Utc>> {
+        self.account_state
+            .as_ref()
+            .and_then(AccountState::last_usage_limit_at)
+    }
+
+    pub fn current_unexpected_response(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Option<&UnexpectedResponseStatus> {
+        self.account_state
+            .as_ref()
+            .and_then(|state| state.current_unexpected_response(now))
+    }
+}
+
+/// One `CODEX_HOME/auth/<email>.json` account as reported by [`list_accounts`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountSummary {
+    /// Email of a ChatGPT account, or the file stem (`apikey-<label>`) of an
+    /// API-key account.
+    pub email: String,
+    pub path: PathBuf,
+    /// ChatGPT plan from the account's id token, e.g. `Plus`, or `API key`.
+    pub plan_type: Option<String>,
+    /// Last time the account was picked or saved (the file's mtime).
+    pub last_used: Option<DateTime<Utc>>,
+    /// Active usage limit recorded for the account, if any.
+    pub usage_limit: Option<UsageLimitStatus>,
+    /// When the account last hit its usage limit, if ever.
+    pub last_usage_limit_at: Option<DateTime<Utc>>,
+    /// Unexpected API response still blocking the account, if any.
+    pub unexpected_response: Option<UnexpectedResponseStatus>,
+    /// Tokens the account consumed today (UTC).
+    pub usage_today: AccountTokenUsage,
+    /// Tokens the account consumed since Monday (UTC).
+    pub usage_this_week: AccountTokenUsage,
+    /// Labels and organization set with `codex auth tag`.
+    pub metadata: AccountMetadata,
+    /// Whether `codex auth use` pinned this account.
+    pub is_pinned: bool,
+    /// Whether the next file-backed load would pick this account.
+    pub is_next: bool,
+}
+
+impl AccountSummary {
+    /// When the account can be used again, or `None` if it is usable now.
+    pub fn retry_at(&self) -> Option<DateTime<Utc>> {
+        self.usage_limit
+            .as_ref()
+            .map(UsageLimitStatus::next_retry_at)
+            .or_else(|| {
+                self.unexpected_response
+                    .as_ref()
+                    .map(UnexpectedResponseStatus::next_retry_at)
+            })
+    }
+}
+
+/// Earliest time one of `accounts` can be used again when every one of them
+/// is usage limited; `None` when some account is available or there are none.
+pub fn accounts_limited_until(accounts: &[AccountSummary]) -> Option<DateTime<Utc>> {
+    accounts
+        .iter()
+        .map(AccountSummary::retry_at)
+        .collect::<Option<Vec<_>>>()?
+        .into_iter()
+        .min()
+}
+
+/// Lists the rotation accounts under `CODEX_HOME/auth` in the order file
+/// storage tries them (least recently used first) and flags the one it would
+/// pick next: the pinned account if there is one, otherwise the least
+/// recently limited account without an active usage limit, or the one whose
+/// limit resets soonest when every account is limited. Only accounts
+/// matching the label filter can be picked next.
+pub fn list_accounts(settings: &AuthStorageSettings) -> std::io::Result<Vec<AccountSummary>> {
+    let storage = FileAuthStorage::new(settings);
+    let now = Utc::now();
+    let pinned = storage.pinned_path()?;
+    let mut accounts = Vec::new();
+    for path in storage.candidate_paths()? {
+        let auth = match storage.try_read_auth_json(&path) {
+            Ok(auth) => auth,
+            Err(err) if err.kind() == ErrorKind::NotFound => continue,
+            Err(err) => {
+                warn!("failed to read account file {}: {err}", path.display());
+                continue;
+            }
+        };
+        let id_token = auth.tokens.as_ref().map(|tokens| &tokens.id_token);
+        let email = id_token
+            .and_then(|token| token.email.clone())
+            .or_else(|| path.file_stem().and_then(OsStr::to_str).map(str::to_string))
+            .unwrap_or_default();
+        let last_used = std::fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .map(DateTime::<Utc>::from);
+        let state = auth.account_state.clone().unwrap_or_default();
+        let today = now.date_naive();
+        let week_start =
+            today - ChronoDuration::days(i64::from(today.weekday().num_days_from_monday()));
+        accounts.push(AccountSummary {
+            email,
+            plan_type: match id_token {
+                Some(token) => token.get_chatgpt_plan_type(),
+                None => auth.openai_api_key.as_ref().map(|_| "API key".to_string()),
+            },
+            last_used,
+            usage_limit: auth.current_usage_limit(now).cloned(),
+            last_usage_limit_at: auth.last_usage_limit_at(),
+            unexpected_response: auth.current_unexpected_response(now).cloned(),
+            usage_today: state.usage_since(today),
+            usage_this_week: state.usage_since(week_start),
+            is_pinned: pinned.as_ref() == Some(&path),
+            path,
+            metadata: auth.metadata,
+            is_next: false,
+        });
+    }
+
+    let selectable = |account: &AccountSummary| {
+        storage
+            .label_filter
+            .as_ref()
+            .is_none_or(|label| account.metadata.labels.contains(label))
+    };
+    let next = accounts
+        .iter()
+        .position(|account| account.is_pinned && selectable(account))
+        .or_else(|| {
+            accounts
+                .iter()
+                .enumerate()
+                .filter(|(_, account)| selectable(account) && account.retry_at().is_none())
+                .min_by_key(|(_, account)| account.last_usage_limit_at)
+                .map(|(index, _)| index)
+        })
+        .or_else(|| {
+            accounts
+                .iter()
+                .enumerate()
+                .filter(|(_, account)| selectable(account))
+                .min_by_key(|(_, account)| account.retry_at())
+                .map(|(index, _)| index)
+        });
+    if let Some(account) = next.and_then(|index| accounts.get_mut(index)) {
+        account.is_next = true;
+    }
+    Ok(accounts)
+}
+
+/// Pins `email` so every file-backed load uses `CODEX_HOME/auth/<email>.json`
+/// instead of rotating. Returns the pinned account file.
+pub fn pin_account(settings: &AuthStorageSettings, email: &str) -> std::io::Result<PathBuf> {
+    ensure_writable(settings)?;
+    let storage = FileAuthStorage::new(settings);
+    let path = storage.stored_account_path(email)?;
+    std::fs::write(storage.pin_marker(), format!("{email}\n"))?;
+    Ok(path)
+}
+
+/// Removes the pin set by [`pin_account`]. Returns whether one was set.
+pub fn unpin_account(settings: &AuthStorageSettings) -> std::io::Result<bool> {
+    ensure_writable(settings)?;
+    let storage = FileAuthStorage::new(settings);
+    match std::fs::remove_file(storage.pin_marker()) {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Clears recorded issues (usage limits and unexpected responses) from the
+/// accounts under `CODEX_HOME/auth`, or only from `email` when given, so
+/// rotation tries them again. Returns the emails whose issue was cleared.
+pub fn clear_account_issues(
+    settings: &AuthStorageSettings,
+    email: Option<&str>,
+) -> std::io::Result<Vec<String>> {
+    ensure_writable(settings)?;
+    let storage = FileAuthStorage::new(settings);
+    let paths = match email {
+        Some(email) => vec![storage.stored_account_path(email)?],
+        None => storage.candidate_paths()?,
+    };
+
+    let mut cleared = Vec::new();
+    for path in paths {
+        let mut auth = match storage.try_read_auth_json(&path) {
+            Ok(auth) => auth,
+            Err(err) if err.kind() == ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        if !auth
+            .account_state
+            .as_mut()
+            .is_some_and(AccountState::clear_issue)
+        {
+            continue;
+        }
+        // Keep the mtime so clearing does not reorder rotation.
+        let modified = std::fs::metadata(&path)?.modified()?;
+        storage.write_json(&path, &auth)?;
+        filetime::set_file_mtime(&path, FileTime::from_system_time(modified))?;
+        if let Some(account) = path.file_stem().and_then(OsStr::to_str) {
+            cleared.push(account.to_string());
+        }
+    }
+    Ok(cleared)
+}
+
+/// Adds `add` to and removes `remove` from the labels of `account` (an email
+/// or `apikey-<label>`), and records its organization and project when
+/// given. Returns the account's updated metadata.
+pub fn tag_account(
+    settings: &AuthStorageSettings,
+    account: &str,
+    add: &[String],
+    remove: &[String],
+    org_id: Option<String>,
+    project_id: Option<String>,
+) -> std::io::Result<AccountMetadata> {
+    ensure_writable(settings)?;
+    let storage = FileAuthStorage::new(settings);
+    let path = storage.stored_account_path(account)?;
+    let mut auth = storage.try_read_auth_json(&path)?;
+    let metadata = &mut auth.metadata;
+    metadata.labels.retain(|label| !remove.contains(label));
+    for label in add {
+        if !metadata.labels.contains(label) {
+            metadata.labels.push(label.clone());
+        }
+    }
+    if org_id.is_some() {
+        metadata.org_id = org_id;
+    }
+    if project_id.is_some() {
+        metadata.project_id = project_id;
+    }
+    // Keep the mtime so tagging does not reorder rotation.
+    let modified = std::fs::metadata(&path)?.modified()?;
+    storage.write_json(&path, &auth)?;
+    filetime::set_file_mtime(&path, FileTime::from_system_time(modified))?;
+    Ok(auth.metadata)
+}
+
+/// Whether `auth` may be selected under the `--account-label` filter.
+fn has_label(label_filter: Option<&str>, auth: &AuthDotJson) -> bool {
+    label_filter.is_none_or(|label| auth.metadata.labels.iter().any(|l| l == label))
 }
 
 enum CandidateOutcome {
     Available(AuthDotJson),
-    UsageLimited {
+    Blocked {
         auth: AuthDotJson,
-        limit: UsageLimitStatus,
+        retry_at: DateTime<Utc>,
     },
 }
 
+/// Marker in the accounts directory holding the email pinned by `codex auth use`.
+const PINNED_ACCOUNT_FILE: &str = "pinned";
+
+/// File name prefix of API-key accounts, e.g. `apikey-team-ci.json`.
+const API_KEY_ACCOUNT_PREFIX: &str = "apikey-";
+
+/// File name prefix `invalidate_active_account` gives dead accounts.
+const INVALID_ACCOUNT_PREFIX: &str = "invalid-";
+
+/// Accounts directory under `CODEX_HOME` unless `accounts_dir` moves it.
+const DEFAULT_ACCOUNTS_DIR: &str = "auth";
+
+/// One credential carried by `codex auth export`: a rotation account file
+/// under `CODEX_HOME/auth` when `account_file` is set, otherwise the primary
+/// credential (`auth.json` or the keyring entry).
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub(super) struct StoredCredential {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) account_file: Option<String>,
+    /// Last-used time of an account file in Unix milliseconds, so rotation
+    /// order survives the move.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) last_used_millis: Option<i64>,
+    pub(super) auth: AuthDotJson,
+}
+
+/// Collects the primary credential for `mode` plus every rotation account
+/// file, including their recorded account state.
+pub(super) fn stored_credentials(
+    settings: &AuthStorageSettings,
+    mode: AuthCredentialsStoreMode,
+    keyring_store: Arc<dyn KeyringStore>,
+) -> std::io::Result<Vec<StoredCredential>> {
+    let files = FileAuthStorage::new(settings);
+    let read_auth_file = || match files.try_read_auth_json(&get_auth_file(settings.codex_home())) {
+        Ok(auth) => Ok(Some(auth)),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    };
+    let keyring = KeyringAuthStorage::new(settings, keyring_store);
+    let keyring_accounts = match mode {
+        AuthCredentialsStoreMode::File => Vec::new(),
+        AuthCredentialsStoreMode::Keyring => keyring.account_credentials()?,
+        AuthCredentialsStoreMode::Auto => keyring.account_credentials().unwrap_or_else(|err| {
+            warn!("failed to load keyring accounts, exporting file storage only: {err}");
+            Vec::new()
+        }),
+    };
+    let primary = match mode {
+        AuthCredentialsStoreMode::File => read_auth_file()?,
+        AuthCredentialsStoreMode::Keyring => keyring.load_primary()?,
+        AuthCredentialsStoreMode::Auto => match keyring.load_primary() {
+            Ok(Some(auth)) => Some(auth),
+            Ok(None) => read_auth_file()?,
+            Err(err) => {
+                warn!("failed to load CLI auth from keyring, exporting file storage: {err}");
+                read_auth_file()?
+            }
+        },
+    };
+
+    let mut credentials: Vec<StoredCredential> = primary
+        .into_iter()
+        .map(|auth| StoredCredential {
+            account_file: None,
+            last_used_millis: None,
+            auth,
+        })
+        .collect();
+    for path in files.candidate_paths()? {
+        let auth = match files.try_read_auth_json(&path) {
+            Ok(auth) => auth,
+            Err(err) if err.kind() == ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        let last_used_millis = std::fs::metadata(&path)
+            .ok()
+            .and_then(|metadata| i64::try_from(modified_millis(&metadata)).ok());
+        credentials.push(StoredCredential {
+            account_file: path.file_name().and_then(OsStr::to_str).map(str::to_string),
+            last_used_millis,
+            auth,
+        });
+    }
+    credentials.extend(keyring_accounts);
+    Ok(credentials)
+}
+
+/// Writes credentials collected by [`stored_credentials`] to the store
+/// selected by `mode`: the primary credential and the rotation accounts go
+/// to `auth.json` and account files, the keyring, or the keyring with a file
+/// fallback. Existing entries with the same name are replaced.
+pub(super) fn restore_credentials(
+    settings: &AuthStorageSettings,
+    mode: AuthCredentialsStoreMode,
+    keyring_store: Arc<dyn KeyringStore>,
+    credentials: &[StoredCredential],
+) -> std::io::Result<()> {
+    ensure_writable(settings)?;
+    let files = FileAuthStorage::new(settings);
+    let (accounts, primary): (Vec<StoredCredential>, Vec<StoredCredential>) = credentials
+        .iter()
+        .cloned()
+        .partition(|credential| credential.account_file.is_some());
+    for account_file in accounts
+        .iter()
+        .filter_map(|credential| credential.account_file.as_deref())
+    {
+        let path = files.accounts_dir().join(account_file);
+        if account_file.contains(['/', '\\']) || !is_account_file(&path) {
+            return Err(std::io::Error::new(
+                ErrorKind::InvalidData,
+                format!("bundle contains an invalid account file name `{account_file}`"),
+            ));
+        }
+    }
+
+    match mode {
+        AuthCredentialsStoreMode::File => {
+            for credential in &primary {
+                files.write_fallback_auth(&credential.auth)?;
+            }
+            files.store_accounts(&accounts)
+        }
+        AuthCredentialsStoreMode::Keyring => {
+            let keyring = KeyringAuthStorage::new(settings, keyring_store);
+            for credential in &primary {
+                keyring.save(&credential.auth)?;
+            }
+            keyring.store_credentials(&accounts)
+        }
+        AuthCredentialsStoreMode::Auto => {
+            let auto = AutoAuthStorage::new(settings, keyring_store);
+            for credential in &primary {
+                auto.save(&credential.auth)?;
+            }
+            auto.store_accounts(&accounts)
+        }
+    }
+}
+
+/// Moves every credential into the store selected by `to` (`file` or
+/// `keyring`): `auth.json` or the primary keyring entry plus all rotation
+/// accounts. The copies are read back and compared before the originals are
+/// removed. Returns how many credentials were moved.
+pub fn migrate_credentials(
+    settings: &AuthStorageSettings,
+    to: AuthCredentialsStoreMode,
+) -> std::io::Result<usize> {
+    migrate_credentials_with_keyring_store(settings, to, Arc::new(DefaultKeyringStore))
+}
+
+fn migrate_credentials_with_keyring_store(
+    settings: &AuthStorageSettings,
+    to: AuthCredentialsStoreMode,
+    keyring_store: Arc<dyn KeyringStore>,
+) -> std::io::Result<usize> {
+    ensure_writable(settings)?;
+    let keyring = KeyringAuthStorage::new(settings, keyring_store.clone());
+    let read_files = || {
+        stored_credentials(
+            settings,
+            AuthCredentialsStoreMode::File,
+            keyring_store.clone(),
+        )
+    };
+    let credentials = match to {
+        AuthCredentialsStoreMode::File => keyring.credentials()?,
+        AuthCredentialsStoreMode::Keyring => read_files()?,
+        AuthCredentialsStoreMode::Auto => {
+            return Err(std::io::Error::new(
+                ErrorKind::InvalidInput,
+                "credentials can only be migrated to `file` or `keyring`",
+            ));
+        }
+    };
+    if credentials.is_empty() {
+        return Err(std::io::Error::new(
+            ErrorKind::NotFound,
+            "no stored credentials to migrate",
+        ));
+    }
+
+    let stored = if to == AuthCredentialsStoreMode::File {
+        restore_credentials(settings, to, keyring_store.clone(), &credentials)?;
+        read_files()?
+    } else {
+        keyring.store_credentials(&credentials)?;
+        keyring.credentials()?
+    };
+    let round_tripped = credentials.iter().all(|credential| {
+        stored.iter().any(|copy| {
+            copy.account_file == credential.account_file && copy.auth == credential.auth
+        })
+    });
+    if !round_tripped {
+        return Err(std::io::Error::new(
+            ErrorKind::InvalidData,
+            "migrated credentials did not read back unchanged; the originals were kept",
+        ));
+    }
+
+    if to == AuthCredentialsStoreMode::File {
+        keyring.delete_all()?;
+    } else {
+        for credential in &credentials {
+            match &credential.account_file {
+                Some(account_file) => {
+                    remove_file_if_exists(&settings.accounts_dir().join(account_file))?
+                }
+                None => {
+                    delete_file_if_exists(settings.codex_home())?;
+                }
+            }
+        }
+    }
+    Ok(credentials.len())
+}
+
+fn remove_file_if_exists(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Directory holding the rotation accounts: [`CODEX_ACCOUNTS_DIR_ENV_VAR`]
+/// when set, otherwise `configured` (`accounts_dir` in config.toml),
+/// otherwise `CODEX_HOME/auth`. Relative paths resolve against `CODEX_HOME`.
+pub(crate) fn resolve_accounts_dir(codex_home: &Path, configured: Option<&Path>) -> PathBuf {
+    let dir = read_env_var(CODEX_ACCOUNTS_DIR_ENV_VAR)
+        .map(PathBuf::from)
+        .or_else(|| configured.map(Path::to_path_buf))
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_ACCOUNTS_DIR));
+    codex_home.join(dir)
+}
+
+/// Where the built-in backends keep credentials: `auth.json` under
+/// `CODEX_HOME` and the rotation accounts in the accounts directory. Built
+/// from [`crate::config::Config::auth_storage_settings`] so the backends
+/// never read config.toml themselves.
+#[derive(Clone, Debug)]
+pub struct AuthStorageSettings {
+    codex_home: PathBuf,
+    accounts_dir: PathBuf,
+    read_only: bool,
+    account_label: Option<String>,
+    /// Shared by every backend built from these settings, so the fresh
+    /// backend `AuthManager::reload` builds still sees refreshed tokens and
+    /// skips invalidated accounts.
+    read_only_overlay: Arc<Mutex<ReadOnlyOverlay>>,
+}
+
+impl AuthStorageSettings {
+    /// Settings with the rotation accounts in `CODEX_HOME/auth`.
+    pub fn new(codex_home: PathBuf) -> Self {
+        Self {
+            accounts_dir: codex_home.join(DEFAULT_ACCOUNTS_DIR),
+            codex_home,
+            read_only: false,
+            account_label: None,
+            read_only_overlay: Arc::default(),
+        }
+    }
+
+    /// Moves the rotation accounts to `accounts_dir`, resolved against
+    /// `CODEX_HOME` when relative.
+    pub fn with_accounts_dir(mut self, accounts_dir: &Path) -> Self {
+        self.accounts_dir = self.codex_home.join(accounts_dir);
+        self
+    }
+
+    /// Makes storage read-only: nothing is written, and saves and
+    /// invalidations only last as long as these settings.
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Restricts account selection to accounts labeled `label`.
+    pub fn with_account_label(mut self, label: Option<String>) -> Self {
+        self.account_label = label;
+        self
+    }
+
+    pub fn codex_home(&self) -> &Path {
+        &self.codex_home
+    }
+
+    pub fn accounts_dir(&self) -> &Path {
+        &self.accounts_dir
+    }
+
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn account_label(&self) -> Option<&str> {
+        self.account_label.as_deref()
+    }
+}
+
+/// Whether credential storage is read-only: [`CODEX_AUTH_READ_ONLY_ENV_VAR`]
+/// when set, otherwise `configured` (`auth_read_only` in config.toml).
+pub(crate) fn resolve_auth_read_only(configured: Option<bool>) -> bool {
+    match read_env_var(CODEX_AUTH_READ_ONLY_ENV_VAR) {
+        Some(value) => value == "1" || value.eq_ignore_ascii_case("true"),
+        None => configured.unwrap_or(false),
+    }
+}
+
+/// Label account selection is restricted to: `configured` (`account_label`
+/// in config.toml or `codex --account-label`) when set, otherwise
+/// [`CODEX_ACCOUNT_LABEL_ENV_VAR`].
+pub(crate) fn resolve_account_label(configured: Option<String>) -> Option<String> {
+    configured.or_else(|| read_env_var(CODEX_ACCOUNT_LABEL_ENV_VAR))
+}
+
+/// Fails when credential storage is read-only, so commands that manage
+/// stored credentials report it instead of silently changing nothing.
+pub(super) fn ensure_writable(settings: &AuthStorageSettings) -> std::io::Result<()> {
+    if settings.read_only {
+        return Err(read_only_error());
+    }
+    Ok(())
+}
+
+fn read_only_error() -> std::io::Error {
+    std::io::Error::new(
+        ErrorKind::PermissionDenied,
+        format!(
+            "credential storage is read-only; unset {CODEX_AUTH_READ_ONLY_ENV_VAR} and `auth_read_only` to change it"
+        ),
+    )
+}
+
+/// Credentials saved and accounts invalidated while storage is read-only,
+/// keyed by file path or keyring entry.
+#[derive(Debug, Default)]
+struct ReadOnlyOverlay {
+    saved: HashMap<String, AuthDotJson>,
+    invalidated: HashSet<String>,
+}
+
+fn lock_read_only_overlay(overlay: &Mutex<ReadOnlyOverlay>) -> MutexGuard<'_, ReadOnlyOverlay> {
+    match overlay.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn overlay_key(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
 pub(super) fn get_auth_file(codex_home: &Path) -> PathBuf {
     codex_home.join("auth.json")
 }
@@ -163,29 +947,99 @@ pub(super) fn delete_file_if_exists(codex_home: &Path) -> std::io::Result<bool>
     }
 }
 
-pub(super) trait AuthStorageBackend: Debug + Send + Sync {
+/// Where CLI credentials live. The built-in backends cover files under
+/// CODEX_HOME and the OS keyring; embedders can implement this trait to keep
+/// credentials elsewhere (Vault, SSM, ...) and register it with
+/// `AuthManager::with_storage_backend`.
+pub trait AuthStorageBackend: Debug + Send + Sync {
+    /// Returns the credentials to use, or `None` when nothing is stored.
     fn load(&self) -> std::io::Result<Option<AuthDotJson>>;
+    /// Persists credentials, e.g. after login or a token refresh.
     fn save(&self, auth: &AuthDotJson) -> std::io::Result<()>;
+    /// Removes stored credentials. Returns whether anything was removed.
     fn delete(&self) -> std::io::Result<bool>;
+    /// Marks the account returned by the last `load` as unusable so the next
+    /// `load` picks another one. Returns the invalidated location, or `None`
+    /// for backends that only hold a single account.
     fn invalidate_active_account(&self) -> std::io::Result<Option<PathBuf>> {
         Ok(None)
     }
+    /// Name of the rotation account returned by the last `load` (its file
+    /// stem), or `None` when the credentials are not a rotation account.
+    fn active_account_label(&self) -> Option<String> {
+        None
+    }
 }
 
 #[derive(Clone, Debug)]
 pub(super) struct FileAuthStorage {
     codex_home: PathBuf,
+    accounts_dir: PathBuf,
+    label_filter: Option<String>,
+    read_only: bool,
+    read_only_overlay: Arc<Mutex<ReadOnlyOverlay>>,
     active_auth_file: Arc<Mutex<Option<PathBuf>>>,
 }
 
 impl FileAuthStorage {
-    pub(super) fn new(codex_home: PathBuf) -> Self {
+    pub(super) fn new(settings: &AuthStorageSettings) -> Self {
         Self {
-            codex_home,
+            codex_home: settings.codex_home.clone(),
+            accounts_dir: settings.accounts_dir.clone(),
+            label_filter: settings.account_label.clone(),
+            read_only: settings.read_only,
+            read_only_overlay: Arc::clone(&settings.read_only_overlay),
             active_auth_file: Arc::new(Mutex::new(None)),
         }
     }
 
+    fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Writes `auth` to `path`, or only remembers it for this process when
+    /// storage is read-only.
+    fn persist(&self, path: &Path, auth: &AuthDotJson) -> std::io::Result<()> {
+        if self.is_read_only() {
+            lock_read_only_overlay(&self.read_only_overlay)
+                .saved
+                .insert(overlay_key(path), auth.clone());
+            return Ok(());
+        }
+        self.write_json(path, auth)
+    }
+
+    #[cfg(test)]
+    fn with_label_filter(mut self, label: &str) -> Self {
+        self.label_filter = Some(label.to_string());
+        self
+    }
+
+    fn matches_label_filter(&self, auth: &AuthDotJson) -> bool {
+        has_label(self.label_filter.as_deref(), auth)
+    }
+
+    /// Warns when the label filter left nothing to load.
+    fn no_labeled_account(&self) -> Option<AuthDotJson> {
+        if let Some(label) = &self.label_filter {
+            warn!("no stored account is labeled {label}");
+        }
+        None
+    }
+
+    /// Account file for `account` (an email or `apikey-<label>`), which must
+    /// exist.
+    fn stored_account_path(&self, account: &str) -> std::io::Result<PathBuf> {
+        let path = self.accounts_dir().join(format!("{account}.json"));
+        if account.contains(['/', '\\']) || !is_account_file(&path) || !path.is_file() {
+            return Err(std::io::Error::new(
+                ErrorKind::NotFound,
+                format!("no stored account for {account} at {}", path.display()),
+            ));
+        }
+        Ok(path)
+    }
+
     fn lock_active_auth_file(&self) -> MutexGuard<'_, Option<PathBuf>> {
         match self.active_auth_file.lock() {
             Ok(guard) => guard,
@@ -205,27 +1059,113 @@ impl FileAuthStorage {
         *guard = Some(path);
     }
 
-    fn accounts_dir(&self) -> PathBuf {
-        self.codex_home.join("auth")
+    fn accounts_dir(&self) -> &Path {
+        &self.accounts_dir
     }
 
+    fn pin_marker(&self) -> PathBuf {
+        self.accounts_dir().join(PINNED_ACCOUNT_FILE)
+    }
+
+    /// Account file named by the pin marker, if an account is pinned.
+    fn pinned_path(&self) -> std::io::Result<Option<PathBuf>> {
+        match std::fs::read_to_string(self.pin_marker()) {
+            Ok(contents) => {
+                let email = contents.trim();
+                Ok((!email.is_empty()).then(|| self.accounts_dir().join(format!("{email}.json"))))
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Atomically replaces `path`: the JSON is written and fsynced to a
+    /// sibling temp file that is then renamed over `path`, so a crash leaves
+    /// either the old or the new credentials, never a truncated file.
     fn write_json(&self, path: &Path, auth: &AuthDotJson) -> std::io::Result<()> {
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
         let json_data = serde_json::to_string_pretty(auth)?;
+        let file_name = path
+            .file_name()
+            .and_then(OsStr::to_str)
+            .unwrap_or("auth.json");
+        let tmp_path = path.with_file_name(format!(".{file_name}.{}.tmp", std::process::id()));
         let mut options = OpenOptions::new();
         options.truncate(true).write(true).create(true);
         #[cfg(unix)]
         {
             options.mode(0o600);
         }
-        let mut file = options.open(path)?;
-        file.write_all(json_data.as_bytes())?;
-        file.flush()?;
+        let written = options.open(&tmp_path).and_then(|mut file| {
+            file.write_all(json_data.as_bytes())?;
+            file.sync_all()
+        });
+        if let Err(err) = written.and_then(|()| std::fs::rename(&tmp_path, path)) {
+            let _ = std::fs::remove_file(&tmp_path);
+            return Err(err);
+        }
+        #[cfg(unix)]
+        if let Some(parent) = path.parent()
+            && let Err(err) = File::open(parent).and_then(|dir| dir.sync_all())
+        {
+            warn!("failed to sync auth directory {}: {err}", parent.display());
+        }
         Ok(())
     }
 
+    /// Reads `path`, treating a missing file as `None`. A file that no longer
+    /// parses (e.g. truncated by a crash) is moved aside to
+    /// `<name>.corrupt-<timestamp>` and also reported as `None`, so one bad
+    /// file cannot block loading the other credentials.
+    fn read_or_quarantine(&self, path: &Path) -> std::io::Result<Option<AuthDotJson>> {
+        if self.is_read_only() {
+            let overlay = lock_read_only_overlay(&self.read_only_overlay);
+            let key = overlay_key(path);
+            if overlay.invalidated.contains(&key) {
+                return Ok(None);
+            }
+            if let Some(auth) = overlay.saved.get(&key) {
+                return Ok(Some(auth.clone()));
+            }
+        }
+        match self.try_read_auth_json(path) {
+            Ok(auth) => Ok(Some(auth)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    ErrorKind::InvalidData | ErrorKind::UnexpectedEof
+                ) =>
+            {
+                if self.is_read_only() {
+                    warn!(
+                        "auth file {} is unreadable ({err}); skipping it",
+                        path.display()
+                    );
+                    self.clear_active_if_matches(path);
+                    return Ok(None);
+                }
+                let file_name = path
+                    .file_name()
+                    .and_then(OsStr::to_str)
+                    .unwrap_or("auth.json");
+                let timestamp = Utc::now().format("%Y%m%dT%H%M%SZ");
+                let quarantine = path.with_file_name(format!("{file_name}.corrupt-{timestamp}"));
+                std::fs::rename(path, &quarantine)?;
+                warn!(
+                    "auth file {} is unreadable ({err}); moved it to {}",
+                    path.display(),
+                    quarantine.display()
+                );
+                self.clear_active_if_matches(path);
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     fn infer_account_file(&self, auth: &AuthDotJson) -> Option<PathBuf> {
         let email = auth.tokens.as_ref()?.id_token.email.as_ref()?;
         Some(self.accounts_dir().join(format!("{email}.json")))
@@ -233,12 +1173,35 @@ impl FileAuthStorage {
 
     fn write_fallback_auth(&self, auth: &AuthDotJson) -> std::io::Result<()> {
         let fallback = get_auth_file(&self.codex_home);
-        self.write_json(&fallback, auth)?;
+        self.persist(&fallback, auth)?;
         self.mark_file_used(&fallback);
         self.set_active_path(fallback);
         Ok(())
     }
 
+    /// Writes each rotation account to its account file, restoring the
+    /// recorded last-used time as the file's mtime.
+    fn store_accounts(&self, accounts: &[StoredCredential]) -> std::io::Result<()> {
+        for credential in accounts {
+            let Some(account_file) = &credential.account_file else {
+                continue;
+            };
+            let path = self.accounts_dir().join(account_file);
+            self.write_json(&path, &credential.auth)?;
+            if let Some(millis) = credential.last_used_millis {
+                let nanos = u32::try_from(millis.rem_euclid(1000) * 1_000_000).unwrap_or_default();
+                let mtime = FileTime::from_unix_time(millis.div_euclid(1000), nanos);
+                if let Err(err) = filetime::set_file_mtime(&path, mtime) {
+                    warn!(
+                        "failed to restore last-used time of {}: {err}",
+                        path.display()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn candidate_paths(&self) -> std::io::Result<Vec<PathBuf>> {
         let mut candidates: Vec<(u128, PathBuf)> = Vec::new();
         match std::fs::read_dir(self.accounts_dir()) {
@@ -263,7 +1226,7 @@ impl FileAuthStorage {
                             continue;
                         }
                     };
-                    if !file_type.is_file() || !is_email_auth_candidate(&path) {
+                    if !file_type.is_file() || !is_account_file(&path) {
                         continue;
                     }
 
@@ -287,6 +1250,9 @@ impl FileAuthStorage {
     }
 
     fn mark_file_used(&self, path: &Path) {
+        if self.is_read_only() {
+            return;
+        }
         if let Err(err) = filetime::set_file_mtime(path, FileTime::now()) {
             warn!(
                 "failed to update auth file timestamp for {}: {err}",
@@ -300,18 +1266,18 @@ impl FileAuthStorage {
         path: &Path,
         now: DateTime<Utc>,
     ) -> std::io::Result<Option<CandidateOutcome>> {
-        match self.try_read_auth_json(path) {
-            Ok(auth) => {
-                if let Some(limit) = auth.current_usage_limit(now).cloned() {
-                    return Ok(Some(CandidateOutcome::UsageLimited { auth, limit }));
+        match self.read_or_quarantine(path)? {
+            Some(auth) if !self.matches_label_filter(&auth) => Ok(None),
+            Some(auth) => {
+                if let Some(retry_at) = auth.current_issue(now).map(AccountIssue::next_retry_at) {
+                    return Ok(Some(CandidateOutcome::Blocked { auth, retry_at }));
                 }
                 Ok(Some(CandidateOutcome::Available(auth)))
             }
-            Err(err) if err.kind() == ErrorKind::NotFound => {
+            None => {
                 self.clear_active_if_matches(path);
                 Ok(None)
             }
-            Err(err) => Err(err),
         }
     }
 
@@ -327,7 +1293,26 @@ impl FileAuthStorage {
     }
 }
 
-fn is_email_auth_candidate(path: &Path) -> bool {
+/// Whether `path` names a file that file storage loads credentials from:
+/// `auth.json`, an account file, or the pin marker.
+pub(super) fn is_credential_file(path: &Path) -> bool {
+    path.file_name() == Some(OsStr::new("auth.json"))
+        || path.file_name() == Some(OsStr::new(PINNED_ACCOUNT_FILE))
+        || is_account_file(path)
+}
+
+/// File stem of `path` when it names a rotation account.
+fn account_label(path: &Path) -> Option<String> {
+    if !is_account_file(path) {
+        return None;
+    }
+    path.file_stem().and_then(OsStr::to_str).map(str::to_string)
+}
+
+/// Whether `path` names a rotation account: `<email>.json` for ChatGPT
+/// logins or `apikey-<label>.json` for API keys. Files moved aside by
+/// `invalidate_active_account` are not accounts.
+fn is_account_file(path: &Path) -> bool {
     if path.file_name() == Some(OsStr::new("auth.json")) {
         return false;
     }
@@ -336,7 +1321,13 @@ fn is_email_auth_candidate(path: &Path) -> bool {
     }
     path.file_stem()
         .and_then(OsStr::to_str)
-        .is_some_and(|stem| stem.contains('@'))
+        .is_some_and(|stem| {
+            !stem.starts_with(INVALID_ACCOUNT_PREFIX)
+                && (stem.contains('@')
+                    || stem
+                        .strip_prefix(API_KEY_ACCOUNT_PREFIX)
+                        .is_some_and(|label| !label.is_empty()))
+        })
 }
 
 fn modified_millis(metadata: &std::fs::Metadata) -> u128 {
@@ -353,17 +1344,52 @@ impl AuthStorageBackend for FileAuthStorage {
     fn load(&self) -> std::io::Result<Option<AuthDotJson>> {
         let now = Utc::now();
 
-        let mut ordered_paths: Vec<PathBuf> = Vec::new();
-        if let Some(active) = self.lock_active_auth_file().clone()
-            && is_email_auth_candidate(&active) {
-                ordered_paths.push(active);
+        if let Some(pinned) = self.pinned_path()? {
+            match self.read_or_quarantine(&pinned)? {
+                Some(auth) if !self.matches_label_filter(&auth) => {
+                    warn!(
+                        "pinned account {} is not labeled {}; ignoring the pin",
+                        pinned.display(),
+                        self.label_filter.as_deref().unwrap_or_default()
+                    );
+                }
+                Some(auth) => {
+                    if let Some(limit) = auth.current_usage_limit(now) {
+                        warn!(
+                            "pinned account {} is usage limited until {}; run `codex auth unpin` to rotate accounts",
+                            pinned.display(),
+                            limit.next_retry_at()
+                        );
+                    }
+                    self.set_active_path(pinned.clone());
+                    self.mark_file_used(&pinned);
+                    return Ok(Some(auth));
+                }
+                None => {
+                    warn!(
+                        "pinned account {} no longer exists; falling back to automatic rotation",
+                        pinned.display()
+                    );
+                }
             }
+        }
+
+        let active = self
+            .lock_active_auth_file()
+            .clone()
+            .filter(|active| is_account_file(active));
+        let mut ordered_paths: Vec<PathBuf> = active.iter().cloned().collect();
         for path in self.candidate_paths()? {
             if !ordered_paths.iter().any(|existing| existing == &path) {
                 ordered_paths.push(path);
             }
         }
 
+        // The active account stays picked while it is usable. Otherwise the
+        // least recently limited available account wins, so accounts that
+        // keep hitting their limits share the load; ties go to the least
+        // recently used one.
+        let mut available: Option<(DateTime<Utc>, PathBuf, AuthDotJson)> = None;
         let mut blocked: Option<(DateTime<Utc>, PathBuf, AuthDotJson)> = None;
 
         for path in ordered_paths {
@@ -373,14 +1399,23 @@ impl AuthStorageBackend for FileAuthStorage {
             };
 
             match outcome {
-                CandidateOutcome::Available(auth) => {
-                    self.set_active_path(path.clone());
-                    self.mark_file_used(&path);
-                    return Ok(Some(auth));
-                }
-                CandidateOutcome::UsageLimited { auth, limit } => {
+                CandidateOutcome::Available(auth) => match auth.last_usage_limit_at() {
+                    Some(limited_at) if active.as_ref() != Some(&path) => {
+                        if available
+                            .as_ref()
+                            .is_none_or(|(best_limited, _, _)| limited_at < *best_limited)
+                        {
+                            available = Some((limited_at, path.clone(), auth));
+                        }
+                    }
+                    _ => {
+                        self.set_active_path(path.clone());
+                        self.mark_file_used(&path);
+                        return Ok(Some(auth));
+                    }
+                },
+                CandidateOutcome::Blocked { auth, retry_at } => {
                     self.clear_active_if_matches(&path);
-                    let retry_at = limit.next_retry_at();
                     if blocked
                         .as_ref()
                         .is_none_or(|(best_retry, _, _)| retry_at < *best_retry)
@@ -391,6 +1426,12 @@ impl AuthStorageBackend for FileAuthStorage {
             }
         }
 
+        if let Some((_, path, auth)) = available {
+            self.set_active_path(path.clone());
+            self.mark_file_used(&path);
+            return Ok(Some(auth));
+        }
+
         if let Some((_, path, auth)) = blocked {
             self.set_active_path(path);
             return Ok(Some(auth));
@@ -398,17 +1439,17 @@ impl AuthStorageBackend for FileAuthStorage {
 
         let fallback = get_auth_file(&self.codex_home);
         match std::fs::metadata(&fallback) {
-            Ok(metadata) if metadata.is_file() => match self.try_read_auth_json(&fallback) {
-                Ok(auth) => {
+            Ok(metadata) if metadata.is_file() => match self.read_or_quarantine(&fallback)? {
+                Some(auth) if self.matches_label_filter(&auth) => {
                     self.set_active_path(fallback.clone());
                     self.mark_file_used(&fallback);
                     Ok(Some(auth))
                 }
-                Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
-                Err(err) => Err(err),
+                Some(_) => Ok(self.no_labeled_account()),
+                None => Ok(None),
             },
-            Ok(_) => Ok(None),
-            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Ok(_) => Ok(self.no_labeled_account()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(self.no_labeled_account()),
             Err(err) => Err(err),
         }
     }
@@ -422,20 +1463,19 @@ impl AuthStorageBackend for FileAuthStorage {
             .as_deref()
             .is_some_and(|path| path.file_name() == Some(OsStr::new("auth.json")));
 
-        if !active_is_fallback
-            && let Some(path) = self.infer_account_file(auth_dot_json) {
-                self.write_json(&path, auth_dot_json)?;
-                self.mark_file_used(&path);
-                self.set_active_path(path);
-                return Ok(());
-            }
+        if !active_is_fallback && let Some(path) = self.infer_account_file(auth_dot_json) {
+            self.persist(&path, auth_dot_json)?;
+            self.mark_file_used(&path);
+            self.set_active_path(path);
+            return Ok(());
+        }
 
         if let Some(path) = current_active {
             if active_is_fallback {
                 return self.write_fallback_auth(auth_dot_json);
             }
 
-            self.write_json(&path, auth_dot_json)?;
+            self.persist(&path, auth_dot_json)?;
             self.mark_file_used(&path);
             self.set_active_path(path);
             return Ok(());
@@ -445,6 +1485,9 @@ impl AuthStorageBackend for FileAuthStorage {
     }
 
     fn delete(&self) -> std::io::Result<bool> {
+        if self.is_read_only() {
+            return Err(read_only_error());
+        }
         let removed_active = {
             let mut guard = self.lock_active_auth_file();
             let active = guard.take();
@@ -476,6 +1519,14 @@ impl AuthStorageBackend for FileAuthStorage {
             return Ok(None);
         }
 
+        if self.is_read_only() {
+            lock_read_only_overlay(&self.read_only_overlay)
+                .invalidated
+                .insert(overlay_key(&path));
+            self.clear_active_if_matches(&path);
+            return Ok(Some(path));
+        }
+
         let original_name = path
             .file_name()
             .map(|name| name.to_string_lossy().into_owned())
@@ -486,10 +1537,12 @@ impl AuthStorageBackend for FileAuthStorage {
             None => return Ok(None),
         };
 
-        let mut invalid_path = parent.join(format!("invalid-{original_name}"));
+        let mut invalid_path = parent.join(format!("{INVALID_ACCOUNT_PREFIX}{original_name}"));
         if invalid_path.exists() {
             let timestamp = Utc::now().format("%Y%m%d%H%M%S");
-            invalid_path = parent.join(format!("invalid-{timestamp}-{original_name}"));
+            invalid_path = parent.join(format!(
+                "{INVALID_ACCOUNT_PREFIX}{timestamp}-{original_name}"
+            ));
         }
 
         match std::fs::rename(&path, &invalid_path) {
@@ -511,10 +1564,74 @@ impl AuthStorageBackend for FileAuthStorage {
 
         Ok(Some(invalid_path))
     }
+
+    fn active_account_label(&self) -> Option<String> {
+        self.lock_active_auth_file()
+            .as_deref()
+            .and_then(account_label)
+    }
+}
+
+/// File storage bound to a single account file, so `codex auth check` can
+/// refresh and invalidate the account it is probing instead of whichever one
+/// rotation would pick.
+#[derive(Debug)]
+pub(super) struct AccountFileStorage {
+    inner: FileAuthStorage,
+    path: PathBuf,
+}
+
+impl AccountFileStorage {
+    pub(super) fn new(settings: &AuthStorageSettings, path: PathBuf) -> Self {
+        Self {
+            inner: FileAuthStorage::new(settings),
+            path,
+        }
+    }
+}
+
+impl AuthStorageBackend for AccountFileStorage {
+    fn load(&self) -> std::io::Result<Option<AuthDotJson>> {
+        self.inner.read_or_quarantine(&self.path)
+    }
+
+    fn save(&self, auth: &AuthDotJson) -> std::io::Result<()> {
+        if self.inner.is_read_only() {
+            return self.inner.persist(&self.path, auth);
+        }
+        // Keep the mtime so probing does not reorder rotation.
+        let modified = std::fs::metadata(&self.path)?.modified()?;
+        self.inner.write_json(&self.path, auth)?;
+        filetime::set_file_mtime(&self.path, FileTime::from_system_time(modified))
+    }
+
+    fn delete(&self) -> std::io::Result<bool> {
+        if self.inner.is_read_only() {
+            return Err(read_only_error());
+        }
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn invalidate_active_account(&self) -> std::io::Result<Option<PathBuf>> {
+        self.inner.set_active_path(self.path.clone());
+        self.inner.invalidate_active_account()
+    }
+
+    fn active_account_label(&self) -> Option<String> {
+        account_label(&self.path)
+    }
 }
 
 const KEYRING_SERVICE: &str = "Codex Auth";
 
+/// Keyring entry (under the store key) listing the rotation accounts moved
+/// into the keyring by `codex auth migrate --to keyring`.
+const KEYRING_ACCOUNTS_INDEX: &str = "accounts";
+
 // turns codex_home path into a stable, short key string
 fn compute_store_key(codex_home: &Path) -> std::io::Result<String> {
     let canonical = codex_home
@@ -529,21 +1646,50 @@ fn compute_store_key(codex_home: &Path) -> std::io::Result<String> {
     Ok(format!("cli|{truncated}"))
 }
 
+/// One rotation account in the keyring index. Its credentials live in their
+/// own entry, named after the account file they came from.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+struct KeyringAccount {
+    account_file: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_used_millis: Option<i64>,
+}
+
 #[derive(Clone, Debug)]
 struct KeyringAuthStorage {
     codex_home: PathBuf,
     keyring_store: Arc<dyn KeyringStore>,
+    label_filter: Option<String>,
+    read_only: bool,
+    read_only_overlay: Arc<Mutex<ReadOnlyOverlay>>,
+    /// Account file name of the keyring account picked by the last load.
+    active_account: Arc<Mutex<Option<String>>>,
 }
 
 impl KeyringAuthStorage {
-    fn new(codex_home: PathBuf, keyring_store: Arc<dyn KeyringStore>) -> Self {
+    fn new(settings: &AuthStorageSettings, keyring_store: Arc<dyn KeyringStore>) -> Self {
         Self {
-            codex_home,
+            codex_home: settings.codex_home.clone(),
             keyring_store,
+            label_filter: settings.account_label.clone(),
+            read_only: settings.read_only,
+            read_only_overlay: Arc::clone(&settings.read_only_overlay),
+            active_account: Arc::new(Mutex::new(None)),
         }
     }
 
+    fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
     fn load_from_keyring(&self, key: &str) -> std::io::Result<Option<AuthDotJson>> {
+        if self.is_read_only()
+            && let Some(auth) = lock_read_only_overlay(&self.read_only_overlay)
+                .saved
+                .get(key)
+        {
+            return Ok(Some(auth.clone()));
+        }
         match self.keyring_store.load(KEYRING_SERVICE, key) {
             Ok(Some(serialized)) => serde_json::from_str(&serialized).map(Some).map_err(|err| {
                 std::io::Error::other(format!(
@@ -558,68 +1704,427 @@ impl KeyringAuthStorage {
         }
     }
 
-    fn save_to_keyring(&self, key: &str, value: &str) -> std::io::Result<()> {
-        match self.keyring_store.save(KEYRING_SERVICE, key, value) {
-            Ok(()) => Ok(()),
-            Err(error) => {
-                let message = format!(
-                    "failed to write OAuth tokens to keyring: {}",
-                    error.message()
-                );
-                warn!("{message}");
-                Err(std::io::Error::other(message))
+    fn save_to_keyring(&self, key: &str, value: &str) -> std::io::Result<()> {
+        match self.keyring_store.save(KEYRING_SERVICE, key, value) {
+            Ok(()) => Ok(()),
+            Err(error) => {
+                let message = format!(
+                    "failed to write OAuth tokens to keyring: {}",
+                    error.message()
+                );
+                warn!("{message}");
+                Err(std::io::Error::other(message))
+            }
+        }
+    }
+
+    fn delete_from_keyring(&self, key: &str) -> std::io::Result<bool> {
+        self.keyring_store
+            .delete(KEYRING_SERVICE, key)
+            .map_err(|err| {
+                std::io::Error::other(format!("failed to delete auth from keyring: {err}"))
+            })
+    }
+
+    fn account_key(&self, account_file: &str) -> std::io::Result<String> {
+        let key = compute_store_key(&self.codex_home)?;
+        Ok(format!("{key}|{account_file}"))
+    }
+
+    fn lock_active_account(&self) -> MutexGuard<'_, Option<String>> {
+        match self.active_account.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Keyring accounts, least recently used first.
+    fn load_account_index(&self) -> std::io::Result<Vec<KeyringAccount>> {
+        let key = self.account_key(KEYRING_ACCOUNTS_INDEX)?;
+        let mut index: Vec<KeyringAccount> = match self.keyring_store.load(KEYRING_SERVICE, &key) {
+            Ok(Some(serialized)) => serde_json::from_str(&serialized).map_err(|err| {
+                std::io::Error::other(format!(
+                    "failed to deserialize keyring account index: {err}"
+                ))
+            })?,
+            Ok(None) => Vec::new(),
+            Err(error) => {
+                return Err(std::io::Error::other(format!(
+                    "failed to load keyring account index: {}",
+                    error.message()
+                )));
+            }
+        };
+        index.sort_by_key(|account| account.last_used_millis);
+        Ok(index)
+    }
+
+    fn save_account_index(&self, index: &[KeyringAccount]) -> std::io::Result<()> {
+        let key = self.account_key(KEYRING_ACCOUNTS_INDEX)?;
+        if index.is_empty() {
+            return self.delete_from_keyring(&key).map(|_| ());
+        }
+        let serialized = serde_json::to_string(index).map_err(std::io::Error::other)?;
+        self.save_to_keyring(&key, &serialized)
+    }
+
+    /// Rotation accounts stored in the keyring, least recently used first.
+    fn account_credentials(&self) -> std::io::Result<Vec<StoredCredential>> {
+        let mut credentials = Vec::new();
+        for account in self.load_account_index()? {
+            let key = self.account_key(&account.account_file)?;
+            if let Some(auth) = self.load_from_keyring(&key)? {
+                credentials.push(StoredCredential {
+                    account_file: Some(account.account_file),
+                    last_used_millis: account.last_used_millis,
+                    auth,
+                });
+            }
+        }
+        Ok(credentials)
+    }
+
+    /// The primary keyring entry, ignoring any keyring accounts.
+    fn load_primary(&self) -> std::io::Result<Option<AuthDotJson>> {
+        self.load_from_keyring(&compute_store_key(&self.codex_home)?)
+    }
+
+    /// The primary keyring entry followed by every keyring account.
+    fn credentials(&self) -> std::io::Result<Vec<StoredCredential>> {
+        let mut credentials: Vec<StoredCredential> = self
+            .load_primary()?
+            .into_iter()
+            .map(|auth| StoredCredential {
+                account_file: None,
+                last_used_millis: None,
+                auth,
+            })
+            .collect();
+        credentials.extend(self.account_credentials()?);
+        Ok(credentials)
+    }
+
+    /// Writes `credentials` to the keyring: the primary credential to the
+    /// main entry, accounts to their own entries listed in the index.
+    /// Existing entries with the same name are replaced.
+    fn store_credentials(&self, credentials: &[StoredCredential]) -> std::io::Result<()> {
+        let mut index = self.load_account_index()?;
+        for credential in credentials {
+            let serialized =
+                serde_json::to_string(&credential.auth).map_err(std::io::Error::other)?;
+            let Some(account_file) = &credential.account_file else {
+                self.save_to_keyring(&compute_store_key(&self.codex_home)?, &serialized)?;
+                continue;
+            };
+            self.save_to_keyring(&self.account_key(account_file)?, &serialized)?;
+            index.retain(|account| &account.account_file != account_file);
+            index.push(KeyringAccount {
+                account_file: account_file.clone(),
+                last_used_millis: credential.last_used_millis,
+            });
+        }
+        self.save_account_index(&index)
+    }
+
+    /// Removes the primary entry and every keyring account.
+    fn delete_all(&self) -> std::io::Result<()> {
+        for account in self.load_account_index()? {
+            self.delete_from_keyring(&self.account_key(&account.account_file)?)?;
+        }
+        self.save_account_index(&[])?;
+        self.delete_from_keyring(&compute_store_key(&self.codex_home)?)?;
+        Ok(())
+    }
+
+    /// Picks a keyring account the way file storage rotates account files:
+    /// the active account while it is usable, otherwise the least recently
+    /// limited available one (least recently used on ties), otherwise the one
+    /// that becomes usable soonest.
+    fn load_account(&self) -> std::io::Result<Option<AuthDotJson>> {
+        let now = Utc::now();
+        let mut index = self.load_account_index()?;
+        let active = self.lock_active_account().clone();
+        if let Some(position) = index
+            .iter()
+            .position(|account| active.as_ref() == Some(&account.account_file))
+        {
+            let account = index.remove(position);
+            index.insert(0, account);
+        }
+
+        let mut picked: Option<(usize, AuthDotJson)> = None;
+        let mut available: Option<(DateTime<Utc>, usize, AuthDotJson)> = None;
+        let mut blocked: Option<(DateTime<Utc>, usize, AuthDotJson)> = None;
+        for (position, account) in index.iter().enumerate() {
+            let key = self.account_key(&account.account_file)?;
+            let Some(auth) = self.load_from_keyring(&key)? else {
+                continue;
+            };
+            if !has_label(self.label_filter.as_deref(), &auth) {
+                continue;
+            }
+            if let Some(retry_at) = auth.current_issue(now).map(AccountIssue::next_retry_at) {
+                if blocked
+                    .as_ref()
+                    .is_none_or(|(best_retry, _, _)| retry_at < *best_retry)
+                {
+                    blocked = Some((retry_at, position, auth));
+                }
+                continue;
+            }
+            match auth.last_usage_limit_at() {
+                Some(limited_at) if active.as_ref() != Some(&account.account_file) => {
+                    if available
+                        .as_ref()
+                        .is_none_or(|(best_limited, _, _)| limited_at < *best_limited)
+                    {
+                        available = Some((limited_at, position, auth));
+                    }
+                }
+                _ => {
+                    picked = Some((position, auth));
+                    break;
+                }
+            }
+        }
+
+        if let Some((position, auth)) =
+            picked.or_else(|| available.map(|(_, position, auth)| (position, auth)))
+        {
+            index[position].last_used_millis = Some(now.timestamp_millis());
+            let account_file = index[position].account_file.clone();
+            if !self.is_read_only()
+                && let Err(err) = self.save_account_index(&index)
+            {
+                warn!("failed to record use of keyring account {account_file}: {err}");
+            }
+            *self.lock_active_account() = Some(account_file);
+            return Ok(Some(auth));
+        }
+        Ok(blocked.map(|(_, position, auth)| {
+            *self.lock_active_account() = Some(index[position].account_file.clone());
+            auth
+        }))
+    }
+}
+
+impl AuthStorageBackend for KeyringAuthStorage {
+    fn load(&self) -> std::io::Result<Option<AuthDotJson>> {
+        if let Some(auth) = self.load_account()? {
+            return Ok(Some(auth));
+        }
+        self.lock_active_account().take();
+        let key = compute_store_key(&self.codex_home)?;
+        Ok(self
+            .load_from_keyring(&key)?
+            .filter(|auth| has_label(self.label_filter.as_deref(), auth)))
+    }
+
+    fn save(&self, auth: &AuthDotJson) -> std::io::Result<()> {
+        // Simpler error mapping per style: prefer method reference over closure
+        let serialized = serde_json::to_string(auth).map_err(std::io::Error::other)?;
+        let active_account = self.lock_active_account().clone();
+        let key = match &active_account {
+            Some(account_file) => self.account_key(account_file)?,
+            None => compute_store_key(&self.codex_home)?,
+        };
+        if self.is_read_only() {
+            lock_read_only_overlay(&self.read_only_overlay)
+                .saved
+                .insert(key, auth.clone());
+            return Ok(());
+        }
+        if active_account.is_some() {
+            return self.save_to_keyring(&key, &serialized);
+        }
+        self.save_to_keyring(&key, &serialized)?;
+        if let Err(err) = delete_file_if_exists(&self.codex_home) {
+            warn!("failed to remove CLI auth fallback file: {err}");
+        }
+        Ok(())
+    }
+
+    fn delete(&self) -> std::io::Result<bool> {
+        if self.is_read_only() {
+            return Err(read_only_error());
+        }
+        let mut account_removed = false;
+        let active_account = self.lock_active_account().take();
+        if let Some(account_file) = active_account {
+            account_removed = self.delete_from_keyring(&self.account_key(&account_file)?)?;
+            let mut index = self.load_account_index()?;
+            index.retain(|account| account.account_file != account_file);
+            self.save_account_index(&index)?;
+        }
+        let key = compute_store_key(&self.codex_home)?;
+        let keyring_removed = self.delete_from_keyring(&key)?;
+        let file_removed = delete_file_if_exists(&self.codex_home)?;
+        Ok(account_removed || keyring_removed || file_removed)
+    }
+
+    fn active_account_label(&self) -> Option<String> {
+        self.lock_active_account()
+            .as_deref()
+            .and_then(|account_file| account_label(Path::new(account_file)))
+    }
+}
+
+type EnvLookup = fn(&str) -> Option<String>;
+
+/// Tokens refreshed while running on environment credentials. Process-wide
+/// because the environment is, and `AuthManager::reload` builds a fresh
+/// backend that must not fall back to the already-rotated refresh token.
+static REFRESHED_ENV_AUTH: LazyLock<Arc<Mutex<Option<AuthDotJson>>>> =
+    LazyLock::new(|| Arc::new(Mutex::new(None)));
+
+/// Read-only credentials from `CODEX_ACCESS_TOKEN` + `CODEX_REFRESH_TOKEN`
+/// (ChatGPT) or `OPENAI_API_KEY`. Nothing is ever written to disk; refreshed
+/// tokens are only kept in memory.
+#[derive(Clone, Debug)]
+struct EnvAuthStorage {
+    lookup: EnvLookup,
+    refreshed: Arc<Mutex<Option<AuthDotJson>>>,
+}
+
+impl EnvAuthStorage {
+    fn new(lookup: EnvLookup, refreshed: Arc<Mutex<Option<AuthDotJson>>>) -> Self {
+        Self { lookup, refreshed }
+    }
+
+    fn from_process_env() -> Self {
+        Self::new(read_env_var, REFRESHED_ENV_AUTH.clone())
+    }
+
+    fn lock_refreshed(&self) -> MutexGuard<'_, Option<AuthDotJson>> {
+        match self.refreshed.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn env_auth(&self) -> std::io::Result<Option<AuthDotJson>> {
+        let access_token = (self.lookup)(CODEX_ACCESS_TOKEN_ENV_VAR);
+        let refresh_token = (self.lookup)(CODEX_REFRESH_TOKEN_ENV_VAR);
+        match (access_token, refresh_token) {
+            (Some(access_token), Some(refresh_token)) => {
+                // Access tokens are JWTs carrying the same account claims as
+                // the id token; without them the account is simply unnamed.
+                let id_token = parse_id_token(&access_token).unwrap_or_default();
+                Ok(Some(AuthDotJson {
+                    openai_api_key: None,
+                    tokens: Some(TokenData {
+                        account_id: id_token.chatgpt_account_id.clone(),
+                        id_token,
+                        access_token,
+                        refresh_token,
+                    }),
+                    last_refresh: Some(Utc::now()),
+                    account_state: None,
+                    metadata: AccountMetadata::default(),
+                }))
             }
+            (None, None) => Ok(
+                (self.lookup)(OPENAI_API_KEY_ENV_VAR).map(|api_key| AuthDotJson {
+                    openai_api_key: Some(api_key),
+                    tokens: None,
+                    last_refresh: None,
+                    account_state: None,
+                    metadata: AccountMetadata::default(),
+                }),
+            ),
+            _ => Err(std::io::Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "{CODEX_ACCESS_TOKEN_ENV_VAR} and {CODEX_REFRESH_TOKEN_ENV_VAR} must be set together"
+                ),
+            )),
         }
     }
 }
 
-impl AuthStorageBackend for KeyringAuthStorage {
+fn read_env_var(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+impl AuthStorageBackend for EnvAuthStorage {
     fn load(&self) -> std::io::Result<Option<AuthDotJson>> {
-        let key = compute_store_key(&self.codex_home)?;
-        self.load_from_keyring(&key)
+        let Some(auth) = self.env_auth()? else {
+            return Ok(None);
+        };
+        Ok(Some(self.lock_refreshed().clone().unwrap_or(auth)))
     }
 
     fn save(&self, auth: &AuthDotJson) -> std::io::Result<()> {
-        let key = compute_store_key(&self.codex_home)?;
-        // Simpler error mapping per style: prefer method reference over closure
-        let serialized = serde_json::to_string(auth).map_err(std::io::Error::other)?;
-        self.save_to_keyring(&key, &serialized)?;
-        if let Err(err) = delete_file_if_exists(&self.codex_home) {
-            warn!("failed to remove CLI auth fallback file: {err}");
+        if self.env_auth()?.is_none() {
+            return Err(std::io::Error::other(
+                "no credentials are set in the environment to update",
+            ));
         }
+        *self.lock_refreshed() = Some(auth.clone());
         Ok(())
     }
 
     fn delete(&self) -> std::io::Result<bool> {
-        let key = compute_store_key(&self.codex_home)?;
-        let keyring_removed = self
-            .keyring_store
-            .delete(KEYRING_SERVICE, &key)
-            .map_err(|err| {
-                std::io::Error::other(format!("failed to delete auth from keyring: {err}"))
-            })?;
-        let file_removed = delete_file_if_exists(&self.codex_home)?;
-        Ok(keyring_removed || file_removed)
+        // The variables themselves stay set; only in-memory refreshes go.
+        self.lock_refreshed().take();
+        Ok(false)
     }
 }
 
 #[derive(Clone, Debug)]
 struct AutoAuthStorage {
+    env_storage: Option<EnvAuthStorage>,
     keyring_storage: Arc<KeyringAuthStorage>,
     file_storage: Arc<FileAuthStorage>,
 }
 
 impl AutoAuthStorage {
-    fn new(codex_home: PathBuf, keyring_store: Arc<dyn KeyringStore>) -> Self {
+    fn new(settings: &AuthStorageSettings, keyring_store: Arc<dyn KeyringStore>) -> Self {
         Self {
-            keyring_storage: Arc::new(KeyringAuthStorage::new(codex_home.clone(), keyring_store)),
-            file_storage: Arc::new(FileAuthStorage::new(codex_home)),
+            env_storage: None,
+            keyring_storage: Arc::new(KeyringAuthStorage::new(settings, keyring_store)),
+            file_storage: Arc::new(FileAuthStorage::new(settings)),
+        }
+    }
+
+    fn with_env_storage(mut self, env_storage: EnvAuthStorage) -> Self {
+        self.env_storage = Some(env_storage);
+        self
+    }
+
+    fn env_is_active(&self) -> bool {
+        self.env_storage
+            .as_ref()
+            .is_some_and(|env| matches!(env.env_auth(), Ok(Some(_))))
+    }
+
+    /// Stores rotation accounts in the keyring, falling back to account
+    /// files when the keyring cannot be written.
+    fn store_accounts(&self, accounts: &[StoredCredential]) -> std::io::Result<()> {
+        match self.keyring_storage.store_credentials(accounts) {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                warn!("failed to save accounts to keyring, falling back to file storage: {err}");
+                self.file_storage.store_accounts(accounts)
+            }
         }
     }
 }
 
 impl AuthStorageBackend for AutoAuthStorage {
     fn load(&self) -> std::io::Result<Option<AuthDotJson>> {
+        if let Some(env_storage) = &self.env_storage {
+            match env_storage.load() {
+                Ok(Some(auth)) => return Ok(Some(auth)),
+                Ok(None) => {}
+                Err(err) => {
+                    warn!("ignoring CLI auth from the environment: {err}");
+                }
+            }
+        }
         match self.keyring_storage.load() {
             Ok(Some(auth)) => Ok(Some(auth)),
             Ok(None) => self.file_storage.load(),
@@ -631,6 +2136,11 @@ impl AuthStorageBackend for AutoAuthStorage {
     }
 
     fn save(&self, auth: &AuthDotJson) -> std::io::Result<()> {
+        if let Some(env_storage) = &self.env_storage
+            && self.env_is_active()
+        {
+            return env_storage.save(auth);
+        }
         match self.keyring_storage.save(auth) {
             Ok(()) => Ok(()),
             Err(err) => {
@@ -641,30 +2151,42 @@ impl AuthStorageBackend for AutoAuthStorage {
     }
 
     fn delete(&self) -> std::io::Result<bool> {
+        if let Some(env_storage) = &self.env_storage {
+            env_storage.delete()?;
+        }
         // Keyring storage will delete from disk as well
         self.keyring_storage.delete()
     }
+
+    fn active_account_label(&self) -> Option<String> {
+        self.keyring_storage
+            .active_account_label()
+            .or_else(|| self.file_storage.active_account_label())
+    }
 }
 
 pub(super) fn create_auth_storage(
-    codex_home: PathBuf,
+    settings: &AuthStorageSettings,
     mode: AuthCredentialsStoreMode,
 ) -> Arc<dyn AuthStorageBackend> {
     let keyring_store: Arc<dyn KeyringStore> = Arc::new(DefaultKeyringStore);
-    create_auth_storage_with_keyring_store(codex_home, mode, keyring_store)
+    create_auth_storage_with_keyring_store(settings, mode, keyring_store)
 }
 
 fn create_auth_storage_with_keyring_store(
-    codex_home: PathBuf,
+    settings: &AuthStorageSettings,
     mode: AuthCredentialsStoreMode,
     keyring_store: Arc<dyn KeyringStore>,
 ) -> Arc<dyn AuthStorageBackend> {
     match mode {
-        AuthCredentialsStoreMode::File => Arc::new(FileAuthStorage::new(codex_home)),
+        AuthCredentialsStoreMode::File => Arc::new(FileAuthStorage::new(settings)),
         AuthCredentialsStoreMode::Keyring => {
-            Arc::new(KeyringAuthStorage::new(codex_home, keyring_store))
+            Arc::new(KeyringAuthStorage::new(settings, keyring_store))
         }
-        AuthCredentialsStoreMode::Auto => Arc::new(AutoAuthStorage::new(codex_home, keyring_store)),
+        AuthCredentialsStoreMode::Auto => Arc::new(
+            AutoAuthStorage::new(settings, keyring_store)
+                .with_env_storage(EnvAuthStorage::from_process_env()),
+        ),
     }
 }
 
@@ -685,12 +2207,13 @@ mod tests {
     #[tokio::test]
     async fn file_storage_load_returns_auth_dot_json() -> anyhow::Result<()> {
         let codex_home = tempdir()?;
-        let storage = FileAuthStorage::new(codex_home.path().to_path_buf());
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
         let auth_dot_json = AuthDotJson {
             openai_api_key: Some("test-key".to_string()),
             tokens: None,
             last_refresh: Some(Utc::now()),
             account_state: None,
+            metadata: AccountMetadata::default(),
         };
 
         storage
@@ -705,12 +2228,13 @@ mod tests {
     #[tokio::test]
     async fn file_storage_save_persists_auth_dot_json() -> anyhow::Result<()> {
         let codex_home = tempdir()?;
-        let storage = FileAuthStorage::new(codex_home.path().to_path_buf());
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
         let auth_dot_json = AuthDotJson {
             openai_api_key: Some("test-key".to_string()),
             tokens: None,
             last_refresh: Some(Utc::now()),
             account_state: None,
+            metadata: AccountMetadata::default(),
         };
 
         let file = get_auth_file(codex_home.path());
@@ -725,10 +2249,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn file_storage_uses_configured_accounts_dir() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let shared = tempdir()?;
+        let team_dir = shared.path().join("team");
+        let settings = settings(codex_home.path()).with_accounts_dir(&team_dir);
+        assert_eq!(settings.accounts_dir(), team_dir);
+
+        let storage = FileAuthStorage::new(&settings);
+        storage.save(&auth_with_prefix("alice"))?;
+        assert!(team_dir.join("alice@example.com.json").is_file());
+        assert!(!codex_home.path().join("auth").exists());
+
+        let accounts = list_accounts(&settings)?;
+        let paths: Vec<PathBuf> = accounts.into_iter().map(|account| account.path).collect();
+        assert_eq!(paths, vec![team_dir.join("alice@example.com.json")]);
+        let fresh = FileAuthStorage::new(&settings);
+        assert_eq!(fresh.load()?, Some(auth_with_prefix("alice")));
+        Ok(())
+    }
+
     #[test]
     fn file_storage_invalidate_active_account_marks_file_invalid() -> anyhow::Result<()> {
         let codex_home = tempdir()?;
-        let storage = FileAuthStorage::new(codex_home.path().to_path_buf());
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
         let auth_dot_json = auth_with_prefix("alice");
 
         <FileAuthStorage as AuthStorageBackend>::save(&storage, &auth_dot_json)?;
@@ -775,17 +2320,150 @@ mod tests {
             tokens: None,
             last_refresh: None,
             account_state: None,
+            metadata: AccountMetadata::default(),
         };
-        let storage = create_auth_storage(dir.path().to_path_buf(), AuthCredentialsStoreMode::File);
+        let storage = create_auth_storage(&settings(dir.path()), AuthCredentialsStoreMode::File);
         storage.save(&auth_dot_json)?;
         assert!(dir.path().join("auth.json").exists());
-        let storage = FileAuthStorage::new(dir.path().to_path_buf());
+        let storage = FileAuthStorage::new(&settings(dir.path()));
         let removed = storage.delete()?;
         assert!(removed);
         assert!(!dir.path().join("auth.json").exists());
         Ok(())
     }
 
+    #[test]
+    fn label_filter_restricts_selection_to_tagged_accounts() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let auth_dir = codex_home.path().join("auth");
+        std::fs::create_dir_all(&auth_dir)?;
+        for (email, mtime) in [("alice", 0), ("bob", 5)] {
+            let path = auth_dir.join(format!("{email}@example.com.json"));
+            std::fs::write(
+                &path,
+                serde_json::to_string_pretty(&auth_with_prefix(email))?,
+            )?;
+            filetime::set_file_mtime(&path, FileTime::from_unix_time(mtime, 0))?;
+        }
+
+        tag_account(
+            &settings(codex_home.path()),
+            "bob@example.com",
+            &["prod".to_string(), "team-a".to_string()],
+            &[],
+            Some("org-123".to_string()),
+            None,
+        )?;
+        let metadata = tag_account(
+            &settings(codex_home.path()),
+            "bob@example.com",
+            &[],
+            &["team-a".to_string()],
+            None,
+            Some("proj-9".to_string()),
+        )?;
+        let expected = AccountMetadata {
+            labels: vec!["prod".to_string()],
+            org_id: Some("org-123".to_string()),
+            project_id: Some("proj-9".to_string()),
+        };
+        assert_eq!(metadata, expected);
+        let bob_path = auth_dir.join("bob@example.com.json");
+        assert_eq!(
+            FileTime::from_last_modification_time(&std::fs::metadata(&bob_path)?),
+            FileTime::from_unix_time(5, 0),
+            "tagging should not reorder rotation"
+        );
+        assert!(
+            tag_account(
+                &settings(codex_home.path()),
+                "carol@example.com",
+                &[],
+                &[],
+                None,
+                None
+            )
+            .is_err()
+        );
+
+        let mut bob = auth_with_prefix("bob");
+        bob.metadata = expected;
+        let prod = FileAuthStorage::new(&settings(codex_home.path())).with_label_filter("prod");
+        assert_eq!(prod.load()?, Some(bob.clone()));
+
+        // A pin on an account without the label is ignored.
+        pin_account(&settings(codex_home.path()), "alice@example.com")?;
+        let prod = FileAuthStorage::new(&settings(codex_home.path())).with_label_filter("prod");
+        assert_eq!(prod.load()?, Some(bob));
+        let unfiltered = FileAuthStorage::new(&settings(codex_home.path()));
+        assert_eq!(unfiltered.load()?, Some(auth_with_prefix("alice")));
+
+        let staging =
+            FileAuthStorage::new(&settings(codex_home.path())).with_label_filter("staging");
+        assert_eq!(staging.load()?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn file_storage_rotates_through_labeled_api_key_accounts() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let auth_dir = codex_home.path().join("auth");
+        std::fs::create_dir_all(&auth_dir)?;
+        let api_key_auth = |key: &str| AuthDotJson {
+            openai_api_key: Some(key.to_string()),
+            tokens: None,
+            last_refresh: None,
+            account_state: None,
+            metadata: AccountMetadata::default(),
+        };
+        for (name, auth, mtime) in [
+            ("invalid-bob@example.com.json", auth_with_prefix("bob"), 0),
+            ("apikey-.json", api_key_auth("sk-unlabeled"), 0),
+            ("apikey-team-ci.json", api_key_auth("sk-ci"), 1),
+            ("alice@example.com.json", auth_with_prefix("alice"), 5),
+        ] {
+            let path = auth_dir.join(name);
+            std::fs::write(&path, serde_json::to_string_pretty(&auth)?)?;
+            filetime::set_file_mtime(&path, FileTime::from_unix_time(mtime, 0))?;
+        }
+
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
+        assert_eq!(storage.load()?, Some(api_key_auth("sk-ci")));
+        assert_eq!(
+            storage.active_account_label().as_deref(),
+            Some("apikey-team-ci")
+        );
+
+        // A usage limit recorded on the API-key account moves rotation on.
+        let mut limited = api_key_auth("sk-ci");
+        limited
+            .account_state
+            .get_or_insert_default()
+            .record_issue(AccountIssue::UsageLimit(UsageLimitStatus {
+                plan_type: None,
+                resets_at: Some(Utc::now() + ChronoDuration::hours(1)),
+                recorded_at: Utc::now(),
+            }));
+        storage.save(&limited)?;
+        let fresh = FileAuthStorage::new(&settings(codex_home.path()));
+        assert_eq!(fresh.load()?, Some(auth_with_prefix("alice")));
+
+        let mut accounts: Vec<(String, Option<String>)> =
+            list_accounts(&settings(codex_home.path()))?
+                .into_iter()
+                .map(|account| (account.email, account.plan_type))
+                .collect();
+        accounts.sort();
+        assert_eq!(
+            accounts,
+            vec![
+                ("alice@example.com".to_string(), None),
+                ("apikey-team-ci".to_string(), Some("API key".to_string())),
+            ]
+        );
+        Ok(())
+    }
+
     #[test]
     fn file_storage_load_rotates_between_oldest_email_files() -> anyhow::Result<()> {
         let codex_home = tempdir()?;
@@ -806,7 +2484,7 @@ mod tests {
         filetime::set_file_mtime(&alice_path, FileTime::from_unix_time(1, 0))?;
         filetime::set_file_mtime(&bob_path, FileTime::from_unix_time(10, 0))?;
 
-        let storage = FileAuthStorage::new(codex_home.path().to_path_buf());
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
         let first = <FileAuthStorage as AuthStorageBackend>::load(&storage)?
             .expect("should load alice auth first");
         assert_eq!(first, alice_auth);
@@ -818,7 +2496,7 @@ mod tests {
             "alice mtime should update after use"
         );
 
-        let storage_second = FileAuthStorage::new(codex_home.path().to_path_buf());
+        let storage_second = FileAuthStorage::new(&settings(codex_home.path()));
         let second = <FileAuthStorage as AuthStorageBackend>::load(&storage_second)?
             .expect("should load bob auth second");
         assert_eq!(second, bob_auth);
@@ -858,7 +2536,7 @@ mod tests {
         filetime::set_file_mtime(&limited_path, FileTime::from_unix_time(1, 0))?;
         filetime::set_file_mtime(&available_path, FileTime::from_unix_time(5, 0))?;
 
-        let storage = FileAuthStorage::new(codex_home.path().to_path_buf());
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
         let loaded = storage
             .load()
             .context("load should skip limited account")?
@@ -872,6 +2550,54 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn file_storage_prefers_least_recently_limited_account() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let auth_dir = codex_home.path().join("auth");
+        std::fs::create_dir_all(&auth_dir)?;
+        let now = Utc::now();
+        let limited_hours_ago = |email: &str, hours: i64| {
+            let mut auth = auth_with_prefix(email);
+            let state = auth.account_state.get_or_insert_default();
+            state.record_issue(AccountIssue::UsageLimit(UsageLimitStatus {
+                plan_type: None,
+                resets_at: Some(now - ChronoDuration::hours(1)),
+                recorded_at: now - ChronoDuration::hours(hours),
+            }));
+            // The limit stays on record after its issue is cleared.
+            state.clear_issue();
+            auth
+        };
+        for (email, auth, mtime) in [
+            ("alice", limited_hours_ago("alice", 2), 0),
+            ("bob", limited_hours_ago("bob", 10), 1),
+            ("carol", auth_with_prefix("carol"), 2),
+        ] {
+            let path = auth_dir.join(format!("{email}@example.com.json"));
+            std::fs::write(&path, serde_json::to_string_pretty(&auth)?)?;
+            filetime::set_file_mtime(&path, FileTime::from_unix_time(mtime, 0))?;
+        }
+
+        // An account that never hit its limit wins over older files.
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
+        assert_eq!(storage.load()?, Some(auth_with_prefix("carol")));
+
+        // Among limited accounts, the one limited longest ago wins even
+        // though alice was used less recently.
+        std::fs::remove_file(auth_dir.join("carol@example.com.json"))?;
+        let next = list_accounts(&settings(codex_home.path()))?
+            .into_iter()
+            .find(|account| account.is_next)
+            .map(|account| account.email);
+        assert_eq!(next.as_deref(), Some("bob@example.com"));
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
+        assert_eq!(storage.load()?, Some(limited_hours_ago("bob", 10)));
+
+        // The active account sticks while it stays usable.
+        assert_eq!(storage.load()?, Some(limited_hours_ago("bob", 10)));
+        Ok(())
+    }
+
     #[test]
     fn file_storage_load_returns_limited_when_only_blocked_accounts() -> anyhow::Result<()> {
         let codex_home = tempdir()?;
@@ -891,7 +2617,7 @@ mod tests {
             serde_json::to_string_pretty(&limited_auth).context("serialize limited auth")?,
         )?;
 
-        let storage = FileAuthStorage::new(codex_home.path().to_path_buf());
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
         let loaded = storage
             .load()
             .context("load should fall back to limited auth when all blocked")?
@@ -905,6 +2631,331 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn list_accounts_reports_rotation_order_and_next_account() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let mut limited_auth = auth_with_prefix("alice");
+        let mut limited_state = AccountState::default();
+        let resets_at = Utc::now() + chrono::Duration::hours(1);
+        limited_state.record_issue(AccountIssue::UsageLimit(UsageLimitStatus {
+            plan_type: None,
+            resets_at: Some(resets_at),
+            recorded_at: Utc::now(),
+        }));
+        limited_auth.account_state = Some(limited_state);
+        let auth_dir = codex_home.path().join("auth");
+        std::fs::create_dir_all(&auth_dir)?;
+        for (email, auth, mtime) in [
+            ("alice@example.com", &limited_auth, 1),
+            ("bob@example.com", &auth_with_prefix("bob"), 5),
+            ("carol@example.com", &auth_with_prefix("carol"), 9),
+        ] {
+            let path = auth_dir.join(format!("{email}.json"));
+            std::fs::write(&path, serde_json::to_string_pretty(auth)?)?;
+            filetime::set_file_mtime(&path, FileTime::from_unix_time(mtime, 0))?;
+        }
+
+        let accounts = list_accounts(&settings(codex_home.path()))?;
+        let summary: Vec<(&str, bool, Option<DateTime<Utc>>)> = accounts
+            .iter()
+            .map(|account| {
+                (
+                    account.email.as_str(),
+                    account.is_next,
+                    account
+                        .usage_limit
+                        .as_ref()
+                        .and_then(|limit| limit.resets_at),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("alice@example.com", false, Some(resets_at)),
+                ("bob@example.com", true, None),
+                ("carol@example.com", false, None),
+            ]
+        );
+        assert_eq!(accounts[1].last_used, DateTime::<Utc>::from_timestamp(5, 0));
+        assert_eq!(accounts_limited_until(&accounts), None);
+
+        let later = resets_at + chrono::Duration::hours(2);
+        let all_limited: Vec<AccountSummary> = accounts
+            .iter()
+            .map(|account| AccountSummary {
+                usage_limit: Some(account.usage_limit.clone().unwrap_or(UsageLimitStatus {
+                    plan_type: None,
+                    resets_at: Some(later),
+                    recorded_at: Utc::now(),
+                })),
+                ..account.clone()
+            })
+            .collect();
+        assert_eq!(accounts_limited_until(&all_limited), Some(resets_at));
+        assert_eq!(all_limited[2].retry_at(), Some(later));
+        Ok(())
+    }
+
+    #[test]
+    fn account_state_rolls_token_usage_into_daily_buckets() -> anyhow::Result<()> {
+        let usage = TokenUsage {
+            input_tokens: 100,
+            cached_input_tokens: 40,
+            output_tokens: 20,
+            reasoning_output_tokens: 5,
+            total_tokens: 120,
+        };
+        // A Wednesday.
+        let now = DateTime::parse_from_rfc3339("2025-06-11T12:00:00Z")?.with_timezone(&Utc);
+        let mut state = AccountState::default();
+        state.record_token_usage(&usage, now - chrono::Duration::days(8));
+        state.record_token_usage(&usage, now - chrono::Duration::days(2));
+        state.record_token_usage(&usage, now);
+        state.record_token_usage(&usage, now + chrono::Duration::hours(1));
+
+        let today = now.date_naive();
+        assert_eq!(
+            state.usage_since(today),
+            AccountTokenUsage {
+                input_tokens: 200,
+                cached_input_tokens: 80,
+                output_tokens: 40,
+                reasoning_output_tokens: 10,
+                total_tokens: 240,
+            }
+        );
+        let monday = today - chrono::Duration::days(2);
+        assert_eq!(state.usage_since(monday).total_tokens, 360);
+        assert_eq!(
+            state
+                .usage_since(today - chrono::Duration::days(30))
+                .total_tokens,
+            360,
+            "usage older than a week is dropped"
+        );
+
+        let codex_home = tempdir()?;
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
+        let mut auth = auth_with_prefix("alice");
+        let mut recent = AccountState::default();
+        recent.record_token_usage(&usage, Utc::now());
+        auth.account_state = Some(recent);
+        storage.write_json(
+            &storage.accounts_dir().join("alice@example.com.json"),
+            &auth,
+        )?;
+        let accounts = list_accounts(&settings(codex_home.path()))?;
+        assert_eq!(accounts[0].usage_today.total_tokens, 120);
+        assert_eq!(accounts[0].usage_this_week.total_tokens, 120);
+        Ok(())
+    }
+
+    #[test]
+    fn pinned_account_wins_over_rotation_until_unpinned() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let alice_auth = auth_with_prefix("alice");
+        let mut bob_auth = auth_with_prefix("bob");
+        let mut limited_state = AccountState::default();
+        limited_state.record_issue(AccountIssue::UsageLimit(UsageLimitStatus {
+            plan_type: None,
+            resets_at: Some(Utc::now() + chrono::Duration::hours(1)),
+            recorded_at: Utc::now(),
+        }));
+        bob_auth.account_state = Some(limited_state);
+        let auth_dir = codex_home.path().join("auth");
+        std::fs::create_dir_all(&auth_dir)?;
+        let alice_path = auth_dir.join("alice@example.com.json");
+        let bob_path = auth_dir.join("bob@example.com.json");
+        std::fs::write(&alice_path, serde_json::to_string_pretty(&alice_auth)?)?;
+        std::fs::write(&bob_path, serde_json::to_string_pretty(&bob_auth)?)?;
+        filetime::set_file_mtime(&alice_path, FileTime::from_unix_time(1, 0))?;
+        filetime::set_file_mtime(&bob_path, FileTime::from_unix_time(5, 0))?;
+
+        assert!(pin_account(&settings(codex_home.path()), "carol@example.com").is_err());
+        assert_eq!(
+            pin_account(&settings(codex_home.path()), "bob@example.com")?,
+            bob_path
+        );
+        let accounts = list_accounts(&settings(codex_home.path()))?;
+        assert_eq!(
+            accounts
+                .iter()
+                .map(|account| (account.email.as_str(), account.is_pinned, account.is_next))
+                .collect::<Vec<_>>(),
+            vec![
+                ("alice@example.com", false, false),
+                ("bob@example.com", true, true),
+            ]
+        );
+
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
+        assert_eq!(storage.load()?, Some(bob_auth));
+
+        assert!(unpin_account(&settings(codex_home.path()))?);
+        assert!(!unpin_account(&settings(codex_home.path()))?);
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
+        assert_eq!(storage.load()?, Some(alice_auth));
+        Ok(())
+    }
+
+    #[test]
+    fn unexpected_response_blocks_rotation_until_cleared() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let mut alice_auth = auth_with_prefix("alice");
+        let bob_auth = auth_with_prefix("bob");
+        let recorded_at = Utc::now();
+        let mut blocked_state = AccountState::default();
+        blocked_state.record_issue(AccountIssue::UnexpectedResponse(UnexpectedResponseStatus {
+            recorded_at,
+            status: 403,
+            request_id: None,
+            body: String::new(),
+            blocked_until: Some(cooldown_end(recorded_at, Duration::from_secs(600))),
+        }));
+        alice_auth.account_state = Some(blocked_state);
+        let auth_dir = codex_home.path().join("auth");
+        std::fs::create_dir_all(&auth_dir)?;
+        let alice_path = auth_dir.join("alice@example.com.json");
+        let bob_path = auth_dir.join("bob@example.com.json");
+        std::fs::write(&alice_path, serde_json::to_string_pretty(&alice_auth)?)?;
+        std::fs::write(&bob_path, serde_json::to_string_pretty(&bob_auth)?)?;
+        filetime::set_file_mtime(&alice_path, FileTime::from_unix_time(1, 0))?;
+        filetime::set_file_mtime(&bob_path, FileTime::from_unix_time(5, 0))?;
+
+        let accounts = list_accounts(&settings(codex_home.path()))?;
+        assert_eq!(
+            accounts[0].retry_at(),
+            Some(recorded_at + chrono::Duration::minutes(10))
+        );
+        assert!(accounts[1].is_next);
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
+        assert_eq!(storage.load()?, Some(bob_auth));
+        assert!(alice_auth.is_available(recorded_at + chrono::Duration::minutes(11)));
+
+        assert!(
+            clear_account_issues(&settings(codex_home.path()), Some("carol@example.com")).is_err()
+        );
+        assert_eq!(
+            clear_account_issues(&settings(codex_home.path()), None)?,
+            vec!["alice@example.com".to_string()]
+        );
+        assert_eq!(
+            FileTime::from_last_modification_time(&std::fs::metadata(&alice_path)?),
+            FileTime::from_unix_time(1, 0)
+        );
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
+        assert_eq!(
+            storage
+                .load()?
+                .and_then(|auth| auth.current_issue(Utc::now()).cloned()),
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn read_only_file_storage_never_writes() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let auth_dir = codex_home.path().join("auth");
+        std::fs::create_dir_all(&auth_dir)?;
+        for (email, mtime) in [("alice", 0), ("bob", 5)] {
+            let path = auth_dir.join(format!("{email}@example.com.json"));
+            std::fs::write(
+                &path,
+                serde_json::to_string_pretty(&auth_with_prefix(email))?,
+            )?;
+            filetime::set_file_mtime(&path, FileTime::from_unix_time(mtime, 0))?;
+        }
+        let corrupt = auth_dir.join("carol@example.com.json");
+        std::fs::write(&corrupt, "{")?;
+        filetime::set_file_mtime(&corrupt, FileTime::from_unix_time(1, 0))?;
+        let snapshot = || -> anyhow::Result<Vec<(String, String, FileTime)>> {
+            let mut files = Vec::new();
+            for entry in std::fs::read_dir(&auth_dir)? {
+                let path = entry?.path();
+                files.push((
+                    path.display().to_string(),
+                    std::fs::read_to_string(&path)?,
+                    FileTime::from_last_modification_time(&std::fs::metadata(&path)?),
+                ));
+            }
+            files.sort();
+            Ok(files)
+        };
+        let before = snapshot()?;
+        let read_only_settings = settings(codex_home.path()).with_read_only(true);
+        let read_only = || FileAuthStorage::new(&read_only_settings);
+
+        // The corrupt file is skipped instead of quarantined.
+        let storage = read_only();
+        assert_eq!(storage.load()?, Some(auth_with_prefix("alice")));
+
+        // Saves only last for this process, so rotation still moves on.
+        let mut limited = auth_with_prefix("alice");
+        limited
+            .account_state
+            .get_or_insert_default()
+            .record_issue(AccountIssue::UsageLimit(UsageLimitStatus {
+                plan_type: None,
+                resets_at: Some(Utc::now() + ChronoDuration::hours(1)),
+                recorded_at: Utc::now(),
+            }));
+        storage.save(&limited)?;
+        let storage = read_only();
+        assert_eq!(storage.load()?, Some(auth_with_prefix("bob")));
+
+        // Invalidation skips the account instead of renaming its file.
+        assert_eq!(
+            storage.invalidate_active_account()?,
+            Some(auth_dir.join("bob@example.com.json"))
+        );
+        assert_eq!(read_only().load()?, Some(limited));
+
+        // Other settings, e.g. another AuthManager's, keep their own overlay.
+        let other = FileAuthStorage::new(&settings(codex_home.path()).with_read_only(true));
+        assert_eq!(other.load()?, Some(auth_with_prefix("alice")));
+
+        assert_eq!(
+            storage.delete().map_err(|err| err.kind()).err(),
+            Some(ErrorKind::PermissionDenied)
+        );
+        assert!(!get_auth_file(codex_home.path()).exists());
+        assert_eq!(snapshot()?, before);
+        Ok(())
+    }
+
+    #[test]
+    fn file_storage_quarantines_corrupt_account_files() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
+        let alice_path = storage.accounts_dir().join("alice@example.com.json");
+        let bob_path = storage.accounts_dir().join("bob@example.com.json");
+        let bob_auth = auth_with_prefix("bob");
+        storage.write_json(&alice_path, &auth_with_prefix("alice"))?;
+        storage.write_json(&bob_path, &bob_auth)?;
+        let leftovers: Vec<_> = std::fs::read_dir(storage.accounts_dir())?
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+
+        // Simulate a crash that left alice's file truncated.
+        std::fs::write(&alice_path, "{\"OPENAI_API_KEY\": \"sk-al")?;
+        filetime::set_file_mtime(&alice_path, FileTime::from_unix_time(1, 0))?;
+        filetime::set_file_mtime(&bob_path, FileTime::from_unix_time(5, 0))?;
+
+        assert_eq!(storage.load()?, Some(bob_auth));
+        assert!(!alice_path.exists());
+        let quarantined: Vec<String> = std::fs::read_dir(storage.accounts_dir())?
+            .filter_map(Result::ok)
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| name.starts_with("alice@example.com.json.corrupt-"))
+            .collect();
+        assert_eq!(quarantined.len(), 1);
+        Ok(())
+    }
+
     #[test]
     fn file_storage_save_writes_to_active_email_file() -> anyhow::Result<()> {
         let codex_home = tempdir()?;
@@ -917,7 +2968,7 @@ mod tests {
             serde_json::to_string_pretty(&alice_auth).context("serialize alice auth")?,
         )?;
 
-        let storage = FileAuthStorage::new(codex_home.path().to_path_buf());
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
         let loaded = <FileAuthStorage as AuthStorageBackend>::load(&storage)?
             .expect("should load alice auth");
         assert_eq!(loaded, alice_auth);
@@ -952,7 +3003,7 @@ mod tests {
             serde_json::to_string_pretty(&alice_auth).context("serialize alice auth")?,
         )?;
 
-        let storage = FileAuthStorage::new(codex_home.path().to_path_buf());
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
         let loaded = <FileAuthStorage as AuthStorageBackend>::load(&storage)?
             .expect("should load alice auth first");
         assert_eq!(loaded, alice_auth);
@@ -988,7 +3039,7 @@ mod tests {
             serde_json::to_string_pretty(&alice_auth).context("serialize alice auth")?,
         )?;
 
-        let storage = FileAuthStorage::new(codex_home.path().to_path_buf());
+        let storage = FileAuthStorage::new(&settings(codex_home.path()));
         let loaded = <FileAuthStorage as AuthStorageBackend>::load(&storage)?
             .expect("should load alice auth first");
         assert_eq!(loaded, alice_auth);
@@ -1094,6 +3145,10 @@ mod tests {
         crate::token_data::parse_id_token(&fake_jwt).expect("fake JWT should parse")
     }
 
+    fn settings(codex_home: &Path) -> AuthStorageSettings {
+        AuthStorageSettings::new(codex_home.to_path_buf())
+    }
+
     fn auth_with_prefix(prefix: &str) -> AuthDotJson {
         AuthDotJson {
             openai_api_key: Some(format!("{prefix}-api-key")),
@@ -1105,6 +3160,7 @@ mod tests {
             }),
             last_refresh: None,
             account_state: None,
+            metadata: AccountMetadata::default(),
         }
     }
 
@@ -1112,15 +3168,14 @@ mod tests {
     fn keyring_auth_storage_load_returns_deserialized_auth() -> anyhow::Result<()> {
         let codex_home = tempdir()?;
         let mock_keyring = MockKeyringStore::default();
-        let storage = KeyringAuthStorage::new(
-            codex_home.path().to_path_buf(),
-            Arc::new(mock_keyring.clone()),
-        );
+        let storage =
+            KeyringAuthStorage::new(&settings(codex_home.path()), Arc::new(mock_keyring.clone()));
         let expected = AuthDotJson {
             openai_api_key: Some("sk-test".to_string()),
             tokens: None,
             last_refresh: None,
             account_state: None,
+            metadata: AccountMetadata::default(),
         };
         seed_keyring_with_auth(
             &mock_keyring,
@@ -1133,6 +3188,98 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn migrate_credentials_moves_accounts_between_files_and_keyring() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let auth_dir = codex_home.path().join("auth");
+        std::fs::create_dir_all(&auth_dir)?;
+        let primary = AuthDotJson {
+            openai_api_key: Some("sk-primary".to_string()),
+            tokens: None,
+            last_refresh: None,
+            account_state: None,
+            metadata: AccountMetadata::default(),
+        };
+        std::fs::write(
+            get_auth_file(codex_home.path()),
+            serde_json::to_string(&primary)?,
+        )?;
+        for (email, mtime) in [("alice", 1), ("bob", 2)] {
+            let path = auth_dir.join(format!("{email}@example.com.json"));
+            std::fs::write(&path, serde_json::to_string(&auth_with_prefix(email))?)?;
+            filetime::set_file_mtime(&path, FileTime::from_unix_time(mtime, 0))?;
+        }
+        let mock_keyring = MockKeyringStore::default();
+        let keyring_store: Arc<dyn KeyringStore> = Arc::new(mock_keyring.clone());
+
+        let migrated = migrate_credentials_with_keyring_store(
+            &settings(codex_home.path()),
+            AuthCredentialsStoreMode::Keyring,
+            keyring_store.clone(),
+        )?;
+        assert_eq!(migrated, 3);
+        assert!(!get_auth_file(codex_home.path()).exists());
+        assert!(!auth_dir.join("alice@example.com.json").exists());
+        assert!(!auth_dir.join("bob@example.com.json").exists());
+
+        // Keyring storage rotates through the migrated accounts and sticks
+        // with the active one.
+        let storage = KeyringAuthStorage::new(&settings(codex_home.path()), keyring_store.clone());
+        assert_eq!(storage.load()?, Some(auth_with_prefix("alice")));
+        assert_eq!(
+            storage.active_account_label().as_deref(),
+            Some("alice@example.com")
+        );
+        assert_eq!(storage.load()?, Some(auth_with_prefix("alice")));
+        let fresh = KeyringAuthStorage::new(&settings(codex_home.path()), keyring_store.clone());
+        assert_eq!(fresh.load()?, Some(auth_with_prefix("bob")));
+
+        let migrated = migrate_credentials_with_keyring_store(
+            &settings(codex_home.path()),
+            AuthCredentialsStoreMode::File,
+            keyring_store.clone(),
+        )?;
+        assert_eq!(migrated, 3);
+        let key = compute_store_key(codex_home.path())?;
+        assert!(!mock_keyring.contains(&key));
+        assert!(!mock_keyring.contains(&format!("{key}|alice@example.com.json")));
+        assert!(!mock_keyring.contains(&format!("{key}|{KEYRING_ACCOUNTS_INDEX}")));
+        let mut restored: Vec<(Option<String>, AuthDotJson)> = stored_credentials(
+            &settings(codex_home.path()),
+            AuthCredentialsStoreMode::File,
+            keyring_store.clone(),
+        )?
+        .into_iter()
+        .map(|credential| (credential.account_file, credential.auth))
+        .collect();
+        restored.sort_by(|(a, _), (b, _)| a.cmp(b));
+        assert_eq!(
+            restored,
+            vec![
+                (None, primary),
+                (
+                    Some("alice@example.com.json".to_string()),
+                    auth_with_prefix("alice")
+                ),
+                (
+                    Some("bob@example.com.json".to_string()),
+                    auth_with_prefix("bob")
+                ),
+            ]
+        );
+
+        let auto = migrate_credentials_with_keyring_store(
+            &settings(codex_home.path()),
+            AuthCredentialsStoreMode::Auto,
+            keyring_store,
+        );
+        assert_eq!(
+            auto.map_err(|err| err.kind()).err(),
+            Some(ErrorKind::InvalidInput)
+        );
+        Ok(())
+    }
+
     #[test]
     fn keyring_auth_storage_compute_store_key_for_home_directory() -> anyhow::Result<()> {
         let codex_home = PathBuf::from("~/.codex");
@@ -1147,10 +3294,8 @@ mod tests {
     fn keyring_auth_storage_save_persists_and_removes_fallback_file() -> anyhow::Result<()> {
         let codex_home = tempdir()?;
         let mock_keyring = MockKeyringStore::default();
-        let storage = KeyringAuthStorage::new(
-            codex_home.path().to_path_buf(),
-            Arc::new(mock_keyring.clone()),
-        );
+        let storage =
+            KeyringAuthStorage::new(&settings(codex_home.path()), Arc::new(mock_keyring.clone()));
         let auth_file = get_auth_file(codex_home.path());
         std::fs::write(&auth_file, "stale")?;
         let auth = AuthDotJson {
@@ -1163,6 +3308,7 @@ mod tests {
             }),
             last_refresh: Some(Utc::now()),
             account_state: None,
+            metadata: AccountMetadata::default(),
         };
 
         storage.save(&auth)?;
@@ -1181,10 +3327,8 @@ mod tests {
     fn keyring_auth_storage_delete_removes_keyring_and_file() -> anyhow::Result<()> {
         let codex_home = tempdir()?;
         let mock_keyring = MockKeyringStore::default();
-        let storage = KeyringAuthStorage::new(
-            codex_home.path().to_path_buf(),
-            Arc::new(mock_keyring.clone()),
-        );
+        let storage =
+            KeyringAuthStorage::new(&settings(codex_home.path()), Arc::new(mock_keyring.clone()));
         let (key, auth_file) = seed_keyring_and_fallback_auth_file_for_delete(
             &mock_keyring,
             codex_home.path(),
@@ -1209,10 +3353,8 @@ mod tests {
     fn auto_auth_storage_load_prefers_keyring_value() -> anyhow::Result<()> {
         let codex_home = tempdir()?;
         let mock_keyring = MockKeyringStore::default();
-        let storage = AutoAuthStorage::new(
-            codex_home.path().to_path_buf(),
-            Arc::new(mock_keyring.clone()),
-        );
+        let storage =
+            AutoAuthStorage::new(&settings(codex_home.path()), Arc::new(mock_keyring.clone()));
         let keyring_auth = auth_with_prefix("keyring");
         seed_keyring_with_auth(
             &mock_keyring,
@@ -1232,7 +3374,7 @@ mod tests {
     fn auto_auth_storage_load_uses_file_when_keyring_empty() -> anyhow::Result<()> {
         let codex_home = tempdir()?;
         let mock_keyring = MockKeyringStore::default();
-        let storage = AutoAuthStorage::new(codex_home.path().to_path_buf(), Arc::new(mock_keyring));
+        let storage = AutoAuthStorage::new(&settings(codex_home.path()), Arc::new(mock_keyring));
 
         let expected = auth_with_prefix("file-only");
         storage.file_storage.save(&expected)?;
@@ -1246,10 +3388,8 @@ mod tests {
     fn auto_auth_storage_load_falls_back_when_keyring_errors() -> anyhow::Result<()> {
         let codex_home = tempdir()?;
         let mock_keyring = MockKeyringStore::default();
-        let storage = AutoAuthStorage::new(
-            codex_home.path().to_path_buf(),
-            Arc::new(mock_keyring.clone()),
-        );
+        let storage =
+            AutoAuthStorage::new(&settings(codex_home.path()), Arc::new(mock_keyring.clone()));
         let key = compute_store_key(codex_home.path())?;
         mock_keyring.set_error(&key, KeyringError::Invalid("error".into(), "load".into()));
 
@@ -1265,10 +3405,8 @@ mod tests {
     fn auto_auth_storage_save_prefers_keyring() -> anyhow::Result<()> {
         let codex_home = tempdir()?;
         let mock_keyring = MockKeyringStore::default();
-        let storage = AutoAuthStorage::new(
-            codex_home.path().to_path_buf(),
-            Arc::new(mock_keyring.clone()),
-        );
+        let storage =
+            AutoAuthStorage::new(&settings(codex_home.path()), Arc::new(mock_keyring.clone()));
         let key = compute_store_key(codex_home.path())?;
 
         let stale = auth_with_prefix("stale");
@@ -1290,10 +3428,8 @@ mod tests {
     fn auto_auth_storage_save_falls_back_when_keyring_errors() -> anyhow::Result<()> {
         let codex_home = tempdir()?;
         let mock_keyring = MockKeyringStore::default();
-        let storage = AutoAuthStorage::new(
-            codex_home.path().to_path_buf(),
-            Arc::new(mock_keyring.clone()),
-        );
+        let storage =
+            AutoAuthStorage::new(&settings(codex_home.path()), Arc::new(mock_keyring.clone()));
         let key = compute_store_key(codex_home.path())?;
         mock_keyring.set_error(&key, KeyringError::Invalid("error".into(), "save".into()));
 
@@ -1321,10 +3457,8 @@ mod tests {
     fn auto_auth_storage_delete_removes_keyring_and_file() -> anyhow::Result<()> {
         let codex_home = tempdir()?;
         let mock_keyring = MockKeyringStore::default();
-        let storage = AutoAuthStorage::new(
-            codex_home.path().to_path_buf(),
-            Arc::new(mock_keyring.clone()),
-        );
+        let storage =
+            AutoAuthStorage::new(&settings(codex_home.path()), Arc::new(mock_keyring.clone()));
         let (key, auth_file) = seed_keyring_and_fallback_auth_file_for_delete(
             &mock_keyring,
             codex_home.path(),
@@ -1344,4 +3478,89 @@ mod tests {
         );
         Ok(())
     }
+
+    fn token_env(name: &str) -> Option<String> {
+        match name {
+            CODEX_ACCESS_TOKEN_ENV_VAR => Some("env-access".to_string()),
+            CODEX_REFRESH_TOKEN_ENV_VAR => Some("env-refresh".to_string()),
+            OPENAI_API_KEY_ENV_VAR => Some("sk-env".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn env_auth_storage_prefers_tokens_and_keeps_refreshes_in_memory() -> anyhow::Result<()> {
+        let storage = EnvAuthStorage::new(token_env, Arc::default());
+        let loaded = storage.load()?.context("env credentials should load")?;
+        let tokens = loaded.tokens.clone().context("tokens should be set")?;
+        assert_eq!(loaded.openai_api_key, None);
+        assert_eq!(
+            (tokens.access_token.as_str(), tokens.refresh_token.as_str()),
+            ("env-access", "env-refresh")
+        );
+
+        let mut refreshed = loaded;
+        if let Some(tokens) = refreshed.tokens.as_mut() {
+            tokens.refresh_token = "rotated-refresh".to_string();
+        }
+        storage.save(&refreshed)?;
+        assert_eq!(storage.load()?, Some(refreshed));
+
+        assert!(!storage.delete()?);
+        assert_eq!(
+            storage
+                .load()?
+                .and_then(|auth| auth.tokens)
+                .map(|tokens| tokens.refresh_token),
+            Some("env-refresh".to_string())
+        );
+
+        let api_key_only = EnvAuthStorage::new(
+            |name| (name == OPENAI_API_KEY_ENV_VAR).then(|| "sk-env".to_string()),
+            Arc::default(),
+        );
+        assert_eq!(
+            api_key_only.load()?.and_then(|auth| auth.openai_api_key),
+            Some("sk-env".to_string())
+        );
+
+        let half_set = EnvAuthStorage::new(
+            |name| (name == CODEX_ACCESS_TOKEN_ENV_VAR).then(|| "env-access".to_string()),
+            Arc::default(),
+        );
+        assert!(half_set.load().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn auto_auth_storage_prefers_env_credentials_without_touching_disk() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let mock_keyring = MockKeyringStore::default();
+        let storage =
+            AutoAuthStorage::new(&settings(codex_home.path()), Arc::new(mock_keyring.clone()))
+                .with_env_storage(EnvAuthStorage::new(token_env, Arc::default()));
+        seed_keyring_with_auth(
+            &mock_keyring,
+            || compute_store_key(codex_home.path()),
+            &auth_with_prefix("keyring"),
+        )?;
+
+        let loaded = storage.load()?.context("env credentials should load")?;
+        assert_eq!(
+            loaded
+                .tokens
+                .as_ref()
+                .map(|tokens| tokens.access_token.as_str()),
+            Some("env-access")
+        );
+
+        storage.save(&loaded)?;
+        let key = compute_store_key(codex_home.path())?;
+        assert_eq!(
+            mock_keyring.saved_value(&key),
+            Some(serde_json::to_string(&auth_with_prefix("keyring"))?)
+        );
+        assert!(!get_auth_file(codex_home.path()).exists());
+        Ok(())
+    }
 }
@@ -281,7 +281,11 @@ impl ModelClient {
         }
 
         let max_attempts = self.provider.request_max_retries();
-        for attempt in 0..=max_attempts {
+        let mut attempt = 0;
+        // Accounts that hit their usage limit during this request; switching
+        // accounts does not use up a retry attempt, so this bounds rotation.
+        let mut limited_accounts: Vec<Option<String>> = Vec::new();
+        loop {
             match self
                 .attempt_stream_responses(attempt, &payload_json, &auth_manager)
                 .await
@@ -289,6 +293,33 @@ impl ModelClient {
                 Ok(stream) => {
                     return Ok(stream);
                 }
+                Err(StreamAttemptError::Fatal(
+                    err @ (CodexErr::UsageLimitReached(_) | CodexErr::QuotaExceeded),
+                )) => {
+                    let Some(manager) = auth_manager.as_ref() else {
+                        return Err(err);
+                    };
+                    let current = manager.auth();
+                    // Only API-key accounts record an exhausted quota, so only
+                    // they rotate on it.
+                    if matches!(err, CodexErr::QuotaExceeded)
+                        && current
+                            .as_ref()
+                            .is_none_or(|auth| auth.mode != AuthMode::ApiKey)
+                    {
+                        return Err(err);
+                    }
+                    limited_accounts.push(current.and_then(|auth| auth.account_label()));
+                    match manager.rotate_after_usage_limit() {
+                        Some(next) if !limited_accounts.contains(&next.account_label()) => {
+                            warn!(
+                                "usage limit reached; retrying with account {}",
+                                next.account_label().unwrap_or_default()
+                            );
+                        }
+                        _ => return Err(err),
+                    }
+                }
                 Err(StreamAttemptError::Fatal(e)) => {
                     return Err(e);
                 }
@@ -298,11 +329,10 @@ impl ModelClient {
                     }
 
                     tokio::time::sleep(retryable_attempt_error.delay(attempt)).await;
+                    attempt += 1;
                 }
             }
         }
-
-        unreachable!("stream_responses_attempt should always return");
     }
 
     /// Single attempt to start a streaming Responses API call.
@@ -329,13 +359,13 @@ impl ModelClient {
 
         // Include subagent header only for subagent sessions.
         if let SessionSource::SubAgent(sub) = &self.session_source {
-            let subagent = if let crate::protocol::SubAgentSource::Other(label) = sub {
-                label.clone()
-            } else {
-                serde_json::to_value(sub)
+            let subagent = match sub {
+                crate::protocol::SubAgentSource::Other(label) => label.clone(),
+                crate::protocol::SubAgentSource::Coco { .. } => "coco".to_string(),
+                _ => serde_json::to_value(sub)
                     .ok()
                     .and_then(|v| v.as_str().map(std::string::ToString::to_string))
-                    .unwrap_or_else(|| "other".to_string())
+                    .unwrap_or_else(|| "other".to_string()),
             };
             req_builder = req_builder.header("x-openai-subagent", subagent);
         }
@@ -443,7 +473,10 @@ impl ModelClient {
                         request_id: request_id.clone(),
                     };
                     if let Some(auth_ref) = auth.as_ref() {
-                        auth_ref.record_unexpected_response(&unexpected);
+                        auth_ref.record_unexpected_response(
+                            &unexpected,
+                            self.config.unexpected_response_cooldown,
+                        );
                     }
                     return Err(StreamAttemptError::Fatal(CodexErr::UnexpectedStatus(
                         unexpected,
@@ -478,6 +511,9 @@ impl ModelClient {
                         } else if error.r#type.as_deref() == Some("usage_not_included") {
                             return Err(StreamAttemptError::Fatal(CodexErr::UsageNotIncluded));
                         } else if is_quota_exceeded_error(&error) {
+                            if let Some(auth_ref) = auth.as_ref() {
+                                auth_ref.record_quota_exceeded();
+                            }
                             return Err(StreamAttemptError::Fatal(CodexErr::QuotaExceeded));
                         }
                     }
@@ -542,13 +578,13 @@ impl ModelClient {
             .create_compact_request_builder(&self.client, &auth)
             .await?;
         if let SessionSource::SubAgent(sub) = &self.session_source {
-            let subagent = if let crate::protocol::SubAgentSource::Other(label) = sub {
-                label.clone()
-            } else {
-                serde_json::to_value(sub)
+            let subagent = match sub {
+                crate::protocol::SubAgentSource::Other(label) => label.clone(),
+                crate::protocol::SubAgentSource::Coco { .. } => "coco".to_string(),
+                _ => serde_json::to_value(sub)
                     .ok()
                     .and_then(|v| v.as_str().map(std::string::ToString::to_string))
-                    .unwrap_or_else(|| "other".to_string())
+                    .unwrap_or_else(|| "other".to_string()),
             };
             req_builder = req_builder.header("x-openai-subagent", subagent);
         }
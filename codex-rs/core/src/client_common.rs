@@ -41,6 +41,10 @@ pub struct Prompt {
     /// Whether parallel tool calls are permitted for this prompt.
     pub(crate) parallel_tool_calls: bool,
 
+    /// Set when the session's tool configuration removes `apply_patch`, so
+    /// the instructions do not describe it either.
+    pub(crate) apply_patch_disabled: bool,
+
     /// Optional override for the built-in BASE_INSTRUCTIONS.
     pub base_instructions_override: Option<String>,
 
@@ -58,6 +62,8 @@ impl Prompt {
         // - the model needs special instructions (4.1)
         // AND
         // - there is no apply_patch tool present
+        // AND
+        // - apply_patch was not disabled for the session
         let is_apply_patch_tool_present = self.tools.iter().any(|tool| match tool {
             ToolSpec::Function(f) => f.name == "apply_patch",
             ToolSpec::Freeform(f) => f.name == "apply_patch",
@@ -66,6 +72,7 @@ impl Prompt {
         if self.base_instructions_override.is_none()
             && model.needs_special_apply_patch_instructions
             && !is_apply_patch_tool_present
+            && !self.apply_patch_disabled
         {
             Cow::Owned(format!("{base}\n{APPLY_PATCH_TOOL_INSTRUCTIONS}"))
         } else {
@@ -443,6 +450,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn get_full_instructions_omits_disabled_apply_patch() {
+        let prompt = Prompt {
+            apply_patch_disabled: true,
+            ..Default::default()
+        };
+        let model_family = find_family_for_model("gpt-4.1").expect("known model slug");
+
+        let full = prompt.get_full_instructions(&model_family);
+        assert_eq!(full, model_family.base_instructions);
+    }
+
     #[test]
     fn serializes_text_verbosity_when_set() {
         let input: Vec<ResponseItem> = vec![];
@@ -0,0 +1,32 @@
+//! Hook through which `coco flow run <workflow.toml>` reaches the codex-flow
+//! runner. The flow crate depends on this one, so the host binary registers
+//! the runner at startup instead of core calling it directly.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::OnceLock;
+
+/// Runs a codex-flow workflow on behalf of a session.
+pub trait CocoFlowRunner: Send + Sync {
+    /// Runs the workflow in `workflow` with `cwd` as its working directory,
+    /// passing one line per step update to `progress`, and returns a summary
+    /// of the finished run for the model.
+    fn run(
+        &self,
+        workflow: &Path,
+        cwd: &Path,
+        progress: Box<dyn FnMut(String) + Send>,
+    ) -> anyhow::Result<String>;
+}
+
+static RUNNER: OnceLock<Arc<dyn CocoFlowRunner>> = OnceLock::new();
+
+/// Registers the runner used by `coco flow run`. Only the first call has an
+/// effect.
+pub fn set_coco_flow_runner(runner: Arc<dyn CocoFlowRunner>) {
+    let _ = RUNNER.set(runner);
+}
+
+pub(crate) fn coco_flow_runner() -> Option<Arc<dyn CocoFlowRunner>> {
+    RUNNER.get().cloned()
+}
@@ -5,6 +5,9 @@ use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
 
 use crate::AuthManager;
+use crate::auth::AuthStorageSettings;
+use crate::auth::accounts_limited_until;
+use crate::auth::list_accounts;
 use crate::client_common::REVIEW_PROMPT;
 use crate::compact;
 use crate::compact::run_inline_auto_compact_task;
@@ -21,6 +24,9 @@ use crate::user_notification::UserNotifier;
 use crate::util::error_or_panic;
 use async_channel::Receiver;
 use async_channel::Sender;
+use chrono::DateTime;
+use chrono::Utc;
+use codex_app_server_protocol::AuthMode;
 use codex_protocol::ConversationId;
 use codex_protocol::items::TurnItem;
 use codex_protocol::protocol::FileChange;
@@ -48,6 +54,7 @@ use serde_json;
 use serde_json::Value;
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
+use tokio::sync::Semaphore;
 use tokio::sync::oneshot;
 use tokio_util::sync::CancellationToken;
 use tracing::debug;
@@ -72,7 +79,10 @@ use crate::mcp::auth::compute_auth_statuses;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::model_family::find_family_for_model;
 use crate::openai_model_info::get_model_info;
+use crate::persistent_shell::PersistentShellSlot;
 use crate::project_doc::get_user_instructions;
+use crate::protocol::AccountUsageLimit;
+use crate::protocol::AccountsUsageLimitedEvent;
 use crate::protocol::AgentMessageContentDeltaEvent;
 use crate::protocol::AgentReasoningSectionBreakEvent;
 use crate::protocol::ApplyPatchApprovalRequestEvent;
@@ -109,11 +119,16 @@ use crate::tasks::ReviewTask;
 use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
 use crate::tools::ToolRouter;
+use crate::tools::coco_subagent::CocoResultCache;
+use crate::tools::coco_subagent::CocoStats;
 use crate::tools::context::SharedTurnDiffTracker;
+use crate::tools::file_reads::FileReadTracker;
 use crate::tools::parallel::ToolCallRuntime;
+use crate::tools::quota::TurnToolQuota;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::tools::spec::ToolsConfig;
 use crate::tools::spec::ToolsConfigParams;
+use crate::tools::usage::ToolUsageTracker;
 use crate::turn_diff_tracker::TurnDiffTracker;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_instructions::DeveloperInstructions;
@@ -287,6 +302,8 @@ pub(crate) struct TurnContext {
     pub(crate) tool_call_gate: Arc<ReadinessFlag>,
     pub(crate) exec_policy: Arc<ExecPolicy>,
     pub(crate) truncation_policy: TruncationPolicy,
+    pub(crate) tool_quota: TurnToolQuota,
+    pub(crate) tool_usage: ToolUsageTracker,
 }
 
 impl TurnContext {
@@ -388,6 +405,10 @@ pub(crate) struct SessionSettingsUpdate {
 }
 
 impl Session {
+    pub(crate) fn conversation_id(&self) -> ConversationId {
+        self.conversation_id
+    }
+
     fn make_turn_context(
         auth_manager: Option<Arc<AuthManager>>,
         otel_event_manager: &OtelEventManager,
@@ -427,7 +448,9 @@ impl Session {
         let tools_config = ToolsConfig::new(&ToolsConfigParams {
             model_family: &model_family,
             features: &config.features,
-        });
+        })
+        .with_allowed_tools(config.allowed_tools.clone())
+        .with_disabled_tools(config.disabled_tools.clone());
 
         TurnContext {
             sub_id,
@@ -446,6 +469,8 @@ impl Session {
             tool_call_gate: Arc::new(ReadinessFlag::new()),
             exec_policy: session_configuration.exec_policy.clone(),
             truncation_policy: TruncationPolicy::new(&per_turn_config),
+            tool_quota: TurnToolQuota::new(per_turn_config.tool_quotas.clone()),
+            tool_usage: ToolUsageTracker::default(),
         }
     }
 
@@ -573,6 +598,11 @@ impl Session {
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager,
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            coco_results: Mutex::new(CocoResultCache::default()),
+            coco_stats: Mutex::new(CocoStats::default()),
+            coco_slots: Semaphore::new(config.coco.max_concurrent.max(1)),
+            persistent_shell: PersistentShellSlot::default(),
+            file_reads: Mutex::new(FileReadTracker::default()),
         };
 
         let sess = Arc::new(Session {
@@ -624,6 +654,16 @@ impl Session {
         // record_initial_history can emit events. We record only after the SessionConfiguredEvent is emitted.
         sess.record_initial_history(initial_history).await;
 
+        // Loading auth fell back to a blocked account when every stored
+        // account is blocked; let automation know before the first turn fails.
+        if auth_manager
+            .auth()
+            .is_some_and(|auth| auth.mode == AuthMode::ChatGPT)
+            && let Some(event) = accounts_usage_limited_event(&config.auth_storage_settings())
+        {
+            sess.notify_accounts_exhausted(&event);
+        }
+
         Ok(sess)
     }
 
@@ -1092,6 +1132,35 @@ impl Session {
                 );
             }
         }
+        if let Some(token_usage) = token_usage
+            && let Some(auth) = turn_context
+                .client
+                .get_auth_manager()
+                .and_then(|manager| manager.auth())
+        {
+            auth.record_token_usage(token_usage);
+        }
+        self.send_token_count_event(turn_context).await;
+    }
+
+    /// Adds tokens spent by a sub-agent to this session's totals. Only the
+    /// totals change: `last_token_usage` keeps describing this session's own
+    /// context window.
+    pub(crate) async fn add_sub_agent_token_usage(
+        &self,
+        turn_context: &TurnContext,
+        token_usage: &TokenUsage,
+    ) {
+        {
+            let mut state = self.state.lock().await;
+            let mut info = state.token_info().unwrap_or(TokenUsageInfo {
+                total_token_usage: TokenUsage::default(),
+                last_token_usage: TokenUsage::default(),
+                model_context_window: turn_context.client.get_model_context_window(),
+            });
+            info.total_token_usage.add_assign(token_usage);
+            state.set_token_info(Some(info));
+        }
         self.send_token_count_event(turn_context).await;
     }
 
@@ -1331,6 +1400,24 @@ impl Session {
         &self.services.notifier
     }
 
+    /// Tells the `notify` program that every stored account is blocked and
+    /// when the first one becomes usable again.
+    fn notify_accounts_exhausted(&self, event: &AccountsUsageLimitedEvent) {
+        let Some(retry_at) = DateTime::<Utc>::from_timestamp(event.retry_at, 0) else {
+            return;
+        };
+        self.notifier()
+            .notify(&UserNotification::AccountsExhausted {
+                thread_id: self.conversation_id.to_string(),
+                retry_at: retry_at.to_rfc3339(),
+                accounts: event
+                    .accounts
+                    .iter()
+                    .map(|account| account.email.clone())
+                    .collect(),
+            });
+    }
+
     pub(crate) fn user_shell(&self) -> &shell::Shell {
         &self.services.user_shell
     }
@@ -1690,6 +1777,7 @@ mod handlers {
                 id: sub_id.clone(),
                 msg: EventMsg::Error(ErrorEvent {
                     message: "Failed to shutdown rollout recorder".to_string(),
+                    kind: None,
                 }),
             };
             sess.send_event_raw(event).await;
@@ -1797,6 +1885,8 @@ async fn spawn_review_thread(
         tool_call_gate: Arc::new(ReadinessFlag::new()),
         exec_policy: parent_turn_context.exec_policy.clone(),
         truncation_policy: TruncationPolicy::new(&per_turn_config),
+        tool_quota: TurnToolQuota::new(per_turn_config.tool_quotas.clone()),
+        tool_usage: ToolUsageTracker::default(),
     };
 
     // Seed the child task with the review prompt as the initial user message.
@@ -1946,6 +2036,7 @@ pub(crate) async fn run_task(
                 info!("Turn error: {e:#}");
                 let event = EventMsg::Error(ErrorEvent {
                     message: e.to_string(),
+                    kind: Some(e.kind()),
                 });
                 sess.send_event(&turn_context, event).await;
                 // let the user continue the conversation
@@ -2011,6 +2102,7 @@ async fn run_turn(
         input,
         tools: router.specs(),
         parallel_tool_calls,
+        apply_patch_disabled: !turn_context.tools_config.allows_tool("apply_patch"),
         base_instructions_override: base_instructions,
         output_schema: turn_context.final_output_json_schema.clone(),
     };
@@ -2047,6 +2139,13 @@ async fn run_turn(
                 if let Some(rate_limits) = rate_limits {
                     sess.update_rate_limits(&turn_context, rate_limits).await;
                 }
+                if let Some(event) = accounts_usage_limited_event(
+                    &turn_context.client.config().auth_storage_settings(),
+                ) {
+                    sess.notify_accounts_exhausted(&event);
+                    sess.send_event(&turn_context, EventMsg::AccountsUsageLimited(event))
+                        .await;
+                }
                 return Err(CodexErr::UsageLimitReached(e));
             }
             Err(CodexErr::UsageNotIncluded) => return Err(CodexErr::UsageNotIncluded),
@@ -2083,6 +2182,33 @@ async fn run_turn(
     }
 }
 
+/// Describes when the stored rotation accounts can be used again, but only
+/// once every one of them is usage limited.
+fn accounts_usage_limited_event(
+    settings: &AuthStorageSettings,
+) -> Option<AccountsUsageLimitedEvent> {
+    let accounts = match list_accounts(settings) {
+        Ok(accounts) => accounts,
+        Err(err) => {
+            warn!("failed to list accounts: {err}");
+            return None;
+        }
+    };
+    let retry_at = accounts_limited_until(&accounts)?;
+    Some(AccountsUsageLimitedEvent {
+        retry_at: retry_at.timestamp(),
+        accounts: accounts
+            .iter()
+            .filter_map(|account| {
+                Some(AccountUsageLimit {
+                    email: account.email.clone(),
+                    retry_at: account.retry_at()?.timestamp(),
+                })
+            })
+            .collect(),
+    })
+}
+
 /// When the model is prompted, it returns a stream of events. Some of these
 /// events map to a `ResponseItem`. A `ResponseItem` may need to be
 /// "handled" such that it produces a `ResponseInputItem` that needs to be
@@ -2597,7 +2723,7 @@ mod tests {
         let conversation_id = ConversationId::default();
         let otel_event_manager = otel_event_manager(conversation_id, config.as_ref());
         let auth_manager = AuthManager::shared(
-            config.cwd.clone(),
+            AuthStorageSettings::new(config.cwd.clone()),
             false,
             config.cli_auth_credentials_store_mode,
         );
@@ -2633,6 +2759,11 @@ mod tests {
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager: otel_event_manager.clone(),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            coco_results: Mutex::new(CocoResultCache::default()),
+            coco_stats: Mutex::new(CocoStats::default()),
+            coco_slots: Semaphore::new(config.coco.max_concurrent.max(1)),
+            persistent_shell: PersistentShellSlot::default(),
+            file_reads: Mutex::new(FileReadTracker::default()),
         };
 
         let turn_context = Session::make_turn_context(
@@ -2675,7 +2806,7 @@ mod tests {
         let conversation_id = ConversationId::default();
         let otel_event_manager = otel_event_manager(conversation_id, config.as_ref());
         let auth_manager = AuthManager::shared(
-            config.cwd.clone(),
+            AuthStorageSettings::new(config.cwd.clone()),
             false,
             config.cli_auth_credentials_store_mode,
         );
@@ -2711,6 +2842,11 @@ mod tests {
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager: otel_event_manager.clone(),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            coco_results: Mutex::new(CocoResultCache::default()),
+            coco_stats: Mutex::new(CocoStats::default()),
+            coco_slots: Semaphore::new(config.coco.max_concurrent.max(1)),
+            persistent_shell: PersistentShellSlot::default(),
+            file_reads: Mutex::new(FileReadTracker::default()),
         };
 
         let turn_context = Arc::new(Session::make_turn_context(
@@ -13,6 +13,7 @@ use codex_protocol::protocol::SessionSource;
 use codex_protocol::protocol::SubAgentSource;
 use codex_protocol::protocol::Submission;
 use codex_protocol::user_input::UserInput;
+use serde_json::Value;
 use tokio_util::sync::CancellationToken;
 
 use crate::AuthManager;
@@ -88,10 +89,13 @@ pub(crate) async fn run_codex_conversation_interactive(
 /// Convenience wrapper for one-time use with an initial prompt.
 ///
 /// Internally calls the interactive variant, then immediately submits the provided input.
+/// With `final_output_json_schema`, the final message is requested as JSON matching it.
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn run_codex_conversation_one_shot(
     config: Config,
     auth_manager: Arc<AuthManager>,
     input: Vec<UserInput>,
+    final_output_json_schema: Option<Value>,
     parent_session: Arc<Session>,
     parent_ctx: Arc<TurnContext>,
     cancel_token: CancellationToken,
@@ -101,6 +105,19 @@ pub(crate) async fn run_codex_conversation_one_shot(
     // Use a child token so we can stop the delegate after completion without
     // requiring the caller to cancel the parent token.
     let child_cancel = cancel_token.child_token();
+    let op = match final_output_json_schema {
+        Some(schema) => Op::UserTurn {
+            items: input,
+            cwd: config.cwd.clone(),
+            approval_policy: config.approval_policy,
+            sandbox_policy: config.sandbox_policy.clone(),
+            model: config.model.clone(),
+            effort: config.model_reasoning_effort,
+            summary: config.model_reasoning_summary,
+            final_output_json_schema: Some(schema),
+        },
+        None => Op::UserInput { items: input },
+    };
     let io = run_codex_conversation_interactive(
         config,
         auth_manager,
@@ -113,7 +130,7 @@ pub(crate) async fn run_codex_conversation_one_shot(
     .await?;
 
     // Send the initial input to kick off the one-shot turn.
-    io.submit(Op::UserInput { items: input }).await?;
+    io.submit(op).await?;
 
     // Bridge events so we can observe completion and shut down automatically.
     let (tx_bridge, rx_bridge) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
@@ -166,10 +183,6 @@ async fn forward_events(
                 id: _,
                 msg: EventMsg::AgentMessageDelta(_) | EventMsg::AgentReasoningDelta(_),
             } => continue,
-            Event {
-                id: _,
-                msg: EventMsg::SessionConfigured(_),
-            } => continue,
             Event {
                 id,
                 msg: EventMsg::ExecApprovalRequest(event),
@@ -207,6 +220,9 @@ async fn forward_events(
 }
 
 /// Forward ops from a caller to a sub-agent, respecting cancellation.
+///
+/// On cancellation the sub-agent is shut down, which aborts any task it is
+/// still running rather than leaving it to execute unobserved.
 async fn forward_ops(
     codex: Arc<Codex>,
     rx_ops: Receiver<Submission>,
@@ -215,7 +231,11 @@ async fn forward_ops(
     loop {
         let op: Op = match rx_ops.recv().or_cancel(&cancel_token_ops).await {
             Ok(Ok(Submission { id: _, op })) => op,
-            Ok(Err(_)) | Err(_) => break,
+            Ok(Err(_)) => break,
+            Err(_) => {
+                let _ = codex.submit(Op::Shutdown {}).await;
+                break;
+            }
         };
         let _ = codex.submit(op).await;
     }
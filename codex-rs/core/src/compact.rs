@@ -130,6 +130,7 @@ async fn run_compact_task_inner(
                 sess.set_total_tokens_full(turn_context.as_ref()).await;
                 let event = EventMsg::Error(ErrorEvent {
                     message: e.to_string(),
+                    kind: Some(e.kind()),
                 });
                 sess.send_event(&turn_context, event).await;
                 return;
@@ -148,6 +149,7 @@ async fn run_compact_task_inner(
                 } else {
                     let event = EventMsg::Error(ErrorEvent {
                         message: e.to_string(),
+                        kind: Some(e.kind()),
                     });
                     sess.send_event(&turn_context, event).await;
                     return;
@@ -32,6 +32,7 @@ async fn run_remote_compact_task_inner(sess: &Arc<Session>, turn_context: &Arc<T
     if let Err(err) = run_remote_compact_task_inner_impl(sess, turn_context).await {
         let event = EventMsg::Error(ErrorEvent {
             message: format!("Error running remote compact task: {err}"),
+            kind: Some(err.kind()),
         });
         sess.send_event(turn_context, event).await;
     }
@@ -46,6 +47,7 @@ async fn run_remote_compact_task_inner_impl(
         input: history.get_history_for_prompt(),
         tools: vec![],
         parallel_tool_calls: false,
+        apply_patch_disabled: false,
         base_instructions_override: turn_context.base_instructions.clone(),
         output_schema: None,
     };
@@ -1,7 +1,14 @@
 use crate::auth::AuthCredentialsStoreMode;
+use crate::auth::AuthStorageSettings;
+use crate::auth::DEFAULT_UNEXPECTED_RESPONSE_COOLDOWN;
+use crate::auth::resolve_account_label;
+use crate::auth::resolve_accounts_dir;
+use crate::auth::resolve_auth_read_only;
+use crate::config::types::CocoConfig;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
 use crate::config::types::History;
 use crate::config::types::McpServerConfig;
+use crate::config::types::ModelPrice;
 use crate::config::types::Notice;
 use crate::config::types::Notifications;
 use crate::config::types::OtelConfig;
@@ -11,6 +18,9 @@ use crate::config::types::ReasoningSummaryFormat;
 use crate::config::types::SandboxWorkspaceWrite;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
+use crate::config::types::ShellPtyConfig;
+use crate::config::types::SubAgentProfile;
+use crate::config::types::ToolQuotaConfig;
 use crate::config::types::Tui;
 use crate::config::types::UriBasedFileOpener;
 use crate::config_loader::LoadedConfigLayers;
@@ -52,6 +62,7 @@ use std::collections::HashMap;
 use std::io::ErrorKind;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use crate::config::profile::ConfigProfile;
 use toml::Value as TomlValue;
@@ -70,6 +81,13 @@ pub const GPT_5_CODEX_MEDIUM_MODEL: &str = "gpt-5.1-codex";
 /// the context window.
 pub(crate) const PROJECT_DOC_MAX_BYTES: usize = 32 * 1024; // 32 KiB
 
+/// Largest file the `write_file` tool will read or produce. Bigger files
+/// have to be handled with shell tools.
+pub(crate) const WRITE_FILE_MAX_BYTES: usize = 4 * 1024 * 1024; // 4 MiB
+
+/// Paths the edit tools refuse to touch unless `protected_paths` is set.
+pub(crate) const DEFAULT_PROTECTED_PATHS: &[&str] = &[".git/**", "**/.git/**", "*.env"];
+
 pub(crate) const CONFIG_TOML_FILE: &str = "config.toml";
 
 /// Application configuration loaded from disk and merged with overrides.
@@ -171,9 +189,42 @@ pub struct Config {
     /// auto: Use the OS-specific keyring service if available, otherwise use a file.
     pub cli_auth_credentials_store_mode: AuthCredentialsStoreMode,
 
+    /// How long account rotation skips an account after an unexpected API
+    /// response was recorded for it.
+    pub unexpected_response_cooldown: Duration,
+
+    /// Directory holding the rotation accounts (`CODEX_HOME/auth` unless
+    /// `accounts_dir` or `CODEX_ACCOUNTS_DIR` moves it).
+    pub accounts_dir: PathBuf,
+
+    /// Never write credential storage (`auth_read_only` or
+    /// `CODEX_AUTH_READ_ONLY`).
+    pub auth_read_only: bool,
+
+    /// Only select accounts with this label (`account_label`,
+    /// `codex --account-label` or `CODEX_ACCOUNT_LABEL`).
+    pub account_label: Option<String>,
+
     /// Definition for MCP servers that Codex can reach out to for tool calls.
     pub mcp_servers: HashMap<String, McpServerConfig>,
 
+    /// Sub-agent profiles that `coco --agent <name>` runs with.
+    pub agents: HashMap<String, SubAgentProfile>,
+
+    /// Per-model token prices, keyed by model slug.
+    pub model_prices: HashMap<String, ModelPrice>,
+
+    /// Settings for `coco` sub-agents.
+    pub coco: CocoConfig,
+
+    /// Tools this session may call; every tool when `None`. Sub-agents narrow
+    /// it further with their profile's `tools`.
+    pub allowed_tools: Option<Vec<String>>,
+
+    /// Tools removed after applying `allowed_tools`. Sub-agents add
+    /// `coco.disabled_tools` and the call's own restrictions.
+    pub disabled_tools: Vec<String>,
+
     /// Preferred store for MCP OAuth credentials.
     /// keyring: Use an OS-specific keyring service.
     ///          Credentials stored in the keyring will only be readable by Codex unless the user explicitly grants access via OS-level keyring access.
@@ -198,6 +249,34 @@ pub struct Config {
     /// Token budget applied when storing tool/function outputs in the context manager.
     pub tool_output_token_limit: Option<usize>,
 
+    /// Maximum size in bytes of a file `write_file` will overwrite or create.
+    pub write_file_max_bytes: usize,
+
+    /// Glob patterns, relative to the session cwd, of files the edit tools
+    /// refuse to create, change, or delete. `*` also matches `/`.
+    pub protected_paths: Vec<String>,
+
+    /// Whether the edit tools also refuse paths outside the session cwd.
+    pub protect_paths_outside_workspace: bool,
+
+    /// Bytes of shell output returned to the model before the rest is cut
+    /// and saved to a file; unlimited when unset.
+    pub shell_output_max_bytes: Option<usize>,
+
+    /// Lines of shell output returned to the model before the rest is cut
+    /// and saved to a file; unlimited when unset.
+    pub shell_output_max_lines: Option<usize>,
+
+    /// Settings for running shell commands in a pseudo-terminal.
+    pub shell_pty: ShellPtyConfig,
+
+    /// Run shell commands in one long-lived shell per session, so `cd`,
+    /// exported variables, and virtualenv activation carry over.
+    pub persistent_shell: bool,
+
+    /// Per-turn caps on shell calls, edited files, and shell run time.
+    pub tool_quotas: ToolQuotaConfig,
+
     /// Directory containing all Codex state (defaults to `~/.codex` but can be
     /// overridden by the `CODEX_HOME` environment variable).
     pub codex_home: PathBuf,
@@ -617,10 +696,39 @@ pub struct ConfigToml {
     #[serde(default)]
     pub cli_auth_credentials_store: Option<AuthCredentialsStoreMode>,
 
+    /// Minutes account rotation skips an account after an unexpected API
+    /// response (default: 30). `0` never skips.
+    pub unexpected_response_cooldown_minutes: Option<u64>,
+
+    /// Directory holding the rotation accounts, e.g. a shared team directory.
+    /// Relative paths resolve against CODEX_HOME; `CODEX_ACCOUNTS_DIR`
+    /// overrides it.
+    pub accounts_dir: Option<PathBuf>,
+
+    /// Never write credential storage, e.g. for credentials mounted
+    /// read-only into CI containers. `CODEX_AUTH_READ_ONLY` overrides it.
+    pub auth_read_only: Option<bool>,
+
+    /// Restricts account rotation to accounts with this label.
+    /// `CODEX_ACCOUNT_LABEL` applies when unset.
+    pub account_label: Option<String>,
+
     /// Definition for MCP servers that Codex can reach out to for tool calls.
     #[serde(default)]
     pub mcp_servers: HashMap<String, McpServerConfig>,
 
+    /// Sub-agent profiles keyed by the name passed to `coco --agent`.
+    #[serde(default)]
+    pub agents: HashMap<String, SubAgentProfile>,
+
+    /// Token prices keyed by model slug, used by `codex exec --max-cost`.
+    #[serde(default)]
+    pub model_prices: HashMap<String, ModelPrice>,
+
+    /// Settings for `coco` sub-agents.
+    #[serde(default)]
+    pub coco: Option<CocoConfig>,
+
     /// Preferred backend for storing MCP OAuth credentials.
     /// keyring: Use an OS-specific keyring service.
     ///          https://github.com/openai/codex/blob/main/codex-rs/rmcp-client/src/oauth.rs#L2
@@ -642,6 +750,38 @@ pub struct ConfigToml {
     /// Token budget applied when storing tool/function outputs in the context manager.
     pub tool_output_token_limit: Option<usize>,
 
+    /// Maximum size in bytes of a file `write_file` will overwrite or create.
+    pub write_file_max_bytes: Option<usize>,
+
+    /// Files the edit tools refuse to touch; replaces the default list.
+    pub protected_paths: Option<Vec<String>>,
+
+    /// Whether the edit tools also refuse paths outside the session cwd.
+    pub protect_paths_outside_workspace: Option<bool>,
+
+    /// Bytes of shell output returned to the model before the rest is cut.
+    pub shell_output_max_bytes: Option<usize>,
+
+    /// Lines of shell output returned to the model before the rest is cut.
+    pub shell_output_max_lines: Option<usize>,
+
+    /// Settings for running shell commands in a pseudo-terminal.
+    #[serde(default)]
+    pub shell_pty: Option<ShellPtyConfig>,
+
+    /// Run shell commands in one long-lived shell per session.
+    pub persistent_shell: Option<bool>,
+
+    /// Per-turn caps on shell calls, edited files, and shell run time.
+    #[serde(default)]
+    pub tool_quotas: Option<ToolQuotaConfig>,
+
+    /// Names of the tools the model may call; every tool when unset.
+    pub allowed_tools: Option<Vec<String>>,
+
+    /// Names of tools removed after applying `allowed_tools`.
+    pub disabled_tools: Option<Vec<String>>,
+
     /// Override path for project-level instructions (experimental).
     pub experimental_agents_file: Option<PathBuf>,
 
@@ -1205,7 +1345,23 @@ impl Config {
             // The config.toml omits "_mode" because it's a config file. However, "_mode"
             // is important in code to differentiate the mode from the store implementation.
             cli_auth_credentials_store_mode: cfg.cli_auth_credentials_store.unwrap_or_default(),
+            unexpected_response_cooldown: cfg
+                .unexpected_response_cooldown_minutes
+                .map_or(DEFAULT_UNEXPECTED_RESPONSE_COOLDOWN, |minutes| {
+                    Duration::from_secs(minutes.saturating_mul(60))
+                }),
+            accounts_dir: resolve_accounts_dir(&codex_home, cfg.accounts_dir.as_deref()),
+            auth_read_only: resolve_auth_read_only(cfg.auth_read_only),
+            account_label: resolve_account_label(cfg.account_label),
             mcp_servers: cfg.mcp_servers,
+            agents: cfg.agents,
+            model_prices: cfg.model_prices,
+            coco: cfg.coco.unwrap_or_default(),
+            allowed_tools: config_profile.allowed_tools.or(cfg.allowed_tools),
+            disabled_tools: config_profile
+                .disabled_tools
+                .or(cfg.disabled_tools)
+                .unwrap_or_default(),
             // The config.toml omits "_mode" because it's a config file. However, "_mode"
             // is important in code to differentiate the mode from the store implementation.
             mcp_oauth_credentials_store_mode: cfg.mcp_oauth_credentials_store.unwrap_or_default(),
@@ -1225,6 +1381,19 @@ impl Config {
                 })
                 .collect(),
             tool_output_token_limit: cfg.tool_output_token_limit,
+            write_file_max_bytes: cfg.write_file_max_bytes.unwrap_or(WRITE_FILE_MAX_BYTES),
+            protected_paths: cfg.protected_paths.unwrap_or_else(|| {
+                DEFAULT_PROTECTED_PATHS
+                    .iter()
+                    .map(|pattern| (*pattern).to_string())
+                    .collect()
+            }),
+            protect_paths_outside_workspace: cfg.protect_paths_outside_workspace.unwrap_or(false),
+            shell_output_max_bytes: cfg.shell_output_max_bytes,
+            shell_output_max_lines: cfg.shell_output_max_lines,
+            shell_pty: cfg.shell_pty.unwrap_or_default(),
+            persistent_shell: cfg.persistent_shell.unwrap_or(false),
+            tool_quotas: cfg.tool_quotas.unwrap_or_default(),
             experimental_agents_file: experimental_agents_path,
             codex_home,
             history,
@@ -1348,6 +1517,15 @@ impl Config {
         }
         self.forced_auto_mode_downgraded_on_windows = !value;
     }
+
+    /// Where the credential store backends keep credentials, for
+    /// [`crate::AuthManager`] and the `codex auth` commands.
+    pub fn auth_storage_settings(&self) -> AuthStorageSettings {
+        AuthStorageSettings::new(self.codex_home.clone())
+            .with_accounts_dir(&self.accounts_dir)
+            .with_read_only(self.auth_read_only)
+            .with_account_label(self.account_label.clone())
+    }
 }
 
 fn default_model() -> String {
@@ -1454,6 +1632,38 @@ persistence = "none"
         assert_eq!(tui.notifications, Notifications::Enabled(true));
     }
 
+    #[test]
+    fn agents_profiles_deserialize() {
+        let cfg = r#"
+[agents.reviewer]
+model = "o3"
+instructions = "Review the diff; do not edit files."
+sandbox_mode = "read-only"
+approval_policy = "never"
+tools = ["shell", "view_image"]
+
+[agents.scratch]
+"#;
+
+        let parsed = toml::from_str::<ConfigToml>(cfg).expect("agents profiles should deserialize");
+
+        assert_eq!(
+            parsed.agents.get("reviewer"),
+            Some(&SubAgentProfile {
+                model: Some("o3".to_string()),
+                instructions: Some("Review the diff; do not edit files.".to_string()),
+                sandbox_mode: Some(SandboxMode::ReadOnly),
+                approval_policy: Some(AskForApproval::Never),
+                tools: Some(vec!["shell".to_string(), "view_image".to_string()]),
+                max_tokens: None,
+            })
+        );
+        assert_eq!(
+            parsed.agents.get("scratch"),
+            Some(&SubAgentProfile::default())
+        );
+    }
+
     #[test]
     fn test_sandbox_config_parsing() {
         let sandbox_full_access = r#"
@@ -1681,6 +1891,32 @@ trust_level = "trusted"
         Ok(())
     }
 
+    #[test]
+    fn config_resolves_auth_storage_settings() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = ConfigToml {
+            accounts_dir: Some(PathBuf::from("team")),
+            auth_read_only: Some(true),
+            account_label: Some("prod".to_string()),
+            ..Default::default()
+        };
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        let team_dir = codex_home.path().join("team");
+        assert_eq!(config.accounts_dir, team_dir);
+        let settings = config.auth_storage_settings();
+        assert_eq!(settings.accounts_dir(), team_dir);
+        assert!(settings.read_only());
+        assert_eq!(settings.account_label(), Some("prod"));
+
+        Ok(())
+    }
+
     #[test]
     fn config_defaults_to_auto_oauth_store_mode() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -1728,6 +1964,43 @@ trust_level = "trusted"
         Ok(())
     }
 
+    #[test]
+    fn profile_tool_lists_replace_base() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let mut profiles = HashMap::new();
+        profiles.insert(
+            "reviewer".to_string(),
+            ConfigProfile {
+                disabled_tools: Some(vec!["apply_patch".to_string(), "write_file".to_string()]),
+                ..Default::default()
+            },
+        );
+        let cfg = ConfigToml {
+            profiles,
+            profile: Some("reviewer".to_string()),
+            allowed_tools: Some(vec!["shell".to_string(), "apply_patch".to_string()]),
+            disabled_tools: Some(vec!["web_search".to_string()]),
+            ..Default::default()
+        };
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.allowed_tools,
+            Some(vec!["shell".to_string(), "apply_patch".to_string()])
+        );
+        assert_eq!(
+            config.disabled_tools,
+            vec!["apply_patch".to_string(), "write_file".to_string()]
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn profile_sandbox_mode_overrides_base() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -2991,12 +3264,32 @@ model_verbosity = "high"
                 notify: None,
                 cwd: fixture.cwd(),
                 cli_auth_credentials_store_mode: Default::default(),
+                unexpected_response_cooldown: DEFAULT_UNEXPECTED_RESPONSE_COOLDOWN,
+                accounts_dir: fixture.codex_home().join("auth"),
+                auth_read_only: false,
+                account_label: None,
                 mcp_servers: HashMap::new(),
+                agents: HashMap::new(),
+                model_prices: HashMap::new(),
+                coco: CocoConfig::default(),
+                allowed_tools: None,
+                disabled_tools: Vec::new(),
                 mcp_oauth_credentials_store_mode: Default::default(),
                 model_providers: fixture.model_provider_map.clone(),
                 project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
                 project_doc_fallback_filenames: Vec::new(),
                 tool_output_token_limit: None,
+                write_file_max_bytes: WRITE_FILE_MAX_BYTES,
+                protected_paths: DEFAULT_PROTECTED_PATHS
+                    .iter()
+                    .map(|pattern| (*pattern).to_string())
+                    .collect(),
+                protect_paths_outside_workspace: false,
+                shell_output_max_bytes: None,
+                shell_output_max_lines: None,
+                shell_pty: ShellPtyConfig::default(),
+                persistent_shell: false,
+                tool_quotas: ToolQuotaConfig::default(),
                 experimental_agents_file: None,
                 codex_home: fixture.codex_home(),
                 history: History::default(),
@@ -3064,12 +3357,32 @@ model_verbosity = "high"
             notify: None,
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
+            unexpected_response_cooldown: DEFAULT_UNEXPECTED_RESPONSE_COOLDOWN,
+            accounts_dir: fixture.codex_home().join("auth"),
+            auth_read_only: false,
+            account_label: None,
             mcp_servers: HashMap::new(),
+            agents: HashMap::new(),
+            model_prices: HashMap::new(),
+            coco: CocoConfig::default(),
+            allowed_tools: None,
+            disabled_tools: Vec::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            write_file_max_bytes: WRITE_FILE_MAX_BYTES,
+            protected_paths: DEFAULT_PROTECTED_PATHS
+                .iter()
+                .map(|pattern| (*pattern).to_string())
+                .collect(),
+            protect_paths_outside_workspace: false,
+            shell_output_max_bytes: None,
+            shell_output_max_lines: None,
+            shell_pty: ShellPtyConfig::default(),
+            persistent_shell: false,
+            tool_quotas: ToolQuotaConfig::default(),
             experimental_agents_file: None,
             codex_home: fixture.codex_home(),
             history: History::default(),
@@ -3152,12 +3465,32 @@ model_verbosity = "high"
             notify: None,
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
+            unexpected_response_cooldown: DEFAULT_UNEXPECTED_RESPONSE_COOLDOWN,
+            accounts_dir: fixture.codex_home().join("auth"),
+            auth_read_only: false,
+            account_label: None,
             mcp_servers: HashMap::new(),
+            agents: HashMap::new(),
+            model_prices: HashMap::new(),
+            coco: CocoConfig::default(),
+            allowed_tools: None,
+            disabled_tools: Vec::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            write_file_max_bytes: WRITE_FILE_MAX_BYTES,
+            protected_paths: DEFAULT_PROTECTED_PATHS
+                .iter()
+                .map(|pattern| (*pattern).to_string())
+                .collect(),
+            protect_paths_outside_workspace: false,
+            shell_output_max_bytes: None,
+            shell_output_max_lines: None,
+            shell_pty: ShellPtyConfig::default(),
+            persistent_shell: false,
+            tool_quotas: ToolQuotaConfig::default(),
             experimental_agents_file: None,
             codex_home: fixture.codex_home(),
             history: History::default(),
@@ -3226,12 +3559,32 @@ model_verbosity = "high"
             notify: None,
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
+            unexpected_response_cooldown: DEFAULT_UNEXPECTED_RESPONSE_COOLDOWN,
+            accounts_dir: fixture.codex_home().join("auth"),
+            auth_read_only: false,
+            account_label: None,
             mcp_servers: HashMap::new(),
+            agents: HashMap::new(),
+            model_prices: HashMap::new(),
+            coco: CocoConfig::default(),
+            allowed_tools: None,
+            disabled_tools: Vec::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            write_file_max_bytes: WRITE_FILE_MAX_BYTES,
+            protected_paths: DEFAULT_PROTECTED_PATHS
+                .iter()
+                .map(|pattern| (*pattern).to_string())
+                .collect(),
+            protect_paths_outside_workspace: false,
+            shell_output_max_bytes: None,
+            shell_output_max_lines: None,
+            shell_pty: ShellPtyConfig::default(),
+            persistent_shell: false,
+            tool_quotas: ToolQuotaConfig::default(),
             experimental_agents_file: None,
             codex_home: fixture.codex_home(),
             history: History::default(),
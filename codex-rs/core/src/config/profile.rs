@@ -31,6 +31,10 @@ pub struct ConfigProfile {
     pub experimental_sandbox_command_assessment: Option<bool>,
     pub tools_web_search: Option<bool>,
     pub tools_view_image: Option<bool>,
+    /// Replaces the top-level `allowed_tools` while this profile is active.
+    pub allowed_tools: Option<Vec<String>>,
+    /// Replaces the top-level `disabled_tools` while this profile is active.
+    pub disabled_tools: Option<Vec<String>>,
     /// Optional feature toggles scoped to this profile.
     #[serde(default)]
     pub features: Option<crate::features::FeaturesToml>,
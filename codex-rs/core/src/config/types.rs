@@ -3,6 +3,8 @@
 // Note this file should generally be restricted to simple struct/enum
 // definitions that do not contain business logic.
 
+use crate::protocol::AskForApproval;
+use codex_protocol::config_types::SandboxMode;
 use serde::Deserializer;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -388,6 +390,200 @@ impl Notice {
     pub(crate) const TABLE_KEY: &'static str = "notice";
 }
 
+pub const DEFAULT_COCO_MAX_DEPTH: u32 = 2;
+pub const DEFAULT_COCO_MAX_OUTPUT_LINES: usize = 200;
+pub const DEFAULT_COCO_MAX_OUTPUT_BYTES: usize = 16 * 1024;
+pub const DEFAULT_COCO_TIMEOUT_MS: u64 = 10 * 60 * 1000;
+pub const DEFAULT_COCO_CACHE_TTL_SEC: u64 = 10 * 60;
+pub const DEFAULT_COCO_MAX_CONCURRENT: usize = 4;
+pub const DEFAULT_COCO_MAX_RETRIES: u32 = 2;
+
+/// Settings for `coco` sub-agents.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct CocoConfig {
+    /// How many levels of `coco` may nest: 1 lets the main session start
+    /// sub-agents but stops those sub-agents from starting their own.
+    #[serde(default = "default_coco_max_depth")]
+    pub max_depth: u32,
+
+    /// Sub-agents one session may run at the same time; further `coco`
+    /// calls wait for a running one to finish.
+    #[serde(default = "default_coco_max_concurrent")]
+    pub max_concurrent: usize,
+
+    /// Tokens (non-cached input plus output) one sub-agent may use before it
+    /// is cancelled; unlimited when unset.
+    #[serde(default)]
+    pub max_tokens: Option<i64>,
+
+    /// Milliseconds a sub-agent may run before it is cancelled, unless the
+    /// call sets its own timeout.
+    #[serde(default = "default_coco_timeout_ms")]
+    pub timeout_ms: u64,
+
+    /// Times a sub-agent is started over after its model stream fails, e.g.
+    /// on a disconnect or rate limit, before the call fails.
+    #[serde(default = "default_coco_max_retries")]
+    pub max_retries: u32,
+
+    /// Lines of output kept per command a sub-agent runs; the rest is only
+    /// in the full log under `CODEX_HOME/coco-logs`.
+    #[serde(default = "default_coco_max_output_lines")]
+    pub max_output_lines: usize,
+
+    /// Bytes of output kept per command a sub-agent runs.
+    #[serde(default = "default_coco_max_output_bytes")]
+    pub max_output_bytes: usize,
+
+    /// Models `coco --model` may pick; any model when unset.
+    #[serde(default)]
+    pub allowed_models: Option<Vec<String>>,
+
+    /// Stream sub-agent reasoning summaries to the parent as `thinking:`
+    /// lines.
+    #[serde(default)]
+    pub show_reasoning: bool,
+
+    /// Seconds a sub-agent's result is reused for an identical `coco` call
+    /// in the same session; 0 turns the cache off.
+    #[serde(default = "default_coco_cache_ttl_sec")]
+    pub cache_ttl_sec: u64,
+
+    /// Tools every sub-agent is limited to; all tools when unset. An agent
+    /// profile's `tools` can only narrow this further.
+    #[serde(default)]
+    pub tools: Option<Vec<String>>,
+
+    /// Tools no sub-agent may call, e.g. `["apply_patch"]` for read-only
+    /// delegation.
+    #[serde(default)]
+    pub disabled_tools: Vec<String>,
+}
+
+impl Default for CocoConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_COCO_MAX_DEPTH,
+            max_concurrent: DEFAULT_COCO_MAX_CONCURRENT,
+            max_tokens: None,
+            timeout_ms: DEFAULT_COCO_TIMEOUT_MS,
+            max_retries: DEFAULT_COCO_MAX_RETRIES,
+            max_output_lines: DEFAULT_COCO_MAX_OUTPUT_LINES,
+            max_output_bytes: DEFAULT_COCO_MAX_OUTPUT_BYTES,
+            allowed_models: None,
+            show_reasoning: false,
+            cache_ttl_sec: DEFAULT_COCO_CACHE_TTL_SEC,
+            tools: None,
+            disabled_tools: Vec::new(),
+        }
+    }
+}
+
+const fn default_coco_max_depth() -> u32 {
+    DEFAULT_COCO_MAX_DEPTH
+}
+
+const fn default_coco_max_concurrent() -> usize {
+    DEFAULT_COCO_MAX_CONCURRENT
+}
+
+const fn default_coco_timeout_ms() -> u64 {
+    DEFAULT_COCO_TIMEOUT_MS
+}
+
+const fn default_coco_max_retries() -> u32 {
+    DEFAULT_COCO_MAX_RETRIES
+}
+
+const fn default_coco_cache_ttl_sec() -> u64 {
+    DEFAULT_COCO_CACHE_TTL_SEC
+}
+
+const fn default_coco_max_output_lines() -> usize {
+    DEFAULT_COCO_MAX_OUTPUT_LINES
+}
+
+const fn default_coco_max_output_bytes() -> usize {
+    DEFAULT_COCO_MAX_OUTPUT_BYTES
+}
+
+pub const DEFAULT_SHELL_PTY_ROWS: u16 = 24;
+pub const DEFAULT_SHELL_PTY_COLS: u16 = 80;
+
+/// Settings for running `shell` commands attached to a pseudo-terminal.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ShellPtyConfig {
+    /// Run every shell command in a PTY, not only calls that set `tty`.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Rows of the terminal the command sees.
+    #[serde(default = "default_shell_pty_rows")]
+    pub rows: u16,
+
+    /// Columns of the terminal the command sees.
+    #[serde(default = "default_shell_pty_cols")]
+    pub cols: u16,
+}
+
+impl Default for ShellPtyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rows: DEFAULT_SHELL_PTY_ROWS,
+            cols: DEFAULT_SHELL_PTY_COLS,
+        }
+    }
+}
+
+const fn default_shell_pty_rows() -> u16 {
+    DEFAULT_SHELL_PTY_ROWS
+}
+
+const fn default_shell_pty_cols() -> u16 {
+    DEFAULT_SHELL_PTY_COLS
+}
+
+/// Per-turn caps on tool use that bound runaway loops. Unset limits are not
+/// enforced.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ToolQuotaConfig {
+    /// Shell commands (including `exec_command` sessions) a turn may start.
+    pub max_shell_calls: Option<u32>,
+
+    /// Distinct files a turn may edit through `apply_patch` and the file
+    /// editing tools.
+    pub max_edited_files: Option<usize>,
+
+    /// Total seconds a turn's shell commands may run for.
+    pub max_exec_time_sec: Option<u64>,
+}
+
+/// Price of a model in US dollars per million tokens, used to estimate what
+/// a run costs.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    pub input_per_mtok: f64,
+    /// Price of cached input tokens; billed as regular input when unset.
+    pub cached_input_per_mtok: Option<f64>,
+    pub output_per_mtok: f64,
+}
+
+/// Named sub-agent configuration, selected with `coco --agent <name>`.
+/// Unset fields inherit the parent session's settings.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct SubAgentProfile {
+    pub model: Option<String>,
+    /// Developer instructions for the sub-agent, replacing the parent's.
+    pub instructions: Option<String>,
+    pub sandbox_mode: Option<SandboxMode>,
+    pub approval_policy: Option<AskForApproval>,
+    /// Names of the tools the sub-agent may call; every tool when unset.
+    pub tools: Option<Vec<String>>,
+    /// Overrides `coco.max_tokens` for this profile.
+    pub max_tokens: Option<i64>,
+}
+
 #[derive(Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct SandboxWorkspaceWrite {
     #[serde(default)]
@@ -10,6 +10,7 @@ use chrono::Local;
 use chrono::Utc;
 use codex_async_utils::CancelErr;
 use codex_protocol::ConversationId;
+use codex_protocol::protocol::CodexErrorKind;
 use codex_protocol::protocol::RateLimitSnapshot;
 use reqwest::StatusCode;
 use serde_json;
@@ -430,6 +431,29 @@ impl CodexErr {
     pub fn downcast_ref<T: std::any::Any>(&self) -> Option<&T> {
         (self as &dyn std::any::Any).downcast_ref::<T>()
     }
+
+    /// The failure class reported alongside this error in [`ErrorEvent`].
+    ///
+    /// [`ErrorEvent`]: codex_protocol::protocol::ErrorEvent
+    pub fn kind(&self) -> CodexErrorKind {
+        match self {
+            CodexErr::Stream(..)
+            | CodexErr::ResponseStreamFailed(_)
+            | CodexErr::ConnectionFailed(_)
+            | CodexErr::RetryLimit(_)
+            | CodexErr::InternalServerError => CodexErrorKind::StreamError,
+            CodexErr::UsageLimitReached(_)
+            | CodexErr::QuotaExceeded
+            | CodexErr::UsageNotIncluded => CodexErrorKind::UsageLimit,
+            CodexErr::Timeout | CodexErr::Sandbox(SandboxErr::Timeout { .. }) => {
+                CodexErrorKind::Timeout
+            }
+            CodexErr::Sandbox(_) | CodexErr::LandlockSandboxExecutableNotProvided => {
+                CodexErrorKind::SandboxDenied
+            }
+            _ => CodexErrorKind::TurnFailed,
+        }
+    }
 }
 
 pub fn get_error_message_ui(e: &CodexErr) -> String {
@@ -525,6 +549,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn kind_classifies_turn_errors() {
+        let output = || {
+            Box::new(ExecToolCallOutput {
+                exit_code: 1,
+                stdout: StreamOutput::new(String::new()),
+                stderr: StreamOutput::new(String::new()),
+                aggregated_output: StreamOutput::new(String::new()),
+                duration: Duration::from_millis(10),
+                timed_out: false,
+            })
+        };
+        let cases = [
+            (
+                CodexErr::Stream("disconnected".to_string(), None),
+                CodexErrorKind::StreamError,
+            ),
+            (CodexErr::QuotaExceeded, CodexErrorKind::UsageLimit),
+            (
+                CodexErr::Sandbox(SandboxErr::Denied { output: output() }),
+                CodexErrorKind::SandboxDenied,
+            ),
+            (
+                CodexErr::Sandbox(SandboxErr::Timeout { output: output() }),
+                CodexErrorKind::Timeout,
+            ),
+            (CodexErr::Timeout, CodexErrorKind::Timeout),
+            (
+                CodexErr::Fatal("boom".to_string()),
+                CodexErrorKind::TurnFailed,
+            ),
+        ];
+        for (err, kind) in cases {
+            assert_eq!(err.kind(), kind, "{err}");
+        }
+    }
+
     #[test]
     fn sandbox_denied_uses_aggregated_output_when_stderr_empty() {
         let output = ExecToolCallOutput {
@@ -10,10 +10,14 @@ use std::time::Duration;
 use std::time::Instant;
 
 use async_channel::Sender;
+use codex_utils_pty::SpawnedPty;
+use codex_utils_pty::TerminalSize;
+use codex_utils_pty::spawn_pty_process_with_size;
 use tokio::io::AsyncRead;
 use tokio::io::AsyncReadExt;
 use tokio::io::BufReader;
 use tokio::process::Child;
+use tokio::sync::broadcast;
 
 use crate::error::CodexErr;
 use crate::error::Result;
@@ -29,7 +33,7 @@ use crate::sandboxing::SandboxManager;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
 
-const DEFAULT_TIMEOUT_MS: u64 = 4_000 * 60 * 10; //40 mins
+pub(crate) const DEFAULT_TIMEOUT_MS: u64 = 4_000 * 60 * 10; //40 mins
 
 // Hardcode these since it does not seem worth including the libc crate just
 // for these.
@@ -42,10 +46,99 @@ const EXEC_TIMEOUT_EXIT_CODE: i32 = 124; // conventional timeout exit code
 const READ_CHUNK_SIZE: usize = 8192; // bytes per read
 const AGGREGATE_BUFFER_INITIAL_CAPACITY: usize = 8 * 1024; // 8 KiB
 
+/// How long to keep reading a PTY after its process exits, for output the
+/// reader thread has not forwarded yet.
+const PTY_DRAIN_TIMEOUT_MS: u64 = 100;
+
 /// Limit the number of ExecCommandOutputDelta events emitted per exec call.
 /// Aggregation still collects full output; only the live event stream is capped.
 pub(crate) const MAX_EXEC_OUTPUT_DELTAS_PER_CALL: usize = 10_000;
 
+/// Live output is coalesced so each stream of a call sends at most this many
+/// ExecCommandOutputDelta events per second.
+pub(crate) const MAX_EXEC_OUTPUT_DELTAS_PER_SECOND: u32 = 20;
+
+/// Largest chunk carried by one ExecCommandOutputDelta event. Output that
+/// piles up beyond this between events is dropped from the live stream,
+/// oldest first; the aggregated output is unaffected.
+pub(crate) const MAX_EXEC_OUTPUT_DELTA_BYTES: usize = 16 * 1024;
+
+/// Coalesces the live output of one stream into ExecCommandOutputDelta
+/// chunks, so a command that prints megabytes does not flood the event
+/// channel and the clients reading it.
+#[derive(Debug, Default)]
+pub(crate) struct OutputDeltaThrottle {
+    pending: Vec<u8>,
+    skipped_bytes: usize,
+    last_emit: Option<tokio::time::Instant>,
+    emitted: usize,
+}
+
+impl OutputDeltaThrottle {
+    fn min_interval() -> Duration {
+        Duration::from_secs(1) / MAX_EXEC_OUTPUT_DELTAS_PER_SECOND
+    }
+
+    /// Buffers `chunk` until the next event is due.
+    pub(crate) fn push(&mut self, chunk: &[u8]) {
+        if self.emitted >= MAX_EXEC_OUTPUT_DELTAS_PER_CALL {
+            return;
+        }
+        self.pending.extend_from_slice(chunk);
+        if self.pending.len() > MAX_EXEC_OUTPUT_DELTA_BYTES {
+            let excess = self.pending.len() - MAX_EXEC_OUTPUT_DELTA_BYTES;
+            self.pending.drain(..excess);
+            self.skipped_bytes += excess;
+        }
+    }
+
+    /// When buffered output may next be sent, or `None` if nothing is
+    /// buffered.
+    pub(crate) fn next_emit_at(&self) -> Option<tokio::time::Instant> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        Some(match self.last_emit {
+            Some(last) => last + Self::min_interval(),
+            None => tokio::time::Instant::now(),
+        })
+    }
+
+    /// Takes the buffered output if an event is due at `now`.
+    pub(crate) fn take_due(&mut self, now: tokio::time::Instant) -> Option<Vec<u8>> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        match self.last_emit {
+            Some(last) if now < last + Self::min_interval() => None,
+            _ => self.take(now),
+        }
+    }
+
+    /// Takes whatever is buffered regardless of the rate, for the end of
+    /// the stream.
+    pub(crate) fn take_all(&mut self) -> Option<Vec<u8>> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        self.take(tokio::time::Instant::now())
+    }
+
+    fn take(&mut self, now: tokio::time::Instant) -> Option<Vec<u8>> {
+        let mut chunk = Vec::with_capacity(self.pending.len() + 64);
+        if self.skipped_bytes > 0 {
+            chunk.extend_from_slice(
+                format!("\n[... {} bytes skipped ...]\n", self.skipped_bytes).as_bytes(),
+            );
+            self.skipped_bytes = 0;
+        }
+        chunk.append(&mut self.pending);
+        self.last_emit = Some(now);
+        self.emitted += 1;
+        Some(chunk)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ExecParams {
     pub command: Vec<String>,
@@ -166,6 +259,160 @@ pub(crate) async fn execute_exec_env(
     finalize_exec_result(raw_output_result, sandbox, duration)
 }
 
+/// Runs `env` attached to a pseudo-terminal of the given size, for commands
+/// that only behave when stdout is a TTY. The terminal merges stdout and
+/// stderr, so all output is reported as stdout, with escape sequences and
+/// carriage-return redraws removed.
+pub(crate) async fn execute_exec_env_in_pty(
+    env: ExecEnv,
+    size: TerminalSize,
+    stdout_stream: Option<StdoutStream>,
+) -> Result<ExecToolCallOutput> {
+    let timeout = Duration::from_millis(env.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS));
+    let (program, args) = env.command.split_first().ok_or_else(|| {
+        CodexErr::Io(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "command args are empty",
+        ))
+    })?;
+
+    let start = Instant::now();
+    let SpawnedPty {
+        session,
+        mut output_rx,
+        mut exit_rx,
+    } = spawn_pty_process_with_size(program, args, &env.cwd, &env.env, &env.arg0, size)
+        .await
+        .map_err(|err| CodexErr::Io(io::Error::other(format!("failed to start PTY: {err}"))))?;
+
+    let mut raw = Vec::with_capacity(AGGREGATE_BUFFER_INITIAL_CAPACITY);
+    let mut throttle = OutputDeltaThrottle::default();
+    let mut output_closed = false;
+    let deadline = tokio::time::sleep(timeout);
+    tokio::pin!(deadline);
+    let exit_code = loop {
+        let emit_at = throttle.next_emit_at();
+        tokio::select! {
+            chunk = output_rx.recv(), if !output_closed => match chunk {
+                Ok(chunk) => {
+                    if stdout_stream.is_some() {
+                        throttle.push(&chunk);
+                    }
+                    append_all(&mut raw, &chunk);
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => output_closed = true,
+            },
+            _ = tokio::time::sleep_until(emit_at.unwrap_or_else(tokio::time::Instant::now)),
+                if emit_at.is_some() => {}
+            code = &mut exit_rx => break Some(code.unwrap_or(-1)),
+            _ = &mut deadline => break None,
+        }
+        if let Some(chunk) = throttle.take_due(tokio::time::Instant::now()) {
+            send_output_delta(stdout_stream.as_ref(), ExecOutputStream::Stdout, chunk).await;
+        }
+    };
+    while let Ok(Ok(chunk)) = tokio::time::timeout(
+        Duration::from_millis(PTY_DRAIN_TIMEOUT_MS),
+        output_rx.recv(),
+    )
+    .await
+    {
+        if stdout_stream.is_some() {
+            throttle.push(&chunk);
+        }
+        append_all(&mut raw, &chunk);
+    }
+    if let Some(chunk) = throttle.take_all() {
+        send_output_delta(stdout_stream.as_ref(), ExecOutputStream::Stdout, chunk).await;
+    }
+    // Dropping the session kills the process if it is still running.
+    drop(session);
+    let duration = start.elapsed();
+
+    let (exit_status, timed_out) = match exit_code {
+        Some(code) => (exit_status_from_code(code), false),
+        None => (
+            synthetic_exit_status(EXIT_CODE_SIGNAL_BASE + TIMEOUT_CODE),
+            true,
+        ),
+    };
+    let text = strip_terminal_output(&String::from_utf8_lossy(&raw)).into_bytes();
+    let raw_output = RawExecToolCallOutput {
+        exit_status,
+        stdout: StreamOutput {
+            text: text.clone(),
+            truncated_after_lines: None,
+        },
+        stderr: StreamOutput {
+            text: Vec::new(),
+            truncated_after_lines: None,
+        },
+        aggregated_output: StreamOutput {
+            text,
+            truncated_after_lines: None,
+        },
+        timed_out,
+    };
+    finalize_exec_result(Ok(raw_output), env.sandbox, duration)
+}
+
+/// Removes ANSI escape sequences and other control characters from terminal
+/// output, and resolves carriage-return redraws (progress bars) to what the
+/// last redraw of each line left on screen.
+pub(crate) fn strip_terminal_output(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => match chars.next() {
+                // CSI: parameter and intermediate bytes up to a final byte.
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('\u{40}'..='\u{7e}').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC, e.g. window titles: up to BEL or ST (`ESC \`).
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\u{7}' {
+                            break;
+                        }
+                        if c == '\u{1b}' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                // Character set selection, e.g. `ESC ( B`.
+                Some('(' | ')') => {
+                    chars.next();
+                }
+                _ => {}
+            },
+            '\u{8}' => {
+                if !plain.ends_with('\n') {
+                    plain.pop();
+                }
+            }
+            '\n' | '\r' | '\t' => plain.push(c),
+            c if c.is_control() => {}
+            c => plain.push(c),
+        }
+    }
+
+    plain
+        .split('\n')
+        .map(|line| {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            line.rsplit('\r').next().unwrap_or(line)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 #[cfg(target_os = "windows")]
 async fn exec_windows_sandbox(
     params: ExecParams,
@@ -612,36 +859,39 @@ async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
 ) -> io::Result<StreamOutput<Vec<u8>>> {
     let mut buf = Vec::with_capacity(AGGREGATE_BUFFER_INITIAL_CAPACITY);
     let mut tmp = [0u8; READ_CHUNK_SIZE];
-    let mut emitted_deltas: usize = 0;
+    let mut throttle = OutputDeltaThrottle::default();
+    let output_stream = if is_stderr {
+        ExecOutputStream::Stderr
+    } else {
+        ExecOutputStream::Stdout
+    };
 
     // No caps: append all bytes
 
     loop {
-        let n = reader.read(&mut tmp).await?;
+        // Wake up for buffered live output even while the command is quiet.
+        let read = match throttle.next_emit_at() {
+            Some(emit_at) => tokio::select! {
+                read = reader.read(&mut tmp) => Some(read?),
+                _ = tokio::time::sleep_until(emit_at) => None,
+            },
+            None => Some(reader.read(&mut tmp).await?),
+        };
+        let Some(n) = read else {
+            if let Some(chunk) = throttle.take_due(tokio::time::Instant::now()) {
+                send_output_delta(stream.as_ref(), output_stream.clone(), chunk).await;
+            }
+            continue;
+        };
         if n == 0 {
             break;
         }
 
-        if let Some(stream) = &stream
-            && emitted_deltas < MAX_EXEC_OUTPUT_DELTAS_PER_CALL
-        {
-            let chunk = tmp[..n].to_vec();
-            let msg = EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
-                call_id: stream.call_id.clone(),
-                stream: if is_stderr {
-                    ExecOutputStream::Stderr
-                } else {
-                    ExecOutputStream::Stdout
-                },
-                chunk,
-            });
-            let event = Event {
-                id: stream.sub_id.clone(),
-                msg,
-            };
-            #[allow(clippy::let_unit_value)]
-            let _ = stream.tx_event.send(event).await;
-            emitted_deltas += 1;
+        if stream.is_some() {
+            throttle.push(&tmp[..n]);
+            if let Some(chunk) = throttle.take_due(tokio::time::Instant::now()) {
+                send_output_delta(stream.as_ref(), output_stream.clone(), chunk).await;
+            }
         }
 
         if let Some(tx) = &aggregate_tx {
@@ -652,12 +902,38 @@ async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
         // Continue reading to EOF to avoid back-pressure
     }
 
+    if let Some(chunk) = throttle.take_all() {
+        send_output_delta(stream.as_ref(), output_stream.clone(), chunk).await;
+    }
+
     Ok(StreamOutput {
         text: buf,
         truncated_after_lines: None,
     })
 }
 
+async fn send_output_delta(
+    stream: Option<&StdoutStream>,
+    output_stream: ExecOutputStream,
+    chunk: Vec<u8>,
+) {
+    let Some(stream) = stream else {
+        return;
+    };
+    let msg = EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
+        call_id: stream.call_id.clone(),
+        stream: output_stream,
+        chunk,
+        sub_agent: None,
+    });
+    let event = Event {
+        id: stream.sub_id.clone(),
+        msg,
+    };
+    #[allow(clippy::let_unit_value)]
+    let _ = stream.tx_event.send(event).await;
+}
+
 #[cfg(unix)]
 fn synthetic_exit_status(code: i32) -> ExitStatus {
     use std::os::unix::process::ExitStatusExt;
@@ -672,8 +948,20 @@ fn synthetic_exit_status(code: i32) -> ExitStatus {
     std::process::ExitStatus::from_raw(code as u32)
 }
 
+/// Builds an `ExitStatus` for a process that exited normally with `code`.
+#[cfg(unix)]
+fn exit_status_from_code(code: i32) -> ExitStatus {
+    // The raw wait status keeps the exit code in the second byte.
+    synthetic_exit_status((code & 0xff) << 8)
+}
+
+#[cfg(windows)]
+fn exit_status_from_code(code: i32) -> ExitStatus {
+    synthetic_exit_status(code)
+}
+
 #[cfg(unix)]
-fn kill_child_process_group(child: &mut Child) -> io::Result<()> {
+pub(crate) fn kill_child_process_group(child: &mut Child) -> io::Result<()> {
     use std::io::ErrorKind;
 
     if let Some(pid) = child.id() {
@@ -700,7 +988,7 @@ fn kill_child_process_group(child: &mut Child) -> io::Result<()> {
 }
 
 #[cfg(not(unix))]
-fn kill_child_process_group(_: &mut Child) -> io::Result<()> {
+pub(crate) fn kill_child_process_group(_: &mut Child) -> io::Result<()> {
     Ok(())
 }
 
@@ -725,6 +1013,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn output_delta_throttle_coalesces_and_caps_chunks() {
+        let mut throttle = OutputDeltaThrottle::default();
+        let start = tokio::time::Instant::now();
+
+        throttle.push(b"first");
+        assert_eq!(throttle.take_due(start), Some(b"first".to_vec()));
+
+        // Within the interval, output piles up instead of being sent.
+        throttle.push(b"second ");
+        throttle.push(b"third");
+        assert_eq!(throttle.take_due(start), None);
+        let due = start + OutputDeltaThrottle::min_interval();
+        assert_eq!(throttle.next_emit_at(), Some(due));
+        assert_eq!(throttle.take_due(due), Some(b"second third".to_vec()));
+        assert_eq!(throttle.next_emit_at(), None);
+
+        // Past the chunk cap, the oldest bytes are dropped and noted.
+        throttle.push(&vec![b'a'; MAX_EXEC_OUTPUT_DELTA_BYTES]);
+        throttle.push(b"tail");
+        let chunk = throttle.take_all().expect("buffered output");
+        let notice = b"\n[... 4 bytes skipped ...]\n";
+        assert!(chunk.starts_with(notice));
+        assert!(chunk.ends_with(b"tail"));
+        assert_eq!(chunk.len(), notice.len() + MAX_EXEC_OUTPUT_DELTA_BYTES);
+    }
+
     #[test]
     fn sandbox_detection_requires_keywords() {
         let output = make_exec_output(1, "", "", "");
@@ -823,4 +1138,41 @@ mod tests {
         assert!(killed, "grandchild process with pid {pid} is still alive");
         Ok(())
     }
+    #[test]
+    fn strip_terminal_output_removes_escapes_and_redraws() {
+        let text = "\u{1b}[32mok\u{1b}[0m\r\n 10%\r 50%\r100%\r\n\u{1b}]0;title\u{7}done\n";
+        assert_eq!(strip_terminal_output(text), "ok\n100%\ndone\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn pty_exec_runs_command_on_a_terminal() -> Result<()> {
+        let env = ExecEnv {
+            command: vec![
+                "/bin/sh".to_string(),
+                "-c".to_string(),
+                "test -t 1 && stty size && exit 3".to_string(),
+            ],
+            cwd: std::env::current_dir()?,
+            env: std::env::vars().collect(),
+            timeout_ms: Some(10_000),
+            sandbox: SandboxType::None,
+            with_escalated_permissions: None,
+            justification: None,
+            arg0: None,
+        };
+
+        let output = execute_exec_env_in_pty(
+            env,
+            TerminalSize {
+                rows: 40,
+                cols: 120,
+            },
+            None,
+        )
+        .await?;
+        assert_eq!(output.exit_code, 3);
+        assert_eq!(output.aggregated_output.text, "40 120\n");
+        Ok(())
+    }
 }
@@ -54,6 +54,8 @@ pub enum Feature {
     ShellTool,
     /// Allow model to call multiple tools in parallel (only for models supporting it).
     ParallelToolCalls,
+    /// Include the `coco` tool for delegating work to a sub-agent.
+    CocoTool,
 }
 
 impl Feature {
@@ -335,4 +337,10 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::Stable,
         default_enabled: true,
     },
+    FeatureSpec {
+        id: Feature::CocoTool,
+        key: "coco_tool",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
 ];
@@ -11,6 +11,7 @@ pub mod bash;
 mod chat_completions;
 mod client;
 mod client_common;
+pub mod coco_flow;
 pub mod codex;
 mod codex_conversation;
 mod compact_remote;
@@ -35,7 +36,9 @@ mod mcp_connection_manager;
 mod mcp_tool_call;
 mod message_history;
 mod model_provider_info;
+pub mod output_schema;
 pub mod parse_command;
+mod persistent_shell;
 pub mod powershell;
 mod response_processing;
 pub mod sandboxing;
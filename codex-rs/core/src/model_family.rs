@@ -146,11 +146,15 @@ pub fn find_family_for_model(slug: &str) -> Option<ModelFamily> {
             reasoning_summary_format: ReasoningSummaryFormat::Experimental,
             base_instructions: GPT_5_CODEX_INSTRUCTIONS.to_string(),
             experimental_supported_tools: vec![
+                "append_to_file".to_string(),
                 "delete".to_string(),
                 "grep_files".to_string(),
+                "insert_at_line".to_string(),
                 "list_dir".to_string(),
+                "multi_edit".to_string(),
                 "read_file".to_string(),
                 "replace".to_string(),
+                "replace_regex".to_string(),
                 "test_sync_tool".to_string(),
                 "write_file".to_string(),
             ],
@@ -237,10 +241,24 @@ pub fn find_family_for_model(slug: &str) -> Option<ModelFamily> {
             base_instructions: LEGACY_MODEL_INSTRUCTIONS.to_string(),
             apply_patch_tool_type: Some(ApplyPatchToolType::Freeform),
             experimental_supported_tools: vec![
+                "verify_patch".to_string(),
+                "glob".to_string(),
+                "grep".to_string(),
                 "read_file".to_string(),
                 "write_file".to_string(),
                 "replace".to_string(),
+                "replace_regex".to_string(),
+                "multi_edit".to_string(),
+                "insert_at_line".to_string(),
+                "append_to_file".to_string(),
                 "delete".to_string(),
+                "check_job".to_string(),
+                "kill_job".to_string(),
+                "git_status".to_string(),
+                "git_diff".to_string(),
+                "git_commit".to_string(),
+                "git_branch".to_string(),
+                "get_turn_diff".to_string(),
             ],
             supports_parallel_tool_calls: true,
         )
@@ -0,0 +1,190 @@
+//! Validation of model output against a JSON Schema.
+//!
+//! Covers the subset of JSON Schema accepted for structured outputs: `type`,
+//! `enum`, `const`, `properties`, `required`, `additionalProperties`,
+//! `items`, `anyOf`, and local `$ref`s. Other keywords are ignored.
+
+use serde_json::Value;
+
+/// Checks `value` against `schema`, returning a message naming the first
+/// mismatch (e.g. `$.items[0].name: expected string, got number`).
+pub fn validate_output(value: &Value, schema: &Value) -> Result<(), String> {
+    validate_at(value, schema, schema, "$")
+}
+
+fn validate_at(value: &Value, schema: &Value, root: &Value, path: &str) -> Result<(), String> {
+    let Some(schema) = schema.as_object() else {
+        // `true` accepts everything; `false` accepts nothing.
+        return match schema {
+            Value::Bool(false) => Err(format!("{path}: no value is allowed here")),
+            _ => Ok(()),
+        };
+    };
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let target = reference
+            .strip_prefix('#')
+            .and_then(|pointer| root.pointer(pointer))
+            .ok_or_else(|| format!("{path}: unsupported $ref `{reference}`"))?;
+        return validate_at(value, target, root, path);
+    }
+
+    if let Some(types) = schema.get("type") {
+        let allowed: Vec<&str> = match types {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|name| has_type(value, name)) {
+            return Err(format!(
+                "{path}: expected {}, got {}",
+                allowed.join(" or "),
+                type_name(value)
+            ));
+        }
+    }
+
+    if let Some(expected) = schema.get("const")
+        && expected != value
+    {
+        return Err(format!("{path}: expected {expected}"));
+    }
+
+    if let Some(Value::Array(options)) = schema.get("enum")
+        && !options.contains(value)
+    {
+        let options: Vec<String> = options.iter().map(Value::to_string).collect();
+        return Err(format!("{path}: expected one of {}", options.join(", ")));
+    }
+
+    if let Some(Value::Array(branches)) = schema.get("anyOf")
+        && !branches
+            .iter()
+            .any(|branch| validate_at(value, branch, root, path).is_ok())
+    {
+        return Err(format!("{path}: does not match any allowed schema"));
+    }
+
+    if let Value::Object(fields) = value {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !fields.contains_key(name) {
+                    return Err(format!("{path}: missing required property `{name}`"));
+                }
+            }
+        }
+        for (name, field) in fields {
+            let field_path = format!("{path}.{name}");
+            match properties.and_then(|properties| properties.get(name)) {
+                Some(field_schema) => validate_at(field, field_schema, root, &field_path)?,
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => {
+                        return Err(format!("{path}: unexpected property `{name}`"));
+                    }
+                    Some(extra_schema) => validate_at(field, extra_schema, root, &field_path)?,
+                    None => {}
+                },
+            }
+        }
+    }
+
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (index, item) in items.iter().enumerate() {
+            validate_at(item, item_schema, root, &format!("{path}[{index}]"))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "verdict": { "enum": ["pass", "fail"] },
+                "findings": {
+                    "type": "array",
+                    "items": { "$ref": "#/$defs/finding" }
+                }
+            },
+            "required": ["verdict", "findings"],
+            "additionalProperties": false,
+            "$defs": {
+                "finding": {
+                    "type": "object",
+                    "properties": {
+                        "file": { "type": "string" },
+                        "line": { "type": ["integer", "null"] }
+                    },
+                    "required": ["file"]
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn accepts_matching_output() {
+        let value = json!({
+            "verdict": "fail",
+            "findings": [{ "file": "src/lib.rs", "line": 3 }, { "file": "a", "line": null }]
+        });
+        assert_eq!(validate_output(&value, &schema()), Ok(()));
+    }
+
+    #[test]
+    fn reports_first_mismatch_with_path() {
+        assert_eq!(
+            validate_output(&json!({ "verdict": "fail" }), &schema()),
+            Err("$: missing required property `findings`".to_string())
+        );
+        assert_eq!(
+            validate_output(
+                &json!({ "verdict": "fail", "findings": [{ "file": 1 }] }),
+                &schema()
+            ),
+            Err("$.findings[0].file: expected string, got number".to_string())
+        );
+        assert_eq!(
+            validate_output(&json!({ "verdict": "maybe", "findings": [] }), &schema()),
+            Err("$.verdict: expected one of \"pass\", \"fail\"".to_string())
+        );
+        assert_eq!(
+            validate_output(
+                &json!({ "verdict": "pass", "findings": [], "extra": true }),
+                &schema()
+            ),
+            Err("$: unexpected property `extra`".to_string())
+        );
+    }
+}
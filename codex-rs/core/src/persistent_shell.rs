@@ -0,0 +1,416 @@
+//! A long-lived shell per conversation, so `cd`, exported variables, and
+//! virtualenv activation carry over between shell tool calls.
+//!
+//! Each command is written to the shell's stdin, followed by a marker line
+//! on stdout and stderr that reports its exit status and working directory;
+//! output is collected up to those markers. The shell is spawned from the
+//! sandbox-transformed `ExecEnv` of the command that first needs it, and is
+//! replaced (keeping only its working directory) when a command runs under a
+//! different sandbox, or after a command times out, is interrupted, or exits
+//! the shell.
+
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+
+use rand::Rng;
+use rand::rng;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Child;
+use tokio::process::ChildStdin;
+use tokio::sync::Mutex;
+use tokio::sync::mpsc;
+
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::error::SandboxErr;
+use crate::exec::ExecToolCallOutput;
+use crate::exec::SandboxType;
+use crate::exec::StreamOutput;
+use crate::exec::is_likely_sandbox_denied;
+use crate::exec::kill_child_process_group;
+use crate::protocol::SandboxPolicy;
+use crate::sandboxing::ExecEnv;
+use crate::spawn::StdioPolicy;
+use crate::spawn::spawn_child_async;
+
+const READ_CHUNK_SIZE: usize = 8192;
+const EXEC_TIMEOUT_EXIT_CODE: i32 = 124;
+
+/// A command for the session's persistent shell.
+#[derive(Clone, Debug)]
+pub(crate) struct PersistentShellCommand {
+    /// Command that starts the shell when none is running, e.g.
+    /// `["/bin/zsh", "-l"]`.
+    pub shell: Vec<String>,
+    /// Shell script to run.
+    pub script: String,
+    /// Directory to `cd` into first; the shell's current directory when
+    /// unset.
+    pub workdir: Option<PathBuf>,
+}
+
+/// The session's persistent shell, if one is running.
+pub(crate) type PersistentShellSlot = Mutex<Option<PersistentShell>>;
+
+pub(crate) struct PersistentShell {
+    child: Child,
+    stdin: ChildStdin,
+    /// Output chunks from the shell, tagged with whether they came from
+    /// stderr. Closed once both pipes are.
+    output_rx: mpsc::UnboundedReceiver<(bool, Vec<u8>)>,
+    sandbox: SandboxType,
+    policy: SandboxPolicy,
+    /// Working directory reported after the last command.
+    cwd: PathBuf,
+    /// Set while a command runs; still set if its caller was dropped
+    /// midway, which leaves the shell unusable.
+    busy: bool,
+}
+
+enum CommandOutcome {
+    Finished { exit_code: i32, cwd: PathBuf },
+    ShellExited { exit_code: i32 },
+    TimedOut,
+}
+
+/// Runs `command` in the persistent shell held by `slot`, starting the shell
+/// from `spawn_env` when there is none that can be reused.
+pub(crate) async fn run_in_persistent_shell(
+    slot: &PersistentShellSlot,
+    command: &PersistentShellCommand,
+    spawn_env: &ExecEnv,
+    policy: &SandboxPolicy,
+    timeout: Duration,
+) -> Result<ExecToolCallOutput> {
+    let mut guard = slot.lock().await;
+    let reusable = guard
+        .as_ref()
+        .is_some_and(|shell| shell.can_run(spawn_env.sandbox, policy));
+    if !reusable {
+        let cwd = guard.take().map(|shell| shell.cwd.clone());
+        *guard = Some(PersistentShell::spawn(spawn_env, policy, cwd).await?);
+    }
+    let Some(shell) = guard.as_mut() else {
+        return Err(CodexErr::Io(std::io::Error::other(
+            "persistent shell is not running",
+        )));
+    };
+
+    let start = Instant::now();
+    let mut output = CollectedOutput::default();
+    let outcome = shell.run(command, timeout, &mut output).await;
+    let duration = start.elapsed();
+
+    let (exit_code, timed_out) = match outcome {
+        Ok(CommandOutcome::Finished { exit_code, cwd }) => {
+            shell.cwd = cwd;
+            (exit_code, false)
+        }
+        Ok(CommandOutcome::ShellExited { exit_code }) => {
+            *guard = None;
+            (exit_code, false)
+        }
+        Ok(CommandOutcome::TimedOut) => {
+            *guard = None;
+            (EXEC_TIMEOUT_EXIT_CODE, true)
+        }
+        Err(err) => {
+            *guard = None;
+            return Err(err);
+        }
+    };
+
+    let exec_output = ExecToolCallOutput {
+        exit_code,
+        stdout: StreamOutput::new(String::from_utf8_lossy(&output.stdout).to_string()),
+        stderr: StreamOutput::new(String::from_utf8_lossy(&output.stderr).to_string()),
+        aggregated_output: StreamOutput::new(
+            String::from_utf8_lossy(&output.aggregated()).to_string(),
+        ),
+        duration,
+        timed_out,
+    };
+    if timed_out {
+        return Err(CodexErr::Sandbox(SandboxErr::Timeout {
+            output: Box::new(exec_output),
+        }));
+    }
+    if is_likely_sandbox_denied(spawn_env.sandbox, &exec_output) {
+        return Err(CodexErr::Sandbox(SandboxErr::Denied {
+            output: Box::new(exec_output),
+        }));
+    }
+    Ok(exec_output)
+}
+
+impl PersistentShell {
+    async fn spawn(env: &ExecEnv, policy: &SandboxPolicy, cwd: Option<PathBuf>) -> Result<Self> {
+        let (program, args) = env.command.split_first().ok_or_else(|| {
+            CodexErr::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "command args are empty",
+            ))
+        })?;
+        let cwd = cwd
+            .filter(|cwd| cwd.is_dir())
+            .unwrap_or_else(|| env.cwd.clone());
+        let mut child = spawn_child_async(
+            PathBuf::from(program),
+            args.to_vec(),
+            env.arg0.as_deref(),
+            cwd.clone(),
+            policy,
+            StdioPolicy::PipedForPersistentShell,
+            env.env.clone(),
+        )
+        .await?;
+
+        let missing_pipe = |name: &str| {
+            CodexErr::Io(std::io::Error::other(format!(
+                "{name} pipe was not available"
+            )))
+        };
+        let stdin = child.stdin.take().ok_or_else(|| missing_pipe("stdin"))?;
+        let stdout = child.stdout.take().ok_or_else(|| missing_pipe("stdout"))?;
+        let stderr = child.stderr.take().ok_or_else(|| missing_pipe("stderr"))?;
+        let (output_tx, output_rx) = mpsc::unbounded_channel();
+        spawn_reader(stdout, false, output_tx.clone());
+        spawn_reader(stderr, true, output_tx);
+
+        Ok(Self {
+            child,
+            stdin,
+            output_rx,
+            sandbox: env.sandbox,
+            policy: policy.clone(),
+            cwd,
+            busy: false,
+        })
+    }
+
+    fn can_run(&self, sandbox: SandboxType, policy: &SandboxPolicy) -> bool {
+        !self.busy && self.sandbox == sandbox && &self.policy == policy
+    }
+
+    async fn run(
+        &mut self,
+        command: &PersistentShellCommand,
+        timeout: Duration,
+        output: &mut CollectedOutput,
+    ) -> Result<CommandOutcome> {
+        let marker = format!("__CODEX_PERSISTENT_SHELL_{:016x}__", rng().random::<u64>());
+        self.busy = true;
+        self.stdin
+            .write_all(wrap_command(command, &marker).as_bytes())
+            .await?;
+        self.stdin.flush().await?;
+
+        let stdout_marker = format!("\n{marker} ");
+        let stderr_marker = format!("\n{marker}\n");
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            match tokio::time::timeout_at(deadline, self.output_rx.recv()).await {
+                Ok(Some((is_stderr, chunk))) => output.push(is_stderr, chunk),
+                Ok(None) => {
+                    let status = self.child.wait().await?;
+                    return Ok(CommandOutcome::ShellExited {
+                        exit_code: status.code().unwrap_or(-1),
+                    });
+                }
+                Err(_) => return Ok(CommandOutcome::TimedOut),
+            }
+
+            let Some(stdout_end) = find(&output.stdout, stdout_marker.as_bytes()) else {
+                continue;
+            };
+            let Some(stderr_end) = find(&output.stderr, stderr_marker.as_bytes()) else {
+                continue;
+            };
+            let status_start = stdout_end + stdout_marker.len();
+            let Some(status_len) = output.stdout[status_start..]
+                .iter()
+                .position(|byte| *byte == b'\n')
+            else {
+                continue;
+            };
+            let status_line =
+                String::from_utf8_lossy(&output.stdout[status_start..status_start + status_len])
+                    .to_string();
+            output.truncate(stdout_end, stderr_end);
+            self.busy = false;
+
+            let (exit_code, cwd) = status_line.split_once(' ').unwrap_or((&status_line, ""));
+            return Ok(CommandOutcome::Finished {
+                exit_code: exit_code.parse().unwrap_or(-1),
+                cwd: if cwd.is_empty() {
+                    self.cwd.clone()
+                } else {
+                    PathBuf::from(cwd)
+                },
+            });
+        }
+    }
+}
+
+impl Drop for PersistentShell {
+    fn drop(&mut self) {
+        // The shell is killed on drop; also stop anything it left running.
+        let _ = kill_child_process_group(&mut self.child);
+    }
+}
+
+/// Output of one command, in the order it arrived.
+#[derive(Default)]
+struct CollectedOutput {
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    chunks: Vec<(bool, usize)>,
+}
+
+impl CollectedOutput {
+    fn push(&mut self, is_stderr: bool, chunk: Vec<u8>) {
+        self.chunks.push((is_stderr, chunk.len()));
+        if is_stderr {
+            self.stderr.extend_from_slice(&chunk);
+        } else {
+            self.stdout.extend_from_slice(&chunk);
+        }
+    }
+
+    /// Drops the marker lines and anything after them.
+    fn truncate(&mut self, stdout_len: usize, stderr_len: usize) {
+        self.stdout.truncate(stdout_len);
+        self.stderr.truncate(stderr_len);
+    }
+
+    /// Interleaves stdout and stderr in arrival order.
+    fn aggregated(&self) -> Vec<u8> {
+        let mut aggregated = Vec::with_capacity(self.stdout.len() + self.stderr.len());
+        let (mut stdout_pos, mut stderr_pos) = (0, 0);
+        for (is_stderr, len) in &self.chunks {
+            let (source, pos) = if *is_stderr {
+                (&self.stderr, &mut stderr_pos)
+            } else {
+                (&self.stdout, &mut stdout_pos)
+            };
+            let end = (*pos + len).min(source.len());
+            aggregated.extend_from_slice(&source[*pos..end]);
+            *pos = end;
+        }
+        aggregated
+    }
+}
+
+/// Wraps `command` so it reads stdin from `/dev/null`, then reports its exit
+/// status and the shell's working directory after `marker`. The brace group
+/// keeps `cd` and `export` in the shell itself.
+fn wrap_command(command: &PersistentShellCommand, marker: &str) -> String {
+    let cd = command
+        .workdir
+        .as_ref()
+        .map(|dir| {
+            let dir = dir.to_string_lossy();
+            let quoted = shlex::try_quote(&dir).unwrap_or(dir.clone());
+            format!("cd -- {quoted} && ")
+        })
+        .unwrap_or_default();
+    format!(
+        "{{ {cd}{{\n:\n{script}\n}}; }} < /dev/null\n\
+         __codex_status=$?\n\
+         printf '\\n%s %s %s\\n' '{marker}' \"$__codex_status\" \"$PWD\"\n\
+         printf '\\n%s\\n' '{marker}' >&2\n",
+        script = command.script,
+    )
+}
+
+fn spawn_reader<R: AsyncRead + Unpin + Send + 'static>(
+    mut reader: R,
+    is_stderr: bool,
+    output_tx: mpsc::UnboundedSender<(bool, Vec<u8>)>,
+) {
+    tokio::spawn(async move {
+        let mut buf = [0u8; READ_CHUNK_SIZE];
+        loop {
+            match reader.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if output_tx.send((is_stderr, buf[..n].to_vec())).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn shell_env(cwd: PathBuf) -> ExecEnv {
+        ExecEnv {
+            command: vec!["/bin/sh".to_string()],
+            cwd,
+            env: std::env::vars().collect(),
+            timeout_ms: None,
+            sandbox: SandboxType::None,
+            with_escalated_permissions: None,
+            justification: None,
+            arg0: None,
+        }
+    }
+
+    async fn run(slot: &PersistentShellSlot, env: &ExecEnv, script: &str) -> ExecToolCallOutput {
+        let command = PersistentShellCommand {
+            shell: env.command.clone(),
+            script: script.to_string(),
+            workdir: None,
+        };
+        let result = run_in_persistent_shell(
+            slot,
+            &command,
+            env,
+            &SandboxPolicy::DangerFullAccess,
+            Duration::from_secs(10),
+        )
+        .await;
+        match result {
+            Ok(output) => output,
+            Err(err) => panic!("persistent shell command failed: {err}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn persistent_shell_keeps_cwd_and_exports_between_commands() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir(dir.path().join("sub")).expect("create sub");
+        let env = shell_env(dir.path().to_path_buf());
+        let slot = PersistentShellSlot::default();
+
+        let first = run(&slot, &env, "cd sub && export GREETING=hi\necho ready").await;
+        assert_eq!(first.exit_code, 0);
+        assert_eq!(first.aggregated_output.text, "ready\n");
+
+        let second = run(&slot, &env, "printf '%s %s' \"$GREETING\" \"${PWD##*/}\"").await;
+        assert_eq!(second.aggregated_output.text, "hi sub");
+
+        let failed = run(&slot, &env, "echo oops >&2; false").await;
+        assert_eq!(failed.exit_code, 1);
+        assert_eq!(failed.stderr.text, "oops\n");
+
+        let exited = run(&slot, &env, "exit 7").await;
+        assert_eq!(exited.exit_code, 7);
+        let restarted = run(&slot, &env, "echo \"${GREETING:-unset}\"").await;
+        assert_eq!(restarted.aggregated_output.text, "unset\n");
+    }
+}
@@ -80,6 +80,10 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::ShutdownComplete
         | EventMsg::ViewImageToolCall(_)
         | EventMsg::DeprecationNotice(_)
+        | EventMsg::AccountsUsageLimited(_)
+        | EventMsg::SubAgentSummary(_)
+        | EventMsg::ToolCallMetrics(_)
+        | EventMsg::ToolUsageSummary(_)
         | EventMsg::ItemStarted(_)
         | EventMsg::ItemCompleted(_)
         | EventMsg::AgentMessageContentDelta(_)
@@ -118,6 +118,7 @@ pub(crate) async fn assess_command(
         }],
         tools: Vec::new(),
         parallel_tool_calls: false,
+        apply_patch_disabled: false,
         base_instructions_override: Some(system_prompt),
         output_schema: Some(sandbox_assessment_schema()),
     };
@@ -12,6 +12,7 @@ use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
 use crate::exec::StdoutStream;
 use crate::exec::execute_exec_env;
+use crate::exec::execute_exec_env_in_pty;
 use crate::landlock::create_linux_sandbox_command_args;
 use crate::protocol::SandboxPolicy;
 #[cfg(target_os = "macos")]
@@ -22,6 +23,7 @@ use crate::seatbelt::create_seatbelt_command_args;
 use crate::spawn::CODEX_SANDBOX_ENV_VAR;
 use crate::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
 use crate::tools::sandboxing::SandboxablePreference;
+use codex_utils_pty::TerminalSize;
 use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
@@ -198,3 +200,20 @@ pub async fn execute_env(
 ) -> crate::error::Result<ExecToolCallOutput> {
     execute_exec_env(env.clone(), policy, stdout_stream).await
 }
+
+/// Like [`execute_env`], but with the command attached to a pseudo-terminal.
+/// The Windows restricted-token sandbox cannot host a PTY, so commands under
+/// it run without one.
+pub async fn execute_env_in_pty(
+    env: &ExecEnv,
+    policy: &SandboxPolicy,
+    size: TerminalSize,
+    stdout_stream: Option<StdoutStream>,
+) -> crate::error::Result<ExecToolCallOutput> {
+    if env.sandbox == SandboxType::WindowsRestrictedToken
+        && !matches!(policy, SandboxPolicy::DangerFullAccess)
+    {
+        return execute_exec_env(env.clone(), policy, stdout_stream).await;
+    }
+    execute_exec_env_in_pty(env.clone(), size, stdout_stream).await
+}
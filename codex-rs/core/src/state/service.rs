@@ -3,12 +3,17 @@ use std::sync::Arc;
 use crate::AuthManager;
 use crate::RolloutRecorder;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::persistent_shell::PersistentShellSlot;
+use crate::tools::coco_subagent::CocoResultCache;
+use crate::tools::coco_subagent::CocoStats;
+use crate::tools::file_reads::FileReadTracker;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_notification::UserNotifier;
 use codex_otel::otel_event_manager::OtelEventManager;
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
+use tokio::sync::Semaphore;
 use tokio_util::sync::CancellationToken;
 
 pub(crate) struct SessionServices {
@@ -22,4 +27,12 @@ pub(crate) struct SessionServices {
     pub(crate) auth_manager: Arc<AuthManager>,
     pub(crate) otel_event_manager: OtelEventManager,
     pub(crate) tool_approvals: Mutex<ApprovalStore>,
+    pub(crate) coco_results: Mutex<CocoResultCache>,
+    pub(crate) coco_stats: Mutex<CocoStats>,
+    /// Bounds how many `coco` sub-agents this session runs at once.
+    pub(crate) coco_slots: Semaphore,
+    /// Long-lived shell for `persistent_shell` mode.
+    pub(crate) persistent_shell: PersistentShellSlot,
+    /// What the model last saw of each file, for stale-read checks on edits.
+    pub(crate) file_reads: Mutex<FileReadTracker>,
 }
@@ -168,6 +168,15 @@ impl Session {
             *active = None;
         }
         drop(active);
+        let summary = self.services.coco_stats.lock().await.take_summary();
+        if let Some(summary) = summary {
+            self.send_event(turn_context.as_ref(), EventMsg::SubAgentSummary(summary))
+                .await;
+        }
+        if let Some(summary) = turn_context.tool_usage.take_summary() {
+            self.send_event(turn_context.as_ref(), EventMsg::ToolUsageSummary(summary))
+                .await;
+        }
         let event = EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message });
         self.send_event(turn_context.as_ref(), event).await;
     }
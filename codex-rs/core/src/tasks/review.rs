@@ -110,6 +110,7 @@ async fn start_review_conversation(
         sub_agent_config,
         session.auth_manager(),
         input,
+        None,
         session.clone_session(),
         ctx.clone(),
         cancellation_token,
@@ -146,7 +147,8 @@ async fn process_review_events(
                 ..
             })
             | EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { .. })
-            | EventMsg::AgentMessageContentDelta(AgentMessageContentDeltaEvent { .. }) => {}
+            | EventMsg::AgentMessageContentDelta(AgentMessageContentDeltaEvent { .. })
+            | EventMsg::SessionConfigured(_) => {}
             EventMsg::TaskComplete(task_complete) => {
                 // Parse review output from the last agent message (if present).
                 let out = task_complete
@@ -1,111 +1,732 @@
+use std::collections::HashMap;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
 use async_channel::Receiver;
+use codex_protocol::ConversationId;
+use codex_protocol::config_types::SandboxMode;
+use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::protocol::InitialHistory;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::SessionSource;
 use codex_protocol::protocol::SubAgentSource;
 use codex_protocol::protocol::TurnAbortReason;
 use codex_protocol::user_input::UserInput;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use serde_json::json;
 use shlex::split;
 use shlex::try_join;
 use tokio::time;
 use tokio_util::sync::CancellationToken;
+use tracing::warn;
 
 use crate::codex::TurnContext;
 use crate::codex_delegate::run_codex_conversation_one_shot;
+use crate::config::Config;
+use crate::config::types::SubAgentProfile;
 use crate::exec::ExecParams;
 use crate::exec::ExecToolCallOutput;
+use crate::exec::OutputDeltaThrottle;
 use crate::exec::StreamOutput;
+use crate::exec_policy::create_approval_requirement_for_command;
 use crate::function_tool::FunctionCallError;
+use crate::model_family::find_family_for_model;
+use crate::openai_model_info::get_model_info;
+use crate::output_schema::validate_output;
+use crate::protocol::AskForApproval;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandOutputDeltaEvent;
+use crate::protocol::ExecCommandSource;
 use crate::protocol::ExecOutputStream;
+use crate::protocol::SandboxPolicy;
+use crate::protocol::SubAgentOutputSource;
+use crate::protocol::SubAgentSummaryEvent;
+use crate::protocol::TokenUsage;
+use crate::protocol::WritableRoot;
+use crate::rollout::RolloutRecorder;
+use crate::rollout::find_conversation_path_by_id_str;
+use crate::sandboxing::SandboxPermissions;
 use crate::tools::context::ToolOutput;
 use crate::tools::events::ToolEmitter;
 use crate::tools::events::ToolEventCtx;
 use crate::tools::events::ToolEventFailure;
 use crate::tools::events::ToolEventStage;
-use crate::tools::format_exec_output_for_model;
+use crate::tools::format_exec_output_for_model_freeform;
+use crate::tools::format_exec_output_for_model_structured;
+use crate::tools::orchestrator::ToolOrchestrator;
+use crate::tools::runtimes::shell::ShellRequest;
+use crate::tools::runtimes::shell::ShellRuntime;
+use crate::tools::sandboxing::ToolCtx;
+use crate::tools::sandboxing::ToolError;
+use crate::util::backoff;
 
 const COCO_BINARY_BASENAMES: &[&str] = &["coco", "coco.exe", "cocos", "cocos.exe"];
 const COCO_TRUNCATION_NOTICE: &str = "[... coco exec output truncated ...]";
-const MAX_COCO_CAPTURED_LINES: usize = 200;
-const COCO_SUB_AGENT_LABEL: &str = "coco";
+const COCO_PARENT_ABORT_NOTICE: &str = "sub-agent aborted by parent";
+/// Directory under CODEX_HOME holding full logs of truncated coco runs.
+const COCO_LOGS_DIR: &str = "coco-logs";
 
 pub(crate) async fn maybe_run_coco_command(
     exec_params: &ExecParams,
     session: &Arc<crate::codex::Session>,
     turn: &Arc<TurnContext>,
     call_id: &str,
-    is_user_shell_command: bool,
+    freeform: bool,
 ) -> Result<Option<ToolOutput>, FunctionCallError> {
-    let Some(invocation) = CocoInvocation::parse(&exec_params.command) else {
+    let (invocation, producer) = match split_coco_pipeline(&exec_params.command) {
+        Some((producer, tokens)) => (CocoInvocation::parse(&tokens), Some(producer)),
+        None => (CocoInvocation::parse(&exec_params.command), None),
+    };
+    let Some(invocation) = invocation else {
         return Ok(None);
     };
-
-    if invocation.prompt().trim().is_empty() {
-        return Err(FunctionCallError::RespondToModel(
-            "coco command requires a prompt argument.".to_string(),
-        ));
+    if let Some(workflow) = &invocation.flow_workflow {
+        if producer.is_some() {
+            return Err(FunctionCallError::RespondToModel(
+                "coco flow run does not read from a pipe; pass the workflow file as an argument"
+                    .to_string(),
+            ));
+        }
+        let output = run_coco_flow(
+            exec_params.cwd.join(workflow),
+            exec_params,
+            session,
+            turn,
+            call_id,
+            freeform,
+        )
+        .await?;
+        return Ok(Some(output));
     }
 
-    let output = run_coco_command(
-        &invocation,
-        exec_params,
-        session,
-        turn,
-        call_id,
-        is_user_shell_command,
-    )
-    .await?;
+    let sandbox_mode = invocation
+        .sandbox
+        .as_deref()
+        .map(|value| parse_flag_value("--sandbox", value))
+        .transpose()?;
+    let approval_policy = invocation
+        .approval
+        .as_deref()
+        .map(|value| parse_flag_value("--approval", value))
+        .transpose()?;
+    let max_output_lines = invocation
+        .max_lines
+        .as_deref()
+        .map(|value| parse_limit("--max-lines", value))
+        .transpose()?;
+    let max_output_bytes = invocation
+        .max_bytes
+        .as_deref()
+        .map(|value| parse_limit("--max-bytes", value))
+        .transpose()?;
+    let timeout_secs = invocation
+        .timeout
+        .as_deref()
+        .map(|value| parse_limit("--timeout", value))
+        .transpose()?;
+    let batch = invocation.batch;
+    // Like the `coco` tool, `--file` and `--output-schema` are relative to
+    // the sub-agent's working directory.
+    let cwd = match &invocation.cwd {
+        Some(dir) => exec_params.cwd.join(dir),
+        None => exec_params.cwd.clone(),
+    };
+    let request = CocoRequest {
+        prompt: invocation.prompt,
+        files: invocation.files.iter().map(|file| cwd.join(file)).collect(),
+        agent: invocation.agent,
+        sandbox_mode,
+        approval_policy,
+        max_output_lines,
+        max_output_bytes,
+        model: invocation.model,
+        resume: invocation.resume,
+        output_schema: invocation.output_schema.map(|path| cwd.join(path)),
+        timeout_ms: timeout_secs
+            .map(|secs| (secs as u64).saturating_mul(1000))
+            .or(exec_params.timeout_ms),
+        tools: invocation.tools.as_deref().map(split_tool_list),
+        disabled_tools: invocation
+            .disabled_tools
+            .as_deref()
+            .map(split_tool_list)
+            .unwrap_or_default(),
+        cwd,
+        command: exec_params.command.clone(),
+    };
+    let output = match (batch, producer) {
+        (true, Some(producer)) => {
+            let prompts =
+                read_batch_prompts(&producer, exec_params, session, turn, call_id).await?;
+            run_coco_batch(request, prompts, session, turn, call_id, freeform).await?
+        }
+        (true, None) => {
+            return Err(FunctionCallError::RespondToModel(
+                "coco --batch reads its prompts from a piped command, e.g. `cat prompts.txt | coco --batch`".to_string(),
+            ));
+        }
+        (false, Some(_)) => {
+            return Err(FunctionCallError::RespondToModel(
+                "only `coco --batch` reads prompts from a pipe; pass the prompt as arguments instead"
+                    .to_string(),
+            ));
+        }
+        (false, None) => run_coco_request(request, session, turn, call_id, freeform).await?,
+    };
 
     Ok(Some(output))
 }
 
-#[derive(Debug)]
+/// One sub-agent run, requested either by a `coco ...` shell command or by
+/// the `coco` function tool.
+#[derive(Debug, Clone)]
+pub(crate) struct CocoRequest {
+    pub(crate) prompt: String,
+    pub(crate) cwd: PathBuf,
+    /// Files attached to the prompt as extra inputs, already resolved
+    /// against the caller's working directory.
+    pub(crate) files: Vec<PathBuf>,
+    /// Name of the `[agents.<name>]` profile the sub-agent runs with.
+    pub(crate) agent: Option<String>,
+    /// Sandbox for the sub-agent; may not be looser than the parent's.
+    pub(crate) sandbox_mode: Option<SandboxMode>,
+    pub(crate) approval_policy: Option<AskForApproval>,
+    /// Override `coco.max_output_lines` / `coco.max_output_bytes`.
+    pub(crate) max_output_lines: Option<usize>,
+    pub(crate) max_output_bytes: Option<usize>,
+    /// Runs the sub-agent on this model instead of the parent's.
+    pub(crate) model: Option<String>,
+    pub(crate) timeout_ms: Option<u64>,
+    /// Id of an earlier sub-agent conversation of this session to continue.
+    pub(crate) resume: Option<String>,
+    /// JSON Schema file the sub-agent's final message must match.
+    pub(crate) output_schema: Option<PathBuf>,
+    /// Limits the sub-agent to these tools, within what `coco.tools` and
+    /// the agent profile already allow.
+    pub(crate) tools: Option<Vec<String>>,
+    /// Tools the sub-agent may not call, on top of `coco.disabled_tools`.
+    pub(crate) disabled_tools: Vec<String>,
+    /// Command reported in the exec begin/end events.
+    pub(crate) command: Vec<String>,
+}
+
+#[derive(Debug, Default)]
 struct CocoInvocation {
     prompt: String,
+    agent: Option<String>,
+    cwd: Option<String>,
+    files: Vec<String>,
+    sandbox: Option<String>,
+    approval: Option<String>,
+    max_lines: Option<String>,
+    max_bytes: Option<String>,
+    model: Option<String>,
+    resume: Option<String>,
+    output_schema: Option<String>,
+    timeout: Option<String>,
+    tools: Option<String>,
+    disabled_tools: Option<String>,
+    /// `--batch`: run once per prompt read from the piped command.
+    batch: bool,
+    /// Workflow file of `coco flow run <workflow.toml>`.
+    flow_workflow: Option<String>,
 }
 
 impl CocoInvocation {
+    /// Reads the `--agent`, `--cwd`, `--model`, `--sandbox`, `--approval`,
+    /// `--max-lines`, `--max-bytes`, `--output-schema`, `--timeout`,
+    /// `--tools`, `--disabled-tools`, and repeatable `--file` options, plus
+    /// the `--batch` switch, that precede the prompt; everything after
+    /// them is the prompt. A leading `resume <id>` continues an earlier
+    /// sub-agent conversation, and `flow run <workflow.toml>` runs a
+    /// codex-flow workflow instead of a sub-agent.
     fn parse(command: &[String]) -> Option<Self> {
         let tokens = parse_coco_tokens(command)?;
-        let prompt = if tokens.len() <= 1 {
-            String::new()
-        } else {
-            tokens[1..].join(" ")
-        };
-        Some(Self { prompt })
+        let mut args = tokens.get(1..).unwrap_or_default();
+        if let [flow, run, workflow] = args
+            && flow == "flow"
+            && run == "run"
+        {
+            return Some(Self {
+                flow_workflow: Some(workflow.clone()),
+                ..Self::default()
+            });
+        }
+        let mut resume = None;
+        if args.len() >= 2 && args[0] == "resume" {
+            resume = Some(args[1].clone());
+            args = &args[2..];
+        }
+        let mut agent = None;
+        let mut cwd = None;
+        let mut files = Vec::new();
+        let mut sandbox = None;
+        let mut approval = None;
+        let mut max_lines = None;
+        let mut max_bytes = None;
+        let mut model = None;
+        let mut output_schema = None;
+        let mut timeout = None;
+        let mut tools = None;
+        let mut disabled_tools = None;
+        let mut batch = false;
+        while let Some(first) = args.first() {
+            if first == "--batch" {
+                batch = true;
+                args = &args[1..];
+                continue;
+            }
+            let (flag, value, consumed) = match first.split_once('=') {
+                Some((flag, value)) => (flag, value.to_string(), 1),
+                None if args.len() >= 2 => (first.as_str(), args[1].clone(), 2),
+                None => break,
+            };
+            match flag {
+                "--agent" => agent = Some(value),
+                "--cwd" => cwd = Some(value),
+                "--file" => files.push(value),
+                "--sandbox" => sandbox = Some(value),
+                "--approval" => approval = Some(value),
+                "--max-lines" => max_lines = Some(value),
+                "--max-bytes" => max_bytes = Some(value),
+                "--model" => model = Some(value),
+                "--output-schema" => output_schema = Some(value),
+                "--timeout" => timeout = Some(value),
+                "--tools" => tools = Some(value),
+                "--disabled-tools" => disabled_tools = Some(value),
+                _ => break,
+            }
+            args = &args[consumed..];
+        }
+        Some(Self {
+            prompt: args.join(" "),
+            agent,
+            cwd,
+            files,
+            sandbox,
+            approval,
+            max_lines,
+            max_bytes,
+            model,
+            resume,
+            output_schema,
+            timeout,
+            tools,
+            disabled_tools,
+            batch,
+            flow_workflow: None,
+        })
+    }
+}
+
+/// Splits a comma-separated `--tools` / `--disabled-tools` value.
+fn split_tool_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|tool| !tool.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_flag_value<T: DeserializeOwned>(flag: &str, value: &str) -> Result<T, FunctionCallError> {
+    serde_json::from_value(serde_json::Value::String(value.to_string())).map_err(|_| {
+        FunctionCallError::RespondToModel(format!("invalid value `{value}` for coco {flag}"))
+    })
+}
+
+fn parse_limit(flag: &str, value: &str) -> Result<usize, FunctionCallError> {
+    value.parse().map_err(|_| {
+        FunctionCallError::RespondToModel(format!("invalid value `{value}` for coco {flag}"))
+    })
+}
+
+/// Runs the command piped into `coco --batch` and returns one prompt per
+/// line. Only a single command is accepted; it is run directly, without a
+/// shell, under the turn's sandbox and approval policy like any other
+/// command the model runs.
+async fn read_batch_prompts(
+    producer: &[String],
+    exec_params: &ExecParams,
+    session: &Arc<crate::codex::Session>,
+    turn: &Arc<TurnContext>,
+    call_id: &str,
+) -> Result<Vec<String>, FunctionCallError> {
+    let is_single_command = !producer
+        .iter()
+        .any(|token| matches!(token.as_str(), "|" | "||" | "&&" | ";"));
+    if !is_single_command {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "coco --batch only reads prompts from a single command such as `cat prompts.txt`, not `{}`",
+            join_command(producer)
+        )));
+    }
+    let req = ShellRequest {
+        command: producer.to_vec(),
+        cwd: exec_params.cwd.clone(),
+        timeout_ms: exec_params.timeout_ms,
+        env: exec_params.env.clone(),
+        with_escalated_permissions: None,
+        justification: None,
+        approval_requirement: create_approval_requirement_for_command(
+            &turn.exec_policy,
+            producer,
+            turn.approval_policy,
+            &turn.sandbox_policy,
+            SandboxPermissions::UseDefault,
+        ),
+        pty: None,
+        persistent: None,
+        sandbox_policy: None,
+    };
+    let tool_ctx = ToolCtx {
+        session: session.as_ref(),
+        turn: turn.as_ref(),
+        call_id: call_id.to_string(),
+        tool_name: "coco".to_string(),
+    };
+    let output = ToolOrchestrator::new()
+        .run(
+            &mut ShellRuntime::new(),
+            &req,
+            &tool_ctx,
+            turn,
+            turn.approval_policy,
+        )
+        .await
+        .map_err(|err| {
+            let reason = match err {
+                ToolError::Rejected(reason) => reason,
+                ToolError::Codex(err) => err.to_string(),
+            };
+            FunctionCallError::RespondToModel(format!(
+                "failed to run `{}` for coco --batch: {reason}",
+                join_command(producer)
+            ))
+        })?;
+    if output.exit_code != 0 {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "`{}` exited {} before coco --batch could read its prompts:\n{}",
+            join_command(producer),
+            output.exit_code,
+            output.stderr.text.trim_end()
+        )));
+    }
+    Ok(parse_batch_prompts(&output.stdout.text))
+}
+
+/// One prompt per non-blank line. A JSONL line holding a string, or an
+/// object with a `prompt` field, contributes that text instead.
+fn parse_batch_prompts(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| match serde_json::from_str::<Value>(line) {
+            Ok(Value::String(prompt)) => prompt,
+            Ok(Value::Object(object)) => object
+                .get("prompt")
+                .and_then(Value::as_str)
+                .map_or_else(|| line.to_string(), str::to_string),
+            _ => line.to_string(),
+        })
+        .collect()
+}
+
+/// Runs a sub-agent for each prompt of `coco --batch`, one after another,
+/// and returns a JSON array of `{prompt, result, exit_code}`. Prompt text
+/// given to `coco --batch` itself is prepended to every prompt.
+async fn run_coco_batch(
+    request: CocoRequest,
+    prompts: Vec<String>,
+    session: &Arc<crate::codex::Session>,
+    turn: &Arc<TurnContext>,
+    call_id: &str,
+    freeform: bool,
+) -> Result<ToolOutput, FunctionCallError> {
+    if prompts.is_empty() {
+        return Err(FunctionCallError::RespondToModel(
+            "coco --batch received no prompts.".to_string(),
+        ));
+    }
+    if request.resume.is_some() {
+        return Err(FunctionCallError::RespondToModel(
+            "coco --batch cannot resume a sub-agent conversation.".to_string(),
+        ));
     }
+    let depth = next_coco_depth(
+        &turn.client.get_session_source(),
+        turn.client.config().coco.max_depth,
+    )?;
+    if let Some(model) = &request.model {
+        check_model_allowed(model, turn.client.config().coco.allowed_models.as_deref())?;
+    }
+
+    let emitter = ToolEmitter::shell(
+        request.command.clone(),
+        request.cwd.clone(),
+        ExecCommandSource::Agent,
+        freeform,
+    );
+    let begin_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), call_id, None);
+    emitter.begin(begin_ctx).await;
 
-    fn prompt(&self) -> &str {
-        &self.prompt
+    let started_at = Instant::now();
+    let source = SubAgentOutputSource {
+        depth,
+        conversation_id: None,
+    };
+    let total = prompts.len();
+    let mut log = Vec::new();
+    let mut results = Vec::with_capacity(total);
+    for (index, prompt) in prompts.into_iter().enumerate() {
+        let header = format!("batch prompt {}/{total}: {prompt}", index + 1);
+        emit_coco_stdout_line(session, turn, call_id, &source, &header).await;
+        log.push(header);
+        let item = CocoRequest {
+            prompt: if request.prompt.trim().is_empty() {
+                prompt.clone()
+            } else {
+                format!("{}\n\n{prompt}", request.prompt)
+            },
+            ..request.clone()
+        };
+        let (result, exit_code) =
+            match execute_coco_subagent_with_retries(&item, depth, session, turn, call_id).await {
+                Ok(outcome) => {
+                    log.extend(outcome.log);
+                    let result = outcome.structured_output.unwrap_or_else(|| {
+                        Value::String(outcome.final_message.unwrap_or_default())
+                    });
+                    (result, outcome.exit_code)
+                }
+                Err(CocoFailure {
+                    message,
+                    log: item_log,
+                }) => {
+                    log.extend(item_log);
+                    (Value::String(message), 1)
+                }
+            };
+        results.push(json!({
+            "prompt": prompt,
+            "result": result,
+            "exit_code": exit_code,
+        }));
     }
+
+    let success = results.iter().all(|result| result["exit_code"] == 0);
+    let log_text = log.join("\n");
+    let event_output = ExecToolCallOutput {
+        exit_code: if success { 0 } else { 1 },
+        stdout: StreamOutput::new(log_text.clone()),
+        stderr: StreamOutput::new(String::new()),
+        aggregated_output: StreamOutput::new(log_text),
+        duration: started_at.elapsed(),
+        timed_out: false,
+    };
+    let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), call_id, None);
+    emitter
+        .emit(event_ctx, ToolEventStage::Success(event_output))
+        .await;
+
+    Ok(ToolOutput::Function {
+        content: Value::Array(results).to_string(),
+        content_items: None,
+        success: Some(success),
+    })
 }
 
-async fn run_coco_command(
-    invocation: &CocoInvocation,
+/// Runs the codex-flow workflow of `coco flow run <workflow.toml>` through
+/// the runner the host registered, streaming its step updates as exec output.
+/// Workflow steps start their own `codex exec` processes with the sandbox
+/// their step config names, so this is only allowed in sessions that are
+/// not sandboxed themselves.
+async fn run_coco_flow(
+    workflow: PathBuf,
     exec_params: &ExecParams,
     session: &Arc<crate::codex::Session>,
     turn: &Arc<TurnContext>,
     call_id: &str,
-    is_user_shell_command: bool,
+    freeform: bool,
 ) -> Result<ToolOutput, FunctionCallError> {
+    let Some(runner) = crate::coco_flow::coco_flow_runner() else {
+        return Err(FunctionCallError::RespondToModel(
+            "coco flow run is not available in this codex build.".to_string(),
+        ));
+    };
+    if !matches!(turn.sandbox_policy, SandboxPolicy::DangerFullAccess) {
+        return Err(FunctionCallError::RespondToModel(
+            "coco flow run starts workflow steps outside this session's sandbox and needs a session running with danger-full-access.".to_string(),
+        ));
+    }
+    let depth = next_coco_depth(
+        &turn.client.get_session_source(),
+        turn.client.config().coco.max_depth,
+    )?;
+
     let emitter = ToolEmitter::shell(
         exec_params.command.clone(),
         exec_params.cwd.clone(),
-        is_user_shell_command,
+        ExecCommandSource::Agent,
+        freeform,
     );
     let begin_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), call_id, None);
     emitter.begin(begin_ctx).await;
 
     let started_at = Instant::now();
-    let outcome = match execute_coco_subagent(invocation, exec_params, session, turn, call_id).await
-    {
+    let source = SubAgentOutputSource {
+        depth,
+        conversation_id: None,
+    };
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let cwd = exec_params.cwd.clone();
+    let run = tokio::task::spawn_blocking(move || {
+        let progress = Box::new(move |line: String| {
+            let _ = tx.send(line);
+        });
+        runner.run(&workflow, &cwd, progress)
+    });
+    let mut log = Vec::new();
+    let mut live = CocoLiveOutput::new(session, turn, call_id);
+    loop {
+        let line = match live.next_emit_at() {
+            Some(emit_at) => match time::timeout_at(emit_at, rx.recv()).await {
+                Ok(line) => line,
+                Err(_) => {
+                    live.flush_due(&source).await;
+                    continue;
+                }
+            },
+            None => rx.recv().await,
+        };
+        let Some(line) = line else {
+            break;
+        };
+        live.line(&source, &line).await;
+        log.push(line);
+    }
+    live.flush(&source).await;
+    let result = match run.await {
+        Ok(result) => result.map_err(|err| format!("coco flow run failed: {err:#}")),
+        Err(err) => Err(format!("coco flow run failed: {err}")),
+    };
+
+    let summary = match result {
+        Ok(summary) => summary,
+        Err(message) => {
+            let mut combined = message.clone();
+            if !log.is_empty() {
+                combined.push('\n');
+                combined.push_str(&log.join("\n"));
+            }
+            let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), call_id, None);
+            emitter
+                .emit(
+                    event_ctx,
+                    ToolEventStage::Failure(ToolEventFailure::Message(message)),
+                )
+                .await;
+            return Err(FunctionCallError::RespondToModel(combined));
+        }
+    };
+
+    let duration = started_at.elapsed();
+    let log_text = log.join("\n");
+    let event_output = ExecToolCallOutput {
+        exit_code: 0,
+        stdout: StreamOutput::new(log_text.clone()),
+        stderr: StreamOutput::new(String::new()),
+        aggregated_output: StreamOutput::new(log_text),
+        duration,
+        timed_out: false,
+    };
+    let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), call_id, None);
+    emitter
+        .emit(event_ctx, ToolEventStage::Success(event_output))
+        .await;
+
+    let model_output = ExecToolCallOutput {
+        exit_code: 0,
+        stdout: StreamOutput::new(summary.clone()),
+        stderr: StreamOutput::new(String::new()),
+        aggregated_output: StreamOutput::new(summary),
+        duration,
+        timed_out: false,
+    };
+    let content = if freeform {
+        format_exec_output_for_model_freeform(&model_output, turn.truncation_policy)
+    } else {
+        format_exec_output_for_model_structured(&model_output, turn.truncation_policy)
+    };
+    Ok(ToolOutput::Function {
+        content,
+        content_items: None,
+        success: Some(true),
+    })
+}
+
+pub(crate) async fn run_coco_request(
+    request: CocoRequest,
+    session: &Arc<crate::codex::Session>,
+    turn: &Arc<TurnContext>,
+    call_id: &str,
+    freeform: bool,
+) -> Result<ToolOutput, FunctionCallError> {
+    if request.prompt.trim().is_empty() {
+        return Err(FunctionCallError::RespondToModel(
+            "coco command requires a prompt argument.".to_string(),
+        ));
+    }
+    let depth = next_coco_depth(
+        &turn.client.get_session_source(),
+        turn.client.config().coco.max_depth,
+    )?;
+    if let Some(model) = &request.model {
+        check_model_allowed(model, turn.client.config().coco.allowed_models.as_deref())?;
+    }
+
+    let emitter = ToolEmitter::shell(
+        request.command.clone(),
+        request.cwd.clone(),
+        ExecCommandSource::Agent,
+        freeform,
+    );
+    let begin_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), call_id, None);
+    emitter.begin(begin_ctx).await;
+
+    let cache_ttl = Duration::from_secs(turn.client.config().coco.cache_ttl_sec);
+    // Continuing a conversation depends on its history, so it never hits the cache.
+    let cache_key = if !cache_ttl.is_zero() && request.resume.is_none() {
+        Some(coco_cache_key(&request, turn.client.config().model.as_str()).await)
+    } else {
+        None
+    };
+    let cached = match cache_key {
+        Some(key) => session
+            .services
+            .coco_results
+            .lock()
+            .await
+            .get(key, cache_ttl),
+        None => None,
+    };
+
+    let started_at = Instant::now();
+    let result = match cached.clone() {
+        Some(outcome) => Ok(outcome),
+        None => execute_coco_subagent_with_retries(&request, depth, session, turn, call_id).await,
+    };
+    let outcome = match result {
         Ok(outcome) => outcome,
-        Err(CocoError::Execution { message, log }) => {
+        Err(CocoFailure { message, log }) => {
             let mut combined = message.clone();
             if !log.is_empty() {
                 combined.push('\n');
@@ -122,11 +743,37 @@ async fn run_coco_command(
         }
     };
 
+    if cached.is_none()
+        && outcome.exit_code == 0
+        && let Some(key) = cache_key
+    {
+        session
+            .services
+            .coco_results
+            .lock()
+            .await
+            .put(key, outcome.clone());
+    }
+
     let duration = started_at.elapsed();
     let log_text = outcome.log.join("\n");
-    let final_message = outcome.final_message.clone().unwrap_or_else(|| {
-        "coco sub-agent finished without returning an agent message.".to_string()
-    });
+    let mut notes = Vec::new();
+    if cached.is_some() {
+        notes.push(
+            "[cached result of an earlier identical coco call; no sub-agent was run]".to_string(),
+        );
+    }
+    if let Some(conversation_id) = outcome.conversation_id {
+        notes.push(format!(
+            "[coco session {conversation_id}; continue it with `coco resume {conversation_id} \"<follow-up>\"`]"
+        ));
+    }
+    if let Some(path) = &outcome.full_log_path {
+        notes.push(format!(
+            "[sub-agent log truncated; full log at {}]",
+            path.display()
+        ));
+    }
 
     let event_output = ExecToolCallOutput {
         exit_code: outcome.exit_code,
@@ -141,6 +788,29 @@ async fn run_coco_command(
         .emit(event_ctx, ToolEventStage::Success(event_output))
         .await;
 
+    if let Some(structured) = outcome.structured_output {
+        // Hand back the validated JSON on its own so the parent can parse it;
+        // the notes travel as separate items.
+        let json = structured.to_string();
+        let content_items = std::iter::once(json.clone())
+            .chain(notes)
+            .map(|text| FunctionCallOutputContentItem::InputText { text })
+            .collect();
+        return Ok(ToolOutput::Function {
+            content: json,
+            content_items: Some(content_items),
+            success: Some(true),
+        });
+    }
+
+    let mut final_message = outcome.final_message.unwrap_or_else(|| {
+        "coco sub-agent finished without returning an agent message.".to_string()
+    });
+    for note in notes {
+        final_message.push_str("\n\n");
+        final_message.push_str(&note);
+    }
+
     let model_output = ExecToolCallOutput {
         exit_code: outcome.exit_code,
         stdout: StreamOutput::new(final_message.clone()),
@@ -149,7 +819,11 @@ async fn run_coco_command(
         duration,
         timed_out: false,
     };
-    let content = format_exec_output_for_model(&model_output);
+    let content = if freeform {
+        format_exec_output_for_model_freeform(&model_output, turn.truncation_policy)
+    } else {
+        format_exec_output_for_model_structured(&model_output, turn.truncation_policy)
+    };
 
     Ok(ToolOutput::Function {
         content,
@@ -158,32 +832,103 @@ async fn run_coco_command(
     })
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct CocoRunOutcome {
     final_message: Option<String>,
+    /// `final_message` parsed and validated against `--output-schema`.
+    structured_output: Option<Value>,
+    /// Id of the sub-agent conversation, for `coco resume`.
+    conversation_id: Option<ConversationId>,
     log: Vec<String>,
+    /// Where the untruncated log was written, when the captured one was cut.
+    full_log_path: Option<PathBuf>,
     exit_code: i32,
 }
 
 #[derive(Debug)]
 enum CocoError {
-    Execution { message: String, log: Vec<String> },
+    Execution {
+        message: String,
+        log: Vec<String>,
+    },
+    /// The sub-agent's model stream failed in a way a fresh attempt may not
+    /// hit again, e.g. a disconnect or rate limit.
+    Transient {
+        message: String,
+        log: Vec<String>,
+    },
+    /// The sub-agent ran past its timeout and was cancelled.
+    TimedOut {
+        message: String,
+        log: Vec<String>,
+    },
 }
 
-#[derive(Debug, Default)]
+/// How a sub-agent run ended once retries are used up; callers report every
+/// kind of [`CocoError`] the same way.
+#[derive(Debug)]
+struct CocoFailure {
+    message: String,
+    log: Vec<String>,
+}
+
+/// Caps on the exec output captured per command the sub-agent runs.
+#[derive(Debug, Clone, Copy)]
+struct CocoOutputLimits {
+    max_lines: usize,
+    max_bytes: usize,
+}
+
+#[derive(Debug)]
 struct CocoEventCollector {
+    limits: CocoOutputLimits,
     lines: Vec<String>,
+    /// Every line, including exec output dropped from `lines`.
+    full_lines: Vec<String>,
+    truncated: bool,
+    conversation_id: Option<ConversationId>,
     pending_agent: Option<String>,
     last_agent_message: Option<String>,
+    /// Sub-agent usage already added to the parent session's totals.
+    token_usage: TokenUsage,
 }
 
 impl CocoEventCollector {
+    fn new(limits: CocoOutputLimits) -> Self {
+        Self {
+            limits,
+            lines: Vec::new(),
+            full_lines: Vec::new(),
+            truncated: false,
+            conversation_id: None,
+            pending_agent: None,
+            last_agent_message: None,
+            token_usage: TokenUsage::default(),
+        }
+    }
+
+    /// Records the sub-agent's cumulative usage and returns what it spent
+    /// since the previous report.
+    fn record_token_usage(&mut self, total: &TokenUsage) -> TokenUsage {
+        let previous = std::mem::replace(&mut self.token_usage, total.clone());
+        TokenUsage {
+            input_tokens: (total.input_tokens - previous.input_tokens).max(0),
+            cached_input_tokens: (total.cached_input_tokens - previous.cached_input_tokens).max(0),
+            output_tokens: (total.output_tokens - previous.output_tokens).max(0),
+            reasoning_output_tokens: (total.reasoning_output_tokens
+                - previous.reasoning_output_tokens)
+                .max(0),
+            total_tokens: (total.total_tokens - previous.total_tokens).max(0),
+        }
+    }
+
     fn push_line(&mut self, line: impl Into<String>) -> Option<String> {
         let line = line.into();
         if line.is_empty() {
             return None;
         }
         self.lines.push(line.clone());
+        self.full_lines.push(line.clone());
         Some(line)
     }
 
@@ -224,17 +969,26 @@ impl CocoEventCollector {
     fn append_exec_output(&mut self, output: &str) -> Vec<String> {
         let mut appended = Vec::new();
         let mut count = 0usize;
+        let mut bytes = 0usize;
+        let mut truncated = false;
         for line in output.lines() {
             if line.is_empty() {
                 continue;
             }
-            if count >= MAX_COCO_CAPTURED_LINES {
+            let formatted = format!("  {line}");
+            self.full_lines.push(formatted.clone());
+            if truncated {
+                continue;
+            }
+            if count >= self.limits.max_lines || bytes + formatted.len() > self.limits.max_bytes {
+                truncated = true;
+                self.truncated = true;
                 let notice = COCO_TRUNCATION_NOTICE.to_string();
                 self.lines.push(notice.clone());
                 appended.push(notice);
-                return appended;
+                continue;
             }
-            let formatted = format!("  {line}");
+            bytes += formatted.len();
             self.lines.push(formatted.clone());
             appended.push(formatted);
             count += 1;
@@ -251,6 +1005,15 @@ impl CocoEventCollector {
     }
 }
 
+async fn write_full_log(path: &Path, lines: &[String]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    tokio::fs::write(path, contents).await
+}
+
 fn parse_coco_tokens(command: &[String]) -> Option<Vec<String>> {
     if command.is_empty() {
         return None;
@@ -258,14 +1021,33 @@ fn parse_coco_tokens(command: &[String]) -> Option<Vec<String>> {
     if is_coco_program(&command[0]) {
         return Some(command.to_vec());
     }
-    if command.len() >= 3 && is_shell_wrapper(&command[0]) && command[1] == "-lc"
+    if command.len() >= 3
+        && is_shell_wrapper(&command[0])
+        && command[1] == "-lc"
         && let Some(tokens) = split(&command[2])
-            && !tokens.is_empty() && is_coco_program(&tokens[0]) {
-                return Some(tokens);
-            }
+        && !tokens.is_empty()
+        && is_coco_program(&tokens[0])
+    {
+        return Some(tokens);
+    }
     None
 }
 
+/// Splits `bash -lc "<producer> | coco ..."` into the producer command and
+/// the `coco` tokens.
+fn split_coco_pipeline(command: &[String]) -> Option<(Vec<String>, Vec<String>)> {
+    if command.len() < 3 || !is_shell_wrapper(&command[0]) || command[1] != "-lc" {
+        return None;
+    }
+    let tokens = split(&command[2])?;
+    let pipe = tokens.iter().rposition(|token| token == "|")?;
+    let coco = tokens[pipe + 1..].to_vec();
+    if !coco.first().is_some_and(|program| is_coco_program(program)) {
+        return None;
+    }
+    Some((tokens[..pipe].to_vec(), coco))
+}
+
 fn is_coco_program(cmd: &str) -> bool {
     let name = command_basename(cmd);
     COCO_BINARY_BASENAMES
@@ -302,6 +1084,7 @@ async fn emit_coco_stdout_line(
     session: &Arc<crate::codex::Session>,
     turn: &Arc<TurnContext>,
     call_id: &str,
+    source: &SubAgentOutputSource,
     line: &str,
 ) {
     if line.is_empty() {
@@ -309,106 +1092,903 @@ async fn emit_coco_stdout_line(
     }
     let mut chunk = line.to_string();
     chunk.push('\n');
+    send_coco_output(session, turn, call_id, source, chunk.into_bytes()).await;
+}
+
+async fn send_coco_output(
+    session: &Arc<crate::codex::Session>,
+    turn: &Arc<TurnContext>,
+    call_id: &str,
+    source: &SubAgentOutputSource,
+    chunk: Vec<u8>,
+) {
     let event = ExecCommandOutputDeltaEvent {
         call_id: call_id.to_string(),
         stream: ExecOutputStream::Stdout,
-        chunk: chunk.into_bytes(),
+        chunk,
+        sub_agent: Some(source.clone()),
     };
     session
         .send_event(turn.as_ref(), EventMsg::ExecCommandOutputDelta(event))
         .await;
 }
 
-async fn execute_coco_subagent(
-    invocation: &CocoInvocation,
-    exec_params: &ExecParams,
-    session: &Arc<crate::codex::Session>,
-    turn: &Arc<TurnContext>,
-    call_id: &str,
-) -> Result<CocoRunOutcome, CocoError> {
-    let mut sub_agent_config = turn.client.config().as_ref().clone();
-    sub_agent_config.cwd = exec_params.cwd.clone();
+/// Streams a sub-agent's log lines through an [`OutputDeltaThrottle`], for
+/// sub-agents whose commands print more than clients can keep up with.
+struct CocoLiveOutput<'a> {
+    session: &'a Arc<crate::codex::Session>,
+    turn: &'a Arc<TurnContext>,
+    call_id: &'a str,
+    throttle: OutputDeltaThrottle,
+}
 
-    let inputs = vec![UserInput::Text {
-        text: invocation.prompt().to_string(),
-    }];
+impl<'a> CocoLiveOutput<'a> {
+    fn new(
+        session: &'a Arc<crate::codex::Session>,
+        turn: &'a Arc<TurnContext>,
+        call_id: &'a str,
+    ) -> Self {
+        Self {
+            session,
+            turn,
+            call_id,
+            throttle: OutputDeltaThrottle::default(),
+        }
+    }
 
-    let cancel_token = CancellationToken::new();
-    let io = run_codex_conversation_one_shot(
-        sub_agent_config,
-        Arc::clone(&session.services.auth_manager),
-        inputs,
-        Arc::clone(session),
-        Arc::clone(turn),
-        cancel_token.clone(),
-        None,
-        SubAgentSource::Other(COCO_SUB_AGENT_LABEL.to_string()),
-    )
-    .await
-    .map_err(|e| CocoError::Execution {
-        message: format!("failed to start coco sub-agent: {e:#}"),
-        log: Vec::new(),
-    })?;
+    fn next_emit_at(&self) -> Option<time::Instant> {
+        self.throttle.next_emit_at()
+    }
 
-    let receiver = io.rx_event;
-    let collect_future = collect_coco_events(receiver, session, turn, call_id);
-    let outcome = if let Some(timeout_ms) = exec_params.timeout_ms {
-        match time::timeout(Duration::from_millis(timeout_ms), collect_future).await {
-            Ok(result) => result,
-            Err(_) => {
-                cancel_token.cancel();
-                return Err(CocoError::Execution {
-                    message: format!("coco sub-agent timed out after {timeout_ms} ms"),
+    async fn line(&mut self, source: &SubAgentOutputSource, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        self.throttle.push(line.as_bytes());
+        self.throttle.push(b"\n");
+        self.flush_due(source).await;
+    }
+
+    async fn flush_due(&mut self, source: &SubAgentOutputSource) {
+        if let Some(chunk) = self.throttle.take_due(time::Instant::now()) {
+            send_coco_output(self.session, self.turn, self.call_id, source, chunk).await;
+        }
+    }
+
+    async fn flush(&mut self, source: &SubAgentOutputSource) {
+        if let Some(chunk) = self.throttle.take_all() {
+            send_coco_output(self.session, self.turn, self.call_id, source, chunk).await;
+        }
+    }
+}
+
+/// Depth of a sub-agent started from a session with `source`, or a
+/// model-facing error when it would exceed `max_depth`.
+fn next_coco_depth(source: &SessionSource, max_depth: u32) -> Result<u32, FunctionCallError> {
+    let current = match source {
+        SessionSource::SubAgent(SubAgentSource::Coco { depth, .. }) => *depth,
+        _ => 0,
+    };
+    if current >= max_depth {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "coco sub-agents may nest at most {max_depth} level(s) deep and this conversation is already at depth {current}; complete the task directly instead of delegating it."
+        )));
+    }
+    Ok(current + 1)
+}
+
+fn check_model_allowed(model: &str, allowed: Option<&[String]>) -> Result<(), FunctionCallError> {
+    match allowed {
+        Some(allowed) if !allowed.iter().any(|candidate| candidate == model) => {
+            Err(FunctionCallError::RespondToModel(format!(
+                "coco --model {model} is not allowed; choose one of: {}",
+                allowed.join(", ")
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Runs the sub-agent, starting it over up to `coco.max_retries` times with
+/// backoff when it fails transiently. Earlier attempts stay in the log.
+async fn execute_coco_subagent_with_retries(
+    request: &CocoRequest,
+    depth: u32,
+    session: &Arc<crate::codex::Session>,
+    turn: &Arc<TurnContext>,
+    call_id: &str,
+) -> Result<CocoRunOutcome, CocoFailure> {
+    let max_retries = turn.client.config().coco.max_retries;
+    let source = SubAgentOutputSource {
+        depth,
+        conversation_id: None,
+    };
+    let mut earlier_log = Vec::new();
+    let mut attempt = 0;
+    let started_at = Instant::now();
+    let result = loop {
+        match execute_coco_subagent(request, depth, session, turn, call_id).await {
+            Err(CocoError::Transient { message, log }) if attempt < max_retries => {
+                attempt += 1;
+                let delay = backoff(u64::from(attempt));
+                let line = format!(
+                    "sub-agent attempt {attempt} failed: {message}; retrying in {} ({attempt}/{max_retries})",
+                    format_duration_compact(delay)
+                );
+                emit_coco_stdout_line(session, turn, call_id, &source, &line).await;
+                earlier_log.extend(log);
+                earlier_log.push(line);
+                time::sleep(delay).await;
+            }
+            result => break result,
+        }
+    };
+    session
+        .services
+        .coco_stats
+        .lock()
+        .await
+        .record(started_at.elapsed(), &result);
+    let with_earlier_log = |mut log: Vec<String>| {
+        earlier_log.append(&mut log);
+        earlier_log
+    };
+    match result {
+        Ok(mut outcome) => {
+            outcome.log = with_earlier_log(outcome.log);
+            Ok(outcome)
+        }
+        Err(
+            CocoError::Execution { message, log }
+            | CocoError::Transient { message, log }
+            | CocoError::TimedOut { message, log },
+        ) => Err(CocoFailure {
+            message,
+            log: with_earlier_log(log),
+        }),
+    }
+}
+
+async fn execute_coco_subagent(
+    request: &CocoRequest,
+    depth: u32,
+    session: &Arc<crate::codex::Session>,
+    turn: &Arc<TurnContext>,
+    call_id: &str,
+) -> Result<CocoRunOutcome, CocoError> {
+    let mut sub_agent_config = turn.client.config().as_ref().clone();
+    sub_agent_config.cwd = request.cwd.clone();
+    let profile = match &request.agent {
+        Some(name) => {
+            let profile = lookup_sub_agent_profile(&sub_agent_config, name)?;
+            apply_sub_agent_profile(&mut sub_agent_config, name, &profile, turn)?;
+            Some(profile)
+        }
+        None => None,
+    };
+    if let Some(sandbox_mode) = request.sandbox_mode {
+        let policy = sandbox_policy_for_mode(sandbox_mode, &sub_agent_config.sandbox_policy);
+        if sandbox_rank(&policy) > sandbox_rank(&turn.sandbox_policy) {
+            return Err(CocoError::Execution {
+                message: format!(
+                    "coco --sandbox {sandbox_mode} is less restrictive than this session's sandbox"
+                ),
+                log: Vec::new(),
+            });
+        }
+        sub_agent_config.sandbox_policy = policy;
+    }
+    if let Some(approval_policy) = request.approval_policy {
+        if approval_rank(approval_policy) > approval_rank(turn.approval_policy) {
+            return Err(CocoError::Execution {
+                message: format!(
+                    "coco --approval {approval_policy} is less restrictive than this session's approval policy"
+                ),
+                log: Vec::new(),
+            });
+        }
+        sub_agent_config.approval_policy = approval_policy;
+    }
+    if request.cwd != turn.cwd {
+        check_sub_agent_cwd(&request.cwd, turn, &sub_agent_config.sandbox_policy)?;
+    }
+    restrict_sub_agent_tools(
+        &mut sub_agent_config,
+        request.tools.as_deref(),
+        &request.disabled_tools,
+    );
+    let max_tokens = profile
+        .as_ref()
+        .and_then(|profile| profile.max_tokens)
+        .or(sub_agent_config.coco.max_tokens);
+    let limits = CocoOutputLimits {
+        max_lines: request
+            .max_output_lines
+            .unwrap_or(sub_agent_config.coco.max_output_lines),
+        max_bytes: request
+            .max_output_bytes
+            .unwrap_or(sub_agent_config.coco.max_output_bytes),
+    };
+    let show_reasoning = sub_agent_config.coco.show_reasoning;
+    let timeout = Duration::from_millis(
+        request
+            .timeout_ms
+            .unwrap_or(sub_agent_config.coco.timeout_ms),
+    );
+    let full_log_path = sub_agent_config
+        .codex_home
+        .join(COCO_LOGS_DIR)
+        .join(format!("{call_id}.log"));
+    let model = request
+        .model
+        .as_ref()
+        .or_else(|| profile.as_ref().and_then(|profile| profile.model.as_ref()));
+    if let Some(model) = model {
+        let model_family =
+            find_family_for_model(model).unwrap_or_else(|| turn.client.get_model_family());
+        sub_agent_config.model = model.clone();
+        if let Some(model_info) = get_model_info(&model_family) {
+            sub_agent_config.model_context_window = Some(model_info.context_window);
+        }
+        sub_agent_config.model_family = model_family;
+    }
+
+    let mut inputs = vec![UserInput::Text {
+        text: request.prompt.clone(),
+    }];
+    for file in &request.files {
+        inputs.push(file_input(file).await?);
+    }
+
+    let output_schema = match &request.output_schema {
+        Some(path) => Some(load_output_schema(path).await?),
+        None => None,
+    };
+
+    let initial_history = match &request.resume {
+        Some(id) => Some(
+            load_coco_history(&sub_agent_config.codex_home, id, session.conversation_id()).await?,
+        ),
+        None => None,
+    };
+
+    // Held until the sub-agent finishes; the wait does not count against
+    // its timeout.
+    let _slot = match session.services.coco_slots.try_acquire() {
+        Ok(permit) => permit,
+        Err(_) => {
+            let source = SubAgentOutputSource {
+                depth,
+                conversation_id: None,
+            };
+            let line = format!(
+                "waiting for one of {} running coco sub-agents to finish",
+                sub_agent_config.coco.max_concurrent
+            );
+            emit_coco_stdout_line(session, turn, call_id, &source, &line).await;
+            session
+                .services
+                .coco_slots
+                .acquire()
+                .await
+                .map_err(|e| CocoError::Execution {
+                    message: format!("failed to start coco sub-agent: {e}"),
                     log: Vec::new(),
-                });
+                })?
+        }
+    };
+
+    let cancel_token = CancellationToken::new();
+    let io = run_codex_conversation_one_shot(
+        sub_agent_config,
+        Arc::clone(&session.services.auth_manager),
+        inputs,
+        output_schema.clone(),
+        Arc::clone(session),
+        Arc::clone(turn),
+        cancel_token.clone(),
+        initial_history,
+        SubAgentSource::Coco {
+            depth,
+            parent_conversation_id: session.conversation_id(),
+            call_id: call_id.to_string(),
+        },
+    )
+    .await
+    .map_err(|e| CocoError::Execution {
+        message: format!("failed to start coco sub-agent: {e:#}"),
+        log: Vec::new(),
+    })?;
+
+    // Dropped without being disarmed when the parent turn is aborted while
+    // the sub-agent is still running.
+    let parent_abort_guard = ParentAbortGuard {
+        cancel_token: cancel_token.clone(),
+        session: Arc::clone(session),
+        turn: Arc::clone(turn),
+        call_id: call_id.to_string(),
+        depth,
+        armed: true,
+    };
+    let outcome = collect_coco_events(
+        io.rx_event,
+        CocoCollectOptions {
+            max_tokens,
+            timeout,
+            limits,
+            full_log_path,
+            show_reasoning,
+            depth,
+        },
+        &cancel_token,
+        session,
+        turn,
+        call_id,
+    )
+    .await;
+    parent_abort_guard.disarm();
+    let outcome = outcome?;
+
+    match output_schema {
+        Some(schema) => parse_structured_output(outcome, &schema),
+        None => Ok(outcome),
+    }
+}
+
+/// Cancels a running sub-agent when the parent stops waiting for it, which
+/// happens when the parent turn is interrupted or replaced and the `coco`
+/// call is dropped mid-run.
+struct ParentAbortGuard {
+    cancel_token: CancellationToken,
+    session: Arc<crate::codex::Session>,
+    turn: Arc<TurnContext>,
+    call_id: String,
+    depth: u32,
+    armed: bool,
+}
+
+impl ParentAbortGuard {
+    fn disarm(mut self) {
+        self.armed = false;
+    }
+}
+
+impl Drop for ParentAbortGuard {
+    fn drop(&mut self) {
+        if !self.armed || self.cancel_token.is_cancelled() {
+            return;
+        }
+        self.cancel_token.cancel();
+        warn!("coco sub-agent for call {} aborted by parent", self.call_id);
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let session = Arc::clone(&self.session);
+            let turn = Arc::clone(&self.turn);
+            let call_id = self.call_id.clone();
+            let source = SubAgentOutputSource {
+                depth: self.depth,
+                conversation_id: None,
+            };
+            runtime.spawn(async move {
+                emit_coco_stdout_line(&session, &turn, &call_id, &source, COCO_PARENT_ABORT_NOTICE)
+                    .await;
+            });
+        }
+    }
+}
+
+/// Results of earlier `coco` runs in a session, reused when the same
+/// delegation is requested again within `coco.cache_ttl_sec`.
+#[derive(Default)]
+pub(crate) struct CocoResultCache {
+    entries: HashMap<u64, (Instant, CocoRunOutcome)>,
+}
+
+impl CocoResultCache {
+    fn get(&self, key: u64, ttl: Duration) -> Option<CocoRunOutcome> {
+        self.entries
+            .get(&key)
+            .filter(|(stored_at, _)| stored_at.elapsed() < ttl)
+            .map(|(_, outcome)| outcome.clone())
+    }
+
+    fn put(&mut self, key: u64, outcome: CocoRunOutcome) {
+        self.entries.insert(key, (Instant::now(), outcome));
+    }
+}
+
+/// Identifies requests that would run the same sub-agent on the same input
+/// with the same permissions and limits. Attached files and the output
+/// schema count by content, so editing one between calls misses the cache.
+async fn coco_cache_key(request: &CocoRequest, default_model: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    request.prompt.hash(&mut hasher);
+    request.cwd.hash(&mut hasher);
+    request
+        .model
+        .as_deref()
+        .unwrap_or(default_model)
+        .hash(&mut hasher);
+    request.agent.hash(&mut hasher);
+    request
+        .sandbox_mode
+        .map(|mode| mode.to_string())
+        .hash(&mut hasher);
+    request.approval_policy.hash(&mut hasher);
+    request.timeout_ms.hash(&mut hasher);
+    request.max_output_lines.hash(&mut hasher);
+    request.max_output_bytes.hash(&mut hasher);
+    request.files.hash(&mut hasher);
+    request.output_schema.hash(&mut hasher);
+    for path in request.files.iter().chain(&request.output_schema) {
+        // An unreadable file fails the run itself; the key only has to differ.
+        tokio::fs::read(path).await.ok().hash(&mut hasher);
+    }
+    request.tools.hash(&mut hasher);
+    request.disabled_tools.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Counts of the `coco` sub-agents a session has run, for the summary sent
+/// when a task completes and for the `coco_status` tool.
+#[derive(Default)]
+pub(crate) struct CocoStats {
+    totals: SubAgentSummaryEvent,
+    /// `totals.invocations` when the last summary was sent.
+    reported_invocations: u64,
+}
+
+impl CocoStats {
+    fn record(&mut self, duration: Duration, result: &Result<CocoRunOutcome, CocoError>) {
+        self.totals.invocations += 1;
+        self.totals.total_duration += duration;
+        match result {
+            Ok(_) => self.totals.succeeded += 1,
+            Err(err) => {
+                self.totals.failed += 1;
+                if matches!(err, CocoError::TimedOut { .. }) {
+                    self.totals.timed_out += 1;
+                }
             }
         }
+    }
+
+    pub(crate) fn totals(&self) -> SubAgentSummaryEvent {
+        self.totals.clone()
+    }
+
+    /// The session totals, when sub-agents have run since the last summary.
+    pub(crate) fn take_summary(&mut self) -> Option<SubAgentSummaryEvent> {
+        if self.totals.invocations == self.reported_invocations {
+            return None;
+        }
+        self.reported_invocations = self.totals.invocations;
+        Some(self.totals.clone())
+    }
+}
+
+async fn load_output_schema(path: &Path) -> Result<Value, CocoError> {
+    let contents = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|err| CocoError::Execution {
+            message: format!(
+                "failed to read coco --output-schema {}: {err}",
+                path.display()
+            ),
+            log: Vec::new(),
+        })?;
+    serde_json::from_str(&contents).map_err(|err| CocoError::Execution {
+        message: format!(
+            "coco --output-schema {} is not valid JSON: {err}",
+            path.display()
+        ),
+        log: Vec::new(),
+    })
+}
+
+fn parse_structured_output(
+    mut outcome: CocoRunOutcome,
+    schema: &Value,
+) -> Result<CocoRunOutcome, CocoError> {
+    let message = outcome.final_message.as_deref().unwrap_or_default();
+    let parsed = serde_json::from_str::<Value>(message)
+        .map_err(|err| format!("coco sub-agent did not return JSON: {err}"))
+        .and_then(|value| {
+            validate_output(&value, schema)
+                .map(|()| value)
+                .map_err(|err| format!("coco sub-agent output does not match the schema: {err}"))
+        });
+    match parsed {
+        Ok(value) => {
+            outcome.structured_output = Some(value);
+            Ok(outcome)
+        }
+        Err(message) => Err(CocoError::Execution {
+            message,
+            log: outcome.log,
+        }),
+    }
+}
+
+/// Images are attached as images; anything else is inlined as text.
+async fn file_input(path: &Path) -> Result<UserInput, CocoError> {
+    let is_image = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            matches!(
+                ext.to_ascii_lowercase().as_str(),
+                "png" | "jpg" | "jpeg" | "gif" | "webp"
+            )
+        });
+    if is_image {
+        return Ok(UserInput::LocalImage {
+            path: path.to_path_buf(),
+        });
+    }
+    let contents = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|err| CocoError::Execution {
+            message: format!("failed to read coco --file {}: {err}", path.display()),
+            log: Vec::new(),
+        })?;
+    Ok(UserInput::Text {
+        text: format!("<file path=\"{}\">\n{contents}\n</file>", path.display()),
+    })
+}
+
+/// Loads the history of sub-agent conversation `id`, which must have been
+/// started by `parent` so a model cannot reattach to unrelated sessions.
+async fn load_coco_history(
+    codex_home: &Path,
+    id: &str,
+    parent: ConversationId,
+) -> Result<InitialHistory, CocoError> {
+    let not_found = || CocoError::Execution {
+        message: format!("no coco sub-agent conversation {id} was started from this conversation"),
+        log: Vec::new(),
+    };
+    let path = find_conversation_path_by_id_str(codex_home, id)
+        .await
+        .ok()
+        .flatten()
+        .ok_or_else(not_found)?;
+    let history = RolloutRecorder::get_rollout_history(&path)
+        .await
+        .map_err(|err| CocoError::Execution {
+            message: format!("failed to load coco sub-agent conversation {id}: {err}"),
+            log: Vec::new(),
+        })?;
+    let started_by_parent = history
+        .get_rollout_items()
+        .iter()
+        .find_map(|item| match item {
+            RolloutItem::SessionMeta(line) => Some(&line.meta.source),
+            _ => None,
+        })
+        .is_some_and(|source| {
+            matches!(
+                source,
+                SessionSource::SubAgent(SubAgentSource::Coco {
+                    parent_conversation_id,
+                    ..
+                }) if *parent_conversation_id == parent
+            )
+        });
+    if !started_by_parent {
+        return Err(not_found());
+    }
+    Ok(history)
+}
+
+fn lookup_sub_agent_profile(config: &Config, name: &str) -> Result<SubAgentProfile, CocoError> {
+    if let Some(profile) = config.agents.get(name) {
+        return Ok(profile.clone());
+    }
+    let mut available: Vec<&str> = config.agents.keys().map(String::as_str).collect();
+    available.sort_unstable();
+    let available = if available.is_empty() {
+        "none are configured".to_string()
+    } else {
+        format!("available: {}", available.join(", "))
+    };
+    Err(CocoError::Execution {
+        message: format!("unknown coco agent `{name}` ({available})"),
+        log: Vec::new(),
+    })
+}
+
+fn sandbox_policy_for_mode(mode: SandboxMode, current: &SandboxPolicy) -> SandboxPolicy {
+    match mode {
+        SandboxMode::ReadOnly => SandboxPolicy::new_read_only_policy(),
+        // Keep the parent's writable roots when it is already sandboxed the
+        // same way.
+        SandboxMode::WorkspaceWrite => match current {
+            policy @ SandboxPolicy::WorkspaceWrite { .. } => policy.clone(),
+            _ => SandboxPolicy::new_workspace_write_policy(),
+        },
+        SandboxMode::DangerFullAccess => SandboxPolicy::DangerFullAccess,
+    }
+}
+
+/// `cwd` must be an existing directory. A sub-agent that may write to its
+/// working directory must also stay within the parent's writable roots, so
+/// moving it elsewhere cannot widen the sandbox.
+fn check_sub_agent_cwd(
+    cwd: &Path,
+    turn: &TurnContext,
+    sandbox_policy: &SandboxPolicy,
+) -> Result<(), CocoError> {
+    let resolved = dunce::canonicalize(cwd)
+        .ok()
+        .filter(|path| path.is_dir())
+        .ok_or_else(|| CocoError::Execution {
+            message: format!("coco --cwd {} is not a directory", cwd.display()),
+            log: Vec::new(),
+        })?;
+    if !matches!(sandbox_policy, SandboxPolicy::WorkspaceWrite { .. })
+        || matches!(turn.sandbox_policy, SandboxPolicy::DangerFullAccess)
+    {
+        return Ok(());
+    }
+    let canonical = |path: &Path| dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let writable = turn
+        .sandbox_policy
+        .get_writable_roots_with_cwd(&turn.cwd)
+        .iter()
+        .any(|root| {
+            WritableRoot {
+                root: canonical(&root.root),
+                read_only_subpaths: root
+                    .read_only_subpaths
+                    .iter()
+                    .map(|path| canonical(path))
+                    .collect(),
+            }
+            .is_path_writable(&resolved)
+        });
+    if writable {
+        Ok(())
     } else {
-        collect_future.await
-    }?;
+        Err(CocoError::Execution {
+            message: format!(
+                "coco --cwd {} is outside the directories this session may write to",
+                cwd.display()
+            ),
+            log: Vec::new(),
+        })
+    }
+}
 
-    Ok(outcome)
+fn sandbox_rank(policy: &SandboxPolicy) -> u8 {
+    match policy {
+        SandboxPolicy::ReadOnly => 0,
+        SandboxPolicy::WorkspaceWrite { .. } => 1,
+        SandboxPolicy::DangerFullAccess => 2,
+    }
+}
+
+/// Orders approval policies from the one that asks the user most often to
+/// the one that never asks.
+fn approval_rank(policy: AskForApproval) -> u8 {
+    match policy {
+        AskForApproval::UnlessTrusted => 0,
+        AskForApproval::OnFailure => 1,
+        AskForApproval::OnRequest => 2,
+        AskForApproval::Never => 3,
+    }
+}
+
+/// Applies everything but the model, which the caller resolves against the
+/// request's own `model`. Like `--sandbox` and `--approval`, a profile cannot
+/// loosen the parent turn's sandbox or approval policy.
+fn apply_sub_agent_profile(
+    config: &mut Config,
+    name: &str,
+    profile: &SubAgentProfile,
+    turn: &TurnContext,
+) -> Result<(), CocoError> {
+    if let Some(instructions) = &profile.instructions {
+        config.developer_instructions = Some(instructions.clone());
+    }
+    if let Some(approval_policy) = profile.approval_policy {
+        if approval_rank(approval_policy) > approval_rank(turn.approval_policy) {
+            return Err(CocoError::Execution {
+                message: format!(
+                    "coco agent `{name}` sets approval_policy {approval_policy}, which is less restrictive than this session's approval policy"
+                ),
+                log: Vec::new(),
+            });
+        }
+        config.approval_policy = approval_policy;
+    }
+    if let Some(sandbox_mode) = profile.sandbox_mode {
+        let policy = sandbox_policy_for_mode(sandbox_mode, &config.sandbox_policy);
+        if sandbox_rank(&policy) > sandbox_rank(&turn.sandbox_policy) {
+            return Err(CocoError::Execution {
+                message: format!(
+                    "coco agent `{name}` sets sandbox_mode {sandbox_mode}, which is less restrictive than this session's sandbox"
+                ),
+                log: Vec::new(),
+            });
+        }
+        config.sandbox_policy = policy;
+    }
+    if let Some(tools) = &profile.tools {
+        config.allowed_tools = Some(narrow_tools(config.allowed_tools.take(), tools));
+    }
+    Ok(())
+}
+
+/// Applies `coco.tools` / `coco.disabled_tools` and the call's own tool
+/// restrictions. Allowlists only ever narrow what the parent (and the agent
+/// profile) already allow, so a sub-agent never gets a tool its parent lacks.
+fn restrict_sub_agent_tools(config: &mut Config, tools: Option<&[String]>, disabled: &[String]) {
+    let coco_tools = config.coco.tools.clone();
+    for tools in [coco_tools.as_deref(), tools].into_iter().flatten() {
+        config.allowed_tools = Some(narrow_tools(config.allowed_tools.take(), tools));
+    }
+    for tool in config.coco.disabled_tools.iter().chain(disabled) {
+        if !config.disabled_tools.contains(tool) {
+            config.disabled_tools.push(tool.clone());
+        }
+    }
+}
+
+/// The tools of `allowed` (every tool when `None`) that are also in `tools`.
+fn narrow_tools(allowed: Option<Vec<String>>, tools: &[String]) -> Vec<String> {
+    match allowed {
+        Some(allowed) => allowed
+            .into_iter()
+            .filter(|tool| tools.contains(tool))
+            .collect(),
+        None => tools.to_vec(),
+    }
+}
+
+struct CocoCollectOptions {
+    max_tokens: Option<i64>,
+    /// How long the sub-agent may run before it is cancelled.
+    timeout: Duration,
+    limits: CocoOutputLimits,
+    /// Written only when exec output had to be truncated.
+    full_log_path: PathBuf,
+    show_reasoning: bool,
+    /// Nesting depth of the sub-agent, stamped on its streamed log.
+    depth: u32,
 }
 
 async fn collect_coco_events(
     rx: Receiver<Event>,
+    options: CocoCollectOptions,
+    cancel_token: &CancellationToken,
     session: &Arc<crate::codex::Session>,
     turn: &Arc<TurnContext>,
     call_id: &str,
 ) -> Result<CocoRunOutcome, CocoError> {
-    let mut collector = CocoEventCollector::default();
+    let CocoCollectOptions {
+        max_tokens,
+        timeout,
+        limits,
+        full_log_path,
+        show_reasoning,
+        depth,
+    } = options;
+    let mut collector = CocoEventCollector::new(limits);
+    let mut source = SubAgentOutputSource {
+        depth,
+        conversation_id: None,
+    };
     let mut task_started_logged = false;
     let mut success = false;
     let mut failure_message: Option<String> = None;
+    let mut transient = false;
+    let mut timed_out = false;
+
+    let deadline = time::Instant::now() + timeout;
+    let mut live = CocoLiveOutput::new(session, turn, call_id);
 
-    while let Ok(event) = rx.recv().await {
+    loop {
+        let recv_until = live
+            .next_emit_at()
+            .map_or(deadline, |emit_at| emit_at.min(deadline));
+        let event = match time::timeout_at(recv_until, rx.recv()).await {
+            Ok(Ok(event)) => event,
+            Ok(Err(_)) => break,
+            Err(_) if time::Instant::now() < deadline => {
+                live.flush_due(&source).await;
+                continue;
+            }
+            Err(_) => {
+                if let Some(line) = collector.finalize_pending_agent() {
+                    live.line(&source, &line).await;
+                }
+                let message = format!(
+                    "coco sub-agent timed out after {}; partial results follow.",
+                    format_duration_compact(timeout)
+                );
+                if let Some(line) = collector.push_line(&message) {
+                    live.line(&source, &line).await;
+                }
+                cancel_token.cancel();
+                failure_message = Some(message);
+                timed_out = true;
+                break;
+            }
+        };
         match event.msg {
             EventMsg::AgentMessage(ev) => {
                 if let Some(line) = collector.commit_agent_message(&ev.message) {
-                    emit_coco_stdout_line(session, turn, call_id, &line).await;
+                    live.line(&source, &line).await;
                 }
             }
             EventMsg::AgentMessageDelta(ev) => {
                 collector.push_agent_delta(&ev.delta);
             }
+            EventMsg::AgentReasoning(ev) if show_reasoning => {
+                let trimmed = ev.text.trim_end();
+                if !trimmed.is_empty()
+                    && let Some(line) = collector.push_line(format!("thinking: {trimmed}"))
+                {
+                    live.line(&source, &line).await;
+                }
+            }
             EventMsg::AgentReasoningRawContent(ev) => {
                 let trimmed = ev.text.trim_end();
                 if !trimmed.is_empty()
-                    && let Some(line) = collector.push_line(format!("thinking: {trimmed}")) {
-                        emit_coco_stdout_line(session, turn, call_id, &line).await;
-                    }
+                    && let Some(line) = collector.push_line(format!("thinking: {trimmed}"))
+                {
+                    live.line(&source, &line).await;
+                }
             }
             EventMsg::AgentReasoningRawContentDelta(ev) => {
                 let trimmed = ev.delta.trim_end();
                 if !trimmed.is_empty()
-                    && let Some(line) = collector.push_line(format!("thinking: {trimmed}")) {
-                        emit_coco_stdout_line(session, turn, call_id, &line).await;
+                    && let Some(line) = collector.push_line(format!("thinking: {trimmed}"))
+                {
+                    live.line(&source, &line).await;
+                }
+            }
+            EventMsg::TokenCount(ev) => {
+                let Some(info) = ev.info else {
+                    continue;
+                };
+                let spent = collector.record_token_usage(&info.total_token_usage);
+                if !spent.is_zero() {
+                    session.add_sub_agent_token_usage(turn, &spent).await;
+                }
+                let used = info.total_token_usage.blended_total();
+                if let Some(max_tokens) = max_tokens
+                    && used > max_tokens
+                {
+                    if let Some(line) = collector.finalize_pending_agent() {
+                        live.line(&source, &line).await;
+                    }
+                    let message = format!(
+                        "coco sub-agent stopped after using {used} tokens, over its budget of {max_tokens}; partial results follow."
+                    );
+                    if let Some(line) = collector.push_line(&message) {
+                        live.line(&source, &line).await;
                     }
+                    cancel_token.cancel();
+                    failure_message = Some(message);
+                    break;
+                }
+            }
+            EventMsg::SessionConfigured(ev) => {
+                collector.conversation_id = Some(ev.session_id);
+                source.conversation_id = Some(ev.session_id);
+                if let Some(line) = collector.push_line(format!(
+                    "sub-agent session {} recorded at {}",
+                    ev.session_id,
+                    ev.rollout_path.display()
+                )) {
+                    live.line(&source, &line).await;
+                }
             }
             EventMsg::TaskStarted(_) => {
                 if !task_started_logged {
                     if let Some(line) = collector.push_line("sub-agent task started") {
-                        emit_coco_stdout_line(session, turn, call_id, &line).await;
+                        live.line(&source, &line).await;
                     }
                     task_started_logged = true;
                 }
@@ -419,7 +1999,7 @@ async fn collect_coco_events(
                     join_command(&ev.command),
                     ev.cwd.display()
                 )) {
-                    emit_coco_stdout_line(session, turn, call_id, &line).await;
+                    live.line(&source, &line).await;
                 }
             }
             EventMsg::ExecCommandEnd(ev) => {
@@ -428,42 +2008,46 @@ async fn collect_coco_events(
                     ev.exit_code,
                     format_duration_compact(ev.duration)
                 )) {
-                    emit_coco_stdout_line(session, turn, call_id, &line).await;
+                    live.line(&source, &line).await;
                 }
                 for line in collector.append_exec_output(&ev.aggregated_output) {
-                    emit_coco_stdout_line(session, turn, call_id, &line).await;
+                    live.line(&source, &line).await;
                 }
             }
             EventMsg::Warning(ev) => {
                 let trimmed = ev.message.trim_end();
                 if !trimmed.is_empty()
-                    && let Some(line) = collector.push_line(format!("warning: {trimmed}")) {
-                        emit_coco_stdout_line(session, turn, call_id, &line).await;
-                    }
+                    && let Some(line) = collector.push_line(format!("warning: {trimmed}"))
+                {
+                    live.line(&source, &line).await;
+                }
             }
             EventMsg::Error(ev) => {
                 let trimmed = ev.message.trim_end().to_string();
                 if !trimmed.is_empty()
-                    && let Some(line) = collector.push_line(format!("error: {trimmed}")) {
-                        emit_coco_stdout_line(session, turn, call_id, &line).await;
-                    }
+                    && let Some(line) = collector.push_line(format!("error: {trimmed}"))
+                {
+                    live.line(&source, &line).await;
+                }
+                transient = is_transient_failure(&trimmed);
                 failure_message = Some(trimmed);
                 break;
             }
             EventMsg::TaskComplete(ev) => {
                 if let Some(line) = collector.finalize_pending_agent() {
-                    emit_coco_stdout_line(session, turn, call_id, &line).await;
+                    live.line(&source, &line).await;
                 }
                 if let Some(last) = ev.last_agent_message.as_deref()
-                    && let Some(line) = collector.commit_agent_message(last) {
-                        emit_coco_stdout_line(session, turn, call_id, &line).await;
-                    }
+                    && let Some(line) = collector.commit_agent_message(last)
+                {
+                    live.line(&source, &line).await;
+                }
                 success = true;
                 break;
             }
             EventMsg::TurnAborted(ev) => {
                 if let Some(line) = collector.finalize_pending_agent() {
-                    emit_coco_stdout_line(session, turn, call_id, &line).await;
+                    live.line(&source, &line).await;
                 }
                 let reason = match ev.reason {
                     TurnAbortReason::Interrupted => "interrupted",
@@ -472,7 +2056,7 @@ async fn collect_coco_events(
                 };
                 let message = format!("sub-agent aborted ({reason})");
                 if let Some(line) = collector.push_line(&message) {
-                    emit_coco_stdout_line(session, turn, call_id, &line).await;
+                    live.line(&source, &line).await;
                 }
                 failure_message = Some(message);
                 break;
@@ -482,9 +2066,28 @@ async fn collect_coco_events(
     }
 
     if let Some(line) = collector.finalize_pending_agent() {
-        emit_coco_stdout_line(session, turn, call_id, &line).await;
+        live.line(&source, &line).await;
+    }
+    let mut written_log_path = None;
+    if collector.truncated {
+        match write_full_log(&full_log_path, &collector.full_lines).await {
+            Ok(()) => {
+                if let Some(line) =
+                    collector.push_line(format!("full log written to {}", full_log_path.display()))
+                {
+                    live.line(&source, &line).await;
+                }
+                written_log_path = Some(full_log_path);
+            }
+            Err(err) => warn!(
+                "failed to write coco log {}: {err}",
+                full_log_path.display()
+            ),
+        }
     }
+    live.flush(&source).await;
     let final_message = collector.last_agent_message().cloned();
+    let conversation_id = collector.conversation_id;
     let lines = collector.into_lines();
 
     if success {
@@ -496,7 +2099,10 @@ async fn collect_coco_events(
         }
         return Ok(CocoRunOutcome {
             final_message,
+            structured_output: None,
+            conversation_id,
             log: lines,
+            full_log_path: written_log_path,
             exit_code: 0,
         });
     }
@@ -504,8 +2110,503 @@ async fn collect_coco_events(
     let message = failure_message.unwrap_or_else(|| {
         "coco sub-agent ended unexpectedly without producing output.".to_string()
     });
+    if transient {
+        return Err(CocoError::Transient {
+            message,
+            log: lines,
+        });
+    }
+    if timed_out {
+        return Err(CocoError::TimedOut {
+            message,
+            log: lines,
+        });
+    }
     Err(CocoError::Execution {
         message,
         log: lines,
     })
 }
+
+/// Whether a sub-agent error reports a stream or rate-limit failure rather
+/// than one that would recur, such as an exhausted usage limit or context
+/// window. Matches the messages of the corresponding `CodexErr`s.
+fn is_transient_failure(message: &str) -> bool {
+    const TRANSIENT_PREFIXES: &[&str] = &[
+        "stream disconnected before completion",
+        "exceeded retry limit",
+        "Connection failed",
+        "Error while reading the server response",
+        "We're currently experiencing high demand",
+        "unexpected status 429",
+    ];
+    TRANSIENT_PREFIXES
+        .iter()
+        .any(|prefix| message.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::ConversationId;
+    use pretty_assertions::assert_eq;
+
+    fn parse(command: &[&str]) -> Option<(String, Option<String>)> {
+        let command: Vec<String> = command.iter().map(ToString::to_string).collect();
+        CocoInvocation::parse(&command).map(|invocation| (invocation.prompt, invocation.agent))
+    }
+
+    #[test]
+    fn parses_repeated_file_flags() {
+        let command: Vec<String> = [
+            "coco",
+            "--file",
+            "src/lib.rs",
+            "--cwd",
+            "crates/core",
+            "--agent=reviewer",
+            "--file=diagram.png",
+            "explain",
+            "these",
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+        let invocation = CocoInvocation::parse(&command).expect("coco command");
+
+        assert_eq!(invocation.prompt, "explain these");
+        assert_eq!(invocation.agent.as_deref(), Some("reviewer"));
+        assert_eq!(invocation.cwd.as_deref(), Some("crates/core"));
+        assert_eq!(invocation.files, vec!["src/lib.rs", "diagram.png"]);
+    }
+
+    #[test]
+    fn parses_sandbox_and_approval_flags() {
+        let command: Vec<String> = [
+            "coco",
+            "--sandbox",
+            "read-only",
+            "--approval=never",
+            "look",
+            "around",
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+        let invocation = CocoInvocation::parse(&command).expect("coco command");
+
+        assert_eq!(invocation.prompt, "look around");
+        let sandbox: SandboxMode = parse_flag_value(
+            "--sandbox",
+            invocation.sandbox.as_deref().unwrap_or_default(),
+        )
+        .expect("valid sandbox");
+        assert_eq!(sandbox, SandboxMode::ReadOnly);
+        let approval: AskForApproval = parse_flag_value(
+            "--approval",
+            invocation.approval.as_deref().unwrap_or_default(),
+        )
+        .expect("valid approval");
+        assert_eq!(approval, AskForApproval::Never);
+        assert_eq!(
+            parse_flag_value::<SandboxMode>("--sandbox", "none"),
+            Err(FunctionCallError::RespondToModel(
+                "invalid value `none` for coco --sandbox".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_timeout_flag() {
+        let command: Vec<String> = ["coco", "--timeout", "90", "run", "the", "tests"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let invocation = CocoInvocation::parse(&command).expect("coco command");
+
+        assert_eq!(invocation.prompt, "run the tests");
+        assert_eq!(invocation.timeout.as_deref(), Some("90"));
+        assert_eq!(
+            parse_limit("--timeout", "10m"),
+            Err(FunctionCallError::RespondToModel(
+                "invalid value `10m` for coco --timeout".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_agent_flag() {
+        assert_eq!(
+            parse(&["coco", "--agent", "reviewer", "check", "the", "diff"]),
+            Some(("check the diff".to_string(), Some("reviewer".to_string())))
+        );
+        assert_eq!(
+            parse(&["bash", "-lc", "coco --agent=reviewer 'check the diff'"]),
+            Some(("check the diff".to_string(), Some("reviewer".to_string())))
+        );
+        assert_eq!(
+            parse(&["coco", "fix", "--agent", "reviewer"]),
+            Some(("fix --agent reviewer".to_string(), None))
+        );
+        assert_eq!(parse(&["ls"]), None);
+    }
+
+    #[test]
+    fn exec_output_truncates_by_lines_and_bytes() {
+        let mut collector = CocoEventCollector::new(CocoOutputLimits {
+            max_lines: 2,
+            max_bytes: 1024,
+        });
+        assert_eq!(
+            collector.append_exec_output("a\nb\nc\nd"),
+            vec!["  a", "  b", COCO_TRUNCATION_NOTICE]
+        );
+        assert!(collector.truncated);
+        assert_eq!(collector.full_lines, vec!["  a", "  b", "  c", "  d"]);
+
+        let mut collector = CocoEventCollector::new(CocoOutputLimits {
+            max_lines: 100,
+            max_bytes: 8,
+        });
+        assert_eq!(
+            collector.append_exec_output("abc\ndef\nghi"),
+            vec!["  abc", COCO_TRUNCATION_NOTICE]
+        );
+
+        let mut collector = CocoEventCollector::new(CocoOutputLimits {
+            max_lines: 2,
+            max_bytes: 1024,
+        });
+        assert_eq!(collector.append_exec_output("a\nb"), vec!["  a", "  b"]);
+        assert!(!collector.truncated);
+    }
+
+    #[test]
+    fn token_usage_is_reported_as_deltas() {
+        let mut collector = CocoEventCollector::new(CocoOutputLimits {
+            max_lines: 10,
+            max_bytes: 1024,
+        });
+        let mut record = |input_tokens, output_tokens| {
+            let spent = collector.record_token_usage(&TokenUsage {
+                input_tokens,
+                output_tokens,
+                total_tokens: input_tokens + output_tokens,
+                ..TokenUsage::default()
+            });
+            (spent.input_tokens, spent.output_tokens, spent.total_tokens)
+        };
+
+        assert_eq!(record(100, 20), (100, 20, 120));
+        assert_eq!(record(100, 20), (0, 0, 0));
+        assert_eq!(record(250, 50), (150, 30, 180));
+    }
+
+    #[test]
+    fn only_stream_failures_are_retried() {
+        use crate::error::CodexErr;
+
+        for err in [
+            CodexErr::Stream("connection reset".to_string(), None),
+            CodexErr::InternalServerError,
+        ] {
+            assert!(is_transient_failure(&err.to_string()), "{err}");
+        }
+        for err in [CodexErr::ContextWindowExceeded, CodexErr::QuotaExceeded] {
+            assert!(!is_transient_failure(&err.to_string()), "{err}");
+        }
+        assert!(!is_transient_failure(
+            "coco sub-agent timed out after 1.000s; partial results follow."
+        ));
+    }
+
+    #[tokio::test]
+    async fn result_cache_is_keyed_by_request_and_expires() {
+        let request = |prompt: &str, model: Option<&str>| CocoRequest {
+            prompt: prompt.to_string(),
+            cwd: PathBuf::from("/repo"),
+            agent: None,
+            files: Vec::new(),
+            sandbox_mode: None,
+            approval_policy: None,
+            max_output_lines: None,
+            max_output_bytes: None,
+            model: model.map(str::to_string),
+            resume: None,
+            output_schema: None,
+            timeout_ms: None,
+            tools: None,
+            disabled_tools: Vec::new(),
+            command: Vec::new(),
+        };
+        let key = coco_cache_key(&request("summarize", None), "gpt-5").await;
+        assert_eq!(
+            key,
+            coco_cache_key(&request("summarize", Some("gpt-5")), "o3").await
+        );
+        assert_ne!(key, coco_cache_key(&request("summarize", None), "o3").await);
+        assert_ne!(
+            key,
+            coco_cache_key(&request("summarise", None), "gpt-5").await
+        );
+        let read_only = CocoRequest {
+            sandbox_mode: Some(SandboxMode::ReadOnly),
+            ..request("summarize", None)
+        };
+        assert_ne!(key, coco_cache_key(&read_only, "gpt-5").await);
+        let never_asks = CocoRequest {
+            approval_policy: Some(AskForApproval::Never),
+            ..request("summarize", None)
+        };
+        assert_ne!(key, coco_cache_key(&never_asks, "gpt-5").await);
+
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes.md");
+        std::fs::write(&notes, "v1").unwrap();
+        let with_file = CocoRequest {
+            files: vec![notes.clone()],
+            ..request("summarize", None)
+        };
+        let before = coco_cache_key(&with_file, "gpt-5").await;
+        std::fs::write(&notes, "v2").unwrap();
+        assert_ne!(before, coco_cache_key(&with_file, "gpt-5").await);
+
+        let mut cache = CocoResultCache::default();
+        cache.put(
+            key,
+            CocoRunOutcome {
+                final_message: Some("done".to_string()),
+                structured_output: None,
+                conversation_id: None,
+                log: Vec::new(),
+                full_log_path: None,
+                exit_code: 0,
+            },
+        );
+        let hit = cache.get(key, Duration::from_secs(60));
+        assert_eq!(
+            hit.and_then(|outcome| outcome.final_message).as_deref(),
+            Some("done")
+        );
+        assert!(cache.get(key, Duration::ZERO).is_none());
+        assert!(cache.get(key + 1, Duration::from_secs(60)).is_none());
+    }
+
+    #[test]
+    fn stats_summarize_runs_since_last_report() {
+        let failure = |timed_out: bool| {
+            let (message, log) = ("failed".to_string(), Vec::new());
+            Err(if timed_out {
+                CocoError::TimedOut { message, log }
+            } else {
+                CocoError::Execution { message, log }
+            })
+        };
+        let mut stats = CocoStats::default();
+        assert_eq!(stats.take_summary(), None);
+
+        stats.record(
+            Duration::from_secs(2),
+            &Ok(CocoRunOutcome {
+                final_message: Some("done".to_string()),
+                structured_output: None,
+                conversation_id: None,
+                log: Vec::new(),
+                full_log_path: None,
+                exit_code: 0,
+            }),
+        );
+        stats.record(Duration::from_secs(3), &failure(true));
+        stats.record(Duration::from_secs(1), &failure(false));
+
+        let expected = SubAgentSummaryEvent {
+            invocations: 3,
+            succeeded: 1,
+            failed: 2,
+            timed_out: 1,
+            total_duration: Duration::from_secs(6),
+        };
+        assert_eq!(stats.take_summary(), Some(expected.clone()));
+        assert_eq!(stats.take_summary(), None);
+        assert_eq!(stats.totals(), expected);
+    }
+
+    #[test]
+    fn model_override_respects_allowlist() {
+        let command: Vec<String> = ["coco", "--model", "o4-mini", "summarize"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let invocation = CocoInvocation::parse(&command).expect("coco command");
+        assert_eq!(invocation.model.as_deref(), Some("o4-mini"));
+        assert_eq!(invocation.resume, None);
+        assert_eq!(invocation.prompt, "summarize");
+
+        let allowed = vec!["o4-mini".to_string(), "gpt-5".to_string()];
+        assert_eq!(check_model_allowed("o4-mini", Some(&allowed)), Ok(()));
+        assert_eq!(check_model_allowed("o3", None), Ok(()));
+        assert_eq!(
+            check_model_allowed("o3", Some(&allowed)),
+            Err(FunctionCallError::RespondToModel(
+                "coco --model o3 is not allowed; choose one of: o4-mini, gpt-5".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn tool_flags_only_narrow_the_parent_allowlist() {
+        let command: Vec<String> = [
+            "coco",
+            "--tools",
+            "shell, view_image,apply_patch",
+            "--disabled-tools=web_search",
+            "summarize",
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+        let invocation = CocoInvocation::parse(&command).expect("coco command");
+        let tools = split_tool_list(invocation.tools.as_deref().unwrap_or_default());
+        assert_eq!(tools, vec!["shell", "view_image", "apply_patch"]);
+        assert_eq!(invocation.disabled_tools.as_deref(), Some("web_search"));
+        assert_eq!(invocation.prompt, "summarize");
+
+        assert_eq!(narrow_tools(None, &tools), tools);
+        let parent = vec!["shell".to_string(), "update_plan".to_string()];
+        assert_eq!(narrow_tools(Some(parent), &tools), vec!["shell"]);
+    }
+
+    #[test]
+    fn batch_reads_prompts_from_piped_command() {
+        let command: Vec<String> = [
+            "bash",
+            "-lc",
+            "cat modules.txt | coco --batch --max-lines 20 'Summarize this module:'",
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+        let (producer, tokens) = split_coco_pipeline(&command).expect("coco pipeline");
+        assert_eq!(producer, vec!["cat", "modules.txt"]);
+        let invocation = CocoInvocation::parse(&tokens).expect("coco command");
+        assert!(invocation.batch);
+        assert_eq!(invocation.max_lines.as_deref(), Some("20"));
+        assert_eq!(invocation.prompt, "Summarize this module:");
+
+        let plain: Vec<String> = ["bash", "-lc", "cat modules.txt | wc -l"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(split_coco_pipeline(&plain), None);
+
+        let prompts = parse_batch_prompts(
+            "core/src\n\n\"tui/src\"\n{\"prompt\": \"exec/src\"}\n{\"path\": \"cli\"}\n",
+        );
+        assert_eq!(
+            prompts,
+            vec!["core/src", "tui/src", "exec/src", "{\"path\": \"cli\"}"]
+        );
+    }
+
+    #[test]
+    fn parses_resume_subcommand() {
+        let command: Vec<String> = [
+            "coco",
+            "resume",
+            "5973b6c0-94b8-487b-a530-2aeb6098ae0e",
+            "--max-lines=50",
+            "now",
+            "fix",
+            "it",
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+        let invocation = CocoInvocation::parse(&command).expect("coco command");
+
+        assert_eq!(
+            invocation.resume.as_deref(),
+            Some("5973b6c0-94b8-487b-a530-2aeb6098ae0e")
+        );
+        assert_eq!(invocation.max_lines.as_deref(), Some("50"));
+        assert_eq!(invocation.timeout, None);
+        assert_eq!(invocation.prompt, "now fix it");
+    }
+
+    #[test]
+    fn parses_flow_run_subcommand() {
+        let command: Vec<String> = ["coco", "flow", "run", "flows/review.toml"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let invocation = CocoInvocation::parse(&command).expect("coco command");
+
+        assert_eq!(
+            invocation.flow_workflow.as_deref(),
+            Some("flows/review.toml")
+        );
+        assert_eq!(invocation.prompt, "");
+
+        let command: Vec<String> = ["coco", "flow", "run", "the", "tests"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let invocation = CocoInvocation::parse(&command).expect("coco command");
+        assert_eq!(invocation.flow_workflow, None);
+        assert_eq!(invocation.prompt, "flow run the tests");
+    }
+
+    #[test]
+    fn structured_output_must_match_schema() {
+        let outcome = |message: &str| CocoRunOutcome {
+            final_message: Some(message.to_string()),
+            structured_output: None,
+            conversation_id: None,
+            log: vec!["assistant: done".to_string()],
+            full_log_path: None,
+            exit_code: 0,
+        };
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": { "ok": { "type": "boolean" } },
+            "required": ["ok"],
+        });
+
+        let parsed = parse_structured_output(outcome(r#"{"ok":true}"#), &schema)
+            .map(|outcome| outcome.structured_output);
+        assert!(matches!(parsed, Ok(Some(value)) if value == serde_json::json!({ "ok": true })));
+
+        match parse_structured_output(outcome(r#"{"ok":"yes"}"#), &schema) {
+            Err(CocoError::Execution { message, log }) => {
+                assert_eq!(
+                    message,
+                    "coco sub-agent output does not match the schema: $.ok: expected boolean, got string"
+                );
+                assert_eq!(log, vec!["assistant: done".to_string()]);
+            }
+            Err(CocoError::Transient { message, .. } | CocoError::TimedOut { message, .. }) => {
+                panic!("schema mismatch reported as another failure: {message}")
+            }
+            Ok(_) => panic!("mismatched output was accepted"),
+        }
+        assert!(parse_structured_output(outcome("not json"), &schema).is_err());
+    }
+
+    #[test]
+    fn coco_depth_is_limited() {
+        let coco = |depth| {
+            SessionSource::SubAgent(SubAgentSource::Coco {
+                depth,
+                parent_conversation_id: ConversationId::new(),
+                call_id: "call".to_string(),
+            })
+        };
+        assert_eq!(next_coco_depth(&SessionSource::Cli, 2), Ok(1));
+        assert_eq!(next_coco_depth(&coco(1), 2), Ok(2));
+        assert!(matches!(
+            next_coco_depth(&coco(2), 2),
+            Err(FunctionCallError::RespondToModel(_))
+        ));
+        assert!(next_coco_depth(&SessionSource::Exec, 0).is_err());
+    }
+}
@@ -81,6 +81,17 @@ impl ToolOutput {
         }
     }
 
+    /// Size of the result as sent back to the model.
+    pub fn output_bytes(&self) -> usize {
+        match self {
+            ToolOutput::Function { content, .. } => content.len(),
+            ToolOutput::Mcp { result: Ok(result) } => {
+                serde_json::to_vec(result).map_or(0, |bytes| bytes.len())
+            }
+            ToolOutput::Mcp { result: Err(err) } => err.len(),
+        }
+    }
+
     pub fn success_for_logging(&self) -> bool {
         match self {
             ToolOutput::Function { success, .. } => success.unwrap_or(true),
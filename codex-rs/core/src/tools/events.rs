@@ -21,6 +21,7 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::time::Duration;
 
+use super::ShellOutputLimits;
 use super::format_exec_output_str;
 
 #[derive(Clone, Copy)]
@@ -89,6 +90,7 @@ pub(crate) enum ToolEmitter {
         source: ExecCommandSource,
         parsed_cmd: Vec<ParsedCommand>,
         freeform: bool,
+        output_limits: ShellOutputLimits,
     },
     ApplyPatch {
         changes: HashMap<PathBuf, FileChange>,
@@ -117,9 +119,18 @@ impl ToolEmitter {
             source,
             parsed_cmd,
             freeform,
+            output_limits: ShellOutputLimits::default(),
         }
     }
 
+    /// Caps the output returned to the model for a `Shell` emitter.
+    pub fn with_output_limits(mut self, limits: ShellOutputLimits) -> Self {
+        if let Self::Shell { output_limits, .. } = &mut self {
+            *output_limits = limits;
+        }
+        self
+    }
+
     pub fn apply_patch(changes: HashMap<PathBuf, FileChange>, auto_approved: bool) -> Self {
         Self::ApplyPatch {
             changes,
@@ -247,6 +258,11 @@ impl ToolEmitter {
         output: &ExecToolCallOutput,
         ctx: ToolEventCtx<'_>,
     ) -> String {
+        let limited = match self {
+            Self::Shell { output_limits, .. } => output_limits.apply(output, ctx.call_id),
+            _ => None,
+        };
+        let output = limited.as_ref().unwrap_or(output);
         match self {
             Self::Shell { freeform: true, .. } => {
                 super::format_exec_output_for_model_freeform(output, ctx.turn.truncation_policy)
@@ -0,0 +1,222 @@
+//! Remembers what each file looked like when the model last read it, so edit
+//! tools can refuse to work from a stale view of a file that changed since.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchFileChange;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::bash::parse_shell_lc_plain_commands;
+
+type ContentHash = [u8; 32];
+
+#[derive(Debug, Default)]
+pub(crate) struct FileReadTracker {
+    reads: HashMap<PathBuf, ContentHash>,
+}
+
+impl FileReadTracker {
+    /// Records that the model has seen `path` with these contents.
+    pub(crate) fn record_read(&mut self, path: &Path, contents: &[u8]) {
+        self.reads.insert(normalize(path), hash(contents));
+    }
+
+    /// Errors when `path` was read before and its contents (`None` if the
+    /// file is gone) no longer match what the model saw.
+    pub(crate) fn check_unchanged(
+        &self,
+        path: &Path,
+        contents: Option<&[u8]>,
+    ) -> Result<(), String> {
+        let Some(seen) = self.reads.get(&normalize(path)) else {
+            return Ok(());
+        };
+        if contents.map(hash).as_ref() == Some(seen) {
+            return Ok(());
+        }
+        Err(format!(
+            "conflict: {} has changed since you last read it. Read the file again and redo the edit against its current contents.",
+            path.display()
+        ))
+    }
+
+    /// Checks every file `action` writes, including move destinations, so no
+    /// patch is applied on top of a stale read whichever tool produced it.
+    pub(crate) async fn check_action(&self, action: &ApplyPatchAction) -> Result<(), String> {
+        for (path, change) in action.changes() {
+            let mut paths = vec![path];
+            if let ApplyPatchFileChange::Update {
+                move_path: Some(move_path),
+                ..
+            } = change
+            {
+                paths.push(move_path);
+            }
+            for path in paths {
+                let contents = tokio::fs::read(path).await.ok();
+                self.check_unchanged(path, contents.as_deref())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Brings already-read entries for `paths` up to date after the model's
+    /// own edit, so its next edit is not mistaken for working from a stale
+    /// read.
+    pub(crate) async fn refresh(&mut self, paths: &[PathBuf]) {
+        for path in paths {
+            let key = normalize(path);
+            if !self.reads.contains_key(&key) {
+                continue;
+            }
+            match tokio::fs::read(path).await {
+                Ok(contents) => {
+                    self.reads.insert(key, hash(&contents));
+                }
+                Err(_) => {
+                    self.reads.remove(&key);
+                }
+            }
+        }
+    }
+}
+
+/// Files printed in full by a shell command made up only of `cat` calls
+/// (directly or inside `bash -lc`). Relative paths are resolved against
+/// `cwd`, or skipped when the directory the command ran in is not known.
+/// Anything besides `cat` in the command could have changed the files after
+/// they were printed, so such commands yield nothing.
+pub(crate) fn shell_read_paths(command: &[String], cwd: Option<&Path>) -> Vec<PathBuf> {
+    let commands = parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()]);
+    let mut paths = Vec::new();
+    for words in commands {
+        let args = match words.split_first() {
+            Some((program, args)) if program == "cat" => args,
+            _ => return Vec::new(),
+        };
+        for arg in args {
+            if arg == "--" {
+                continue;
+            }
+            if arg.starts_with('-') {
+                return Vec::new();
+            }
+            let path = Path::new(arg);
+            if path.is_absolute() {
+                paths.push(path.to_path_buf());
+            } else if let Some(cwd) = cwd {
+                paths.push(cwd.join(path));
+            }
+        }
+    }
+    paths
+}
+
+/// The key a file is tracked under, so `./a.rs`, `a.rs` and a symlinked path
+/// to the same file all match. A file that no longer exists is keyed through
+/// its canonical parent directory.
+fn normalize(path: &Path) -> PathBuf {
+    if let Ok(canonical) = std::fs::canonicalize(path) {
+        return canonical;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => match std::fs::canonicalize(parent) {
+            Ok(parent) => parent.join(name),
+            Err(_) => path.components().collect(),
+        },
+        _ => path.components().collect(),
+    }
+}
+
+fn hash(contents: &[u8]) -> ContentHash {
+    Sha256::digest(contents).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn edits_conflict_only_after_outside_changes() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("lib.rs");
+        let unread = tmp.path().join("main.rs");
+        let mut tracker = FileReadTracker::default();
+
+        assert_eq!(tracker.check_unchanged(&unread, Some(b"anything")), Ok(()));
+
+        tracker.record_read(&path, b"fn a() {}\n");
+        assert_eq!(tracker.check_unchanged(&path, Some(b"fn a() {}\n")), Ok(()));
+        let err = tracker
+            .check_unchanged(&path, Some(b"fn b() {}\n"))
+            .unwrap_err();
+        assert!(err.contains("has changed since you last read it"), "{err}");
+        assert!(tracker.check_unchanged(&path, None).is_err());
+
+        // The model's own edit moves the baseline forward.
+        std::fs::write(&path, "fn c() {}\n").unwrap();
+        tracker.refresh(&[path.clone(), unread.clone()]).await;
+        assert_eq!(tracker.check_unchanged(&path, Some(b"fn c() {}\n")), Ok(()));
+        assert_eq!(tracker.check_unchanged(&unread, Some(b"anything")), Ok(()));
+    }
+
+    #[test]
+    fn reads_and_edits_match_through_equivalent_paths() {
+        let tmp = tempdir().unwrap();
+        std::fs::create_dir(tmp.path().join("src")).unwrap();
+        std::fs::write(tmp.path().join("a.rs"), "fn a() {}\n").unwrap();
+        let mut tracker = FileReadTracker::default();
+
+        tracker.record_read(&tmp.path().join("./a.rs"), b"fn a() {}\n");
+        assert!(
+            tracker
+                .check_unchanged(&tmp.path().join("a.rs"), Some(b"fn b() {}\n"))
+                .is_err()
+        );
+        std::fs::remove_file(tmp.path().join("a.rs")).unwrap();
+        assert!(
+            tracker
+                .check_unchanged(&tmp.path().join("src/../a.rs"), None)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn shell_read_paths_only_accepts_plain_cat() {
+        let cwd = Some(Path::new("/repo"));
+        let cat = vec!["cat".to_string(), "src/lib.rs".to_string()];
+        assert_eq!(
+            shell_read_paths(&cat, cwd),
+            vec![PathBuf::from("/repo/src/lib.rs")]
+        );
+
+        let script = vec![
+            "bash".to_string(),
+            "-lc".to_string(),
+            "cat -- a.rs && cat /tmp/b.rs".to_string(),
+        ];
+        assert_eq!(
+            shell_read_paths(&script, cwd),
+            vec![PathBuf::from("/repo/a.rs"), PathBuf::from("/tmp/b.rs")]
+        );
+        assert_eq!(
+            shell_read_paths(&script, None),
+            vec![PathBuf::from("/tmp/b.rs")]
+        );
+
+        let script = vec![
+            "bash".to_string(),
+            "-lc".to_string(),
+            "cat src/lib.rs && sed -i 's/a/b/' src/lib.rs".to_string(),
+        ];
+        assert_eq!(shell_read_paths(&script, cwd), Vec::<PathBuf>::new());
+        let numbered = vec!["cat".to_string(), "-n".to_string(), "a.rs".to_string()];
+        assert_eq!(shell_read_paths(&numbered, cwd), Vec::<PathBuf>::new());
+    }
+}
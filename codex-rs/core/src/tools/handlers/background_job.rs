@@ -0,0 +1,160 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::unified_exec::UnifiedExecResponse;
+use crate::unified_exec::WriteStdinRequest;
+
+/// Handles `check_job` and `kill_job` for commands started with
+/// `shell` and `run_in_background: true`.
+pub struct BackgroundJobHandler;
+
+#[derive(Debug, Deserialize)]
+struct CheckJobArgs {
+    job_id: i32,
+    #[serde(default = "default_check_job_wait_ms")]
+    wait_ms: u64,
+    #[serde(default)]
+    max_output_tokens: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KillJobArgs {
+    job_id: i32,
+    #[serde(default)]
+    max_output_tokens: Option<usize>,
+}
+
+fn default_check_job_wait_ms() -> u64 {
+    1000
+}
+
+#[async_trait]
+impl ToolHandler for BackgroundJobHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            call_id,
+            tool_name,
+            payload,
+            ..
+        } = invocation;
+
+        let ToolPayload::Function { arguments } = payload else {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "unsupported payload for {tool_name}"
+            )));
+        };
+        let manager = &session.services.unified_exec_manager;
+
+        let content = match tool_name.as_str() {
+            "check_job" => {
+                let args: CheckJobArgs = serde_json::from_str(&arguments).map_err(|err| {
+                    FunctionCallError::RespondToModel(format!(
+                        "failed to parse check_job arguments: {err:?}"
+                    ))
+                })?;
+                let response = manager
+                    .write_stdin(WriteStdinRequest {
+                        call_id: &call_id,
+                        session_id: args.job_id,
+                        input: "",
+                        yield_time_ms: args.wait_ms,
+                        max_output_tokens: args.max_output_tokens,
+                    })
+                    .await
+                    .map_err(|err| {
+                        FunctionCallError::RespondToModel(format!(
+                            "check_job failed for job {}: {err}",
+                            args.job_id
+                        ))
+                    })?;
+                format_job_response(args.job_id, &response, "is still running")
+            }
+            "kill_job" => {
+                let args: KillJobArgs = serde_json::from_str(&arguments).map_err(|err| {
+                    FunctionCallError::RespondToModel(format!(
+                        "failed to parse kill_job arguments: {err:?}"
+                    ))
+                })?;
+                let response = manager
+                    .terminate_session(args.job_id, args.max_output_tokens)
+                    .await
+                    .map_err(|err| {
+                        FunctionCallError::RespondToModel(format!(
+                            "kill_job failed for job {}: {err}",
+                            args.job_id
+                        ))
+                    })?;
+                format_job_response(args.job_id, &response, "was killed")
+            }
+            other => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "unsupported background job tool {other}"
+                )));
+            }
+        };
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+fn format_job_response(job_id: i32, response: &UnifiedExecResponse, running: &str) -> String {
+    let status = match (response.session_id, response.exit_code) {
+        (Some(_), _) => format!("Job {job_id} {running}."),
+        (None, Some(exit_code)) => format!("Job {job_id} exited with code {exit_code}."),
+        (None, None) => format!("Job {job_id} {running}."),
+    };
+    format!(
+        "{status}\nOutput since the last check:\n{}",
+        response.output
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn response(session_id: Option<i32>, exit_code: Option<i32>) -> UnifiedExecResponse {
+        UnifiedExecResponse {
+            event_call_id: "call".to_string(),
+            chunk_id: "abc123".to_string(),
+            wall_time: Duration::from_millis(5),
+            output: "ready\n".to_string(),
+            session_id,
+            exit_code,
+            original_token_count: None,
+            session_command: None,
+        }
+    }
+
+    #[test]
+    fn job_response_reports_running_and_exited_jobs() {
+        assert_eq!(
+            format_job_response(3, &response(Some(3), None), "is still running"),
+            "Job 3 is still running.\nOutput since the last check:\nready\n"
+        );
+        assert_eq!(
+            format_job_response(3, &response(None, Some(1)), "is still running"),
+            "Job 3 exited with code 1.\nOutput since the last check:\nready\n"
+        );
+        assert_eq!(
+            format_job_response(3, &response(None, None), "was killed"),
+            "Job 3 was killed.\nOutput since the last check:\nready\n"
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use async_trait::async_trait;
+use codex_protocol::config_types::SandboxMode;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::function_tool::FunctionCallError;
+use crate::protocol::AskForApproval;
+use crate::tools::coco_subagent::CocoRequest;
+use crate::tools::coco_subagent::run_coco_request;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct CocoHandler;
+
+pub struct CocoStatusHandler;
+
+#[derive(Debug, Deserialize)]
+struct CocoToolCallParams {
+    prompt: String,
+    #[serde(default)]
+    cwd: Option<String>,
+    #[serde(default)]
+    resume: Option<String>,
+    #[serde(default)]
+    files: Vec<String>,
+    #[serde(default)]
+    agent: Option<String>,
+    #[serde(default)]
+    sandbox: Option<SandboxMode>,
+    #[serde(default)]
+    approval: Option<AskForApproval>,
+    #[serde(default)]
+    max_output_lines: Option<usize>,
+    #[serde(default)]
+    max_output_bytes: Option<usize>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    timeout_ms: Option<u64>,
+    #[serde(default)]
+    output_schema: Option<String>,
+    #[serde(default)]
+    tools: Option<Vec<String>>,
+    #[serde(default)]
+    disabled_tools: Vec<String>,
+}
+
+impl CocoToolCallParams {
+    /// The equivalent `coco` command line, shown in exec events.
+    fn command(&self) -> Vec<String> {
+        let mut command = vec!["coco".to_string()];
+        if let Some(resume) = &self.resume {
+            command.push("resume".to_string());
+            command.push(resume.clone());
+        }
+        if let Some(agent) = &self.agent {
+            command.push("--agent".to_string());
+            command.push(agent.clone());
+        }
+        if let Some(sandbox) = self.sandbox {
+            command.push("--sandbox".to_string());
+            command.push(sandbox.to_string());
+        }
+        if let Some(approval) = self.approval {
+            command.push("--approval".to_string());
+            command.push(approval.to_string());
+        }
+        if let Some(max_lines) = self.max_output_lines {
+            command.push("--max-lines".to_string());
+            command.push(max_lines.to_string());
+        }
+        if let Some(max_bytes) = self.max_output_bytes {
+            command.push("--max-bytes".to_string());
+            command.push(max_bytes.to_string());
+        }
+        for file in &self.files {
+            command.push("--file".to_string());
+            command.push(file.clone());
+        }
+        if let Some(model) = &self.model {
+            command.push("--model".to_string());
+            command.push(model.clone());
+        }
+        if let Some(output_schema) = &self.output_schema {
+            command.push("--output-schema".to_string());
+            command.push(output_schema.clone());
+        }
+        if let Some(tools) = &self.tools {
+            command.push("--tools".to_string());
+            command.push(tools.join(","));
+        }
+        if !self.disabled_tools.is_empty() {
+            command.push("--disabled-tools".to_string());
+            command.push(self.disabled_tools.join(","));
+        }
+        command.push(self.prompt.clone());
+        command
+    }
+}
+
+#[async_trait]
+impl ToolHandler for CocoHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "coco handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let params: CocoToolCallParams = serde_json::from_str(&arguments).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to parse function arguments: {err:?}"
+            ))
+        })?;
+        let cwd = turn.resolve_path(params.cwd.clone());
+        let request = CocoRequest {
+            command: params.command(),
+            files: params.files.iter().map(|file| cwd.join(file)).collect(),
+            output_schema: params.output_schema.map(|path| cwd.join(path)),
+            cwd,
+            prompt: params.prompt,
+            agent: params.agent,
+            sandbox_mode: params.sandbox,
+            approval_policy: params.approval,
+            max_output_lines: params.max_output_lines,
+            max_output_bytes: params.max_output_bytes,
+            model: params.model,
+            timeout_ms: params.timeout_ms,
+            resume: params.resume,
+            tools: params.tools,
+            disabled_tools: params.disabled_tools,
+        };
+        run_coco_request(request, &session, &turn, &call_id, false).await
+    }
+}
+
+#[async_trait]
+impl ToolHandler for CocoStatusHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        if !matches!(invocation.payload, ToolPayload::Function { .. }) {
+            return Err(FunctionCallError::RespondToModel(
+                "coco_status handler received unsupported payload".to_string(),
+            ));
+        }
+        let totals = invocation.session.services.coco_stats.lock().await.totals();
+        let content = json!({
+            "invocations": totals.invocations,
+            "succeeded": totals.succeeded,
+            "failed": totals.failed,
+            "timed_out": totals.timed_out,
+            "total_duration_ms": totals.total_duration.as_millis(),
+        })
+        .to_string();
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
@@ -8,6 +8,10 @@ use crate::tools::context::ToolPayload;
 use crate::tools::events::ToolEmitter;
 use crate::tools::events::ToolEventCtx;
 use crate::tools::handlers::legacy_edit;
+use crate::tools::handlers::legacy_edit::EditPolicy;
+use crate::tools::handlers::legacy_edit::LineEndings;
+use crate::tools::handlers::legacy_edit::ReplaceEdit;
+use crate::tools::handlers::legacy_edit::WriteFileOptions;
 use crate::tools::orchestrator::ToolOrchestrator;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
@@ -30,6 +34,14 @@ pub struct EditHandler;
 struct WriteFileToolArgs {
     file_path: String,
     content: String,
+    #[serde(default)]
+    create_parents: Option<bool>,
+    #[serde(default)]
+    must_exist: bool,
+    #[serde(default)]
+    preview: bool,
+    #[serde(default)]
+    line_endings: Option<LineEndings>,
     #[serde(flatten)]
     _extra: HashMap<String, JsonValue>,
 }
@@ -43,6 +55,72 @@ struct ReplaceToolArgs {
     _instruction: Option<String>,
     #[serde(default)]
     expected_replacements: Option<usize>,
+    #[serde(default)]
+    fuzzy_whitespace: bool,
+    #[serde(default)]
+    preview: bool,
+    #[serde(default)]
+    line_endings: Option<LineEndings>,
+    #[serde(flatten)]
+    _extra: HashMap<String, JsonValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReplaceRegexToolArgs {
+    file_path: String,
+    pattern: String,
+    replacement: String,
+    #[serde(default)]
+    expected_matches: Option<usize>,
+    #[serde(default)]
+    preview: bool,
+    #[serde(default)]
+    line_endings: Option<LineEndings>,
+    #[serde(flatten)]
+    _extra: HashMap<String, JsonValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MultiEditToolArgs {
+    file_path: String,
+    edits: Vec<MultiEditEntry>,
+    #[serde(default)]
+    preview: bool,
+    #[serde(default)]
+    line_endings: Option<LineEndings>,
+    #[serde(flatten)]
+    _extra: HashMap<String, JsonValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MultiEditEntry {
+    old_string: String,
+    new_string: String,
+    #[serde(default)]
+    expected_replacements: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InsertAtLineToolArgs {
+    file_path: String,
+    line_number: usize,
+    content: String,
+    #[serde(default)]
+    preview: bool,
+    #[serde(default)]
+    line_endings: Option<LineEndings>,
+    #[serde(flatten)]
+    _extra: HashMap<String, JsonValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AppendToFileToolArgs {
+    file_path: String,
+    content: String,
+    #[serde(default)]
+    preview: bool,
+    #[serde(default)]
+    line_endings: Option<LineEndings>,
     #[serde(flatten)]
     _extra: HashMap<String, JsonValue>,
 }
@@ -50,6 +128,8 @@ struct ReplaceToolArgs {
 #[derive(Debug, Deserialize)]
 struct DeleteFileToolArgs {
     file_path: String,
+    #[serde(default)]
+    preview: bool,
     #[serde(flatten)]
     _extra: HashMap<String, JsonValue>,
 }
@@ -80,7 +160,11 @@ impl ToolHandler for EditHandler {
         };
 
         let cwd = turn.cwd.clone();
-        let mut target_path: Option<String> = None;
+        let policy = EditPolicy::from_config(&turn.client.config());
+        let target_path: Option<String>;
+        let preview: bool;
+        let mut line_endings: Option<LineEndings> = None;
+        let mut match_note: Option<String> = None;
         let action = match tool_name.as_str() {
             "write_file" => {
                 let params: WriteFileToolArgs =
@@ -103,7 +187,13 @@ impl ToolHandler for EditHandler {
                     "write_file invocation received"
                 );
                 target_path = Some(params.file_path.clone());
-                build_write_file_action(&params.file_path, &params.content, &cwd)?
+                preview = params.preview;
+                line_endings = params.line_endings;
+                let options = WriteFileOptions {
+                    create_parents: params.create_parents.unwrap_or(true),
+                    must_exist: params.must_exist,
+                };
+                build_write_file_action(&params.file_path, &params.content, options, &policy, &cwd)?
             }
             "replace" => {
                 let params: ReplaceToolArgs = serde_json::from_str(&arguments).map_err(|err| {
@@ -124,17 +214,168 @@ impl ToolHandler for EditHandler {
                     old_bytes = params.old_string.len(),
                     new_bytes = params.new_string.len(),
                     expected_replacements = ?params.expected_replacements,
+                    fuzzy_whitespace = params.fuzzy_whitespace,
                     "replace invocation received"
                 );
                 target_path = Some(params.file_path.clone());
-                build_replace_action(
+                preview = params.preview;
+                line_endings = params.line_endings;
+                if params.fuzzy_whitespace {
+                    let (action, fuzzy) = legacy_edit::build_replace_action_with_fuzzy_fallback(
+                        &params.file_path,
+                        &params.old_string,
+                        &params.new_string,
+                        params.expected_replacements,
+                        &policy,
+                        &cwd,
+                    )
+                    .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?;
+                    if let Some(fuzzy) = fuzzy {
+                        info!(
+                            tool = "replace",
+                            %call_id,
+                            start_line = fuzzy.start_line,
+                            end_line = fuzzy.end_line,
+                            "replace fell back to whitespace-insensitive matching"
+                        );
+                        match_note = Some(fuzzy.describe());
+                    }
+                    action
+                } else {
+                    build_replace_action(
+                        &params.file_path,
+                        &params.old_string,
+                        &params.new_string,
+                        params.expected_replacements,
+                        &policy,
+                        &cwd,
+                    )?
+                }
+            }
+            "replace_regex" => {
+                let params: ReplaceRegexToolArgs =
+                    serde_json::from_str(&arguments).map_err(|err| {
+                        warn!(
+                            tool = "replace_regex",
+                            %call_id,
+                            error = ?err,
+                            "failed to parse replace_regex arguments"
+                        );
+                        FunctionCallError::RespondToModel(format!(
+                            "replace_regex arguments could not be parsed as JSON: {err}"
+                        ))
+                    })?;
+                info!(
+                    tool = "replace_regex",
+                    %call_id,
+                    path = %params.file_path,
+                    pattern_bytes = params.pattern.len(),
+                    expected_matches = ?params.expected_matches,
+                    "replace_regex invocation received"
+                );
+                target_path = Some(params.file_path.clone());
+                preview = params.preview;
+                line_endings = params.line_endings;
+                build_replace_regex_action(
+                    &params.file_path,
+                    &params.pattern,
+                    &params.replacement,
+                    params.expected_matches,
+                    &policy,
+                    &cwd,
+                )?
+            }
+            "multi_edit" => {
+                let params: MultiEditToolArgs =
+                    serde_json::from_str(&arguments).map_err(|err| {
+                        warn!(
+                            tool = "multi_edit",
+                            %call_id,
+                            error = ?err,
+                            "failed to parse multi_edit arguments"
+                        );
+                        FunctionCallError::RespondToModel(format!(
+                            "multi_edit arguments could not be parsed as JSON: {err}"
+                        ))
+                    })?;
+                info!(
+                    tool = "multi_edit",
+                    %call_id,
+                    path = %params.file_path,
+                    edits = params.edits.len(),
+                    "multi_edit invocation received"
+                );
+                target_path = Some(params.file_path.clone());
+                preview = params.preview;
+                line_endings = params.line_endings;
+                let edits: Vec<ReplaceEdit> = params
+                    .edits
+                    .into_iter()
+                    .map(|edit| ReplaceEdit {
+                        old: edit.old_string,
+                        new: edit.new_string,
+                        expected_replacements: edit.expected_replacements,
+                    })
+                    .collect();
+                build_multi_edit_action(&params.file_path, &edits, &policy, &cwd)?
+            }
+            "insert_at_line" => {
+                let params: InsertAtLineToolArgs =
+                    serde_json::from_str(&arguments).map_err(|err| {
+                        warn!(
+                            tool = "insert_at_line",
+                            %call_id,
+                            error = ?err,
+                            "failed to parse insert_at_line arguments"
+                        );
+                        FunctionCallError::RespondToModel(format!(
+                            "insert_at_line arguments could not be parsed as JSON: {err}"
+                        ))
+                    })?;
+                info!(
+                    tool = "insert_at_line",
+                    %call_id,
+                    path = %params.file_path,
+                    line_number = params.line_number,
+                    content_bytes = params.content.len(),
+                    "insert_at_line invocation received"
+                );
+                target_path = Some(params.file_path.clone());
+                preview = params.preview;
+                line_endings = params.line_endings;
+                build_insert_action(
                     &params.file_path,
-                    &params.old_string,
-                    &params.new_string,
-                    params.expected_replacements,
+                    params.line_number,
+                    &params.content,
+                    &policy,
                     &cwd,
                 )?
             }
+            "append_to_file" => {
+                let params: AppendToFileToolArgs =
+                    serde_json::from_str(&arguments).map_err(|err| {
+                        warn!(
+                            tool = "append_to_file",
+                            %call_id,
+                            error = ?err,
+                            "failed to parse append_to_file arguments"
+                        );
+                        FunctionCallError::RespondToModel(format!(
+                            "append_to_file arguments could not be parsed as JSON: {err}"
+                        ))
+                    })?;
+                info!(
+                    tool = "append_to_file",
+                    %call_id,
+                    path = %params.file_path,
+                    content_bytes = params.content.len(),
+                    "append_to_file invocation received"
+                );
+                target_path = Some(params.file_path.clone());
+                preview = params.preview;
+                line_endings = params.line_endings;
+                build_append_action(&params.file_path, &params.content, &policy, &cwd)?
+            }
             "delete" => {
                 let params: DeleteFileToolArgs =
                     serde_json::from_str(&arguments).map_err(|err| {
@@ -155,7 +396,8 @@ impl ToolHandler for EditHandler {
                     "delete invocation received"
                 );
                 target_path = Some(params.file_path.clone());
-                build_delete_action(&params.file_path, &cwd)?
+                preview = params.preview;
+                build_delete_action(&params.file_path, &policy, &cwd)?
             }
             other => {
                 warn!(tool = %other, %call_id, "unsupported edit tool");
@@ -165,10 +407,85 @@ impl ToolHandler for EditHandler {
             }
         };
 
-        let result = Self::execute_apply_patch_action(
+        // Applying re-checks this, but a preview should not show a diff
+        // against a stale read either.
+        if let Err(conflict) = session
+            .services
+            .file_reads
+            .lock()
+            .await
+            .check_action(&action)
+            .await
+        {
+            warn!(
+                tool = %tool_name,
+                %call_id,
+                path = target_path.as_deref(),
+                "edit tool refused: file changed since it was last read"
+            );
+            return Err(FunctionCallError::RespondToModel(conflict));
+        }
+
+        if preview {
+            info!(
+                tool = %tool_name,
+                %call_id,
+                path = target_path.as_deref(),
+                "edit tool previewed without applying"
+            );
+            let mut diff = legacy_edit::preview_diff(&action);
+            if let Some(note) = &match_note {
+                diff = format!("{note}\n\n{diff}");
+            }
+            return Ok(ToolOutput::Function {
+                content: format!(
+                    "{diff}\nPreview only; no changes were applied. Call {tool_name} again without preview to apply them."
+                ),
+                content_items: None,
+                success: Some(true),
+            });
+        }
+
+        let permissions = legacy_edit::PreservedPermissions::capture(&action);
+        let converted_paths = legacy_edit::written_paths(&action);
+        let mut result = Self::execute_apply_patch_action(
             &tool_name, action, &session, &turn, &tracker, &call_id,
         )
         .await;
+        if result.is_ok()
+            && let Some(line_endings) = line_endings
+        {
+            if let Err(err) = legacy_edit::convert_line_endings(&converted_paths, line_endings) {
+                warn!(
+                    tool = %tool_name,
+                    %call_id,
+                    error = ?err,
+                    "failed to convert line endings after edit"
+                );
+            }
+            // The conversion rewrote the files after the patch runtime
+            // recorded them.
+            session
+                .services
+                .file_reads
+                .lock()
+                .await
+                .refresh(&converted_paths)
+                .await;
+        }
+        if result.is_ok()
+            && let Err(err) = permissions.restore()
+        {
+            warn!(
+                tool = %tool_name,
+                %call_id,
+                error = ?err,
+                "failed to restore file permissions after edit"
+            );
+        }
+        if let (Some(note), Ok(ToolOutput::Function { content, .. })) = (&match_note, &mut result) {
+            *content = format!("{note}\n\n{content}");
+        }
 
         match &result {
             Ok(_) => info!(
@@ -193,9 +510,11 @@ impl ToolHandler for EditHandler {
 fn build_write_file_action(
     file_path: &str,
     content: &str,
+    options: WriteFileOptions,
+    policy: &EditPolicy,
     cwd: &Path,
 ) -> Result<ApplyPatchAction, FunctionCallError> {
-    legacy_edit::build_write_file_action(file_path, content, cwd)
+    legacy_edit::build_write_file_action(file_path, content, options, policy, cwd)
         .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))
 }
 
@@ -204,14 +523,69 @@ fn build_replace_action(
     old: &str,
     new: &str,
     expected_replacements: Option<usize>,
+    policy: &EditPolicy,
+    cwd: &Path,
+) -> Result<ApplyPatchAction, FunctionCallError> {
+    legacy_edit::build_replace_action(file_path, old, new, expected_replacements, policy, cwd)
+        .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))
+}
+
+fn build_replace_regex_action(
+    file_path: &str,
+    pattern: &str,
+    replacement: &str,
+    expected_matches: Option<usize>,
+    policy: &EditPolicy,
+    cwd: &Path,
+) -> Result<ApplyPatchAction, FunctionCallError> {
+    legacy_edit::build_replace_regex_action(
+        file_path,
+        pattern,
+        replacement,
+        expected_matches,
+        policy,
+        cwd,
+    )
+    .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))
+}
+
+fn build_multi_edit_action(
+    file_path: &str,
+    edits: &[ReplaceEdit],
+    policy: &EditPolicy,
+    cwd: &Path,
+) -> Result<ApplyPatchAction, FunctionCallError> {
+    legacy_edit::build_multi_edit_action(file_path, edits, policy, cwd)
+        .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))
+}
+
+fn build_insert_action(
+    file_path: &str,
+    line_number: usize,
+    content: &str,
+    policy: &EditPolicy,
     cwd: &Path,
 ) -> Result<ApplyPatchAction, FunctionCallError> {
-    legacy_edit::build_replace_action(file_path, old, new, expected_replacements, cwd)
+    legacy_edit::build_insert_action(file_path, line_number, content, policy, cwd)
         .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))
 }
 
-fn build_delete_action(file_path: &str, cwd: &Path) -> Result<ApplyPatchAction, FunctionCallError> {
-    legacy_edit::build_delete_file_action(file_path, cwd)
+fn build_append_action(
+    file_path: &str,
+    content: &str,
+    policy: &EditPolicy,
+    cwd: &Path,
+) -> Result<ApplyPatchAction, FunctionCallError> {
+    legacy_edit::build_append_action(file_path, content, policy, cwd)
+        .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))
+}
+
+fn build_delete_action(
+    file_path: &str,
+    policy: &EditPolicy,
+    cwd: &Path,
+) -> Result<ApplyPatchAction, FunctionCallError> {
+    legacy_edit::build_delete_file_action(file_path, policy, cwd)
         .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))
 }
 
@@ -0,0 +1,771 @@
+use std::path::Path;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use codex_utils_string::take_bytes_at_char_boundary;
+use serde::Deserialize;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio::process::Command;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::exec_env::create_env;
+use crate::exec_policy::create_approval_requirement_for_command;
+use crate::function_tool::FunctionCallError;
+use crate::protocol::ExecCommandSource;
+use crate::sandboxing::SandboxPermissions;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::events::ToolEmitter;
+use crate::tools::events::ToolEventCtx;
+use crate::tools::orchestrator::ToolOrchestrator;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::runtimes::shell::ShellRequest;
+use crate::tools::runtimes::shell::ShellRuntime;
+use crate::tools::sandboxing::ToolCtx;
+
+/// Serves `git_status`, `git_diff`, `git_commit` and `git_branch`. Reads run
+/// git directly, with the repo-configurable helpers they could trigger
+/// (external diff, textconv, fsmonitor) switched off; anything that changes the repository runs like a shell
+/// command, so it goes through the same approval and sandbox checks.
+pub struct GitHandler;
+
+const GIT_TIMEOUT_MS: u64 = 30_000;
+/// Longer diffs are cut off; `files` still lists every changed file.
+const MAX_DIFF_BYTES: usize = 64 * 1024;
+
+#[derive(Deserialize)]
+struct GitStatusArgs {
+    #[serde(default)]
+    workdir: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GitDiffArgs {
+    #[serde(default)]
+    workdir: Option<String>,
+    #[serde(default)]
+    staged: bool,
+    #[serde(default)]
+    base: Option<String>,
+    #[serde(default)]
+    paths: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct GitCommitArgs {
+    #[serde(default)]
+    workdir: Option<String>,
+    message: String,
+    #[serde(default)]
+    all: bool,
+    #[serde(default)]
+    paths: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct GitBranchArgs {
+    #[serde(default)]
+    workdir: Option<String>,
+    #[serde(default)]
+    action: BranchAction,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    start_point: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum BranchAction {
+    #[default]
+    List,
+    Create,
+    Switch,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct GitStatus {
+    /// `None` on a detached HEAD.
+    branch: Option<String>,
+    upstream: Option<String>,
+    ahead: u64,
+    behind: u64,
+    files: Vec<StatusEntry>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct StatusEntry {
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    original_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    staged: Option<FileState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unstaged: Option<FileState>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum FileState {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    Copied,
+    TypeChanged,
+    Conflicted,
+    Untracked,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct GitDiff {
+    files: Vec<DiffFile>,
+    diff: String,
+    truncated: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct DiffFile {
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    original_path: Option<String>,
+    additions: u64,
+    deletions: u64,
+    binary: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct GitCommit {
+    sha: String,
+    subject: String,
+    branch: Option<String>,
+    files: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct GitBranches {
+    current: Option<String>,
+    branches: Vec<Branch>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Branch {
+    name: String,
+    current: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upstream: Option<String>,
+    sha: String,
+}
+
+#[async_trait]
+impl ToolHandler for GitHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    fn is_mutating(&self, invocation: &ToolInvocation) -> bool {
+        match invocation.tool_name.as_str() {
+            "git_status" | "git_diff" => false,
+            "git_branch" => match &invocation.payload {
+                ToolPayload::Function { arguments } => {
+                    serde_json::from_str::<GitBranchArgs>(arguments)
+                        .map(|args| args.action != BranchAction::List)
+                        .unwrap_or(true)
+                }
+                _ => true,
+            },
+            _ => true,
+        }
+    }
+
+    fn is_read_only(&self, invocation: &ToolInvocation) -> bool {
+        !self.is_mutating(invocation)
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            tool_name,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "git handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let git = MutatingGit {
+            session: &session,
+            turn: &turn,
+            call_id: &call_id,
+            tool_name: &tool_name,
+        };
+        let content = match tool_name.as_str() {
+            "git_status" => {
+                let args: GitStatusArgs = parse_arguments(&arguments)?;
+                to_json(&git_status(&turn.resolve_path(args.workdir)).await?)
+            }
+            "git_diff" => {
+                let args: GitDiffArgs = parse_arguments(&arguments)?;
+                to_json(&git_diff(&turn.resolve_path(args.workdir.clone()), &args).await?)
+            }
+            "git_commit" => {
+                let args: GitCommitArgs = parse_arguments(&arguments)?;
+                to_json(&git_commit(&turn.resolve_path(args.workdir.clone()), &args, &git).await?)
+            }
+            "git_branch" => {
+                let args: GitBranchArgs = parse_arguments(&arguments)?;
+                to_json(&git_branch(&turn.resolve_path(args.workdir.clone()), &args, &git).await?)
+            }
+            _ => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "git handler does not support tool {tool_name}"
+                )));
+            }
+        };
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+fn parse_arguments<T: DeserializeOwned>(arguments: &str) -> Result<T, FunctionCallError> {
+    serde_json::from_str(arguments).map_err(|err| {
+        FunctionCallError::RespondToModel(format!("failed to parse function arguments: {err:?}"))
+    })
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|err| format!("{{\"error\":\"{err}\"}}"))
+}
+
+/// Rejects refs that git would read as an option.
+fn check_ref(kind: &str, value: &str) -> Result<(), FunctionCallError> {
+    if value.is_empty() || value.starts_with('-') {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "invalid {kind} `{value}`"
+        )));
+    }
+    Ok(())
+}
+
+async fn git_status(cwd: &Path) -> Result<GitStatus, FunctionCallError> {
+    let output = run_git(cwd, &["status", "--porcelain=v2", "--branch", "-z"]).await?;
+    Ok(parse_status(&output))
+}
+
+async fn git_diff(cwd: &Path, args: &GitDiffArgs) -> Result<GitDiff, FunctionCallError> {
+    // The diff drivers a repository configures are arbitrary commands.
+    let mut diff_args = vec!["diff", "--no-ext-diff", "--no-textconv"];
+    if args.staged {
+        diff_args.push("--cached");
+    }
+    if let Some(base) = &args.base {
+        check_ref("base", base)?;
+        diff_args.push(base);
+    }
+    let mut numstat_args = diff_args.clone();
+    numstat_args.extend(["--numstat", "-z"]);
+    let mut patch_args = diff_args;
+    for args_list in [&mut numstat_args, &mut patch_args] {
+        args_list.push("--");
+        args_list.extend(args.paths.iter().map(String::as_str));
+    }
+
+    let files = parse_numstat(&run_git(cwd, &numstat_args).await?);
+    let mut diff = run_git(cwd, &patch_args).await?;
+    let truncated = diff.len() > MAX_DIFF_BYTES;
+    if truncated {
+        diff = take_bytes_at_char_boundary(&diff, MAX_DIFF_BYTES).to_string();
+    }
+    Ok(GitDiff {
+        files,
+        diff,
+        truncated,
+    })
+}
+
+async fn git_commit(
+    cwd: &Path,
+    args: &GitCommitArgs,
+    git: &MutatingGit<'_>,
+) -> Result<GitCommit, FunctionCallError> {
+    if args.message.trim().is_empty() {
+        return Err(FunctionCallError::RespondToModel(
+            "message must not be empty".to_string(),
+        ));
+    }
+
+    if !args.paths.is_empty() {
+        let mut add = vec!["add".to_string(), "--".to_string()];
+        add.extend(args.paths.iter().cloned());
+        git.run(cwd, add).await?;
+    }
+    let mut commit = vec!["commit".to_string(), "-m".to_string(), args.message.clone()];
+    if args.all {
+        commit.push("--all".to_string());
+    }
+    git.run(cwd, commit).await?;
+
+    let head = run_git(cwd, &["log", "-1", "--format=%H%x00%s"]).await?;
+    let (sha, subject) = head
+        .trim_end()
+        .split_once('\0')
+        .unwrap_or((head.trim_end(), ""));
+    let files = run_git(
+        cwd,
+        &[
+            "diff-tree",
+            "--no-commit-id",
+            "--name-only",
+            "-r",
+            "-z",
+            "--root",
+            "HEAD",
+        ],
+    )
+    .await?;
+    Ok(GitCommit {
+        sha: sha.to_string(),
+        subject: subject.to_string(),
+        branch: current_branch(cwd).await?,
+        files: files
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(str::to_string)
+            .collect(),
+    })
+}
+
+async fn git_branch(
+    cwd: &Path,
+    args: &GitBranchArgs,
+    git: &MutatingGit<'_>,
+) -> Result<GitBranches, FunctionCallError> {
+    let name = || {
+        let name = args.name.as_deref().ok_or_else(|| {
+            FunctionCallError::RespondToModel(
+                "name is required to create or switch branches".to_string(),
+            )
+        })?;
+        check_ref("branch name", name)?;
+        Ok::<_, FunctionCallError>(name.to_string())
+    };
+    match args.action {
+        BranchAction::List => {}
+        BranchAction::Create => {
+            let mut command = vec!["branch".to_string(), name()?];
+            if let Some(start_point) = &args.start_point {
+                check_ref("start_point", start_point)?;
+                command.push(start_point.clone());
+            }
+            git.run(cwd, command).await?;
+        }
+        BranchAction::Switch => {
+            git.run(cwd, vec!["switch".to_string(), name()?]).await?;
+        }
+    }
+
+    let output = run_git(
+        cwd,
+        &[
+            "branch",
+            "--format=%(HEAD)%00%(refname:short)%00%(upstream:short)%00%(objectname:short)",
+        ],
+    )
+    .await?;
+    let branches = parse_branches(&output);
+    Ok(GitBranches {
+        current: current_branch(cwd).await?,
+        branches,
+    })
+}
+
+async fn current_branch(cwd: &Path) -> Result<Option<String>, FunctionCallError> {
+    let branch = run_git(cwd, &["branch", "--show-current"]).await?;
+    let branch = branch.trim();
+    Ok((!branch.is_empty()).then(|| branch.to_string()))
+}
+
+/// Runs a read-only git command and returns its stdout.
+async fn run_git(cwd: &Path, args: &[&str]) -> Result<String, FunctionCallError> {
+    let output = tokio::time::timeout(
+        Duration::from_millis(GIT_TIMEOUT_MS),
+        Command::new("git")
+            .args(["-c", "core.quotepath=off"])
+            // An fsmonitor hook from the repo config would run unsandboxed.
+            .args(["-c", "core.fsmonitor=false"])
+            .args(args)
+            // Reads must not take the index lock out from under a concurrent
+            // commit.
+            .env("GIT_OPTIONAL_LOCKS", "0")
+            .current_dir(cwd)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| FunctionCallError::RespondToModel(format!("`git {}` timed out", args.join(" "))))?
+    .map_err(|err| FunctionCallError::RespondToModel(format!("failed to run git: {err}")))?;
+
+    if !output.status.success() {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Runs git commands that change the repository the way the shell tool runs
+/// commands: announced to the client, approved per the session's policy, and
+/// sandboxed.
+struct MutatingGit<'a> {
+    session: &'a Session,
+    turn: &'a TurnContext,
+    call_id: &'a str,
+    tool_name: &'a str,
+}
+
+impl MutatingGit<'_> {
+    async fn run(&self, cwd: &Path, args: Vec<String>) -> Result<(), FunctionCallError> {
+        let mut command = vec!["git".to_string()];
+        command.extend(args);
+
+        let emitter = ToolEmitter::shell(
+            command.clone(),
+            cwd.to_path_buf(),
+            ExecCommandSource::Agent,
+            false,
+        );
+        let event_ctx = ToolEventCtx::new(self.session, self.turn, self.call_id, None);
+        emitter.begin(event_ctx).await;
+
+        let req = ShellRequest {
+            command: command.clone(),
+            cwd: cwd.to_path_buf(),
+            timeout_ms: Some(GIT_TIMEOUT_MS),
+            env: create_env(&self.turn.shell_environment_policy),
+            with_escalated_permissions: None,
+            justification: None,
+            approval_requirement: create_approval_requirement_for_command(
+                &self.turn.exec_policy,
+                &command,
+                self.turn.approval_policy,
+                &self.turn.sandbox_policy,
+                SandboxPermissions::from(false),
+            ),
+            pty: None,
+            persistent: None,
+            sandbox_policy: None,
+        };
+        let mut orchestrator = ToolOrchestrator::new();
+        let mut runtime = ShellRuntime::new();
+        let tool_ctx = ToolCtx {
+            session: self.session,
+            turn: self.turn,
+            call_id: self.call_id.to_string(),
+            tool_name: self.tool_name.to_string(),
+        };
+        let out = orchestrator
+            .run(
+                &mut runtime,
+                &req,
+                &tool_ctx,
+                self.turn,
+                self.turn.approval_policy,
+            )
+            .await;
+        let event_ctx = ToolEventCtx::new(self.session, self.turn, self.call_id, None);
+        emitter.finish(event_ctx, out).await.map(|_| ())
+    }
+}
+
+/// Parses `git status --porcelain=v2 --branch -z`.
+fn parse_status(output: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+    let mut records = output.split('\0').filter(|record| !record.is_empty());
+    while let Some(record) = records.next() {
+        let Some((kind, rest)) = record.split_once(' ') else {
+            continue;
+        };
+        match kind {
+            "#" => {
+                if let Some(head) = rest.strip_prefix("branch.head ") {
+                    status.branch = (head != "(detached)").then(|| head.to_string());
+                } else if let Some(upstream) = rest.strip_prefix("branch.upstream ") {
+                    status.upstream = Some(upstream.to_string());
+                } else if let Some(counts) = rest.strip_prefix("branch.ab ") {
+                    for count in counts.split(' ') {
+                        if let Some(ahead) = count.strip_prefix('+') {
+                            status.ahead = ahead.parse().unwrap_or(0);
+                        } else if let Some(behind) = count.strip_prefix('-') {
+                            status.behind = behind.parse().unwrap_or(0);
+                        }
+                    }
+                }
+            }
+            "1" | "2" => {
+                let fields: Vec<&str> = rest.splitn(if kind == "1" { 8 } else { 9 }, ' ').collect();
+                let (Some(xy), Some(path)) = (fields.first(), fields.last()) else {
+                    continue;
+                };
+                let mut xy = xy.chars();
+                status.files.push(StatusEntry {
+                    path: path.to_string(),
+                    original_path: if kind == "2" {
+                        records.next().map(str::to_string)
+                    } else {
+                        None
+                    },
+                    staged: xy.next().and_then(file_state),
+                    unstaged: xy.next().and_then(file_state),
+                });
+            }
+            "u" => {
+                if let Some(path) = rest.splitn(10, ' ').nth(9) {
+                    status.files.push(StatusEntry {
+                        path: path.to_string(),
+                        original_path: None,
+                        staged: Some(FileState::Conflicted),
+                        unstaged: Some(FileState::Conflicted),
+                    });
+                }
+            }
+            "?" => status.files.push(StatusEntry {
+                path: rest.to_string(),
+                original_path: None,
+                staged: None,
+                unstaged: Some(FileState::Untracked),
+            }),
+            _ => {}
+        }
+    }
+    status
+}
+
+fn file_state(code: char) -> Option<FileState> {
+    match code {
+        'A' => Some(FileState::Added),
+        'M' => Some(FileState::Modified),
+        'D' => Some(FileState::Deleted),
+        'R' => Some(FileState::Renamed),
+        'C' => Some(FileState::Copied),
+        'T' => Some(FileState::TypeChanged),
+        'U' => Some(FileState::Conflicted),
+        _ => None,
+    }
+}
+
+/// Parses `git diff --numstat -z`, where a rename leaves the path empty and
+/// follows the counts with the old and new paths as separate records.
+fn parse_numstat(output: &str) -> Vec<DiffFile> {
+    let mut files = Vec::new();
+    let mut records = output.split('\0');
+    while let Some(record) = records.next() {
+        let mut parts = record.splitn(3, '\t');
+        let (Some(additions), Some(deletions), Some(path)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        let (path, original_path) = if path.is_empty() {
+            let original_path = records.next().unwrap_or_default();
+            let path = records.next().unwrap_or_default();
+            (path, Some(original_path.to_string()))
+        } else {
+            (path, None)
+        };
+        files.push(DiffFile {
+            path: path.to_string(),
+            original_path,
+            additions: additions.parse().unwrap_or(0),
+            deletions: deletions.parse().unwrap_or(0),
+            binary: additions == "-",
+        });
+    }
+    files
+}
+
+fn parse_branches(output: &str) -> Vec<Branch> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\0');
+            let head = fields.next()?;
+            let name = fields.next()?;
+            let upstream = fields.next().unwrap_or_default();
+            let sha = fields.next().unwrap_or_default();
+            Some(Branch {
+                name: name.to_string(),
+                current: head == "*",
+                upstream: (!upstream.is_empty()).then(|| upstream.to_string()),
+                sha: sha.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn reads_skip_repo_configured_helpers() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let repo = tempfile::tempdir().unwrap();
+        let cwd = repo.path();
+        let marker = cwd.join("helper-ran");
+        let helper = cwd.join("helper.sh");
+        std::fs::write(
+            &helper,
+            format!("#!/bin/sh\ntouch '{}'\n", marker.display()),
+        )
+        .unwrap();
+        std::fs::set_permissions(&helper, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+                .args(args)
+                .current_dir(cwd)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {args:?} failed");
+        };
+        git(&["init", "-q"]);
+        std::fs::write(cwd.join("a.txt"), "one\n").unwrap();
+        git(&["add", "a.txt"]);
+        git(&["commit", "-q", "-m", "init"]);
+        let helper = helper.display().to_string();
+        git(&["config", "diff.external", &helper]);
+        git(&["config", "core.fsmonitor", &helper]);
+        std::fs::write(cwd.join(".gitattributes"), "*.txt diff=conv\n").unwrap();
+        git(&["config", "diff.conv.textconv", &helper]);
+        std::fs::write(cwd.join("a.txt"), "two\n").unwrap();
+
+        git_status(cwd).await.unwrap();
+        let diff = git_diff(
+            cwd,
+            &GitDiffArgs {
+                workdir: None,
+                staged: false,
+                base: None,
+                paths: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(diff.diff.contains("+two"), "{}", diff.diff);
+        assert!(!marker.exists(), "a repo-configured helper ran");
+    }
+
+    #[test]
+    fn parse_status_reads_branch_headers_and_entries() {
+        let output = [
+            "# branch.oid 1234567890abcdef",
+            "# branch.head main",
+            "# branch.upstream origin/main",
+            "# branch.ab +2 -1",
+            "1 M. N... 100644 100644 100644 aaaa bbbb src/lib.rs",
+            "1 .D N... 100644 100644 000000 aaaa aaaa old notes.txt",
+            "2 R. N... 100644 100644 100644 aaaa aaaa R100 src/new.rs",
+            "src/old.rs",
+            "u UU N... 100644 100644 100644 100644 aaaa bbbb cccc conflict.rs",
+            "? scratch file.txt",
+            "",
+        ]
+        .join("\0");
+
+        assert_eq!(
+            parse_status(&output),
+            GitStatus {
+                branch: Some("main".to_string()),
+                upstream: Some("origin/main".to_string()),
+                ahead: 2,
+                behind: 1,
+                files: vec![
+                    StatusEntry {
+                        path: "src/lib.rs".to_string(),
+                        original_path: None,
+                        staged: Some(FileState::Modified),
+                        unstaged: None,
+                    },
+                    StatusEntry {
+                        path: "old notes.txt".to_string(),
+                        original_path: None,
+                        staged: None,
+                        unstaged: Some(FileState::Deleted),
+                    },
+                    StatusEntry {
+                        path: "src/new.rs".to_string(),
+                        original_path: Some("src/old.rs".to_string()),
+                        staged: Some(FileState::Renamed),
+                        unstaged: None,
+                    },
+                    StatusEntry {
+                        path: "conflict.rs".to_string(),
+                        original_path: None,
+                        staged: Some(FileState::Conflicted),
+                        unstaged: Some(FileState::Conflicted),
+                    },
+                    StatusEntry {
+                        path: "scratch file.txt".to_string(),
+                        original_path: None,
+                        staged: None,
+                        unstaged: Some(FileState::Untracked),
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn parse_numstat_handles_renames_and_binaries() {
+        let output = "3\t1\tsrc/lib.rs\0-\t-\tlogo.png\x000\t0\t\0a.rs\0b.rs\0";
+        assert_eq!(
+            parse_numstat(output),
+            vec![
+                DiffFile {
+                    path: "src/lib.rs".to_string(),
+                    original_path: None,
+                    additions: 3,
+                    deletions: 1,
+                    binary: false,
+                },
+                DiffFile {
+                    path: "logo.png".to_string(),
+                    original_path: None,
+                    additions: 0,
+                    deletions: 0,
+                    binary: true,
+                },
+                DiffFile {
+                    path: "b.rs".to_string(),
+                    original_path: Some("a.rs".to_string()),
+                    additions: 0,
+                    deletions: 0,
+                    binary: false,
+                },
+            ]
+        );
+    }
+}
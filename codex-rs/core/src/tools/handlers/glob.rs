@@ -0,0 +1,235 @@
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use ignore::Walk;
+use ignore::WalkBuilder;
+use ignore::overrides::Override;
+use ignore::overrides::OverrideBuilder;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct GlobHandler;
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 2000;
+/// Walks stop after this long and report what they found so far.
+pub(super) const SEARCH_TIME_BUDGET: Duration = Duration::from_secs(30);
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+#[derive(Deserialize)]
+struct GlobArgs {
+    pattern: String,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+#[async_trait]
+impl ToolHandler for GlobHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { payload, turn, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "glob handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: GlobArgs = serde_json::from_str(&arguments).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to parse function arguments: {err:?}"
+            ))
+        })?;
+
+        let pattern = args.pattern.trim().to_string();
+        if pattern.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "pattern must not be empty".to_string(),
+            ));
+        }
+        if args.limit == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "limit must be greater than zero".to_string(),
+            ));
+        }
+
+        let limit = args.limit.min(MAX_LIMIT);
+        let root = turn.resolve_path(args.path);
+        tokio::fs::metadata(&root).await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "unable to access `{}`: {err}",
+                root.display()
+            ))
+        })?;
+
+        let found = tokio::task::spawn_blocking(move || find_files(&root, &pattern, limit))
+            .await
+            .map_err(|err| FunctionCallError::RespondToModel(format!("glob failed: {err}")))?
+            .map_err(FunctionCallError::RespondToModel)?;
+
+        if found.paths.is_empty() {
+            return Ok(ToolOutput::Function {
+                content: if found.timed_out {
+                    "No files matched before the search time limit.".to_string()
+                } else {
+                    "No files matched.".to_string()
+                },
+                content_items: None,
+                success: Some(false),
+            });
+        }
+
+        let mut content = found
+            .paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if found.total > found.paths.len() {
+            content.push_str(&format!(
+                "\n[showing {} of {} matching files; narrow the pattern or raise limit]",
+                found.paths.len(),
+                found.total
+            ));
+        }
+        if found.timed_out {
+            content.push_str("\n[search stopped at the time limit; results may be incomplete]");
+        }
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+struct FoundFiles {
+    /// Most recently modified first.
+    paths: Vec<PathBuf>,
+    total: usize,
+    timed_out: bool,
+}
+
+/// Finds the files under `root` matching `pattern`, skipping anything
+/// `.gitignore` excludes.
+fn find_files(root: &Path, pattern: &str, limit: usize) -> Result<FoundFiles, String> {
+    let matcher = glob_matcher(root, pattern)?;
+    let started = Instant::now();
+    let mut timed_out = false;
+    let mut matches = Vec::new();
+    for entry in walk_files(root).flatten() {
+        if started.elapsed() > SEARCH_TIME_BUDGET {
+            timed_out = true;
+            break;
+        }
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+            || !matcher.matched(entry.path(), false).is_whitelist()
+        {
+            continue;
+        }
+        let modified = entry
+            .metadata()
+            .ok()
+            .and_then(|metadata| metadata.modified().ok())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        matches.push((modified, entry.into_path()));
+    }
+
+    matches.sort_by(|(a_time, a_path), (b_time, b_path)| {
+        b_time.cmp(a_time).then_with(|| a_path.cmp(b_path))
+    });
+    let total = matches.len();
+    Ok(FoundFiles {
+        paths: matches
+            .into_iter()
+            .take(limit)
+            .map(|(_, path)| path)
+            .collect(),
+        total,
+        timed_out,
+    })
+}
+
+/// Compiles a gitignore-style glob relative to `root`: `*.rs` matches at any
+/// depth, `src/**/*.rs` only under `src`.
+pub(super) fn glob_matcher(root: &Path, pattern: &str) -> Result<Override, String> {
+    let mut builder = OverrideBuilder::new(root);
+    builder
+        .add(pattern)
+        .map_err(|err| format!("invalid glob pattern `{pattern}`: {err}"))?;
+    builder
+        .build()
+        .map_err(|err| format!("invalid glob pattern `{pattern}`: {err}"))
+}
+
+/// Walks `root` in path order, honoring `.gitignore` files (even outside a
+/// git repository) and skipping `.git` itself.
+pub(super) fn walk_files(root: &Path) -> Walk {
+    WalkBuilder::new(root)
+        .hidden(false)
+        .require_git(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .sort_by_file_name(Ord::cmp)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn find_files_matches_at_any_depth_and_skips_ignored_files() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        fs::create_dir_all(root.join("src/nested")).unwrap();
+        fs::create_dir_all(root.join("target")).unwrap();
+        fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        fs::write(root.join("src/lib.rs"), "").unwrap();
+        fs::write(root.join("src/nested/mod.rs"), "").unwrap();
+        fs::write(root.join("src/notes.txt"), "").unwrap();
+        fs::write(root.join("target/build.rs"), "").unwrap();
+
+        let mut found = find_files(root, "*.rs", 10).unwrap();
+        found.paths.sort();
+        assert_eq!(
+            found.paths,
+            vec![root.join("src/lib.rs"), root.join("src/nested/mod.rs")]
+        );
+        assert_eq!(found.total, 2);
+
+        let found = find_files(root, "src/*.rs", 10).unwrap();
+        assert_eq!(found.paths, vec![root.join("src/lib.rs")]);
+
+        let found = find_files(root, "**/*.rs", 1).unwrap();
+        assert_eq!(found.paths.len(), 1);
+        assert_eq!(found.total, 2);
+
+        assert!(find_files(root, "src/[", 10).is_err());
+    }
+}
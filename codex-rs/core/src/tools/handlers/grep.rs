@@ -0,0 +1,325 @@
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use codex_utils_string::take_bytes_at_char_boundary;
+use regex_lite::Regex;
+use regex_lite::RegexBuilder;
+use serde::Deserialize;
+
+use super::glob::SEARCH_TIME_BUDGET;
+use super::glob::glob_matcher;
+use super::glob::walk_files;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct GrepHandler;
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 2000;
+const MAX_CONTEXT_LINES: usize = 10;
+const MAX_LINE_LENGTH: usize = 500;
+/// Larger files are skipped rather than searched.
+const MAX_FILE_BYTES: u64 = 4 * 1024 * 1024;
+/// A NUL byte in this much of a file marks it as binary.
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+const MAX_PATTERN_COMPILED_BYTES: usize = 1024 * 1024;
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+#[derive(Deserialize)]
+struct GrepArgs {
+    pattern: String,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    context_lines: usize,
+    #[serde(default)]
+    include: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+#[async_trait]
+impl ToolHandler for GrepHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { payload, turn, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "grep handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: GrepArgs = serde_json::from_str(&arguments).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to parse function arguments: {err:?}"
+            ))
+        })?;
+
+        if args.pattern.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "pattern must not be empty".to_string(),
+            ));
+        }
+        if args.limit == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "limit must be greater than zero".to_string(),
+            ));
+        }
+
+        let root = turn.resolve_path(args.path.clone());
+        tokio::fs::metadata(&root).await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "unable to access `{}`: {err}",
+                root.display()
+            ))
+        })?;
+        let regex = RegexBuilder::new(&args.pattern)
+            .size_limit(MAX_PATTERN_COMPILED_BYTES)
+            .build()
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!("invalid regex pattern: {err}"))
+            })?;
+        let options = GrepOptions {
+            context_lines: args.context_lines.min(MAX_CONTEXT_LINES),
+            include: args
+                .include
+                .map(|include| include.trim().to_string())
+                .filter(|include| !include.is_empty()),
+            limit: args.limit.min(MAX_LIMIT),
+        };
+
+        let found = tokio::task::spawn_blocking(move || search(&root, &regex, &options))
+            .await
+            .map_err(|err| FunctionCallError::RespondToModel(format!("grep failed: {err}")))?
+            .map_err(FunctionCallError::RespondToModel)?;
+
+        if found.matches == 0 {
+            return Ok(ToolOutput::Function {
+                content: if found.timed_out {
+                    "No matches found before the search time limit.".to_string()
+                } else {
+                    "No matches found.".to_string()
+                },
+                content_items: None,
+                success: Some(false),
+            });
+        }
+
+        let mut content = found.output;
+        if found.truncated {
+            let _ = write!(
+                content,
+                "\n[stopped after {} matching lines; narrow the pattern or path, or raise limit]",
+                found.matches
+            );
+        }
+        if found.timed_out {
+            content.push_str("\n[search stopped at the time limit; results may be incomplete]");
+        }
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+struct GrepOptions {
+    context_lines: usize,
+    include: Option<String>,
+    limit: usize,
+}
+
+struct GrepResult {
+    /// `path:line:text` for matches and `path-line-text` for context, with
+    /// `--` between non-adjacent groups.
+    output: String,
+    matches: usize,
+    truncated: bool,
+    timed_out: bool,
+}
+
+/// Searches the text files under `root`, skipping anything `.gitignore`
+/// excludes, binary files, and files over [`MAX_FILE_BYTES`].
+fn search(root: &Path, regex: &Regex, options: &GrepOptions) -> Result<GrepResult, String> {
+    let include = options
+        .include
+        .as_deref()
+        .map(|include| glob_matcher(root, include))
+        .transpose()?;
+    let started = Instant::now();
+    let mut result = GrepResult {
+        output: String::new(),
+        matches: 0,
+        truncated: false,
+        timed_out: false,
+    };
+
+    for entry in walk_files(root).flatten() {
+        if started.elapsed() > SEARCH_TIME_BUDGET {
+            result.timed_out = true;
+            break;
+        }
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+            || include
+                .as_ref()
+                .is_some_and(|include| !include.matched(entry.path(), false).is_whitelist())
+            || entry
+                .metadata()
+                .is_ok_and(|metadata| metadata.len() > MAX_FILE_BYTES)
+        {
+            continue;
+        }
+        let Ok(bytes) = std::fs::read(entry.path()) else {
+            continue;
+        };
+        if bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+            continue;
+        }
+        let text = String::from_utf8_lossy(&bytes);
+        search_file(entry.path(), &text, regex, options, &mut result);
+        if result.truncated {
+            break;
+        }
+    }
+    Ok(result)
+}
+
+fn search_file(
+    path: &Path,
+    text: &str,
+    regex: &Regex,
+    options: &GrepOptions,
+    result: &mut GrepResult,
+) {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut hits = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if !regex.is_match(line) {
+            continue;
+        }
+        if result.matches == options.limit {
+            result.truncated = true;
+            break;
+        }
+        hits.push(index);
+        result.matches += 1;
+    }
+
+    // Merge each hit's context window with the next when they touch.
+    let context = options.context_lines;
+    let mut groups: Vec<(usize, usize)> = Vec::new();
+    for &hit in &hits {
+        let start = hit.saturating_sub(context);
+        let end = (hit + context).min(lines.len() - 1);
+        match groups.last_mut() {
+            Some((_, last_end)) if start <= *last_end + 1 => *last_end = end,
+            _ => groups.push((start, end)),
+        }
+    }
+
+    let display = path.display();
+    let mut hits = hits.iter().peekable();
+    for (start, end) in groups {
+        if context > 0 && !result.output.is_empty() {
+            result.output.push_str("--\n");
+        }
+        for (index, line) in lines.iter().enumerate().take(end + 1).skip(start) {
+            let separator = if hits.next_if_eq(&&index).is_some() {
+                ':'
+            } else {
+                '-'
+            };
+            let line = take_bytes_at_char_boundary(line, MAX_LINE_LENGTH);
+            let _ = writeln!(
+                result.output,
+                "{display}{separator}{}{separator}{line}",
+                index + 1
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn options(context_lines: usize, include: Option<&str>, limit: usize) -> GrepOptions {
+        GrepOptions {
+            context_lines,
+            include: include.map(str::to_string),
+            limit,
+        }
+    }
+
+    #[test]
+    fn search_reports_matches_with_merged_context() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let file = root.join("lib.rs");
+        fs::write(
+            &file,
+            "a\nfn one() {}\nb\nfn two() {}\nc\nd\ne\nfn three() {}\n",
+        )
+        .unwrap();
+        fs::write(root.join("notes.txt"), "fn not_code() {}\n").unwrap();
+        let regex = Regex::new(r"^fn \w+").unwrap();
+
+        let found = search(root, &regex, &options(1, Some("*.rs"), 10)).unwrap();
+        let path = file.display();
+        assert_eq!(
+            found.output,
+            format!(
+                "{path}-1-a\n{path}:2:fn one() {{}}\n{path}-3-b\n{path}:4:fn two() {{}}\n{path}-5-c\n--\n{path}-7-e\n{path}:8:fn three() {{}}\n"
+            )
+        );
+        assert_eq!(found.matches, 3);
+        assert!(!found.truncated);
+
+        let found = search(root, &regex, &options(0, None, 2)).unwrap();
+        assert_eq!(
+            found.output,
+            format!("{path}:2:fn one() {{}}\n{path}:4:fn two() {{}}\n")
+        );
+        assert!(found.truncated);
+    }
+
+    #[test]
+    fn search_skips_ignored_and_binary_files() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        fs::create_dir_all(root.join("build")).unwrap();
+        fs::write(root.join(".gitignore"), "build/\n").unwrap();
+        fs::write(root.join("build/out.txt"), "needle\n").unwrap();
+        fs::write(root.join("blob.bin"), b"needle\0\n").unwrap();
+        fs::write(root.join("src.txt"), "needle\n").unwrap();
+        let regex = Regex::new("needle").unwrap();
+
+        let found = search(root, &regex, &options(0, None, 10)).unwrap();
+        assert_eq!(
+            found.output,
+            format!("{}:1:needle\n", root.join("src.txt").display())
+        );
+    }
+}
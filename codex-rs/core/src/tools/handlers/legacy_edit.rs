@@ -4,9 +4,24 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchFileChange;
 use codex_apply_patch::MaybeApplyPatchVerified;
+use regex_lite::Regex;
+use regex_lite::RegexBuilder;
+use serde::Deserialize;
 use similar::TextDiff;
 use thiserror::Error;
+use wildmatch::WildMatchPattern;
+
+use crate::config::Config;
+use crate::config::DEFAULT_PROTECTED_PATHS;
+use crate::config::WRITE_FILE_MAX_BYTES;
+
+/// Longest `replace_regex` pattern accepted, in bytes.
+const MAX_REGEX_PATTERN_BYTES: usize = 1024;
+/// Cap on the compiled size of a `replace_regex` pattern.
+const MAX_REGEX_COMPILED_BYTES: usize = 1024 * 1024;
+const MAX_REGEX_NESTING: u32 = 50;
 
 #[derive(Debug, Error)]
 pub(crate) enum LegacyEditError {
@@ -20,11 +35,149 @@ impl LegacyEditError {
     }
 }
 
+/// Limits every edit is checked against before a patch is built.
+#[derive(Debug, Clone)]
+pub(crate) struct EditPolicy {
+    pub(crate) write_file_max_bytes: usize,
+    protected_paths: Vec<(String, WildMatchPattern<'*', '?'>)>,
+    protect_outside_workspace: bool,
+}
+
+impl EditPolicy {
+    pub(crate) fn from_config(config: &Config) -> Self {
+        Self::new(
+            config.write_file_max_bytes,
+            &config.protected_paths,
+            config.protect_paths_outside_workspace,
+        )
+    }
+
+    fn new(
+        write_file_max_bytes: usize,
+        protected_paths: &[impl AsRef<str>],
+        protect_outside_workspace: bool,
+    ) -> Self {
+        Self {
+            write_file_max_bytes,
+            protected_paths: protected_paths
+                .iter()
+                .map(|pattern| {
+                    let pattern = pattern.as_ref();
+                    let matcher = if CASE_INSENSITIVE_FS {
+                        WildMatchPattern::new_case_insensitive(pattern)
+                    } else {
+                        WildMatchPattern::new(pattern)
+                    };
+                    (pattern.to_string(), matcher)
+                })
+                .collect(),
+            protect_outside_workspace,
+        }
+    }
+
+    /// Refuses `path` when it matches a protected pattern or, if configured,
+    /// lies outside `cwd`. Checked even in full-access sandboxes.
+    fn check(&self, path: &Path, cwd: &Path) -> Result<(), LegacyEditError> {
+        let path = resolve_existing_prefix(&normalize_lexically(path));
+        let cwd = resolve_existing_prefix(&normalize_lexically(cwd));
+        let candidate = match path.strip_prefix(&cwd) {
+            Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+            Err(_) if self.protect_outside_workspace => {
+                return Err(LegacyEditError::new(format!(
+                    "edit refused: {} is outside the workspace {}. Only files under the working directory may be edited.",
+                    path.display(),
+                    cwd.display()
+                )));
+            }
+            Err(_) => path.to_string_lossy().replace('\\', "/"),
+        };
+        if let Some((pattern, _)) = self
+            .protected_paths
+            .iter()
+            .find(|(_, matcher)| matcher.matches(&candidate))
+        {
+            return Err(LegacyEditError::new(format!(
+                "edit refused: {} matches the protected path pattern `{pattern}`. Protected files cannot be changed with the edit tools; leave it as is or ask the user to change it.",
+                path.display()
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for EditPolicy {
+    fn default() -> Self {
+        Self::new(WRITE_FILE_MAX_BYTES, DEFAULT_PROTECTED_PATHS, false)
+    }
+}
+
+/// The default filesystems on macOS and Windows ignore case, so `.GIT/config`
+/// is the same file as `.git/config` there.
+const CASE_INSENSITIVE_FS: bool = cfg!(any(target_os = "macos", windows));
+
+/// Canonicalizes the deepest ancestor of `path` that exists and re-appends
+/// the rest, so a symlink such as `link -> .git` is checked as its target.
+fn resolve_existing_prefix(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut missing = Vec::new();
+    loop {
+        if let Ok(resolved) = existing.canonicalize() {
+            return missing
+                .iter()
+                .rev()
+                .fold(resolved, |resolved, name| resolved.join(name));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+/// Resolves `.` and `..` components without touching the filesystem, so
+/// `src/../.git/config` is checked as `.git/config`.
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// How `write_file` treats a target whose file or parent directory does not
+/// exist yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct WriteFileOptions {
+    /// Create missing parent directories instead of refusing the write.
+    pub(crate) create_parents: bool,
+    /// Only overwrite an existing file; never create one.
+    pub(crate) must_exist: bool,
+}
+
+impl Default for WriteFileOptions {
+    fn default() -> Self {
+        Self {
+            create_parents: true,
+            must_exist: false,
+        }
+    }
+}
+
 #[derive(Debug)]
 enum LegacyEditCommand {
     WriteFile {
         path: String,
         content: String,
+        options: WriteFileOptions,
     },
     DeleteFile {
         path: String,
@@ -35,12 +188,35 @@ enum LegacyEditCommand {
         new: String,
         expected_replacements: Option<usize>,
     },
+    ReplaceRegex {
+        path: String,
+        pattern: String,
+        replacement: String,
+        expected_matches: Option<usize>,
+    },
+    InsertAtLine {
+        path: String,
+        line_number: usize,
+        content: String,
+    },
+    Append {
+        path: String,
+        content: String,
+    },
+    Prepend {
+        path: String,
+        content: String,
+    },
 }
 
 pub(crate) fn maybe_build_apply_patch_action(
     command: &[String],
     cwd: &Path,
+    policy: &EditPolicy,
 ) -> Result<Option<ApplyPatchAction>, LegacyEditError> {
+    if let Some(edit_command) = parse_heredoc_write_file(command)? {
+        return build_action(edit_command, policy, cwd).map(Some);
+    }
     let Some(command_name) = command.first().map(std::string::String::as_str) else {
         return Ok(None);
     };
@@ -52,9 +228,13 @@ pub(crate) fn maybe_build_apply_patch_action(
                     "{command_name} expects exactly 2 arguments: path and content."
                 )));
             }
+            if command[2] == "-" {
+                return Err(heredoc_usage_error(command_name, &command[1]));
+            }
             LegacyEditCommand::WriteFile {
                 path: command[1].clone(),
                 content: command[2].clone(),
+                options: WriteFileOptions::default(),
             }
         }
         "delete" | "delete_file" => {
@@ -85,19 +265,187 @@ pub(crate) fn maybe_build_apply_patch_action(
                 expected_replacements,
             }
         }
+        "replace_regex" => {
+            if command.len() != 4 && command.len() != 5 {
+                return Err(LegacyEditError::new(
+                    "replace_regex expects arguments: path, pattern, replacement, [expected_matches]",
+                ));
+            }
+            let expected_matches = if command.len() == 5 {
+                Some(parse_expected_matches(&command[4])?)
+            } else {
+                None
+            };
+            LegacyEditCommand::ReplaceRegex {
+                path: command[1].clone(),
+                pattern: command[2].clone(),
+                replacement: command[3].clone(),
+                expected_matches,
+            }
+        }
+        "insert" | "insert_at_line" => {
+            if command.len() != 4 {
+                return Err(LegacyEditError::new(format!(
+                    "{command_name} expects exactly 3 arguments: path, line_number, and content."
+                )));
+            }
+            LegacyEditCommand::InsertAtLine {
+                path: command[1].clone(),
+                line_number: parse_line_number(&command[2])?,
+                content: command[3].clone(),
+            }
+        }
+        "append" | "append_to_file" => {
+            if command.len() != 3 {
+                return Err(LegacyEditError::new(format!(
+                    "{command_name} expects exactly 2 arguments: path and content."
+                )));
+            }
+            LegacyEditCommand::Append {
+                path: command[1].clone(),
+                content: command[2].clone(),
+            }
+        }
+        "prepend" | "prepend_to_file" => {
+            if command.len() != 3 {
+                return Err(LegacyEditError::new(format!(
+                    "{command_name} expects exactly 2 arguments: path and content."
+                )));
+            }
+            LegacyEditCommand::Prepend {
+                path: command[1].clone(),
+                content: command[2].clone(),
+            }
+        }
         _ => return Ok(None),
     };
 
-    let action = build_action(edit_command, cwd)?;
+    let action = build_action(edit_command, policy, cwd)?;
     Ok(Some(action))
 }
 
+/// Recognizes `write_file <path> - <<'EOF'` run through `bash -lc` (or `sh`
+/// or `zsh`), taking the file's content from the heredoc body so large
+/// multi-line files need not be quoted into a single argument.
+fn parse_heredoc_write_file(
+    command: &[String],
+) -> Result<Option<LegacyEditCommand>, LegacyEditError> {
+    let [shell, flag, script] = command else {
+        return Ok(None);
+    };
+    let shell_name = Path::new(shell)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(shell);
+    if !matches!(shell_name, "bash" | "sh" | "zsh") || !matches!(flag.as_str(), "-c" | "-lc") {
+        return Ok(None);
+    }
+
+    let script = script.trim_start();
+    let (first_line, rest) = script.split_once('\n').unwrap_or((script, ""));
+    let Some(tokens) = shlex::split(first_line) else {
+        return Ok(None);
+    };
+    let [command_name, path, dash, redirect @ ..] = tokens.as_slice() else {
+        return Ok(None);
+    };
+    if !matches!(command_name.as_str(), "create" | "write_file") || dash != "-" {
+        return Ok(None);
+    }
+    let redirect = redirect.concat();
+    let Some(delimiter) = redirect.strip_prefix("<<") else {
+        return Err(heredoc_usage_error(command_name, path));
+    };
+    let (strip_tabs, delimiter) = match delimiter.strip_prefix('-') {
+        Some(delimiter) => (true, delimiter),
+        None => (false, delimiter),
+    };
+    if delimiter.is_empty() {
+        return Err(heredoc_usage_error(command_name, path));
+    }
+    // With a quoted delimiter the shell leaves the body alone; otherwise it
+    // would expand `$`, backticks, and backslashes before writing.
+    let quoted = first_line
+        .split_once("<<")
+        .map(|(_, after)| after.trim_start_matches('-').trim_start())
+        .is_some_and(|after| after.starts_with(['\'', '"', '\\']));
+
+    let mut content = String::new();
+    let mut lines = rest.split('\n');
+    let mut terminated = false;
+    for line in lines.by_ref() {
+        let line = if strip_tabs {
+            line.trim_start_matches('\t')
+        } else {
+            line
+        };
+        if line == delimiter {
+            terminated = true;
+            break;
+        }
+        content.push_str(line);
+        content.push('\n');
+    }
+    if !terminated {
+        return Err(LegacyEditError::new(format!(
+            "{command_name} failed: the heredoc has no closing {delimiter} line."
+        )));
+    }
+    if lines.any(|line| !line.trim().is_empty()) {
+        return Err(LegacyEditError::new(format!(
+            "{command_name} with a heredoc must be the only command in the script."
+        )));
+    }
+    if !quoted && content.contains(['$', '`', '\\']) {
+        return Err(LegacyEditError::new(format!(
+            "{command_name} failed: quote the heredoc delimiter (<<'{delimiter}') so the content is written verbatim."
+        )));
+    }
+
+    Ok(Some(LegacyEditCommand::WriteFile {
+        path: path.clone(),
+        content,
+        options: WriteFileOptions::default(),
+    }))
+}
+
+fn heredoc_usage_error(command_name: &str, path: &str) -> LegacyEditError {
+    LegacyEditError::new(format!(
+        "{command_name} with `-` as the content reads it from a heredoc: run `{command_name} {path} - <<'EOF'` through the shell, followed by the content and a closing EOF line."
+    ))
+}
+
+impl LegacyEditCommand {
+    fn path(&self) -> &str {
+        match self {
+            Self::WriteFile { path, .. }
+            | Self::DeleteFile { path }
+            | Self::Replace { path, .. }
+            | Self::ReplaceRegex { path, .. }
+            | Self::InsertAtLine { path, .. }
+            | Self::Append { path, .. }
+            | Self::Prepend { path, .. } => path,
+        }
+    }
+}
+
 fn build_action(
     edit_command: LegacyEditCommand,
+    policy: &EditPolicy,
     cwd: &Path,
 ) -> Result<ApplyPatchAction, LegacyEditError> {
+    if edit_command.path().trim().is_empty() {
+        return Err(LegacyEditError::new(
+            "edit refused: the file path is empty.",
+        ));
+    }
+    policy.check(&resolve_path(edit_command.path(), cwd), cwd)?;
     match edit_command {
-        LegacyEditCommand::WriteFile { path, content } => prepare_write_file(&path, content, cwd),
+        LegacyEditCommand::WriteFile {
+            path,
+            content,
+            options,
+        } => prepare_write_file(&path, content, policy.write_file_max_bytes, options, cwd),
         LegacyEditCommand::DeleteFile { path } => prepare_delete_file(&path, cwd),
         LegacyEditCommand::Replace {
             path,
@@ -105,31 +453,50 @@ fn build_action(
             new,
             expected_replacements,
         } => prepare_replace(&path, &old, &new, expected_replacements, cwd),
+        LegacyEditCommand::ReplaceRegex {
+            path,
+            pattern,
+            replacement,
+            expected_matches,
+        } => prepare_replace_regex(&path, &pattern, &replacement, expected_matches, cwd),
+        LegacyEditCommand::InsertAtLine {
+            path,
+            line_number,
+            content,
+        } => prepare_insert(&path, line_number, &content, cwd),
+        LegacyEditCommand::Append { path, content } => prepare_append(&path, &content, cwd),
+        LegacyEditCommand::Prepend { path, content } => prepare_prepend(&path, &content, cwd),
     }
 }
 
 pub(crate) fn build_write_file_action(
     path: &str,
     content: &str,
+    options: WriteFileOptions,
+    policy: &EditPolicy,
     cwd: &Path,
 ) -> Result<ApplyPatchAction, LegacyEditError> {
     build_action(
         LegacyEditCommand::WriteFile {
             path: path.to_string(),
             content: content.to_string(),
+            options,
         },
+        policy,
         cwd,
     )
 }
 
 pub(crate) fn build_delete_file_action(
     path: &str,
+    policy: &EditPolicy,
     cwd: &Path,
 ) -> Result<ApplyPatchAction, LegacyEditError> {
     build_action(
         LegacyEditCommand::DeleteFile {
             path: path.to_string(),
         },
+        policy,
         cwd,
     )
 }
@@ -139,6 +506,7 @@ pub(crate) fn build_replace_action(
     old: &str,
     new: &str,
     expected_replacements: Option<usize>,
+    policy: &EditPolicy,
     cwd: &Path,
 ) -> Result<ApplyPatchAction, LegacyEditError> {
     build_action(
@@ -148,19 +516,143 @@ pub(crate) fn build_replace_action(
             new: new.to_string(),
             expected_replacements,
         },
+        policy,
+        cwd,
+    )
+}
+
+pub(crate) fn build_replace_regex_action(
+    path: &str,
+    pattern: &str,
+    replacement: &str,
+    expected_matches: Option<usize>,
+    policy: &EditPolicy,
+    cwd: &Path,
+) -> Result<ApplyPatchAction, LegacyEditError> {
+    build_action(
+        LegacyEditCommand::ReplaceRegex {
+            path: path.to_string(),
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            expected_matches,
+        },
+        policy,
+        cwd,
+    )
+}
+
+pub(crate) fn build_insert_action(
+    path: &str,
+    line_number: usize,
+    content: &str,
+    policy: &EditPolicy,
+    cwd: &Path,
+) -> Result<ApplyPatchAction, LegacyEditError> {
+    build_action(
+        LegacyEditCommand::InsertAtLine {
+            path: path.to_string(),
+            line_number,
+            content: content.to_string(),
+        },
+        policy,
+        cwd,
+    )
+}
+
+pub(crate) fn build_append_action(
+    path: &str,
+    content: &str,
+    policy: &EditPolicy,
+    cwd: &Path,
+) -> Result<ApplyPatchAction, LegacyEditError> {
+    build_action(
+        LegacyEditCommand::Append {
+            path: path.to_string(),
+            content: content.to_string(),
+        },
+        policy,
         cwd,
     )
 }
 
+/// One `old_string` -> `new_string` replacement of a `multi_edit` call.
+#[derive(Debug, Clone)]
+pub(crate) struct ReplaceEdit {
+    pub(crate) old: String,
+    pub(crate) new: String,
+    pub(crate) expected_replacements: Option<usize>,
+}
+
+/// Applies `edits` in order, each to the result of the previous one, and
+/// builds a single patch from the outcome, so either every edit lands or
+/// none does.
+pub(crate) fn build_multi_edit_action(
+    path: &str,
+    edits: &[ReplaceEdit],
+    policy: &EditPolicy,
+    cwd: &Path,
+) -> Result<ApplyPatchAction, LegacyEditError> {
+    let absolute_path = resolve_path(path, cwd);
+    policy.check(&absolute_path, cwd)?;
+    if edits.is_empty() {
+        return Err(LegacyEditError::new(
+            "multi_edit failed: edits must contain at least one edit.",
+        ));
+    }
+    let current_content = read_to_lf_string(&absolute_path).map_err(|err| {
+        LegacyEditError::new(format!(
+            "multi_edit failed: unable to read {} ({err}).",
+            absolute_path.display()
+        ))
+    })?;
+
+    let mut new_content = current_content.clone();
+    for (index, edit) in edits.iter().enumerate() {
+        new_content = replace_in_content(
+            &format!("multi_edit edit {}", index + 1),
+            &new_content,
+            &edit.old,
+            &edit.new,
+            edit.expected_replacements,
+            &absolute_path,
+        )?;
+    }
+    if new_content == current_content {
+        return Err(LegacyEditError::new(
+            "multi_edit skipped: no changes were produced.",
+        ));
+    }
+
+    let patch = build_update_patch(&absolute_path, cwd, &current_content, &new_content)?;
+    parse_patch(patch, cwd)
+}
+
+/// Writes `content` to `path`. Existing files are only read when they are
+/// within `max_bytes` and valid UTF-8 text, so binary or huge files are
+/// refused instead of being mangled or loaded whole.
 fn prepare_write_file(
     path: &str,
     content: String,
+    max_bytes: usize,
+    options: WriteFileOptions,
     cwd: &Path,
 ) -> Result<ApplyPatchAction, LegacyEditError> {
     let absolute_path = resolve_path(path, cwd);
-    let (current_content, existed) = match fs::read_to_string(&absolute_path) {
+    if content.len() > max_bytes {
+        return Err(LegacyEditError::new(format!(
+            "write_file refused: content is {} bytes, over the {max_bytes}-byte limit. Use shell tools to generate large files.",
+            content.len()
+        )));
+    }
+    let (current_content, existed) = match read_text_file(&absolute_path, max_bytes) {
         Ok(content) => (content, true),
         Err(err) if err.kind() == std::io::ErrorKind::NotFound => (String::new(), false),
+        Err(err) if err.kind() == std::io::ErrorKind::InvalidData => {
+            return Err(LegacyEditError::new(format!(
+                "write_file refused: {} {err}. Use shell tools to modify it instead.",
+                absolute_path.display()
+            )));
+        }
         Err(err) => {
             return Err(LegacyEditError::new(format!(
                 "write_file failed: unable to read {} ({err}).",
@@ -169,6 +661,24 @@ fn prepare_write_file(
         }
     };
 
+    if !existed {
+        if options.must_exist {
+            return Err(LegacyEditError::new(format!(
+                "write_file refused: {} does not exist and must_exist is set. Check the path for typos, or leave must_exist unset to create the file.",
+                absolute_path.display()
+            )));
+        }
+        if !options.create_parents
+            && let Some(parent) = absolute_path.parent()
+            && !parent.is_dir()
+        {
+            return Err(LegacyEditError::new(format!(
+                "write_file refused: directory {} does not exist and create_parents is false. Check the path for typos, or set create_parents to create it.",
+                parent.display()
+            )));
+        }
+    }
+
     if current_content == content {
         return Err(LegacyEditError::new(format!(
             "write_file skipped: new content for {} is identical to the existing content.",
@@ -185,6 +695,41 @@ fn prepare_write_file(
     parse_patch(patch, cwd)
 }
 
+/// Reads `path` as text, failing with `InvalidData` when it is larger than
+/// `max_bytes`, contains NUL bytes, or is not UTF-8.
+fn read_text_file(path: &Path, max_bytes: usize) -> std::io::Result<String> {
+    let len = fs::metadata(path)?.len();
+    if len > max_bytes as u64 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("is {len} bytes, over the {max_bytes}-byte limit"),
+        ));
+    }
+    let bytes = fs::read(path)?;
+    if bytes.contains(&0) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "looks like a binary file",
+        ));
+    }
+    String::from_utf8(bytes)
+        .map(|content| to_lf(&content))
+        .map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "is not valid UTF-8 text")
+        })
+}
+
+/// Reads `path` with CRLF line breaks turned into LF. Edits are computed on
+/// LF text; apply_patch writes updated files back with their original
+/// convention.
+fn read_to_lf_string(path: &Path) -> std::io::Result<String> {
+    fs::read_to_string(path).map(|content| to_lf(&content))
+}
+
+fn to_lf(content: &str) -> String {
+    content.replace("\r\n", "\n")
+}
+
 fn prepare_delete_file(path: &str, cwd: &Path) -> Result<ApplyPatchAction, LegacyEditError> {
     let absolute_path = resolve_path(path, cwd);
     if !absolute_path.exists() {
@@ -206,52 +751,475 @@ fn prepare_replace(
     cwd: &Path,
 ) -> Result<ApplyPatchAction, LegacyEditError> {
     let absolute_path = resolve_path(path, cwd);
-    let current_content = fs::read_to_string(&absolute_path).map_err(|err| {
+    let current_content = read_to_lf_string(&absolute_path).map_err(|err| {
         LegacyEditError::new(format!(
             "replace failed: unable to read {} ({err}).",
             absolute_path.display()
         ))
     })?;
 
-    if old.is_empty() {
-        return Err(LegacyEditError::new(
-            "replace failed: old_string must not be empty. Use write_file to create a new file.",
-        ));
+    let new_content = replace_in_content(
+        "replace",
+        &current_content,
+        old,
+        new,
+        expected_replacements,
+        &absolute_path,
+    )?;
+    let patch = build_update_patch(&absolute_path, cwd, &current_content, &new_content)?;
+    parse_patch(patch, cwd)
+}
+
+/// Where a `replace` landed after falling back to whitespace-insensitive
+/// matching.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FuzzyMatch {
+    /// 1-based, inclusive line range of the first matched region.
+    pub(crate) start_line: usize,
+    pub(crate) end_line: usize,
+    pub(crate) occurrences: usize,
+    /// The file's text for the first matched region, as it was before the edit.
+    pub(crate) matched: String,
+}
+
+impl FuzzyMatch {
+    pub(crate) fn describe(&self) -> String {
+        let location = if self.start_line == self.end_line {
+            format!("line {}", self.start_line)
+        } else {
+            format!("lines {}-{}", self.start_line, self.end_line)
+        };
+        let others = match self.occurrences {
+            1 => String::new(),
+            n => format!(" and {} other place(s)", n - 1),
+        };
+        format!(
+            "old_string had no exact match; it matched {location}{others} after normalizing whitespace and indentation. Matched text:\n{}",
+            self.matched
+        )
     }
+}
 
-    let occurrences = current_content.match_indices(old).count();
-    if occurrences == 0 {
+/// Like [`build_replace_action`], but when `old` does not occur verbatim,
+/// retries by comparing whole lines with whitespace runs collapsed and
+/// indentation ignored. `new` is re-indented to the matched region.
+pub(crate) fn build_replace_action_with_fuzzy_fallback(
+    path: &str,
+    old: &str,
+    new: &str,
+    expected_replacements: Option<usize>,
+    policy: &EditPolicy,
+    cwd: &Path,
+) -> Result<(ApplyPatchAction, Option<FuzzyMatch>), LegacyEditError> {
+    let absolute_path = resolve_path(path, cwd);
+    policy.check(&absolute_path, cwd)?;
+    let current_content = read_to_lf_string(&absolute_path).map_err(|err| {
+        LegacyEditError::new(format!(
+            "replace failed: unable to read {} ({err}).",
+            absolute_path.display()
+        ))
+    })?;
+    if old.is_empty() || current_content.contains(old) {
+        let action = build_replace_action(path, old, new, expected_replacements, policy, cwd)?;
+        return Ok((action, None));
+    }
+
+    let (new_content, fuzzy) = fuzzy_replace_in_content(
+        &current_content,
+        old,
+        new,
+        expected_replacements,
+        &absolute_path,
+    )?;
+    let patch = build_update_patch(&absolute_path, cwd, &current_content, &new_content)?;
+    Ok((parse_patch(patch, cwd)?, Some(fuzzy)))
+}
+
+fn fuzzy_replace_in_content(
+    content: &str,
+    old: &str,
+    new: &str,
+    expected_replacements: Option<usize>,
+    absolute_path: &Path,
+) -> Result<(String, FuzzyMatch), LegacyEditError> {
+    let wanted: Vec<String> = old
+        .trim_matches('\n')
+        .lines()
+        .map(normalize_whitespace)
+        .collect();
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    if wanted.iter().all(String::is_empty) || wanted.len() > lines.len() {
         return Err(LegacyEditError::new(format!(
-            "replace failed: did not find old_string in {}.",
+            "replace failed: did not find old_string in {}, even ignoring whitespace differences.",
             absolute_path.display()
         )));
     }
 
+    let mut starts = Vec::new();
+    let mut index = 0;
+    while index + wanted.len() <= lines.len() {
+        let window = &lines[index..index + wanted.len()];
+        if window
+            .iter()
+            .zip(&wanted)
+            .all(|(line, want)| normalize_whitespace(line) == *want)
+        {
+            starts.push(index);
+            index += wanted.len();
+        } else {
+            index += 1;
+        }
+    }
+
     let expected = expected_replacements.unwrap_or(1);
-    if occurrences != expected {
+    if starts.is_empty() {
+        return Err(LegacyEditError::new(format!(
+            "replace failed: did not find old_string in {}, even ignoring whitespace differences.",
+            absolute_path.display()
+        )));
+    }
+    if starts.len() != expected {
         return Err(LegacyEditError::new(format!(
-            "replace failed: expected {expected} occurrence(s) but found {occurrences} in {}.",
+            "replace failed: old_string had no exact match, and ignoring whitespace it matched {} place(s) where {expected} were expected in {}.",
+            starts.len(),
             absolute_path.display()
         )));
     }
 
-    if old == new {
+    let old_indent = leading_whitespace(old.trim_start_matches('\n'));
+    let mut new_content = String::with_capacity(content.len());
+    let mut next = 0;
+    for &start in &starts {
+        for line in &lines[next..start] {
+            new_content.push_str(line);
+        }
+        let region = &lines[start..start + wanted.len()];
+        let file_indent = leading_whitespace(region[0]);
+        let replacement = reindent(new.trim_end_matches('\n'), old_indent, file_indent);
+        if !replacement.is_empty() || !new.is_empty() {
+            new_content.push_str(&replacement);
+            if region.last().is_some_and(|line| line.ends_with('\n')) {
+                new_content.push('\n');
+            }
+        }
+        next = start + wanted.len();
+    }
+    for line in &lines[next..] {
+        new_content.push_str(line);
+    }
+    if new_content == content {
         return Err(LegacyEditError::new(
-            "replace skipped: old_string and new_string are identical.",
+            "replace skipped: no changes were produced.",
         ));
     }
 
-    let new_content = current_content.replacen(old, new, expected);
+    let first = starts[0];
+    let fuzzy = FuzzyMatch {
+        start_line: first + 1,
+        end_line: first + wanted.len(),
+        occurrences: starts.len(),
+        matched: lines[first..first + wanted.len()].concat(),
+    };
+    Ok((new_content, fuzzy))
+}
+
+fn normalize_whitespace(line: &str) -> String {
+    line.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn leading_whitespace(text: &str) -> &str {
+    let trimmed = text.trim_start_matches([' ', '\t']);
+    &text[..text.len() - trimmed.len()]
+}
+
+/// Shifts the indentation of `text` from `from` to `to`, when one is a
+/// prefix of the other; otherwise leaves it unchanged.
+fn reindent(text: &str, from: &str, to: &str) -> String {
+    let shift = |line: &str| -> String {
+        if line.trim().is_empty() {
+            return line.to_string();
+        }
+        if let Some(extra) = to.strip_prefix(from) {
+            format!("{extra}{line}")
+        } else if let Some(extra) = from.strip_prefix(to) {
+            line.strip_prefix(extra).unwrap_or(line).to_string()
+        } else {
+            line.to_string()
+        }
+    };
+    text.split('\n').map(shift).collect::<Vec<_>>().join("\n")
+}
+
+/// Replaces every match of `pattern`, expanding `$1` / `${name}` in
+/// `replacement`, after checking the number of matches.
+fn prepare_replace_regex(
+    path: &str,
+    pattern: &str,
+    replacement: &str,
+    expected_matches: Option<usize>,
+    cwd: &Path,
+) -> Result<ApplyPatchAction, LegacyEditError> {
+    let absolute_path = resolve_path(path, cwd);
+    let regex = compile_edit_regex(pattern)?;
+    check_replacement_groups(&regex, replacement)?;
+    let current_content = read_to_lf_string(&absolute_path).map_err(|err| {
+        LegacyEditError::new(format!(
+            "replace_regex failed: unable to read {} ({err}).",
+            absolute_path.display()
+        ))
+    })?;
+
+    let mut occurrences = 0usize;
+    for found in regex.find_iter(&current_content) {
+        if found.is_empty() {
+            return Err(LegacyEditError::new(format!(
+                "replace_regex failed: pattern matches an empty string at byte {}; make it match at least one character.",
+                found.start()
+            )));
+        }
+        occurrences += 1;
+    }
+    if occurrences == 0 {
+        return Err(LegacyEditError::new(format!(
+            "replace_regex failed: pattern did not match in {}.",
+            absolute_path.display()
+        )));
+    }
+    let expected = expected_matches.unwrap_or(1);
+    if occurrences != expected {
+        return Err(LegacyEditError::new(format!(
+            "replace_regex failed: expected {expected} match(es) but found {occurrences} in {}.",
+            absolute_path.display()
+        )));
+    }
+
+    let new_content = regex.replace_all(&current_content, replacement);
     if new_content == current_content {
         return Err(LegacyEditError::new(
-            "replace skipped: no changes were produced.",
+            "replace_regex skipped: no changes were produced.",
+        ));
+    }
+
+    let patch = build_update_patch(&absolute_path, cwd, &current_content, &new_content)?;
+    parse_patch(patch, cwd)
+}
+
+fn compile_edit_regex(pattern: &str) -> Result<Regex, LegacyEditError> {
+    if pattern.is_empty() {
+        return Err(LegacyEditError::new(
+            "replace_regex failed: pattern must not be empty.",
+        ));
+    }
+    if pattern.len() > MAX_REGEX_PATTERN_BYTES {
+        return Err(LegacyEditError::new(format!(
+            "replace_regex failed: pattern is {} bytes; the limit is {MAX_REGEX_PATTERN_BYTES}.",
+            pattern.len()
+        )));
+    }
+    RegexBuilder::new(pattern)
+        .size_limit(MAX_REGEX_COMPILED_BYTES)
+        .nest_limit(MAX_REGEX_NESTING)
+        .build()
+        .map_err(|err| {
+            LegacyEditError::new(format!("replace_regex failed: invalid pattern: {err}"))
+        })
+}
+
+/// Rejects `$N` / `${name}` references to groups the pattern does not
+/// define, e.g. `$1x`, which names a group `1x` rather than group 1.
+fn check_replacement_groups(regex: &Regex, replacement: &str) -> Result<(), LegacyEditError> {
+    let mut rest = replacement;
+    while let Some(index) = rest.find('$') {
+        rest = &rest[index + 1..];
+        if let Some(after) = rest.strip_prefix('$') {
+            rest = after;
+            continue;
+        }
+        let (name, after) = match rest.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], &braced[end + 1..]),
+                None => {
+                    return Err(LegacyEditError::new(
+                        "replace_regex failed: unterminated `${` in replacement.",
+                    ));
+                }
+            },
+            None => {
+                let end = rest
+                    .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_'))
+                    .unwrap_or(rest.len());
+                (&rest[..end], &rest[end..])
+            }
+        };
+        rest = after;
+        if name.is_empty() {
+            continue;
+        }
+        let known = match name.parse::<usize>() {
+            Ok(group) => group < regex.captures_len(),
+            Err(_) => regex.capture_names().flatten().any(|group| group == name),
+        };
+        if !known {
+            return Err(LegacyEditError::new(format!(
+                "replace_regex failed: replacement refers to group `{name}`, which the pattern does not define. Use `${{1}}` to put text right after a group reference and `$$` for a literal `$`."
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Inserts `content` as whole lines before line `line_number` (1-based);
+/// one past the last line inserts at the end of the file.
+fn prepare_insert(
+    path: &str,
+    line_number: usize,
+    content: &str,
+    cwd: &Path,
+) -> Result<ApplyPatchAction, LegacyEditError> {
+    let absolute_path = resolve_path(path, cwd);
+    let current_content = read_to_lf_string(&absolute_path).map_err(|err| {
+        LegacyEditError::new(format!(
+            "insert failed: unable to read {} ({err}).",
+            absolute_path.display()
+        ))
+    })?;
+    if content.is_empty() {
+        return Err(LegacyEditError::new(
+            "insert skipped: content must not be empty.",
         ));
     }
 
+    let lines: Vec<&str> = current_content.split_inclusive('\n').collect();
+    if line_number == 0 || line_number > lines.len() + 1 {
+        return Err(LegacyEditError::new(format!(
+            "insert failed: line_number must be between 1 and {} for {}.",
+            lines.len() + 1,
+            absolute_path.display()
+        )));
+    }
+
+    let mut new_content = String::with_capacity(current_content.len() + content.len() + 2);
+    for line in &lines[..line_number - 1] {
+        new_content.push_str(line);
+    }
+    push_lines(&mut new_content, content);
+    for line in &lines[line_number - 1..] {
+        new_content.push_str(line);
+    }
+
     let patch = build_update_patch(&absolute_path, cwd, &current_content, &new_content)?;
     parse_patch(patch, cwd)
 }
 
+/// Adds `content` as whole lines at the end of an existing file.
+fn prepare_append(
+    path: &str,
+    content: &str,
+    cwd: &Path,
+) -> Result<ApplyPatchAction, LegacyEditError> {
+    let absolute_path = resolve_path(path, cwd);
+    let current_content = read_to_lf_string(&absolute_path).map_err(|err| {
+        LegacyEditError::new(format!(
+            "append failed: unable to read {} ({err}). Use write_file to create a new file.",
+            absolute_path.display()
+        ))
+    })?;
+    if content.is_empty() {
+        return Err(LegacyEditError::new(
+            "append skipped: content must not be empty.",
+        ));
+    }
+
+    let mut new_content = current_content.clone();
+    push_lines(&mut new_content, content);
+    let patch = build_update_patch(&absolute_path, cwd, &current_content, &new_content)?;
+    parse_patch(patch, cwd)
+}
+
+/// Adds `content` as whole lines at the start of an existing file.
+fn prepare_prepend(
+    path: &str,
+    content: &str,
+    cwd: &Path,
+) -> Result<ApplyPatchAction, LegacyEditError> {
+    let absolute_path = resolve_path(path, cwd);
+    let current_content = read_to_lf_string(&absolute_path).map_err(|err| {
+        LegacyEditError::new(format!(
+            "prepend failed: unable to read {} ({err}). Use write_file to create a new file.",
+            absolute_path.display()
+        ))
+    })?;
+    if content.is_empty() {
+        return Err(LegacyEditError::new(
+            "prepend skipped: content must not be empty.",
+        ));
+    }
+
+    let mut new_content = String::with_capacity(current_content.len() + content.len() + 1);
+    push_lines(&mut new_content, content);
+    new_content.push_str(&current_content);
+    let patch = build_update_patch(&absolute_path, cwd, &current_content, &new_content)?;
+    parse_patch(patch, cwd)
+}
+
+/// Appends `lines` to `dst` so that both start and end on a line boundary.
+fn push_lines(dst: &mut String, lines: &str) {
+    if !dst.is_empty() && !dst.ends_with('\n') {
+        dst.push('\n');
+    }
+    dst.push_str(lines);
+    if !lines.ends_with('\n') {
+        dst.push('\n');
+    }
+}
+
+/// Replaces `old` with `new` in `content`, checking the occurrence count.
+/// Errors start with `label`, e.g. `replace failed: ...`.
+fn replace_in_content(
+    label: &str,
+    content: &str,
+    old: &str,
+    new: &str,
+    expected_replacements: Option<usize>,
+    absolute_path: &Path,
+) -> Result<String, LegacyEditError> {
+    if old.is_empty() {
+        return Err(LegacyEditError::new(format!(
+            "{label} failed: old_string must not be empty. Use write_file to create a new file."
+        )));
+    }
+
+    let occurrences = content.match_indices(old).count();
+    if occurrences == 0 {
+        return Err(LegacyEditError::new(format!(
+            "{label} failed: did not find old_string in {}.",
+            absolute_path.display()
+        )));
+    }
+
+    let expected = expected_replacements.unwrap_or(1);
+    if occurrences != expected {
+        return Err(LegacyEditError::new(format!(
+            "{label} failed: expected {expected} occurrence(s) but found {occurrences} in {}.",
+            absolute_path.display()
+        )));
+    }
+
+    if old == new {
+        return Err(LegacyEditError::new(format!(
+            "{label} skipped: old_string and new_string are identical."
+        )));
+    }
+
+    let new_content = content.replacen(old, new, expected);
+    if new_content == content {
+        return Err(LegacyEditError::new(format!(
+            "{label} skipped: no changes were produced."
+        )));
+    }
+    Ok(new_content)
+}
+
 fn build_add_file_patch(path: &Path, cwd: &Path, content: &str) -> String {
     let patch_path = path_for_patch(path, cwd);
     let mut patch = String::new();
@@ -284,8 +1252,12 @@ fn build_update_patch(
     Ok(patch)
 }
 
+/// Rewrites `similar`'s unified diff into the hunk format apply_patch
+/// parses. "No newline at end of file" markers are dropped; apply_patch
+/// always ends files with a newline.
 fn normalize_unified_diff(diff: &str) -> String {
     diff.split_inclusive('\n')
+        .filter(|line| !line.starts_with("\\ "))
         .map(|line| {
             let (body, newline) = line
                 .strip_suffix('\n')
@@ -335,6 +1307,24 @@ fn parse_expected_replacements(value: &str) -> Result<usize, LegacyEditError> {
     Ok(parsed)
 }
 
+fn parse_expected_matches(value: &str) -> Result<usize, LegacyEditError> {
+    match value.parse::<usize>() {
+        Ok(parsed) if parsed > 0 => Ok(parsed),
+        _ => Err(LegacyEditError::new(format!(
+            "replace_regex failed: expected_matches must be a positive integer, got {value}."
+        ))),
+    }
+}
+
+fn parse_line_number(value: &str) -> Result<usize, LegacyEditError> {
+    match value.parse::<usize>() {
+        Ok(parsed) if parsed > 0 => Ok(parsed),
+        _ => Err(LegacyEditError::new(format!(
+            "insert failed: line_number must be a positive integer, got {value}."
+        ))),
+    }
+}
+
 fn parse_patch(patch: String, cwd: &Path) -> Result<ApplyPatchAction, LegacyEditError> {
     let argv = vec!["apply_patch".to_string(), patch];
     match codex_apply_patch::maybe_parse_apply_patch_verified(&argv, cwd) {
@@ -351,13 +1341,144 @@ fn parse_patch(patch: String, cwd: &Path) -> Result<ApplyPatchAction, LegacyEdit
     }
 }
 
-fn resolve_path(path: &str, cwd: &Path) -> PathBuf {
-    let candidate = PathBuf::from(path);
-    if candidate.is_absolute() {
-        candidate
-    } else {
-        cwd.join(candidate)
+/// Renders the changes of `action` as a git-style unified diff, so an edit
+/// can be shown without applying it.
+pub(crate) fn preview_diff(action: &ApplyPatchAction) -> String {
+    let mut paths: Vec<&PathBuf> = action.changes().keys().collect();
+    paths.sort();
+    let mut out = String::new();
+    for path in paths {
+        let patch_path = path_for_patch(path, &action.cwd);
+        let (old_content, new_content, old_label, new_label) = match &action.changes()[path] {
+            ApplyPatchFileChange::Add { content } => (
+                String::new(),
+                content.clone(),
+                "/dev/null".to_string(),
+                format!("b/{patch_path}"),
+            ),
+            ApplyPatchFileChange::Delete { content } => (
+                content.clone(),
+                String::new(),
+                format!("a/{patch_path}"),
+                "/dev/null".to_string(),
+            ),
+            ApplyPatchFileChange::Update {
+                new_content,
+                move_path,
+                ..
+            } => (
+                fs::read_to_string(path).unwrap_or_default(),
+                new_content.clone(),
+                format!("a/{patch_path}"),
+                format!(
+                    "b/{}",
+                    move_path
+                        .as_deref()
+                        .map_or(patch_path.clone(), |dest| path_for_patch(dest, &action.cwd))
+                ),
+            ),
+        };
+        let diff = TextDiff::from_lines(&old_content, &new_content);
+        out.push_str(
+            &diff
+                .unified_diff()
+                .context_radius(3)
+                .header(&old_label, &new_label)
+                .to_string(),
+        );
+    }
+    out
+}
+
+/// Permissions of the files an edit overwrites, captured before the patch
+/// is applied. Applying a patch rewrites file contents and can drop the
+/// original mode (notably the executable bit on scripts), so callers put
+/// these back once the patch has landed.
+#[derive(Debug, Default)]
+pub(crate) struct PreservedPermissions(Vec<(PathBuf, fs::Permissions)>);
+
+impl PreservedPermissions {
+    pub(crate) fn capture(action: &ApplyPatchAction) -> Self {
+        let mut preserved = Vec::new();
+        for (path, change) in action.changes() {
+            let ApplyPatchFileChange::Update { move_path, .. } = change else {
+                continue;
+            };
+            if let Ok(metadata) = fs::metadata(path) {
+                let target = move_path.clone().unwrap_or_else(|| path.clone());
+                preserved.push((target, metadata.permissions()));
+            }
+        }
+        Self(preserved)
+    }
+
+    /// Restores every captured mode that changed, returning the first error.
+    pub(crate) fn restore(&self) -> std::io::Result<()> {
+        let mut first_error = None;
+        for (path, permissions) in &self.0 {
+            let result = fs::metadata(path).and_then(|metadata| {
+                if metadata.permissions() == *permissions {
+                    Ok(())
+                } else {
+                    fs::set_permissions(path, permissions.clone())
+                }
+            });
+            if let Err(err) = result {
+                first_error.get_or_insert(err);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+}
+
+/// Line-break convention requested through an edit tool's `line_endings`
+/// argument. Without it, updated files keep the convention they already use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum LineEndings {
+    Lf,
+    Crlf,
+}
+
+/// Files `action` creates or rewrites, i.e. the ones a `line_endings`
+/// override applies to.
+pub(crate) fn written_paths(action: &ApplyPatchAction) -> Vec<PathBuf> {
+    action
+        .changes()
+        .iter()
+        .filter_map(|(path, change)| match change {
+            ApplyPatchFileChange::Add { .. } => Some(path.clone()),
+            ApplyPatchFileChange::Update { move_path, .. } => {
+                Some(move_path.clone().unwrap_or_else(|| path.clone()))
+            }
+            ApplyPatchFileChange::Delete { .. } => None,
+        })
+        .collect()
+}
+
+/// Rewrites `paths` to use `line_endings`, after the patch has been applied.
+pub(crate) fn convert_line_endings(
+    paths: &[PathBuf],
+    line_endings: LineEndings,
+) -> std::io::Result<()> {
+    for path in paths {
+        let content = fs::read_to_string(path)?;
+        let lf = to_lf(&content);
+        let converted = match line_endings {
+            LineEndings::Lf => lf,
+            LineEndings::Crlf => lf.replace('\n', "\r\n"),
+        };
+        if converted != content {
+            fs::write(path, converted)?;
+        }
     }
+    Ok(())
+}
+
+/// Resolves `path` against `cwd` and drops `.` and `..` components, so edits
+/// and the workspace checks agree on which file is meant.
+fn resolve_path(path: &str, cwd: &Path) -> PathBuf {
+    normalize_lexically(&cwd.join(path))
 }
 
 fn path_for_patch(path: &Path, cwd: &Path) -> String {
@@ -372,7 +1493,6 @@ fn path_for_patch(path: &Path, cwd: &Path) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use codex_apply_patch::ApplyPatchFileChange;
     use std::fs;
     use tempfile::tempdir;
 
@@ -385,7 +1505,7 @@ mod tests {
         let tmp = tempdir().unwrap();
         let cwd = tmp.path();
         let args = command(&["write_file", "hello.txt", "hi there\n"]);
-        let action = maybe_build_apply_patch_action(&args, cwd)
+        let action = maybe_build_apply_patch_action(&args, cwd, &EditPolicy::default())
             .unwrap()
             .expect("write_file action");
         let changes = action.changes();
@@ -398,12 +1518,296 @@ mod tests {
         }
     }
 
+    #[test]
+    fn write_file_refuses_binary_and_oversized_files() {
+        let tmp = tempdir().unwrap();
+        fs::write(
+            tmp.path().join("image.png"),
+            [0x89, b'P', b'N', b'G', 0, 0, 1],
+        )
+        .unwrap();
+        let err = build_write_file_action(
+            "image.png",
+            "text\n",
+            WriteFileOptions::default(),
+            &EditPolicy::default(),
+            tmp.path(),
+        )
+        .expect_err("binary target should be refused");
+        assert!(
+            err.to_string().contains("binary"),
+            "unexpected error: {err}"
+        );
+        assert!(
+            err.to_string().contains("shell tools"),
+            "unexpected error: {err}"
+        );
+
+        let small = EditPolicy {
+            write_file_max_bytes: 16,
+            ..EditPolicy::default()
+        };
+        fs::write(tmp.path().join("big.txt"), "x".repeat(64)).unwrap();
+        let err = build_write_file_action(
+            "big.txt",
+            "small\n",
+            WriteFileOptions::default(),
+            &small,
+            tmp.path(),
+        )
+        .expect_err("oversized target should be refused");
+        assert!(
+            err.to_string().contains("16-byte limit"),
+            "unexpected error: {err}"
+        );
+
+        let err = build_write_file_action(
+            "new.txt",
+            &"y".repeat(64),
+            WriteFileOptions::default(),
+            &small,
+            tmp.path(),
+        )
+        .expect_err("oversized content should be refused");
+        assert!(
+            err.to_string().contains("16-byte limit"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn write_file_options_control_missing_files_and_directories() {
+        let tmp = tempdir().unwrap();
+        let policy = EditPolicy::default();
+        let strict = WriteFileOptions {
+            create_parents: false,
+            must_exist: false,
+        };
+
+        let err = build_write_file_action("sercv/lib.rs", "x\n", strict, &policy, tmp.path())
+            .expect_err("missing directory should be refused");
+        assert!(
+            err.to_string().contains("create_parents"),
+            "unexpected error: {err}"
+        );
+        build_write_file_action("./nested/../lib.rs", "x\n", strict, &policy, tmp.path())
+            .expect("existing directory should be accepted");
+        build_write_file_action(
+            "sercv/lib.rs",
+            "x\n",
+            WriteFileOptions::default(),
+            &policy,
+            tmp.path(),
+        )
+        .expect("parents are created by default");
+
+        let must_exist = WriteFileOptions {
+            create_parents: true,
+            must_exist: true,
+        };
+        let err = build_write_file_action("lib.rs", "x\n", must_exist, &policy, tmp.path())
+            .expect_err("missing file should be refused");
+        assert!(
+            err.to_string().contains("must_exist"),
+            "unexpected error: {err}"
+        );
+        fs::write(tmp.path().join("lib.rs"), "old\n").unwrap();
+        build_write_file_action("lib.rs", "x\n", must_exist, &policy, tmp.path())
+            .expect("existing file should be overwritten");
+
+        let err = build_write_file_action(" ", "x\n", must_exist, &policy, tmp.path())
+            .expect_err("empty path should be refused");
+        assert!(err.to_string().contains("empty"), "unexpected error: {err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn preserved_permissions_restore_executable_bit() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempdir().unwrap();
+        let script = tmp.path().join("run.sh");
+        fs::write(&script, "#!/bin/sh\necho hi\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let action = build_replace_action(
+            "run.sh",
+            "echo hi",
+            "echo bye",
+            None,
+            &EditPolicy::default(),
+            tmp.path(),
+        )
+        .expect("replace action");
+        let preserved = PreservedPermissions::capture(&action);
+
+        // Simulate a writer that recreates the file with default permissions.
+        fs::remove_file(&script).unwrap();
+        fs::write(&script, "#!/bin/sh\necho bye\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o644)).unwrap();
+
+        preserved.restore().unwrap();
+        let mode = fs::metadata(&script).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+    }
+
+    #[test]
+    fn replace_matches_crlf_files_and_line_endings_can_be_converted() {
+        let tmp = tempdir().unwrap();
+        let file = tmp.path().join("win.txt");
+        fs::write(&file, "first\r\nsecond\r\nthird\r\n").unwrap();
+
+        let action = build_replace_action(
+            "win.txt",
+            "first\nsecond",
+            "first\n2nd",
+            None,
+            &EditPolicy::default(),
+            tmp.path(),
+        )
+        .expect("multi-line old_string should match CRLF content");
+        match action.changes().get(&file) {
+            Some(ApplyPatchFileChange::Update { new_content, .. }) => {
+                assert_eq!(new_content, "first\r\n2nd\r\nthird\r\n");
+            }
+            other => panic!("expected Update change, got {other:?}"),
+        }
+
+        convert_line_endings(&written_paths(&action), LineEndings::Lf).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "first\nsecond\nthird\n");
+        convert_line_endings(std::slice::from_ref(&file), LineEndings::Crlf).unwrap();
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "first\r\nsecond\r\nthird\r\n"
+        );
+    }
+
+    #[test]
+    fn protected_paths_are_refused() {
+        let tmp = tempdir().unwrap();
+        fs::create_dir(tmp.path().join(".git")).unwrap();
+        fs::write(tmp.path().join(".git/config"), "[core]\n").unwrap();
+        let policy = EditPolicy::default();
+
+        let err = build_write_file_action(
+            "src/../.git/config",
+            "[user]\n",
+            WriteFileOptions::default(),
+            &policy,
+            tmp.path(),
+        )
+        .expect_err(".git should be protected");
+        assert!(
+            err.to_string().contains("`.git/**`"),
+            "unexpected error: {err}"
+        );
+        let err = build_write_file_action(
+            "config/prod.env",
+            "KEY=1\n",
+            WriteFileOptions::default(),
+            &policy,
+            tmp.path(),
+        )
+        .expect_err("env files should be protected");
+        assert!(
+            err.to_string().contains("`*.env`"),
+            "unexpected error: {err}"
+        );
+
+        let outside = tempdir().unwrap();
+        let outside_file = outside.path().join("notes.txt");
+        let outside_path = outside_file.to_string_lossy();
+        build_write_file_action(
+            &outside_path,
+            "hi\n",
+            WriteFileOptions::default(),
+            &policy,
+            tmp.path(),
+        )
+        .expect("outside paths are allowed by default");
+        let strict = EditPolicy::new(WRITE_FILE_MAX_BYTES, DEFAULT_PROTECTED_PATHS, true);
+        let err = build_write_file_action(
+            &outside_path,
+            "hi\n",
+            WriteFileOptions::default(),
+            &strict,
+            tmp.path(),
+        )
+        .expect_err("outside paths should be refused");
+        assert!(
+            err.to_string().contains("outside the workspace"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn protected_paths_are_refused_through_symlinks() {
+        let tmp = tempdir().unwrap();
+        fs::create_dir(tmp.path().join(".git")).unwrap();
+        fs::write(tmp.path().join(".git/config"), "[core]\n").unwrap();
+        std::os::unix::fs::symlink(".git", tmp.path().join("link")).unwrap();
+        let policy = EditPolicy::default();
+
+        for path in ["link/config", "link/hooks/pre-commit"] {
+            let err = build_write_file_action(
+                path,
+                "#!/bin/sh\n",
+                WriteFileOptions::default(),
+                &policy,
+                tmp.path(),
+            )
+            .expect_err("symlinked .git should be protected");
+            assert!(
+                err.to_string().contains("`.git/**`"),
+                "unexpected error for {path}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn write_file_reads_content_from_a_heredoc() {
+        let tmp = tempdir().unwrap();
+        let file = tmp.path().join("run.sh");
+        let script = "write_file run.sh - <<'EOF'\necho \"$HOME\"\n\tindented\nEOF\n";
+
+        let args = command(&["bash", "-lc", script]);
+        let action = maybe_build_apply_patch_action(&args, tmp.path(), &EditPolicy::default())
+            .unwrap()
+            .expect("write_file action");
+        match action.changes().get(&file) {
+            Some(ApplyPatchFileChange::Add { content }) => {
+                assert_eq!(content, "echo \"$HOME\"\n\tindented\n");
+            }
+            other => panic!("expected Add change, got {other:?}"),
+        }
+
+        let err = |args: &[&str]| {
+            maybe_build_apply_patch_action(&command(args), tmp.path(), &EditPolicy::default())
+                .expect_err("invalid heredoc write")
+                .to_string()
+        };
+        assert!(err(&["write_file", "run.sh", "-"]).contains("reads it from a heredoc"));
+        assert!(
+            err(&["bash", "-lc", "write_file run.sh - <<EOF\necho $HOME\nEOF"])
+                .contains("quote the heredoc delimiter")
+        );
+        assert!(
+            err(&["bash", "-lc", "write_file run.sh - <<'EOF'\nno end"])
+                .contains("no closing EOF line")
+        );
+        assert!(
+            err(&["bash", "-lc", "write_file run.sh - <<'EOF'\nx\nEOF\nls"])
+                .contains("only command")
+        );
+    }
+
     #[test]
     fn delete_file_requires_existing_file() {
         let tmp = tempdir().unwrap();
         let cwd = tmp.path();
         let args = command(&["delete", "missing.txt"]);
-        let err = maybe_build_apply_patch_action(&args, cwd)
+        let err = maybe_build_apply_patch_action(&args, cwd, &EditPolicy::default())
             .expect_err("delete should fail for missing file");
         assert!(
             err.to_string().contains("does not exist"),
@@ -417,7 +1821,7 @@ mod tests {
         let file = tmp.path().join("note.md");
         fs::write(&file, "hello world\n").unwrap();
         let args = command(&["replace", "note.md", "world", "codex"]);
-        let action = maybe_build_apply_patch_action(&args, tmp.path())
+        let action = maybe_build_apply_patch_action(&args, tmp.path(), &EditPolicy::default())
             .unwrap()
             .expect("replace action");
         match action.changes().get(&file) {
@@ -434,4 +1838,229 @@ mod tests {
             other => panic!("expected Update change, got {other:?}"),
         }
     }
+
+    #[test]
+    fn fuzzy_replace_tolerates_whitespace_and_reindents() {
+        let tmp = tempdir().unwrap();
+        let file = tmp.path().join("lib.rs");
+        fs::write(&file, "fn main() {\n    let x = 1;  \n    run(x);\n}\n").unwrap();
+
+        let (action, fuzzy) = build_replace_action_with_fuzzy_fallback(
+            "lib.rs",
+            "let x = 1;\nrun(x);",
+            "let x = 2;\nrun(x);",
+            None,
+            &EditPolicy::default(),
+            tmp.path(),
+        )
+        .unwrap();
+        let fuzzy = fuzzy.expect("fuzzy match reported");
+        assert_eq!((fuzzy.start_line, fuzzy.end_line), (2, 3));
+        assert_eq!(fuzzy.matched, "    let x = 1;  \n    run(x);\n");
+        match action.changes().get(&file) {
+            Some(ApplyPatchFileChange::Update { new_content, .. }) => {
+                assert_eq!(new_content, "fn main() {\n    let x = 2;\n    run(x);\n}\n");
+            }
+            other => panic!("expected Update change, got {other:?}"),
+        }
+
+        let err = build_replace_action_with_fuzzy_fallback(
+            "lib.rs",
+            "let y = 1;",
+            "let y = 2;",
+            None,
+            &EditPolicy::default(),
+            tmp.path(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("even ignoring whitespace"));
+    }
+
+    #[test]
+    fn preview_renders_unified_diff_without_touching_the_file() {
+        let tmp = tempdir().unwrap();
+        let file = tmp.path().join("note.md");
+        fs::write(&file, "hello world\n").unwrap();
+        let action = build_replace_action(
+            "note.md",
+            "world",
+            "codex",
+            None,
+            &EditPolicy::default(),
+            tmp.path(),
+        )
+        .expect("replace action");
+
+        assert_eq!(
+            preview_diff(&action),
+            "--- a/note.md\n+++ b/note.md\n@@ -1 +1 @@\n-hello world\n+hello codex\n"
+        );
+        assert_eq!(fs::read_to_string(&file).unwrap(), "hello world\n");
+    }
+
+    #[test]
+    fn replace_regex_expands_capture_groups() {
+        let tmp = tempdir().unwrap();
+        let file = tmp.path().join("Cargo.toml");
+        fs::write(&file, "a = \"1.2.3\"\nb =   \"1.4.0\"\n").unwrap();
+
+        let args = command(&[
+            "replace_regex",
+            "Cargo.toml",
+            r#"(\w) *= *"1\.(\d+)\.\d+""#,
+            r#"$1 = "2.${2}.0""#,
+            "2",
+        ]);
+        let action = maybe_build_apply_patch_action(&args, tmp.path(), &EditPolicy::default())
+            .unwrap()
+            .expect("replace_regex action");
+        match action.changes().get(&file) {
+            Some(ApplyPatchFileChange::Update { new_content, .. }) => {
+                assert_eq!(new_content, "a = \"2.2.0\"\nb = \"2.4.0\"\n");
+            }
+            other => panic!("expected Update change, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn replace_regex_rejects_ambiguous_patterns() {
+        let tmp = tempdir().unwrap();
+        fs::write(tmp.path().join("note.md"), "version 1\n").unwrap();
+        let err = |pattern: &str, replacement: &str| {
+            build_replace_regex_action(
+                "note.md",
+                pattern,
+                replacement,
+                None,
+                &EditPolicy::default(),
+                tmp.path(),
+            )
+            .expect_err("pattern should be rejected")
+            .to_string()
+        };
+
+        assert!(err("x*", "y").contains("matches an empty string"));
+        assert!(err(r"version (\d)", "v$1x").contains("group `1x`"));
+        assert!(err("(", "y").contains("invalid pattern"));
+        assert!(err(&"a".repeat(MAX_REGEX_PATTERN_BYTES + 1), "y").contains("the limit is"));
+    }
+
+    #[test]
+    fn insert_adds_lines_before_the_given_line() {
+        let tmp = tempdir().unwrap();
+        let file = tmp.path().join("main.rs");
+        fs::write(&file, "use a;\nuse c;\n\nfn main() {}\n").unwrap();
+
+        let args = command(&["insert_at_line", "main.rs", "2", "use b;"]);
+        let action = maybe_build_apply_patch_action(&args, tmp.path(), &EditPolicy::default())
+            .unwrap()
+            .expect("insert action");
+        match action.changes().get(&file) {
+            Some(ApplyPatchFileChange::Update { new_content, .. }) => {
+                assert_eq!(new_content, "use a;\nuse b;\nuse c;\n\nfn main() {}\n");
+            }
+            other => panic!("expected Update change, got {other:?}"),
+        }
+
+        let args = command(&["insert_at_line", "main.rs", "6", "use d;"]);
+        let err = maybe_build_apply_patch_action(&args, tmp.path(), &EditPolicy::default())
+            .expect_err("line past the end of the file");
+        assert!(
+            err.to_string().contains("between 1 and 5"),
+            "unexpected error message: {err}"
+        );
+    }
+
+    #[test]
+    fn append_adds_lines_at_end_of_file() {
+        let tmp = tempdir().unwrap();
+        let file = tmp.path().join("notes.md");
+        fs::write(&file, "first").unwrap();
+
+        let args = command(&["append_to_file", "notes.md", "second"]);
+        let action = maybe_build_apply_patch_action(&args, tmp.path(), &EditPolicy::default())
+            .unwrap()
+            .expect("append action");
+        match action.changes().get(&file) {
+            Some(ApplyPatchFileChange::Update { new_content, .. }) => {
+                assert_eq!(new_content, "first\nsecond\n");
+            }
+            other => panic!("expected Update change, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn prepend_adds_lines_at_start_of_file() {
+        let tmp = tempdir().unwrap();
+        let file = tmp.path().join("notes.md");
+        fs::write(&file, "body\n").unwrap();
+
+        let args = command(&["prepend", "notes.md", "# Title"]);
+        let action = maybe_build_apply_patch_action(&args, tmp.path(), &EditPolicy::default())
+            .unwrap()
+            .expect("prepend action");
+        match action.changes().get(&file) {
+            Some(ApplyPatchFileChange::Update { new_content, .. }) => {
+                assert_eq!(new_content, "# Title\nbody\n");
+            }
+            other => panic!("expected Update change, got {other:?}"),
+        }
+
+        let args = command(&["prepend", "missing.md", "# Title"]);
+        let err = maybe_build_apply_patch_action(&args, tmp.path(), &EditPolicy::default())
+            .expect_err("prepend needs an existing file");
+        assert!(
+            err.to_string().starts_with("prepend failed"),
+            "unexpected error message: {err}"
+        );
+    }
+
+    #[test]
+    fn multi_edit_applies_edits_in_order_as_one_patch() {
+        let tmp = tempdir().unwrap();
+        let file = tmp.path().join("lib.rs");
+        fs::write(&file, "fn old() {}\n\nfn main() {\n    old();\n}\n").unwrap();
+        let edit = |old: &str, new: &str| ReplaceEdit {
+            old: old.to_string(),
+            new: new.to_string(),
+            expected_replacements: None,
+        };
+
+        let action = build_multi_edit_action(
+            "lib.rs",
+            &[
+                edit("fn old()", "fn renamed()"),
+                edit("old();", "renamed();"),
+            ],
+            &EditPolicy::default(),
+            tmp.path(),
+        )
+        .expect("multi_edit action");
+        match action.changes().get(&file) {
+            Some(ApplyPatchFileChange::Update { new_content, .. }) => {
+                assert_eq!(
+                    new_content,
+                    "fn renamed() {}\n\nfn main() {\n    renamed();\n}\n"
+                );
+            }
+            other => panic!("expected Update change, got {other:?}"),
+        }
+
+        let err = build_multi_edit_action(
+            "lib.rs",
+            &[
+                edit("fn old()", "fn renamed()"),
+                edit("old();", "x();"),
+                edit("missing", "y"),
+            ],
+            &EditPolicy::default(),
+            tmp.path(),
+        )
+        .expect_err("a failing edit rejects the whole call");
+        assert!(
+            err.to_string()
+                .starts_with("multi_edit edit 3 failed: did not find old_string"),
+            "unexpected error message: {err}"
+        );
+    }
 }
@@ -1,5 +1,10 @@
 pub mod apply_patch;
+mod background_job;
+mod coco;
 mod edit;
+mod git;
+mod glob;
+mod grep;
 mod grep_files;
 mod legacy_edit;
 mod list_dir;
@@ -9,13 +14,21 @@ mod plan;
 mod read_file;
 mod shell;
 mod test_sync;
+mod turn_diff;
 mod unified_exec;
+mod verify_patch;
 mod view_image;
 
 pub use plan::PLAN_TOOL;
 
 pub use apply_patch::ApplyPatchHandler;
+pub use background_job::BackgroundJobHandler;
+pub use coco::CocoHandler;
+pub use coco::CocoStatusHandler;
 pub use edit::EditHandler;
+pub use git::GitHandler;
+pub use glob::GlobHandler;
+pub use grep::GrepHandler;
 pub use grep_files::GrepFilesHandler;
 pub use list_dir::ListDirHandler;
 pub use mcp::McpHandler;
@@ -25,5 +38,7 @@ pub use read_file::ReadFileHandler;
 pub use shell::ShellCommandHandler;
 pub use shell::ShellHandler;
 pub use test_sync::TestSyncHandler;
+pub use turn_diff::TurnDiffHandler;
 pub use unified_exec::UnifiedExecHandler;
+pub use verify_patch::VerifyPatchHandler;
 pub use view_image::ViewImageHandler;
@@ -1,9 +1,12 @@
 use std::collections::VecDeque;
+use std::path::Path;
 use std::path::PathBuf;
 
 use async_trait::async_trait;
 use codex_utils_string::take_bytes_at_char_boundary;
 use serde::Deserialize;
+use tokio::fs::File;
+use tokio::io::AsyncReadExt;
 use tracing::info;
 use tracing::warn;
 
@@ -33,6 +36,10 @@ struct ReadFileArgs {
     /// Maximum number of lines to return; defaults to 2000.
     #[serde(default = "defaults::limit")]
     limit: usize,
+    /// Maximum bytes of lines to return; defaults to 64 KiB. At least one
+    /// line is always returned.
+    #[serde(default = "defaults::max_bytes")]
+    max_bytes: usize,
     /// Determines whether the handler reads a simple slice or indentation-aware block.
     #[serde(default)]
     mode: ReadMode,
@@ -98,7 +105,9 @@ impl ToolHandler for ReadFileHandler {
     }
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, .. } = invocation;
+        let ToolInvocation {
+            session, payload, ..
+        } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -119,6 +128,7 @@ impl ToolHandler for ReadFileHandler {
             file_path,
             offset,
             limit,
+            max_bytes,
             mode,
             indentation,
         } = args;
@@ -147,6 +157,18 @@ impl ToolHandler for ReadFileHandler {
             ));
         }
 
+        if max_bytes == 0 {
+            warn!(
+                tool = "read_file",
+                %file_path,
+                max_bytes,
+                "invalid read_file max_bytes"
+            );
+            return Err(FunctionCallError::RespondToModel(
+                "max_bytes must be greater than zero".to_string(),
+            ));
+        }
+
         let path = PathBuf::from(&file_path);
         if !path.is_absolute() {
             warn!(
@@ -164,24 +186,58 @@ impl ToolHandler for ReadFileHandler {
             %file_path,
             offset,
             limit,
+            max_bytes,
             mode = ?mode,
             has_indentation_args = indentation.is_some(),
             "read_file invocation received"
         );
 
-        let collected = match mode {
+        let mut collected = match mode {
             ReadMode::Slice => slice::read(&path, offset, limit).await?,
             ReadMode::Indentation => {
                 let indentation = indentation.unwrap_or_default();
                 indentation::read_block(&path, offset, limit, indentation).await?
             }
         };
+        let truncated = truncate_to_bytes(&mut collected, max_bytes);
+        let total_lines = count_lines(&path).await?;
         info!(
             tool = "read_file",
             %file_path,
             returned_lines = collected.len(),
+            total_lines,
+            truncated,
             "read_file completed successfully"
         );
+
+        let mut footer = match mode {
+            ReadMode::Slice => format!(
+                "[lines {offset}-{} of {total_lines}",
+                offset + collected.len().saturating_sub(1)
+            ),
+            ReadMode::Indentation => format!("[{total_lines} lines in file"),
+        };
+        if truncated {
+            footer.push_str(&format!("; output cut at {max_bytes} bytes"));
+            if matches!(mode, ReadMode::Slice) {
+                footer.push_str(&format!(
+                    ", continue with offset {}",
+                    offset + collected.len()
+                ));
+            }
+        }
+        footer.push(']');
+        collected.push(footer);
+
+        if let Ok(contents) = tokio::fs::read(&path).await {
+            session
+                .services
+                .file_reads
+                .lock()
+                .await
+                .record_read(&path, &contents);
+        }
+
         Ok(ToolOutput::Function {
             content: collected.join("\n"),
             content_items: None,
@@ -475,6 +531,47 @@ fn format_line(bytes: &[u8]) -> String {
     }
 }
 
+/// Drops trailing lines until the joined output fits in `max_bytes`, always
+/// keeping the first line. Returns whether any line was dropped.
+fn truncate_to_bytes(lines: &mut Vec<String>, max_bytes: usize) -> bool {
+    let mut used = 0usize;
+    let keep = lines
+        .iter()
+        .position(|line| {
+            used += line.len() + 1;
+            used > max_bytes + 1
+        })
+        .unwrap_or(lines.len())
+        .max(1);
+    let truncated = keep < lines.len();
+    lines.truncate(keep);
+    truncated
+}
+
+/// Number of lines in the file, counting a final line without a newline.
+async fn count_lines(path: &Path) -> Result<usize, FunctionCallError> {
+    let mut file = File::open(path)
+        .await
+        .map_err(|err| FunctionCallError::RespondToModel(format!("failed to read file: {err}")))?;
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut lines = 0usize;
+    let mut last = None;
+    loop {
+        let read = file.read(&mut buffer).await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to read file: {err}"))
+        })?;
+        if read == 0 {
+            break;
+        }
+        lines += buffer[..read].iter().filter(|byte| **byte == b'\n').count();
+        last = Some(buffer[read - 1]);
+    }
+    if last.is_some_and(|byte| byte != b'\n') {
+        lines += 1;
+    }
+    Ok(lines)
+}
+
 fn trim_empty_lines(out: &mut VecDeque<&LineRecord>) {
     while matches!(out.front(), Some(line) if line.raw.trim().is_empty()) {
         out.pop_front();
@@ -513,6 +610,10 @@ mod defaults {
         2000
     }
 
+    pub fn max_bytes() -> usize {
+        64 * 1024
+    }
+
     pub fn max_levels() -> usize {
         0
     }
@@ -551,6 +652,40 @@ gamma
         Ok(())
     }
 
+    #[tokio::test]
+    async fn counts_lines_with_and_without_trailing_newline() -> anyhow::Result<()> {
+        let mut temp = NamedTempFile::new()?;
+        use std::io::Write as _;
+        write!(temp, "alpha\nbeta\ngamma")?;
+        assert_eq!(count_lines(temp.path()).await?, 3);
+        writeln!(temp)?;
+        assert_eq!(count_lines(temp.path()).await?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn truncates_to_byte_budget_keeping_first_line() {
+        let lines = || {
+            vec![
+                "L1: alpha".to_string(),
+                "L2: beta".to_string(),
+                "L3: gamma".to_string(),
+            ]
+        };
+
+        let mut fits = lines();
+        assert!(!truncate_to_bytes(&mut fits, 28));
+        assert_eq!(fits, lines());
+
+        let mut cut = lines();
+        assert!(truncate_to_bytes(&mut cut, 27));
+        assert_eq!(cut, vec!["L1: alpha".to_string(), "L2: beta".to_string()]);
+
+        let mut first_only = lines();
+        assert!(truncate_to_bytes(&mut first_only, 1));
+        assert_eq!(first_only, vec!["L1: alpha".to_string()]);
+    }
+
     #[tokio::test]
     async fn errors_when_offset_exceeds_length() -> anyhow::Result<()> {
         let mut temp = NamedTempFile::new()?;
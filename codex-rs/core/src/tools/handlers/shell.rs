@@ -1,6 +1,8 @@
 use async_trait::async_trait;
 use codex_protocol::models::ShellCommandToolCallParams;
+use codex_protocol::models::ShellNetworkAccess;
 use codex_protocol::models::ShellToolCallParams;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use super::legacy_edit;
@@ -8,19 +10,29 @@ use crate::apply_patch;
 use crate::apply_patch::InternalApplyPatchInvocation;
 use crate::apply_patch::convert_apply_patch_to_protocol;
 use crate::codex::TurnContext;
+use crate::config::Config;
 use crate::exec::ExecParams;
 use crate::exec_env::create_env;
 use crate::exec_policy::create_approval_requirement_for_command;
 use crate::function_tool::FunctionCallError;
 use crate::is_safe_command::is_known_safe_command;
+use crate::persistent_shell::PersistentShellCommand;
 use crate::protocol::ExecCommandSource;
 use crate::sandboxing::SandboxPermissions;
+use crate::shell::BashShell;
+use crate::shell::Shell;
+use crate::shell::ShellType;
+use crate::shell::ZshShell;
+use crate::shell::detect_shell_type;
+use crate::tools::ShellOutputLimits;
 use crate::tools::coco_subagent;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
 use crate::tools::events::ToolEmitter;
 use crate::tools::events::ToolEventCtx;
+use crate::tools::events::ToolEventStage;
+use crate::tools::file_reads::shell_read_paths;
 use crate::tools::orchestrator::ToolOrchestrator;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
@@ -29,12 +41,53 @@ use crate::tools::runtimes::apply_patch::ApplyPatchRuntime;
 use crate::tools::runtimes::shell::ShellRequest;
 use crate::tools::runtimes::shell::ShellRuntime;
 use crate::tools::sandboxing::ToolCtx;
+use crate::unified_exec::ExecCommandRequest;
+use crate::unified_exec::MIN_YIELD_TIME_MS;
+use crate::unified_exec::UnifiedExecContext;
 use codex_apply_patch::ApplyPatchAction;
+use codex_utils_pty::TerminalSize;
 
 pub struct ShellHandler;
 
 pub struct ShellCommandHandler;
 
+/// How a call's command is run and its output reported, from the call's
+/// arguments and the config.
+#[derive(Clone, Debug, Default)]
+struct ShellCallOptions {
+    output_limits: ShellOutputLimits,
+    /// The terminal to run on, if any: the call's `tty` flag wins over
+    /// `shell_pty.enabled`.
+    pty: Option<TerminalSize>,
+    /// Whether the call named its own `workdir`, which a persistent shell
+    /// has to `cd` into.
+    explicit_workdir: bool,
+    /// Whether the call asked for `network: "off"`.
+    network_off: bool,
+}
+
+impl ShellCallOptions {
+    fn new(
+        max_output_bytes: Option<usize>,
+        max_output_lines: Option<usize>,
+        tty: Option<bool>,
+        network: Option<ShellNetworkAccess>,
+        workdir: Option<&String>,
+        config: &Config,
+    ) -> Self {
+        let pty = &config.shell_pty;
+        Self {
+            output_limits: ShellOutputLimits::resolve(max_output_bytes, max_output_lines, config),
+            pty: tty.unwrap_or(pty.enabled).then_some(TerminalSize {
+                rows: pty.rows,
+                cols: pty.cols,
+            }),
+            explicit_workdir: workdir.is_some(),
+            network_off: network == Some(ShellNetworkAccess::Off),
+        }
+    }
+}
+
 impl ShellHandler {
     fn to_exec_params(params: ShellToolCallParams, turn_context: &TurnContext) -> ExecParams {
         ExecParams {
@@ -96,6 +149,22 @@ impl ToolHandler for ShellHandler {
         }
     }
 
+    fn is_read_only(&self, invocation: &ToolInvocation) -> bool {
+        match &invocation.payload {
+            ToolPayload::Function { arguments } => {
+                serde_json::from_str::<ShellToolCallParams>(arguments).is_ok_and(|params| {
+                    is_read_only_shell_call(
+                        &params.command,
+                        params.with_escalated_permissions,
+                        params.run_in_background,
+                    )
+                })
+            }
+            ToolPayload::LocalShell { params } => is_known_safe_command(&params.command),
+            _ => false,
+        }
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
         let ToolInvocation {
             session,
@@ -114,7 +183,24 @@ impl ToolHandler for ShellHandler {
                             "failed to parse function arguments: {e:?}"
                         ))
                     })?;
+                let run_in_background = params.run_in_background.unwrap_or(false);
+                let options = ShellCallOptions::new(
+                    params.max_output_bytes,
+                    params.max_output_lines,
+                    params.tty,
+                    params.network,
+                    params.workdir.as_ref(),
+                    &turn.client.config(),
+                );
                 let exec_params = Self::to_exec_params(params, turn.as_ref());
+                if run_in_background {
+                    if options.network_off {
+                        return Err(FunctionCallError::RespondToModel(
+                            "network \"off\" is not supported for background jobs; run the command in the foreground".to_string(),
+                        ));
+                    }
+                    return Self::start_background_job(exec_params, session, turn, call_id).await;
+                }
                 Self::run_exec_like(
                     tool_name.as_str(),
                     exec_params,
@@ -123,10 +209,19 @@ impl ToolHandler for ShellHandler {
                     tracker,
                     call_id,
                     false,
+                    options,
                 )
                 .await
             }
             ToolPayload::LocalShell { params } => {
+                let options = ShellCallOptions::new(
+                    params.max_output_bytes,
+                    params.max_output_lines,
+                    params.tty,
+                    params.network,
+                    params.workdir.as_ref(),
+                    &turn.client.config(),
+                );
                 let exec_params = Self::to_exec_params(params, turn.as_ref());
                 Self::run_exec_like(
                     tool_name.as_str(),
@@ -136,6 +231,7 @@ impl ToolHandler for ShellHandler {
                     tracker,
                     call_id,
                     false,
+                    options,
                 )
                 .await
             }
@@ -156,6 +252,19 @@ impl ToolHandler for ShellCommandHandler {
         matches!(payload, ToolPayload::Function { .. })
     }
 
+    fn is_read_only(&self, invocation: &ToolInvocation) -> bool {
+        let ToolPayload::Function { arguments } = &invocation.payload else {
+            return false;
+        };
+        serde_json::from_str::<ShellCommandToolCallParams>(arguments).is_ok_and(|params| {
+            let command = invocation
+                .session
+                .user_shell()
+                .derive_exec_args(&params.command, true);
+            is_read_only_shell_call(&command, params.with_escalated_permissions, None)
+        })
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
         let ToolInvocation {
             session,
@@ -175,6 +284,14 @@ impl ToolHandler for ShellCommandHandler {
         let params: ShellCommandToolCallParams = serde_json::from_str(&arguments).map_err(|e| {
             FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e:?}"))
         })?;
+        let options = ShellCallOptions::new(
+            params.max_output_bytes,
+            params.max_output_lines,
+            params.tty,
+            params.network,
+            params.workdir.as_ref(),
+            &turn.client.config(),
+        );
         let exec_params = Self::to_exec_params(params, session.as_ref(), turn.as_ref());
         ShellHandler::run_exec_like(
             tool_name.as_str(),
@@ -184,12 +301,83 @@ impl ToolHandler for ShellCommandHandler {
             tracker,
             call_id,
             true,
+            options,
         )
         .await
     }
 }
 
 impl ShellHandler {
+    /// Starts the command as a background job on the unified exec session
+    /// manager and returns its job id right away; `check_job` and `kill_job`
+    /// take it from there.
+    async fn start_background_job(
+        exec_params: ExecParams,
+        session: Arc<crate::codex::Session>,
+        turn: Arc<TurnContext>,
+        call_id: String,
+    ) -> Result<ToolOutput, FunctionCallError> {
+        if exec_params.with_escalated_permissions.unwrap_or(false)
+            && !matches!(
+                turn.approval_policy,
+                codex_protocol::protocol::AskForApproval::OnRequest
+            )
+        {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "approval policy is {policy:?}; reject command — you should not ask for escalated permissions if the approval policy is {policy:?}",
+                policy = turn.approval_policy
+            )));
+        }
+
+        let context = UnifiedExecContext::new(Arc::clone(&session), Arc::clone(&turn), call_id);
+        let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &context.call_id, None);
+        ToolEmitter::unified_exec(
+            &exec_params.command,
+            exec_params.cwd.clone(),
+            ExecCommandSource::UnifiedExecStartup,
+            None,
+        )
+        .emit(event_ctx, ToolEventStage::Begin)
+        .await;
+
+        let response = session
+            .services
+            .unified_exec_manager
+            .exec_command(
+                ExecCommandRequest {
+                    command: exec_params.command,
+                    yield_time_ms: MIN_YIELD_TIME_MS,
+                    max_output_tokens: None,
+                    workdir: Some(exec_params.cwd),
+                    with_escalated_permissions: exec_params.with_escalated_permissions,
+                    justification: exec_params.justification,
+                },
+                &context,
+            )
+            .await
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!("failed to start background job: {err}"))
+            })?;
+
+        let content = match (response.session_id, response.exit_code) {
+            (Some(job_id), _) => format!(
+                "Started background job {job_id}. Use check_job to read its output and kill_job to stop it.\nOutput so far:\n{}",
+                response.output
+            ),
+            (None, exit_code) => format!(
+                "Command finished before it was moved to the background (exit code {}).\nOutput:\n{}",
+                exit_code.unwrap_or(-1),
+                response.output
+            ),
+        };
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
     async fn run_exec_like(
         tool_name: &str,
         exec_params: ExecParams,
@@ -198,6 +386,7 @@ impl ShellHandler {
         tracker: crate::tools::context::SharedTurnDiffTracker,
         call_id: String,
         freeform: bool,
+        options: ShellCallOptions,
     ) -> Result<ToolOutput, FunctionCallError> {
         // Approval policy guard for explicit escalation in non-OnRequest modes.
         if exec_params.with_escalated_permissions.unwrap_or(false)
@@ -212,14 +401,15 @@ impl ShellHandler {
             )));
         }
 
-        if let Some(output) = coco_subagent::maybe_run_coco_command(
-            &exec_params,
-            &session,
-            &turn,
-            &call_id,
-            is_user_shell_command,
-        )
-        .await?
+        if options.network_off && exec_params.with_escalated_permissions.unwrap_or(false) {
+            return Err(FunctionCallError::RespondToModel(
+                "network \"off\" cannot be combined with with_escalated_permissions; the command has to run in the sandbox".to_string(),
+            ));
+        }
+
+        if let Some(output) =
+            coco_subagent::maybe_run_coco_command(&exec_params, &session, &turn, &call_id, freeform)
+                .await?
         {
             return Ok(output);
         }
@@ -229,6 +419,13 @@ impl ShellHandler {
             &exec_params.command,
             &exec_params.cwd,
         ) {
+            codex_apply_patch::MaybeApplyPatchVerified::Body(_)
+                if !turn.tools_config.allows_tool("apply_patch") =>
+            {
+                return Err(FunctionCallError::RespondToModel(
+                    "apply_patch is disabled for this session".to_string(),
+                ));
+            }
             codex_apply_patch::MaybeApplyPatchVerified::Body(changes) => {
                 return Self::execute_apply_patch_action(
                     tool_name,
@@ -255,9 +452,14 @@ impl ShellHandler {
             }
         }
 
-        match legacy_edit::maybe_build_apply_patch_action(&exec_params.command, &exec_params.cwd) {
+        match legacy_edit::maybe_build_apply_patch_action(
+            &exec_params.command,
+            &exec_params.cwd,
+            &legacy_edit::EditPolicy::from_config(&turn.client.config()),
+        ) {
             Ok(Some(action)) => {
-                return Self::execute_apply_patch_action(
+                let permissions = legacy_edit::PreservedPermissions::capture(&action);
+                let result = Self::execute_apply_patch_action(
                     tool_name,
                     action,
                     exec_params.timeout_ms,
@@ -267,6 +469,12 @@ impl ShellHandler {
                     &call_id,
                 )
                 .await;
+                if result.is_ok()
+                    && let Err(err) = permissions.restore()
+                {
+                    tracing::warn!("failed to restore file permissions after edit: {err}");
+                }
+                return result;
             }
             Ok(None) => { /* proceed with shell */ }
             Err(err) => {
@@ -280,10 +488,22 @@ impl ShellHandler {
             exec_params.cwd.clone(),
             source,
             freeform,
-        );
+        )
+        .with_output_limits(options.output_limits);
         let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
         emitter.begin(event_ctx).await;
 
+        let sandbox_policy = options
+            .network_off
+            .then(|| turn.sandbox_policy.without_network_access());
+        let persistent = if turn.client.config().persistent_shell
+            && options.pty.is_none()
+            && sandbox_policy.is_none()
+        {
+            persistent_shell_command(&exec_params, options.explicit_workdir, session.user_shell())
+        } else {
+            None
+        };
         let req = ShellRequest {
             command: exec_params.command.clone(),
             cwd: exec_params.cwd.clone(),
@@ -295,9 +515,12 @@ impl ShellHandler {
                 &turn.exec_policy,
                 &exec_params.command,
                 turn.approval_policy,
-                &turn.sandbox_policy,
+                sandbox_policy.as_ref().unwrap_or(&turn.sandbox_policy),
                 SandboxPermissions::from(exec_params.with_escalated_permissions.unwrap_or(false)),
             ),
+            pty: options.pty,
+            persistent,
+            sandbox_policy,
         };
         let mut orchestrator = ToolOrchestrator::new();
         let mut runtime = ShellRuntime::new();
@@ -312,6 +535,19 @@ impl ShellHandler {
             .await;
         let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
         let content = emitter.finish(event_ctx, out).await?;
+        // A persistent shell may be sitting in another directory, so relative
+        // paths are only trusted when the call pinned its workdir.
+        let cwd = (req.persistent.is_none() || options.explicit_workdir)
+            .then_some(exec_params.cwd.as_path());
+        let read_paths = shell_read_paths(&exec_params.command, cwd);
+        if !read_paths.is_empty() {
+            let mut file_reads = session.services.file_reads.lock().await;
+            for path in read_paths {
+                if let Ok(contents) = tokio::fs::read(&path).await {
+                    file_reads.record_read(&path, &contents);
+                }
+            }
+        }
         Ok(ToolOutput::Function {
             content,
             content_items: None,
@@ -377,15 +613,59 @@ impl ShellHandler {
     }
 }
 
+/// Whether a shell call may run alongside other read-only tool calls: a
+/// known-safe command that neither asks for escalation nor starts a
+/// background job.
+fn is_read_only_shell_call(
+    command: &[String],
+    with_escalated_permissions: Option<bool>,
+    run_in_background: Option<bool>,
+) -> bool {
+    !with_escalated_permissions.unwrap_or(false)
+        && !run_in_background.unwrap_or(false)
+        && is_known_safe_command(command)
+}
+
+/// The command to run in the persistent shell, or `None` when the user's
+/// shell is not bash or zsh. A `bash -lc <script>`-style command runs its
+/// script directly; any other argv is quoted into one.
+fn persistent_shell_command(
+    exec_params: &ExecParams,
+    explicit_workdir: bool,
+    user_shell: &Shell,
+) -> Option<PersistentShellCommand> {
+    let (Shell::Bash(BashShell { shell_path }) | Shell::Zsh(ZshShell { shell_path })) = user_shell
+    else {
+        return None;
+    };
+    let script = match exec_params.command.as_slice() {
+        [program, flag, script]
+            if matches!(flag.as_str(), "-c" | "-lc") && is_posix_shell(program) =>
+        {
+            script.clone()
+        }
+        command => shlex::try_join(command.iter().map(String::as_str)).ok()?,
+    };
+    Some(PersistentShellCommand {
+        shell: vec![shell_path.to_string_lossy().to_string(), "-l".to_string()],
+        script,
+        workdir: explicit_workdir.then(|| exec_params.cwd.clone()),
+    })
+}
+
+fn is_posix_shell(program: &str) -> bool {
+    let path = PathBuf::from(program);
+    matches!(
+        detect_shell_type(&path),
+        Some(ShellType::Bash | ShellType::Zsh)
+    ) || path.file_name().is_some_and(|name| name == "sh")
+}
+
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
-
-    use crate::is_safe_command::is_known_safe_command;
-    use crate::shell::BashShell;
+    use super::*;
     use crate::shell::PowerShellConfig;
-    use crate::shell::Shell;
-    use crate::shell::ZshShell;
+    use pretty_assertions::assert_eq;
 
     /// The logic for is_known_safe_command() has heuristics for known shells,
     /// so we must ensure the commands generated by [ShellCommandHandler] can be
@@ -408,6 +688,60 @@ mod tests {
         assert_safe(&powershell, "ls -Name");
     }
 
+    #[test]
+    fn only_plain_known_safe_shell_calls_are_read_only() {
+        let cat = vec!["cat".to_string(), "README.md".to_string()];
+        assert!(is_read_only_shell_call(&cat, None, None));
+        assert!(!is_read_only_shell_call(&cat, Some(true), None));
+        assert!(!is_read_only_shell_call(&cat, None, Some(true)));
+
+        let rm = vec!["rm".to_string(), "README.md".to_string()];
+        assert!(!is_read_only_shell_call(&rm, None, None));
+    }
+
+    #[test]
+    fn persistent_shell_command_unwraps_shell_scripts_and_quotes_argv() {
+        let bash_shell = Shell::Bash(BashShell {
+            shell_path: PathBuf::from("/bin/bash"),
+        });
+        let exec_params = |command: &[&str]| ExecParams {
+            command: command.iter().map(ToString::to_string).collect(),
+            cwd: PathBuf::from("/repo"),
+            timeout_ms: None,
+            env: Default::default(),
+            with_escalated_permissions: None,
+            justification: None,
+            arg0: None,
+        };
+
+        let command = persistent_shell_command(
+            &exec_params(&["bash", "-lc", "cd src && ls"]),
+            false,
+            &bash_shell,
+        )
+        .expect("bash supports a persistent shell");
+        assert_eq!(
+            command.shell,
+            vec!["/bin/bash".to_string(), "-l".to_string()]
+        );
+        assert_eq!(command.script, "cd src && ls");
+        assert_eq!(command.workdir, None);
+
+        let command = persistent_shell_command(
+            &exec_params(&["grep", "-n", "two words", "file.txt"]),
+            true,
+            &bash_shell,
+        )
+        .expect("bash supports a persistent shell");
+        assert_eq!(command.script, "grep -n 'two words' file.txt");
+        assert_eq!(command.workdir, Some(PathBuf::from("/repo")));
+
+        let powershell = Shell::PowerShell(PowerShellConfig {
+            shell_path: PathBuf::from("pwsh.exe"),
+        });
+        assert!(persistent_shell_command(&exec_params(&["ls"]), false, &powershell).is_none());
+    }
+
     fn assert_safe(shell: &Shell, command: &str) {
         assert!(is_known_safe_command(
             &shell.derive_exec_args(command, /* use_login_shell */ true)
@@ -0,0 +1,200 @@
+use async_trait::async_trait;
+use codex_utils_string::take_bytes_at_char_boundary;
+use serde::Serialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct TurnDiffHandler;
+
+/// Longer diffs are cut off; `files` still lists every changed file.
+const MAX_DIFF_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, PartialEq, Serialize)]
+struct TurnDiff {
+    files: Vec<FileDiffStat>,
+    diff: String,
+    truncated: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct FileDiffStat {
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    original_path: Option<String>,
+    status: FileDiffStatus,
+    additions: u64,
+    deletions: u64,
+    binary: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum FileDiffStatus {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+}
+
+#[async_trait]
+impl ToolHandler for TurnDiffHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            payload, tracker, ..
+        } = invocation;
+
+        if !matches!(payload, ToolPayload::Function { .. }) {
+            return Err(FunctionCallError::RespondToModel(
+                "get_turn_diff handler received unsupported payload".to_string(),
+            ));
+        }
+
+        let unified_diff = {
+            let mut tracker = tracker.lock().await;
+            tracker.get_unified_diff().map_err(|err| {
+                FunctionCallError::RespondToModel(format!("failed to compute turn diff: {err}"))
+            })?
+        };
+        let Some(unified_diff) = unified_diff else {
+            return Ok(ToolOutput::Function {
+                content: "No files have been changed in this turn.".to_string(),
+                content_items: None,
+                success: Some(true),
+            });
+        };
+
+        let files = file_stats(&unified_diff);
+        let truncated = unified_diff.len() > MAX_DIFF_BYTES;
+        let diff = take_bytes_at_char_boundary(&unified_diff, MAX_DIFF_BYTES).to_string();
+        let content = serde_json::to_string_pretty(&TurnDiff {
+            files,
+            diff,
+            truncated,
+        })
+        .map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to serialize turn diff: {err}"))
+        })?;
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+/// Summarizes each `diff --git` section of the tracker's unified diff.
+fn file_stats(unified_diff: &str) -> Vec<FileDiffStat> {
+    let mut files: Vec<FileDiffStat> = Vec::new();
+    let mut in_hunk = false;
+    for line in unified_diff.lines() {
+        if let Some(paths) = line.strip_prefix("diff --git a/") {
+            let (left, right) = paths.rsplit_once(" b/").unwrap_or((paths, paths));
+            files.push(FileDiffStat {
+                path: right.to_string(),
+                original_path: (left != right).then(|| left.to_string()),
+                status: if left == right {
+                    FileDiffStatus::Modified
+                } else {
+                    FileDiffStatus::Renamed
+                },
+                additions: 0,
+                deletions: 0,
+                binary: false,
+            });
+            in_hunk = false;
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+        if line.starts_with("@@") {
+            in_hunk = true;
+        } else if !in_hunk {
+            if line.starts_with("new file mode") {
+                file.status = FileDiffStatus::Added;
+            } else if line.starts_with("deleted file mode") {
+                file.status = FileDiffStatus::Deleted;
+            } else if line.starts_with("Binary files") {
+                file.binary = true;
+            }
+        } else if line.starts_with('+') {
+            file.additions += 1;
+        } else if line.starts_with('-') {
+            file.deletions += 1;
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn file_stats_counts_lines_per_file() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111..2222
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,2 +1,3 @@
+-old
++new
++added
+ same
+diff --git a/notes.txt b/notes.txt
+new file mode 100644
+index 0000..3333
+--- /dev/null
++++ b/notes.txt
+@@ -0,0 +1 @@
++--- not a header
+diff --git a/a.rs b/b.rs
+index 4444..5555
+--- a/a.rs
++++ b/b.rs
+@@ -1 +1 @@
+-x
++y
+";
+        assert_eq!(
+            file_stats(diff),
+            vec![
+                FileDiffStat {
+                    path: "src/lib.rs".to_string(),
+                    original_path: None,
+                    status: FileDiffStatus::Modified,
+                    additions: 2,
+                    deletions: 1,
+                    binary: false,
+                },
+                FileDiffStat {
+                    path: "notes.txt".to_string(),
+                    original_path: None,
+                    status: FileDiffStatus::Added,
+                    additions: 1,
+                    deletions: 0,
+                    binary: false,
+                },
+                FileDiffStat {
+                    path: "b.rs".to_string(),
+                    original_path: Some("a.rs".to_string()),
+                    status: FileDiffStatus::Renamed,
+                    additions: 1,
+                    deletions: 1,
+                    binary: false,
+                },
+            ]
+        );
+    }
+}
@@ -220,6 +220,7 @@ impl ToolHandler for UnifiedExecHandler {
                 call_id: response.event_call_id.clone(),
                 stream: ExecOutputStream::Stdout,
                 chunk: response.output.as_bytes().to_vec(),
+                sub_agent: None,
             };
             session
                 .send_event(turn.as_ref(), EventMsg::ExecCommandOutputDelta(delta))
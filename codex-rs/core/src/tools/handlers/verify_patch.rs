@@ -0,0 +1,206 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
+use async_trait::async_trait;
+use codex_apply_patch::ChunkCheck;
+use codex_apply_patch::Hunk;
+use codex_apply_patch::MaybeApplyPatchVerified;
+use codex_apply_patch::NearestLines;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::spec::ApplyPatchToolArgs;
+
+pub struct VerifyPatchHandler;
+
+#[async_trait]
+impl ToolHandler for VerifyPatchHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { payload, turn, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "verify_patch handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: ApplyPatchToolArgs = serde_json::from_str(&arguments).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to parse function arguments: {err:?}"
+            ))
+        })?;
+
+        let cwd = turn.cwd.clone();
+        let (content, applies) =
+            tokio::task::spawn_blocking(move || verify_patch(&cwd, &args.input))
+                .await
+                .map_err(|err| {
+                    FunctionCallError::RespondToModel(format!("verify_patch failed: {err}"))
+                })?
+                .map_err(FunctionCallError::RespondToModel)?;
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(applies),
+        })
+    }
+}
+
+/// Checks `patch` against the files under `cwd` without writing anything,
+/// returning a per-hunk report and whether `apply_patch` would accept it.
+fn verify_patch(cwd: &Path, patch: &str) -> Result<(String, bool), String> {
+    let hunks = codex_apply_patch::parse_patch(patch)
+        .map_err(|err| format!("invalid patch: {err}"))?
+        .hunks;
+    let command = vec!["apply_patch".to_string(), patch.to_string()];
+    let (mut report, applies) =
+        match codex_apply_patch::maybe_parse_apply_patch_verified(&command, cwd) {
+            MaybeApplyPatchVerified::Body(_) => ("Patch would apply cleanly.\n".to_string(), true),
+            MaybeApplyPatchVerified::CorrectnessError(err) => {
+                (format!("Patch would not apply: {err}\n"), false)
+            }
+            MaybeApplyPatchVerified::ShellParseError(err) => {
+                return Err(format!("invalid patch: {err:?}"));
+            }
+            MaybeApplyPatchVerified::NotApplyPatch => {
+                return Err(
+                    "verify_patch expects a patch starting with `*** Begin Patch`".to_string(),
+                );
+            }
+        };
+
+    for hunk in &hunks {
+        let resolved = hunk.resolve_path(cwd);
+        match hunk {
+            Hunk::AddFile { path, .. } => {
+                let note = if resolved.exists() {
+                    "would replace the existing file"
+                } else {
+                    "would create"
+                };
+                let _ = writeln!(report, "A {}: {note}", path.display());
+            }
+            Hunk::DeleteFile { path } => {
+                let note = if resolved.is_file() {
+                    "would delete"
+                } else {
+                    "file not found"
+                };
+                let _ = writeln!(report, "D {}: {note}", path.display());
+            }
+            Hunk::UpdateFile {
+                path,
+                move_path,
+                chunks,
+            } => {
+                let target = match move_path {
+                    Some(move_path) => format!("{} -> {}", path.display(), move_path.display()),
+                    None => path.display().to_string(),
+                };
+                match codex_apply_patch::verify_update_chunks(&resolved, chunks) {
+                    Ok(checks) => {
+                        let _ = writeln!(report, "M {target}");
+                        for (index, check) in checks.iter().enumerate() {
+                            write_chunk_check(&mut report, index + 1, check);
+                        }
+                    }
+                    Err(err) => {
+                        let _ = writeln!(report, "M {target}: {err}");
+                    }
+                }
+            }
+        }
+    }
+
+    Ok((report.trim_end().to_string(), applies))
+}
+
+fn write_chunk_check(report: &mut String, number: usize, check: &ChunkCheck) {
+    let nearest = match check {
+        ChunkCheck::Applies { line } => {
+            let _ = writeln!(report, "  hunk {number}: would apply at line {line}");
+            return;
+        }
+        ChunkCheck::ContextNotFound { context, nearest } => {
+            let _ = writeln!(
+                report,
+                "  hunk {number}: context mismatch, `@@ {context}` not found"
+            );
+            nearest
+        }
+        ChunkCheck::LinesNotFound { nearest } => {
+            let _ = writeln!(
+                report,
+                "  hunk {number}: context mismatch, expected lines not found"
+            );
+            nearest
+        }
+    };
+    match nearest {
+        Some(NearestLines { line, lines }) => {
+            let _ = writeln!(report, "    nearest lines in the file:");
+            for (offset, text) in lines.iter().enumerate() {
+                let _ = writeln!(report, "    {:>5} | {text}", line + offset);
+            }
+        }
+        None => {
+            let _ = writeln!(report, "    no similar lines in the file");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn verify_patch_reports_hunks_without_writing() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let original = "fn main() {\n    let x = 1;\n    println!(\"{x}\");\n}\n";
+        fs::write(root.join("main.rs"), original).unwrap();
+        let patch = "*** Begin Patch\n*** Add File: new.txt\n+hello\n*** Update File: main.rs\n@@\n-    let x = 1;\n+    let x = 2;\n@@\n-    let y = 1;\n+    let y = 2;\n*** End Patch";
+
+        let (report, applies) = verify_patch(root, patch).unwrap();
+        assert!(!applies);
+        assert_eq!(
+            report
+                .lines()
+                .skip_while(|line| !line.starts_with("A "))
+                .collect::<Vec<_>>(),
+            vec![
+                "A new.txt: would create",
+                "M main.rs",
+                "  hunk 1: would apply at line 2",
+                "  hunk 2: context mismatch, expected lines not found",
+                "    nearest lines in the file:",
+                "        2 |     let x = 1;",
+            ]
+        );
+        assert_eq!(fs::read_to_string(root.join("main.rs")).unwrap(), original);
+        assert!(!root.join("new.txt").exists());
+
+        let patch = "*** Begin Patch\n*** Update File: main.rs\n@@\n-    let x = 1;\n+    let x = 2;\n*** End Patch";
+        let (report, applies) = verify_patch(root, patch).unwrap();
+        assert!(applies);
+        assert_eq!(
+            report,
+            "Patch would apply cleanly.\nM main.rs\n  hunk 1: would apply at line 2"
+        );
+    }
+}
@@ -1,21 +1,27 @@
 pub(crate) mod coco_subagent;
 pub mod context;
 pub mod events;
+pub(crate) mod file_reads;
 pub(crate) mod handlers;
 pub mod orchestrator;
 pub mod parallel;
+pub(crate) mod quota;
 pub mod registry;
 pub mod router;
 pub mod runtimes;
 pub mod sandboxing;
 pub mod spec;
+pub(crate) mod usage;
 
+use crate::config::Config;
 use crate::exec::ExecToolCallOutput;
+use crate::exec::StreamOutput;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::formatted_truncate_text;
 use crate::truncate::truncate_text;
 pub use router::ToolRouter;
 use serde::Serialize;
+use std::path::PathBuf;
 
 // Telemetry preview limits: keep log events smaller than model budgets.
 pub(crate) const TELEMETRY_PREVIEW_MAX_BYTES: usize = 2 * 1024; // 2 KiB
@@ -111,3 +117,190 @@ pub fn format_exec_output_str(
     // Truncate for model consumption before serialization.
     formatted_truncate_text(&body, truncation_policy)
 }
+
+/// Caps on the shell output returned to the model, applied before the
+/// turn's truncation policy. Whatever is cut is still available: the full
+/// output is saved to a file whose path is named in the notice.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct ShellOutputLimits {
+    pub max_bytes: Option<usize>,
+    pub max_lines: Option<usize>,
+}
+
+impl ShellOutputLimits {
+    /// Per-call limits take precedence over the configured defaults.
+    pub(crate) fn resolve(
+        max_bytes: Option<usize>,
+        max_lines: Option<usize>,
+        config: &Config,
+    ) -> Self {
+        Self {
+            max_bytes: max_bytes.or(config.shell_output_max_bytes),
+            max_lines: max_lines.or(config.shell_output_max_lines),
+        }
+    }
+
+    /// Returns a copy of `exec_output` whose aggregated output keeps the head
+    /// and tail within these limits, or `None` when the output already fits.
+    pub(crate) fn apply(
+        &self,
+        exec_output: &ExecToolCallOutput,
+        call_id: &str,
+    ) -> Option<ExecToolCallOutput> {
+        let text = exec_output.aggregated_output.text.as_str();
+        let (head, tail) = self.head_and_tail(text)?;
+
+        let omitted = &text[head.len()..text.len() - tail.len()];
+        let saved = match save_full_shell_output(call_id, text) {
+            Ok(path) => format!("full output saved to {}", path.display()),
+            Err(err) => format!("full output could not be saved: {err}"),
+        };
+        let notice = format!(
+            "[... {} lines ({} bytes) omitted; {saved} ...]",
+            omitted.lines().count(),
+            omitted.len()
+        );
+        let separator = if head.is_empty() || head.ends_with('\n') {
+            ""
+        } else {
+            "\n"
+        };
+
+        let mut limited = exec_output.clone();
+        limited.aggregated_output = StreamOutput {
+            text: format!("{head}{separator}{notice}\n{tail}"),
+            truncated_after_lines: exec_output.aggregated_output.truncated_after_lines,
+        };
+        Some(limited)
+    }
+
+    /// Splits `text` into a head and tail that together fit the limits,
+    /// giving each half of the budget. Returns `None` when nothing is cut.
+    fn head_and_tail<'a>(&self, text: &'a str) -> Option<(&'a str, &'a str)> {
+        let mut head = text;
+        let mut tail = "";
+
+        if let Some(max_lines) = self.max_lines {
+            let lines: Vec<&str> = text.split_inclusive('\n').collect();
+            if lines.len() > max_lines {
+                let head_lines = max_lines.div_ceil(2);
+                let tail_lines = max_lines - head_lines;
+                let head_len: usize = lines[..head_lines].iter().map(|line| line.len()).sum();
+                let tail_len: usize = lines[lines.len() - tail_lines..]
+                    .iter()
+                    .map(|line| line.len())
+                    .sum();
+                head = &text[..head_len];
+                tail = &text[text.len() - tail_len..];
+            }
+        }
+
+        if let Some(max_bytes) = self.max_bytes
+            && head.len() + tail.len() > max_bytes
+        {
+            if tail.is_empty() {
+                // Only the byte cap applies: split the whole text.
+                let mut tail_start = text.len() - max_bytes / 2;
+                while !text.is_char_boundary(tail_start) {
+                    tail_start += 1;
+                }
+                head = &text[..tail_start];
+                tail = &text[tail_start..];
+            }
+            let mut head_end = head.len().min(max_bytes.div_ceil(2));
+            while !head.is_char_boundary(head_end) {
+                head_end -= 1;
+            }
+            head = &head[..head_end];
+            let tail_budget = max_bytes - head.len();
+            if tail.len() > tail_budget {
+                let mut tail_start = tail.len() - tail_budget;
+                while !tail.is_char_boundary(tail_start) {
+                    tail_start += 1;
+                }
+                tail = &tail[tail_start..];
+            }
+        }
+
+        if head.len() + tail.len() >= text.len() {
+            None
+        } else {
+            Some((head, tail))
+        }
+    }
+}
+
+/// Writes the untruncated output of a shell call to a per-call log file.
+fn save_full_shell_output(call_id: &str, text: &str) -> std::io::Result<PathBuf> {
+    let dir = std::env::temp_dir().join("codex-shell-output");
+    std::fs::create_dir_all(&dir)?;
+    let file_name: String = call_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let path = dir.join(format!("{file_name}.log"));
+    std::fs::write(&path, text)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn exec_output(text: &str) -> ExecToolCallOutput {
+        ExecToolCallOutput {
+            exit_code: 0,
+            stdout: StreamOutput::new(text.to_string()),
+            stderr: StreamOutput::new(String::new()),
+            aggregated_output: StreamOutput::new(text.to_string()),
+            duration: Duration::from_millis(10),
+            timed_out: false,
+        }
+    }
+
+    #[test]
+    fn shell_output_limits_keep_head_and_tail_and_save_full_output() {
+        let text: String = (1..=10).map(|i| format!("line {i}\n")).collect();
+        let output = exec_output(&text);
+
+        let unlimited = ShellOutputLimits::default();
+        assert!(unlimited.apply(&output, "call-fits").is_none());
+
+        let limits = ShellOutputLimits {
+            max_bytes: None,
+            max_lines: Some(4),
+        };
+        let limited = limits
+            .apply(&output, "call-lines")
+            .expect("output exceeds max_lines");
+        let path = std::env::temp_dir()
+            .join("codex-shell-output")
+            .join("call-lines.log");
+        assert_eq!(
+            limited.aggregated_output.text,
+            format!(
+                "line 1\nline 2\n[... 6 lines (42 bytes) omitted; full output saved to {} ...]\nline 9\nline 10\n",
+                path.display()
+            )
+        );
+        assert_eq!(std::fs::read_to_string(&path).ok(), Some(text));
+
+        let limits = ShellOutputLimits {
+            max_bytes: Some(10),
+            max_lines: None,
+        };
+        let limited = limits
+            .apply(&output, "call-bytes")
+            .expect("output exceeds max_bytes");
+        assert!(limited.aggregated_output.text.starts_with("line "));
+        assert!(limited.aggregated_output.text.ends_with("e 10\n"));
+    }
+}
@@ -2,24 +2,27 @@
 Module: orchestrator
 
 Central place for approvals + sandbox selection + retry semantics. Drives a
-simple sequence for any ToolRuntime: approval → select sandbox → attempt →
-retry without sandbox on denial (no re‑approval thanks to caching).
+simple sequence for any ToolRuntime: quota check → approval → select sandbox →
+attempt → retry without sandbox on denial (no re‑approval thanks to caching).
 */
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::error::get_error_message_ui;
 use crate::exec::ExecToolCallOutput;
 use crate::sandboxing::SandboxManager;
+use crate::tools::quota::QuotaCharge;
 use crate::tools::sandboxing::ApprovalCtx;
 use crate::tools::sandboxing::ApprovalRequirement;
 use crate::tools::sandboxing::ProvidesSandboxRetryData;
 use crate::tools::sandboxing::SandboxAttempt;
+use crate::tools::sandboxing::SandboxablePreference;
 use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::default_approval_requirement;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::ReviewDecision;
+use std::time::Instant;
 
 pub(crate) struct ToolOrchestrator {
     sandbox: SandboxManager,
@@ -50,6 +53,14 @@ impl ToolOrchestrator {
         let otel_user = codex_otel::otel_event_manager::ToolDecisionSource::User;
         let otel_cfg = codex_otel::otel_event_manager::ToolDecisionSource::Config;
 
+        // 0) Quota: refuse once the turn has used up its budget for this kind
+        // of request, before bothering the user for approval.
+        let charge = tool.quota_charge(req);
+        turn_ctx
+            .tool_quota
+            .charge(&charge)
+            .map_err(ToolError::Rejected)?;
+
         // 1) Approval
         let mut already_approved = false;
 
@@ -87,7 +98,7 @@ impl ToolOrchestrator {
                 };
                 let decision = tool.start_approval_async(req, approval_ctx).await;
 
-                otel.tool_decision(otel_tn, otel_ci, decision, otel_user.clone());
+                otel.tool_decision(otel_tn, otel_ci, decision, otel_user);
 
                 match decision {
                     ReviewDecision::Denied | ReviewDecision::Abort => {
@@ -99,18 +110,68 @@ impl ToolOrchestrator {
             }
         }
 
-        // 2) First attempt under the selected sandbox.
-        let mut initial_sandbox = self
-            .sandbox
-            .select_initial(&turn_ctx.sandbox_policy, tool.sandbox_preference());
-        if tool.wants_escalated_first_attempt(req) {
-            initial_sandbox = crate::exec::SandboxType::None;
+        let started = Instant::now();
+        let result = self
+            .run_attempts(
+                tool,
+                req,
+                tool_ctx,
+                turn_ctx,
+                approval_policy,
+                already_approved,
+            )
+            .await;
+        if charge == QuotaCharge::ShellCall {
+            turn_ctx.tool_quota.record_exec_time(started.elapsed());
         }
+        result
+    }
+
+    async fn run_attempts<Rq, Out, T>(
+        &mut self,
+        tool: &mut T,
+        req: &Rq,
+        tool_ctx: &ToolCtx<'_>,
+        turn_ctx: &crate::codex::TurnContext,
+        approval_policy: AskForApproval,
+        already_approved: bool,
+    ) -> Result<Out, ToolError>
+    where
+        T: ToolRuntime<Rq, Out>,
+        Rq: ProvidesSandboxRetryData,
+    {
+        let otel = turn_ctx.client.get_otel_event_manager();
+        let otel_tn = &tool_ctx.tool_name;
+        let otel_ci = &tool_ctx.call_id;
+        let otel_user = codex_otel::otel_event_manager::ToolDecisionSource::User;
+
+        // 2) First attempt under the selected sandbox.
+        let policy_override = tool.sandbox_policy_override(req);
+        let initial_sandbox = match &policy_override {
+            // A stricter per-request policy only means something if a sandbox
+            // enforces it, so refuse rather than run the command unconfined.
+            Some(policy) => match self
+                .sandbox
+                .select_initial(policy, SandboxablePreference::Require)
+            {
+                crate::exec::SandboxType::None => {
+                    return Err(ToolError::Rejected(
+                        "no sandbox is available on this platform to enforce the requested restrictions"
+                            .to_string(),
+                    ));
+                }
+                sandbox => sandbox,
+            },
+            None if tool.wants_escalated_first_attempt(req) => crate::exec::SandboxType::None,
+            None => self
+                .sandbox
+                .select_initial(&turn_ctx.sandbox_policy, tool.sandbox_preference()),
+        };
         // Platform-specific flag gating is handled by SandboxManager::select_initial
         // via crate::safety::get_platform_sandbox().
         let initial_attempt = SandboxAttempt {
             sandbox: initial_sandbox,
-            policy: &turn_ctx.sandbox_policy,
+            policy: policy_override.as_ref().unwrap_or(&turn_ctx.sandbox_policy),
             manager: &self.sandbox,
             sandbox_cwd: &turn_ctx.cwd,
             codex_linux_sandbox_exe: turn_ctx.codex_linux_sandbox_exe.as_ref(),
@@ -122,7 +183,7 @@ impl ToolOrchestrator {
                 Ok(out)
             }
             Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output }))) => {
-                if !tool.escalate_on_failure() {
+                if !tool.escalate_on_failure() || policy_override.is_some() {
                     return Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied {
                         output,
                     })));
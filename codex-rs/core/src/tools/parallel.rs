@@ -11,6 +11,7 @@ use crate::codex::TurnContext;
 use crate::error::CodexErr;
 use crate::function_tool::FunctionCallError;
 use crate::tools::context::SharedTurnDiffTracker;
+use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolPayload;
 use crate::tools::router::ToolCall;
 use crate::tools::router::ToolRouter;
@@ -46,7 +47,18 @@ impl ToolCallRuntime {
         call: ToolCall,
         cancellation_token: CancellationToken,
     ) -> impl std::future::Future<Output = Result<ResponseInputItem, CodexErr>> {
-        let supports_parallel = self.router.tool_supports_parallel(&call.tool_name);
+        // Tools registered as parallel-safe, and individual calls that only read
+        // state (e.g. a `cat` through the shell tool), share the lock; anything
+        // else runs alone.
+        let supports_parallel = self.router.tool_supports_parallel(&call.tool_name)
+            || self.router.is_read_only_call(&ToolInvocation {
+                session: Arc::clone(&self.session),
+                turn: Arc::clone(&self.turn_context),
+                tracker: Arc::clone(&self.tracker),
+                call_id: call.call_id.clone(),
+                tool_name: call.tool_name.clone(),
+                payload: call.payload.clone(),
+            });
 
         let router = Arc::clone(&self.router);
         let session = Arc::clone(&self.session);
@@ -0,0 +1,143 @@
+//! Per-turn tool usage quotas (`tool_quotas` in config.toml), enforced by the
+//! orchestrator before a request is approved or run.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::time::Duration;
+
+use crate::config::types::ToolQuotaConfig;
+
+/// What running a single request counts against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum QuotaCharge {
+    None,
+    ShellCall,
+    EditFiles(Vec<PathBuf>),
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct TurnToolQuota {
+    limits: ToolQuotaConfig,
+    usage: Mutex<ToolUsage>,
+}
+
+#[derive(Debug, Default)]
+struct ToolUsage {
+    shell_calls: u32,
+    edited_files: HashSet<PathBuf>,
+    exec_time: Duration,
+}
+
+impl TurnToolQuota {
+    pub(crate) fn new(limits: ToolQuotaConfig) -> Self {
+        Self {
+            limits,
+            usage: Mutex::default(),
+        }
+    }
+
+    /// Records `charge` against this turn, or returns a message for the model
+    /// naming the limit it would exceed.
+    pub(crate) fn charge(&self, charge: &QuotaCharge) -> Result<(), String> {
+        let mut usage = self.usage();
+        match charge {
+            QuotaCharge::None => {}
+            QuotaCharge::ShellCall => {
+                if let Some(max) = self.limits.max_shell_calls
+                    && usage.shell_calls >= max
+                {
+                    return Err(quota_message(&format!(
+                        "this turn already ran {max} shell commands (tool_quotas.max_shell_calls)"
+                    )));
+                }
+                if let Some(max) = self.limits.max_exec_time_sec
+                    && usage.exec_time >= Duration::from_secs(max)
+                {
+                    return Err(quota_message(&format!(
+                        "this turn's shell commands already ran for {max}s in total (tool_quotas.max_exec_time_sec)"
+                    )));
+                }
+                usage.shell_calls += 1;
+            }
+            QuotaCharge::EditFiles(paths) => {
+                if let Some(max) = self.limits.max_edited_files {
+                    let new_files = paths
+                        .iter()
+                        .filter(|path| !usage.edited_files.contains(*path))
+                        .collect::<HashSet<_>>()
+                        .len();
+                    if usage.edited_files.len() + new_files > max {
+                        return Err(quota_message(&format!(
+                            "this edit would take the turn past {max} edited files (tool_quotas.max_edited_files)"
+                        )));
+                    }
+                }
+                usage.edited_files.extend(paths.iter().cloned());
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the wall time of a shell command to this turn's total.
+    pub(crate) fn record_exec_time(&self, elapsed: Duration) {
+        self.usage().exec_time += elapsed;
+    }
+
+    fn usage(&self) -> MutexGuard<'_, ToolUsage> {
+        match self.usage.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+fn quota_message(reason: &str) -> String {
+    format!(
+        "tool quota reached: {reason}. Stop calling tools and summarize what is done and what remains so the user can decide how to continue."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn charge_enforces_each_limit() {
+        let quota = TurnToolQuota::new(ToolQuotaConfig {
+            max_shell_calls: Some(2),
+            max_edited_files: Some(2),
+            max_exec_time_sec: Some(10),
+        });
+
+        assert_eq!(quota.charge(&QuotaCharge::ShellCall), Ok(()));
+        quota.record_exec_time(Duration::from_secs(10));
+        let err = quota.charge(&QuotaCharge::ShellCall).unwrap_err();
+        assert!(err.contains("tool_quotas.max_exec_time_sec"), "{err}");
+
+        let a = PathBuf::from("/repo/a.rs");
+        let b = PathBuf::from("/repo/b.rs");
+        let c = PathBuf::from("/repo/c.rs");
+        assert_eq!(
+            quota.charge(&QuotaCharge::EditFiles(vec![a.clone(), b.clone()])),
+            Ok(())
+        );
+        // Editing a file again does not count twice.
+        assert_eq!(quota.charge(&QuotaCharge::EditFiles(vec![a])), Ok(()));
+        let err = quota
+            .charge(&QuotaCharge::EditFiles(vec![b, c]))
+            .unwrap_err();
+        assert!(err.contains("tool_quotas.max_edited_files"), "{err}");
+
+        let quota = TurnToolQuota::new(ToolQuotaConfig {
+            max_shell_calls: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(quota.charge(&QuotaCharge::ShellCall), Ok(()));
+        let err = quota.charge(&QuotaCharge::ShellCall).unwrap_err();
+        assert!(err.contains("tool_quotas.max_shell_calls"), "{err}");
+        assert_eq!(quota.charge(&QuotaCharge::None), Ok(()));
+    }
+}
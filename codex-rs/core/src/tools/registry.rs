@@ -1,9 +1,14 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::Instant;
 
 use crate::client_common::tools::ToolSpec;
+use crate::codex::Session;
+use crate::codex::TurnContext;
 use crate::function_tool::FunctionCallError;
+use crate::protocol::EventMsg;
+use crate::protocol::ToolCallMetricsEvent;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -34,6 +39,13 @@ pub trait ToolHandler: Send + Sync {
         false
     }
 
+    /// Whether this particular call only reads state, so it can run alongside
+    /// other read-only calls even though the tool as a whole is not
+    /// registered as parallel-safe.
+    fn is_read_only(&self, _invocation: &ToolInvocation) -> bool {
+        false
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError>;
 }
 
@@ -67,6 +79,17 @@ impl ToolRegistry {
         let otel = invocation.turn.client.get_otel_event_manager();
         let payload_for_response = invocation.payload.clone();
         let log_payload = payload_for_response.log_payload();
+        let session = Arc::clone(&invocation.session);
+        let turn = Arc::clone(&invocation.turn);
+        let metrics =
+            |duration: Duration, success: bool, output_bytes: usize| ToolCallMetricsEvent {
+                call_id: call_id_owned.clone(),
+                tool_name: tool_name.clone(),
+                duration,
+                success,
+                input_bytes: log_payload.len() as u64,
+                output_bytes: output_bytes as u64,
+            };
 
         let handler = match self.handler(tool_name.as_ref()) {
             Some(handler) => handler,
@@ -81,6 +104,12 @@ impl ToolRegistry {
                     false,
                     &message,
                 );
+                report_tool_call(
+                    &session,
+                    &turn,
+                    metrics(Duration::ZERO, false, message.len()),
+                )
+                .await;
                 return Err(FunctionCallError::RespondToModel(message));
             }
         };
@@ -95,11 +124,18 @@ impl ToolRegistry {
                 false,
                 &message,
             );
+            report_tool_call(
+                &session,
+                &turn,
+                metrics(Duration::ZERO, false, message.len()),
+            )
+            .await;
             return Err(FunctionCallError::Fatal(message));
         }
 
         let output_cell = tokio::sync::Mutex::new(None);
 
+        let started = Instant::now();
         let result = otel
             .log_tool_result(
                 tool_name.as_ref(),
@@ -129,20 +165,43 @@ impl ToolRegistry {
                 },
             )
             .await;
+        let duration = started.elapsed();
 
         match result {
-            Ok(_) => {
-                let mut guard = output_cell.lock().await;
-                let output = guard.take().ok_or_else(|| {
+            Ok((_, success)) => {
+                let output = output_cell.lock().await.take().ok_or_else(|| {
                     FunctionCallError::Fatal("tool produced no output".to_string())
                 })?;
+                report_tool_call(
+                    &session,
+                    &turn,
+                    metrics(duration, success, output.output_bytes()),
+                )
+                .await;
                 Ok(output.into_response(&call_id_owned, &payload_for_response))
             }
-            Err(err) => Err(err),
+            Err(err) => {
+                report_tool_call(
+                    &session,
+                    &turn,
+                    metrics(duration, false, err.to_string().len()),
+                )
+                .await;
+                Err(err)
+            }
         }
     }
 }
 
+/// Counts a finished call toward the task's tool usage summary and reports it
+/// to clients.
+async fn report_tool_call(session: &Session, turn: &TurnContext, metrics: ToolCallMetricsEvent) {
+    turn.tool_usage.record(&metrics);
+    session
+        .send_event(turn, EventMsg::ToolCallMetrics(metrics))
+        .await;
+}
+
 #[derive(Debug, Clone)]
 pub struct ConfiguredToolSpec {
     pub spec: ToolSpec,
@@ -195,6 +254,20 @@ impl ToolRegistryBuilder {
         }
     }
 
+    /// Drops every spec and handler whose name is not in `allowed`.
+    pub fn retain_tools(&mut self, allowed: &[String]) {
+        let is_allowed = |name: &str| allowed.iter().any(|tool| tool == name);
+        self.specs.retain(|config| is_allowed(config.spec.name()));
+        self.handlers.retain(|name, _| is_allowed(name));
+    }
+
+    /// Drops every spec and handler whose name is in `disabled`.
+    pub fn remove_tools(&mut self, disabled: &[String]) {
+        let is_disabled = |name: &str| disabled.iter().any(|tool| tool == name);
+        self.specs.retain(|config| !is_disabled(config.spec.name()));
+        self.handlers.retain(|name, _| !is_disabled(name));
+    }
+
     // TODO(jif) for dynamic tools.
     // pub fn register_many<I>(&mut self, names: I, handler: Arc<dyn ToolHandler>)
     // where
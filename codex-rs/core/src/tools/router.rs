@@ -54,6 +54,14 @@ impl ToolRouter {
             .any(|config| config.spec.name() == tool_name)
     }
 
+    /// Whether the handler for `invocation` reports that this call only reads
+    /// state.
+    pub fn is_read_only_call(&self, invocation: &ToolInvocation) -> bool {
+        self.registry
+            .handler(&invocation.tool_name)
+            .is_some_and(|handler| handler.is_read_only(invocation))
+    }
+
     pub async fn build_tool_call(
         session: &Session,
         item: ResponseItem,
@@ -116,6 +124,11 @@ impl ToolRouter {
                             timeout_ms: exec.timeout_ms,
                             with_escalated_permissions: None,
                             justification: None,
+                            run_in_background: None,
+                            max_output_bytes: None,
+                            max_output_lines: None,
+                            tty: None,
+                            network: None,
                         };
                         Ok(Some(ToolCall {
                             tool_name: "local_shell".to_string(),
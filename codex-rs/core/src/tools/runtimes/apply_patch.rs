@@ -8,6 +8,7 @@ use crate::CODEX_APPLY_PATCH_ARG1;
 use crate::exec::ExecToolCallOutput;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::execute_env;
+use crate::tools::quota::QuotaCharge;
 use crate::tools::sandboxing::Approvable;
 use crate::tools::sandboxing::ApprovalCtx;
 use crate::tools::sandboxing::ProvidesSandboxRetryData;
@@ -19,6 +20,7 @@ use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::with_cached_approval;
+use codex_apply_patch::Hunk;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::ReviewDecision;
 use futures::future::BoxFuture;
@@ -75,6 +77,25 @@ impl ApplyPatchRuntime {
         })
     }
 
+    /// Every file the patch creates, changes, deletes, or moves to.
+    fn edited_paths(req: &ApplyPatchRequest) -> Vec<PathBuf> {
+        let Ok(parsed) = codex_apply_patch::parse_patch(&req.patch) else {
+            return Vec::new();
+        };
+        let mut paths = Vec::new();
+        for hunk in &parsed.hunks {
+            paths.push(hunk.resolve_path(&req.cwd));
+            if let Hunk::UpdateFile {
+                move_path: Some(move_path),
+                ..
+            } = hunk
+            {
+                paths.push(req.cwd.join(move_path));
+            }
+        }
+        paths
+    }
+
     fn stdout_stream(ctx: &ToolCtx<'_>) -> Option<crate::exec::StdoutStream> {
         Some(crate::exec::StdoutStream {
             sub_id: ctx.turn.sub_id.clone(),
@@ -145,6 +166,10 @@ impl Approvable<ApplyPatchRequest> for ApplyPatchRuntime {
 }
 
 impl ToolRuntime<ApplyPatchRequest, ExecToolCallOutput> for ApplyPatchRuntime {
+    fn quota_charge(&self, req: &ApplyPatchRequest) -> QuotaCharge {
+        QuotaCharge::EditFiles(Self::edited_paths(req))
+    }
+
     async fn run(
         &mut self,
         req: &ApplyPatchRequest,
@@ -158,6 +183,15 @@ impl ToolRuntime<ApplyPatchRequest, ExecToolCallOutput> for ApplyPatchRuntime {
         let out = execute_env(&env, attempt.policy, Self::stdout_stream(ctx))
             .await
             .map_err(ToolError::Codex)?;
+        if out.exit_code == 0 {
+            ctx.session
+                .services
+                .file_reads
+                .lock()
+                .await
+                .refresh(&Self::edited_paths(req))
+                .await;
+        }
         Ok(out)
     }
 }
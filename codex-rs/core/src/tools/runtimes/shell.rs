@@ -4,8 +4,13 @@ Runtime: shell
 Executes shell requests under the orchestrator: asks for approval when needed,
 builds a CommandSpec, and runs it under the current SandboxAttempt.
 */
+use crate::exec::DEFAULT_TIMEOUT_MS;
 use crate::exec::ExecToolCallOutput;
+use crate::persistent_shell::PersistentShellCommand;
+use crate::persistent_shell::run_in_persistent_shell;
 use crate::sandboxing::execute_env;
+use crate::sandboxing::execute_env_in_pty;
+use crate::tools::quota::QuotaCharge;
 use crate::tools::runtimes::build_command_spec;
 use crate::tools::sandboxing::Approvable;
 use crate::tools::sandboxing::ApprovalCtx;
@@ -20,8 +25,11 @@ use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::with_cached_approval;
 use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::protocol::SandboxPolicy;
+use codex_utils_pty::TerminalSize;
 use futures::future::BoxFuture;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Clone, Debug)]
 pub struct ShellRequest {
@@ -32,6 +40,14 @@ pub struct ShellRequest {
     pub with_escalated_permissions: Option<bool>,
     pub justification: Option<String>,
     pub approval_requirement: ApprovalRequirement,
+    /// Run the command on a pseudo-terminal of this size instead of pipes.
+    pub pty: Option<TerminalSize>,
+    /// Run the command in the session's persistent shell instead of a fresh
+    /// process.
+    pub persistent: Option<PersistentShellCommand>,
+    /// Run under this policy instead of the turn's, for commands that asked
+    /// for less access than the session has.
+    pub sandbox_policy: Option<SandboxPolicy>,
 }
 
 impl ProvidesSandboxRetryData for ShellRequest {
@@ -51,6 +67,7 @@ pub(crate) struct ApprovalKey {
     command: Vec<String>,
     cwd: PathBuf,
     escalated: bool,
+    restricted: bool,
 }
 
 impl ShellRuntime {
@@ -84,6 +101,7 @@ impl Approvable<ShellRequest> for ShellRuntime {
             command: req.command.clone(),
             cwd: req.cwd.clone(),
             escalated: req.with_escalated_permissions.unwrap_or(false),
+            restricted: req.sandbox_policy.is_some(),
         }
     }
 
@@ -123,12 +141,44 @@ impl Approvable<ShellRequest> for ShellRuntime {
 }
 
 impl ToolRuntime<ShellRequest, ExecToolCallOutput> for ShellRuntime {
+    fn sandbox_policy_override(&self, req: &ShellRequest) -> Option<SandboxPolicy> {
+        req.sandbox_policy.clone()
+    }
+
+    fn quota_charge(&self, _req: &ShellRequest) -> QuotaCharge {
+        QuotaCharge::ShellCall
+    }
+
     async fn run(
         &mut self,
         req: &ShellRequest,
         attempt: &SandboxAttempt<'_>,
         ctx: &ToolCtx<'_>,
     ) -> Result<ExecToolCallOutput, ToolError> {
+        if let Some(persistent) = &req.persistent {
+            let spec = build_command_spec(
+                &persistent.shell,
+                &req.cwd,
+                &req.env,
+                req.timeout_ms,
+                req.with_escalated_permissions,
+                req.justification.clone(),
+            )?;
+            let env = attempt
+                .env_for(&spec)
+                .map_err(|err| ToolError::Codex(err.into()))?;
+            let timeout = Duration::from_millis(req.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS));
+            return run_in_persistent_shell(
+                &ctx.session.services.persistent_shell,
+                persistent,
+                &env,
+                attempt.policy,
+                timeout,
+            )
+            .await
+            .map_err(ToolError::Codex);
+        }
+
         let spec = build_command_spec(
             &req.command,
             &req.cwd,
@@ -140,9 +190,13 @@ impl ToolRuntime<ShellRequest, ExecToolCallOutput> for ShellRuntime {
         let env = attempt
             .env_for(&spec)
             .map_err(|err| ToolError::Codex(err.into()))?;
-        let out = execute_env(&env, attempt.policy, Self::stdout_stream(ctx))
-            .await
-            .map_err(ToolError::Codex)?;
+        let out = match req.pty {
+            Some(size) => {
+                execute_env_in_pty(&env, attempt.policy, size, Self::stdout_stream(ctx)).await
+            }
+            None => execute_env(&env, attempt.policy, Self::stdout_stream(ctx)).await,
+        }
+        .map_err(ToolError::Codex)?;
         Ok(out)
     }
 }
@@ -13,6 +13,7 @@ use crate::sandboxing::CommandSpec;
 use crate::sandboxing::SandboxManager;
 use crate::sandboxing::SandboxTransformError;
 use crate::state::SessionServices;
+use crate::tools::quota::QuotaCharge;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::ReviewDecision;
 use std::collections::HashMap;
@@ -159,7 +160,6 @@ pub(crate) trait Approvable<Req> {
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum SandboxablePreference {
     Auto,
-    #[allow(dead_code)] // Will be used by later tools.
     Require,
     #[allow(dead_code)] // Will be used by later tools.
     Forbid,
@@ -197,6 +197,18 @@ pub(crate) enum ToolError {
 }
 
 pub(crate) trait ToolRuntime<Req, Out>: Approvable<Req> + Sandboxable {
+    /// A sandbox policy stricter than the turn's that this request must run
+    /// under, e.g. a command that asked for no network. Such requests always
+    /// run under a platform sandbox and are never retried without one.
+    fn sandbox_policy_override(&self, _req: &Req) -> Option<SandboxPolicy> {
+        None
+    }
+
+    /// What this request counts against the turn's `tool_quotas`.
+    fn quota_charge(&self, _req: &Req) -> QuotaCharge {
+        QuotaCharge::None
+    }
+
     async fn run(
         &mut self,
         req: &Req,
@@ -36,7 +36,12 @@ pub(crate) struct ToolsConfig {
     pub apply_patch_tool_type: Option<ApplyPatchToolType>,
     pub web_search_request: bool,
     pub include_view_image_tool: bool,
+    pub include_coco_tool: bool,
     pub experimental_supported_tools: Vec<String>,
+    /// Tools the model may call; every configured tool when `None`.
+    pub allowed_tools: Option<Vec<String>>,
+    /// Tools removed after applying `allowed_tools`.
+    pub disabled_tools: Vec<String>,
 }
 
 pub(crate) struct ToolsConfigParams<'a> {
@@ -53,6 +58,7 @@ impl ToolsConfig {
         let include_apply_patch_tool = features.enabled(Feature::ApplyPatchFreeform);
         let include_web_search_request = features.enabled(Feature::WebSearchRequest);
         let include_view_image_tool = features.enabled(Feature::ViewImageTool);
+        let include_coco_tool = features.enabled(Feature::CocoTool);
 
         let shell_type = if !features.enabled(Feature::ShellTool) {
             ConfigShellToolType::Disabled
@@ -81,9 +87,30 @@ impl ToolsConfig {
             apply_patch_tool_type,
             web_search_request: include_web_search_request,
             include_view_image_tool,
+            include_coco_tool,
             experimental_supported_tools: model_family.experimental_supported_tools.clone(),
+            allowed_tools: None,
+            disabled_tools: Vec::new(),
         }
     }
+
+    pub fn with_allowed_tools(mut self, allowed_tools: Option<Vec<String>>) -> Self {
+        self.allowed_tools = allowed_tools;
+        self
+    }
+
+    pub fn with_disabled_tools(mut self, disabled_tools: Vec<String>) -> Self {
+        self.disabled_tools = disabled_tools;
+        self
+    }
+
+    /// Whether `allowed_tools` and `disabled_tools` leave `name` available.
+    pub fn allows_tool(&self, name: &str) -> bool {
+        self.allowed_tools
+            .as_ref()
+            .is_none_or(|allowed| allowed.iter().any(|tool| tool == name))
+            && !self.disabled_tools.iter().any(|tool| tool == name)
+    }
 }
 
 /// Generic JSON‑Schema subset needed for our tool definitions
@@ -267,7 +294,46 @@ fn create_write_stdin_tool() -> ToolSpec {
     })
 }
 
-fn create_shell_tool() -> ToolSpec {
+fn insert_output_limit_properties(properties: &mut BTreeMap<String, JsonSchema>) {
+    properties.insert(
+        "max_output_bytes".to_string(),
+        JsonSchema::Number {
+            description: Some("Optional cap on the bytes of output returned. Past the cap only the head and tail are kept and the full output is saved to a file named in the result.".to_string()),
+        },
+    );
+    properties.insert(
+        "max_output_lines".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Optional cap on the lines of output returned, handled like max_output_bytes."
+                    .to_string(),
+            ),
+        },
+    );
+}
+
+fn insert_tty_property(properties: &mut BTreeMap<String, JsonSchema>) {
+    properties.insert(
+        "tty".to_string(),
+        JsonSchema::Boolean {
+            description: Some("Run the command on a pseudo-terminal, for programs that need a TTY such as progress bars, some test runners, or git with an editor. Color codes and redraws are stripped from the output.".to_string()),
+        },
+    );
+}
+
+fn insert_network_property(properties: &mut BTreeMap<String, JsonSchema>) {
+    properties.insert(
+        "network".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Set to \"off\" to run the command with no network access, e.g. for untrusted build or install scripts. Defaults to \"on\", which keeps the session's network setting."
+                    .to_string(),
+            ),
+        },
+    );
+}
+
+fn create_shell_tool(background_jobs: bool) -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
         "command".to_string(),
@@ -301,6 +367,17 @@ fn create_shell_tool() -> ToolSpec {
             description: Some("Only set if with_escalated_permissions is true. 1-sentence explanation of why we want to run this command.".to_string()),
         },
     );
+    insert_output_limit_properties(&mut properties);
+    insert_tty_property(&mut properties);
+    insert_network_property(&mut properties);
+    if background_jobs {
+        properties.insert(
+            "run_in_background".to_string(),
+            JsonSchema::Boolean {
+                description: Some("Start the command as a background job and return its job id immediately, for dev servers, watch builds, and other long-running commands. Poll it with check_job and stop it with kill_job.".to_string()),
+            },
+        );
+    }
 
     let description  = if cfg!(windows) {
         r#"Runs a Powershell command (Windows) and returns its output. Arguments to `shell` will be passed to CreateProcessW(). Most commands should be prefixed with ["powershell.exe", "-Command"].
@@ -331,6 +408,241 @@ Examples of valid command strings:
     })
 }
 
+fn create_check_job_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "job_id".to_string(),
+        JsonSchema::Number {
+            description: Some("Job id returned by a `run_in_background` shell call.".to_string()),
+        },
+    );
+    properties.insert(
+        "wait_ms".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "How long to wait (in milliseconds) for new output before returning. Defaults to 1000."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "max_output_tokens".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Maximum number of tokens to return. Excess output will be truncated.".to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "check_job".to_string(),
+        description: "Returns a background job's output since the last check and whether it is still running.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["job_id".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_kill_job_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "job_id".to_string(),
+        JsonSchema::Number {
+            description: Some("Job id returned by a `run_in_background` shell call.".to_string()),
+        },
+    );
+    properties.insert(
+        "max_output_tokens".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Maximum number of tokens to return. Excess output will be truncated.".to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "kill_job".to_string(),
+        description:
+            "Stops a background job and returns the output it produced since the last check."
+                .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["job_id".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_get_turn_diff_tool() -> ToolSpec {
+    ToolSpec::Function(ResponsesApiTool {
+        name: "get_turn_diff".to_string(),
+        description: "Returns the cumulative unified diff of the files changed with apply_patch \
+                      and the file editing tools so far this turn, with per-file added/deleted \
+                      line counts, as JSON. Use it to review your changes before finishing."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties: BTreeMap::new(),
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn insert_git_workdir_property(properties: &mut BTreeMap<String, JsonSchema>) {
+    properties.insert(
+        "workdir".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Directory inside the repository. Defaults to the session's working directory."
+                    .to_string(),
+            ),
+        },
+    );
+}
+
+fn create_git_status_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    insert_git_workdir_property(&mut properties);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "git_status".to_string(),
+        description: "Returns the current branch, its upstream and ahead/behind counts, and the \
+                      staged, unstaged and untracked files as JSON."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_git_diff_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    insert_git_workdir_property(&mut properties);
+    properties.insert(
+        "staged".to_string(),
+        JsonSchema::Boolean {
+            description: Some("Diff the staged changes instead of the unstaged ones.".to_string()),
+        },
+    );
+    properties.insert(
+        "base".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Commit, branch or tag to diff against instead of the index.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "paths".to_string(),
+        JsonSchema::Array {
+            items: Box::new(JsonSchema::String { description: None }),
+            description: Some("Limit the diff to these paths.".to_string()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "git_diff".to_string(),
+        description: "Returns per-file added/deleted line counts and the unified diff as JSON."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_git_commit_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    insert_git_workdir_property(&mut properties);
+    properties.insert(
+        "message".to_string(),
+        JsonSchema::String {
+            description: Some("The commit message.".to_string()),
+        },
+    );
+    properties.insert(
+        "all".to_string(),
+        JsonSchema::Boolean {
+            description: Some(
+                "Also commit every modified or deleted tracked file, like `git commit --all`."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "paths".to_string(),
+        JsonSchema::Array {
+            items: Box::new(JsonSchema::String { description: None }),
+            description: Some("Paths to stage before committing, including new files.".to_string()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "git_commit".to_string(),
+        description: "Commits the staged changes (plus `paths`, or all tracked changes with \
+                      `all`) and returns the new commit's sha, subject and files as JSON. Runs \
+                      under the same approval and sandbox rules as shell commands."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["message".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_git_branch_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    insert_git_workdir_property(&mut properties);
+    properties.insert(
+        "action".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "\"list\" (default), \"create\" to create `name` without switching to it, or \
+                 \"switch\" to check out `name`."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "name".to_string(),
+        JsonSchema::String {
+            description: Some("Branch to create or switch to.".to_string()),
+        },
+    );
+    properties.insert(
+        "start_point".to_string(),
+        JsonSchema::String {
+            description: Some("Commit a created branch starts at. Defaults to HEAD.".to_string()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "git_branch".to_string(),
+        description: "Lists, creates or switches local branches and returns the branch list as \
+                      JSON. Creating and switching run under the same approval and sandbox \
+                      rules as shell commands."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_shell_command_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
@@ -365,6 +677,9 @@ fn create_shell_command_tool() -> ToolSpec {
             description: Some("Only set if with_escalated_permissions is true. 1-sentence explanation of why we want to run this command.".to_string()),
         },
     );
+    insert_output_limit_properties(&mut properties);
+    insert_tty_property(&mut properties);
+    insert_network_property(&mut properties);
 
     let description = if cfg!(windows) {
         r#"Runs a Powershell command (Windows) and returns its output.
@@ -418,57 +733,212 @@ fn create_view_image_tool() -> ToolSpec {
     })
 }
 
-fn create_test_sync_tool() -> ToolSpec {
+fn create_coco_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
-        "sleep_before_ms".to_string(),
-        JsonSchema::Number {
-            description: Some("Optional delay in milliseconds before any other action".to_string()),
+        "prompt".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Task for the sub-agent, written as a self-contained request.".to_string(),
+            ),
         },
     );
     properties.insert(
-        "sleep_after_ms".to_string(),
-        JsonSchema::Number {
+        "cwd".to_string(),
+        JsonSchema::String {
             description: Some(
-                "Optional delay in milliseconds after completing the barrier".to_string(),
+                "Working directory for the sub-agent; defaults to the current working directory. A sub-agent that may write files must stay within the directories this conversation may write to."
+                    .to_string(),
             ),
         },
     );
-
-    let mut barrier_properties = BTreeMap::new();
-    barrier_properties.insert(
-        "id".to_string(),
+    properties.insert(
+        "resume".to_string(),
         JsonSchema::String {
             description: Some(
-                "Identifier shared by concurrent calls that should rendezvous".to_string(),
+                "Id of a coco session returned by an earlier call in this conversation; continues that sub-agent with `prompt` as the follow-up instead of starting a new one."
+                    .to_string(),
             ),
         },
     );
-    barrier_properties.insert(
-        "participants".to_string(),
-        JsonSchema::Number {
+    properties.insert(
+        "files".to_string(),
+        JsonSchema::Array {
+            items: Box::new(JsonSchema::String { description: None }),
             description: Some(
-                "Number of tool calls that must arrive before the barrier opens".to_string(),
+                "Files to attach to the prompt, relative to `cwd`; images are attached as images and other files inlined as text."
+                    .to_string(),
             ),
         },
     );
-    barrier_properties.insert(
-        "timeout_ms".to_string(),
-        JsonSchema::Number {
-            description: Some("Maximum time in milliseconds to wait at the barrier".to_string()),
+    properties.insert(
+        "sandbox".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Sandbox for the sub-agent: `read-only`, `workspace-write`, or `danger-full-access`; it cannot be looser than this conversation's."
+                    .to_string(),
+            ),
         },
     );
-
     properties.insert(
-        "barrier".to_string(),
-        JsonSchema::Object {
-            properties: barrier_properties,
-            required: Some(vec!["id".to_string(), "participants".to_string()]),
-            additional_properties: Some(false.into()),
+        "approval".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Approval policy for the sub-agent: `untrusted`, `on-failure`, `on-request`, or `never`."
+                    .to_string(),
+            ),
         },
     );
-
-    ToolSpec::Function(ResponsesApiTool {
+    properties.insert(
+        "max_output_lines".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Lines of output kept per command the sub-agent runs; the full log is saved to a file when output is cut."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "max_output_bytes".to_string(),
+        JsonSchema::Number {
+            description: Some("Bytes of output kept per command the sub-agent runs.".to_string()),
+        },
+    );
+    properties.insert(
+        "agent".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Name of a configured sub-agent profile whose model, instructions, sandbox, and tools to use."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "model".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Model for the sub-agent; defaults to the model of this conversation.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "timeout_ms".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Milliseconds after which the sub-agent is cancelled and its partial output returned; defaults to `coco.timeout_ms` (10 minutes)."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "output_schema".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Path, relative to `cwd`, of a JSON Schema file; the sub-agent must answer with JSON matching it, which is returned as the result."
+                    .to_string(),
+            ),
+        },
+    );
+
+    properties.insert(
+        "tools".to_string(),
+        JsonSchema::Array {
+            items: Box::new(JsonSchema::String { description: None }),
+            description: Some(
+                "Tools the sub-agent may call, e.g. `[\"shell\"]`; it never gets tools this conversation lacks."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "disabled_tools".to_string(),
+        JsonSchema::Array {
+            items: Box::new(JsonSchema::String { description: None }),
+            description: Some(
+                "Tools the sub-agent may not call, e.g. `[\"apply_patch\"]` to keep it read-only."
+                    .to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "coco".to_string(),
+        description: "Delegates a task to a sub-agent that runs its own conversation with the same tools and returns its final message. Use it for self-contained work such as investigating a question or making a focused change."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["prompt".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_coco_status_tool() -> ToolSpec {
+    ToolSpec::Function(ResponsesApiTool {
+        name: "coco_status".to_string(),
+        description: "Reports how many coco sub-agents this session has run, how many succeeded, failed, or timed out, and the total time spent in them."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties: BTreeMap::new(),
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_test_sync_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "sleep_before_ms".to_string(),
+        JsonSchema::Number {
+            description: Some("Optional delay in milliseconds before any other action".to_string()),
+        },
+    );
+    properties.insert(
+        "sleep_after_ms".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Optional delay in milliseconds after completing the barrier".to_string(),
+            ),
+        },
+    );
+
+    let mut barrier_properties = BTreeMap::new();
+    barrier_properties.insert(
+        "id".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Identifier shared by concurrent calls that should rendezvous".to_string(),
+            ),
+        },
+    );
+    barrier_properties.insert(
+        "participants".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Number of tool calls that must arrive before the barrier opens".to_string(),
+            ),
+        },
+    );
+    barrier_properties.insert(
+        "timeout_ms".to_string(),
+        JsonSchema::Number {
+            description: Some("Maximum time in milliseconds to wait at the barrier".to_string()),
+        },
+    );
+
+    properties.insert(
+        "barrier".to_string(),
+        JsonSchema::Object {
+            properties: barrier_properties,
+            required: Some(vec!["id".to_string(), "participants".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
         name: "test_sync_tool".to_string(),
         description: "Internal synchronization helper used by Codex integration tests.".to_string(),
         strict: false,
@@ -530,192 +1000,574 @@ fn create_grep_files_tool() -> ToolSpec {
     })
 }
 
-fn create_read_file_tool() -> ToolSpec {
+fn create_verify_patch_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "input".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "The entire patch, in the same format apply_patch accepts.".to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "verify_patch".to_string(),
+        description: "Checks whether a patch would apply without writing anything. Reports, \
+                      for each hunk, the line it would apply at or, on a context mismatch, \
+                      the nearest matching lines in the file, so the patch can be fixed \
+                      before calling apply_patch."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["input".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_glob_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "pattern".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Glob to match file paths against, gitignore-style: \"*.rs\" matches at any depth, \"src/**/*.ts\" only under src."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "path".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Directory to search. Defaults to the session's working directory.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "limit".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Maximum number of file paths to return (defaults to 100).".to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "glob".to_string(),
+        description:
+            "Finds files by name pattern, skipping files ignored by .gitignore, and lists \
+                      them most recently modified first."
+                .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["pattern".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_grep_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "pattern".to_string(),
+        JsonSchema::String {
+            description: Some("Regular expression to search each line for.".to_string()),
+        },
+    );
+    properties.insert(
+        "path".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Directory or file to search. Defaults to the session's working directory."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "context_lines".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Lines of context to show before and after each match (defaults to 0, at most 10)."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "include".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional glob that limits which files are searched (e.g. \"*.rs\").".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "limit".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Maximum number of matching lines to return (defaults to 100).".to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "grep".to_string(),
+        description: "Searches file contents for a regular expression, skipping binary files and \
+                      files ignored by .gitignore. Prints `path:line:text` for matches and \
+                      `path-line-text` for context lines."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["pattern".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_read_file_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "file_path".to_string(),
+        JsonSchema::String {
+            description: Some("Absolute path to the file".to_string()),
+        },
+    );
+    properties.insert(
+        "offset".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "The line number to start reading from. Must be 1 or greater.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "limit".to_string(),
+        JsonSchema::Number {
+            description: Some("The maximum number of lines to return.".to_string()),
+        },
+    );
+    properties.insert(
+        "max_bytes".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "The maximum number of bytes of lines to return; defaults to 65536. The output ends with the total line count and, when cut, the offset to continue from."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "mode".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional mode selector: \"slice\" for simple ranges (default) or \"indentation\" \
+                 to expand around an anchor line."
+                    .to_string(),
+            ),
+        },
+    );
+
+    let mut indentation_properties = BTreeMap::new();
+    indentation_properties.insert(
+        "anchor_line".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Anchor line to center the indentation lookup on (defaults to offset).".to_string(),
+            ),
+        },
+    );
+    indentation_properties.insert(
+        "max_levels".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "How many parent indentation levels (smaller indents) to include.".to_string(),
+            ),
+        },
+    );
+    indentation_properties.insert(
+        "include_siblings".to_string(),
+        JsonSchema::Boolean {
+            description: Some(
+                "When true, include additional blocks that share the anchor indentation."
+                    .to_string(),
+            ),
+        },
+    );
+    indentation_properties.insert(
+        "include_header".to_string(),
+        JsonSchema::Boolean {
+            description: Some(
+                "Include doc comments or attributes directly above the selected block.".to_string(),
+            ),
+        },
+    );
+    indentation_properties.insert(
+        "max_lines".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Hard cap on the number of lines returned when using indentation mode.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "indentation".to_string(),
+        JsonSchema::Object {
+            properties: indentation_properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "read_file".to_string(),
+        description:
+            "Reads a local file with 1-indexed line numbers, supporting slice and indentation-aware block modes."
+                .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["file_path".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+/// Adds the `preview` flag shared by the file editing tools.
+fn insert_preview_property(properties: &mut BTreeMap<String, JsonSchema>) {
+    properties.insert(
+        "preview".to_string(),
+        JsonSchema::Boolean {
+            description: Some(
+                "When true, return the unified diff of the change without applying it.".to_string(),
+            ),
+        },
+    );
+}
+
+fn insert_line_endings_property(properties: &mut BTreeMap<String, JsonSchema>) {
+    properties.insert(
+        "line_endings".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional \"lf\" or \"crlf\" to force the file's line endings. By default an existing file keeps its current line endings."
+                    .to_string(),
+            ),
+        },
+    );
+}
+
+fn create_write_file_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "file_path".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Absolute path to the file that should be created or overwritten.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "content".to_string(),
+        JsonSchema::String {
+            description: Some("The full contents that should be written to the file.".to_string()),
+        },
+    );
+    properties.insert(
+        "create_parents".to_string(),
+        JsonSchema::Boolean {
+            description: Some(
+                "Create missing parent directories (default: true). Set to false to fail instead, e.g. to catch a mistyped directory."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "must_exist".to_string(),
+        JsonSchema::Boolean {
+            description: Some(
+                "Only overwrite an existing file; fail if it does not exist (default: false)."
+                    .to_string(),
+            ),
+        },
+    );
+
+    insert_preview_property(&mut properties);
+    insert_line_endings_property(&mut properties);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "write_file".to_string(),
+        description: "Creates or overwrites a file with the provided content. Use absolute paths."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["file_path".to_string(), "content".to_string()]),
+            additional_properties: Some(true.into()),
+        },
+    })
+}
+
+fn create_replace_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "file_path".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Absolute path to the file that contains the text to replace.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "instruction".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "High-level instruction describing the intent of the change. Provide enough context so another engineer could apply it."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "old_string".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Exact literal text to replace. Include surrounding context and match whitespace precisely."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "new_string".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Exact literal replacement for `old_string`. Ensure the resulting code is complete and idiomatic."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "expected_replacements".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Optional expected number of replacements. Defaults to 1; set explicitly when replacing multiple occurrences."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "fuzzy_whitespace".to_string(),
+        JsonSchema::Boolean {
+            description: Some(
+                "When true and `old_string` has no exact match, retry matching whole lines while ignoring indentation and whitespace differences. The result reports which lines matched."
+                    .to_string(),
+            ),
+        },
+    );
+
+    insert_preview_property(&mut properties);
+    insert_line_endings_property(&mut properties);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "replace".to_string(),
+        description: "Performs a literal search-and-replace within an existing file. Provide the exact text you expect to change.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec![
+                "file_path".to_string(),
+                "old_string".to_string(),
+                "new_string".to_string(),
+            ]),
+            additional_properties: Some(true.into()),
+        },
+    })
+}
+
+fn create_replace_regex_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
         "file_path".to_string(),
         JsonSchema::String {
-            description: Some("Absolute path to the file".to_string()),
+            description: Some("Absolute path to the file to edit.".to_string()),
         },
     );
     properties.insert(
-        "offset".to_string(),
-        JsonSchema::Number {
+        "pattern".to_string(),
+        JsonSchema::String {
             description: Some(
-                "The line number to start reading from. Must be 1 or greater.".to_string(),
+                "Regular expression to replace (Rust regex syntax, at most 1024 bytes). It must not match an empty string."
+                    .to_string(),
             ),
         },
     );
     properties.insert(
-        "limit".to_string(),
-        JsonSchema::Number {
-            description: Some("The maximum number of lines to return.".to_string()),
-        },
-    );
-    properties.insert(
-        "mode".to_string(),
+        "replacement".to_string(),
         JsonSchema::String {
             description: Some(
-                "Optional mode selector: \"slice\" for simple ranges (default) or \"indentation\" \
-                 to expand around an anchor line."
+                "Replacement text; `$1` or `${name}` inserts a capture group and `$$` a literal `$`."
                     .to_string(),
             ),
         },
     );
-
-    let mut indentation_properties = BTreeMap::new();
-    indentation_properties.insert(
-        "anchor_line".to_string(),
+    properties.insert(
+        "expected_matches".to_string(),
         JsonSchema::Number {
             description: Some(
-                "Anchor line to center the indentation lookup on (defaults to offset).".to_string(),
+                "Expected number of matches. Defaults to 1; every match is replaced.".to_string(),
             ),
         },
     );
-    indentation_properties.insert(
-        "max_levels".to_string(),
-        JsonSchema::Number {
-            description: Some(
-                "How many parent indentation levels (smaller indents) to include.".to_string(),
-            ),
+    insert_preview_property(&mut properties);
+    insert_line_endings_property(&mut properties);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "replace_regex".to_string(),
+        description: "Replaces regular expression matches within an existing file, for mechanical changes exact-string replace cannot express, such as version bumps or renames across varying whitespace.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec![
+                "file_path".to_string(),
+                "pattern".to_string(),
+                "replacement".to_string(),
+            ]),
+            additional_properties: Some(true.into()),
         },
-    );
-    indentation_properties.insert(
-        "include_siblings".to_string(),
-        JsonSchema::Boolean {
+    })
+}
+
+fn create_multi_edit_tool() -> ToolSpec {
+    let mut edit_properties = BTreeMap::new();
+    edit_properties.insert(
+        "old_string".to_string(),
+        JsonSchema::String {
             description: Some(
-                "When true, include additional blocks that share the anchor indentation."
-                    .to_string(),
+                "Exact literal text to replace, as it reads after the earlier edits.".to_string(),
             ),
         },
     );
-    indentation_properties.insert(
-        "include_header".to_string(),
-        JsonSchema::Boolean {
-            description: Some(
-                "Include doc comments or attributes directly above the selected block.".to_string(),
-            ),
+    edit_properties.insert(
+        "new_string".to_string(),
+        JsonSchema::String {
+            description: Some("Exact literal replacement for `old_string`.".to_string()),
         },
     );
-    indentation_properties.insert(
-        "max_lines".to_string(),
+    edit_properties.insert(
+        "expected_replacements".to_string(),
         JsonSchema::Number {
             description: Some(
-                "Hard cap on the number of lines returned when using indentation mode.".to_string(),
+                "Optional expected number of replacements. Defaults to 1.".to_string(),
             ),
         },
     );
+
+    let mut properties = BTreeMap::new();
     properties.insert(
-        "indentation".to_string(),
-        JsonSchema::Object {
-            properties: indentation_properties,
-            required: None,
-            additional_properties: Some(false.into()),
+        "file_path".to_string(),
+        JsonSchema::String {
+            description: Some("Absolute path to the file to edit.".to_string()),
+        },
+    );
+    properties.insert(
+        "edits".to_string(),
+        JsonSchema::Array {
+            items: Box::new(JsonSchema::Object {
+                properties: edit_properties,
+                required: Some(vec!["old_string".to_string(), "new_string".to_string()]),
+                additional_properties: Some(false.into()),
+            }),
+            description: Some(
+                "Replacements applied in order, each to the result of the previous one."
+                    .to_string(),
+            ),
         },
     );
 
+    insert_preview_property(&mut properties);
+    insert_line_endings_property(&mut properties);
+
     ToolSpec::Function(ResponsesApiTool {
-        name: "read_file".to_string(),
-        description:
-            "Reads a local file with 1-indexed line numbers, supporting slice and indentation-aware block modes."
-                .to_string(),
+        name: "multi_edit".to_string(),
+        description: "Performs several literal search-and-replace edits within one existing file as a single change. If any edit fails, none are applied.".to_string(),
         strict: false,
         parameters: JsonSchema::Object {
             properties,
-            required: Some(vec!["file_path".to_string()]),
-            additional_properties: Some(false.into()),
+            required: Some(vec!["file_path".to_string(), "edits".to_string()]),
+            additional_properties: Some(true.into()),
         },
     })
 }
 
-fn create_write_file_tool() -> ToolSpec {
+fn create_insert_at_line_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
         "file_path".to_string(),
         JsonSchema::String {
+            description: Some("Absolute path to the file to insert into.".to_string()),
+        },
+    );
+    properties.insert(
+        "line_number".to_string(),
+        JsonSchema::Number {
             description: Some(
-                "Absolute path to the file that should be created or overwritten.".to_string(),
+                "1-based line the content is inserted before; one past the last line inserts at the end of the file."
+                    .to_string(),
             ),
         },
     );
     properties.insert(
         "content".to_string(),
         JsonSchema::String {
-            description: Some("The full contents that should be written to the file.".to_string()),
+            description: Some("Lines to insert.".to_string()),
         },
     );
 
+    insert_preview_property(&mut properties);
+    insert_line_endings_property(&mut properties);
+
     ToolSpec::Function(ResponsesApiTool {
-        name: "write_file".to_string(),
-        description: "Creates or overwrites a file with the provided content. Use absolute paths."
-            .to_string(),
+        name: "insert_at_line".to_string(),
+        description: "Inserts lines into an existing file before the given line number, e.g. to add an import, without matching surrounding text.".to_string(),
         strict: false,
         parameters: JsonSchema::Object {
             properties,
-            required: Some(vec!["file_path".to_string(), "content".to_string()]),
+            required: Some(vec![
+                "file_path".to_string(),
+                "line_number".to_string(),
+                "content".to_string(),
+            ]),
             additional_properties: Some(true.into()),
         },
     })
 }
 
-fn create_replace_tool() -> ToolSpec {
+fn create_append_to_file_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
         "file_path".to_string(),
         JsonSchema::String {
-            description: Some(
-                "Absolute path to the file that contains the text to replace.".to_string(),
-            ),
-        },
-    );
-    properties.insert(
-        "instruction".to_string(),
-        JsonSchema::String {
-            description: Some(
-                "High-level instruction describing the intent of the change. Provide enough context so another engineer could apply it."
-                    .to_string(),
-            ),
-        },
-    );
-    properties.insert(
-        "old_string".to_string(),
-        JsonSchema::String {
-            description: Some(
-                "Exact literal text to replace. Include surrounding context and match whitespace precisely."
-                    .to_string(),
-            ),
+            description: Some("Absolute path to the existing file to append to.".to_string()),
         },
     );
     properties.insert(
-        "new_string".to_string(),
+        "content".to_string(),
         JsonSchema::String {
-            description: Some(
-                "Exact literal replacement for `old_string`. Ensure the resulting code is complete and idiomatic."
-                    .to_string(),
-            ),
-        },
-    );
-    properties.insert(
-        "expected_replacements".to_string(),
-        JsonSchema::Number {
-            description: Some(
-                "Optional expected number of replacements. Defaults to 1; set explicitly when replacing multiple occurrences."
-                    .to_string(),
-            ),
+            description: Some("Lines to add at the end of the file.".to_string()),
         },
     );
 
+    insert_preview_property(&mut properties);
+    insert_line_endings_property(&mut properties);
+
     ToolSpec::Function(ResponsesApiTool {
-        name: "replace".to_string(),
-        description: "Performs a literal search-and-replace within an existing file. Provide the exact text you expect to change.".to_string(),
+        name: "append_to_file".to_string(),
+        description: "Adds lines to the end of an existing file.".to_string(),
         strict: false,
         parameters: JsonSchema::Object {
             properties,
-            required: Some(vec![
-                "file_path".to_string(),
-                "old_string".to_string(),
-                "new_string".to_string(),
-            ]),
+            required: Some(vec!["file_path".to_string(), "content".to_string()]),
             additional_properties: Some(true.into()),
         },
     })
@@ -730,6 +1582,8 @@ fn create_delete_file_tool() -> ToolSpec {
         },
     );
 
+    insert_preview_property(&mut properties);
+
     ToolSpec::Function(ResponsesApiTool {
         name: "delete".to_string(),
         description: "Deletes an existing file from the workspace. Provide an absolute file path."
@@ -1092,7 +1946,13 @@ pub(crate) fn build_specs(
     mcp_tools: Option<HashMap<String, mcp_types::Tool>>,
 ) -> ToolRegistryBuilder {
     use crate::tools::handlers::ApplyPatchHandler;
+    use crate::tools::handlers::BackgroundJobHandler;
+    use crate::tools::handlers::CocoHandler;
+    use crate::tools::handlers::CocoStatusHandler;
+    use crate::tools::handlers::GitHandler;
+    use crate::tools::handlers::GlobHandler;
     use crate::tools::handlers::GrepFilesHandler;
+    use crate::tools::handlers::GrepHandler;
     use crate::tools::handlers::ListDirHandler;
     use crate::tools::handlers::McpHandler;
     use crate::tools::handlers::McpResourceHandler;
@@ -1101,7 +1961,9 @@ pub(crate) fn build_specs(
     use crate::tools::handlers::ShellCommandHandler;
     use crate::tools::handlers::ShellHandler;
     use crate::tools::handlers::TestSyncHandler;
+    use crate::tools::handlers::TurnDiffHandler;
     use crate::tools::handlers::UnifiedExecHandler;
+    use crate::tools::handlers::VerifyPatchHandler;
     use crate::tools::handlers::ViewImageHandler;
     use std::sync::Arc;
 
@@ -1119,7 +1981,11 @@ pub(crate) fn build_specs(
 
     match &config.shell_type {
         ConfigShellToolType::Default => {
-            builder.push_spec(create_shell_tool());
+            builder.push_spec(create_shell_tool(
+                config
+                    .experimental_supported_tools
+                    .contains(&"check_job".to_string()),
+            ));
         }
         ConfigShellToolType::Local => {
             builder.push_spec(ToolSpec::LocalShell {});
@@ -1166,6 +2032,14 @@ pub(crate) fn build_specs(
             }
         }
         builder.register_handler("apply_patch", apply_patch_handler);
+
+        if config
+            .experimental_supported_tools
+            .contains(&"verify_patch".to_string())
+        {
+            builder.push_spec_with_parallel_support(create_verify_patch_tool(), true);
+            builder.register_handler("verify_patch", Arc::new(VerifyPatchHandler));
+        }
     }
 
     if config
@@ -1177,6 +2051,22 @@ pub(crate) fn build_specs(
         builder.register_handler("grep_files", grep_files_handler);
     }
 
+    if config
+        .experimental_supported_tools
+        .contains(&"glob".to_string())
+    {
+        builder.push_spec_with_parallel_support(create_glob_tool(), true);
+        builder.register_handler("glob", Arc::new(GlobHandler));
+    }
+
+    if config
+        .experimental_supported_tools
+        .contains(&"grep".to_string())
+    {
+        builder.push_spec_with_parallel_support(create_grep_tool(), true);
+        builder.register_handler("grep", Arc::new(GrepHandler));
+    }
+
     if config
         .experimental_supported_tools
         .contains(&"read_file".to_string())
@@ -1202,6 +2092,38 @@ pub(crate) fn build_specs(
         builder.register_handler("replace", edit_handler.clone());
     }
 
+    if config
+        .experimental_supported_tools
+        .contains(&"replace_regex".to_string())
+    {
+        builder.push_spec(create_replace_regex_tool());
+        builder.register_handler("replace_regex", edit_handler.clone());
+    }
+
+    if config
+        .experimental_supported_tools
+        .contains(&"multi_edit".to_string())
+    {
+        builder.push_spec(create_multi_edit_tool());
+        builder.register_handler("multi_edit", edit_handler.clone());
+    }
+
+    if config
+        .experimental_supported_tools
+        .contains(&"insert_at_line".to_string())
+    {
+        builder.push_spec(create_insert_at_line_tool());
+        builder.register_handler("insert_at_line", edit_handler.clone());
+    }
+
+    if config
+        .experimental_supported_tools
+        .contains(&"append_to_file".to_string())
+    {
+        builder.push_spec(create_append_to_file_tool());
+        builder.register_handler("append_to_file", edit_handler.clone());
+    }
+
     if config
         .experimental_supported_tools
         .contains(&"delete".to_string())
@@ -1210,6 +2132,47 @@ pub(crate) fn build_specs(
         builder.register_handler("delete", edit_handler);
     }
 
+    let background_job_handler = Arc::new(BackgroundJobHandler);
+    if config
+        .experimental_supported_tools
+        .contains(&"check_job".to_string())
+    {
+        builder.push_spec(create_check_job_tool());
+        builder.register_handler("check_job", background_job_handler.clone());
+    }
+
+    if config
+        .experimental_supported_tools
+        .contains(&"kill_job".to_string())
+    {
+        builder.push_spec(create_kill_job_tool());
+        builder.register_handler("kill_job", background_job_handler);
+    }
+
+    let git_handler = Arc::new(GitHandler);
+    for (name, spec, read_only) in [
+        ("git_status", create_git_status_tool(), true),
+        ("git_diff", create_git_diff_tool(), true),
+        ("git_commit", create_git_commit_tool(), false),
+        ("git_branch", create_git_branch_tool(), false),
+    ] {
+        if config
+            .experimental_supported_tools
+            .contains(&name.to_string())
+        {
+            builder.push_spec_with_parallel_support(spec, read_only);
+            builder.register_handler(name, git_handler.clone());
+        }
+    }
+
+    if config
+        .experimental_supported_tools
+        .contains(&"get_turn_diff".to_string())
+    {
+        builder.push_spec_with_parallel_support(create_get_turn_diff_tool(), true);
+        builder.register_handler("get_turn_diff", Arc::new(TurnDiffHandler));
+    }
+
     if config
         .experimental_supported_tools
         .iter()
@@ -1238,6 +2201,13 @@ pub(crate) fn build_specs(
         builder.register_handler("view_image", view_image_handler);
     }
 
+    if config.include_coco_tool {
+        builder.push_spec(create_coco_tool());
+        builder.register_handler("coco", Arc::new(CocoHandler));
+        builder.push_spec_with_parallel_support(create_coco_status_tool(), true);
+        builder.register_handler("coco_status", Arc::new(CocoStatusHandler));
+    }
+
     if let Some(mcp_tools) = mcp_tools {
         let mut entries: Vec<(String, mcp_types::Tool)> = mcp_tools.into_iter().collect();
         entries.sort_by(|a, b| a.0.cmp(&b.0));
@@ -1255,6 +2225,11 @@ pub(crate) fn build_specs(
         }
     }
 
+    if let Some(allowed_tools) = &config.allowed_tools {
+        builder.retain_tools(allowed_tools);
+    }
+    builder.remove_tools(&config.disabled_tools);
+
     builder
 }
 
@@ -1625,6 +2600,75 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_specs_coco_tool_present() {
+        assert_model_tools(
+            "codex-mini-latest",
+            Features::with_defaults().enable(Feature::CocoTool),
+            &[
+                "local_shell",
+                "list_mcp_resources",
+                "list_mcp_resource_templates",
+                "read_mcp_resource",
+                "update_plan",
+                "view_image",
+                "coco",
+                "coco_status",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_build_specs_allowed_tools_filters_specs() {
+        let model_family = find_family_for_model("codex-mini-latest")
+            .expect("codex-mini-latest should be a valid model family");
+        let features = Features::with_defaults();
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &features,
+        })
+        .with_allowed_tools(Some(vec![
+            "local_shell".to_string(),
+            "update_plan".to_string(),
+        ]));
+        let (tools, registry) = build_specs(&config, None).build();
+
+        let tool_names = tools
+            .iter()
+            .map(|tool| tool_name(&tool.spec))
+            .collect::<Vec<_>>();
+        assert_eq!(tool_names, vec!["local_shell", "update_plan"]);
+        assert!(registry.handler("view_image").is_none());
+    }
+
+    #[test]
+    fn test_build_specs_disabled_tools_removes_specs() {
+        let model_family = find_family_for_model("codex-mini-latest")
+            .expect("codex-mini-latest should be a valid model family");
+        let features = Features::with_defaults();
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &features,
+        })
+        .with_allowed_tools(Some(vec![
+            "local_shell".to_string(),
+            "update_plan".to_string(),
+            "view_image".to_string(),
+        ]))
+        .with_disabled_tools(vec!["view_image".to_string()]);
+        let (tools, registry) = build_specs(&config, None).build();
+
+        let tool_names = tools
+            .iter()
+            .map(|tool| tool_name(&tool.spec))
+            .collect::<Vec<_>>();
+        assert_eq!(tool_names, vec!["local_shell", "update_plan"]);
+        assert!(registry.handler("view_image").is_none());
+        assert!(config.allows_tool("local_shell"));
+        assert!(!config.allows_tool("view_image"));
+        assert!(!config.allows_tool("apply_patch"));
+    }
+
     #[test]
     #[ignore]
     fn test_parallel_support_flags() {
@@ -1680,6 +2724,26 @@ mod tests {
                 .any(|tool| tool_name(&tool.spec) == "write_file")
         );
         assert!(tools.iter().any(|tool| tool_name(&tool.spec) == "replace"));
+        assert!(
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "multi_edit")
+        );
+        assert!(
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "replace_regex")
+        );
+        assert!(
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "insert_at_line")
+        );
+        assert!(
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "append_to_file")
+        );
         assert!(tools.iter().any(|tool| tool_name(&tool.spec) == "delete"));
     }
 
@@ -2074,7 +3138,7 @@ mod tests {
 
     #[test]
     fn test_shell_tool() {
-        let tool = super::create_shell_tool();
+        let tool = super::create_shell_tool(false);
         let ToolSpec::Function(ResponsesApiTool {
             description, name, ..
         }) = &tool
@@ -0,0 +1,111 @@
+//! Per-task tool call totals, reported as `ToolUsageSummary` when the task
+//! completes.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+
+use crate::protocol::ToolCallMetricsEvent;
+use crate::protocol::ToolUsageStats;
+use crate::protocol::ToolUsageSummaryEvent;
+
+#[derive(Debug, Default)]
+pub(crate) struct ToolUsageTracker {
+    by_tool: Mutex<HashMap<String, ToolUsageStats>>,
+}
+
+impl ToolUsageTracker {
+    pub(crate) fn record(&self, metrics: &ToolCallMetricsEvent) {
+        let mut by_tool = self.by_tool();
+        let stats = by_tool
+            .entry(metrics.tool_name.clone())
+            .or_insert_with(|| ToolUsageStats {
+                tool_name: metrics.tool_name.clone(),
+                ..Default::default()
+            });
+        stats.calls += 1;
+        if !metrics.success {
+            stats.failures += 1;
+        }
+        stats.total_duration += metrics.duration;
+        stats.input_bytes += metrics.input_bytes;
+        stats.output_bytes += metrics.output_bytes;
+    }
+
+    /// Returns the totals recorded so far, most total time first, and
+    /// resets them. `None` when no tool was called.
+    pub(crate) fn take_summary(&self) -> Option<ToolUsageSummaryEvent> {
+        let mut tools: Vec<ToolUsageStats> =
+            self.by_tool().drain().map(|(_, stats)| stats).collect();
+        if tools.is_empty() {
+            return None;
+        }
+        tools.sort_by(|a, b| {
+            b.total_duration
+                .cmp(&a.total_duration)
+                .then_with(|| a.tool_name.cmp(&b.tool_name))
+        });
+        Some(ToolUsageSummaryEvent { tools })
+    }
+
+    fn by_tool(&self) -> MutexGuard<'_, HashMap<String, ToolUsageStats>> {
+        match self.by_tool.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn call(tool_name: &str, millis: u64, success: bool) -> ToolCallMetricsEvent {
+        ToolCallMetricsEvent {
+            call_id: "call".to_string(),
+            tool_name: tool_name.to_string(),
+            duration: Duration::from_millis(millis),
+            success,
+            input_bytes: 10,
+            output_bytes: 100,
+        }
+    }
+
+    #[test]
+    fn summary_totals_each_tool_slowest_first() {
+        let tracker = ToolUsageTracker::default();
+        assert_eq!(tracker.take_summary(), None);
+
+        tracker.record(&call("read_file", 5, true));
+        tracker.record(&call("shell", 300, true));
+        tracker.record(&call("shell", 200, false));
+        tracker.record(&call("read_file", 5, true));
+
+        assert_eq!(
+            tracker.take_summary(),
+            Some(ToolUsageSummaryEvent {
+                tools: vec![
+                    ToolUsageStats {
+                        tool_name: "shell".to_string(),
+                        calls: 2,
+                        failures: 1,
+                        total_duration: Duration::from_millis(500),
+                        input_bytes: 20,
+                        output_bytes: 200,
+                    },
+                    ToolUsageStats {
+                        tool_name: "read_file".to_string(),
+                        calls: 2,
+                        failures: 0,
+                        total_duration: Duration::from_millis(10),
+                        input_bytes: 20,
+                        output_bytes: 200,
+                    },
+                ],
+            })
+        );
+        assert_eq!(tracker.take_summary(), None);
+    }
+}
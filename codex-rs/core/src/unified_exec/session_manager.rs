@@ -238,6 +238,49 @@ impl UnifiedExecSessionManager {
         Ok(response)
     }
 
+    /// Kills the process behind `session_id` and forgets the session,
+    /// returning the output it produced that was not read yet.
+    pub(crate) async fn terminate_session(
+        &self,
+        session_id: i32,
+        max_output_tokens: Option<usize>,
+    ) -> Result<UnifiedExecResponse, UnifiedExecError> {
+        let entry = self
+            .sessions
+            .lock()
+            .await
+            .remove(&session_id)
+            .ok_or(UnifiedExecError::UnknownSessionId { session_id })?;
+
+        let (output_buffer, _) = entry.session.output_handles();
+        let collected = output_buffer.lock().await.drain().concat();
+        let text = String::from_utf8_lossy(&collected).to_string();
+        let max_tokens = resolve_max_tokens(max_output_tokens);
+        let output = formatted_truncate_text(&text, TruncationPolicy::Tokens(max_tokens));
+        let exit_code = entry.session.exit_code();
+        let wall_time = Instant::now().saturating_duration_since(entry.started_at);
+
+        let response = UnifiedExecResponse {
+            event_call_id: entry.call_id.clone(),
+            chunk_id: generate_chunk_id(),
+            wall_time,
+            output,
+            session_id: None,
+            exit_code,
+            original_token_count: Some(approx_token_count(&text)),
+            session_command: Some(entry.command.clone()),
+        };
+        // Dropping the session (with the entry) kills the process.
+        Self::emit_exec_end_from_entry(
+            entry,
+            response.output.clone(),
+            exit_code.unwrap_or(-1),
+            wall_time,
+        )
+        .await;
+        Ok(response)
+    }
+
     async fn refresh_session_state(&self, session_id: i32) -> SessionStatus {
         let mut sessions = self.sessions.lock().await;
         let Some(entry) = sessions.get(&session_id) else {
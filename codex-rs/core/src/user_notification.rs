@@ -59,6 +59,19 @@ pub(crate) enum UserNotification {
         /// The last message sent by the assistant in the turn.
         last_assistant_message: Option<String>,
     },
+
+    /// Every stored account is blocked, so Codex fell back to the account
+    /// that becomes usable first.
+    #[serde(rename_all = "kebab-case")]
+    AccountsExhausted {
+        thread_id: String,
+
+        /// RFC 3339 time at which the first account becomes usable again.
+        retry_at: String,
+
+        /// Emails of the blocked accounts.
+        accounts: Vec<String>,
+    },
 }
 
 #[cfg(test)]
@@ -84,4 +97,22 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn accounts_exhausted_notification() -> Result<()> {
+        let notification = UserNotification::AccountsExhausted {
+            thread_id: "b5f6c1c2-1111-2222-3333-444455556666".to_string(),
+            retry_at: "2025-06-11T17:00:00+00:00".to_string(),
+            accounts: vec![
+                "alice@example.com".to_string(),
+                "bob@example.com".to_string(),
+            ],
+        };
+        let serialized = serde_json::to_string(&notification)?;
+        assert_eq!(
+            serialized,
+            r#"{"type":"accounts-exhausted","thread-id":"b5f6c1c2-1111-2222-3333-444455556666","retry-at":"2025-06-11T17:00:00+00:00","accounts":["alice@example.com","bob@example.com"]}"#
+        );
+        Ok(())
+    }
 }
@@ -796,6 +796,60 @@ async fn apply_patch_shell_failure_propagates_error_and_skips_diff() -> Result<(
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn apply_patch_shell_refuses_file_changed_since_read() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = apply_patch_harness().await?;
+    let target = harness.path("stale.txt");
+    fs::write(&target, "before\n")?;
+
+    // Read through `./stale.txt`, change the file behind the read's back, then
+    // patch it as `stale.txt`.
+    let calls = [
+        ("read-stale", json!(["cat", "./stale.txt"])),
+        (
+            "change-stale",
+            json!(["bash", "-lc", "echo changed > stale.txt"]),
+        ),
+        (
+            "patch-stale",
+            json!([
+                "bash",
+                "-lc",
+                "apply_patch <<'EOF'\n*** Begin Patch\n*** Update File: stale.txt\n@@\n-changed\n+after\n*** End Patch\nEOF\n"
+            ]),
+        ),
+    ];
+    let mut bodies = Vec::new();
+    for (i, (call_id, command)) in calls.iter().enumerate() {
+        let response_id = format!("resp-{}", i + 1);
+        let args = json!({ "command": command, "timeout_ms": 5_000 });
+        bodies.push(sse(vec![
+            ev_response_created(&response_id),
+            ev_function_call(call_id, "shell", &serde_json::to_string(&args)?),
+            ev_completed(&response_id),
+        ]));
+    }
+    bodies.push(sse(vec![
+        ev_assistant_message("msg-1", "done"),
+        ev_completed("resp-4"),
+    ]));
+    mount_sse_sequence(harness.server(), bodies).await;
+
+    harness
+        .submit("patch a file that changed since it was read")
+        .await?;
+
+    let out = harness.function_call_stdout("patch-stale").await;
+    assert!(
+        out.contains("has changed since you last read it"),
+        "expected stale read conflict: {out}"
+    );
+    assert_eq!(fs::read_to_string(&target)?, "changed\n");
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn apply_patch_function_accepts_lenient_heredoc_wrapped_patch() -> Result<()> {
     skip_if_no_network!(Ok(()));
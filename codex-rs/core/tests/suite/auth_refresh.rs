@@ -4,8 +4,10 @@ use base64::Engine;
 use chrono::Duration;
 use chrono::Utc;
 use codex_core::CodexAuth;
+use codex_core::auth::AccountMetadata;
 use codex_core::auth::AuthCredentialsStoreMode;
 use codex_core::auth::AuthDotJson;
+use codex_core::auth::AuthStorageSettings;
 use codex_core::auth::REFRESH_TOKEN_URL_OVERRIDE_ENV_VAR;
 use codex_core::auth::RefreshTokenError;
 use codex_core::auth::load_auth_dot_json;
@@ -185,17 +187,15 @@ impl RefreshTokenTestContext {
             tokens: Some(tokens),
             last_refresh: Some(initial_last_refresh),
             account_state: None,
+            metadata: AccountMetadata::default(),
         };
-        save_auth(
-            codex_home.path(),
-            &auth_dot_json,
-            AuthCredentialsStoreMode::File,
-        )?;
+        let settings = AuthStorageSettings::new(codex_home.path().to_path_buf());
+        save_auth(&settings, &auth_dot_json, AuthCredentialsStoreMode::File)?;
 
         let endpoint = format!("{}/oauth/token", server.uri());
         let env_guard = EnvGuard::set(REFRESH_TOKEN_URL_OVERRIDE_ENV_VAR, endpoint);
 
-        let auth = CodexAuth::from_auth_storage(codex_home.path(), AuthCredentialsStoreMode::File)?
+        let auth = CodexAuth::from_auth_storage(&settings, AuthCredentialsStoreMode::File)?
             .context("auth should load from storage")?;
 
         Ok(Self {
@@ -207,7 +207,8 @@ impl RefreshTokenTestContext {
     }
 
     fn load_auth(&self) -> Result<AuthDotJson> {
-        load_auth_dot_json(self.codex_home.path(), AuthCredentialsStoreMode::File)
+        let settings = AuthStorageSettings::new(self.codex_home.path().to_path_buf());
+        load_auth_dot_json(&settings, AuthCredentialsStoreMode::File)
             .context("load auth.json")?
             .context("auth.json should exist")
     }
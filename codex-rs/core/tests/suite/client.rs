@@ -535,12 +535,14 @@ async fn prefers_apikey_when_config_prefers_apikey_even_with_chatgpt_tokens() {
     let mut config = load_default_config_for_test(&codex_home);
     config.model_provider = model_provider;
 
-    let auth_manager =
-        match CodexAuth::from_auth_storage(codex_home.path(), AuthCredentialsStoreMode::File) {
-            Ok(Some(auth)) => codex_core::AuthManager::from_auth_for_testing(auth),
-            Ok(None) => panic!("No CodexAuth found in codex_home"),
-            Err(e) => panic!("Failed to load CodexAuth: {e}"),
-        };
+    let auth_manager = match CodexAuth::from_auth_storage(
+        &config.auth_storage_settings(),
+        AuthCredentialsStoreMode::File,
+    ) {
+        Ok(Some(auth)) => codex_core::AuthManager::from_auth_for_testing(auth),
+        Ok(None) => panic!("No CodexAuth found in codex_home"),
+        Err(e) => panic!("Failed to load CodexAuth: {e}"),
+    };
     let conversation_manager = ConversationManager::new(auth_manager, SessionSource::Exec);
     let NewConversation {
         conversation: codex,
@@ -1280,6 +1282,115 @@ async fn usage_limit_error_emits_rate_limit_event() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Writes a rotation account to `CODEX_HOME/auth/<email>.json` whose access
+/// token is `<name>-access`, last used at `mtime`.
+#[expect(clippy::unwrap_used)]
+fn write_rotation_account(codex_home: &TempDir, email: &str, name: &str, mtime: i64) {
+    use base64::Engine as _;
+
+    let b64 = |b: &[u8]| base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(b);
+    let header = json!({ "alg": "none", "typ": "JWT" });
+    let payload = json!({
+        "email": email,
+        "https://api.openai.com/auth": {
+            "chatgpt_plan_type": "pro",
+            "chatgpt_account_id": format!("{name}-account")
+        }
+    });
+    let fake_jwt = format!(
+        "{}.{}.{}",
+        b64(&serde_json::to_vec(&header).unwrap()),
+        b64(&serde_json::to_vec(&payload).unwrap()),
+        b64(b"sig")
+    );
+    let auth_json = json!({
+        "OPENAI_API_KEY": null,
+        "tokens": {
+            "id_token": fake_jwt,
+            "access_token": format!("{name}-access"),
+            "refresh_token": format!("{name}-refresh"),
+            "account_id": format!("{name}-account"),
+        },
+        "last_refresh": chrono::Utc::now(),
+    });
+
+    let auth_dir = codex_home.path().join("auth");
+    std::fs::create_dir_all(&auth_dir).unwrap();
+    let path = auth_dir.join(format!("{email}.json"));
+    std::fs::write(&path, serde_json::to_string_pretty(&auth_json).unwrap()).unwrap();
+    filetime::set_file_mtime(&path, filetime::FileTime::from_unix_time(mtime, 0)).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn usage_limit_rotates_to_next_stored_account() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .and(header_regex("Authorization", r"Bearer alice-access"))
+        .respond_with(ResponseTemplate::new(429).set_body_json(json!({
+            "error": {
+                "type": "usage_limit_reached",
+                "message": "limit reached",
+                "resets_at": chrono::Utc::now().timestamp() + 3600,
+                "plan_type": "pro"
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .and(header_regex("Authorization", r"Bearer bob-access"))
+        .and(header_regex("chatgpt-account-id", r"bob-account"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_raw(sse_completed("resp1"), "text/event-stream"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let codex_home = TempDir::new()?;
+    write_rotation_account(&codex_home, "alice@example.com", "alice", 1);
+    write_rotation_account(&codex_home, "bob@example.com", "bob", 5);
+
+    let mut config = load_default_config_for_test(&codex_home);
+    config.model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    let auth_manager = codex_core::AuthManager::shared(
+        config.auth_storage_settings(),
+        false,
+        AuthCredentialsStoreMode::File,
+    );
+    let conversation_manager = ConversationManager::new(auth_manager, SessionSource::Exec);
+    let NewConversation {
+        conversation: codex,
+        ..
+    } = conversation_manager.new_conversation(config).await?;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await?;
+
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let alice = std::fs::read_to_string(codex_home.path().join("auth/alice@example.com.json"))?;
+    assert!(
+        alice.contains("usage_limit"),
+        "usage limit should be recorded on the limited account: {alice}"
+    );
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn context_window_error_sets_total_tokens_to_model_window() -> anyhow::Result<()> {
     skip_if_no_network!(Ok(()));
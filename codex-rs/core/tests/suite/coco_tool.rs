@@ -0,0 +1,1594 @@
+use std::time::Duration;
+
+use codex_core::config::types::SubAgentProfile;
+use codex_core::features::Feature;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::config_types::SandboxMode;
+use codex_protocol::user_input::UserInput;
+use core_test_support::fs_wait;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_completed_with_tokens;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_reasoning_item;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use core_test_support::wait_for_event_match;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+
+/// The `coco` function tool runs a sub-agent conversation on the given prompt
+/// and hands its final message back to the parent as the tool output.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_returns_sub_agent_final_message() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "coco-call";
+    let arguments = json!({
+        "prompt": "Summarize the README",
+        "timeout_ms": 60_000,
+    })
+    .to_string();
+    let server = start_mock_server().await;
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-sub", "The README is empty."),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+        })
+        .build(&server)
+        .await?;
+    test.submit_turn("delegate the summary").await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    let tool_names: Vec<String> = requests[0].body_json()["tools"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|tool| tool.get("name").and_then(Value::as_str))
+        .map(str::to_string)
+        .collect();
+    assert!(tool_names.contains(&"coco".to_string()), "{tool_names:?}");
+    assert!(
+        requests[1]
+            .message_input_texts("user")
+            .contains(&"Summarize the README".to_string())
+    );
+
+    let output = requests[2]
+        .function_call_output_text(call_id)
+        .expect("coco output present");
+    let output: Value = serde_json::from_str(&output)?;
+    let text = output["output"].as_str().unwrap_or_default();
+    let (message, session_note) = text.split_once("\n\n").unwrap_or((text, ""));
+    assert_eq!(message, "The README is empty.");
+    assert!(session_note.starts_with("[coco session "), "{text}");
+    assert!(session_note.contains("coco resume "), "{text}");
+    assert_eq!(output["metadata"]["exit_code"], 0);
+    Ok(())
+}
+
+/// A sub-agent that goes over `coco.max_tokens` is cancelled and the parent
+/// gets the partial transcript instead of a final answer.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_stops_sub_agent_over_token_budget() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "coco-call";
+    let arguments = json!({
+        "prompt": "Audit every file",
+        "timeout_ms": 60_000,
+    })
+    .to_string();
+    let server = start_mock_server().await;
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-sub", "Checked src/ so far."),
+                ev_completed_with_tokens("resp-2", 5_000),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+            config.coco.max_tokens = Some(100);
+        })
+        .build(&server)
+        .await?;
+    test.submit_turn("delegate the audit").await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    let output = requests[2]
+        .function_call_output_text(call_id)
+        .expect("coco output present");
+    assert!(
+        output.contains("using 5000 tokens, over its budget of 100"),
+        "{output}"
+    );
+    assert!(
+        output.contains("assistant: Checked src/ so far."),
+        "{output}"
+    );
+    Ok(())
+}
+
+/// A sub-agent still running when `coco.timeout_ms` elapses is cancelled and
+/// the parent gets what it produced so far.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_times_out_with_partial_output() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "coco-call";
+    let arguments = json!({ "prompt": "Wait for the build" }).to_string();
+    let sleep_arguments = json!({ "command": ["sleep", "10"] }).to_string();
+    let server = start_mock_server().await;
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-sub", "Waiting on the build."),
+                ev_function_call("sleep-call", "shell", &sleep_arguments),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+            config.coco.timeout_ms = 1_000;
+            config.sandbox_policy = SandboxPolicy::DangerFullAccess;
+        })
+        .build(&server)
+        .await?;
+    test.submit_turn("delegate the wait").await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    let output = requests[2]
+        .function_call_output_text(call_id)
+        .expect("coco output present");
+    assert!(
+        output.starts_with("coco sub-agent timed out after 1.000s; partial results follow."),
+        "{output}"
+    );
+    assert!(
+        output.contains("assistant: Waiting on the build."),
+        "{output}"
+    );
+    Ok(())
+}
+
+/// `files` are read relative to the working directory and handed to the
+/// sub-agent as extra inputs after the prompt.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_attaches_files_to_sub_agent_prompt() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "coco-call";
+    let arguments = json!({
+        "prompt": "Review the notes",
+        "files": ["notes.txt"],
+        "timeout_ms": 60_000,
+    })
+    .to_string();
+    let server = start_mock_server().await;
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-sub", "The notes look fine."),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+        })
+        .build(&server)
+        .await?;
+    let notes = test.workspace_path("notes.txt");
+    std::fs::write(&notes, "ship on friday")?;
+    test.submit_turn("delegate the review").await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    let sub_agent_inputs = requests[1].message_input_texts("user");
+    assert!(
+        sub_agent_inputs.contains(&format!(
+            "<file path=\"{}\">\nship on friday\n</file>",
+            notes.display()
+        )),
+        "{sub_agent_inputs:?}"
+    );
+    Ok(())
+}
+
+/// With `output_schema` the sub-agent is asked for JSON matching the schema
+/// and the validated JSON comes back as the tool result.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_returns_structured_output_matching_schema() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "coco-call";
+    let arguments = json!({
+        "prompt": "Grade the change",
+        "output_schema": "schema.json",
+        "timeout_ms": 60_000,
+    })
+    .to_string();
+    let server = start_mock_server().await;
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-sub", r#"{"verdict":"pass"}"#),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+        })
+        .build(&server)
+        .await?;
+    let schema = json!({
+        "type": "object",
+        "properties": { "verdict": { "enum": ["pass", "fail"] } },
+        "required": ["verdict"],
+        "additionalProperties": false,
+    });
+    std::fs::write(test.workspace_path("schema.json"), schema.to_string())?;
+    test.submit_turn("delegate the grading").await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[1].body_json()["text"]["format"]["schema"], schema);
+
+    let output = requests[2]
+        .inputs_of_type("function_call_output")
+        .into_iter()
+        .find(|item| item["call_id"] == call_id)
+        .expect("coco output present");
+    assert_eq!(output["output"][0]["text"], r#"{"verdict":"pass"}"#);
+    Ok(())
+}
+
+/// The sub-agent conversation is recorded as its own rollout whose session
+/// meta points back at the parent conversation and the `coco` call.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_persists_sub_agent_rollout_linked_to_call() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "coco-linked-call";
+    let arguments = json!({
+        "prompt": "List the modules",
+        "timeout_ms": 60_000,
+    })
+    .to_string();
+    let server = start_mock_server().await;
+    mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-sub", "There are two modules."),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+        })
+        .build(&server)
+        .await?;
+    test.submit_turn("delegate the listing").await?;
+
+    let sessions_dir = test.home.path().join("sessions");
+    // Only the sub-agent's session meta (its first line) names the call.
+    let rollout =
+        fs_wait::wait_for_matching_file(&sessions_dir, Duration::from_secs(10), move |path| {
+            std::fs::read_to_string(path).is_ok_and(|contents| {
+                contents
+                    .lines()
+                    .next()
+                    .is_some_and(|meta| meta.contains(call_id))
+            })
+        })
+        .await?;
+    let contents = std::fs::read_to_string(&rollout)?;
+    let meta: Value = serde_json::from_str(contents.lines().next().unwrap_or_default())?;
+    let source = &meta["payload"]["source"]["subagent"]["coco"];
+    assert_eq!(source["call_id"], call_id);
+    assert_eq!(source["depth"], 1);
+    assert_eq!(
+        source["parent_conversation_id"],
+        test.session_configured.session_id.to_string()
+    );
+    Ok(())
+}
+
+/// `resume` only reattaches to sub-agent conversations this conversation
+/// started; its own session id is rejected.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_resume_rejects_foreign_conversation() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+        })
+        .build(&server)
+        .await?;
+    let own_id = test.session_configured.session_id.to_string();
+
+    let call_id = "coco-resume-call";
+    let arguments = json!({
+        "prompt": "keep going",
+        "resume": own_id,
+    })
+    .to_string();
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+    test.submit_turn("continue the delegate").await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    let output = requests[1]
+        .function_call_output_text(call_id)
+        .expect("coco output present");
+    assert_eq!(
+        output,
+        format!("no coco sub-agent conversation {own_id} was started from this conversation")
+    );
+    Ok(())
+}
+
+/// With `coco.show_reasoning`, the sub-agent's reasoning summaries show up as
+/// `thinking:` lines in the streamed log.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_streams_reasoning_summaries_when_enabled() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "coco-call";
+    let arguments = json!({
+        "prompt": "Summarize the README",
+        "timeout_ms": 60_000,
+    })
+    .to_string();
+    let server = start_mock_server().await;
+    mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_reasoning_item("reasoning-sub", &["Opening the README"], &[]),
+                ev_assistant_message("msg-sub", "The README is empty."),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+            config.coco.show_reasoning = true;
+        })
+        .build(&server)
+        .await?;
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "delegate the summary".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd_path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+
+    let log = wait_for_event_match(&test.codex, |event| match event {
+        EventMsg::ExecCommandEnd(end) if end.call_id == call_id => {
+            Some(end.aggregated_output.clone())
+        }
+        _ => None,
+    })
+    .await;
+    assert!(log.contains("thinking: Opening the README"), "{log}");
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+    Ok(())
+}
+
+/// Interrupting the parent turn shuts down a sub-agent that is still running
+/// and says so in the `coco` call's output.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_stops_sub_agent_when_parent_turn_is_aborted() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "coco-call";
+    let arguments = json!({ "prompt": "Wait for the build" }).to_string();
+    let sleep_arguments = json!({ "command": ["sleep", "10"] }).to_string();
+    let server = start_mock_server().await;
+    mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_function_call("sleep-call", "shell", &sleep_arguments),
+                ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+            config.sandbox_policy = SandboxPolicy::DangerFullAccess;
+        })
+        .build(&server)
+        .await?;
+    test.codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "delegate the wait".into(),
+            }],
+        })
+        .await?;
+
+    let coco_output = |event: &EventMsg| match event {
+        EventMsg::ExecCommandOutputDelta(delta) if delta.call_id == call_id => {
+            Some(String::from_utf8_lossy(&delta.chunk).into_owned())
+        }
+        _ => None,
+    };
+    wait_for_event(&test.codex, |event| {
+        coco_output(event)
+            .is_some_and(|chunk| chunk.lines().any(|line| line.starts_with("exec: sleep 10")))
+    })
+    .await;
+    test.codex.submit(Op::Interrupt).await?;
+
+    // The notice and the parent's TurnAborted may arrive in either order.
+    let mut aborted = false;
+    let mut notified = false;
+    wait_for_event(&test.codex, |event| {
+        aborted |= matches!(event, EventMsg::TurnAborted(_));
+        notified |= coco_output(event).is_some_and(|line| line == "sub-agent aborted by parent\n");
+        aborted && notified
+    })
+    .await;
+    Ok(())
+}
+
+/// Tokens a sub-agent spends are added to the parent session's totals, while
+/// the parent's last-turn usage keeps describing its own context window.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_adds_sub_agent_tokens_to_parent_usage() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "coco-call";
+    let arguments = json!({ "prompt": "Summarize the README" }).to_string();
+    let server = start_mock_server().await;
+    mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed_with_tokens("resp-1", 100),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-sub", "The README is empty."),
+                ev_completed_with_tokens("resp-2", 5_000),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed_with_tokens("resp-3", 200),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+        })
+        .build(&server)
+        .await?;
+    test.codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "delegate the summary".into(),
+            }],
+        })
+        .await?;
+
+    let mut last_usage = None;
+    wait_for_event(&test.codex, |event| {
+        if let EventMsg::TokenCount(count) = event
+            && let Some(info) = &count.info
+        {
+            last_usage = Some((
+                info.total_token_usage.total_tokens,
+                info.last_token_usage.total_tokens,
+            ));
+        }
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+    assert_eq!(last_usage, Some((5_300, 200)));
+    Ok(())
+}
+
+/// Asking the same delegated question twice in a session reuses the first
+/// sub-agent's answer instead of running another one.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_reuses_cached_result_for_repeated_prompt() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let arguments = json!({ "prompt": "Summarize the README" }).to_string();
+    let server = start_mock_server().await;
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call("coco-1", "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-sub", "The README is empty."),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_function_call("coco-2", "coco", &arguments),
+                ev_completed("resp-3"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-4"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-4"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+        })
+        .build(&server)
+        .await?;
+    test.submit_turn("delegate the summary twice").await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 4);
+    let output = requests[3]
+        .function_call_output_text("coco-2")
+        .expect("second coco output present");
+    let output: Value = serde_json::from_str(&output)?;
+    let text = output["output"].as_str().unwrap_or_default();
+    assert!(text.starts_with("The README is empty.\n\n"), "{text}");
+    assert!(
+        text.contains("[cached result of an earlier identical coco call"),
+        "{text}"
+    );
+    Ok(())
+}
+
+/// Tools disabled in `coco.disabled_tools` or by the call itself are not
+/// offered to the sub-agent, while the parent keeps them.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_withholds_disabled_tools_from_sub_agent() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "coco-call";
+    let arguments = json!({
+        "prompt": "Summarize the README",
+        "disabled_tools": ["view_image"],
+    })
+    .to_string();
+    let server = start_mock_server().await;
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-sub", "The README is empty."),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+            config.features.enable(Feature::ViewImageTool);
+            config.coco.disabled_tools = vec!["coco".to_string()];
+        })
+        .build(&server)
+        .await?;
+    test.submit_turn("delegate the summary").await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    let tool_names = |index: usize| -> Vec<String> {
+        requests[index].body_json()["tools"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|tool| tool.get("name").and_then(Value::as_str))
+            .map(str::to_string)
+            .collect()
+    };
+    let parent_tools = tool_names(0);
+    assert!(
+        parent_tools.contains(&"coco".to_string()),
+        "{parent_tools:?}"
+    );
+    assert!(
+        parent_tools.contains(&"view_image".to_string()),
+        "{parent_tools:?}"
+    );
+    let sub_agent_tools = tool_names(1);
+    assert!(
+        !sub_agent_tools.contains(&"coco".to_string()),
+        "{sub_agent_tools:?}"
+    );
+    assert!(
+        !sub_agent_tools.contains(&"view_image".to_string()),
+        "{sub_agent_tools:?}"
+    );
+    Ok(())
+}
+
+/// A sub-agent that may write to its working directory cannot be moved
+/// outside the directories the parent session may write to.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_rejects_cwd_outside_writable_roots() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let sandbox_policy = SandboxPolicy::WorkspaceWrite {
+        writable_roots: Vec::new(),
+        network_access: false,
+        exclude_tmpdir_env_var: true,
+        exclude_slash_tmp: true,
+    };
+    let outside = tempfile::tempdir()?;
+    let call_id = "coco-call";
+    let arguments = json!({
+        "prompt": "Fix the build",
+        "cwd": outside.path(),
+    })
+    .to_string();
+    let server = start_mock_server().await;
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config({
+            let sandbox_policy = sandbox_policy.clone();
+            move |config| {
+                config.features.enable(Feature::CocoTool);
+                config.sandbox_policy = sandbox_policy;
+            }
+        })
+        .build(&server)
+        .await?;
+    test.submit_turn_with_policy("delegate the fix", sandbox_policy)
+        .await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    let output = requests[1]
+        .function_call_output_text(call_id)
+        .expect("coco output present");
+    assert_eq!(
+        output,
+        format!(
+            "coco --cwd {} is outside the directories this session may write to",
+            outside.path().display()
+        )
+    );
+    Ok(())
+}
+
+/// A call cannot hand its sub-agent an approval policy that asks the user
+/// less often than the parent turn's.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_rejects_looser_approval_policy() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "coco-call";
+    let arguments = json!({
+        "prompt": "Fix the build",
+        "approval": "never",
+    })
+    .to_string();
+    let server = start_mock_server().await;
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+        })
+        .build(&server)
+        .await?;
+    test.submit_turn_with_policies(
+        "delegate the fix",
+        AskForApproval::OnRequest,
+        SandboxPolicy::DangerFullAccess,
+    )
+    .await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    let output = requests[1]
+        .function_call_output_text(call_id)
+        .expect("coco output present");
+    assert_eq!(
+        output,
+        "coco --approval never is less restrictive than this session's approval policy"
+    );
+    Ok(())
+}
+
+/// An agent profile cannot loosen the parent turn's sandbox either; its
+/// `sandbox_mode` is held to the same limit as `--sandbox`.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_rejects_profile_with_looser_sandbox() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "coco-call";
+    let arguments = json!({
+        "prompt": "Fix the build",
+        "agent": "fixer",
+    })
+    .to_string();
+    let server = start_mock_server().await;
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+            config.agents.insert(
+                "fixer".to_string(),
+                SubAgentProfile {
+                    sandbox_mode: Some(SandboxMode::DangerFullAccess),
+                    ..Default::default()
+                },
+            );
+        })
+        .build(&server)
+        .await?;
+    test.submit_turn_with_policies(
+        "delegate the fix",
+        AskForApproval::OnRequest,
+        SandboxPolicy::new_read_only_policy(),
+    )
+    .await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    let output = requests[1]
+        .function_call_output_text(call_id)
+        .expect("coco output present");
+    assert_eq!(
+        output,
+        "coco agent `fixer` sets sandbox_mode danger-full-access, which is less restrictive than this session's sandbox"
+    );
+    Ok(())
+}
+
+/// The sub-agent's streamed log is tagged with its depth and conversation so
+/// clients can nest it under the `coco` call.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_tags_streamed_log_with_sub_agent() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "coco-call";
+    let arguments = json!({ "prompt": "Summarize the README" }).to_string();
+    let server = start_mock_server().await;
+    mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-sub", "The README is empty."),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+        })
+        .build(&server)
+        .await?;
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "delegate the summary".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd_path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+
+    let source = wait_for_event_match(&test.codex, |event| match event {
+        EventMsg::ExecCommandOutputDelta(delta)
+            if delta.call_id == call_id
+                && String::from_utf8_lossy(&delta.chunk)
+                    .lines()
+                    .any(|line| line.starts_with("assistant: ")) =>
+        {
+            Some(delta.sub_agent.clone())
+        }
+        _ => None,
+    })
+    .await
+    .expect("sub-agent output is tagged");
+    assert_eq!(source.depth, 1);
+    assert!(source.conversation_id.is_some());
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+    Ok(())
+}
+
+/// `coco --batch` runs one sub-agent per piped prompt and returns their
+/// results as a JSON array.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_batch_runs_one_sub_agent_per_piped_prompt() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "batch-call";
+    let arguments = json!({
+        "command": ["bash", "-lc", "cat prompts.txt | coco --batch"],
+    })
+    .to_string();
+    let server = start_mock_server().await;
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "shell", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-sub-1", "core is the engine."),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_assistant_message("msg-sub-2", "tui is the UI."),
+                ev_completed("resp-3"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-4"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-4"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+        })
+        .build(&server)
+        .await?;
+    std::fs::write(
+        test.workspace_path("prompts.txt"),
+        "Describe core\n{\"prompt\": \"Describe tui\"}\n",
+    )?;
+    test.submit_turn("describe each crate").await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 4);
+    assert!(
+        requests[2]
+            .message_input_texts("user")
+            .contains(&"Describe tui".to_string())
+    );
+    let output = requests[3]
+        .function_call_output_text(call_id)
+        .expect("batch output present");
+    let output: Value = serde_json::from_str(&output)?;
+    assert_eq!(
+        output,
+        json!([
+            { "prompt": "Describe core", "result": "core is the engine.", "exit_code": 0 },
+            { "prompt": "Describe tui", "result": "tui is the UI.", "exit_code": 0 },
+        ])
+    );
+    Ok(())
+}
+
+/// The command piped into `coco --batch` goes through the turn's approval
+/// policy like any other command, and does not run when the user denies it.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_batch_producer_needs_approval() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "batch-call";
+    let arguments = json!({
+        "command": ["bash", "-lc", "touch ran | coco --batch"],
+    })
+    .to_string();
+    let server = start_mock_server().await;
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "shell", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+        })
+        .build(&server)
+        .await?;
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "describe each crate".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::UnlessTrusted,
+            sandbox_policy: SandboxPolicy::new_read_only_policy(),
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+    let approval = wait_for_event_match(&test.codex, |event| match event {
+        EventMsg::ExecApprovalRequest(request) => Some(request.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(approval.command, vec!["touch", "ran"]);
+    test.codex
+        .submit(Op::ExecApproval {
+            id: "0".into(),
+            decision: ReviewDecision::Denied,
+        })
+        .await?;
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(
+        requests[1].function_call_output_text(call_id).as_deref(),
+        Some("failed to run `touch ran` for coco --batch: rejected by user")
+    );
+    assert!(!test.workspace_path("ran").exists());
+    Ok(())
+}
+
+/// A task that ran sub-agents ends with a summary of the session's sub-agent
+/// work, sent before `TaskComplete`.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_reports_sub_agent_summary_at_task_completion() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "coco-call";
+    let arguments = json!({ "prompt": "Summarize the README" }).to_string();
+    let server = start_mock_server().await;
+    mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-sub", "The README is empty."),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+        })
+        .build(&server)
+        .await?;
+    test.codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "delegate the summary".into(),
+            }],
+        })
+        .await?;
+
+    let summary = wait_for_event_match(&test.codex, |event| match event {
+        EventMsg::SubAgentSummary(summary) => Some(summary.clone()),
+        EventMsg::TaskComplete(_) => panic!("task completed without a sub-agent summary"),
+        _ => None,
+    })
+    .await;
+    assert_eq!(summary.invocations, 1);
+    assert_eq!(summary.succeeded, 1);
+    assert_eq!(summary.failed, 0);
+    assert_eq!(summary.timed_out, 0);
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+    Ok(())
+}
+
+/// A sub-agent whose model stream drops is started over, and the parent gets
+/// the answer from the attempt that succeeded.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_retries_sub_agent_after_transient_failure() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "coco-call";
+    let arguments = json!({ "prompt": "Summarize the README" }).to_string();
+    let server = start_mock_server().await;
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![ev_response_created("resp-2")]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_assistant_message("msg-sub", "The README is empty."),
+                ev_completed("resp-3"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-4"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-4"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+            config.model_provider.stream_max_retries = Some(0);
+            config.coco.max_retries = 1;
+        })
+        .build(&server)
+        .await?;
+    test.submit_turn("delegate the summary").await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 4);
+    assert!(
+        requests[2]
+            .message_input_texts("user")
+            .contains(&"Summarize the README".to_string())
+    );
+    let output = requests[3]
+        .function_call_output_text(call_id)
+        .expect("coco output present");
+    let output: Value = serde_json::from_str(&output)?;
+    let text = output["output"].as_str().unwrap_or_default();
+    assert!(text.starts_with("The README is empty."), "{text}");
+    assert_eq!(output["metadata"]["exit_code"], 0);
+    Ok(())
+}
+
+/// Once `coco.max_retries` fresh attempts have also failed transiently, the
+/// parent gets the last failure along with the log of every attempt.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_stops_retrying_sub_agent_at_max_retries() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let call_id = "coco-call";
+    let arguments = json!({ "prompt": "Summarize the README" }).to_string();
+    let server = start_mock_server().await;
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "coco", &arguments),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![ev_response_created("resp-2")]),
+            sse(vec![ev_response_created("resp-3")]),
+            sse(vec![
+                ev_response_created("resp-4"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-4"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+            config.model_provider.stream_max_retries = Some(0);
+            config.coco.max_retries = 1;
+        })
+        .build(&server)
+        .await?;
+    test.submit_turn("delegate the summary").await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 4);
+    let output = requests[3]
+        .function_call_output_text(call_id)
+        .expect("coco output present");
+    assert!(
+        output.starts_with("stream disconnected before completion"),
+        "{output}"
+    );
+    assert!(
+        output.contains("sub-agent attempt 1 failed: stream disconnected before completion"),
+        "{output}"
+    );
+    assert!(!output.contains("sub-agent attempt 2 failed"), "{output}");
+    Ok(())
+}
+
+/// A sub-agent at `coco.max_depth` cannot start a sub-agent of its own; it
+/// gets a model-facing error and finishes the task itself.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_rejects_nesting_beyond_max_depth() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let outer_call = "coco-outer";
+    let inner_call = "coco-inner";
+    let server = start_mock_server().await;
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(
+                    outer_call,
+                    "coco",
+                    &json!({ "prompt": "Summarize the README" }).to_string(),
+                ),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_function_call(
+                    inner_call,
+                    "coco",
+                    &json!({ "prompt": "Summarize it for me" }).to_string(),
+                ),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_assistant_message("msg-sub", "The README is empty."),
+                ev_completed("resp-3"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-4"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-4"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+            config.coco.max_depth = 1;
+        })
+        .build(&server)
+        .await?;
+    test.submit_turn("delegate the summary").await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 4);
+    assert_eq!(
+        requests[2].function_call_output_text(inner_call).as_deref(),
+        Some(
+            "coco sub-agents may nest at most 1 level(s) deep and this conversation is already at depth 1; complete the task directly instead of delegating it."
+        )
+    );
+    let output = requests[3]
+        .function_call_output_text(outer_call)
+        .expect("coco output present");
+    let output: Value = serde_json::from_str(&output)?;
+    let text = output["output"].as_str().unwrap_or_default();
+    assert!(text.starts_with("The README is empty."), "{text}");
+    Ok(())
+}
+
+/// `model` runs the sub-agent on another model, as long as it is in
+/// `coco.allowed_models`.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_runs_sub_agent_on_allowed_model() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let denied_call = "coco-denied";
+    let allowed_call = "coco-allowed";
+    let server = start_mock_server().await;
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(
+                    denied_call,
+                    "coco",
+                    &json!({ "prompt": "Summarize the README", "model": "gpt-5" }).to_string(),
+                ),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_function_call(
+                    allowed_call,
+                    "coco",
+                    &json!({ "prompt": "Summarize the README", "model": "o4-mini" }).to_string(),
+                ),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_assistant_message("msg-sub", "The README is empty."),
+                ev_completed("resp-3"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-4"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-4"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+            config.coco.allowed_models = Some(vec!["o4-mini".to_string()]);
+        })
+        .build(&server)
+        .await?;
+    test.submit_turn("delegate the summary").await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 4);
+    assert_eq!(
+        requests[1]
+            .function_call_output_text(denied_call)
+            .as_deref(),
+        Some("coco --model gpt-5 is not allowed; choose one of: o4-mini")
+    );
+    assert_eq!(requests[2].body_json()["model"], "o4-mini");
+    assert_ne!(requests[3].body_json()["model"], "o4-mini");
+    let output = requests[3]
+        .function_call_output_text(allowed_call)
+        .expect("coco output present");
+    let output: Value = serde_json::from_str(&output)?;
+    let text = output["output"].as_str().unwrap_or_default();
+    assert!(text.starts_with("The README is empty."), "{text}");
+    Ok(())
+}
+
+/// With `coco.max_concurrent = 1` a sub-agent that failed gives its slot
+/// back, so the next `coco` call starts instead of waiting forever.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn coco_tool_releases_concurrency_slot_after_failure() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let failed_call = "coco-failed";
+    let next_call = "coco-next";
+    let server = start_mock_server().await;
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(
+                    failed_call,
+                    "coco",
+                    &json!({ "prompt": "Summarize the README" }).to_string(),
+                ),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![ev_response_created("resp-2")]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_function_call(
+                    next_call,
+                    "coco",
+                    &json!({ "prompt": "Summarize the README again" }).to_string(),
+                ),
+                ev_completed("resp-3"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-4"),
+                ev_assistant_message("msg-sub", "The README is empty."),
+                ev_completed("resp-4"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-5"),
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-5"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::CocoTool);
+            config.model_provider.stream_max_retries = Some(0);
+            config.coco.max_retries = 0;
+            config.coco.max_concurrent = 1;
+        })
+        .build(&server)
+        .await?;
+    test.submit_turn("delegate the summary").await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 5);
+    let failed = requests[2]
+        .function_call_output_text(failed_call)
+        .expect("failed coco output present");
+    assert!(
+        failed.starts_with("stream disconnected before completion"),
+        "{failed}"
+    );
+    let output = requests[4]
+        .function_call_output_text(next_call)
+        .expect("coco output present");
+    let output: Value = serde_json::from_str(&output)?;
+    let text = output["output"].as_str().unwrap_or_default();
+    assert!(text.starts_with("The README is empty."), "{text}");
+    Ok(())
+}
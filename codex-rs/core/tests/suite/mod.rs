@@ -22,6 +22,7 @@ mod approvals;
 mod auth_refresh;
 mod cli_stream;
 mod client;
+mod coco_tool;
 mod codex_delegate;
 mod compact;
 mod compact_remote;
@@ -167,12 +167,26 @@ async fn model_selects_expected_tools() {
             "read_mcp_resource".to_string(),
             "update_plan".to_string(),
             "apply_patch".to_string(),
+            "verify_patch".to_string(),
+            "glob".to_string(),
+            "grep".to_string(),
             "read_file".to_string(),
             "write_file".to_string(),
             "replace".to_string(),
-            "delete".to_string()
+            "replace_regex".to_string(),
+            "multi_edit".to_string(),
+            "insert_at_line".to_string(),
+            "append_to_file".to_string(),
+            "delete".to_string(),
+            "check_job".to_string(),
+            "kill_job".to_string(),
+            "git_status".to_string(),
+            "git_diff".to_string(),
+            "git_commit".to_string(),
+            "git_branch".to_string(),
+            "get_turn_diff".to_string()
         ],
-        "qwen models should expose read/write/replace/delete tools",
+        "qwen models should expose the file editing tools",
     );
 
     let deepseek_tools = collect_tool_identifiers_for_model("deepseek-test-model").await;
@@ -185,11 +199,25 @@ async fn model_selects_expected_tools() {
             "read_mcp_resource".to_string(),
             "update_plan".to_string(),
             "apply_patch".to_string(),
+            "verify_patch".to_string(),
+            "glob".to_string(),
+            "grep".to_string(),
             "read_file".to_string(),
             "write_file".to_string(),
             "replace".to_string(),
-            "delete".to_string()
+            "replace_regex".to_string(),
+            "multi_edit".to_string(),
+            "insert_at_line".to_string(),
+            "append_to_file".to_string(),
+            "delete".to_string(),
+            "check_job".to_string(),
+            "kill_job".to_string(),
+            "git_status".to_string(),
+            "git_diff".to_string(),
+            "git_commit".to_string(),
+            "git_branch".to_string(),
+            "get_turn_diff".to_string()
         ],
-        "deepseek models should expose read/write/replace/delete tools",
+        "deepseek models should expose the file editing tools",
     );
 }
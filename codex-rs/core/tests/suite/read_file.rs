@@ -36,7 +36,7 @@ async fn read_file_tool_returns_requested_lines() -> anyhow::Result<()> {
         .function_call_output_content_and_success(call_id)
         .expect("output present");
     let output_text = output_text_opt.expect("output text present");
-    assert_eq!(output_text, "L2: second\nL3: third");
+    assert_eq!(output_text, "L2: second\nL3: third\n[lines 2-3 of 4]");
 
     Ok(())
 }
@@ -0,0 +1,69 @@
+//! `--max-tokens` / `--max-cost` limits on what a single `codex exec` run may
+//! spend.
+
+use codex_core::config::Config;
+use codex_core::config::types::ModelPrice;
+use codex_core::protocol::TokenUsage;
+
+pub(crate) struct RunBudget {
+    max_tokens: Option<i64>,
+    max_cost: Option<(f64, ModelPrice)>,
+}
+
+impl RunBudget {
+    /// Errors when `max_cost` is set but `model_prices` has no price for the
+    /// configured model.
+    pub(crate) fn new(
+        max_tokens: Option<i64>,
+        max_cost: Option<f64>,
+        config: &Config,
+    ) -> Result<Self, String> {
+        let max_cost = match max_cost {
+            Some(max_cost) => {
+                let price = config.model_prices.get(&config.model).ok_or_else(|| {
+                    format!(
+                        "--max-cost needs a price for model `{}`; add it under [model_prices] in config.toml",
+                        config.model
+                    )
+                })?;
+                Some((max_cost, *price))
+            }
+            None => None,
+        };
+        Ok(Self {
+            max_tokens,
+            max_cost,
+        })
+    }
+
+    /// Checks the session's total usage so far, returning a message naming
+    /// the limit it went over.
+    pub(crate) fn check(&self, usage: &TokenUsage) -> Result<(), String> {
+        let used = usage.blended_total();
+        if let Some(max_tokens) = self.max_tokens
+            && used > max_tokens
+        {
+            return Err(format!(
+                "Stopping: the run used {used} tokens, over its --max-tokens budget of {max_tokens}."
+            ));
+        }
+        if let Some((max_cost, price)) = &self.max_cost {
+            let spent = cost(price, usage);
+            if spent > *max_cost {
+                return Err(format!(
+                    "Stopping: the run cost an estimated ${spent:.4}, over its --max-cost budget of ${max_cost:.4}."
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Estimated cost of `usage` in US dollars.
+fn cost(price: &ModelPrice, usage: &TokenUsage) -> f64 {
+    let cached_price = price.cached_input_per_mtok.unwrap_or(price.input_per_mtok);
+    (usage.non_cached_input() as f64 * price.input_per_mtok
+        + usage.cached_input() as f64 * cached_price
+        + usage.output_tokens.max(0) as f64 * price.output_per_mtok)
+        / 1_000_000.0
+}
@@ -63,9 +63,40 @@ pub struct Cli {
     pub add_dir: Vec<PathBuf>,
 
     /// Path to a JSON Schema file describing the model's final response shape.
+    /// The run fails when the final message does not match it.
     #[arg(long = "output-schema", value_name = "FILE")]
     pub output_schema: Option<PathBuf>,
 
+    /// Stop the run once it has used more than this many tokens (non-cached
+    /// input plus output), exiting with status 3.
+    #[arg(long = "max-tokens", value_name = "TOKENS")]
+    pub max_tokens: Option<i64>,
+
+    /// Stop the run once its estimated cost in US dollars exceeds this,
+    /// exiting with status 3. Prices come from `model_prices` in config.toml.
+    #[arg(long = "max-cost", value_name = "USD")]
+    pub max_cost: Option<f64>,
+
+    /// Resume a turn that failed with a stream error up to this many times
+    /// before giving up.
+    #[arg(long = "retry", value_name = "N", default_value_t = 0)]
+    pub retry: u32,
+
+    /// Delay before the first retry; each later retry waits twice as long.
+    #[arg(long = "retry-backoff-ms", value_name = "MS", default_value_t = 1000)]
+    pub retry_backoff_ms: u64,
+
+    /// Which problems make the run exit nonzero: `warning` also fails on
+    /// agent-reported warnings, `never` only on failures of the process
+    /// itself (configuration, budgets, `--output-schema`).
+    #[arg(long = "fail-on", value_enum, default_value_t = FailOn::Error)]
+    pub fail_on: FailOn,
+
+    /// Continue the recorded conversation with this thread id (printed in
+    /// the `thread.started` event) instead of starting a new one.
+    #[arg(long = "resume-thread", value_name = "THREAD_ID")]
+    pub resume_thread: Option<String>,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 
@@ -77,10 +108,29 @@ pub struct Cli {
     #[arg(long = "json", alias = "experimental-json", default_value_t = false)]
     pub json: bool,
 
+    /// Also write events as JSONL to this file, alongside the normal output.
+    #[arg(long = "json-file", value_name = "FILE")]
+    pub json_file: Option<PathBuf>,
+
+    /// Add an RFC 3339 `ts` field to every JSONL event, on stdout with
+    /// `--json` or in the `--json-file`.
+    #[arg(long = "json-timestamps", default_value_t = false)]
+    pub json_timestamps: bool,
+
     /// Only emit the final agent message.
     #[arg(long = "last", default_value_t = false, conflicts_with = "json")]
     pub last_only: bool,
 
+    /// Format of what is printed to stdout. `sarif` prints the run's review
+    /// findings as a SARIF log once it finishes.
+    #[arg(
+        long = "output-format",
+        value_enum,
+        default_value_t = OutputFormat::Text,
+        conflicts_with_all = ["json", "last_only"]
+    )]
+    pub output_format: OutputFormat,
+
     /// Specifies file where the last message from the agent should be written.
     #[arg(long = "output-last-message", short = 'o', value_name = "FILE")]
     pub last_message_file: Option<PathBuf>,
@@ -121,3 +171,23 @@ pub enum Color {
     #[default]
     Auto,
 }
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Sarif,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum FailOn {
+    /// Fail on warnings as well as errors.
+    Warning,
+    /// Fail when the turn fails or the agent reports an error.
+    #[default]
+    Error,
+    /// Do not fail because of anything the agent reports.
+    Never,
+}
@@ -1,4 +1,5 @@
 use std::path::Path;
+use std::time::Duration;
 
 use codex_core::config::Config;
 use codex_core::protocol::Event;
@@ -22,9 +23,23 @@ pub(crate) trait EventProcessor {
     /// Handle a single event emitted by the agent.
     fn process_event(&mut self, event: Event) -> CodexStatus;
 
+    /// Report that the turn that just failed will be retried (`--retry`).
+    fn process_retry(&mut self, _retry: &TurnRetry) {}
+
     fn print_final_output(&mut self) {}
 }
 
+/// A failed turn that `--retry` is about to resume.
+pub(crate) struct TurnRetry {
+    /// 1-based number of this retry.
+    pub attempt: u32,
+    pub max_attempts: u32,
+    /// How long the driver waits before resuming.
+    pub delay: Duration,
+    /// The error that ended the failed turn.
+    pub message: String,
+}
+
 pub(crate) fn handle_last_message(last_agent_message: Option<&str>, output_file: &Path) {
     let message = last_agent_message.unwrap_or_default();
     write_last_message_file(message, Some(output_file));
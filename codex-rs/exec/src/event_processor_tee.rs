@@ -0,0 +1,53 @@
+use codex_core::config::Config;
+use codex_core::protocol::Event;
+use codex_core::protocol::SessionConfiguredEvent;
+
+use crate::event_processor::CodexStatus;
+use crate::event_processor::EventProcessor;
+use crate::event_processor::TurnRetry;
+
+/// Feeds every event to two processors, e.g. human output on the terminal
+/// plus a JSONL copy for `--json-file`. The primary processor decides when
+/// the run shuts down.
+pub(crate) struct TeeEventProcessor {
+    primary: Box<dyn EventProcessor>,
+    secondary: Box<dyn EventProcessor>,
+}
+
+impl TeeEventProcessor {
+    pub(crate) fn new(
+        primary: Box<dyn EventProcessor>,
+        secondary: Box<dyn EventProcessor>,
+    ) -> Self {
+        Self { primary, secondary }
+    }
+}
+
+impl EventProcessor for TeeEventProcessor {
+    fn print_config_summary(
+        &mut self,
+        config: &Config,
+        prompt: &str,
+        session_configured: &SessionConfiguredEvent,
+    ) {
+        self.primary
+            .print_config_summary(config, prompt, session_configured);
+        self.secondary
+            .print_config_summary(config, prompt, session_configured);
+    }
+
+    fn process_event(&mut self, event: Event) -> CodexStatus {
+        self.secondary.process_event(event.clone());
+        self.primary.process_event(event)
+    }
+
+    fn process_retry(&mut self, retry: &TurnRetry) {
+        self.primary.process_retry(retry);
+        self.secondary.process_retry(retry);
+    }
+
+    fn print_final_output(&mut self) {
+        self.primary.print_final_output();
+        self.secondary.print_final_output();
+    }
+}
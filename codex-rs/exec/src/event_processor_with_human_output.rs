@@ -1,6 +1,8 @@
+use chrono::DateTime;
 use codex_common::elapsed::format_duration;
 use codex_common::elapsed::format_elapsed;
 use codex_core::config::Config;
+use codex_core::protocol::AccountsUsageLimitedEvent;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::BackgroundEventEvent;
@@ -20,7 +22,9 @@ use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::StreamErrorEvent;
+use codex_core::protocol::SubAgentSummaryEvent;
 use codex_core::protocol::TaskCompleteEvent;
+use codex_core::protocol::ToolUsageSummaryEvent;
 use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnDiffEvent;
 use codex_core::protocol::WarningEvent;
@@ -36,6 +40,7 @@ use std::time::Instant;
 
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
+use crate::event_processor::TurnRetry;
 use crate::event_processor::handle_last_message;
 use codex_common::create_config_summary_entries;
 use codex_protocol::plan_tool::StepStatus;
@@ -167,7 +172,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
     fn process_event(&mut self, event: Event) -> CodexStatus {
         let Event { id: _, msg } = event;
         match msg {
-            EventMsg::Error(ErrorEvent { message }) => {
+            EventMsg::Error(ErrorEvent { message, .. }) => {
                 let prefix = "ERROR:".style(self.red);
                 ts_msg!(self, "{prefix} {message}");
             }
@@ -188,6 +193,55 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     ts_msg!(self, "  {}", details.style(self.dimmed));
                 }
             }
+            EventMsg::AccountsUsageLimited(AccountsUsageLimitedEvent { retry_at, .. }) => {
+                let until = DateTime::from_timestamp(retry_at, 0)
+                    .map(|time| time.format("%Y-%m-%d %H:%M UTC").to_string())
+                    .unwrap_or_else(|| retry_at.to_string());
+                ts_msg!(
+                    self,
+                    "{} all accounts are usage limited until {until}",
+                    "warning:".style(self.yellow).style(self.bold)
+                );
+            }
+            EventMsg::SubAgentSummary(SubAgentSummaryEvent {
+                invocations,
+                succeeded,
+                failed,
+                timed_out,
+                total_duration,
+            }) => {
+                let timed_out = if timed_out > 0 {
+                    format!(" ({timed_out} timed out)")
+                } else {
+                    String::new()
+                };
+                ts_msg!(
+                    self,
+                    "{} {invocations} run, {succeeded} succeeded, {failed} failed{timed_out} in {}",
+                    "coco sub-agents:".style(self.cyan),
+                    format_duration(total_duration)
+                );
+            }
+            EventMsg::ToolUsageSummary(ToolUsageSummaryEvent { tools }) => {
+                let breakdown = tools
+                    .iter()
+                    .map(|tool| {
+                        let failed = if tool.failures > 0 {
+                            format!(", {} failed", tool.failures)
+                        } else {
+                            String::new()
+                        };
+                        format!(
+                            "{} {}x{failed} {}",
+                            tool.tool_name,
+                            tool.calls,
+                            format_duration(tool.total_duration)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" · ");
+                ts_msg!(self, "{} {breakdown}", "tool time:".style(self.cyan));
+            }
             EventMsg::McpStartupUpdate(update) => {
                 let status_text = match update.status {
                     codex_core::protocol::McpStartupStatus::Starting => "starting".to_string(),
@@ -592,11 +646,23 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::ReasoningContentDelta(_)
             | EventMsg::ReasoningRawContentDelta(_)
             | EventMsg::UndoCompleted(_)
-            | EventMsg::UndoStarted(_) => {}
+            | EventMsg::UndoStarted(_)
+            | EventMsg::ToolCallMetrics(_) => {}
         }
         CodexStatus::Running
     }
 
+    fn process_retry(&mut self, retry: &TurnRetry) {
+        ts_msg!(
+            self,
+            "{} turn failed; resuming in {} (attempt {}/{})",
+            "retry:".style(self.yellow).style(self.bold),
+            format_duration(retry.delay),
+            retry.attempt,
+            retry.max_attempts
+        );
+    }
+
     fn print_final_output(&mut self) {
         if let Some(usage_info) = &self.last_total_token_usage {
             eprintln!(
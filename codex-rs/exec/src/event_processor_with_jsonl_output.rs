@@ -1,9 +1,11 @@
 use std::collections::HashMap;
+use std::io::Write;
 use std::path::PathBuf;
 use std::sync::atomic::AtomicU64;
 
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
+use crate::event_processor::TurnRetry;
 use crate::event_processor::handle_last_message;
 use crate::exec_events::AgentMessageItem;
 use crate::exec_events::CommandExecutionItem;
@@ -26,13 +28,18 @@ use crate::exec_events::ThreadEvent;
 use crate::exec_events::ThreadItem;
 use crate::exec_events::ThreadItemDetails;
 use crate::exec_events::ThreadStartedEvent;
+use crate::exec_events::TimestampedThreadEvent;
 use crate::exec_events::TodoItem;
 use crate::exec_events::TodoListItem;
+use crate::exec_events::ToolUsage;
 use crate::exec_events::TurnCompletedEvent;
 use crate::exec_events::TurnFailedEvent;
+use crate::exec_events::TurnRetryingEvent;
 use crate::exec_events::TurnStartedEvent;
 use crate::exec_events::Usage;
 use crate::exec_events::WebSearchItem;
+use chrono::SecondsFormat;
+use chrono::Utc;
 use codex_core::config::Config;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningEvent;
@@ -64,8 +71,14 @@ pub struct EventProcessorWithJsonOutput {
     // Tracks the todo list for the current turn (at most one per turn).
     running_todo_list: Option<RunningTodoList>,
     last_total_token_usage: Option<codex_core::protocol::TokenUsage>,
+    /// Per-tool totals for the running turn, attached to `turn.completed`.
+    last_tool_usage: Vec<ToolUsage>,
     running_mcp_tool_calls: HashMap<String, RunningMcpToolCall>,
     last_critical_error: Option<ThreadErrorEvent>,
+    /// Stamp each printed event with the time it was emitted.
+    timestamps: bool,
+    /// Where events are written, one per line; stdout unless redirected.
+    out: Box<dyn Write + Send>,
 }
 
 #[derive(Debug, Clone)]
@@ -97,8 +110,46 @@ impl EventProcessorWithJsonOutput {
             running_patch_applies: HashMap::new(),
             running_todo_list: None,
             last_total_token_usage: None,
+            last_tool_usage: Vec::new(),
             running_mcp_tool_calls: HashMap::new(),
             last_critical_error: None,
+            timestamps: false,
+            out: Box::new(std::io::stdout()),
+        }
+    }
+
+    /// Writes events to `out` instead of stdout.
+    pub fn with_output(mut self, out: Box<dyn Write + Send>) -> Self {
+        self.out = out;
+        self
+    }
+
+    /// Adds an RFC 3339 `ts` field to every printed event.
+    pub fn with_timestamps(mut self, timestamps: bool) -> Self {
+        self.timestamps = timestamps;
+        self
+    }
+
+    fn print_event(&mut self, event: ThreadEvent) {
+        let line = if self.timestamps {
+            serde_json::to_string(&TimestampedThreadEvent {
+                event,
+                ts: Some(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)),
+            })
+        } else {
+            serde_json::to_string(&event)
+        };
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                error!("Failed to serialize event: {e:?}");
+                return;
+            }
+        };
+        // Flush every line so the stream can be followed while the run is
+        // going.
+        if let Err(e) = writeln!(self.out, "{line}").and_then(|()| self.out.flush()) {
+            error!("Failed to write event: {e:?}");
         }
     }
 
@@ -143,6 +194,22 @@ impl EventProcessorWithJsonOutput {
                 message: ev.message.clone(),
             })],
             EventMsg::PlanUpdate(ev) => self.handle_plan_update(ev),
+            EventMsg::ToolUsageSummary(ev) => {
+                self.last_tool_usage = ev
+                    .tools
+                    .iter()
+                    .map(|tool| ToolUsage {
+                        tool_name: tool.tool_name.clone(),
+                        calls: tool.calls,
+                        failures: tool.failures,
+                        duration_ms: u64::try_from(tool.total_duration.as_millis())
+                            .unwrap_or(u64::MAX),
+                        input_bytes: tool.input_bytes,
+                        output_bytes: tool.output_bytes,
+                    })
+                    .collect();
+                Vec::new()
+            }
             _ => Vec::new(),
         }
     }
@@ -442,6 +509,7 @@ impl EventProcessorWithJsonOutput {
         } else {
             Usage::default()
         };
+        let tool_usage = std::mem::take(&mut self.last_tool_usage);
 
         let mut items = Vec::new();
 
@@ -458,7 +526,10 @@ impl EventProcessorWithJsonOutput {
         if let Some(error) = self.last_critical_error.take() {
             items.push(ThreadEvent::TurnFailed(TurnFailedEvent { error }));
         } else {
-            items.push(ThreadEvent::TurnCompleted(TurnCompletedEvent { usage }));
+            items.push(ThreadEvent::TurnCompleted(TurnCompletedEvent {
+                usage,
+                tool_usage,
+            }));
         }
 
         items
@@ -473,18 +544,21 @@ impl EventProcessor for EventProcessorWithJsonOutput {
         });
     }
 
-    #[allow(clippy::print_stdout)]
+    fn process_retry(&mut self, retry: &TurnRetry) {
+        self.print_event(ThreadEvent::TurnRetrying(TurnRetryingEvent {
+            attempt: retry.attempt,
+            max_attempts: retry.max_attempts,
+            delay_ms: u64::try_from(retry.delay.as_millis()).unwrap_or(u64::MAX),
+            error: ThreadErrorEvent {
+                message: retry.message.clone(),
+            },
+        }));
+    }
+
     fn process_event(&mut self, event: Event) -> CodexStatus {
         let aggregated = self.collect_thread_events(&event);
         for conv_event in aggregated {
-            match serde_json::to_string(&conv_event) {
-                Ok(line) => {
-                    println!("{line}");
-                }
-                Err(e) => {
-                    error!("Failed to serialize event: {e:?}");
-                }
-            }
+            self.print_event(conv_event);
         }
 
         let Event { msg, .. } = event;
@@ -0,0 +1,189 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::config::Config;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExitedReviewModeEvent;
+use codex_core::protocol::ReviewFinding;
+use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::protocol::TaskCompleteEvent;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::event_processor::CodexStatus;
+use crate::event_processor::EventProcessor;
+use crate::event_processor::handle_last_message;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const RULE_ID: &str = "codex-review";
+
+/// Prints the run's review findings as a single SARIF log once it finishes.
+pub(crate) struct EventProcessorWithSarifOutput {
+    last_message_path: Option<PathBuf>,
+    /// Finding locations are reported relative to this directory.
+    root: PathBuf,
+    /// Findings from review events; `None` when the run had no review.
+    review_findings: Option<Vec<ReviewFinding>>,
+    final_message: Option<String>,
+}
+
+/// The part of a final message that carries findings, in the same shape as
+/// review output.
+#[derive(Deserialize)]
+struct FindingsMessage {
+    findings: Vec<ReviewFinding>,
+}
+
+impl EventProcessorWithSarifOutput {
+    pub(crate) fn new(last_message_path: Option<PathBuf>, root: PathBuf) -> Self {
+        Self {
+            last_message_path,
+            root,
+            review_findings: None,
+            final_message: None,
+        }
+    }
+
+    fn findings(&mut self) -> Vec<ReviewFinding> {
+        if let Some(findings) = self.review_findings.take() {
+            return findings;
+        }
+        let Some(message) = self.final_message.as_deref() else {
+            return Vec::new();
+        };
+        match parse_findings(message) {
+            Ok(findings) => findings,
+            Err(err) => {
+                eprintln!("Final message has no structured findings for SARIF output: {err}");
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// Parses findings from the message, or from the outermost `{...}` in it when
+/// the JSON is wrapped in other text (e.g. a code fence).
+fn parse_findings(message: &str) -> Result<Vec<ReviewFinding>, serde_json::Error> {
+    let err = match serde_json::from_str::<FindingsMessage>(message) {
+        Ok(parsed) => return Ok(parsed.findings),
+        Err(err) => err,
+    };
+    if let (Some(start), Some(end)) = (message.find('{'), message.rfind('}'))
+        && start < end
+        && let Some(slice) = message.get(start..=end)
+        && let Ok(parsed) = serde_json::from_str::<FindingsMessage>(slice)
+    {
+        return Ok(parsed.findings);
+    }
+    Err(err)
+}
+
+impl EventProcessor for EventProcessorWithSarifOutput {
+    fn print_config_summary(&mut self, _: &Config, _: &str, _: &SessionConfiguredEvent) {}
+
+    fn process_event(&mut self, event: Event) -> CodexStatus {
+        match event.msg {
+            EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
+                review_output: Some(output),
+            }) => {
+                self.review_findings
+                    .get_or_insert_with(Vec::new)
+                    .extend(output.findings);
+                CodexStatus::Running
+            }
+            EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
+                if let Some(path) = self.last_message_path.as_deref() {
+                    handle_last_message(last_agent_message.as_deref(), path);
+                }
+                self.final_message = last_agent_message;
+                CodexStatus::InitiateShutdown
+            }
+            EventMsg::ShutdownComplete => CodexStatus::Shutdown,
+            _ => CodexStatus::Running,
+        }
+    }
+
+    fn print_final_output(&mut self) {
+        let findings = self.findings();
+        let log = sarif_log(&findings, &self.root);
+        match serde_json::to_string_pretty(&log) {
+            #[allow(clippy::print_stdout)]
+            Ok(log) => println!("{log}"),
+            Err(err) => eprintln!("Failed to serialize SARIF output: {err}"),
+        }
+    }
+}
+
+fn sarif_log(findings: &[ReviewFinding], root: &Path) -> Value {
+    let results: Vec<Value> = findings
+        .iter()
+        .map(|finding| sarif_result(finding, root))
+        .collect();
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "codex",
+                    "informationUri": "https://github.com/openai/codex",
+                    "rules": [{
+                        "id": RULE_ID,
+                        "shortDescription": { "text": "Codex review finding" },
+                    }],
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn sarif_result(finding: &ReviewFinding, root: &Path) -> Value {
+    let location = &finding.code_location;
+    // SARIF uses forward slashes, and a `file://` URI for files outside the
+    // root that code scanning resolves relative paths against.
+    let uri = match location.absolute_file_path.strip_prefix(root) {
+        Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+        Err(_) => format!(
+            "file://{}",
+            location
+                .absolute_file_path
+                .to_string_lossy()
+                .replace('\\', "/")
+        ),
+    };
+    // Lines are 1-based.
+    let start_line = location.line_range.start.max(1);
+    let end_line = location.line_range.end.max(start_line);
+    let text = if finding.body.is_empty() {
+        finding.title.clone()
+    } else {
+        format!("{}\n\n{}", finding.title, finding.body)
+    };
+    json!({
+        "ruleId": RULE_ID,
+        "level": sarif_level(finding.priority),
+        "message": { "text": text },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": { "uri": uri },
+                "region": { "startLine": start_line, "endLine": end_line },
+            },
+        }],
+        "properties": {
+            "priority": finding.priority,
+            "confidence": finding.confidence_score,
+        },
+    })
+}
+
+/// Review priorities run from 0 (most urgent) to 3.
+fn sarif_level(priority: i32) -> &'static str {
+    match priority {
+        i32::MIN..=1 => "error",
+        2 => "warning",
+        _ => "note",
+    }
+}
@@ -21,6 +21,10 @@ pub enum ThreadEvent {
     /// Indicates that a turn failed with an error.
     #[serde(rename = "turn.failed")]
     TurnFailed(TurnFailedEvent),
+    /// Emitted after a turn failed with a stream error when `--retry` will
+    /// resume it in a new turn.
+    #[serde(rename = "turn.retrying")]
+    TurnRetrying(TurnRetryingEvent),
     /// Emitted when a new item is added to the thread. Typically the item will be in an "in progress" state.
     #[serde(rename = "item.started")]
     ItemStarted(ItemStartedEvent),
@@ -35,6 +39,18 @@ pub enum ThreadEvent {
     Error(ThreadErrorEvent),
 }
 
+/// A [`ThreadEvent`] as printed by `--json --json-timestamps`, stamped with
+/// the time it was emitted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct TimestampedThreadEvent {
+    #[serde(flatten)]
+    pub event: ThreadEvent,
+    /// RFC 3339 time the event was emitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub ts: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct ThreadStartedEvent {
     /// The identified of the new thread. Can be used to resume the thread later.
@@ -48,6 +64,9 @@ pub struct TurnStartedEvent {}
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct TurnCompletedEvent {
     pub usage: Usage,
+    /// Time and calls per tool during the turn, most total time first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_usage: Vec<ToolUsage>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -55,6 +74,17 @@ pub struct TurnFailedEvent {
     pub error: ThreadErrorEvent,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct TurnRetryingEvent {
+    /// 1-based number of this retry.
+    pub attempt: u32,
+    pub max_attempts: u32,
+    /// Delay before the next turn starts, in milliseconds.
+    pub delay_ms: u64,
+    /// The error that ended the failed turn.
+    pub error: ThreadErrorEvent,
+}
+
 /// Describes the usage of tokens during a turn.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, Default)]
 pub struct Usage {
@@ -66,6 +96,21 @@ pub struct Usage {
     pub output_tokens: i64,
 }
 
+/// Calls made to one tool during a turn.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, Default)]
+pub struct ToolUsage {
+    pub tool_name: String,
+    pub calls: u64,
+    /// Calls that returned an error or reported failure.
+    pub failures: u64,
+    /// Total wall time spent in the tool, in milliseconds.
+    pub duration_ms: u64,
+    /// Total size of the arguments passed to the tool.
+    pub input_bytes: u64,
+    /// Total size of the results returned to the model.
+    pub output_bytes: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct ItemStartedEvent {
     pub item: ThreadItem,
@@ -0,0 +1,45 @@
+//! Exit statuses of `codex exec`, one per failure class, so scripts and
+//! `codex flow` can tell why a run failed. Documented in `docs/exec.md`.
+
+use codex_core::protocol::CodexErrorKind;
+
+/// The run failed for a reason without a more specific code (including
+/// invalid arguments or configuration), or the turn itself failed.
+pub const FAILURE: i32 = 1;
+/// The run went over its `--max-tokens` or `--max-cost` budget.
+pub const BUDGET_EXCEEDED: i32 = 3;
+/// The model stream failed and retries ran out.
+pub const STREAM_ERROR: i32 = 4;
+/// The account hit a usage limit or quota.
+pub const USAGE_LIMIT: i32 = 5;
+/// The sandbox refused or could not run something the turn needed.
+pub const SANDBOX_DENIED: i32 = 6;
+/// Something the turn waited on timed out.
+pub const TIMEOUT: i32 = 7;
+/// The agent reported a warning and the run used `--fail-on warning`.
+pub const WARNING: i32 = 8;
+
+/// Exit status for a run that ended with an error of `kind`.
+pub fn for_error(kind: Option<CodexErrorKind>) -> i32 {
+    match kind {
+        Some(CodexErrorKind::StreamError) => STREAM_ERROR,
+        Some(CodexErrorKind::UsageLimit) => USAGE_LIMIT,
+        Some(CodexErrorKind::SandboxDenied) => SANDBOX_DENIED,
+        Some(CodexErrorKind::Timeout) => TIMEOUT,
+        Some(CodexErrorKind::TurnFailed) | None => FAILURE,
+    }
+}
+
+/// Short description of a `codex exec` exit status other than success.
+pub fn describe(code: i32) -> Option<&'static str> {
+    match code {
+        FAILURE => Some("run failed"),
+        BUDGET_EXCEEDED => Some("budget exceeded"),
+        STREAM_ERROR => Some("model stream error"),
+        USAGE_LIMIT => Some("usage limit reached"),
+        SANDBOX_DENIED => Some("sandbox denied"),
+        TIMEOUT => Some("timed out"),
+        WARNING => Some("agent reported a warning"),
+        _ => None,
+    }
+}
@@ -4,13 +4,18 @@
 // For both modes, any other output must be written to stderr.
 #![deny(clippy::print_stdout)]
 
+mod budget;
 mod cli;
 mod event_processor;
 mod event_processor_last_only;
+mod event_processor_tee;
 mod event_processor_with_human_output;
 pub mod event_processor_with_jsonl_output;
+mod event_processor_with_sarif_output;
 pub mod exec_events;
+pub mod exit_code;
 
+use budget::RunBudget;
 pub use cli::Cli;
 use codex_common::oss::ensure_oss_provider_ready;
 use codex_common::oss::get_default_model_for_oss_provider;
@@ -26,21 +31,29 @@ use codex_core::config::find_codex_home;
 use codex_core::config::load_config_as_toml_with_cli_overrides;
 use codex_core::config::resolve_oss_provider;
 use codex_core::git_info::get_git_repo_root;
+use codex_core::output_schema::validate_output;
 use codex_core::protocol::AskForApproval;
+use codex_core::protocol::CodexErrorKind;
+use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
 use codex_core::protocol::SessionSource;
+use codex_core::protocol::TaskCompleteEvent;
+use codex_core::protocol::TokenCountEvent;
 use codex_protocol::config_types::SandboxMode;
 use codex_protocol::user_input::UserInput;
 use event_processor_last_only::EventProcessorLastOnly;
+use event_processor_tee::TeeEventProcessor;
 use event_processor_with_human_output::EventProcessorWithHumanOutput;
 use event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
+use event_processor_with_sarif_output::EventProcessorWithSarifOutput;
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
 use serde_json::Value;
 use std::io::IsTerminal;
 use std::io::Read;
 use std::path::PathBuf;
+use std::time::Duration;
 use supports_color::Stream;
 use tracing::debug;
 use tracing::error;
@@ -49,8 +62,10 @@ use tracing_subscriber::EnvFilter;
 use tracing_subscriber::prelude::*;
 
 use crate::cli::Command as ExecCommand;
+use crate::cli::FailOn;
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
+use crate::event_processor::TurnRetry;
 use codex_core::default_client::set_default_originator;
 use codex_core::find_conversation_path_by_id_str;
 
@@ -73,14 +88,28 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         add_dir,
         color,
         json: json_mode,
+        json_timestamps,
+        json_file,
         last_only,
+        output_format,
         last_message_file,
         sandbox_mode: sandbox_mode_cli_arg,
         prompt,
         output_schema: output_schema_path,
+        max_tokens,
+        max_cost,
+        retry,
+        retry_backoff_ms,
+        resume_thread,
+        fail_on,
         config_overrides,
     } = cli;
 
+    if resume_thread.is_some() && command.is_some() {
+        eprintln!("--resume-thread cannot be combined with the resume subcommand.");
+        std::process::exit(1);
+    }
+
     // Determine the prompt source (parent or subcommand) and read from stdin if needed.
     let prompt_arg = match &command {
         // Allow prompt before the subcommand by falling back to the parent-level prompt
@@ -243,6 +272,14 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         std::process::exit(1);
     }
 
+    let budget = match RunBudget::new(max_tokens, max_cost, &config) {
+        Ok(budget) => budget,
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+    };
+
     let otel = codex_core::otel_init::build_provider(&config, env!("CARGO_PKG_VERSION"));
 
     #[allow(clippy::print_stderr)]
@@ -267,15 +304,40 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         let _ = tracing_subscriber::registry().with(fmt_layer).try_init();
     }
 
-    let mut event_processor: Box<dyn EventProcessor> = match (json_mode, last_only) {
-        (true, _) => Box::new(EventProcessorWithJsonOutput::new(last_message_file.clone())),
-        (false, true) => Box::new(EventProcessorLastOnly::new(last_message_file.clone())),
-        (false, false) => Box::new(EventProcessorWithHumanOutput::create_with_ansi(
-            stdout_with_ansi,
-            &config,
+    let mut event_processor: Box<dyn EventProcessor> = match (json_mode, last_only, output_format) {
+        (true, _, _) => Box::new(
+            EventProcessorWithJsonOutput::new(last_message_file.clone())
+                .with_timestamps(json_timestamps),
+        ),
+        (false, true, _) => Box::new(EventProcessorLastOnly::new(last_message_file.clone())),
+        (false, false, cli::OutputFormat::Sarif) => Box::new(EventProcessorWithSarifOutput::new(
             last_message_file.clone(),
+            get_git_repo_root(&config.cwd).unwrap_or_else(|| config.cwd.clone()),
         )),
+        (false, false, cli::OutputFormat::Text) => {
+            Box::new(EventProcessorWithHumanOutput::create_with_ansi(
+                stdout_with_ansi,
+                &config,
+                last_message_file.clone(),
+            ))
+        }
     };
+    if let Some(path) = json_file.as_deref() {
+        let file = match std::fs::File::create(path) {
+            Ok(file) => file,
+            Err(err) => {
+                eprintln!("Failed to create --json-file {}: {err}", path.display());
+                std::process::exit(1);
+            }
+        };
+        let json_processor = EventProcessorWithJsonOutput::new(None)
+            .with_timestamps(json_timestamps)
+            .with_output(Box::new(std::io::BufWriter::new(file)));
+        event_processor = Box::new(TeeEventProcessor::new(
+            event_processor,
+            Box::new(json_processor),
+        ));
+    }
 
     if oss {
         // We're in the oss section, so provider_id should be Some
@@ -307,18 +369,28 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     }
 
     let auth_manager = AuthManager::shared(
-        config.codex_home.clone(),
+        config.auth_storage_settings(),
         true,
         config.cli_auth_credentials_store_mode,
     );
     let conversation_manager = ConversationManager::new(auth_manager.clone(), SessionSource::Exec);
 
-    // Handle resume subcommand by resolving a rollout path and using explicit resume API.
+    // Handle --resume-thread and the resume subcommand by resolving a rollout
+    // path and using explicit resume API.
     let NewConversation {
         conversation_id: _,
         conversation,
         session_configured,
-    } = if let Some(ExecCommand::Resume(args)) = command {
+    } = if let Some(thread_id) = resume_thread.as_deref() {
+        let Some(path) = find_conversation_path_by_id_str(&config.codex_home, thread_id).await?
+        else {
+            eprintln!("No recorded thread with id {thread_id} to resume.");
+            std::process::exit(1);
+        };
+        conversation_manager
+            .resume_conversation_from_rollout(config.clone(), path, auth_manager.clone())
+            .await?
+    } else if let Some(ExecCommand::Resume(args)) = command {
         let resume_path = resolve_resume_path(&config, &args).await?;
 
         if let Some(path) = resume_path {
@@ -386,33 +458,85 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         .map(|path| UserInput::LocalImage { path })
         .collect();
     items.push(UserInput::Text { text: prompt });
-    let initial_prompt_task_id = conversation
-        .submit(Op::UserTurn {
-            items,
-            cwd: default_cwd,
-            approval_policy: default_approval_policy,
-            sandbox_policy: default_sandbox_policy,
-            model: default_model,
-            effort: default_effort,
-            summary: default_summary,
-            final_output_json_schema: output_schema,
-        })
-        .await?;
+    let user_turn = |items: Vec<UserInput>| Op::UserTurn {
+        items,
+        cwd: default_cwd.clone(),
+        approval_policy: default_approval_policy,
+        sandbox_policy: default_sandbox_policy.clone(),
+        model: default_model.clone(),
+        effort: default_effort,
+        summary: default_summary,
+        final_output_json_schema: output_schema.clone(),
+    };
+    let initial_prompt_task_id = conversation.submit(user_turn(items)).await?;
     info!("Sent prompt with event ID: {initial_prompt_task_id}");
 
     // Run the loop until the task is complete.
-    // Track whether a fatal error was reported by the server so we can
-    // exit with a non-zero status for automation-friendly signaling.
-    let mut error_seen = false;
+    // Track the kind of the first fatal error reported by the server so we
+    // can exit with a matching status for automation-friendly signaling.
+    let mut error_seen: Option<Option<CodexErrorKind>> = None;
+    let mut warning_seen = false;
+    let mut final_message = None;
+    let mut budget_exceeded = false;
+    let mut shutdown_requested = false;
+    // Set when the running turn failed with a stream error that `--retry`
+    // will resume once the turn completes.
+    let mut pending_retry: Option<String> = None;
+    let mut retries_used = 0;
     while let Some(event) = rx.recv().await {
-        if matches!(event.msg, EventMsg::Error(_)) {
-            error_seen = true;
+        match &event.msg {
+            EventMsg::Error(ErrorEvent { message, kind }) => {
+                if *kind == Some(CodexErrorKind::StreamError) && retries_used < retry {
+                    pending_retry = Some(message.clone());
+                } else {
+                    error_seen.get_or_insert(*kind);
+                }
+            }
+            EventMsg::Warning(_) => warning_seen = true,
+            EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
+                final_message = last_agent_message.clone();
+            }
+            EventMsg::TokenCount(TokenCountEvent {
+                info: Some(info), ..
+            }) if !budget_exceeded => {
+                if let Err(err) = budget.check(&info.total_token_usage) {
+                    eprintln!("{err}");
+                    budget_exceeded = true;
+                    if !shutdown_requested {
+                        shutdown_requested = true;
+                        conversation.submit(Op::Shutdown).await?;
+                    }
+                }
+            }
+            _ => {}
         }
         let shutdown: CodexStatus = event_processor.process_event(event);
         match shutdown {
             CodexStatus::Running => continue,
             CodexStatus::InitiateShutdown => {
-                conversation.submit(Op::Shutdown).await?;
+                if let Some(message) = pending_retry.take()
+                    && !shutdown_requested
+                {
+                    retries_used += 1;
+                    let delay = retry_delay(retry_backoff_ms, retries_used);
+                    event_processor.process_retry(&TurnRetry {
+                        attempt: retries_used,
+                        max_attempts: retry,
+                        delay,
+                        message,
+                    });
+                    tokio::time::sleep(delay).await;
+                    conversation
+                        .submit(user_turn(vec![UserInput::Text {
+                            text: RETRY_PROMPT.to_string(),
+                        }]))
+                        .await?;
+                    continue;
+                }
+                if !shutdown_requested {
+                    shutdown_requested = true;
+                    conversation.submit(Op::Shutdown).await?;
+                }
             }
             CodexStatus::Shutdown => {
                 break;
@@ -420,13 +544,38 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     }
     event_processor.print_final_output();
-    if error_seen {
+    if budget_exceeded {
+        std::process::exit(exit_code::BUDGET_EXCEEDED);
+    }
+    if let Some(kind) = error_seen
+        && fail_on != FailOn::Never
+    {
+        std::process::exit(exit_code::for_error(kind));
+    }
+    if let Some(schema) = &output_schema
+        && let Err(err) = check_final_message(final_message.as_deref(), schema)
+    {
+        eprintln!("Final message does not match the output schema: {err}");
         std::process::exit(1);
     }
+    if warning_seen && fail_on == FailOn::Warning {
+        std::process::exit(exit_code::WARNING);
+    }
 
     Ok(())
 }
 
+/// Sent as a new turn when `--retry` resumes a turn that a stream error cut
+/// short.
+const RETRY_PROMPT: &str =
+    "The previous turn was cut off by a connection error. Continue where you left off.";
+
+/// Exponential backoff: `base_ms`, then twice that, and so on.
+fn retry_delay(base_ms: u64, attempt: u32) -> Duration {
+    let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+    Duration::from_millis(base_ms.saturating_mul(factor))
+}
+
 async fn resolve_resume_path(
     config: &Config,
     args: &crate::cli::ResumeArgs,
@@ -457,6 +606,15 @@ async fn resolve_resume_path(
     }
 }
 
+/// Parses the final agent message as JSON and validates it against the
+/// `--output-schema` schema.
+fn check_final_message(message: Option<&str>, schema: &Value) -> Result<(), String> {
+    let message = message.ok_or_else(|| "the run ended without a final message".to_string())?;
+    let value = serde_json::from_str::<Value>(message)
+        .map_err(|err| format!("the final message is not JSON: {err}"))?;
+    validate_output(&value, schema)
+}
+
 fn load_output_schema(path: Option<PathBuf>) -> Option<Value> {
     let path = path?;
 
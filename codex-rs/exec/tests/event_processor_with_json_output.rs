@@ -15,6 +15,8 @@ use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::protocol::ToolUsageStats;
+use codex_core::protocol::ToolUsageSummaryEvent;
 use codex_core::protocol::WarningEvent;
 use codex_core::protocol::WebSearchEndEvent;
 use codex_exec::event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
@@ -37,8 +39,10 @@ use codex_exec::exec_events::ThreadEvent;
 use codex_exec::exec_events::ThreadItem;
 use codex_exec::exec_events::ThreadItemDetails;
 use codex_exec::exec_events::ThreadStartedEvent;
+use codex_exec::exec_events::TimestampedThreadEvent;
 use codex_exec::exec_events::TodoItem as ExecTodoItem;
 use codex_exec::exec_events::TodoListItem as ExecTodoListItem;
+use codex_exec::exec_events::ToolUsage;
 use codex_exec::exec_events::TurnCompletedEvent;
 use codex_exec::exec_events::TurnFailedEvent;
 use codex_exec::exec_events::TurnStartedEvent;
@@ -242,6 +246,7 @@ fn plan_update_emits_todo_list_started_updated_and_completed() {
             }),
             ThreadEvent::TurnCompleted(TurnCompletedEvent {
                 usage: Usage::default(),
+                tool_usage: Vec::new(),
             }),
         ]
     );
@@ -539,6 +544,7 @@ fn error_event_produces_error() {
         "e1",
         EventMsg::Error(codex_core::protocol::ErrorEvent {
             message: "boom".to_string(),
+            kind: None,
         }),
     ));
     assert_eq!(
@@ -596,6 +602,7 @@ fn error_followed_by_task_complete_produces_turn_failed() {
         "e1",
         EventMsg::Error(ErrorEvent {
             message: "boom".to_string(),
+            kind: None,
         }),
     );
     assert_eq!(
@@ -953,6 +960,21 @@ fn task_complete_produces_turn_completed_with_usage() {
     );
     assert!(ep.collect_thread_events(&token_count_event).is_empty());
 
+    let tool_usage_event = event(
+        "e1b",
+        EventMsg::ToolUsageSummary(ToolUsageSummaryEvent {
+            tools: vec![ToolUsageStats {
+                tool_name: "shell".to_string(),
+                calls: 3,
+                failures: 1,
+                total_duration: Duration::from_millis(1500),
+                input_bytes: 120,
+                output_bytes: 4096,
+            }],
+        }),
+    );
+    assert!(ep.collect_thread_events(&tool_usage_event).is_empty());
+
     // Then TaskComplete should produce turn.completed with the captured usage.
     let complete_event = event(
         "e2",
@@ -969,6 +991,38 @@ fn task_complete_produces_turn_completed_with_usage() {
                 cached_input_tokens: 200,
                 output_tokens: 345,
             },
+            tool_usage: vec![ToolUsage {
+                tool_name: "shell".to_string(),
+                calls: 3,
+                failures: 1,
+                duration_ms: 1500,
+                input_bytes: 120,
+                output_bytes: 4096,
+            }],
         })]
     );
 }
+
+#[test]
+fn timestamped_events_add_ts_and_still_parse_as_thread_events() {
+    let event = ThreadEvent::ThreadStarted(ThreadStartedEvent {
+        thread_id: "67e55044-10b1-426f-9247-bb680e5fe0c8".to_string(),
+    });
+    let line = serde_json::to_string(&TimestampedThreadEvent {
+        event: event.clone(),
+        ts: Some("2025-01-01T00:00:00.000Z".to_string()),
+    })
+    .expect("serialize");
+
+    let value: serde_json::Value = serde_json::from_str(&line).expect("json");
+    assert_eq!(
+        value,
+        json!({
+            "type": "thread.started",
+            "thread_id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "ts": "2025-01-01T00:00:00.000Z",
+        })
+    );
+    let parsed: ThreadEvent = serde_json::from_str(&line).expect("parse thread event");
+    assert_eq!(parsed, event);
+}
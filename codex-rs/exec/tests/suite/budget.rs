@@ -0,0 +1,48 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+use predicates::str::contains;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exits_with_budget_code_when_max_tokens_is_exceeded() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+
+    let server = responses::start_mock_server().await;
+    let body = responses::sse(vec![
+        responses::ev_response_created("resp1"),
+        responses::ev_assistant_message("m1", "done"),
+        responses::ev_completed_with_tokens("resp1", 5000),
+    ]);
+    responses::mount_sse_once(&server, body).await;
+
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--max-tokens")
+        .arg("1000")
+        .arg("tell me something")
+        .assert()
+        .code(codex_exec::exit_code::BUDGET_EXCEEDED)
+        .stderr(contains(
+            "the run used 5000 tokens, over its --max-tokens budget of 1000",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn max_cost_requires_a_price_for_the_model() {
+    let test = test_codex_exec();
+
+    test.cmd()
+        .arg("--skip-git-repo-check")
+        .arg("-m")
+        .arg("gpt-5.1")
+        .arg("--max-cost")
+        .arg("0.50")
+        .arg("tell me something")
+        .assert()
+        .code(1)
+        .stderr(contains("--max-cost needs a price for model `gpt-5.1`"));
+}
@@ -0,0 +1,72 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use std::path::Path;
+
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fail_on_never_ignores_a_failed_turn() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+
+    // Fail the first request and each of the five stream retries.
+    let server = responses::start_mock_server().await;
+    let body = responses::sse(vec![serde_json::json!({
+        "type": "response.failed",
+        "response": {
+            "id": "resp_err_1",
+            "error": {
+                "code": "rate_limit_exceeded",
+                "message": "synthetic server error; try again in 1ms"
+            }
+        }
+    })]);
+    responses::mount_sse_sequence(&server, vec![body; 6]).await;
+
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--fail-on")
+        .arg("never")
+        .arg("tell me something")
+        .assert()
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+fn fail_on_warning_fails_when_the_agent_warns() {
+    let test = test_codex_exec();
+    let fixture =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cli_responses_fixture.sse");
+
+    test.cmd()
+        .env("CODEX_RS_SSE_FIXTURE", &fixture)
+        .env("OPENAI_BASE_URL", "http://unused.local")
+        .arg("--skip-git-repo-check")
+        .arg("--model")
+        .arg("gpt-5.1")
+        .arg("echo first")
+        .assert()
+        .success();
+
+    // Resuming with a different model than the last turn used makes the
+    // agent warn about it.
+    let resume_with = |model: &str, fail_on: &str| {
+        test.cmd()
+            .env("CODEX_RS_SSE_FIXTURE", &fixture)
+            .env("OPENAI_BASE_URL", "http://unused.local")
+            .arg("--skip-git-repo-check")
+            .arg("--model")
+            .arg(model)
+            .arg("--fail-on")
+            .arg(fail_on)
+            .arg("echo second")
+            .arg("resume")
+            .arg("--last")
+            .assert()
+    };
+    resume_with("gpt-5.1-codex", "error").success();
+    resume_with("gpt-5.1", "warning").code(codex_exec::exit_code::WARNING);
+}
@@ -0,0 +1,50 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+use predicates::str::contains;
+use serde_json::Value;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn json_file_records_events_alongside_human_output() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+
+    let server = responses::start_mock_server().await;
+    let body = responses::sse(vec![
+        responses::ev_response_created("resp1"),
+        responses::ev_assistant_message("m1", "hello from the agent"),
+        responses::ev_completed("resp1"),
+    ]);
+    responses::mount_sse_once(&server, body).await;
+
+    let events_path = test.cwd_path().join("events.jsonl");
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--json-file")
+        .arg(&events_path)
+        .arg("--json-timestamps")
+        .arg("tell me something")
+        .assert()
+        .code(0)
+        .stdout(contains("hello from the agent"));
+
+    let contents = std::fs::read_to_string(&events_path)?;
+    let events: Vec<Value> = contents
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line is a JSON event"))
+        .collect();
+    let types: Vec<&str> = events
+        .iter()
+        .map(|event| event["type"].as_str().unwrap())
+        .collect();
+    assert_eq!(types.first(), Some(&"thread.started"));
+    assert!(types.contains(&"item.completed"), "events: {types:?}");
+    assert_eq!(types.last(), Some(&"turn.completed"));
+    assert!(
+        events.iter().all(|event| event["ts"].is_string()),
+        "every event should carry a timestamp: {contents}"
+    );
+
+    Ok(())
+}
@@ -2,9 +2,14 @@
 mod add_dir;
 mod apply_patch;
 mod auth_env;
+mod budget;
+mod fail_on;
+mod json_file;
 mod last;
 mod originator;
 mod output_schema;
 mod resume;
+mod retry;
 mod sandbox;
+mod sarif;
 mod server_error_exit;
@@ -24,7 +24,7 @@ async fn exec_includes_output_schema_in_request() -> anyhow::Result<()> {
     let server = responses::start_mock_server().await;
     let body = responses::sse(vec![
         responses::ev_response_created("resp1"),
-        responses::ev_assistant_message("m1", "fixture hello"),
+        responses::ev_assistant_message("m1", r#"{"answer":"hello"}"#),
         responses::ev_completed("resp1"),
     ]);
     let response_mock = responses::mount_sse_once(&server, body).await;
@@ -60,3 +60,44 @@ async fn exec_includes_output_schema_in_request() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exec_fails_when_final_message_does_not_match_output_schema() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+
+    let schema_path = test.cwd_path().join("schema.json");
+    std::fs::write(
+        &schema_path,
+        serde_json::to_vec_pretty(&serde_json::json!({
+            "type": "object",
+            "properties": {
+                "answer": { "type": "string" }
+            },
+            "required": ["answer"],
+            "additionalProperties": false
+        }))?,
+    )?;
+
+    let server = responses::start_mock_server().await;
+    let body = responses::sse(vec![
+        responses::ev_response_created("resp1"),
+        responses::ev_assistant_message("m1", r#"{"answer":42}"#),
+        responses::ev_completed("resp1"),
+    ]);
+    responses::mount_sse_once(&server, body).await;
+
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--output-schema")
+        .arg(&schema_path)
+        .arg("-m")
+        .arg("gpt-5.1")
+        .arg("tell me a joke")
+        .assert()
+        .code(1)
+        .stderr(predicates::str::contains(
+            "$.answer: expected string, got number",
+        ));
+
+    Ok(())
+}
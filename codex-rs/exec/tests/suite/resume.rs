@@ -255,3 +255,83 @@ fn exec_resume_preserves_cli_configuration_overrides() -> anyhow::Result<()> {
     assert!(content.contains(&marker2));
     Ok(())
 }
+
+#[test]
+fn exec_resume_thread_continues_the_thread_from_json_output() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+    let fixture =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cli_responses_fixture.sse");
+
+    // 1) First run: read the thread id from the `thread.started` event.
+    let marker = format!("resume-thread-{}", Uuid::new_v4());
+    let prompt = format!("echo {marker}");
+
+    let output = test
+        .cmd()
+        .env("CODEX_RS_SSE_FIXTURE", &fixture)
+        .env("OPENAI_BASE_URL", "http://unused.local")
+        .arg("--skip-git-repo-check")
+        .arg("-C")
+        .arg(env!("CARGO_MANIFEST_DIR"))
+        .arg("--json")
+        .arg(&prompt)
+        .output()
+        .context("should run first exec")?;
+    assert!(output.status.success(), "first run failed: {output:?}");
+    let stdout = String::from_utf8(output.stdout)?;
+    let thread_id = stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .find(|event| event["type"] == "thread.started")
+        .and_then(|event| event["thread_id"].as_str().map(ToString::to_string))
+        .expect("thread.started event with a thread id");
+
+    let sessions_dir = test.home_path().join("sessions");
+    let path = find_session_file_containing_marker(&sessions_dir, &marker)
+        .expect("no session file found after first run");
+
+    // 2) Second run: continue the same thread.
+    let marker2 = format!("resume-thread-2-{}", Uuid::new_v4());
+    let prompt2 = format!("echo {marker2}");
+
+    test.cmd()
+        .env("CODEX_RS_SSE_FIXTURE", &fixture)
+        .env("OPENAI_BASE_URL", "http://unused.local")
+        .arg("--skip-git-repo-check")
+        .arg("-C")
+        .arg(env!("CARGO_MANIFEST_DIR"))
+        .arg("--resume-thread")
+        .arg(&thread_id)
+        .arg(&prompt2)
+        .assert()
+        .success();
+
+    let resumed_path = find_session_file_containing_marker(&sessions_dir, &marker2)
+        .expect("no resumed session file containing marker2");
+    assert_eq!(
+        resumed_path, path,
+        "--resume-thread should append to the thread's file"
+    );
+    let content = std::fs::read_to_string(&resumed_path)?;
+    assert!(content.contains(&marker));
+    assert!(content.contains(&marker2));
+    Ok(())
+}
+
+#[test]
+fn exec_resume_thread_fails_for_unknown_thread() {
+    let test = test_codex_exec();
+    let thread_id = Uuid::new_v4().to_string();
+
+    test.cmd()
+        .env("OPENAI_BASE_URL", "http://unused.local")
+        .arg("--skip-git-repo-check")
+        .arg("--resume-thread")
+        .arg(&thread_id)
+        .arg("echo hello")
+        .assert()
+        .code(1)
+        .stderr(predicates::str::contains(format!(
+            "No recorded thread with id {thread_id} to resume."
+        )));
+}
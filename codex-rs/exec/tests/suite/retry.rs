@@ -0,0 +1,83 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+use predicates::str::contains;
+
+/// A stream that fails the first request and each of the five stream retries
+/// inside the turn, so the turn itself fails with a stream error.
+fn failed_turn_bodies() -> Vec<String> {
+    let body = responses::sse(vec![serde_json::json!({
+        "type": "response.failed",
+        "response": {
+            "id": "resp_err_1",
+            "error": {
+                "code": "rate_limit_exceeded",
+                "message": "synthetic server error; try again in 1ms"
+            }
+        }
+    })]);
+    vec![body; 6]
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn retry_resumes_a_turn_that_failed_with_a_stream_error() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+
+    let server = responses::start_mock_server().await;
+    let mut bodies = failed_turn_bodies();
+    bodies.push(responses::sse(vec![
+        responses::ev_response_created("resp1"),
+        responses::ev_assistant_message("m1", "recovered"),
+        responses::ev_completed("resp1"),
+    ]));
+    let mock = responses::mount_sse_sequence(&server, bodies).await;
+
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--retry")
+        .arg("1")
+        .arg("--retry-backoff-ms")
+        .arg("1")
+        .arg("--experimental-json")
+        .arg("tell me something")
+        .assert()
+        .code(0)
+        .stdout(contains(r#""type":"turn.retrying""#))
+        .stdout(contains("recovered"));
+
+    let retry_request = mock.last_request().expect("retry request");
+    assert!(
+        retry_request
+            .message_input_texts("user")
+            .iter()
+            .any(|text| text.contains("cut off by a connection error")),
+        "retry turn should ask the model to continue"
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exits_with_stream_error_code_when_retries_run_out() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+
+    let server = responses::start_mock_server().await;
+    let mut bodies = failed_turn_bodies();
+    bodies.extend(failed_turn_bodies());
+    responses::mount_sse_sequence(&server, bodies).await;
+
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--retry")
+        .arg("1")
+        .arg("--retry-backoff-ms")
+        .arg("1")
+        .arg("tell me something")
+        .assert()
+        .code(codex_exec::exit_code::STREAM_ERROR)
+        .stderr(contains("retry: turn failed"));
+
+    Ok(())
+}
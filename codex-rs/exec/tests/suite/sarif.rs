@@ -0,0 +1,92 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exec_prints_final_message_findings_as_sarif() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+
+    let findings = serde_json::json!({
+        "findings": [
+            {
+                "title": "[P1] Off-by-one in loop bound",
+                "body": "The loop skips the last element.",
+                "confidence_score": 0.8,
+                "priority": 1,
+                "code_location": {
+                    "absolute_file_path": test.cwd_path().join("src/lib.rs"),
+                    "line_range": { "start": 10, "end": 12 }
+                }
+            },
+            {
+                "title": "[P3] Typo in comment",
+                "body": "",
+                "confidence_score": 0.5,
+                "priority": 3,
+                "code_location": {
+                    "absolute_file_path": "/elsewhere/notes.md",
+                    "line_range": { "start": 0, "end": 0 }
+                }
+            }
+        ]
+    });
+    let message = format!("```json\n{findings}\n```");
+
+    let server = responses::start_mock_server().await;
+    let body = responses::sse(vec![
+        responses::ev_response_created("resp1"),
+        responses::ev_assistant_message("m1", &message),
+        responses::ev_completed("resp1"),
+    ]);
+    responses::mount_sse_once(&server, body).await;
+
+    let output = test
+        .cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--output-format")
+        .arg("sarif")
+        .arg("review the change")
+        .output()?;
+    assert!(output.status.success(), "{output:?}");
+
+    let log: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(log["version"], "2.1.0");
+    let results = log["runs"][0]["results"].as_array().expect("results array");
+    let summary = results
+        .iter()
+        .map(|result| {
+            let location = &result["locations"][0]["physicalLocation"];
+            (
+                result["level"].as_str().unwrap().to_string(),
+                location["artifactLocation"]["uri"]
+                    .as_str()
+                    .unwrap()
+                    .to_string(),
+                location["region"]["startLine"].as_u64().unwrap(),
+                location["region"]["endLine"].as_u64().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            ("error".to_string(), "src/lib.rs".to_string(), 10, 12),
+            (
+                "note".to_string(),
+                "file:///elsewhere/notes.md".to_string(),
+                1,
+                1
+            ),
+        ]
+    );
+    assert_eq!(
+        results[0]["message"]["text"],
+        "[P1] Off-by-one in loop bound\n\nThe loop skips the last element."
+    );
+
+    Ok(())
+}
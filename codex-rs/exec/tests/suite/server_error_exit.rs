@@ -4,30 +4,34 @@
 use core_test_support::responses;
 use core_test_support::test_codex_exec::test_codex_exec;
 
-/// Verify that when the server reports an error, `codex-exec` exits with a
-/// non-zero status code so automation can detect failures.
+/// Verify that when the server reports an error, `codex-exec` exits with the
+/// stream error status code so automation can detect failures.
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn exits_non_zero_when_server_reports_error() -> anyhow::Result<()> {
     let test = test_codex_exec();
 
-    // Mock a simple Responses API SSE stream that immediately reports a
-    // `response.failed` event with an error message.
+    // Mock a Responses API SSE stream that immediately reports a
+    // `response.failed` event, for the first request and each of the five
+    // stream retries. The retry hint keeps the retries fast.
     let server = responses::start_mock_server().await;
     let body = responses::sse(vec![serde_json::json!({
         "type": "response.failed",
         "response": {
             "id": "resp_err_1",
-            "error": {"code": "rate_limit_exceeded", "message": "synthetic server error"}
+            "error": {
+                "code": "rate_limit_exceeded",
+                "message": "synthetic server error; try again in 1ms"
+            }
         }
     })]);
-    responses::mount_sse_once(&server, body).await;
+    responses::mount_sse_sequence(&server, vec![body; 6]).await;
 
     test.cmd_with_server(&server)
         .arg("--skip-git-repo-check")
         .arg("tell me something")
         .arg("--experimental-json")
         .assert()
-        .code(1);
+        .code(codex_exec::exit_code::STREAM_ERROR);
 
     Ok(())
 }
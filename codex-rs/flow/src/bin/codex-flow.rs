@@ -1,5 +1,3 @@
-use anyhow::Result;
-
-fn main() -> Result<()> {
-    codex_flow::cli::run()
+fn main() {
+    std::process::exit(codex_flow::cli::run());
 }
@@ -0,0 +1,684 @@
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::fs::File;
+use std::fs::{self};
+use std::io::Read;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use chrono::Utc;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::config::FlowConfig;
+use crate::runner::WorkflowRunState;
+use crate::runner::migrations;
+use crate::runtime::config as runtime_config;
+use crate::runtime::state_store as runtime_state;
+
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+pub const MANIFEST_PATH: &str = "manifest.json";
+pub const STATE_PATH: &str = "state.resume.json";
+
+/// Describes every file in an exported run bundle and where it lived on the
+/// exporting machine, so an import can remap recorded paths.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub workflow_name: String,
+    pub run_id: String,
+    pub exported_at: String,
+    pub codex_flow_version: String,
+    pub entries: Vec<BundleEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BundleEntry {
+    pub kind: EntryKind,
+    /// Path inside the archive
+    pub path: String,
+    /// Path as recorded on the exporting machine
+    pub original_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step_index: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    State,
+    DebugLog,
+    HumanLog,
+    Result,
+    Workflow,
+    Prompt,
+    Artifact,
+}
+
+/// Writes `<workflow_file>`'s run `run_id` (state, per-step logs and results,
+/// the workflow definition, its prompts, and declared file outputs) into a
+/// gzip-compressed tarball at `output`.
+pub fn export_run(
+    workflow_file: &Path,
+    cfg: &FlowConfig,
+    workflow_name: &str,
+    run_id: &str,
+    output: &Path,
+) -> Result<BundleManifest> {
+    let state_path = runtime_state::state_root()
+        .join(workflow_name)
+        .join(format!("{run_id}.resume.json"));
+    if !state_path.exists() {
+        bail!(
+            "no state recorded for workflow `{workflow_name}` run `{run_id}` (expected {})",
+            state_path.display()
+        );
+    }
+    let state = WorkflowRunState::load_from_path(&state_path)?;
+
+    let mut planned = Vec::new();
+    planned.push((
+        entry(EntryKind::State, STATE_PATH, &state_path, None),
+        state_path,
+    ));
+    planned.push(planned_file(EntryKind::Workflow, workflow_file, None));
+    for (kind, path, step_index) in run_artifacts(&state) {
+        planned.push(planned_file(kind, &path, step_index));
+    }
+    for (kind, path) in workflow_inputs(cfg, workflow_name) {
+        planned.push(planned_file(kind, &path, None));
+    }
+
+    let mut seen = BTreeSet::new();
+    planned.retain(|(bundle_entry, source)| {
+        if !seen.insert(bundle_entry.path.clone()) {
+            return false;
+        }
+        if source.is_file() {
+            true
+        } else {
+            eprintln!(
+                "[export] skipping missing {} {}",
+                kind_label(bundle_entry.kind),
+                source.display()
+            );
+            false
+        }
+    });
+
+    let manifest = BundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        workflow_name: workflow_name.to_string(),
+        run_id: run_id.to_string(),
+        exported_at: Utc::now().to_rfc3339(),
+        codex_flow_version: env!("CARGO_PKG_VERSION").to_string(),
+        entries: planned.iter().map(|(entry, _)| entry.clone()).collect(),
+    };
+    write_archive(output, &manifest, &planned)?;
+    Ok(manifest)
+}
+
+fn write_archive(
+    output: &Path,
+    manifest: &BundleManifest,
+    files: &[(BundleEntry, PathBuf)],
+) -> Result<()> {
+    if let Some(parent) = output
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let file = File::create(output)
+        .with_context(|| format!("failed to create bundle {}", output.display()))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let manifest_json = serde_json::to_vec_pretty(manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    archive
+        .append_data(&mut header, MANIFEST_PATH, manifest_json.as_slice())
+        .context("failed to write bundle manifest")?;
+
+    for (entry, source) in files {
+        archive
+            .append_path_with_name(source, &entry.path)
+            .with_context(|| format!("failed to add {} to bundle", source.display()))?;
+    }
+    archive
+        .into_inner()
+        .and_then(GzEncoder::finish)
+        .with_context(|| format!("failed to finish bundle {}", output.display()))?;
+    Ok(())
+}
+
+/// Per-step debug logs and result markdown recorded in the state, plus the
+/// human-readable log that sits next to each debug log under `runtime/logs`.
+fn run_artifacts(state: &WorkflowRunState) -> Vec<(EntryKind, PathBuf, Option<usize>)> {
+    let mut artifacts = Vec::new();
+    for step in &state.steps {
+        // Steps stopped by `codex-flow kill` never produced a result.
+        if !step.memory_path.is_empty() {
+            artifacts.push((
+                EntryKind::Result,
+                PathBuf::from(&step.memory_path),
+                Some(step.index),
+            ));
+        }
+        let Some(debug_log) = step.debug_log.as_deref().map(PathBuf::from) else {
+            continue;
+        };
+        if let (Some(stem), Some(debug_dir)) = (debug_log.file_stem(), debug_log.parent())
+            && let Some(runtime_dir) = debug_dir.parent()
+        {
+            let mut human_log = runtime_dir.join("logs").join(stem);
+            human_log.set_extension("log");
+            artifacts.push((EntryKind::HumanLog, human_log, Some(step.index)));
+        }
+        artifacts.push((EntryKind::DebugLog, debug_log, Some(step.index)));
+    }
+    artifacts
+}
+
+/// Prompts used by the workflow's steps and file outputs they declare.
+fn workflow_inputs(cfg: &FlowConfig, workflow_name: &str) -> Vec<(EntryKind, PathBuf)> {
+    let Some(workflow) = cfg.workflows.get(workflow_name) else {
+        return Vec::new();
+    };
+    let mut inputs = Vec::new();
+    for step in &workflow.steps {
+        let prompt = step.prompt.as_deref().or_else(|| {
+            cfg.agents
+                .get(&step.agent)
+                .map(|agent| agent.prompt.as_str())
+        });
+        if let Some(prompt) = prompt {
+            inputs.push((EntryKind::Prompt, PathBuf::from(prompt)));
+        }
+        if step.output.kind == "file"
+            && let Some(path) = &step.output.path
+        {
+            inputs.push((EntryKind::Artifact, path.clone()));
+        }
+    }
+    inputs
+}
+
+fn planned_file(
+    kind: EntryKind,
+    source: &Path,
+    step_index: Option<usize>,
+) -> (BundleEntry, PathBuf) {
+    let bundle_path = bundle_path_for(kind, source);
+    (
+        entry(kind, &bundle_path, source, step_index),
+        source.to_path_buf(),
+    )
+}
+
+fn entry(kind: EntryKind, path: &str, source: &Path, step_index: Option<usize>) -> BundleEntry {
+    BundleEntry {
+        kind,
+        path: path.to_string(),
+        original_path: source.display().to_string(),
+        step_index,
+    }
+}
+
+pub struct ImportSummary {
+    pub manifest: BundleManifest,
+    pub run_id: String,
+    pub state_path: PathBuf,
+    pub written: usize,
+    pub unchanged: usize,
+}
+
+/// Unpacks a bundle produced by [`export_run`] into the local workspace:
+/// runtime artifacts go under `<runtime-root>/imports/<workflow>-<run-id>/`,
+/// prompts/artifacts/workflow under `<flow-dir>/imports/<workflow>-<run-id>/`,
+/// and the state's recorded step paths are rewritten to the new locations.
+/// `run_id` (the bundle's own when `None`) names both directories, so the
+/// same bundle can be imported under several ids. Existing files with
+/// different content are only replaced when `force` is set.
+pub fn import_bundle(bundle: &Path, run_id: Option<&str>, force: bool) -> Result<ImportSummary> {
+    import_bundle_at(
+        bundle,
+        run_id,
+        force,
+        &runtime_config::flow_dir(),
+        &runtime_state::runtime_root(),
+    )
+}
+
+fn import_bundle_at(
+    bundle: &Path,
+    run_id: Option<&str>,
+    force: bool,
+    flow_dir: &Path,
+    runtime_root: &Path,
+) -> Result<ImportSummary> {
+    let mut contents = read_archive(bundle)?;
+    let manifest_bytes = contents
+        .remove(MANIFEST_PATH)
+        .with_context(|| format!("{} has no {MANIFEST_PATH}", bundle.display()))?;
+    let manifest: BundleManifest =
+        serde_json::from_slice(&manifest_bytes).context("failed to parse bundle manifest")?;
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        bail!(
+            "bundle format version {} is newer than supported {BUNDLE_FORMAT_VERSION}",
+            manifest.format_version
+        );
+    }
+    // The names become path components below, and a bundle is untrusted.
+    check_path_name("workflow name", &manifest.workflow_name)?;
+    check_path_name("run id", &manifest.run_id)?;
+    let run_id = run_id.unwrap_or(&manifest.run_id).to_string();
+    check_path_name("run id", &run_id)?;
+    let import_name = format!("{}-{run_id}", manifest.workflow_name);
+
+    let mut state = None;
+    let mut files = Vec::new();
+    let mut remapped = HashMap::new();
+    for entry in &manifest.entries {
+        let data = contents
+            .remove(&entry.path)
+            .with_context(|| format!("bundle is missing {}", entry.path))?;
+        if entry.kind == EntryKind::State {
+            let (value, _) = migrations::upgrade(&String::from_utf8_lossy(&data))
+                .context("failed to migrate bundled workflow state")?;
+            let parsed: WorkflowRunState =
+                serde_json::from_value(value).context("failed to parse bundled workflow state")?;
+            state = Some(parsed);
+            continue;
+        }
+        let destination = local_path_for(entry, &import_name, flow_dir, runtime_root)?;
+        remapped.insert(
+            entry.original_path.clone(),
+            destination.display().to_string(),
+        );
+        files.push((destination, data));
+    }
+    let mut state = state.context("bundle does not contain a workflow state")?;
+    remap_state(&mut state, &remapped, &run_id);
+
+    let state_path = runtime_root
+        .join("state")
+        .join(&manifest.workflow_name)
+        .join(format!("{run_id}.resume.json"));
+    let conflicts: Vec<String> = files
+        .iter()
+        .filter(|(path, data)| matches_existing(path, data) == Some(false))
+        .map(|(path, _)| path.display().to_string())
+        .chain(
+            state_path
+                .exists()
+                .then(|| state_path.display().to_string()),
+        )
+        .collect();
+    if !conflicts.is_empty() && !force {
+        bail!(
+            "import would overwrite existing files (pass --force to replace them or --run-id to import under another id):\n  {}",
+            conflicts.join("\n  ")
+        );
+    }
+
+    let mut written = 0;
+    let mut unchanged = 0;
+    for (path, data) in &files {
+        if matches_existing(path, data) == Some(true) {
+            unchanged += 1;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        fs::write(path, data).with_context(|| format!("failed to write {}", path.display()))?;
+        written += 1;
+    }
+    state.write_to_path(&state_path)?;
+
+    Ok(ImportSummary {
+        manifest,
+        run_id,
+        state_path,
+        written,
+        unchanged,
+    })
+}
+
+fn read_archive(bundle: &Path) -> Result<HashMap<String, Vec<u8>>> {
+    let file = File::open(bundle)
+        .with_context(|| format!("failed to open bundle {}", bundle.display()))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let mut contents = HashMap::new();
+    for entry in archive
+        .entries()
+        .with_context(|| format!("failed to read bundle {}", bundle.display()))?
+    {
+        let mut entry = entry.context("failed to read bundle entry")?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path().context("invalid path in bundle")?.into_owned();
+        if !is_plain_relative(&path) {
+            bail!("bundle entry {} escapes the bundle root", path.display());
+        }
+        let mut data = Vec::new();
+        entry
+            .read_to_end(&mut data)
+            .with_context(|| format!("failed to read {} from bundle", path.display()))?;
+        contents.insert(to_archive_path(&path), data);
+    }
+    Ok(contents)
+}
+
+/// Runtime artifacts land under `<runtime-root>/imports/<import_name>/` with
+/// their runtime layout; every other file goes under
+/// `<flow-dir>/imports/<import_name>/` at its path inside the bundle. The
+/// recorded `original_path` never picks the destination, so a crafted bundle
+/// cannot write elsewhere in the workspace.
+fn local_path_for(
+    entry: &BundleEntry,
+    import_name: &str,
+    flow_dir: &Path,
+    runtime_root: &Path,
+) -> Result<PathBuf> {
+    if !is_plain_relative(Path::new(&entry.path)) {
+        bail!("bundle entry {} escapes the bundle root", entry.path);
+    }
+    let (root, relative) = match entry.path.strip_prefix("runtime/") {
+        Some(relative) => (runtime_root, relative),
+        None => (flow_dir, entry.path.as_str()),
+    };
+    Ok(root.join("imports").join(import_name).join(relative))
+}
+
+/// Rejects names that would not stay a single path component: empty, `.`,
+/// `..`, or containing a separator.
+fn check_path_name(label: &str, name: &str) -> Result<()> {
+    let path = Path::new(name);
+    let single_component = matches!(
+        path.components().collect::<Vec<_>>().as_slice(),
+        [Component::Normal(_)]
+    );
+    if !single_component || name.contains('/') || name.contains('\\') {
+        bail!("bundle {label} `{name}` is not a plain file name");
+    }
+    Ok(())
+}
+
+fn remap_state(state: &mut WorkflowRunState, remapped: &HashMap<String, String>, run_id: &str) {
+    state.run_id = run_id.to_string();
+    for step in &mut state.steps {
+        if let Some(path) = remapped.get(&step.memory_path) {
+            step.memory_path = path.clone();
+        }
+        if let Some(path) = step.debug_log.as_ref().and_then(|log| remapped.get(log)) {
+            step.debug_log = Some(path.clone());
+        }
+    }
+}
+
+/// `None` when `path` cannot be read (usually because it does not exist yet),
+/// otherwise whether its content equals `data`.
+fn matches_existing(path: &Path, data: &[u8]) -> Option<bool> {
+    fs::read(path).ok().map(|existing| existing == data)
+}
+
+/// Runtime artifacts keep their layout under `runtime/`; everything else goes
+/// under `files/`, keeping relative paths that stay inside the workspace.
+fn bundle_path_for(kind: EntryKind, source: &Path) -> String {
+    let runtime_root = runtime_state::runtime_root();
+    let runtime_relative = match (
+        std::path::absolute(source),
+        std::path::absolute(&runtime_root),
+    ) {
+        (Ok(source), Ok(root)) => source.strip_prefix(root).ok().map(Path::to_path_buf),
+        _ => None,
+    };
+    if let Some(relative) = runtime_relative.filter(|path| is_plain_relative(path)) {
+        return format!("runtime/{}", to_archive_path(&relative));
+    }
+    let file_name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| kind_label(kind).replace(' ', "-"));
+    match kind {
+        EntryKind::Workflow => format!("workflow/{file_name}"),
+        _ if is_plain_relative(source) => format!("files/{}", to_archive_path(source)),
+        _ => format!("files/{file_name}"),
+    }
+}
+
+/// True for relative paths made only of normal components (no `..`, no root).
+pub(crate) fn is_plain_relative(path: &Path) -> bool {
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        && path
+            .components()
+            .any(|component| matches!(component, Component::Normal(_)))
+}
+
+fn to_archive_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn kind_label(kind: EntryKind) -> &'static str {
+    match kind {
+        EntryKind::State => "state file",
+        EntryKind::DebugLog => "debug log",
+        EntryKind::HumanLog => "human log",
+        EntryKind::Result => "result",
+        EntryKind::Workflow => "workflow file",
+        EntryKind::Prompt => "prompt",
+        EntryKind::Artifact => "artifact",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn bundle_paths_stay_inside_the_archive() {
+        assert_eq!(
+            bundle_path_for(
+                EntryKind::Prompt,
+                Path::new(".codex-flow/prompts/review/main.md")
+            ),
+            "files/.codex-flow/prompts/review/main.md"
+        );
+        assert_eq!(
+            bundle_path_for(EntryKind::Artifact, Path::new("../outside/report.md")),
+            "files/report.md"
+        );
+        assert_eq!(
+            bundle_path_for(EntryKind::Workflow, Path::new("/abs/review.workflow.toml")),
+            "workflow/review.workflow.toml"
+        );
+    }
+
+    #[test]
+    fn imported_files_stay_in_the_import_directory() {
+        let flow_dir = Path::new(".codex-flow");
+        let runtime_root = Path::new(".codex-flow/runtime");
+        let hook = BundleEntry {
+            kind: EntryKind::Artifact,
+            path: "files/pre-commit".to_string(),
+            original_path: ".git/hooks/pre-commit".to_string(),
+            step_index: None,
+        };
+        assert_eq!(
+            local_path_for(&hook, "review-r1", flow_dir, runtime_root).expect("local path"),
+            flow_dir
+                .join("imports")
+                .join("review-r1")
+                .join("files/pre-commit")
+        );
+        let result = BundleEntry {
+            kind: EntryKind::Result,
+            path: "runtime/memory/01-main-agent-result.md".to_string(),
+            original_path: "/home/alice/.codex-flow/runtime/memory/01-main-agent-result.md"
+                .to_string(),
+            step_index: Some(0),
+        };
+        assert_eq!(
+            local_path_for(&result, "review-r1", flow_dir, runtime_root).expect("local path"),
+            runtime_root
+                .join("imports")
+                .join("review-r1")
+                .join("memory/01-main-agent-result.md")
+        );
+
+        let escaping = BundleEntry {
+            path: "files/../../x".to_string(),
+            ..hook
+        };
+        assert!(local_path_for(&escaping, "review-r1", flow_dir, runtime_root).is_err());
+    }
+
+    #[test]
+    fn importing_under_two_run_ids_keeps_both_copies() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let source = tmp.path().join("source");
+        fs::create_dir_all(&source).expect("create source dir");
+        let memory_path = "/home/alice/.codex-flow/runtime/memory/01-main-agent-result.md";
+        let state = WorkflowRunState {
+            schema_version: 2,
+            workflow_name: "review".to_string(),
+            run_id: "r1".to_string(),
+            resume_pointer: 1,
+            steps: vec![crate::runner::StepState {
+                index: 0,
+                status: crate::runner::StepStatus::Completed,
+                memory_path: memory_path.to_string(),
+                debug_log: None,
+                needs_real: false,
+                token_delta: None,
+            }],
+            token_usage: None,
+            started_at: None,
+            total_steps: Some(1),
+            pid: None,
+        };
+        let state_source = source.join(STATE_PATH);
+        state.write_to_path(&state_source).expect("write state");
+        let result_source = source.join("result.md");
+        fs::write(&result_source, "reviewed").expect("write result");
+        let files = vec![
+            (
+                entry(EntryKind::State, STATE_PATH, &state_source, None),
+                state_source,
+            ),
+            (
+                BundleEntry {
+                    kind: EntryKind::Result,
+                    path: "runtime/memory/01-main-agent-result.md".to_string(),
+                    original_path: memory_path.to_string(),
+                    step_index: Some(0),
+                },
+                result_source,
+            ),
+        ];
+        let manifest = BundleManifest {
+            format_version: BUNDLE_FORMAT_VERSION,
+            workflow_name: "review".to_string(),
+            run_id: "r1".to_string(),
+            exported_at: String::new(),
+            codex_flow_version: String::new(),
+            entries: files.iter().map(|(entry, _)| entry.clone()).collect(),
+        };
+        let bundle = tmp.path().join("review-r1.flow.tar.gz");
+        write_archive(&bundle, &manifest, &files).expect("write bundle");
+
+        let flow_dir = tmp.path().join(".codex-flow");
+        let runtime_root = flow_dir.join("runtime");
+        for run_id in ["r1-a", "r1-b"] {
+            let summary = import_bundle_at(&bundle, Some(run_id), false, &flow_dir, &runtime_root)
+                .expect("import bundle");
+            assert_eq!(summary.written, 1);
+            let imported = WorkflowRunState::load_from_path(&summary.state_path).expect("state");
+            let result = runtime_root
+                .join("imports")
+                .join(format!("review-{run_id}"))
+                .join("memory/01-main-agent-result.md");
+            assert_eq!(imported.run_id, run_id);
+            assert_eq!(imported.steps[0].memory_path, result.display().to_string());
+            assert_eq!(fs::read_to_string(&result).expect("result"), "reviewed");
+        }
+    }
+
+    #[test]
+    fn rejects_names_that_are_not_single_components() {
+        for name in ["review", "r1.imported"] {
+            assert!(check_path_name("run id", name).is_ok(), "{name}");
+        }
+        for name in ["", ".", "..", "../x", "a/b", "a\\b", "/abs"] {
+            assert!(check_path_name("run id", name).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn remaps_recorded_step_paths() {
+        let mut state = WorkflowRunState {
+            schema_version: 2,
+            workflow_name: "review".to_string(),
+            run_id: "r1".to_string(),
+            resume_pointer: 1,
+            steps: vec![crate::runner::StepState {
+                index: 0,
+                status: crate::runner::StepStatus::Completed,
+                memory_path: "/home/alice/.codex-flow/runtime/memory/01-main-agent-result.md"
+                    .to_string(),
+                debug_log: Some(
+                    "/home/alice/.codex-flow/runtime/debug/01-main-agent.json".to_string(),
+                ),
+                needs_real: false,
+                token_delta: None,
+            }],
+            token_usage: None,
+            started_at: None,
+            total_steps: Some(1),
+            pid: None,
+        };
+        let remapped = HashMap::from([
+            (
+                state.steps[0].memory_path.clone(),
+                ".codex-flow/runtime/memory/01-main-agent-result.md".to_string(),
+            ),
+            (
+                "/home/alice/.codex-flow/runtime/debug/01-main-agent.json".to_string(),
+                ".codex-flow/runtime/debug/01-main-agent.json".to_string(),
+            ),
+        ]);
+
+        remap_state(&mut state, &remapped, "r1-imported");
+        assert_eq!(state.run_id, "r1-imported");
+        assert_eq!(
+            state.steps[0].memory_path,
+            ".codex-flow/runtime/memory/01-main-agent-result.md"
+        );
+        assert_eq!(
+            state.steps[0].debug_log.as_deref(),
+            Some(".codex-flow/runtime/debug/01-main-agent.json")
+        );
+    }
+}
@@ -1,9 +1,13 @@
 use std::path::PathBuf;
 
 use clap::ArgAction;
+use clap::ArgGroup;
 use clap::Args;
 use clap::Parser;
 use clap::Subcommand;
+use clap::ValueEnum;
+
+use crate::failure::FailureClass;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -12,21 +16,76 @@ use clap::Subcommand;
     about = "Lightweight agent workflow runner (mock-first)"
 )]
 pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
     #[command(subcommand)]
     pub command: Command,
 }
 
+#[derive(Args, Debug, Default)]
+pub struct GlobalArgs {
+    /// Flow workspace root (default: $CODEX_FLOW_DIR or ./.codex-flow)
+    #[arg(long, global = true, value_name = "DIR")]
+    pub flow_dir: Option<PathBuf>,
+
+    /// Failure classes that still exit 0 (comma-separated: config, step, budget, interrupted)
+    #[arg(long, global = true, value_delimiter = ',', value_name = "CLASSES")]
+    pub exit_zero_on: Vec<FailureClass>,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Command {
+    /// Initialize a .codex-flow workspace with sample prompts and workflows
     Init(InitArgs),
+    /// Execute a workflow definition
     Run(RunArgs),
+    /// Continue a persisted run from its saved resume pointer
     Resume(ResumeArgs),
+    /// Inspect and maintain persisted workflow state
     State(StateArgs),
+    /// Generate workflow or agent stanzas with stub prompts
+    New(NewArgs),
+    /// Normalize workflow TOML layout
+    Fmt(FmtArgs),
+    /// List recorded runs with status, token usage, cost, and duration
+    Runs(RunsArgs),
+    /// Package a run's state, logs, results, and prompts into a tar.gz bundle
+    Export(ExportArgs),
+    /// Unpack a bundle created by `export` into the local workspace
+    Import(ImportArgs),
+    /// Stop a running workflow and mark its run interrupted
+    Kill(KillArgs),
+    /// Inspect, send, or clear opt-in usage metrics (enable with CODEX_FLOW_METRICS=1)
+    Metrics(MetricsArgs),
+    /// Generate man pages and a markdown CLI reference from the clap definitions
+    #[command(hide = true)]
+    Docs(DocsArgs),
+}
+
+impl Command {
+    /// Subcommand name recorded in usage metrics.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Init(_) => "init",
+            Command::Run(_) => "run",
+            Command::Resume(_) => "resume",
+            Command::State(_) => "state",
+            Command::New(_) => "new",
+            Command::Fmt(_) => "fmt",
+            Command::Runs(_) => "runs",
+            Command::Export(_) => "export",
+            Command::Import(_) => "import",
+            Command::Kill(_) => "kill",
+            Command::Metrics(_) => "metrics",
+            Command::Docs(_) => "docs",
+        }
+    }
 }
 
 #[derive(Args, Debug)]
 pub struct InitArgs {
-    /// Target directory to place .codex-flow (default: current dir)
+    /// Target directory to place .codex-flow (default: current dir; ignored with --flow-dir)
     #[arg(long)]
     pub dir: Option<PathBuf>,
 
@@ -41,8 +100,8 @@ pub struct InitArgs {
 
 #[derive(Args, Debug)]
 pub struct RunArgs {
-    /// Path to workflow TOML file
-    pub file: PathBuf,
+    /// Path to workflow TOML file (omit on a terminal to pick from <flow-dir>/workflows)
+    pub file: Option<PathBuf>,
 
     /// Force mock execution (overrides defaults.mock)
     #[arg(long, action = ArgAction::SetTrue, conflicts_with = "no_mock")]
@@ -52,9 +111,9 @@ pub struct RunArgs {
     #[arg(long, action = ArgAction::SetTrue, conflicts_with = "mock")]
     pub no_mock: bool,
 
-    /// Verbose logs
-    #[arg(long)]
-    pub verbose: bool,
+    /// Increase log detail: -v step banners, -vv resolved configs and commands, -vvv engine diagnostics
+    #[arg(short = 'v', long = "verbose", action = ArgAction::Count)]
+    pub verbose: u8,
 
     /// Custom run identifier used for resume state files
     #[arg(long, value_name = "RUN_ID")]
@@ -63,6 +122,19 @@ pub struct RunArgs {
     /// Resume from an existing state file instead of starting from step-0
     #[arg(long, value_name = "STATE_PATH")]
     pub resume_from: Option<PathBuf>,
+
+    /// Run as if started in DIR (runtime artifacts and engine subprocesses use it)
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Refuse to start if any step has a missing prompt, unknown agent,
+    /// unsupported engine, or unwritable output directory
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Stream runner progress events as JSON lines to clients of a unix socket at PATH
+    #[arg(long, value_name = "PATH")]
+    pub events_socket: Option<PathBuf>,
 }
 
 #[derive(Args, Debug)]
@@ -86,9 +158,17 @@ pub struct ResumeArgs {
     #[arg(long, action = ArgAction::SetTrue, hide = true)]
     pub mock_only: bool,
 
-    /// Verbose logs
-    #[arg(long)]
-    pub verbose: bool,
+    /// Increase log detail: -v step banners, -vv resolved configs and commands, -vvv engine diagnostics
+    #[arg(short = 'v', long = "verbose", action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Resume as if started in DIR (runtime artifacts and engine subprocesses use it)
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Stream runner progress events as JSON lines to clients of a unix socket at PATH
+    #[arg(long, value_name = "PATH")]
+    pub events_socket: Option<PathBuf>,
 }
 
 #[derive(Args, Debug)]
@@ -99,6 +179,7 @@ pub struct StateArgs {
 
 #[derive(Subcommand, Debug)]
 pub enum StateCommand {
+    /// Delete resume state files older than a threshold
     Prune(StatePruneArgs),
 }
 
@@ -107,4 +188,168 @@ pub struct StatePruneArgs {
     /// Delete resume files older than this many days
     #[arg(long, value_name = "DAYS")]
     pub days: u64,
+
+    /// Always keep the N most recent resume files per workflow, regardless of age
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub keep_last: usize,
+
+    /// Report what would be deleted without removing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct FmtArgs {
+    /// Workflow files to format (default: every .toml under <flow-dir>/workflows)
+    pub files: Vec<PathBuf>,
+
+    /// Report files that need formatting and exit non-zero instead of rewriting them
+    #[arg(long)]
+    pub check: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct NewArgs {
+    #[command(subcommand)]
+    pub command: NewCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum NewCommand {
+    /// Create <flow-dir>/workflows/<NAME>.workflow.toml with a starter agent and prompt stub
+    Workflow(NewWorkflowArgs),
+    /// Append an agent stanza (and a step using it) to an existing workflow file
+    Agent(NewAgentArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct NewWorkflowArgs {
+    /// Workflow name used for the file name and the `name` key
+    pub name: String,
+
+    /// Name of the starter agent (default: main)
+    #[arg(long, value_name = "AGENT")]
+    pub agent: Option<String>,
+
+    /// Engine for the starter agent (prompted on a TTY when omitted)
+    #[arg(long)]
+    pub engine: Option<String>,
+
+    /// Model for the starter agent (prompted on a TTY when omitted)
+    #[arg(long)]
+    pub model: Option<String>,
+
+    /// Overwrite an existing workflow file
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct NewAgentArgs {
+    /// Agent name used as the `[agents.<NAME>]` key
+    pub name: String,
+
+    /// Workflow file to append the agent to
+    #[arg(long, value_name = "FILE")]
+    pub workflow: PathBuf,
+
+    /// Engine for the agent (prompted on a TTY when omitted)
+    #[arg(long)]
+    pub engine: Option<String>,
+
+    /// Model for the agent (prompted on a TTY when omitted)
+    #[arg(long)]
+    pub model: Option<String>,
+
+    /// Only add the agent definition without appending a workflow step
+    #[arg(long)]
+    pub no_step: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct RunsArgs {
+    /// Only list runs of this workflow
+    #[arg(long, value_name = "NAME")]
+    pub workflow: Option<String>,
+
+    /// Sort order: newest first (date) or most expensive first (cost)
+    #[arg(long, value_enum, default_value_t = RunsSort::Date)]
+    pub sort: RunsSort,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum RunsSort {
+    Date,
+    Cost,
+}
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    /// Path to workflow TOML file
+    pub file: PathBuf,
+
+    /// Run identifier to export
+    #[arg(long, value_name = "RUN_ID")]
+    pub run_id: String,
+
+    /// Bundle path (default: ./<workflow>-<run-id>.flow.tar.gz)
+    #[arg(long, short = 'o', value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ImportArgs {
+    /// Bundle created by `codex-flow export`
+    pub bundle: PathBuf,
+
+    /// Store the run under this identifier instead of the exported one
+    #[arg(long, value_name = "RUN_ID")]
+    pub run_id: Option<String>,
+
+    /// Overwrite existing files and state that differ from the bundle
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct KillArgs {
+    /// Run identifier of the run to stop
+    #[arg(long, value_name = "RUN_ID")]
+    pub run_id: String,
+
+    /// Workflow name, needed when several workflows share the run id
+    #[arg(long, value_name = "NAME")]
+    pub workflow: Option<String>,
+
+    /// Seconds to wait after SIGINT before sending SIGKILL
+    #[arg(long, value_name = "SECS", default_value_t = 10)]
+    pub grace: u64,
+}
+
+#[derive(Args, Debug)]
+pub struct MetricsArgs {
+    #[command(subcommand)]
+    pub command: MetricsCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum MetricsCommand {
+    /// Summarize locally recorded usage metrics per command
+    Show,
+    /// Send recorded metrics to CODEX_FLOW_METRICS_ENDPOINT and clear them
+    Flush,
+    /// Delete locally recorded metrics without sending them
+    Clear,
+}
+
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("outputs").required(true).multiple(true)))]
+pub struct DocsArgs {
+    /// Write the markdown reference for every subcommand and flag to PATH
+    #[arg(long, value_name = "PATH", group = "outputs")]
+    pub markdown: Option<PathBuf>,
+
+    /// Write one man page per (sub)command into DIR
+    #[arg(long, value_name = "DIR", group = "outputs")]
+    pub man_dir: Option<PathBuf>,
 }
@@ -0,0 +1,201 @@
+use std::fs;
+
+use anyhow::Context;
+use anyhow::Result;
+use clap::Arg;
+use clap::CommandFactory;
+
+use crate::cli::args::Cli;
+use crate::cli::args::DocsArgs;
+
+pub fn run(args: DocsArgs) -> Result<()> {
+    if let Some(path) = &args.markdown {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        fs::write(path, render_markdown())
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        println!("[docs] wrote {}", path.display());
+    }
+    if let Some(dir) = &args.man_dir {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        let pages = man_pages()?;
+        for (file_name, page) in &pages {
+            let path = dir.join(file_name);
+            fs::write(&path, page)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+        }
+        println!(
+            "[docs] wrote {} man page(s) to {}",
+            pages.len(),
+            dir.display()
+        );
+    }
+    Ok(())
+}
+
+/// The fully built `codex-flow` command, with global flags propagated and
+/// bin names filled in for every subcommand.
+fn root_command() -> clap::Command {
+    let mut cmd = Cli::command();
+    cmd.build();
+    cmd
+}
+
+/// Visits `cmd` and its visible subcommands depth-first with their full
+/// invocation path, e.g. `["codex-flow", "state", "prune"]`.
+fn walk(
+    cmd: &clap::Command,
+    path: &mut Vec<String>,
+    visit: &mut dyn FnMut(&[String], &clap::Command),
+) {
+    path.push(cmd.get_name().to_string());
+    visit(path, cmd);
+    for sub in cmd.get_subcommands() {
+        if !sub.is_hide_set() && sub.get_name() != "help" {
+            walk(sub, path, visit);
+        }
+    }
+    path.pop();
+}
+
+/// One man page per command, named after its invocation path
+/// (`codex-flow-state-prune.1`).
+fn man_pages() -> Result<Vec<(String, Vec<u8>)>> {
+    let mut commands = Vec::new();
+    walk(&root_command(), &mut Vec::new(), &mut |path, cmd| {
+        commands.push((path.join("-"), cmd.clone()));
+    });
+    commands
+        .into_iter()
+        .map(|(name, cmd)| {
+            let mut page = Vec::new();
+            clap_mangen::Man::new(cmd.display_name(name.clone()))
+                .render(&mut page)
+                .with_context(|| format!("failed to render man page for {name}"))?;
+            Ok((format!("{name}.1"), page))
+        })
+        .collect()
+}
+
+fn render_markdown() -> String {
+    let mut out = String::from(
+        "# codex-flow CLI Reference\n\n\
+         <!-- Generated by `codex-flow docs --markdown`; edit the clap definitions instead. -->\n",
+    );
+    walk(&root_command(), &mut Vec::new(), &mut |path, cmd| {
+        let is_root = path.len() == 1;
+        out.push_str(&format!("\n## `{}`\n\n", path.join(" ")));
+        if let Some(about) = cmd.get_long_about().or_else(|| cmd.get_about()) {
+            out.push_str(&format!("{}\n\n", escape_markdown(&about.to_string())));
+        }
+        let usage = cmd.clone().render_usage().to_string();
+        out.push_str(&format!("```text\n{}\n```\n", usage.trim()));
+
+        // Global flags are documented once, on the root command.
+        let args: Vec<&Arg> = cmd
+            .get_arguments()
+            .filter(|arg| !arg.is_hide_set())
+            .filter(|arg| !matches!(arg.get_id().as_str(), "help" | "version"))
+            .filter(|arg| is_root || !arg.is_global_set())
+            .collect();
+        if args.is_empty() {
+            return;
+        }
+        out.push_str("\n| Argument | Description |\n| --- | --- |\n");
+        for arg in args {
+            out.push_str(&format!(
+                "| {} | {} |\n",
+                arg_label(arg),
+                arg_description(arg)
+            ));
+        }
+    });
+    out
+}
+
+fn arg_label(arg: &Arg) -> String {
+    let value = arg
+        .get_value_names()
+        .map(|names| names.join(" "))
+        .unwrap_or_else(|| arg.get_id().as_str().to_uppercase());
+    if arg.is_positional() {
+        return format!("`<{value}>`");
+    }
+    let mut names = Vec::new();
+    if let Some(short) = arg.get_short() {
+        names.push(format!("`-{short}`"));
+    }
+    if let Some(long) = arg.get_long() {
+        if arg.get_action().takes_values() {
+            names.push(format!("`--{long} <{value}>`"));
+        } else {
+            names.push(format!("`--{long}`"));
+        }
+    }
+    names.join(", ")
+}
+
+fn arg_description(arg: &Arg) -> String {
+    let mut description = arg
+        .get_help()
+        .map(|help| {
+            help.to_string()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .unwrap_or_default();
+    if arg.get_action().takes_values() {
+        let possible: Vec<String> = arg
+            .get_possible_values()
+            .iter()
+            .filter(|value| !value.is_hide_set())
+            .map(|value| format!("`{}`", value.get_name()))
+            .collect();
+        if !possible.is_empty() {
+            description.push_str(&format!(" (values: {})", possible.join(", ")));
+        }
+        let defaults: Vec<String> = arg
+            .get_default_values()
+            .iter()
+            .map(|value| value.to_string_lossy().into_owned())
+            .collect();
+        if !defaults.is_empty() {
+            description.push_str(&format!(" (default: `{}`)", defaults.join(",")));
+        }
+    }
+    escape_markdown(description.trim())
+}
+
+/// Help text is plain prose; keeps `<placeholder>` and `|` from turning into
+/// HTML tags or table columns.
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|").replace('<', "\\<")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn committed_reference_matches_clap_definitions() {
+        assert_eq!(
+            include_str!("../../docs/flow/cli-reference.md"),
+            render_markdown(),
+            "docs/flow/cli-reference.md is stale; regenerate it with \
+             `cargo run -p codex-flow -- docs --markdown flow/docs/flow/cli-reference.md`"
+        );
+
+        let pages: Vec<String> = man_pages()
+            .unwrap()
+            .into_iter()
+            .map(|(file_name, _)| file_name)
+            .collect();
+        assert!(pages.contains(&"codex-flow.1".to_string()));
+        assert!(pages.contains(&"codex-flow-state-prune.1".to_string()));
+        assert!(!pages.iter().any(|page| page.starts_with("codex-flow-docs")));
+    }
+}
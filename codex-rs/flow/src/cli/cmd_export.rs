@@ -0,0 +1,25 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::bundle;
+use crate::cli::args::ExportArgs;
+use crate::cli::load_workflow;
+use crate::cli::validate_run_id;
+
+pub fn run(args: ExportArgs) -> Result<()> {
+    validate_run_id(&args.run_id)?;
+    let (cfg, workflow_name, _) = load_workflow(&args.file)?;
+    let output = args
+        .output
+        .unwrap_or_else(|| PathBuf::from(format!("{workflow_name}-{}.flow.tar.gz", args.run_id)));
+    let manifest = bundle::export_run(&args.file, &cfg, &workflow_name, &args.run_id, &output)?;
+    println!(
+        "[export] wrote {} file(s) for `{}` run `{}` to {}",
+        manifest.entries.len(),
+        workflow_name,
+        args.run_id,
+        output.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,362 @@
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use toml_edit::Decor;
+use toml_edit::DocumentMut;
+use toml_edit::Item;
+use toml_edit::RawString;
+use toml_edit::Table;
+use walkdir::WalkDir;
+
+use crate::cli::args::FmtArgs;
+use crate::runtime::config as runtime_config;
+
+const ROOT_ORDER: &[&str] = &[
+    "name",
+    "version",
+    "defaults",
+    "engines",
+    "agents",
+    "workflow",
+    "workflows",
+    "vars",
+];
+const DEFAULTS_ORDER: &[&str] = &["engine", "model", "mock", "run_id_template"];
+const ENGINES_ORDER: &[&str] = &["codex", "codemachine"];
+const ENGINE_ORDER: &[&str] = &["bin", "args"];
+const AGENT_ORDER: &[&str] = &[
+    "engine",
+    "model",
+    "profile",
+    "reasoning_effort",
+    "reasoning_summary",
+    "prompt",
+];
+const WORKFLOW_ORDER: &[&str] = &["description", "steps"];
+const STEP_ORDER: &[&str] = &[
+    "agent",
+    "description",
+    "engine",
+    "model",
+    "reasoning_effort",
+    "reasoning_summary",
+    "prompt",
+    "input",
+    "output",
+];
+const STEP_INPUT_ORDER: &[&str] = &["template"];
+const STEP_OUTPUT_ORDER: &[&str] = &["kind", "path"];
+
+pub fn run(args: FmtArgs) -> Result<()> {
+    let files = if args.files.is_empty() {
+        discover_workflow_files(&runtime_config::flow_dir().join("workflows"))?
+    } else {
+        args.files
+    };
+    if files.is_empty() {
+        bail!("no workflow files found; pass one or more paths to format");
+    }
+
+    let mut unformatted = Vec::new();
+    for path in &files {
+        let original = fs::read_to_string(path)
+            .with_context(|| format!("failed to read workflow file {}", path.display()))?;
+        let formatted = format_workflow_toml(&original)
+            .with_context(|| format!("failed to format {}", path.display()))?;
+        if formatted == original {
+            continue;
+        }
+        if args.check {
+            println!("[fmt] would reformat {}", path.display());
+        } else {
+            fs::write(path, &formatted)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            println!("[fmt] formatted {}", path.display());
+        }
+        unformatted.push(path);
+    }
+
+    if args.check && !unformatted.is_empty() {
+        bail!(
+            "{} of {} workflow file(s) need formatting; run `codex-flow fmt`",
+            unformatted.len(),
+            files.len()
+        );
+    }
+    if unformatted.is_empty() {
+        println!("[fmt] {} file(s) already formatted", files.len());
+    }
+    Ok(())
+}
+
+pub(crate) fn discover_workflow_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in WalkDir::new(dir).sort_by_file_name() {
+        let entry = entry.with_context(|| format!("failed to walk {}", dir.display()))?;
+        if entry.file_type().is_file()
+            && entry.path().extension().and_then(|ext| ext.to_str()) == Some("toml")
+        {
+            files.push(entry.into_path());
+        }
+    }
+    Ok(files)
+}
+
+/// Re-serializes a workflow TOML document with canonical key/table ordering and
+/// whitespace while keeping comments attached to the keys and tables they precede.
+pub fn format_workflow_toml(input: &str) -> Result<String> {
+    let mut doc: DocumentMut = input.parse().context("failed to parse TOML")?;
+    let mut next_position = 1isize;
+    format_table(doc.as_table_mut(), Section::Root, &mut next_position);
+    let trailing = comment_block(Some(doc.trailing()));
+    doc.set_trailing(trailing);
+
+    let rendered = doc.to_string();
+    let mut formatted = rendered.trim_start_matches('\n').trim_end().to_string();
+    formatted.push('\n');
+
+    let before: toml::Value = toml::from_str(input).context("failed to parse TOML")?;
+    let after: toml::Value =
+        toml::from_str(&formatted).context("formatted output is not valid TOML")?;
+    if before != after {
+        bail!("formatting would change the meaning of the document");
+    }
+    Ok(formatted)
+}
+
+#[derive(Clone, Copy)]
+enum Section {
+    Root,
+    Defaults,
+    Engines,
+    Engine,
+    Agents,
+    Agent,
+    Workflows,
+    Workflow,
+    Step,
+    StepInput,
+    StepOutput,
+    Other,
+}
+
+impl Section {
+    fn key_order(self) -> &'static [&'static str] {
+        match self {
+            Section::Root => ROOT_ORDER,
+            Section::Defaults => DEFAULTS_ORDER,
+            Section::Engines => ENGINES_ORDER,
+            Section::Engine => ENGINE_ORDER,
+            Section::Agent => AGENT_ORDER,
+            Section::Workflow => WORKFLOW_ORDER,
+            Section::Step => STEP_ORDER,
+            Section::StepInput => STEP_INPUT_ORDER,
+            Section::StepOutput => STEP_OUTPUT_ORDER,
+            Section::Agents | Section::Workflows | Section::Other => &[],
+        }
+    }
+
+    fn child(self, key: &str) -> Section {
+        match (self, key) {
+            (Section::Root, "defaults") => Section::Defaults,
+            (Section::Root, "engines") => Section::Engines,
+            (Section::Root, "agents") => Section::Agents,
+            (Section::Root, "workflow") => Section::Workflow,
+            (Section::Root, "workflows") => Section::Workflows,
+            (Section::Engines, _) => Section::Engine,
+            (Section::Agents, _) => Section::Agent,
+            (Section::Workflows, _) => Section::Workflow,
+            (Section::Workflow, "steps") => Section::Step,
+            (Section::Step, "input") => Section::StepInput,
+            (Section::Step, "output") => Section::StepOutput,
+            _ => Section::Other,
+        }
+    }
+}
+
+/// Known keys sort in schema order; unknown keys (agent ids, vars) keep their
+/// original relative order after them.
+fn rank(order: &[&str], key: &str) -> usize {
+    order
+        .iter()
+        .position(|known| *known == key)
+        .unwrap_or(order.len())
+}
+
+fn format_table(table: &mut Table, section: Section, next_position: &mut isize) {
+    let order = section.key_order();
+    table
+        .sort_values_by(|left, _, right, _| rank(order, left.get()).cmp(&rank(order, right.get())));
+
+    for (mut key, item) in table.iter_mut() {
+        let child = section.child(key.get());
+        match item {
+            Item::Value(value) => {
+                normalize_key_decor(key.leaf_decor_mut());
+                normalize_value_decor(value.decor_mut());
+            }
+            Item::Table(child_table) => {
+                if !child_table.is_dotted() {
+                    child_table.set_position(*next_position);
+                    *next_position += 1;
+                    normalize_table_decor(child_table.decor_mut());
+                }
+                format_table(child_table, child, next_position);
+            }
+            Item::ArrayOfTables(tables) => {
+                for child_table in tables.iter_mut() {
+                    child_table.set_position(*next_position);
+                    *next_position += 1;
+                    normalize_table_decor(child_table.decor_mut());
+                    format_table(child_table, child, next_position);
+                }
+            }
+            Item::None => {}
+        }
+    }
+}
+
+fn normalize_key_decor(decor: &mut Decor) {
+    let comments = comment_block(decor.prefix());
+    decor.set_prefix(comments);
+    decor.set_suffix(" ");
+}
+
+fn normalize_value_decor(decor: &mut Decor) {
+    let trailing = trailing_comment(decor.suffix());
+    decor.set_prefix(" ");
+    decor.set_suffix(trailing);
+}
+
+fn normalize_table_decor(decor: &mut Decor) {
+    let comments = comment_block(decor.prefix());
+    let trailing = trailing_comment(decor.suffix());
+    decor.set_prefix(format!("\n{comments}"));
+    decor.set_suffix(trailing);
+}
+
+/// Keeps only full-line comments, de-indented, one per line.
+fn comment_block(raw: Option<&RawString>) -> String {
+    raw.and_then(RawString::as_str)
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('#'))
+        .map(|line| format!("{line}\n"))
+        .collect()
+}
+
+/// Keeps an end-of-line comment (if any), separated from the value by one space.
+fn trailing_comment(raw: Option<&RawString>) -> String {
+    raw.and_then(RawString::as_str)
+        .and_then(|text| text.find('#').map(|idx| text[idx..].trim_end()))
+        .map(|comment| format!(" {comment}"))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn orders_keys_and_tables_canonically() {
+        let input = r#"name = "demo"
+
+[workflow]
+description = "demo"
+
+  [[workflow.steps]]
+    description   =   "first"
+  agent = "a"
+
+[agents.a]
+prompt = "a.md"
+model = "gpt-5"
+engine = "codex"
+"#;
+
+        let formatted = format_workflow_toml(input).unwrap();
+        assert_eq!(
+            formatted,
+            r#"name = "demo"
+
+[agents.a]
+engine = "codex"
+model = "gpt-5"
+prompt = "a.md"
+
+[workflow]
+description = "demo"
+
+[[workflow.steps]]
+agent = "a"
+description = "first"
+"#
+        );
+    }
+
+    #[test]
+    fn preserves_comments() {
+        let input = r#"name = "demo" # workflow id
+
+# Agents used by the steps below
+[agents.a]
+# keep this on gpt-5
+model = "gpt-5"
+prompt = "a.md"
+
+[workflow]
+[[workflow.steps]]
+agent = "a"
+# trailing note
+"#;
+        let formatted = format_workflow_toml(input).unwrap();
+        assert_eq!(
+            formatted,
+            r#"name = "demo" # workflow id
+
+# Agents used by the steps below
+[agents.a]
+# keep this on gpt-5
+model = "gpt-5"
+prompt = "a.md"
+
+[workflow]
+
+[[workflow.steps]]
+agent = "a"
+# trailing note
+"#
+        );
+    }
+
+    #[test]
+    fn formatting_is_idempotent() {
+        let input = r#"
+[defaults]
+mock = true
+engine = "codex"
+[agents.b]
+prompt="b.md"
+[agents.a]
+prompt = "a.md"
+[workflow]
+[[workflow.steps]]
+agent = "b"
+[[workflow.steps]]
+agent = "a"
+"#;
+        let once = format_workflow_toml(input).unwrap();
+        let twice = format_workflow_toml(&once).unwrap();
+        assert_eq!(once, twice);
+        assert!(once.find("[agents.b]").unwrap() < once.find("[agents.a]").unwrap());
+    }
+}
@@ -0,0 +1,20 @@
+use anyhow::Result;
+
+use crate::bundle;
+use crate::cli::args::ImportArgs;
+use crate::cli::validate_run_id;
+use crate::runtime::init as runtime_init;
+
+pub fn run(args: ImportArgs) -> Result<()> {
+    if let Some(run_id) = &args.run_id {
+        validate_run_id(run_id)?;
+    }
+    runtime_init::ensure_runtime_tree()?;
+    let summary = bundle::import_bundle(&args.bundle, args.run_id.as_deref(), args.force)?;
+    println!(
+        "[import] restored `{}` run `{}` ({} file(s) written, {} unchanged)",
+        summary.manifest.workflow_name, summary.run_id, summary.written, summary.unchanged
+    );
+    println!("[import] state: {}", summary.state_path.display());
+    Ok(())
+}
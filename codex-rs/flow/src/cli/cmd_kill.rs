@@ -0,0 +1,228 @@
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use anyhow::bail;
+
+use crate::cli::args::KillArgs;
+use crate::cli::validate_run_id;
+use crate::runner::StepState;
+use crate::runner::StepStatus;
+use crate::runner::WorkflowRunState;
+use crate::runner::runner_lock_held;
+use crate::runner::runner_lock_path;
+use crate::runtime::state_store as runtime_state;
+
+enum Stopped {
+    AlreadyExited,
+    Interrupted,
+    Killed,
+}
+
+pub fn run(args: KillArgs) -> Result<()> {
+    validate_run_id(&args.run_id)?;
+    let state_path = locate_state(
+        &runtime_state::state_root(),
+        &args.run_id,
+        args.workflow.as_deref(),
+    )?;
+    let state = WorkflowRunState::load_from_path(&state_path)?;
+    let Some(pid) = state.pid else {
+        bail!(
+            "run `{}` of workflow `{}` has no recorded runner pid; it is not running",
+            state.run_id,
+            state.workflow_name
+        );
+    };
+
+    let lock_path = runner_lock_path(&state_path);
+    match stop_runner(pid, &lock_path, Duration::from_secs(args.grace))? {
+        Stopped::AlreadyExited => println!("[kill] runner pid {pid} had already exited"),
+        Stopped::Interrupted => println!("[kill] runner pid {pid} stopped after SIGINT"),
+        Stopped::Killed => println!(
+            "[kill] runner pid {pid} ignored SIGINT for {}s; sent SIGKILL",
+            args.grace
+        ),
+    }
+
+    // Reload: the runner may have persisted progress while shutting down.
+    let mut state = WorkflowRunState::load_from_path(&state_path)?;
+    mark_interrupted(&mut state);
+    state.write_to_path(&state_path)?;
+    println!(
+        "[kill] marked `{}` run `{}` interrupted at step-{}",
+        state.workflow_name,
+        state.run_id,
+        state.resume_pointer + 1
+    );
+    Ok(())
+}
+
+/// Finds `<workflow>/<run_id>.resume.json` under the state root, scanning
+/// every workflow when `workflow` is not given.
+fn locate_state(state_root: &Path, run_id: &str, workflow: Option<&str>) -> Result<PathBuf> {
+    let file_name = format!("{run_id}.resume.json");
+    if let Some(workflow) = workflow {
+        let path = state_root.join(workflow).join(&file_name);
+        if !path.is_file() {
+            bail!("no state for run `{run_id}` at {}", path.display());
+        }
+        return Ok(path);
+    }
+    let mut matches: Vec<PathBuf> = std::fs::read_dir(state_root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path().join(&file_name))
+        .filter(|path| path.is_file())
+        .collect();
+    matches.sort();
+    match matches.len() {
+        0 => bail!("no state for run `{run_id}` under {}", state_root.display()),
+        1 => Ok(matches.remove(0)),
+        _ => {
+            let workflows: Vec<String> = matches
+                .iter()
+                .filter_map(|path| path.parent()?.file_name())
+                .map(|name| name.to_string_lossy().into_owned())
+                .collect();
+            bail!(
+                "run `{run_id}` exists for several workflows ({}); pass --workflow",
+                workflows.join(", ")
+            )
+        }
+    }
+}
+
+/// Clears the runner pid and records the step at the resume pointer as
+/// interrupted unless the run already finished or recorded that step.
+fn mark_interrupted(state: &mut WorkflowRunState) {
+    state.pid = None;
+    let index = state.resume_pointer;
+    if state.total_steps.is_some_and(|total| index >= total)
+        || state.steps.iter().any(|step| step.index == index)
+    {
+        return;
+    }
+    state.steps.push(StepState {
+        index,
+        status: StepStatus::Interrupted,
+        memory_path: String::new(),
+        debug_log: None,
+        needs_real: false,
+        token_delta: None,
+    });
+    state.steps.sort_by_key(|step| step.index);
+}
+
+/// Signals `pid` only while the runner lock at `lock_path` is held: the
+/// runner takes it before recording its pid and the OS drops it when the
+/// runner exits, so a recycled pid is never signalled.
+#[cfg(unix)]
+fn stop_runner(pid: u32, lock_path: &Path, grace: Duration) -> Result<Stopped> {
+    use anyhow::Context;
+
+    /// How long to wait for the process to disappear after SIGKILL.
+    const KILL_WAIT: Duration = Duration::from_secs(5);
+
+    let pid = libc::pid_t::try_from(pid).with_context(|| format!("invalid runner pid {pid}"))?;
+    if !runner_lock_held(lock_path)? {
+        return Ok(Stopped::AlreadyExited);
+    }
+    send_signal(pid, libc::SIGINT)?;
+    if wait_for_exit(lock_path, grace)? {
+        return Ok(Stopped::Interrupted);
+    }
+    send_signal(pid, libc::SIGKILL)?;
+    if wait_for_exit(lock_path, KILL_WAIT)? {
+        return Ok(Stopped::Killed);
+    }
+    bail!("runner pid {pid} is still alive after SIGKILL")
+}
+
+#[cfg(not(unix))]
+fn stop_runner(pid: u32, _lock_path: &Path, _grace: Duration) -> Result<Stopped> {
+    bail!("codex-flow kill cannot signal runner pid {pid}: only supported on unix")
+}
+
+#[cfg(unix)]
+fn send_signal(pid: libc::pid_t, signal: libc::c_int) -> Result<()> {
+    use anyhow::Context;
+
+    // SAFETY: kill(2) has no memory-safety preconditions.
+    if unsafe { libc::kill(pid, signal) } == 0 {
+        return Ok(());
+    }
+    let err = std::io::Error::last_os_error();
+    if err.raw_os_error() == Some(libc::ESRCH) {
+        // Exited between the lock check and the signal.
+        return Ok(());
+    }
+    Err(err).with_context(|| format!("failed to signal runner pid {pid}"))
+}
+
+/// Waits until the runner releases its lock, i.e. has exited.
+#[cfg(unix)]
+fn wait_for_exit(lock_path: &Path, timeout: Duration) -> Result<bool> {
+    let deadline = std::time::Instant::now() + timeout;
+    while runner_lock_held(lock_path)? {
+        if std::time::Instant::now() >= deadline {
+            return Ok(false);
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn locates_state_and_marks_pointer_step_interrupted() {
+        let tmp = tempfile::tempdir().unwrap();
+        for workflow in ["review", "deploy"] {
+            std::fs::create_dir_all(tmp.path().join(workflow)).unwrap();
+        }
+        std::fs::write(tmp.path().join("review/r1.resume.json"), "{}").unwrap();
+        std::fs::write(tmp.path().join("review/r2.resume.json"), "{}").unwrap();
+        std::fs::write(tmp.path().join("deploy/r2.resume.json"), "{}").unwrap();
+
+        assert_eq!(
+            locate_state(tmp.path(), "r1", None).unwrap(),
+            tmp.path().join("review/r1.resume.json")
+        );
+        assert!(locate_state(tmp.path(), "r2", None).is_err());
+        assert_eq!(
+            locate_state(tmp.path(), "r2", Some("deploy")).unwrap(),
+            tmp.path().join("deploy/r2.resume.json")
+        );
+
+        let mut state: WorkflowRunState = serde_json::from_str(
+            r#"{"schema_version":2,"workflow_name":"review","run_id":"r1","resume_pointer":1,"total_steps":3,"pid":4242}"#,
+        )
+        .unwrap();
+        mark_interrupted(&mut state);
+        assert_eq!(state.pid, None);
+        assert_eq!(
+            state
+                .steps
+                .iter()
+                .map(|step| (step.index, step.status))
+                .collect::<Vec<_>>(),
+            vec![(1, StepStatus::Interrupted)]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn does_not_signal_a_pid_without_the_runner_lock() {
+        let tmp = tempfile::tempdir().unwrap();
+        let lock_path = tmp.path().join("r1.resume.lock");
+        // A live pid that is not the runner, as after pid reuse.
+        let stopped = stop_runner(std::process::id(), &lock_path, Duration::ZERO).unwrap();
+        assert!(matches!(stopped, Stopped::AlreadyExited));
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::Result;
+
+use crate::cli::args::MetricsArgs;
+use crate::cli::args::MetricsCommand;
+use crate::runtime::config as runtime_config;
+use crate::usage_metrics;
+
+pub fn run(args: MetricsArgs) -> Result<()> {
+    match args.command {
+        MetricsCommand::Show => show(),
+        MetricsCommand::Flush => {
+            let sent = usage_metrics::flush()?;
+            println!("[metrics] sent {sent} record(s)");
+            Ok(())
+        }
+        MetricsCommand::Clear => {
+            usage_metrics::clear()?;
+            println!("[metrics] cleared local usage metrics");
+            Ok(())
+        }
+    }
+}
+
+fn show() -> Result<()> {
+    if !runtime_config::metrics_enabled() {
+        println!(
+            "[metrics] disabled; set {}=1 to record usage locally",
+            runtime_config::METRICS_ENV
+        );
+    }
+    let path = usage_metrics::metrics_path();
+    let records = usage_metrics::load_records(&path)?;
+    if records.is_empty() {
+        println!("[metrics] no usage recorded at {}", path.display());
+        return Ok(());
+    }
+    println!(
+        "{:<10} {:>11} {:>8} {:>12} {:>6}",
+        "COMMAND", "INVOCATIONS", "FAILURES", "AVG_DURATION", "STEPS"
+    );
+    for (command, stats) in usage_metrics::summarize(&records) {
+        let average_ms = stats.total_duration_ms / stats.invocations.max(1) as u64;
+        println!(
+            "{command:<10} {:>11} {:>8} {:>12} {:>6}",
+            stats.invocations,
+            stats.failures,
+            format!("{average_ms}ms"),
+            stats.steps_executed
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,349 @@
+use std::fs;
+use std::io::BufRead;
+use std::io::IsTerminal;
+use std::io::Write;
+use std::io::{self};
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+
+use crate::cli::args::NewAgentArgs;
+use crate::cli::args::NewArgs;
+use crate::cli::args::NewCommand;
+use crate::cli::args::NewWorkflowArgs;
+use crate::config::WorkflowFile;
+use crate::engine::SUPPORTED_ENGINES;
+use crate::runtime::config as runtime_config;
+
+const DEFAULT_ENGINE: &str = "codex";
+const DEFAULT_MODEL: &str = "gpt-5";
+
+pub fn run(args: NewArgs) -> Result<()> {
+    match args.command {
+        NewCommand::Workflow(args) => new_workflow(args),
+        NewCommand::Agent(args) => new_agent(args),
+    }
+}
+
+fn new_workflow(args: NewWorkflowArgs) -> Result<()> {
+    validate_name("workflow", &args.name)?;
+    let flow_root = runtime_config::flow_dir();
+    let workflow_path = flow_root
+        .join("workflows")
+        .join(format!("{}.workflow.toml", args.name));
+    if workflow_path.exists() && !args.force {
+        bail!(
+            "workflow file {} already exists; pass --force to overwrite",
+            workflow_path.display()
+        );
+    }
+
+    let agent = args.agent.unwrap_or_else(|| "main".to_string());
+    validate_name("agent", &agent)?;
+    let engine = resolve_engine(args.engine)?;
+    let model = resolve_model(args.model)?;
+    let prompt_path = prompt_path_for(&flow_root, &args.name, &agent);
+
+    let content = render_workflow(&args.name, &agent, &engine, &model, &prompt_path);
+    ensure_parses(&workflow_path, &content)?;
+    write_atomically(&workflow_path, &content)?;
+    let prompt_created = write_prompt_stub(&prompt_path, &agent)?;
+
+    println!("[new] created workflow {}", workflow_path.display());
+    report_prompt(&prompt_path, prompt_created);
+    Ok(())
+}
+
+fn new_agent(args: NewAgentArgs) -> Result<()> {
+    validate_name("agent", &args.name)?;
+    let file = WorkflowFile::load(&args.workflow)?;
+    if file.agents.contains_key(&args.name) {
+        bail!(
+            "agent `{}` already exists in {}",
+            args.name,
+            args.workflow.display()
+        );
+    }
+    let workflow_name = file.name.unwrap_or_else(|| "main".to_string());
+    let engine = resolve_engine(args.engine)?;
+    let model = resolve_model(args.model)?;
+    let prompt_path = prompt_path_for(&runtime_config::flow_dir(), &workflow_name, &args.name);
+
+    let mut stanza = render_agent(&args.name, &engine, &model, &prompt_path);
+    if !args.no_step {
+        stanza.push_str(&render_step(&args.name));
+    }
+    let existing = fs::read_to_string(&args.workflow)
+        .with_context(|| format!("failed to read workflow file {}", args.workflow.display()))?;
+    let content = with_stanza(existing, &stanza);
+    ensure_parses(&args.workflow, &content)?;
+    write_atomically(&args.workflow, &content)?;
+    let prompt_created = write_prompt_stub(&prompt_path, &args.name)?;
+
+    println!(
+        "[new] appended agent `{}` to {}",
+        args.name,
+        args.workflow.display()
+    );
+    report_prompt(&prompt_path, prompt_created);
+    Ok(())
+}
+
+fn validate_name(kind: &str, name: &str) -> Result<()> {
+    if name.is_empty() {
+        bail!("{kind} name must not be empty");
+    }
+    if name
+        .chars()
+        .any(|ch| !(ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_')))
+    {
+        bail!("{kind} name may only contain alphanumeric characters, '-', or '_'");
+    }
+    Ok(())
+}
+
+fn resolve_engine(flag: Option<String>) -> Result<String> {
+    let engine = match flag {
+        Some(engine) => engine,
+        None => ask("engine", DEFAULT_ENGINE)?,
+    };
+    if !SUPPORTED_ENGINES.contains(&engine.as_str()) {
+        bail!(
+            "unsupported engine `{engine}` (expected one of: {})",
+            SUPPORTED_ENGINES.join(", ")
+        );
+    }
+    Ok(engine)
+}
+
+fn resolve_model(flag: Option<String>) -> Result<String> {
+    match flag {
+        Some(model) => Ok(model),
+        None => ask("model", DEFAULT_MODEL),
+    }
+}
+
+/// Prompts on stderr when attached to a terminal; otherwise falls back to the
+/// default so scripted invocations never block on input.
+fn ask(label: &str, default: &str) -> Result<String> {
+    if !io::stdin().is_terminal() {
+        return Ok(default.to_string());
+    }
+    eprint!("{label} [{default}]: ");
+    io::stderr().flush().ok();
+    let mut line = String::new();
+    io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .with_context(|| format!("failed to read {label} from stdin"))?;
+    let answer = line.trim();
+    if answer.is_empty() {
+        Ok(default.to_string())
+    } else {
+        Ok(answer.to_string())
+    }
+}
+
+fn prompt_path_for(flow_root: &Path, workflow: &str, agent: &str) -> PathBuf {
+    flow_root
+        .join("prompts")
+        .join(workflow)
+        .join(format!("{agent}.md"))
+}
+
+fn render_workflow(
+    name: &str,
+    agent: &str,
+    engine: &str,
+    model: &str,
+    prompt_path: &Path,
+) -> String {
+    let mut out = format!(
+        r#"name = {name}
+
+[defaults]
+engine = {engine}
+mock = false
+"#,
+        name = toml_string(name),
+        engine = toml_string(engine),
+    );
+    out.push_str(&render_agent(agent, engine, model, prompt_path));
+    out.push_str(&format!(
+        r#"
+[workflow]
+description = {description}
+"#,
+        description = toml_string(&format!("TODO: describe what `{name}` does")),
+    ));
+    out.push_str(&render_step(agent));
+    out
+}
+
+/// `name` is a validated bare key; every value goes through [`toml_string`].
+fn render_agent(name: &str, engine: &str, model: &str, prompt_path: &Path) -> String {
+    format!(
+        r#"
+[agents.{name}]
+engine = {engine}
+model = {model}
+prompt = {prompt}
+"#,
+        engine = toml_string(engine),
+        model = toml_string(model),
+        prompt = toml_string(&toml_path(prompt_path)),
+    )
+}
+
+fn render_step(agent: &str) -> String {
+    format!(
+        r#"
+[[workflow.steps]]
+agent = {agent}
+"#,
+        agent = toml_string(agent),
+    )
+}
+
+/// `value` as a quoted, escaped TOML string.
+fn toml_string(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+fn toml_path(path: &Path) -> String {
+    path.display().to_string().replace('\\', "/")
+}
+
+fn write_new_file(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create dir {}", parent.display()))?;
+    }
+    fs::write(path, content).with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Writes `content` to a sibling `.tmp` file and renames it over `path`, so
+/// a failed write never leaves a half-written workflow behind.
+fn write_atomically(path: &Path, content: &str) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("workflow.toml");
+    let tmp_path = path.with_file_name(format!("{file_name}.tmp"));
+    write_new_file(&tmp_path, content)?;
+    fs::rename(&tmp_path, path)
+        .with_context(|| format!("failed to atomically write {}", path.display()))
+}
+
+fn with_stanza(mut existing: String, stanza: &str) -> String {
+    if !existing.is_empty() && !existing.ends_with('\n') {
+        existing.push('\n');
+    }
+    existing.push_str(stanza);
+    existing
+}
+
+/// Parses the generated `content` before anything is written to `path`.
+fn ensure_parses(path: &Path, content: &str) -> Result<()> {
+    toml::from_str::<WorkflowFile>(content)
+        .map(|_| ())
+        .with_context(|| format!("generated workflow {} failed to parse", path.display()))
+}
+
+/// Writes a stub prompt unless one already exists; returns whether a file was created.
+fn write_prompt_stub(path: &Path, agent: &str) -> Result<bool> {
+    if path.exists() {
+        return Ok(false);
+    }
+    let stub = format!(
+        "# {agent}\n\nTODO: describe the task for the `{agent}` agent, the inputs it should read, and the output it must produce.\n"
+    );
+    write_new_file(path, &stub)?;
+    Ok(true)
+}
+
+fn report_prompt(path: &Path, created: bool) {
+    if created {
+        println!("[new] created prompt stub {}", path.display());
+    } else {
+        println!("[new] kept existing prompt {}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn rendered_workflow_round_trips() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("review.workflow.toml");
+        let prompt = Path::new(".codex-flow/prompts/review/main.md");
+        fs::write(
+            &path,
+            render_workflow("review", "main", "codex", "gpt-5", prompt),
+        )
+        .unwrap();
+
+        let file = WorkflowFile::load(&path).unwrap();
+        assert_eq!(file.name.as_deref(), Some("review"));
+        assert_eq!(file.workflow.steps.len(), 1);
+        assert_eq!(file.workflow.steps[0].agent, "main");
+        let agent = file.agents.get("main").unwrap();
+        assert_eq!(agent.model.as_deref(), Some("gpt-5"));
+        assert_eq!(agent.prompt, ".codex-flow/prompts/review/main.md");
+    }
+
+    #[test]
+    fn appended_agent_adds_step() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("review.workflow.toml");
+        let prompt = Path::new(".codex-flow/prompts/review/main.md");
+        fs::write(
+            &path,
+            render_workflow("review", "main", "codex", "gpt-5", prompt),
+        )
+        .unwrap();
+
+        let extra = Path::new(".codex-flow/prompts/review/lint.md");
+        let mut stanza = render_agent("lint", "codex", "gpt-5-codex", extra);
+        stanza.push_str(&render_step("lint"));
+        let content = with_stanza(fs::read_to_string(&path).unwrap(), &stanza);
+        ensure_parses(&path, &content).unwrap();
+        write_atomically(&path, &content).unwrap();
+
+        let file = WorkflowFile::load(&path).unwrap();
+        let agents: Vec<&str> = file
+            .workflow
+            .steps
+            .iter()
+            .map(|step| step.agent.as_str())
+            .collect();
+        assert_eq!(agents, vec!["main", "lint"]);
+        assert!(file.agents.contains_key("lint"));
+    }
+
+    #[test]
+    fn rendered_values_are_escaped() {
+        let prompt = Path::new(r#"prompts/"quoted"/main.md"#);
+        let content = render_workflow("review", "main", "codex", "gpt\"5\nx", prompt);
+        ensure_parses(Path::new("review.workflow.toml"), &content).unwrap();
+
+        let file: WorkflowFile = toml::from_str(&content).unwrap();
+        let agent = file.agents.get("main").unwrap();
+        assert_eq!(agent.model.as_deref(), Some("gpt\"5\nx"));
+        assert_eq!(agent.prompt, r#"prompts/"quoted"/main.md"#);
+    }
+
+    #[test]
+    fn rejects_names_that_are_not_toml_keys() {
+        assert!(validate_name("agent", "").is_err());
+        assert!(validate_name("agent", "has space").is_err());
+        assert!(validate_name("agent", "a.b").is_err());
+        assert!(validate_name("agent", "lint_agent-2").is_ok());
+    }
+}
@@ -0,0 +1,216 @@
+use std::path::Path;
+use std::time::SystemTime;
+
+use anyhow::Context;
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use walkdir::WalkDir;
+
+use crate::cli::args::RunsArgs;
+use crate::cli::args::RunsSort;
+use crate::runner::StepStatus;
+use crate::runner::WorkflowRunState;
+use crate::runtime::state_store as runtime_state;
+
+const HEADERS: [&str; 8] = [
+    "WORKFLOW", "RUN_ID", "STATUS", "STEPS", "TOKENS", "COST", "DURATION", "UPDATED",
+];
+
+struct RunRow {
+    workflow: String,
+    run_id: String,
+    complete: bool,
+    executed_steps: usize,
+    total_steps: Option<usize>,
+    total_tokens: i64,
+    total_cost: f64,
+    duration_secs: Option<i64>,
+    updated: DateTime<Utc>,
+}
+
+pub fn run(args: RunsArgs) -> Result<()> {
+    let state_root = runtime_state::state_root();
+    let mut rows = collect_runs(&state_root)?;
+    if let Some(workflow) = &args.workflow {
+        rows.retain(|row| &row.workflow == workflow);
+    }
+    if rows.is_empty() {
+        println!("[runs] no recorded runs under {}", state_root.display());
+        return Ok(());
+    }
+    match args.sort {
+        RunsSort::Date => rows.sort_by(|left, right| right.updated.cmp(&left.updated)),
+        RunsSort::Cost => {
+            rows.sort_by(|left, right| right.total_cost.total_cmp(&left.total_cost));
+        }
+    }
+    print!("{}", render_table(&rows));
+    Ok(())
+}
+
+fn collect_runs(state_root: &Path) -> Result<Vec<RunRow>> {
+    let mut rows = Vec::new();
+    if !state_root.exists() {
+        return Ok(rows);
+    }
+    for entry in WalkDir::new(state_root).sort_by_file_name() {
+        let entry = entry.with_context(|| format!("failed to walk {}", state_root.display()))?;
+        if !entry.file_type().is_file()
+            || !entry
+                .file_name()
+                .to_string_lossy()
+                .ends_with(".resume.json")
+        {
+            continue;
+        }
+        let state = match WorkflowRunState::load_from_path(entry.path()) {
+            Ok(state) => state,
+            Err(err) => {
+                eprintln!("[runs] skipping {}: {err:#}", entry.path().display());
+                continue;
+            }
+        };
+        let modified = entry
+            .metadata()
+            .ok()
+            .and_then(|metadata| metadata.modified().ok())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        rows.push(summarize(state, DateTime::<Utc>::from(modified)));
+    }
+    Ok(rows)
+}
+
+/// Builds a ledger row; the state file's mtime marks the last recorded activity.
+fn summarize(state: WorkflowRunState, updated: DateTime<Utc>) -> RunRow {
+    let executed_steps = state
+        .steps
+        .iter()
+        .filter(|step| step.status == StepStatus::Completed)
+        .count();
+    let complete = state
+        .total_steps
+        .is_some_and(|total| state.resume_pointer >= total);
+    let duration_secs = state
+        .started_at
+        .as_deref()
+        .and_then(|started| DateTime::parse_from_rfc3339(started).ok())
+        .map(|started| (updated - started.with_timezone(&Utc)).num_seconds().max(0));
+    let usage = state.token_usage.unwrap_or_default();
+    RunRow {
+        workflow: state.workflow_name,
+        run_id: state.run_id,
+        complete,
+        executed_steps,
+        total_steps: state.total_steps,
+        total_tokens: usage.total_tokens,
+        total_cost: usage.total_cost,
+        duration_secs,
+        updated,
+    }
+}
+
+fn render_table(rows: &[RunRow]) -> String {
+    let cells: Vec<[String; 8]> = rows
+        .iter()
+        .map(|row| {
+            let steps = match row.total_steps {
+                Some(total) => format!("{}/{total}", row.executed_steps),
+                None => row.executed_steps.to_string(),
+            };
+            [
+                row.workflow.clone(),
+                row.run_id.clone(),
+                if row.complete { "complete" } else { "partial" }.to_string(),
+                steps,
+                row.total_tokens.to_string(),
+                format!("${:.4}", row.total_cost),
+                row.duration_secs
+                    .map(format_duration)
+                    .unwrap_or_else(|| "-".to_string()),
+                row.updated.format("%Y-%m-%d %H:%M").to_string(),
+            ]
+        })
+        .collect();
+
+    let mut widths = HEADERS.map(str::len);
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut out = String::new();
+    let headers = HEADERS.map(str::to_string);
+    for row in std::iter::once(&headers).chain(&cells) {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+fn format_duration(secs: i64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}h{minutes:02}m")
+    } else if minutes > 0 {
+        format!("{minutes}m{seconds:02}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::StepState;
+    use crate::runner::TokenUsage;
+    use pretty_assertions::assert_eq;
+
+    fn completed_step(index: usize) -> StepState {
+        StepState {
+            index,
+            status: StepStatus::Completed,
+            memory_path: format!("step-{index}.md"),
+            debug_log: None,
+            needs_real: false,
+            token_delta: None,
+        }
+    }
+
+    #[test]
+    fn renders_status_steps_and_duration() {
+        let updated = DateTime::parse_from_rfc3339("2025-11-11T12:05:30Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let state = WorkflowRunState {
+            schema_version: 2,
+            workflow_name: "review".to_string(),
+            run_id: "20251111T120000Z".to_string(),
+            resume_pointer: 1,
+            steps: vec![completed_step(0)],
+            token_usage: Some(TokenUsage {
+                prompt_tokens: 900,
+                completion_tokens: 300,
+                total_tokens: 1200,
+                total_cost: 0.0125,
+            }),
+            started_at: Some("2025-11-11T12:00:00Z".to_string()),
+            total_steps: Some(2),
+            pid: None,
+        };
+
+        let table = render_table(&[summarize(state, updated)]);
+        assert_eq!(
+            table,
+            "WORKFLOW  RUN_ID            STATUS   STEPS  TOKENS  COST     DURATION  UPDATED\n\
+             review    20251111T120000Z  partial  1/2    1200    $0.0125  5m30s     2025-11-11 12:05\n"
+        );
+    }
+}
@@ -1,5 +1,7 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
+use std::path::PathBuf;
 use std::time::Duration;
 use std::time::SystemTime;
 
@@ -11,6 +13,7 @@ use walkdir::WalkDir;
 use crate::cli::args::StateArgs;
 use crate::cli::args::StateCommand;
 use crate::cli::args::StatePruneArgs;
+use crate::runner::runner_lock_path;
 use crate::runtime::init as runtime_init;
 
 pub fn run(args: StateArgs) -> Result<()> {
@@ -30,8 +33,41 @@ fn prune_state(args: StatePruneArgs) -> Result<()> {
         .checked_sub(Duration::from_secs(args.days.saturating_mul(86_400)))
         .unwrap_or(SystemTime::UNIX_EPOCH);
 
-    let mut stats = PruneStats::default();
-    for entry in WalkDir::new(&state_root) {
+    let files = collect_state_files(&state_root)?;
+    let mut stats = PruneStats {
+        total_files: files.len() as u64,
+        total_bytes: files.iter().map(|file| file.len).sum(),
+        ..PruneStats::default()
+    };
+    for file in select_for_removal(files, cutoff, args.keep_last) {
+        if args.dry_run {
+            println!("[state] would remove {}", file.path.display());
+        } else {
+            fs::remove_file(&file.path)
+                .with_context(|| format!("failed to remove {}", file.path.display()))?;
+            // Best effort: only runs that recorded a pid ever created one.
+            let _ = fs::remove_file(runner_lock_path(&file.path));
+        }
+        stats.removed_files += 1;
+        stats.reclaimed_bytes += file.len;
+    }
+
+    if !args.dry_run {
+        runtime_init::refresh_state_readme()?;
+    }
+    print_summary(&state_root, &args, &stats);
+    Ok(())
+}
+
+struct StateFile {
+    path: PathBuf,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+fn collect_state_files(state_root: &Path) -> Result<Vec<StateFile>> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(state_root) {
         let entry = entry.with_context(|| format!("failed to walk {}", state_root.display()))?;
         if !entry.file_type().is_file() {
             continue;
@@ -43,28 +79,45 @@ fn prune_state(args: StatePruneArgs) -> Result<()> {
         let metadata = entry
             .metadata()
             .with_context(|| format!("failed to read metadata for {}", entry.path().display()))?;
-        let len = metadata.len();
-        stats.total_files += 1;
-        stats.total_bytes += len;
-
-        let stale = metadata
-            .modified()
-            .map(|mtime| mtime <= cutoff)
-            .unwrap_or(true);
-        if stale {
-            fs::remove_file(entry.path())
-                .with_context(|| format!("failed to remove {}", entry.path().display()))?;
-            stats.removed_files += 1;
-            stats.reclaimed_bytes += len;
-        }
+        files.push(StateFile {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+            path: entry.into_path(),
+        });
     }
+    Ok(files)
+}
 
-    runtime_init::refresh_state_readme()?;
-    print_summary(&state_root, args.days, &stats);
-    Ok(())
+/// Picks the files older than `cutoff`, sparing the `keep_last` most recent
+/// files of each workflow (state files are grouped by their parent directory).
+fn select_for_removal(
+    files: Vec<StateFile>,
+    cutoff: SystemTime,
+    keep_last: usize,
+) -> Vec<StateFile> {
+    let mut by_workflow: BTreeMap<PathBuf, Vec<StateFile>> = BTreeMap::new();
+    for file in files {
+        let workflow_dir = file
+            .path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        by_workflow.entry(workflow_dir).or_default().push(file);
+    }
+
+    let mut selected = Vec::new();
+    for mut runs in by_workflow.into_values() {
+        runs.sort_by(|left, right| right.modified.cmp(&left.modified));
+        selected.extend(
+            runs.into_iter()
+                .skip(keep_last)
+                .filter(|file| file.modified.map(|mtime| mtime <= cutoff).unwrap_or(true)),
+        );
+    }
+    selected
 }
 
-fn print_summary(state_root: &Path, days: u64, stats: &PruneStats) {
+fn print_summary(state_root: &Path, args: &StatePruneArgs, stats: &PruneStats) {
     let remaining_bytes = stats.total_bytes.saturating_sub(stats.reclaimed_bytes);
     println!(
         "[state] scanned {} file(s) ({}) under {}",
@@ -72,10 +125,20 @@ fn print_summary(state_root: &Path, days: u64, stats: &PruneStats) {
         format_bytes(stats.total_bytes),
         state_root.display()
     );
+    let verb = if args.dry_run {
+        "would remove"
+    } else {
+        "removed"
+    };
+    let kept = if args.keep_last > 0 {
+        format!(", keeping the last {} per workflow", args.keep_last)
+    } else {
+        String::new()
+    };
     println!(
-        "[state] removed {} file(s) older than {} day(s); reclaimed {} (remaining {})",
+        "[state] {verb} {} file(s) older than {} day(s){kept}; reclaimed {} (remaining {})",
         stats.removed_files,
-        days,
+        args.days,
         format_bytes(stats.reclaimed_bytes),
         format_bytes(remaining_bytes)
     );
@@ -106,3 +169,43 @@ fn format_bytes(bytes: u64) -> String {
         format!("{value:.2} {}", UNITS[unit])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn state_file(path: &str, age_days: u64, now: SystemTime) -> StateFile {
+        StateFile {
+            path: PathBuf::from(path),
+            len: 1,
+            modified: now.checked_sub(Duration::from_secs(age_days * 86_400)),
+        }
+    }
+
+    #[test]
+    fn keep_last_spares_recent_runs_per_workflow() {
+        let now = SystemTime::now();
+        let cutoff = now - Duration::from_secs(7 * 86_400);
+        let files = vec![
+            state_file("state/a/1.resume.json", 30, now),
+            state_file("state/a/2.resume.json", 20, now),
+            state_file("state/a/3.resume.json", 10, now),
+            state_file("state/a/4.resume.json", 1, now),
+            state_file("state/b/1.resume.json", 40, now),
+        ];
+
+        let mut removed: Vec<PathBuf> = select_for_removal(files, cutoff, 2)
+            .into_iter()
+            .map(|file| file.path)
+            .collect();
+        removed.sort();
+        assert_eq!(
+            removed,
+            vec![
+                PathBuf::from("state/a/1.resume.json"),
+                PathBuf::from("state/a/2.resume.json"),
+            ]
+        );
+    }
+}
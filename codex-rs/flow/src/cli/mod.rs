@@ -1,68 +1,152 @@
 use std::path::Path;
+use std::time::Instant;
 
 use anyhow::Context;
 use anyhow::Result;
+use anyhow::anyhow;
 use anyhow::bail;
 use chrono::Utc;
 use clap::Parser;
 
 use crate::config;
+use crate::failure::FailureClass;
+use crate::failure::{self};
 use crate::runner::PersistenceMode;
 use crate::runner::RunOptions;
 use crate::runner::StatePersistence;
 use crate::runner::StepStatus;
+use crate::runner::Verbosity;
 use crate::runner::WorkflowRunState;
 use crate::runner::WorkflowStateStore;
 use crate::runner::planner::ResumePlanner;
+use crate::runner::preflight;
 use crate::runner::{self};
 use crate::runtime::config as runtime_config;
+use crate::runtime::event_socket;
 use crate::runtime::init as runtime_init;
 use crate::runtime::state_store as runtime_state;
 use crate::scaffold;
+use crate::usage_metrics;
 
 pub mod args;
+mod cmd_docs;
+mod cmd_export;
+mod cmd_fmt;
+mod cmd_import;
+mod cmd_kill;
+mod cmd_metrics;
+mod cmd_new;
+mod cmd_runs;
 mod cmd_state;
 mod output;
+mod picker;
+mod run_id;
 
 use args::Cli;
 use args::Command;
+use args::GlobalArgs;
 use args::InitArgs;
 use args::ResumeArgs;
 use args::RunArgs;
 use output::print_completion_summary;
 
-pub fn run() -> Result<()> {
+pub fn run() -> i32 {
     let cli = Cli::parse();
-    dispatch(cli)
+    execute(cli.global, cli.command)
 }
 
-fn dispatch(cli: Cli) -> Result<()> {
-    match cli.command {
+/// Runs a parsed subcommand, reports any error on stderr, and returns the exit
+/// code chosen by the failure class and `--exit-zero-on` policy.
+pub fn execute(global: GlobalArgs, command: Command) -> i32 {
+    let exit_zero_on = global.exit_zero_on.clone();
+    // `metrics` itself is never recorded so inspecting metrics does not skew them.
+    let recorded_command = (!matches!(command, Command::Metrics(_))).then(|| command.name());
+    let started = Instant::now();
+    let result = dispatch(global, command);
+    if let Some(name) = recorded_command {
+        usage_metrics::record(name, started.elapsed(), &result);
+    }
+    let code = failure::exit_code(&result, &exit_zero_on);
+    if let Err(err) = &result {
+        eprintln!("Error: {err:?}");
+        if code == 0 {
+            eprintln!("[flow] exiting 0 because of --exit-zero-on");
+        }
+    }
+    code
+}
+
+/// Executes a parsed subcommand. Shared by the standalone `codex-flow` binary
+/// and the `codex flow` subcommand so both expose the same surface.
+pub fn dispatch(global: GlobalArgs, command: Command) -> Result<()> {
+    // Pin a relative flow dir to the directory the command was started in
+    // before `--cwd` moves the process elsewhere.
+    if let Some(flow_dir) = global.flow_dir.or_else(runtime_config::flow_dir_override) {
+        runtime_config::set_flow_dir(std::path::absolute(flow_dir)?);
+    }
+    match command {
         Command::Init(args) => cmd_init(args),
         Command::Run(args) => cmd_run(args),
         Command::Resume(args) => cmd_resume(args),
         Command::State(args) => cmd_state::run(args),
+        Command::New(args) => cmd_new::run(args),
+        Command::Fmt(args) => cmd_fmt::run(args),
+        Command::Runs(args) => cmd_runs::run(args),
+        Command::Export(args) => cmd_export::run(args),
+        Command::Import(args) => cmd_import::run(args),
+        Command::Kill(args) => cmd_kill::run(args),
+        Command::Metrics(args) => cmd_metrics::run(args),
+        Command::Docs(args) => cmd_docs::run(args),
     }
 }
 
 fn cmd_init(args: InitArgs) -> Result<()> {
+    let templates = args.templates_dir.as_deref();
+    if let Some(flow_dir) = runtime_config::flow_dir_override() {
+        return scaffold::init_scaffold_at(&flow_dir, templates, args.force);
+    }
     let dir = args
         .dir
         .clone()
         .unwrap_or(std::env::current_dir().context("failed to read current dir")?);
-    let templates = args.templates_dir.as_deref();
     scaffold::init_scaffold(&dir, templates, args.force)
 }
 
-fn cmd_run(args: RunArgs) -> Result<()> {
+fn cmd_run(mut args: RunArgs) -> Result<()> {
+    let mut file = picker::resolve_workflow_file(args.file.take())?;
+    if let Some(cwd) = args.cwd.take() {
+        file = std::path::absolute(&file)?;
+        if let Some(path) = args.resume_from.take() {
+            args.resume_from = Some(std::path::absolute(path)?);
+        }
+        if let Some(path) = args.events_socket.take() {
+            args.events_socket = Some(std::path::absolute(path)?);
+        }
+        enter_cwd(&cwd)?;
+    }
     runtime_init::ensure_runtime_tree()?;
-    let (cfg, workflow_name, defaults_mock) = load_workflow(&args.file)?;
+    let (cfg, workflow_name, defaults_mock) = load_workflow(&file)?;
     let workflow = cfg
         .workflows
         .get(&workflow_name)
-        .with_context(|| format!("workflow `{workflow_name}` not found"))?;
+        .with_context(|| format!("workflow `{workflow_name}` not found"))
+        .map_err(|err| FailureClass::Config.tag(err))?;
+    if args.strict {
+        let problems = preflight::check_workflow(&cfg, workflow);
+        if !problems.is_empty() {
+            return Err(FailureClass::Config.tag(anyhow!(
+                "--strict: workflow `{workflow_name}` failed validation:\n  {}",
+                problems.join("\n  ")
+            )));
+        }
+    }
     let mock = resolve_mock_flag(&args, defaults_mock);
-    let (run_id, was_generated) = derive_run_id(args.run_id.clone())?;
+    let verbosity = Verbosity(args.verbose.max(runtime_config::verbose_default()));
+    let (run_id, was_generated) = derive_run_id(
+        args.run_id.clone(),
+        cfg.defaults.run_id_template.as_deref(),
+        &workflow_name,
+    )?;
     let resume_disabled = runtime_config::resume_disabled();
     if resume_disabled && args.resume_from.is_some() {
         bail!(
@@ -97,12 +181,18 @@ fn cmd_run(args: RunArgs) -> Result<()> {
         ))
     };
 
+    let _events_socket = args
+        .events_socket
+        .as_deref()
+        .map(event_socket::serve)
+        .transpose()?;
     let summary = runner::run_workflow(
         &cfg,
         &workflow_name,
         RunOptions {
             mock,
-            verbose: args.verbose,
+            verbosity,
+            embedded: false,
         },
         persistence,
     )?;
@@ -116,11 +206,19 @@ fn cmd_run(args: RunArgs) -> Result<()> {
             runtime_config::RESUME_DISABLED_ENV
         );
     }
-    print_completion_summary("run", Some(&run_id), &summary, args.verbose);
+    usage_metrics::note_steps(summary.executed_steps, workflow.steps.len());
+    print_completion_summary("run", Some(&run_id), &summary, verbosity.banners());
     Ok(())
 }
 
-fn cmd_resume(args: ResumeArgs) -> Result<()> {
+fn cmd_resume(mut args: ResumeArgs) -> Result<()> {
+    if let Some(cwd) = args.cwd.take() {
+        args.file = std::path::absolute(&args.file)?;
+        if let Some(path) = args.events_socket.take() {
+            args.events_socket = Some(std::path::absolute(path)?);
+        }
+        enter_cwd(&cwd)?;
+    }
     runtime_init::ensure_runtime_tree()?;
     if runtime_config::resume_disabled() {
         bail!(
@@ -134,8 +232,10 @@ fn cmd_resume(args: ResumeArgs) -> Result<()> {
     let workflow = cfg
         .workflows
         .get(&workflow_name)
-        .with_context(|| format!("workflow `{workflow_name}` not found"))?;
+        .with_context(|| format!("workflow `{workflow_name}` not found"))
+        .map_err(|err| FailureClass::Config.tag(err))?;
     let mock = resolve_resume_mock_flag(&args, defaults_mock);
+    let verbosity = Verbosity(args.verbose.max(runtime_config::verbose_default()));
     let mode = if mock {
         PersistenceMode::Mock
     } else {
@@ -178,27 +278,92 @@ fn cmd_resume(args: ResumeArgs) -> Result<()> {
     }
 
     let persistence = StatePersistence::with_start(args.run_id.clone(), start_index, store);
+    let _events_socket = args
+        .events_socket
+        .as_deref()
+        .map(event_socket::serve)
+        .transpose()?;
     let summary = runner::run_workflow(
         &cfg,
         &workflow_name,
         RunOptions {
             mock,
-            verbose: args.verbose,
+            verbosity,
+            embedded: false,
         },
         Some(persistence),
     )?;
 
-    print_completion_summary("resume", Some(&args.run_id), &summary, args.verbose);
+    usage_metrics::note_steps(summary.executed_steps, workflow.steps.len());
+    print_completion_summary("resume", Some(&args.run_id), &summary, verbosity.banners());
     Ok(())
 }
 
+/// Runs the workflow in `file` inside a host process (such as a `coco flow
+/// run` inside a Codex session): nothing is written to stdout, Ctrl-C is left
+/// to the host, and `on_event` sees every runner event as it happens.
+/// Returns the workflow name with the run summary.
+pub fn run_embedded(
+    file: &Path,
+    on_event: impl FnMut(&runner::events::RunnerEvent) + 'static,
+) -> Result<(String, runner::RunSummary)> {
+    runtime_init::ensure_runtime_tree()?;
+    let (cfg, workflow_name, defaults_mock) = load_workflow(file)?;
+    let workflow = cfg
+        .workflows
+        .get(&workflow_name)
+        .with_context(|| format!("workflow `{workflow_name}` not found"))
+        .map_err(|err| FailureClass::Config.tag(err))?;
+    let mock = runtime_config::mock_default()
+        .or(defaults_mock)
+        .unwrap_or(false);
+    let (run_id, _) = derive_run_id(
+        None,
+        cfg.defaults.run_id_template.as_deref(),
+        &workflow_name,
+    )?;
+    let persistence = if runtime_config::resume_disabled() {
+        None
+    } else {
+        let mode = if mock {
+            PersistenceMode::Mock
+        } else {
+            PersistenceMode::Real
+        };
+        let store = WorkflowStateStore::load_or_init(&workflow_name, &run_id, mode)?;
+        Some(StatePersistence::with_start(run_id, 0, store))
+    };
+    let summary = runner::events::with_listener(on_event, || {
+        runner::run_workflow(
+            &cfg,
+            &workflow_name,
+            RunOptions {
+                mock,
+                verbosity: Verbosity(0),
+                embedded: true,
+            },
+            persistence,
+        )
+    })?;
+    usage_metrics::note_steps(summary.executed_steps, workflow.steps.len());
+    Ok((workflow_name, summary))
+}
+
+/// Switches the process into `cwd` so relative prompt paths, runtime artifacts,
+/// and engine subprocesses resolve there. Callers absolutize user-supplied
+/// paths first so they keep pointing at what the caller meant.
+fn enter_cwd(cwd: &Path) -> Result<()> {
+    std::env::set_current_dir(cwd)
+        .with_context(|| format!("failed to change directory to {}", cwd.display()))
+}
+
 fn load_workflow(path: &Path) -> Result<(config::FlowConfig, String, Option<bool>)> {
     if let Ok(file) = config::WorkflowFile::load(path) {
         let name = file.name.clone().unwrap_or_else(|| "main".to_string());
         let defaults = file.defaults.mock;
         Ok((file.into_flow_config(), name, defaults))
     } else {
-        let cfg = config::FlowConfig::load(path)?;
+        let cfg = config::FlowConfig::load(path).map_err(|err| FailureClass::Config.tag(err))?;
         let name = cfg
             .workflows
             .keys()
@@ -216,17 +381,49 @@ fn resolve_mock_flag(args: &RunArgs, default: Option<bool>) -> bool {
     } else if args.no_mock {
         false
     } else {
-        default.unwrap_or(false)
+        runtime_config::mock_default().or(default).unwrap_or(false)
     }
 }
 
-fn derive_run_id(input: Option<String>) -> Result<(String, bool)> {
-    if let Some(value) = input {
+/// Picks the run id from `--run-id`, then `CODEX_FLOW_RUN_ID`, and otherwise
+/// generates one from `defaults.run_id_template` (or a bare timestamp),
+/// prefixed with `CODEX_FLOW_RUN_ID_PREFIX`.
+fn derive_run_id(
+    input: Option<String>,
+    template: Option<&str>,
+    workflow_name: &str,
+) -> Result<(String, bool)> {
+    if let Some(value) = input.or_else(runtime_config::run_id_default) {
         validate_run_id(&value)?;
-        Ok((value, false))
-    } else {
-        Ok((default_run_id(), true))
+        return Ok((value, false));
     }
+    let prefix = runtime_config::run_id_prefix().unwrap_or_default();
+    let generated = match template {
+        Some(template) => {
+            let state_dir = runtime_state::state_root().join(workflow_name);
+            let exists = |id: &str| state_dir.join(format!("{prefix}{id}.resume.json")).exists();
+            let ctx = run_id::TemplateContext {
+                now: Utc::now(),
+                workflow: workflow_name,
+                git_branch: &run_id::current_git_branch,
+                exists: &exists,
+            };
+            let expanded = run_id::expand_template(template, &ctx)
+                .map_err(|err| FailureClass::Config.tag(err))?;
+            let value = format!("{prefix}{expanded}");
+            validate_run_id(&value)
+                .with_context(|| format!("run_id_template `{template}` produced `{value}`"))
+                .map_err(|err| FailureClass::Config.tag(err))?;
+            value
+        }
+        None => {
+            let value = format!("{prefix}{}", default_run_id());
+            validate_run_id(&value)
+                .with_context(|| format!("invalid {}", runtime_config::RUN_ID_PREFIX_ENV))?;
+            value
+        }
+    };
+    Ok((generated, true))
 }
 
 fn validate_run_id(id: &str) -> Result<()> {
@@ -258,7 +455,7 @@ fn resolve_resume_mock_flag(args: &ResumeArgs, default: Option<bool>) -> bool {
     } else if args.no_mock {
         false
     } else {
-        default.unwrap_or(true)
+        runtime_config::mock_default().or(default).unwrap_or(true)
     }
 }
 
@@ -0,0 +1,200 @@
+use std::io::BufRead;
+use std::io::IsTerminal;
+use std::io::Write;
+use std::io::{self};
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use anyhow::bail;
+use nucleo_matcher::Matcher;
+use nucleo_matcher::pattern::CaseMatching;
+use nucleo_matcher::pattern::Normalization;
+use nucleo_matcher::pattern::Pattern;
+
+use crate::cli::cmd_fmt::discover_workflow_files;
+use crate::config;
+use crate::failure::FailureClass;
+use crate::runtime::config as runtime_config;
+
+/// Maximum number of candidates listed at once; narrow further by typing.
+const MAX_LISTED: usize = 20;
+
+struct Candidate {
+    path: PathBuf,
+    label: String,
+    description: String,
+    haystack: String,
+}
+
+impl AsRef<str> for Candidate {
+    fn as_ref(&self) -> &str {
+        &self.haystack
+    }
+}
+
+/// Resolves the workflow file for `run`: the explicit argument when given,
+/// otherwise an interactive pick from `<flow-dir>/workflows` on a terminal.
+pub fn resolve_workflow_file(file: Option<PathBuf>) -> Result<PathBuf> {
+    if let Some(file) = file {
+        return Ok(file);
+    }
+    if !io::stdin().is_terminal() {
+        return Err(FailureClass::Config.tag(anyhow!(
+            "no workflow file given; pass a path (the interactive picker needs a terminal)"
+        )));
+    }
+    let workflows_dir = runtime_config::flow_dir().join("workflows");
+    let candidates = discover_candidates(&workflows_dir)?;
+    if candidates.is_empty() {
+        return Err(FailureClass::Config.tag(anyhow!(
+            "no workflow files found under {}; pass a path or run `codex-flow new workflow`",
+            workflows_dir.display()
+        )));
+    }
+    pick(&candidates, &workflows_dir)
+}
+
+fn discover_candidates(dir: &Path) -> Result<Vec<Candidate>> {
+    let candidates = discover_workflow_files(dir)?
+        .into_iter()
+        .map(|path| {
+            let label = path
+                .strip_prefix(dir)
+                .unwrap_or(&path)
+                .display()
+                .to_string();
+            let description = describe(&path);
+            let haystack = format!("{label} {description}");
+            Candidate {
+                path,
+                label,
+                description,
+                haystack,
+            }
+        })
+        .collect();
+    Ok(candidates)
+}
+
+fn describe(path: &Path) -> String {
+    if let Ok(file) = config::WorkflowFile::load(path) {
+        return file.workflow.description.unwrap_or_default();
+    }
+    match config::FlowConfig::load(path) {
+        Ok(cfg) => cfg
+            .workflows
+            .values()
+            .find_map(|workflow| workflow.description.clone())
+            .unwrap_or_default(),
+        Err(_) => "(failed to parse)".to_string(),
+    }
+}
+
+fn pick(candidates: &[Candidate], workflows_dir: &Path) -> Result<PathBuf> {
+    let mut shown: Vec<&Candidate> = candidates.iter().collect();
+    let stdin = io::stdin();
+    loop {
+        eprintln!("Workflows in {}:", workflows_dir.display());
+        for (idx, candidate) in shown.iter().take(MAX_LISTED).enumerate() {
+            if candidate.description.is_empty() {
+                eprintln!("  {:>2}) {}", idx + 1, candidate.label);
+            } else {
+                eprintln!(
+                    "  {:>2}) {} — {}",
+                    idx + 1,
+                    candidate.label,
+                    candidate.description
+                );
+            }
+        }
+        if shown.len() > MAX_LISTED {
+            eprintln!("  … {} more; type to filter", shown.len() - MAX_LISTED);
+        }
+        eprint!("Select a workflow (number, or text to filter; empty to cancel): ");
+        io::stderr().flush().ok();
+
+        let mut line = String::new();
+        let read = stdin
+            .lock()
+            .read_line(&mut line)
+            .context("failed to read workflow selection from stdin")?;
+        let answer = line.trim();
+        if read == 0 || answer.is_empty() {
+            bail!("no workflow selected");
+        }
+        if let Ok(number) = answer.parse::<usize>() {
+            if (1..=shown.len().min(MAX_LISTED)).contains(&number) {
+                return Ok(shown[number - 1].path.clone());
+            }
+            eprintln!("No workflow numbered {number}.");
+            continue;
+        }
+        let matches = fuzzy_filter(candidates, answer);
+        match matches.as_slice() {
+            [] => {
+                eprintln!("No workflows match `{answer}`.");
+                shown = candidates.iter().collect();
+            }
+            [only] => {
+                eprintln!("Selected {}", only.label);
+                return Ok(only.path.clone());
+            }
+            _ => shown = matches,
+        }
+    }
+}
+
+/// Returns candidates matching `query`, best match first.
+fn fuzzy_filter<'a>(candidates: &'a [Candidate], query: &str) -> Vec<&'a Candidate> {
+    let pattern = Pattern::parse(query, CaseMatching::Ignore, Normalization::Smart);
+    let mut matcher = Matcher::new(nucleo_matcher::Config::DEFAULT.match_paths());
+    pattern
+        .match_list(candidates, &mut matcher)
+        .into_iter()
+        .map(|(candidate, _score)| candidate)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    #[test]
+    fn discovers_descriptions_and_filters_fuzzily() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(
+            tmp.path().join("review.workflow.toml"),
+            "name = \"review\"\n\n[workflow]\ndescription = \"Review the diff\"\n",
+        )
+        .unwrap();
+        fs::write(
+            tmp.path().join("release.workflow.toml"),
+            "name = \"release\"\n\n[workflow]\ndescription = \"Cut a release\"\n",
+        )
+        .unwrap();
+
+        let candidates = discover_candidates(tmp.path()).unwrap();
+        let labels: Vec<(&str, &str)> = candidates
+            .iter()
+            .map(|candidate| (candidate.label.as_str(), candidate.description.as_str()))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                ("release.workflow.toml", "Cut a release"),
+                ("review.workflow.toml", "Review the diff"),
+            ]
+        );
+
+        let matches: Vec<&str> = fuzzy_filter(&candidates, "diff")
+            .into_iter()
+            .map(|candidate| candidate.label.as_str())
+            .collect();
+        assert_eq!(matches, vec!["review.workflow.toml"]);
+    }
+}
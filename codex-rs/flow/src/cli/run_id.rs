@@ -0,0 +1,119 @@
+use std::process::Command;
+
+use anyhow::Result;
+use anyhow::bail;
+use chrono::DateTime;
+use chrono::Utc;
+
+/// Highest `{seq}` tried before giving up on finding an unused run id.
+const MAX_SEQ: u32 = 9999;
+
+/// Inputs for expanding `defaults.run_id_template`.
+pub struct TemplateContext<'a> {
+    pub now: DateTime<Utc>,
+    pub workflow: &'a str,
+    /// Resolved lazily so templates without `{git_branch}` never shell out.
+    pub git_branch: &'a dyn Fn() -> Option<String>,
+    /// Whether a run with this id already exists (drives `{seq}`).
+    pub exists: &'a dyn Fn(&str) -> bool,
+}
+
+/// Expands `{date}`, `{time}`, `{timestamp}`, `{workflow}`, `{git_branch}`, and
+/// `{seq}` (the lowest zero-padded counter whose run id is not taken yet).
+pub fn expand_template(template: &str, ctx: &TemplateContext<'_>) -> Result<String> {
+    let mut base = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        base.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            bail!("run_id_template has an unclosed `{{` in `{template}`");
+        };
+        let name = &rest[start + 1..start + len];
+        let value = match name {
+            "date" => ctx.now.format("%Y%m%d").to_string(),
+            "time" => ctx.now.format("%H%M%S").to_string(),
+            "timestamp" => ctx.now.format("%Y%m%dT%H%M%SZ").to_string(),
+            "workflow" => sanitize(ctx.workflow),
+            "git_branch" => (ctx.git_branch)()
+                .map(|branch| sanitize(&branch))
+                .unwrap_or_else(|| "nogit".to_string()),
+            // Kept as a marker and resolved once the rest of the id is known.
+            "seq" => "{seq}".to_string(),
+            other => bail!(
+                "unknown placeholder `{{{other}}}` in run_id_template (expected date, time, timestamp, workflow, git_branch, or seq)"
+            ),
+        };
+        base.push_str(&value);
+        rest = &rest[start + len + 1..];
+    }
+    base.push_str(rest);
+
+    if !base.contains("{seq}") {
+        return Ok(base);
+    }
+    for seq in 1..=MAX_SEQ {
+        let candidate = base.replace("{seq}", &format!("{seq:03}"));
+        if !(ctx.exists)(&candidate) {
+            return Ok(candidate);
+        }
+    }
+    bail!("run_id_template `{template}` exhausted {{seq}} values up to {MAX_SEQ}");
+}
+
+/// Current git branch of the working directory, if any.
+pub fn current_git_branch() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!branch.is_empty()).then_some(branch)
+}
+
+/// Maps characters that run ids reject (e.g. `/` in `feature/x`) to `-`.
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.') {
+                ch
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn expands_placeholders_and_picks_next_free_seq() {
+        let now = DateTime::parse_from_rfc3339("2025-11-11T12:30:45Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let taken = ["20251111-feature-login-001", "20251111-feature-login-002"];
+        let ctx = TemplateContext {
+            now,
+            workflow: "review",
+            git_branch: &|| Some("feature/login".to_string()),
+            exists: &|id| taken.contains(&id),
+        };
+
+        assert_eq!(
+            expand_template("{date}-{git_branch}-{seq}", &ctx).unwrap(),
+            "20251111-feature-login-003"
+        );
+        assert_eq!(
+            expand_template("{workflow}_{timestamp}", &ctx).unwrap(),
+            "review_20251111T123045Z"
+        );
+        assert!(expand_template("{branch}", &ctx).is_err());
+        assert!(expand_template("{date", &ctx).is_err());
+    }
+}
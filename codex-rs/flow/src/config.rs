@@ -14,6 +14,9 @@ use serde::Serialize;
 pub struct DefaultsConfig {
     pub engine: Option<String>,
     pub mock: Option<bool>,
+    /// Template for generated run ids, e.g. `"{date}-{git_branch}-{seq}"`
+    #[serde(default)]
+    pub run_id_template: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
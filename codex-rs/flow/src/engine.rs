@@ -11,6 +11,7 @@ use std::process::ExitStatus;
 use std::process::Stdio;
 use std::thread;
 use std::time::Duration;
+use std::time::Instant;
 
 use anyhow::Context;
 use anyhow::Result;
@@ -22,11 +23,15 @@ use codex_exec::exec_events::ThreadItemDetails;
 use crate::config::AgentSpec;
 use crate::config::FlowConfig;
 use crate::config::StepSpec;
+use crate::failure::FailureClass;
 use crate::human_renderer::HumanEventRenderer;
 use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::ReasoningSummary;
 use metrics::token_ledger::UsageRecorder;
 
+/// Engines a step may select via `engine = "..."`.
+pub const SUPPORTED_ENGINES: [&str; 2] = ["codex", "codemachine"];
+
 #[derive(Debug, Clone)]
 pub struct ResolvedStep {
     pub engine: String,
@@ -71,6 +76,8 @@ pub struct EngineContext<'a> {
     // Path to write the agent's final message (Markdown) via `codex exec -o`
     pub result_path: &'a Path,
     pub renderer: &'a mut HumanEventRenderer,
+    /// Echo engine stderr and event parse timings to our stderr (`-vvv`)
+    pub diagnostics: bool,
 }
 
 pub trait Engine {
@@ -226,6 +233,7 @@ fn run_codex(ctx: EngineContext<'_>, mut metrics: Option<&mut dyn UsageRecorder>
             .with_context(|| format!("failed to create step log {}", ctx.memory_path.display()))?,
     );
 
+    let passthrough = ctx.diagnostics;
     let stderr_handle = thread::spawn(move || -> io::Result<String> {
         let mut reader = BufReader::new(stderr);
         let mut collected = String::new();
@@ -235,6 +243,9 @@ fn run_codex(ctx: EngineContext<'_>, mut metrics: Option<&mut dyn UsageRecorder>
             if len == 0 {
                 break;
             }
+            if passthrough {
+                eprint!("       stderr: {line}");
+            }
             io::stderr().flush().ok();
             collected.push_str(&line);
         }
@@ -264,8 +275,16 @@ fn run_codex(ctx: EngineContext<'_>, mut metrics: Option<&mut dyn UsageRecorder>
         log_writer
             .flush()
             .with_context(|| format!("failed to flush step log {}", ctx.memory_path.display()))?;
+        let parse_started = Instant::now();
         let event: ThreadEvent = serde_json::from_str(trimmed)
             .with_context(|| format!("failed to parse codex exec event: {trimmed}"))?;
+        if ctx.diagnostics {
+            eprintln!(
+                "       event parsed in {:?} ({} bytes)",
+                parse_started.elapsed(),
+                trimmed.len()
+            );
+        }
         ctx.renderer.render_event(&event);
         if let Some(sink) = metrics.as_deref_mut()
             && let ThreadEvent::TurnCompleted(turn) = &event
@@ -296,7 +315,11 @@ fn run_codex(ctx: EngineContext<'_>, mut metrics: Option<&mut dyn UsageRecorder>
     }
 
     if !status.success() {
-        bail!("codex exec exited with {}", display_exit(status));
+        let err = anyhow!("codex exec exited with {}", display_exit(status));
+        if status.code() == Some(codex_exec::exit_code::BUDGET_EXCEEDED) {
+            return Err(FailureClass::Budget.tag(err));
+        }
+        return Err(err);
     }
 
     Ok(())
@@ -388,7 +411,10 @@ fn replay_mock(
 
 fn display_exit(status: ExitStatus) -> String {
     if let Some(code) = status.code() {
-        format!("code {code}")
+        match codex_exec::exit_code::describe(code) {
+            Some(reason) => format!("code {code} ({reason})"),
+            None => format!("code {code}"),
+        }
     } else {
         "signal".to_string()
     }
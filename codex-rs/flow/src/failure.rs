@@ -0,0 +1,97 @@
+use std::fmt;
+
+use clap::ValueEnum;
+
+/// Broad outcome classes that map to distinct process exit codes so CI can
+/// tell a broken workflow definition apart from a failing agent step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum FailureClass {
+    /// The workflow file, agent table, or engine selection is invalid (exit 2)
+    Config,
+    /// A workflow step failed while running its engine (exit 3)
+    Step,
+    /// A configured budget was exceeded (exit 4)
+    Budget,
+    /// The run was interrupted by SIGINT (exit 130)
+    Interrupted,
+}
+
+impl FailureClass {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            FailureClass::Config => 2,
+            FailureClass::Step => 3,
+            FailureClass::Budget => 4,
+            FailureClass::Interrupted => 130,
+        }
+    }
+
+    /// Returns the class attached to `err`, either as the error itself or as
+    /// context added with `.context(FailureClass::..)`.
+    pub fn of(err: &anyhow::Error) -> Option<FailureClass> {
+        err.downcast_ref::<FailureClass>().copied()
+    }
+
+    /// Attaches `self` to `err` unless an inner layer already classified it.
+    pub fn tag(self, err: anyhow::Error) -> anyhow::Error {
+        if FailureClass::of(&err).is_some() {
+            err
+        } else {
+            err.context(self)
+        }
+    }
+}
+
+impl fmt::Display for FailureClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            FailureClass::Config => "invalid workflow configuration",
+            FailureClass::Step => "workflow step failed",
+            FailureClass::Budget => "workflow budget exceeded",
+            FailureClass::Interrupted => "workflow interrupted (SIGINT)",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for FailureClass {}
+
+/// Maps a command outcome to its exit code. Unclassified errors exit 1; classes
+/// listed in `exit_zero_on` are reported but treated as success.
+pub fn exit_code(result: &anyhow::Result<()>, exit_zero_on: &[FailureClass]) -> i32 {
+    let Err(err) = result else {
+        return 0;
+    };
+    match FailureClass::of(err) {
+        Some(class) if exit_zero_on.contains(&class) => 0,
+        Some(class) => class.exit_code(),
+        None => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn maps_classes_to_exit_codes() {
+        let step: anyhow::Result<()> = Err(FailureClass::Step.tag(anyhow!("codex exec exited")));
+        let interrupted: anyhow::Result<()> = Err(FailureClass::Interrupted.into());
+        let generic: anyhow::Result<()> = Err(anyhow!("boom"));
+
+        assert_eq!(exit_code(&Ok(()), &[]), 0);
+        assert_eq!(exit_code(&step, &[]), 3);
+        assert_eq!(exit_code(&interrupted, &[]), 130);
+        assert_eq!(exit_code(&generic, &[]), 1);
+        assert_eq!(exit_code(&step, &[FailureClass::Step]), 0);
+        assert_eq!(exit_code(&generic, &[FailureClass::Step]), 1);
+    }
+
+    #[test]
+    fn inner_classification_wins() {
+        let err = FailureClass::Step.tag(FailureClass::Config.tag(anyhow!("unsupported engine")));
+        assert_eq!(FailureClass::of(&err), Some(FailureClass::Config));
+    }
+}
@@ -28,6 +28,7 @@ use codex_exec::exec_events::ThreadStartedEvent;
 use codex_exec::exec_events::TodoListItem;
 use codex_exec::exec_events::TurnCompletedEvent;
 use codex_exec::exec_events::TurnFailedEvent;
+use codex_exec::exec_events::TurnRetryingEvent;
 use codex_exec::exec_events::Usage;
 use codex_exec::exec_events::WebSearchItem;
 use codex_protocol::num_format::format_with_separators;
@@ -62,6 +63,15 @@ impl HumanEventRenderer {
         Ok(Self::with_output(output))
     }
 
+    /// Like [`Self::with_log_path`] but writes nothing to stdout, for runs
+    /// embedded in another program's terminal UI.
+    pub fn log_only(path: &Path) -> Result<Self> {
+        let mut output = OutputSink::with_log_file(path)
+            .with_context(|| format!("failed to create human output log {}", path.display()))?;
+        output.stdout = None;
+        Ok(Self::with_output(output))
+    }
+
     fn with_output(output: OutputSink) -> Self {
         let with_ansi = supports_color::on_cached(Stream::Stdout).is_some();
         Self {
@@ -77,6 +87,7 @@ impl HumanEventRenderer {
             ThreadEvent::TurnStarted(_) => {}
             ThreadEvent::TurnCompleted(ev) => self.render_turn_completed(ev),
             ThreadEvent::TurnFailed(ev) => self.render_turn_failed(ev),
+            ThreadEvent::TurnRetrying(ev) => self.render_turn_retrying(ev),
             ThreadEvent::ItemStarted(ev) => self.render_item_started(ev),
             ThreadEvent::ItemUpdated(ev) => self.render_item_updated(ev),
             ThreadEvent::ItemCompleted(ev) => self.render_item_completed(ev),
@@ -134,6 +145,27 @@ impl HumanEventRenderer {
             cached = totals.cached,
             output = totals.output,
         ));
+        if !ev.tool_usage.is_empty() {
+            self.write_line(
+                "tool time"
+                    .style(self.styles.magenta)
+                    .style(self.styles.italic),
+            );
+            for tool in &ev.tool_usage {
+                let failed = if tool.failures > 0 {
+                    format!(", {} failed", tool.failures)
+                } else {
+                    String::new()
+                };
+                self.write_line(format!(
+                    "{} {} ({} call{}{failed})",
+                    tool.tool_name,
+                    format_millis(tool.duration_ms),
+                    tool.calls,
+                    if tool.calls == 1 { "" } else { "s" },
+                ));
+            }
+        }
     }
 
     fn render_turn_failed(&mut self, ev: &TurnFailedEvent) {
@@ -144,6 +176,16 @@ impl HumanEventRenderer {
         ));
     }
 
+    fn render_turn_retrying(&mut self, ev: &TurnRetryingEvent) {
+        self.write_line(format!(
+            "{} resuming in {} (attempt {}/{})",
+            "retry:".style(self.styles.yellow).style(self.styles.bold),
+            format_millis(ev.delay_ms),
+            ev.attempt,
+            ev.max_attempts
+        ));
+    }
+
     fn render_item_started(&mut self, ev: &ItemStartedEvent) {
         match &ev.item.details {
             ThreadItemDetails::CommandExecution(cmd) => self.render_command_start(&ev.item.id, cmd),
@@ -388,14 +430,14 @@ impl TurnTotals {
 }
 
 struct OutputSink {
-    stdout: io::Stdout,
+    stdout: Option<io::Stdout>,
     file: Option<BufWriter<File>>,
 }
 
 impl OutputSink {
     fn stdout_only() -> Self {
         Self {
-            stdout: io::stdout(),
+            stdout: Some(io::stdout()),
             file: None,
         }
     }
@@ -403,7 +445,7 @@ impl OutputSink {
     fn with_log_file(path: &Path) -> io::Result<Self> {
         let file = File::create(path)?;
         Ok(Self {
-            stdout: io::stdout(),
+            stdout: Some(io::stdout()),
             file: Some(BufWriter::new(file)),
         })
     }
@@ -412,7 +454,9 @@ impl OutputSink {
         if text.is_empty() {
             return;
         }
-        let _ = self.stdout.write_all(text.as_bytes());
+        if let Some(stdout) = &mut self.stdout {
+            let _ = stdout.write_all(text.as_bytes());
+        }
         if let Some(file) = &mut self.file {
             let plain = strip_ansi_codes(text);
             let _ = file.write_all(plain.as_ref().as_bytes());
@@ -429,14 +473,18 @@ impl OutputSink {
     }
 
     fn write_newline(&mut self) {
-        let _ = self.stdout.write_all(b"\n");
+        if let Some(stdout) = &mut self.stdout {
+            let _ = stdout.write_all(b"\n");
+        }
         if let Some(file) = &mut self.file {
             let _ = file.write_all(b"\n");
         }
     }
 
     fn flush(&mut self) {
-        let _ = self.stdout.flush();
+        if let Some(stdout) = &mut self.stdout {
+            let _ = stdout.flush();
+        }
         if let Some(file) = &mut self.file {
             let _ = file.flush();
         }
@@ -531,6 +579,17 @@ impl Styles {
     }
 }
 
+fn format_millis(millis: u64) -> String {
+    if millis < 1_000 {
+        format!("{millis}ms")
+    } else if millis < 60_000 {
+        format!("{:.1}s", millis as f64 / 1_000.0)
+    } else {
+        let secs = millis / 1_000;
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    }
+}
+
 fn format_mcp_invocation(server: &str, tool: &str, arguments: &JsonValue) -> String {
     let fq_tool_name = format!("{server}.{tool}");
     let args_str = match arguments {
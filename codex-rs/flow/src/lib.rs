@@ -1,8 +1,11 @@
+pub mod bundle;
 pub mod cli;
 pub mod config;
 pub mod engine;
+pub mod failure;
 pub mod human_renderer;
 pub mod runner;
 pub mod runtime;
 pub mod scaffold;
+pub mod usage_metrics;
 pub mod utils;
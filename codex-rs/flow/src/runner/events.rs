@@ -0,0 +1,195 @@
+use std::cell::RefCell;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+use crate::runner::TokenUsage;
+
+/// Progress events emitted while a workflow runs, serialized one JSON object
+/// per line for `--events-socket` listeners.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RunnerEvent {
+    WorkflowStarted {
+        workflow: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        run_id: Option<String>,
+        total_steps: usize,
+        mock: bool,
+    },
+    StepSkipped {
+        index: usize,
+        agent: String,
+    },
+    StepStarted {
+        index: usize,
+        agent: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+    },
+    StepCompleted {
+        index: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        token_delta: Option<TokenUsage>,
+    },
+    StepFailed {
+        index: usize,
+        error: String,
+    },
+    WorkflowCompleted {
+        executed_steps: usize,
+        skipped_steps: usize,
+    },
+    WorkflowFailed {
+        error: String,
+    },
+    Interrupted {
+        resume_pointer: usize,
+    },
+}
+
+impl RunnerEvent {
+    /// One-line, human-readable form of the event; step indexes are shown
+    /// 1-based.
+    pub fn describe(&self) -> String {
+        match self {
+            RunnerEvent::WorkflowStarted {
+                workflow,
+                total_steps,
+                mock,
+                ..
+            } => {
+                let mode = if *mock { " (mock)" } else { "" };
+                format!("workflow {workflow} started with {total_steps} step(s){mode}")
+            }
+            RunnerEvent::StepSkipped { index, agent } => {
+                format!("step {} ({agent}) skipped", index + 1)
+            }
+            RunnerEvent::StepStarted {
+                index,
+                agent,
+                description,
+            } => match description {
+                Some(description) => {
+                    format!("step {} ({agent}) started: {description}", index + 1)
+                }
+                None => format!("step {} ({agent}) started", index + 1),
+            },
+            RunnerEvent::StepCompleted { index, token_delta } => match token_delta {
+                Some(usage) => format!(
+                    "step {} completed ({} tokens)",
+                    index + 1,
+                    usage.total_tokens
+                ),
+                None => format!("step {} completed", index + 1),
+            },
+            RunnerEvent::StepFailed { index, error } => {
+                format!("step {} failed: {error}", index + 1)
+            }
+            RunnerEvent::WorkflowCompleted {
+                executed_steps,
+                skipped_steps,
+            } => format!(
+                "workflow completed: {executed_steps} step(s) executed, {skipped_steps} skipped"
+            ),
+            RunnerEvent::WorkflowFailed { error } => format!("workflow failed: {error}"),
+            RunnerEvent::Interrupted { resume_pointer } => {
+                format!("workflow interrupted at step {}", resume_pointer + 1)
+            }
+        }
+    }
+}
+
+/// Receives every [`RunnerEvent`] the runner emits.
+pub trait EventSink: Send + Sync {
+    fn emit(&self, line: &str);
+}
+
+static SINK: OnceLock<Box<dyn EventSink>> = OnceLock::new();
+
+type Listener = Box<dyn FnMut(&RunnerEvent)>;
+
+thread_local! {
+    static LISTENER: RefCell<Option<Listener>> = const { RefCell::new(None) };
+}
+
+/// Installs the process-wide sink; later calls are ignored.
+pub fn set_sink(sink: Box<dyn EventSink>) {
+    let _ = SINK.set(sink);
+}
+
+/// Runs `f` with `listener` receiving every event emitted on this thread,
+/// in addition to the installed sink.
+pub fn with_listener<T>(listener: impl FnMut(&RunnerEvent) + 'static, f: impl FnOnce() -> T) -> T {
+    let previous = LISTENER.with(|slot| slot.replace(Some(Box::new(listener))));
+    let result = f();
+    LISTENER.with(|slot| slot.replace(previous));
+    result
+}
+
+/// Forwards `event` to this thread's listener and the installed sink, if any.
+pub fn emit(event: &RunnerEvent) {
+    LISTENER.with(|slot| {
+        if let Some(listener) = slot.borrow_mut().as_mut() {
+            listener(event);
+        }
+    });
+    let Some(sink) = SINK.get() else {
+        return;
+    };
+    match serde_json::to_string(event) {
+        Ok(line) => sink.emit(&line),
+        Err(err) => eprintln!("[events] failed to serialize event: {err}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn events_serialize_with_type_tag() {
+        let line = serde_json::to_string(&RunnerEvent::StepStarted {
+            index: 1,
+            agent: "reviewer".to_string(),
+            description: None,
+        })
+        .unwrap();
+        assert_eq!(
+            line,
+            r#"{"type":"step_started","index":1,"agent":"reviewer"}"#
+        );
+    }
+
+    #[test]
+    fn listener_sees_events_emitted_on_its_thread() {
+        let seen = std::rc::Rc::new(RefCell::new(Vec::new()));
+        let recorder = seen.clone();
+        with_listener(
+            move |event| recorder.borrow_mut().push(event.describe()),
+            || {
+                emit(&RunnerEvent::StepStarted {
+                    index: 0,
+                    agent: "reviewer".to_string(),
+                    description: Some("Review the diff".to_string()),
+                });
+                emit(&RunnerEvent::StepFailed {
+                    index: 0,
+                    error: "boom".to_string(),
+                });
+            },
+        );
+        emit(&RunnerEvent::StepSkipped {
+            index: 1,
+            agent: "writer".to_string(),
+        });
+        assert_eq!(
+            *seen.borrow(),
+            vec![
+                "step 1 (reviewer) started: Review the diff".to_string(),
+                "step 1 failed: boom".to_string(),
+            ]
+        );
+    }
+}
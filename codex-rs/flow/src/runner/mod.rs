@@ -8,7 +8,7 @@ use std::sync::atomic::Ordering;
 
 use anyhow::Context;
 use anyhow::Result;
-use anyhow::bail;
+use anyhow::anyhow;
 
 use crate::config::FlowConfig;
 use crate::config::StepSpec;
@@ -22,11 +22,16 @@ use crate::engine::metrics::token_ledger::StepHandle;
 use crate::engine::metrics::token_ledger::TokenLedger;
 use crate::engine::metrics::token_ledger::UsageRecorder;
 use crate::engine::resolve_step;
+use crate::failure::FailureClass;
 use crate::human_renderer::HumanEventRenderer;
 use crate::runtime::init as runtime_init;
+use crate::runtime::state_store as runtime_state;
+use events::RunnerEvent;
 
+pub mod events;
 pub mod migrations;
 pub mod planner;
+pub mod preflight;
 pub mod state_store;
 
 pub use state_store::PersistenceMode;
@@ -35,6 +40,8 @@ pub use state_store::StepStatus;
 pub use state_store::TokenUsage;
 pub use state_store::WorkflowRunState;
 pub use state_store::WorkflowStateStore;
+pub use state_store::runner_lock_held;
+pub use state_store::runner_lock_path;
 
 #[derive(Debug)]
 pub struct RunSummary {
@@ -64,7 +71,30 @@ impl StatePersistence {
 #[derive(Clone, Copy)]
 pub struct RunOptions {
     pub mock: bool,
-    pub verbose: bool,
+    pub verbosity: Verbosity,
+    /// Running inside another program, e.g. for `coco flow run` in a codex
+    /// session: step output only goes to the step logs and no Ctrl-C
+    /// handler is installed.
+    pub embedded: bool,
+}
+
+/// Number of `-v` flags: 1 prints step banners, 2 adds resolved configs and
+/// commands, 3 enables engine diagnostics (stderr passthrough, parse timings).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Verbosity(pub u8);
+
+impl Verbosity {
+    pub fn banners(self) -> bool {
+        self.0 >= 1
+    }
+
+    pub fn details(self) -> bool {
+        self.0 >= 2
+    }
+
+    pub fn diagnostics(self) -> bool {
+        self.0 >= 3
+    }
 }
 
 pub fn run_workflow(
@@ -72,12 +102,32 @@ pub fn run_workflow(
     name: &str,
     opts: RunOptions,
     persistence: Option<StatePersistence>,
+) -> Result<RunSummary> {
+    let result = execute_workflow(cfg, name, opts, persistence);
+    match &result {
+        Ok(summary) => events::emit(&RunnerEvent::WorkflowCompleted {
+            executed_steps: summary.executed_steps,
+            skipped_steps: summary.skipped_steps,
+        }),
+        Err(err) if FailureClass::of(err) == Some(FailureClass::Interrupted) => {}
+        Err(err) => events::emit(&RunnerEvent::WorkflowFailed {
+            error: format!("{err:#}"),
+        }),
+    }
+    result
+}
+
+fn execute_workflow(
+    cfg: &FlowConfig,
+    name: &str,
+    opts: RunOptions,
+    persistence: Option<StatePersistence>,
 ) -> Result<RunSummary> {
     runtime_init::ensure_runtime_tree()?;
     let Some(wf) = cfg.workflows.get(name) else {
-        bail!("workflow not found: {name}");
+        return Err(FailureClass::Config.tag(anyhow!("workflow not found: {name}")));
     };
-    if opts.verbose {
+    if opts.verbosity.banners() {
         eprintln!("Running workflow {name} (mock={})", opts.mock);
     }
 
@@ -87,11 +137,31 @@ pub fn run_workflow(
         (None, 0, None)
     };
     let initial_pointer = resume_cursor;
-    let interrupt_flag = install_interrupt_handler();
+    events::emit(&RunnerEvent::WorkflowStarted {
+        workflow: name.to_string(),
+        run_id: run_id.clone(),
+        total_steps: wf.steps.len(),
+        mock: opts.mock,
+    });
+    // Taken before the pid is recorded, so `kill` only trusts a pid whose
+    // lock is still held.
+    let _runner_lock = state_store
+        .as_ref()
+        .map(WorkflowStateStore::lock_runner)
+        .transpose()?;
+    if let Some(store) = state_store.as_mut() {
+        store.state_mut().total_steps = Some(wf.steps.len());
+        store.set_pid(Some(std::process::id()))?;
+    }
+    let interrupt_flag = if opts.embedded {
+        Arc::new(AtomicBool::new(false))
+    } else {
+        install_interrupt_handler()
+    };
     interrupt_flag.store(false, Ordering::SeqCst);
 
     let mut executed_steps = 0usize;
-    let mut ledger = if state_store.is_some() || opts.verbose {
+    let mut ledger = if state_store.is_some() || opts.verbosity.banners() {
         Some(TokenLedger::new())
     } else {
         None
@@ -100,28 +170,41 @@ pub fn run_workflow(
     for (idx, step) in wf.steps.iter().enumerate() {
         if interrupt_flag.load(Ordering::SeqCst) {
             if let Some(store) = state_store.as_mut() {
+                store.state_mut().pid = None;
                 store.record_interruption(store.state().resume_pointer)?;
             }
-            bail!("workflow interrupted (SIGINT)");
+            events::emit(&RunnerEvent::Interrupted {
+                resume_pointer: resume_cursor,
+            });
+            return Err(FailureClass::Interrupted.into());
         }
         if idx < resume_cursor {
-            if opts.verbose {
+            if opts.verbosity.banners() {
                 eprintln!(
                     "Skipping step-{} (resume pointer at {})",
                     idx + 1,
                     resume_cursor
                 );
             }
+            events::emit(&RunnerEvent::StepSkipped {
+                index: idx,
+                agent: step.agent.clone(),
+            });
             continue;
         }
         let agent_id = &step.agent;
         let Some(agent) = cfg.agents.get(agent_id) else {
-            bail!("agent not found: {agent_id}");
+            return Err(FailureClass::Config.tag(anyhow!("agent not found: {agent_id}")));
         };
         let resolved = resolve_step(agent, step);
         let paths = create_step_paths(idx, step, agent_id)?;
         let memory_path_str = paths.result_md.display().to_string();
         let debug_log_str = paths.memory.display().to_string();
+        events::emit(&RunnerEvent::StepStarted {
+            index: idx,
+            agent: agent_id.clone(),
+            description: step.description.clone(),
+        });
         let mut step_handle = ledger.as_mut().map(|ledger| ledger.step(&resolved.model));
         let run_result = {
             let usage_recorder = step_handle
@@ -154,10 +237,15 @@ pub fn run_workflow(
                     })?;
                     resume_cursor = store.state().resume_pointer;
                 }
+                events::emit(&RunnerEvent::StepCompleted {
+                    index: idx,
+                    token_delta,
+                });
                 executed_steps += 1;
             }
             Err(err) => {
                 if let Some(store) = state_store.as_mut() {
+                    store.state_mut().pid = None;
                     store.record_step(StepState {
                         index: idx,
                         status: StepStatus::Failed,
@@ -167,7 +255,11 @@ pub fn run_workflow(
                         token_delta,
                     })?;
                 }
-                return Err(err);
+                events::emit(&RunnerEvent::StepFailed {
+                    index: idx,
+                    error: format!("{err:#}"),
+                });
+                return Err(FailureClass::Step.tag(err));
             }
         }
     }
@@ -178,8 +270,11 @@ pub fn run_workflow(
     let ledger_total = ledger
         .as_ref()
         .and_then(|ledger| ledger.total_usage().cloned());
-    if let (Some(store), Some(delta)) = (state_store.as_mut(), ledger_total.as_ref()) {
-        store.append_token_usage(delta)?;
+    if let Some(store) = state_store.as_mut() {
+        if let Some(delta) = ledger_total.as_ref() {
+            store.append_token_usage(delta)?;
+        }
+        store.set_pid(None)?;
     }
     Ok(RunSummary {
         executed_steps,
@@ -219,13 +314,15 @@ fn run_step<'a>(
         .filter(|desc| !desc.trim().is_empty())
         .unwrap_or(agent_id);
 
-    if opts.verbose {
+    if opts.verbosity.banners() {
         let mode = if opts.mock { "mock" } else { "real" };
         eprintln!(
             "[{mode}] step-{} ({}) -> {agent_id}",
             step_index + 1,
             step_label
         );
+    }
+    if opts.verbosity.details() {
         if opts.mock {
             eprintln!("       replay={}", memory_path.display());
             eprintln!(
@@ -248,7 +345,11 @@ fn run_step<'a>(
         }
     }
 
-    let mut renderer = HumanEventRenderer::with_log_path(human_log_path)?;
+    let mut renderer = if opts.embedded {
+        HumanEventRenderer::log_only(human_log_path)?
+    } else {
+        HumanEventRenderer::with_log_path(human_log_path)?
+    };
     match step.engine.as_str() {
         "codex" => {
             if opts.mock {
@@ -260,6 +361,7 @@ fn run_step<'a>(
                         memory_path,
                         result_path,
                         renderer: &mut renderer,
+                        diagnostics: opts.verbosity.diagnostics(),
                     },
                     usage_recorder.take(),
                 )?;
@@ -272,6 +374,7 @@ fn run_step<'a>(
                         memory_path,
                         result_path,
                         renderer: &mut renderer,
+                        diagnostics: opts.verbosity.diagnostics(),
                     },
                     usage_recorder.take(),
                 )?;
@@ -281,7 +384,9 @@ fn run_step<'a>(
             let cmd = build_shell_command(step, Some(result_path));
             eprintln!("codemachine execution not yet implemented, command: {cmd}");
         }
-        other => bail!("Unsupported engine: {other}"),
+        other => {
+            return Err(FailureClass::Config.tag(anyhow!("Unsupported engine: {other}")));
+        }
     }
     Ok(())
 }
@@ -328,8 +433,8 @@ fn create_step_paths(step_index: usize, _step: &StepSpec, agent_id: &str) -> Res
     let slug = sanitize_label(agent_id);
     let stem = format!("{:02}-{slug}-agent", step_index + 1, slug = slug);
 
-    // All runtime artifacts live under .codex-flow/runtime to keep the workspace tidy
-    let runtime_root = Path::new(".codex-flow").join("runtime");
+    // All runtime artifacts live under <flow-dir>/runtime to keep the workspace tidy
+    let runtime_root = runtime_state::runtime_root();
     fs::create_dir_all(&runtime_root)
         .with_context(|| format!("failed to create runtime dir {}", runtime_root.display()))?;
 
@@ -58,6 +58,9 @@ mod tests {
             resume_pointer: 3,
             steps: Vec::new(),
             token_usage: None,
+            started_at: None,
+            total_steps: None,
+            pid: None,
         };
         let planner = ResumePlanner::new(&wf);
         let plan = planner.plan(&state);
@@ -0,0 +1,136 @@
+use std::fs::OpenOptions;
+use std::fs::{self};
+use std::path::Path;
+
+use crate::config::FlowConfig;
+use crate::config::WorkflowSpec;
+use crate::engine::SUPPORTED_ENGINES;
+use crate::engine::resolve_step;
+
+/// Checks everything a run would otherwise only discover mid-way: unknown
+/// agents, unsupported engines, missing prompt files, and output directories
+/// that cannot be written. Returns one message per problem, in step order.
+pub fn check_workflow(cfg: &FlowConfig, workflow: &WorkflowSpec) -> Vec<String> {
+    let mut problems = Vec::new();
+    for (idx, step) in workflow.steps.iter().enumerate() {
+        let label = format!("step-{}", idx + 1);
+        let Some(agent) = cfg.agents.get(&step.agent) else {
+            problems.push(format!("{label} references unknown agent `{}`", step.agent));
+            continue;
+        };
+        let resolved = resolve_step(agent, step);
+        if !SUPPORTED_ENGINES.contains(&resolved.engine.as_str()) {
+            problems.push(format!(
+                "{label} uses unsupported engine `{}` (expected one of: {})",
+                resolved.engine,
+                SUPPORTED_ENGINES.join(", ")
+            ));
+        }
+        if !Path::new(&resolved.prompt_path).is_file() {
+            problems.push(format!(
+                "{label} prompt file {} does not exist",
+                resolved.prompt_path
+            ));
+        }
+        if step.output.kind == "file"
+            && let Some(path) = &step.output.path
+        {
+            let dir = path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .unwrap_or(Path::new("."));
+            if let Err(reason) = check_writable_dir(dir) {
+                problems.push(format!(
+                    "{label} output directory {} is not writable: {reason}",
+                    dir.display()
+                ));
+            }
+        }
+    }
+    problems
+}
+
+/// Probes `dir` (or, when it does not exist yet, its nearest existing
+/// ancestor that the run would create it under) by creating a scratch file.
+fn check_writable_dir(dir: &Path) -> Result<(), String> {
+    let Some(existing) = dir.ancestors().find(|ancestor| ancestor.exists()) else {
+        return Err("no existing parent directory".to_string());
+    };
+    if !existing.is_dir() {
+        return Err(format!("{} is not a directory", existing.display()));
+    }
+    let probe = existing.join(format!(".codex-flow-write-probe-{}", std::process::id()));
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .map_err(|err| err.to_string())?;
+    fs::remove_file(&probe).ok();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AgentSpec;
+    use crate::config::StepOutput;
+    use crate::config::StepSpec;
+    use pretty_assertions::assert_eq;
+
+    fn agent(engine: &str, prompt: &Path) -> AgentSpec {
+        AgentSpec {
+            engine: Some(engine.to_string()),
+            prompt: prompt.display().to_string(),
+            ..AgentSpec::default()
+        }
+    }
+
+    #[test]
+    fn reports_every_problem_before_the_run_starts() {
+        let tmp = tempfile::tempdir().unwrap();
+        let prompt = tmp.path().join("ok.md");
+        fs::write(&prompt, "# ok\n").unwrap();
+
+        let mut cfg = FlowConfig::default();
+        cfg.agents.insert("ok".to_string(), agent("codex", &prompt));
+        cfg.agents.insert(
+            "broken".to_string(),
+            agent("gemini", &tmp.path().join("missing.md")),
+        );
+        let workflow = WorkflowSpec {
+            description: None,
+            steps: vec![
+                StepSpec {
+                    agent: "ok".to_string(),
+                    output: StepOutput {
+                        kind: "file".to_string(),
+                        path: Some(tmp.path().join("out/report.md")),
+                    },
+                    ..StepSpec::default()
+                },
+                StepSpec {
+                    agent: "broken".to_string(),
+                    ..StepSpec::default()
+                },
+                StepSpec {
+                    agent: "ghost".to_string(),
+                    ..StepSpec::default()
+                },
+            ],
+        };
+
+        let problems = check_workflow(&cfg, &workflow);
+        assert_eq!(
+            problems,
+            vec![
+                "step-2 uses unsupported engine `gemini` (expected one of: codex, codemachine)"
+                    .to_string(),
+                format!(
+                    "step-2 prompt file {} does not exist",
+                    tmp.path().join("missing.md").display()
+                ),
+                "step-3 references unknown agent `ghost`".to_string(),
+            ]
+        );
+    }
+}
@@ -92,6 +92,15 @@ pub struct WorkflowRunState {
     pub steps: Vec<StepState>,
     #[serde(default)]
     pub token_usage: Option<TokenUsage>,
+    /// RFC 3339 timestamp of when the run state was first created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    /// Number of steps in the workflow definition at the time of the last run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_steps: Option<usize>,
+    /// PID of the runner executing this run; cleared when the runner stops
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
 }
 
 pub struct WorkflowStateStore {
@@ -178,6 +187,17 @@ impl WorkflowStateStore {
         self.persist()
     }
 
+    /// Takes the advisory lock `kill` checks before signalling the recorded
+    /// pid. Hold it for as long as this process runs the workflow.
+    pub fn lock_runner(&self) -> Result<RunnerLock> {
+        RunnerLock::acquire(&runner_lock_path(&self.path))
+    }
+
+    pub fn set_pid(&mut self, pid: Option<u32>) -> Result<()> {
+        self.state.pid = pid;
+        self.persist()
+    }
+
     pub fn update_token_usage(&mut self, usage: TokenUsage) -> Result<()> {
         self.state.token_usage = Some(usage);
         self.persist()
@@ -204,30 +224,7 @@ impl WorkflowStateStore {
     }
 
     fn persist(&self) -> Result<()> {
-        if let Some(dir) = self.path.parent() {
-            fs::create_dir_all(dir).with_context(|| {
-                format!("failed to create workflow state dir {}", dir.display())
-            })?;
-        }
-        let json = serde_json::to_string_pretty(&self.state)? + "\n";
-        let tmp_name = format!(
-            "{}.tmp",
-            self.path
-                .file_name()
-                .and_then(|name| name.to_str())
-                .unwrap_or("state.resume.json")
-        );
-        let tmp_path = self.path.with_file_name(tmp_name);
-        fs::write(&tmp_path, json.as_bytes()).with_context(|| {
-            format!("failed to write workflow state tmp {}", tmp_path.display())
-        })?;
-        fs::rename(&tmp_path, &self.path).with_context(|| {
-            format!(
-                "failed to atomically persist workflow state {}",
-                self.path.display()
-            )
-        })?;
-        Ok(())
+        self.state.write_to_path(&self.path)
     }
 }
 
@@ -240,6 +237,9 @@ impl WorkflowRunState {
             resume_pointer: 0,
             steps: Vec::new(),
             token_usage: None,
+            started_at: Some(Utc::now().to_rfc3339()),
+            total_steps: None,
+            pid: None,
         }
     }
 
@@ -255,6 +255,33 @@ impl WorkflowRunState {
         let (state, _) = read_state(path)?;
         Ok(state)
     }
+
+    /// Atomically writes the state as pretty JSON (via a sibling `.tmp` file).
+    pub fn write_to_path(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| {
+                format!("failed to create workflow state dir {}", dir.display())
+            })?;
+        }
+        let json = serde_json::to_string_pretty(self)? + "\n";
+        let tmp_name = format!(
+            "{}.tmp",
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("state.resume.json")
+        );
+        let tmp_path = path.with_file_name(tmp_name);
+        fs::write(&tmp_path, json.as_bytes()).with_context(|| {
+            format!("failed to write workflow state tmp {}", tmp_path.display())
+        })?;
+        fs::rename(&tmp_path, path).with_context(|| {
+            format!(
+                "failed to atomically persist workflow state {}",
+                path.display()
+            )
+        })?;
+        Ok(())
+    }
 }
 
 impl WorkflowStateStore {
@@ -263,6 +290,75 @@ impl WorkflowStateStore {
     }
 }
 
+/// Lock file next to a state file; the runner holds an exclusive `flock` on
+/// it while running, so a stale pid (reused by another process after the
+/// runner died) is never mistaken for the runner.
+pub fn runner_lock_path(state_path: &Path) -> PathBuf {
+    state_path.with_extension("lock")
+}
+
+/// Exclusive lock on a run's lock file, released when dropped or when the
+/// process exits.
+pub struct RunnerLock {
+    _file: fs::File,
+}
+
+impl RunnerLock {
+    fn acquire(path: &Path) -> Result<Self> {
+        let file = open_lock_file(path)?;
+        if !try_lock(&file)? {
+            anyhow::bail!(
+                "another runner holds {}; the run is already in progress",
+                path.display()
+            );
+        }
+        Ok(Self { _file: file })
+    }
+}
+
+/// Whether some runner currently holds the lock at `path`.
+pub fn runner_lock_held(path: &Path) -> Result<bool> {
+    if !path.exists() {
+        return Ok(false);
+    }
+    let file = open_lock_file(path)?;
+    // Our probe lock is dropped together with `file`.
+    Ok(!try_lock(&file)?)
+}
+
+fn open_lock_file(path: &Path) -> Result<fs::File> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create workflow state dir {}", dir.display()))?;
+    }
+    fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)
+        .with_context(|| format!("failed to open runner lock {}", path.display()))
+}
+
+#[cfg(unix)]
+fn try_lock(file: &fs::File) -> Result<bool> {
+    use std::os::fd::AsRawFd;
+
+    // SAFETY: flock(2) only operates on the descriptor, which `file` keeps open.
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0 {
+        return Ok(true);
+    }
+    let err = std::io::Error::last_os_error();
+    if err.raw_os_error() == Some(libc::EWOULDBLOCK) {
+        return Ok(false);
+    }
+    Err(err).context("failed to lock runner lock file")
+}
+
+#[cfg(not(unix))]
+fn try_lock(_file: &fs::File) -> Result<bool> {
+    Ok(true)
+}
+
 fn read_state(path: &Path) -> Result<(WorkflowRunState, bool)> {
     let raw = fs::read_to_string(path)
         .with_context(|| format!("failed to read workflow state {}", path.display()))?;
@@ -363,6 +459,24 @@ mod tests {
         );
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn runner_lock_is_visible_until_dropped() {
+        let tmp = tempdir().expect("tempdir");
+        let _guard = DirGuard::enter(tmp.path());
+        let store = WorkflowStateStore::load_or_init("workflow", "run-1", PersistenceMode::Mock)
+            .expect("load store");
+        let lock_path = runner_lock_path(&store.path);
+        assert!(!runner_lock_held(&lock_path).expect("probe"));
+
+        let lock = store.lock_runner().expect("lock");
+        assert!(runner_lock_held(&lock_path).expect("probe"));
+        assert!(store.lock_runner().is_err());
+
+        drop(lock);
+        assert!(!runner_lock_held(&lock_path).expect("probe"));
+    }
+
     #[test]
     fn applies_migrations() {
         let tmp = tempdir().expect("tempdir");
@@ -1,6 +1,38 @@
 use std::env;
+use std::path::PathBuf;
+use std::sync::OnceLock;
 
 pub const RESUME_DISABLED_ENV: &str = "CODEX_RESUME_DISABLED";
+pub const FLOW_DIR_ENV: &str = "CODEX_FLOW_DIR";
+pub const DEFAULT_FLOW_DIR: &str = ".codex-flow";
+pub const MOCK_ENV: &str = "CODEX_FLOW_MOCK";
+pub const VERBOSE_ENV: &str = "CODEX_FLOW_VERBOSE";
+pub const RUN_ID_ENV: &str = "CODEX_FLOW_RUN_ID";
+pub const RUN_ID_PREFIX_ENV: &str = "CODEX_FLOW_RUN_ID_PREFIX";
+pub const METRICS_ENV: &str = "CODEX_FLOW_METRICS";
+pub const METRICS_ENDPOINT_ENV: &str = "CODEX_FLOW_METRICS_ENDPOINT";
+
+static FLOW_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Pins the flow root for the rest of the process (set from `--flow-dir`).
+pub fn set_flow_dir(path: PathBuf) {
+    let _ = FLOW_DIR_OVERRIDE.set(path);
+}
+
+/// Flow root requested via `--flow-dir` or `CODEX_FLOW_DIR`, if any.
+pub fn flow_dir_override() -> Option<PathBuf> {
+    if let Some(path) = FLOW_DIR_OVERRIDE.get() {
+        return Some(path.clone());
+    }
+    env::var_os(FLOW_DIR_ENV)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Root of the flow workspace; defaults to `.codex-flow` in the current directory.
+pub fn flow_dir() -> PathBuf {
+    flow_dir_override().unwrap_or_else(|| PathBuf::from(DEFAULT_FLOW_DIR))
+}
 
 pub fn resume_disabled() -> bool {
     match env::var(RESUME_DISABLED_ENV) {
@@ -10,6 +42,52 @@ pub fn resume_disabled() -> bool {
     }
 }
 
+/// `CODEX_FLOW_MOCK` as a default for `--mock/--no-mock`; unset or empty
+/// leaves the decision to the workflow's `defaults.mock`.
+pub fn mock_default() -> Option<bool> {
+    non_empty_var(MOCK_ENV).map(|value| parse_truthy(&value))
+}
+
+/// `CODEX_FLOW_VERBOSE` sets the minimum verbosity: a number is used as the
+/// `-v` count, any other truthy value means one `-v`.
+pub fn verbose_default() -> u8 {
+    match non_empty_var(VERBOSE_ENV) {
+        Some(value) => value
+            .parse()
+            .unwrap_or_else(|_| u8::from(parse_truthy(&value))),
+        None => 0,
+    }
+}
+
+/// `CODEX_FLOW_RUN_ID` supplies the run id when `--run-id` is not passed.
+pub fn run_id_default() -> Option<String> {
+    non_empty_var(RUN_ID_ENV)
+}
+
+/// `CODEX_FLOW_RUN_ID_PREFIX` is prepended to generated timestamp run ids.
+pub fn run_id_prefix() -> Option<String> {
+    non_empty_var(RUN_ID_PREFIX_ENV)
+}
+
+/// Usage metrics are recorded only when `CODEX_FLOW_METRICS` is set to a
+/// truthy value; unset or empty keeps them off.
+pub fn metrics_enabled() -> bool {
+    non_empty_var(METRICS_ENV).is_some_and(|value| parse_truthy(&value))
+}
+
+/// `CODEX_FLOW_METRICS_ENDPOINT` is where `codex-flow metrics flush` posts
+/// recorded metrics.
+pub fn metrics_endpoint() -> Option<String> {
+    non_empty_var(METRICS_ENDPOINT_ENV)
+}
+
+fn non_empty_var(name: &str) -> Option<String> {
+    env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
 fn parse_truthy(value: &str) -> bool {
     let trimmed = value.trim();
     if trimmed.is_empty() {
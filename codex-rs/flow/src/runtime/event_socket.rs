@@ -0,0 +1,161 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+/// Removes the socket file once the run that owns it finishes.
+pub struct EventSocketGuard {
+    path: PathBuf,
+}
+
+impl Drop for EventSocketGuard {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Binds `path` and installs a sink that streams runner events to every
+/// connected client as JSON lines. Clients that connect mid-run first receive
+/// the events emitted so far, so an editor attaching late still sees the
+/// whole step tree.
+#[cfg(unix)]
+pub fn serve(path: &Path) -> Result<EventSocketGuard> {
+    use std::os::unix::net::UnixListener;
+    use std::sync::Arc;
+
+    use anyhow::Context;
+
+    use crate::runner::events;
+
+    if path.exists() {
+        // A leftover socket from a crashed run would otherwise fail the bind.
+        if !unix::is_socket(path) {
+            anyhow::bail!(
+                "--events-socket {} already exists and is not a socket",
+                path.display()
+            );
+        }
+        std::fs::remove_file(path)
+            .with_context(|| format!("failed to remove stale socket {}", path.display()))?;
+    }
+    let listener = UnixListener::bind(path)
+        .with_context(|| format!("failed to bind events socket {}", path.display()))?;
+    let broadcaster = Arc::new(unix::Broadcaster::default());
+    let accepting = Arc::clone(&broadcaster);
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            accepting.attach(stream);
+        }
+    });
+    events::set_sink(Box::new(unix::SharedSink(broadcaster)));
+    Ok(EventSocketGuard {
+        path: path.to_path_buf(),
+    })
+}
+
+#[cfg(not(unix))]
+pub fn serve(path: &Path) -> Result<EventSocketGuard> {
+    anyhow::bail!(
+        "--events-socket {} requires unix domain sockets; named pipes are not supported yet",
+        path.display()
+    )
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::io::Write;
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::UnixStream;
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use crate::runner::events::EventSink;
+
+    /// Slow readers are dropped instead of stalling the run.
+    const WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+
+    pub(super) fn is_socket(path: &Path) -> bool {
+        std::fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_socket())
+    }
+
+    #[derive(Default)]
+    pub(super) struct Broadcaster {
+        inner: Mutex<Inner>,
+    }
+
+    #[derive(Default)]
+    struct Inner {
+        history: Vec<String>,
+        clients: Vec<UnixStream>,
+    }
+
+    impl Broadcaster {
+        pub(super) fn attach(&self, mut stream: UnixStream) {
+            let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+            let Ok(mut inner) = self.inner.lock() else {
+                return;
+            };
+            if inner
+                .history
+                .iter()
+                .all(|line| write_line(&mut stream, line))
+            {
+                inner.clients.push(stream);
+            }
+        }
+
+        pub(super) fn broadcast(&self, line: &str) {
+            let Ok(mut inner) = self.inner.lock() else {
+                return;
+            };
+            inner.history.push(line.to_string());
+            inner.clients.retain_mut(|stream| write_line(stream, line));
+        }
+    }
+
+    fn write_line(stream: &mut UnixStream, line: &str) -> bool {
+        stream
+            .write_all(line.as_bytes())
+            .and_then(|()| stream.write_all(b"\n"))
+            .is_ok()
+    }
+
+    pub(super) struct SharedSink(pub(super) Arc<Broadcaster>);
+
+    impl EventSink for SharedSink {
+        fn emit(&self, line: &str) {
+            self.0.broadcast(line);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::io::BufRead;
+        use std::io::BufReader;
+
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn late_clients_receive_history_then_live_events() {
+            let broadcaster = Broadcaster::default();
+            broadcaster.broadcast(r#"{"type":"workflow_started"}"#);
+
+            let (server, client) = UnixStream::pair().unwrap();
+            broadcaster.attach(server);
+            broadcaster.broadcast(r#"{"type":"step_started"}"#);
+            drop(broadcaster);
+
+            let lines: Vec<String> = BufReader::new(client).lines().map(Result::unwrap).collect();
+            assert_eq!(
+                lines,
+                vec![
+                    r#"{"type":"workflow_started"}"#.to_string(),
+                    r#"{"type":"step_started"}"#.to_string(),
+                ]
+            );
+        }
+    }
+}
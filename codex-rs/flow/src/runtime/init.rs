@@ -5,10 +5,12 @@ use std::path::PathBuf;
 use anyhow::Context;
 use anyhow::Result;
 
+use crate::runtime::config as runtime_config;
+
 const STATE_README_TEMPLATE: &str = include_str!("../../templates/runtime/README-state.md");
 
 pub fn ensure_runtime_tree() -> Result<PathBuf> {
-    ensure_runtime_tree_at(Path::new(".codex-flow"))
+    ensure_runtime_tree_at(&runtime_config::flow_dir())
 }
 
 pub fn ensure_runtime_tree_at(flow_root: &Path) -> Result<PathBuf> {
@@ -29,7 +31,7 @@ pub fn ensure_runtime_tree_at(flow_root: &Path) -> Result<PathBuf> {
 }
 
 pub fn warn_if_state_missing() {
-    warn_if_state_missing_at(Path::new(".codex-flow"));
+    warn_if_state_missing_at(&runtime_config::flow_dir());
 }
 
 pub fn warn_if_state_missing_at(flow_root: &Path) {
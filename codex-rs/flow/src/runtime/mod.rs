@@ -1,3 +1,4 @@
 pub mod config;
+pub mod event_socket;
 pub mod init;
 pub mod state_store;
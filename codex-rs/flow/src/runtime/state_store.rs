@@ -4,6 +4,8 @@ use std::path::PathBuf;
 use anyhow::Context;
 use anyhow::Result;
 
+use crate::runtime::config as runtime_config;
+
 const RUNTIME_STATE_ENV: &str = "CODEX_FLOW_RUNTIME_DIR";
 
 pub fn state_file_path(workflow_name: &str, run_id: &str) -> Result<PathBuf> {
@@ -30,6 +32,6 @@ pub fn runtime_root() -> PathBuf {
     if let Ok(path) = std::env::var(RUNTIME_STATE_ENV) {
         PathBuf::from(path)
     } else {
-        PathBuf::from(".codex-flow").join("runtime")
+        runtime_config::flow_dir().join("runtime")
     }
 }
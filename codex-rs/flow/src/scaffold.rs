@@ -7,6 +7,7 @@ use include_dir::DirEntry;
 use include_dir::include_dir;
 use walkdir::WalkDir;
 
+use crate::runtime::config as runtime_config;
 use crate::runtime::init as runtime_init;
 
 const DEFAULT_WORKFLOW_TOML: &str = r#"name = "commit_flow"
@@ -38,15 +39,23 @@ description = "从 git diff 生成提交信息"
 static EMBEDDED_PROMPTS: include_dir::Dir<'_> =
     include_dir!("$CARGO_MANIFEST_DIR/templates/prompts");
 
+/// Creates the default `.codex-flow` directory inside `target_dir`.
 pub fn init_scaffold(target_dir: &Path, templates_dir: Option<&Path>, force: bool) -> Result<()> {
-    let root = target_dir.join(".codex-flow");
+    init_scaffold_at(
+        &target_dir.join(runtime_config::DEFAULT_FLOW_DIR),
+        templates_dir,
+        force,
+    )
+}
+
+/// Populates `root`, the flow directory itself (e.g. the `--flow-dir` path).
+pub fn init_scaffold_at(root: &Path, templates_dir: Option<&Path>, force: bool) -> Result<()> {
     let prompts_dst = root.join("prompts");
     if !root.exists() {
-        fs::create_dir_all(&root)
-            .with_context(|| format!("failed to create {}", root.display()))?;
+        fs::create_dir_all(root).with_context(|| format!("failed to create {}", root.display()))?;
     }
 
-    runtime_init::ensure_runtime_tree_at(&root)?;
+    runtime_init::ensure_runtime_tree_at(root)?;
 
     fs::create_dir_all(&prompts_dst)
         .with_context(|| format!("failed to create {}", prompts_dst.display()))?;
@@ -56,7 +65,7 @@ pub fn init_scaffold(target_dir: &Path, templates_dir: Option<&Path>, force: boo
         copy_embedded_templates(&prompts_dst, force)?;
     }
 
-    // Create a sample single-workflow file under .codex-flow/workflows/
+    // Create a sample single-workflow file under <flow-dir>/workflows/
     let workflows_dir = root.join("workflows");
     fs::create_dir_all(&workflows_dir)
         .with_context(|| format!("failed to create {}", workflows_dir.display()))?;
@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::fs::{self};
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use chrono::Utc;
+use clap::ValueEnum;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::failure::FailureClass;
+use crate::runtime::config as runtime_config;
+use crate::runtime::state_store as runtime_state;
+
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One command invocation. Deliberately carries no workflow names, run ids,
+/// paths, or prompt content.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub command: String,
+    pub recorded_at: String,
+    pub duration_ms: u64,
+    /// `ok`, a failure class such as `config` or `step`, or `error`
+    pub outcome: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steps_executed: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steps_total: Option<usize>,
+    pub codex_flow_version: String,
+}
+
+/// Aggregated view of the records for one command.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CommandStats {
+    pub invocations: usize,
+    pub failures: usize,
+    pub total_duration_ms: u64,
+    pub steps_executed: usize,
+}
+
+#[derive(Serialize)]
+struct FlushPayload<'a> {
+    records: &'a [UsageRecord],
+}
+
+static STEP_COUNTS: Mutex<Option<(usize, usize)>> = Mutex::new(None);
+
+/// Local metrics log under the runtime root.
+pub fn metrics_path() -> PathBuf {
+    runtime_state::runtime_root()
+        .join("metrics")
+        .join("usage.jsonl")
+}
+
+/// Remembers the step counts of the current `run`/`resume` so the record
+/// written when the command finishes can include them.
+pub fn note_steps(executed: usize, total: usize) {
+    if let Ok(mut counts) = STEP_COUNTS.lock() {
+        *counts = Some((executed, total));
+    }
+}
+
+/// Appends a record for `command` when metrics are enabled. Failures to write
+/// are reported but never change the command's outcome.
+pub fn record(command: &str, duration: Duration, result: &Result<()>) {
+    if !runtime_config::metrics_enabled() {
+        return;
+    }
+    let steps = STEP_COUNTS.lock().ok().and_then(|mut counts| counts.take());
+    let outcome = match result {
+        Ok(()) => "ok".to_string(),
+        Err(err) => FailureClass::of(err)
+            .and_then(|class| class.to_possible_value())
+            .map_or_else(|| "error".to_string(), |value| value.get_name().to_string()),
+    };
+    let record = UsageRecord {
+        command: command.to_string(),
+        recorded_at: Utc::now().to_rfc3339(),
+        duration_ms: u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+        outcome,
+        steps_executed: steps.map(|(executed, _)| executed),
+        steps_total: steps.map(|(_, total)| total),
+        codex_flow_version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    if let Err(err) = append_record(&metrics_path(), &record) {
+        eprintln!("[metrics] failed to record usage: {err:#}");
+    }
+}
+
+fn append_record(path: &Path, record: &UsageRecord) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create metrics dir {}", dir.display()))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(record)?)
+        .with_context(|| format!("failed to append to {}", path.display()))
+}
+
+/// Reads every record from `path`, skipping lines that do not parse.
+pub fn load_records(path: &Path) -> Result<Vec<UsageRecord>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(raw
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+pub fn summarize(records: &[UsageRecord]) -> BTreeMap<String, CommandStats> {
+    let mut stats: BTreeMap<String, CommandStats> = BTreeMap::new();
+    for record in records {
+        let entry = stats.entry(record.command.clone()).or_default();
+        entry.invocations += 1;
+        if record.outcome != "ok" {
+            entry.failures += 1;
+        }
+        entry.total_duration_ms += record.duration_ms;
+        entry.steps_executed += record.steps_executed.unwrap_or(0);
+    }
+    stats
+}
+
+/// Posts the local records to `CODEX_FLOW_METRICS_ENDPOINT` as
+/// `{"records": [...]}` and removes them once the endpoint accepts them.
+/// Returns how many records were sent.
+pub fn flush() -> Result<usize> {
+    let Some(endpoint) = runtime_config::metrics_endpoint() else {
+        bail!(
+            "{} is not set; nothing to send metrics to",
+            runtime_config::METRICS_ENDPOINT_ENV
+        );
+    };
+    let path = metrics_path();
+    let records = load_records(&path)?;
+    if records.is_empty() {
+        return Ok(0);
+    }
+    let client = reqwest::blocking::Client::builder()
+        .timeout(FLUSH_TIMEOUT)
+        .build()
+        .context("failed to build metrics HTTP client")?;
+    client
+        .post(&endpoint)
+        .json(&FlushPayload { records: &records })
+        .send()
+        .and_then(reqwest::blocking::Response::error_for_status)
+        .with_context(|| format!("failed to send metrics to {endpoint}"))?;
+    clear()?;
+    Ok(records.len())
+}
+
+/// Deletes the local metrics log.
+pub fn clear() -> Result<()> {
+    let path = metrics_path();
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err).with_context(|| format!("failed to remove {}", path.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn usage(command: &str, outcome: &str, duration_ms: u64, steps: Option<usize>) -> UsageRecord {
+        UsageRecord {
+            command: command.to_string(),
+            recorded_at: "2025-11-11T12:00:00+00:00".to_string(),
+            duration_ms,
+            outcome: outcome.to_string(),
+            steps_executed: steps,
+            steps_total: steps,
+            codex_flow_version: "0.1.0".to_string(),
+        }
+    }
+
+    #[test]
+    fn appended_records_round_trip_into_per_command_stats() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("metrics/usage.jsonl");
+        append_record(&path, &usage("run", "ok", 1200, Some(3))).unwrap();
+        append_record(&path, &usage("run", "step", 800, Some(1))).unwrap();
+        append_record(&path, &usage("fmt", "ok", 15, None)).unwrap();
+
+        let records = load_records(&path).unwrap();
+        assert_eq!(records.len(), 3);
+        let stats = summarize(&records);
+        assert_eq!(
+            stats.get("run"),
+            Some(&CommandStats {
+                invocations: 2,
+                failures: 1,
+                total_duration_ms: 2000,
+                steps_executed: 4,
+            })
+        );
+        assert_eq!(stats.get("fmt").map(|stats| stats.invocations), Some(1));
+    }
+}
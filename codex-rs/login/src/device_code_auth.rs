@@ -194,7 +194,7 @@ pub async fn run_device_code_login(opts: ServerOptions) -> std::io::Result<()> {
     }
 
     crate::server::persist_tokens_async(
-        &opts.codex_home,
+        &opts.storage_settings,
         None,
         tokens.id_token,
         tokens.access_token,
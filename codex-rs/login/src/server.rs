@@ -4,8 +4,6 @@ use std::io::Write;
 use std::io::{self};
 use std::net::SocketAddr;
 use std::net::TcpStream;
-use std::path::Path;
-use std::path::PathBuf;
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
@@ -14,8 +12,10 @@ use crate::pkce::PkceCodes;
 use crate::pkce::generate_pkce;
 use base64::Engine;
 use chrono::Utc;
+use codex_core::auth::AccountMetadata;
 use codex_core::auth::AuthCredentialsStoreMode;
 use codex_core::auth::AuthDotJson;
+use codex_core::auth::AuthStorageSettings;
 use codex_core::auth::save_auth;
 use codex_core::default_client::originator;
 use codex_core::token_data::TokenData;
@@ -33,7 +33,7 @@ const DEFAULT_PORT: u16 = 1455;
 
 #[derive(Debug, Clone)]
 pub struct ServerOptions {
-    pub codex_home: PathBuf,
+    pub storage_settings: AuthStorageSettings,
     pub client_id: String,
     pub issuer: String,
     pub port: u16,
@@ -45,13 +45,13 @@ pub struct ServerOptions {
 
 impl ServerOptions {
     pub fn new(
-        codex_home: PathBuf,
+        storage_settings: AuthStorageSettings,
         client_id: String,
         forced_chatgpt_workspace_id: Option<String>,
         cli_auth_credentials_store_mode: AuthCredentialsStoreMode,
     ) -> Self {
         Self {
-            codex_home,
+            storage_settings,
             client_id,
             issuer: DEFAULT_ISSUER.to_string(),
             port: DEFAULT_PORT,
@@ -269,7 +269,7 @@ async fn process_request(
                         .await
                         .ok();
                     if let Err(err) = persist_tokens_async(
-                        &opts.codex_home,
+                        &opts.storage_settings,
                         api_key.clone(),
                         tokens.id_token.clone(),
                         tokens.access_token.clone(),
@@ -536,7 +536,7 @@ pub(crate) async fn exchange_code_for_tokens(
 }
 
 pub(crate) async fn persist_tokens_async(
-    codex_home: &Path,
+    storage_settings: &AuthStorageSettings,
     api_key: Option<String>,
     id_token: String,
     access_token: String,
@@ -544,7 +544,7 @@ pub(crate) async fn persist_tokens_async(
     auth_credentials_store_mode: AuthCredentialsStoreMode,
 ) -> io::Result<()> {
     // Reuse existing synchronous logic but run it off the async runtime.
-    let codex_home = codex_home.to_path_buf();
+    let storage_settings = storage_settings.clone();
     tokio::task::spawn_blocking(move || {
         let mut tokens = TokenData {
             id_token: parse_id_token(&id_token).map_err(io::Error::other)?,
@@ -563,8 +563,9 @@ pub(crate) async fn persist_tokens_async(
             tokens: Some(tokens),
             last_refresh: Some(Utc::now()),
             account_state: None,
+            metadata: AccountMetadata::default(),
         };
-        save_auth(&codex_home, &auth, auth_credentials_store_mode)
+        save_auth(&storage_settings, &auth, auth_credentials_store_mode)
     })
     .await
     .map_err(|e| io::Error::other(format!("persist task failed: {e}")))?
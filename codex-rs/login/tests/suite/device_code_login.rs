@@ -4,6 +4,7 @@ use anyhow::Context;
 use base64::Engine;
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use codex_core::auth::AuthCredentialsStoreMode;
+use codex_core::auth::AuthStorageSettings;
 use codex_core::auth::load_auth_dot_json;
 use codex_login::ServerOptions;
 use codex_login::run_device_code_login;
@@ -103,7 +104,7 @@ fn server_opts(
     cli_auth_credentials_store_mode: AuthCredentialsStoreMode,
 ) -> ServerOptions {
     let mut opts = ServerOptions::new(
-        codex_home.path().to_path_buf(),
+        AuthStorageSettings::new(codex_home.path().to_path_buf()),
         "client-id".to_string(),
         None,
         cli_auth_credentials_store_mode,
@@ -139,9 +140,12 @@ async fn device_code_login_integration_succeeds() -> anyhow::Result<()> {
         .await
         .expect("device code login integration should succeed");
 
-    let auth = load_auth_dot_json(codex_home.path(), AuthCredentialsStoreMode::File)
-        .context("auth.json should load after login succeeds")?
-        .context("auth.json written")?;
+    let auth = load_auth_dot_json(
+        &AuthStorageSettings::new(codex_home.path().to_path_buf()),
+        AuthCredentialsStoreMode::File,
+    )
+    .context("auth.json should load after login succeeds")?
+    .context("auth.json written")?;
     // assert_eq!(auth.openai_api_key.as_deref(), Some("api-key-321"));
     let tokens = auth.tokens.expect("tokens persisted");
     assert_eq!(tokens.access_token, "access-token-123");
@@ -180,8 +184,11 @@ async fn device_code_login_rejects_workspace_mismatch() -> anyhow::Result<()> {
         .expect_err("device code login should fail when workspace mismatches");
     assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
 
-    let auth = load_auth_dot_json(codex_home.path(), AuthCredentialsStoreMode::File)
-        .context("auth.json should load after login fails")?;
+    let auth = load_auth_dot_json(
+        &AuthStorageSettings::new(codex_home.path().to_path_buf()),
+        AuthCredentialsStoreMode::File,
+    )
+    .context("auth.json should load after login fails")?;
     assert!(
         auth.is_none(),
         "auth.json should not be created when workspace validation fails"
@@ -211,8 +218,11 @@ async fn device_code_login_integration_handles_usercode_http_failure() -> anyhow
         "unexpected error: {err:?}"
     );
 
-    let auth = load_auth_dot_json(codex_home.path(), AuthCredentialsStoreMode::File)
-        .context("auth.json should load after login fails")?;
+    let auth = load_auth_dot_json(
+        &AuthStorageSettings::new(codex_home.path().to_path_buf()),
+        AuthCredentialsStoreMode::File,
+    )
+    .context("auth.json should load after login fails")?;
     assert!(
         auth.is_none(),
         "auth.json should not be created when login fails"
@@ -240,7 +250,7 @@ async fn device_code_login_integration_persists_without_api_key_on_exchange_fail
     let issuer = mock_server.uri();
 
     let mut opts = ServerOptions::new(
-        codex_home.path().to_path_buf(),
+        AuthStorageSettings::new(codex_home.path().to_path_buf()),
         "client-id".to_string(),
         None,
         AuthCredentialsStoreMode::File,
@@ -252,9 +262,12 @@ async fn device_code_login_integration_persists_without_api_key_on_exchange_fail
         .await
         .expect("device login should succeed without API key exchange");
 
-    let auth = load_auth_dot_json(codex_home.path(), AuthCredentialsStoreMode::File)
-        .context("auth.json should load after login succeeds")?
-        .context("auth.json written")?;
+    let auth = load_auth_dot_json(
+        &AuthStorageSettings::new(codex_home.path().to_path_buf()),
+        AuthCredentialsStoreMode::File,
+    )
+    .context("auth.json should load after login succeeds")?
+    .context("auth.json written")?;
     assert!(auth.openai_api_key.is_none());
     let tokens = auth.tokens.expect("tokens persisted");
     assert_eq!(tokens.access_token, "access-token-123");
@@ -290,7 +303,7 @@ async fn device_code_login_integration_handles_error_payload() -> anyhow::Result
     let issuer = mock_server.uri();
 
     let mut opts = ServerOptions::new(
-        codex_home.path().to_path_buf(),
+        AuthStorageSettings::new(codex_home.path().to_path_buf()),
         "client-id".to_string(),
         None,
         AuthCredentialsStoreMode::File,
@@ -308,8 +321,11 @@ async fn device_code_login_integration_handles_error_payload() -> anyhow::Result
         "Expected an authorization_declined / 400 / 404 error, got {err:?}"
     );
 
-    let auth = load_auth_dot_json(codex_home.path(), AuthCredentialsStoreMode::File)
-        .context("auth.json should load after login fails")?;
+    let auth = load_auth_dot_json(
+        &AuthStorageSettings::new(codex_home.path().to_path_buf()),
+        AuthCredentialsStoreMode::File,
+    )
+    .context("auth.json should load after login fails")?;
     assert!(
         auth.is_none(),
         "auth.json should not be created when device auth fails"
@@ -8,6 +8,7 @@ use std::time::Duration;
 use anyhow::Result;
 use base64::Engine;
 use codex_core::auth::AuthCredentialsStoreMode;
+use codex_core::auth::AuthStorageSettings;
 use codex_login::ServerOptions;
 use codex_login::run_login_server;
 use core_test_support::skip_if_no_network;
@@ -110,7 +111,7 @@ async fn end_to_end_login_flow_persists_auth_json() -> Result<()> {
     let server_home = codex_home.clone();
 
     let opts = ServerOptions {
-        codex_home: server_home,
+        storage_settings: AuthStorageSettings::new(server_home),
         cli_auth_credentials_store_mode: AuthCredentialsStoreMode::File,
         client_id: codex_login::CLIENT_ID.to_string(),
         issuer,
@@ -171,7 +172,7 @@ async fn creates_missing_codex_home_dir() -> Result<()> {
     // Run server in background
     let server_home = codex_home.clone();
     let opts = ServerOptions {
-        codex_home: server_home,
+        storage_settings: AuthStorageSettings::new(server_home),
         cli_auth_credentials_store_mode: AuthCredentialsStoreMode::File,
         client_id: codex_login::CLIENT_ID.to_string(),
         issuer,
@@ -210,7 +211,7 @@ async fn forced_chatgpt_workspace_id_mismatch_blocks_login() -> Result<()> {
     let state = "state-mismatch".to_string();
 
     let opts = ServerOptions {
-        codex_home: codex_home.clone(),
+        storage_settings: AuthStorageSettings::new(codex_home.clone()),
         cli_auth_credentials_store_mode: AuthCredentialsStoreMode::File,
         client_id: codex_login::CLIENT_ID.to_string(),
         issuer,
@@ -266,7 +267,7 @@ async fn cancels_previous_login_server_when_port_is_in_use() -> Result<()> {
     let first_codex_home = first_tmp.path().to_path_buf();
 
     let first_opts = ServerOptions {
-        codex_home: first_codex_home,
+        storage_settings: AuthStorageSettings::new(first_codex_home),
         cli_auth_credentials_store_mode: AuthCredentialsStoreMode::File,
         client_id: codex_login::CLIENT_ID.to_string(),
         issuer: issuer.clone(),
@@ -286,7 +287,7 @@ async fn cancels_previous_login_server_when_port_is_in_use() -> Result<()> {
     let second_codex_home = second_tmp.path().to_path_buf();
 
     let second_opts = ServerOptions {
-        codex_home: second_codex_home,
+        storage_settings: AuthStorageSettings::new(second_codex_home),
         cli_auth_credentials_store_mode: AuthCredentialsStoreMode::File,
         client_id: codex_login::CLIENT_ID.to_string(),
         issuer,
@@ -301,7 +301,11 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::UndoStarted(_)
                     | EventMsg::UndoCompleted(_)
                     | EventMsg::ExitedReviewMode(_)
-                    | EventMsg::DeprecationNotice(_) => {
+                    | EventMsg::DeprecationNotice(_)
+                    | EventMsg::AccountsUsageLimited(_)
+                    | EventMsg::SubAgentSummary(_)
+                    | EventMsg::ToolCallMetrics(_)
+                    | EventMsg::ToolUsageSummary(_) => {
                         // For now, we do not do anything extra for these
                         // events. Note that
                         // send(codex_event_to_notification(&event)) above has
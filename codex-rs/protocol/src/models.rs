@@ -322,6 +322,34 @@ pub struct ShellToolCallParams {
     pub with_escalated_permissions: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub justification: Option<String>,
+    /// Start the command as a background job and return its job id instead
+    /// of waiting for it to finish.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_in_background: Option<bool>,
+    /// Caps on the output returned for this call, overriding
+    /// `shell_output_max_bytes` / `shell_output_max_lines` from the config.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_bytes: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_lines: Option<usize>,
+    /// Run the command attached to a pseudo-terminal, for programs that
+    /// behave differently when stdout is not a TTY.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tty: Option<bool>,
+    /// Cut this command off from the network even when the session allows
+    /// network access.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<ShellNetworkAccess>,
+}
+
+/// Network access requested for a single shell command.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum ShellNetworkAccess {
+    /// Whatever the session's sandbox policy allows.
+    On,
+    /// No network access, enforced by the sandbox.
+    Off,
 }
 
 /// If the `name` of a `ResponseItem::FunctionCall` is `shell_command`, the
@@ -338,6 +366,14 @@ pub struct ShellCommandToolCallParams {
     pub with_escalated_permissions: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub justification: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_bytes: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_lines: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tty: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<ShellNetworkAccess>,
 }
 
 /// Responses API compatible content items that can be returned by a tool call.
@@ -650,12 +686,29 @@ mod tests {
                 timeout_ms: Some(1000),
                 with_escalated_permissions: None,
                 justification: None,
+                run_in_background: None,
+                max_output_bytes: None,
+                max_output_lines: None,
+                tty: None,
+                network: None,
             },
             params
         );
         Ok(())
     }
 
+    #[test]
+    fn deserialize_shell_tool_call_params_with_network_off() -> Result<()> {
+        let json = r#"{
+            "command": ["make"],
+            "network": "off"
+        }"#;
+
+        let params: ShellToolCallParams = serde_json::from_str(json)?;
+        assert_eq!(params.network, Some(ShellNetworkAccess::Off));
+        Ok(())
+    }
+
     #[test]
     fn local_image_read_error_adds_placeholder() -> Result<()> {
         let dir = tempdir()?;
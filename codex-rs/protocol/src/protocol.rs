@@ -356,6 +356,33 @@ impl SandboxPolicy {
         }
     }
 
+    /// Returns this policy with outbound network access turned off. Disk
+    /// access is unchanged, except that `DangerFullAccess` becomes a
+    /// workspace-write policy whose writable root is `/`, since only the
+    /// sandboxed policies can cut off the network.
+    pub fn without_network_access(&self) -> SandboxPolicy {
+        match self {
+            SandboxPolicy::DangerFullAccess => SandboxPolicy::WorkspaceWrite {
+                writable_roots: vec![PathBuf::from("/")],
+                network_access: false,
+                exclude_tmpdir_env_var: false,
+                exclude_slash_tmp: false,
+            },
+            SandboxPolicy::ReadOnly => SandboxPolicy::ReadOnly,
+            SandboxPolicy::WorkspaceWrite {
+                writable_roots,
+                network_access: _,
+                exclude_tmpdir_env_var,
+                exclude_slash_tmp,
+            } => SandboxPolicy::WorkspaceWrite {
+                writable_roots: writable_roots.clone(),
+                network_access: false,
+                exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
+                exclude_slash_tmp: *exclude_slash_tmp,
+            },
+        }
+    }
+
     /// Returns the list of writable roots (tailored to the current working
     /// directory) together with subpaths that should remain read‑only under
     /// each writable root.
@@ -511,6 +538,21 @@ pub enum EventMsg {
     /// deprecated and should be phased out.
     DeprecationNotice(DeprecationNoticeEvent),
 
+    /// Every stored ChatGPT account has hit its usage limit; reports when
+    /// each one can be used again.
+    AccountsUsageLimited(AccountsUsageLimitedEvent),
+
+    /// Totals of the `coco` sub-agents this session has run, sent when a task
+    /// that ran any of them completes.
+    SubAgentSummary(SubAgentSummaryEvent),
+
+    /// How a single tool call went, sent once its result is ready.
+    ToolCallMetrics(ToolCallMetricsEvent),
+
+    /// Per-tool totals for the task, sent before `TaskComplete` when any
+    /// tool was called.
+    ToolUsageSummary(ToolUsageSummaryEvent),
+
     BackgroundEvent(BackgroundEventEvent),
 
     UndoStarted(UndoStartedEvent),
@@ -686,6 +728,27 @@ pub struct ExitedReviewModeEvent {
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ErrorEvent {
     pub message: String,
+    /// What kind of failure ended the turn, when it came from the agent loop.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub kind: Option<CodexErrorKind>,
+}
+
+/// Broad class of a failure reported by [`ErrorEvent`], so clients such as
+/// `codex exec` can react without parsing the message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum CodexErrorKind {
+    /// Any failure not covered by a more specific kind.
+    TurnFailed,
+    /// The model stream failed or disconnected and retries ran out.
+    StreamError,
+    /// The account hit a usage limit or quota.
+    UsageLimit,
+    /// The sandbox refused or could not run something the turn needed.
+    SandboxDenied,
+    /// Something the turn waited on timed out.
+    Timeout,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1082,6 +1145,14 @@ pub enum SessionSource {
 pub enum SubAgentSource {
     Review,
     Compact,
+    /// A `coco` sub-agent started by tool call `call_id` of
+    /// `parent_conversation_id`. `depth` is 1 for one started by a top-level
+    /// session and grows by one per nested `coco`.
+    Coco {
+        depth: u32,
+        parent_conversation_id: ConversationId,
+        call_id: String,
+    },
     Other(String),
 }
 
@@ -1331,6 +1402,67 @@ pub struct ExecCommandOutputDeltaEvent {
     #[schemars(with = "String")]
     #[ts(type = "string")]
     pub chunk: Vec<u8>,
+    /// Set when the chunk is part of a `coco` sub-agent's log, so clients
+    /// can nest it under the `coco` call instead of showing flat exec output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub sub_agent: Option<SubAgentOutputSource>,
+}
+
+/// The `coco` sub-agent an [`ExecCommandOutputDeltaEvent`] came from.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct SubAgentOutputSource {
+    /// Nesting depth, as in [`SubAgentSource::Coco`].
+    pub depth: u32,
+    /// The sub-agent's conversation, once it has started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub conversation_id: Option<ConversationId>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct SubAgentSummaryEvent {
+    /// Sub-agents started, not counting retries of the same run or answers
+    /// served from the result cache.
+    pub invocations: u64,
+    pub succeeded: u64,
+    /// Failed runs, including those that timed out.
+    pub failed: u64,
+    pub timed_out: u64,
+    /// Time spent in sub-agents, summed over all runs.
+    #[ts(type = "string")]
+    pub total_duration: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ToolCallMetricsEvent {
+    pub call_id: String,
+    pub tool_name: String,
+    /// Time from dispatch to result, including waits for approval.
+    #[ts(type = "string")]
+    pub duration: Duration,
+    pub success: bool,
+    /// Size of the call's arguments.
+    pub input_bytes: u64,
+    /// Size of the result returned to the model.
+    pub output_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ToolUsageSummaryEvent {
+    /// One entry per tool, most total time first.
+    pub tools: Vec<ToolUsageStats>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ToolUsageStats {
+    pub tool_name: String,
+    pub calls: u64,
+    pub failures: u64,
+    #[ts(type = "string")]
+    pub total_duration: Duration,
+    pub input_bytes: u64,
+    pub output_bytes: u64,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1338,6 +1470,25 @@ pub struct BackgroundEventEvent {
     pub message: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct AccountsUsageLimitedEvent {
+    /// Unix timestamp (seconds since epoch) when the first account becomes
+    /// usable again.
+    #[ts(type = "number")]
+    pub retry_at: i64,
+    /// Every stored account in rotation order.
+    pub accounts: Vec<AccountUsageLimit>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct AccountUsageLimit {
+    pub email: String,
+    /// Unix timestamp (seconds since epoch) when the account's usage limit
+    /// resets.
+    #[ts(type = "number")]
+    pub retry_at: i64,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct DeprecationNoticeEvent {
     /// Concise summary of what is deprecated.
@@ -1586,6 +1737,37 @@ mod tests {
     use serde_json::json;
     use tempfile::NamedTempFile;
 
+    #[test]
+    fn without_network_access_keeps_disk_access() {
+        let workspace = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![PathBuf::from("/data")],
+            network_access: true,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: false,
+        };
+        assert_eq!(
+            workspace.without_network_access(),
+            SandboxPolicy::WorkspaceWrite {
+                writable_roots: vec![PathBuf::from("/data")],
+                network_access: false,
+                exclude_tmpdir_env_var: true,
+                exclude_slash_tmp: false,
+            }
+        );
+
+        let full = SandboxPolicy::DangerFullAccess.without_network_access();
+        assert!(!full.has_full_network_access());
+        assert!(
+            full.get_writable_roots_with_cwd(Path::new("/home/user/project"))
+                .iter()
+                .any(|root| root.root == Path::new("/"))
+        );
+        assert_eq!(
+            SandboxPolicy::ReadOnly.without_network_access(),
+            SandboxPolicy::ReadOnly
+        );
+    }
+
     #[test]
     fn item_started_event_from_web_search_emits_begin_event() {
         let event = ItemStartedEvent {
@@ -1667,6 +1849,7 @@ mod tests {
             call_id: "call21".to_string(),
             stream: ExecOutputStream::Stdout,
             chunk: vec![1, 2, 3, 4, 5],
+            sub_agent: None,
         };
         let serialized = serde_json::to_string(&event)?;
         assert_eq!(
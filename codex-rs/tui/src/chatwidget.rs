@@ -11,6 +11,7 @@ use codex_core::config::types::Notifications;
 use codex_core::git_info::current_branch_name;
 use codex_core::git_info::local_git_branches;
 use codex_core::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
+use codex_core::protocol::AccountsUsageLimitedEvent;
 use codex_core::protocol::AgentMessageDeltaEvent;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningDeltaEvent;
@@ -40,6 +41,7 @@ use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::StreamErrorEvent;
+use codex_core::protocol::SubAgentSummaryEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
@@ -115,9 +117,12 @@ use self::session_header::SessionHeader;
 use crate::streaming::controller::StreamController;
 use std::path::Path;
 
+use chrono::DateTime;
 use chrono::Local;
+use chrono::Utc;
 use codex_common::approval_presets::ApprovalPreset;
 use codex_common::approval_presets::builtin_approval_presets;
+use codex_common::elapsed::format_duration;
 use codex_common::model_presets::ModelPreset;
 use codex_common::model_presets::builtin_model_presets;
 use codex_core::AuthManager;
@@ -824,6 +829,32 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn on_accounts_usage_limited(&mut self, event: AccountsUsageLimitedEvent) {
+        let now = Local::now();
+        let until = DateTime::<Utc>::from_timestamp(event.retry_at, 0)
+            .map(|dt| crate::status::format_reset_timestamp(dt.with_timezone(&Local), now))
+            .unwrap_or_else(|| event.retry_at.to_string());
+        self.on_warning(format!("All accounts are usage limited until {until}"));
+    }
+
+    fn on_sub_agent_summary(&mut self, event: SubAgentSummaryEvent) {
+        let SubAgentSummaryEvent {
+            invocations,
+            succeeded,
+            failed,
+            timed_out,
+            total_duration,
+        } = event;
+        let mut message =
+            format!("coco sub-agents: {invocations} run, {succeeded} succeeded, {failed} failed");
+        if timed_out > 0 {
+            message.push_str(&format!(" ({timed_out} timed out)"));
+        }
+        let hint = format!("{} total", format_duration(total_duration));
+        self.add_to_history(history_cell::new_info_event(message, Some(hint)));
+        self.request_redraw();
+    }
+
     fn on_background_event(&mut self, message: String) {
         debug!("BackgroundEvent: {message}");
         self.bottom_pane.ensure_status_indicator();
@@ -1401,7 +1432,7 @@ impl ChatWidget {
             }
             SlashCommand::Logout => {
                 if let Err(e) = codex_core::auth::logout(
-                    &self.config.codex_home,
+                    &self.config.auth_storage_settings(),
                     self.config.cli_auth_credentials_store_mode,
                 ) {
                     tracing::error!("failed to logout: {e}");
@@ -1654,7 +1685,7 @@ impl ChatWidget {
                 self.on_rate_limit_snapshot(ev.rate_limits);
             }
             EventMsg::Warning(WarningEvent { message }) => self.on_warning(message),
-            EventMsg::Error(ErrorEvent { message }) => self.on_error(message),
+            EventMsg::Error(ErrorEvent { message, .. }) => self.on_error(message),
             EventMsg::McpStartupUpdate(ev) => self.on_mcp_startup_update(ev),
             EventMsg::McpStartupComplete(ev) => self.on_mcp_startup_complete(ev),
             EventMsg::TurnAborted(ev) => match ev.reason {
@@ -1692,6 +1723,8 @@ impl ChatWidget {
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
             EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
             EventMsg::DeprecationNotice(ev) => self.on_deprecation_notice(ev),
+            EventMsg::AccountsUsageLimited(ev) => self.on_accounts_usage_limited(ev),
+            EventMsg::SubAgentSummary(ev) => self.on_sub_agent_summary(ev),
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
                 self.on_background_event(message)
             }
@@ -1708,6 +1741,8 @@ impl ChatWidget {
             }
             EventMsg::ExitedReviewMode(review) => self.on_exited_review_mode(review),
             EventMsg::RawResponseItem(_)
+            | EventMsg::ToolCallMetrics(_)
+            | EventMsg::ToolUsageSummary(_)
             | EventMsg::ItemStarted(_)
             | EventMsg::ItemCompleted(_)
             | EventMsg::AgentMessageContentDelta(_)
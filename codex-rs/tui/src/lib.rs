@@ -383,10 +383,13 @@ async fn run_ratatui_app(
     session_log::maybe_init(&initial_config);
 
     let auth_manager = AuthManager::shared(
-        initial_config.codex_home.clone(),
+        initial_config.auth_storage_settings(),
         false,
         initial_config.cli_auth_credentials_store_mode,
     );
+    if let Err(err) = auth_manager.watch_auth_files() {
+        tracing::warn!("failed to watch auth files: {err}");
+    }
     let login_status = get_login_status(&initial_config);
     let should_show_trust_screen = should_show_trust_screen(&initial_config);
     let should_show_onboarding =
@@ -536,8 +539,11 @@ fn get_login_status(config: &Config) -> LoginStatus {
     if config.model_provider.requires_openai_auth {
         // Reading the OpenAI API key is an async operation because it may need
         // to refresh the token. Block on it.
-        let codex_home = config.codex_home.clone();
-        match CodexAuth::from_auth_storage(&codex_home, config.cli_auth_credentials_store_mode) {
+        let storage_settings = config.auth_storage_settings();
+        match CodexAuth::from_auth_storage(
+            &storage_settings,
+            config.cli_auth_credentials_store_mode,
+        ) {
             Ok(Some(auth)) => LoginStatus::AuthMode(auth.mode),
             Ok(None) => LoginStatus::NotAuthenticated,
             Err(err) => {
@@ -2,6 +2,7 @@
 
 use codex_core::AuthManager;
 use codex_core::auth::AuthCredentialsStoreMode;
+use codex_core::auth::AuthStorageSettings;
 use codex_core::auth::CLIENT_ID;
 use codex_core::auth::login_with_api_key;
 use codex_core::auth::read_openai_api_key_from_env;
@@ -38,7 +39,6 @@ use crate::onboarding::onboarding_screen::KeyboardHandler;
 use crate::onboarding::onboarding_screen::StepStateProvider;
 use crate::shimmer::shimmer_spans;
 use crate::tui::FrameRequester;
-use std::path::PathBuf;
 use std::sync::Arc;
 
 use super::onboarding_screen::StepState;
@@ -149,7 +149,7 @@ pub(crate) struct AuthModeWidget {
     pub highlighted_mode: AuthMode,
     pub error: Option<String>,
     pub sign_in_state: Arc<RwLock<SignInState>>,
-    pub codex_home: PathBuf,
+    pub storage_settings: AuthStorageSettings,
     pub cli_auth_credentials_store_mode: AuthCredentialsStoreMode,
     pub login_status: LoginStatus,
     pub auth_manager: Arc<AuthManager>,
@@ -518,7 +518,7 @@ impl AuthModeWidget {
             return;
         }
         match login_with_api_key(
-            &self.codex_home,
+            &self.storage_settings,
             &api_key,
             self.cli_auth_credentials_store_mode,
         ) {
@@ -559,7 +559,7 @@ impl AuthModeWidget {
 
         self.error = None;
         let opts = ServerOptions::new(
-            self.codex_home.clone(),
+            self.storage_settings.clone(),
             CLIENT_ID.to_string(),
             self.forced_chatgpt_workspace_id.clone(),
             self.cli_auth_credentials_store_mode,
@@ -654,17 +654,17 @@ mod tests {
 
     fn widget_forced_chatgpt() -> (AuthModeWidget, TempDir) {
         let codex_home = TempDir::new().unwrap();
-        let codex_home_path = codex_home.path().to_path_buf();
+        let storage_settings = AuthStorageSettings::new(codex_home.path().to_path_buf());
         let widget = AuthModeWidget {
             request_frame: FrameRequester::test_dummy(),
             highlighted_mode: AuthMode::ChatGPT,
             error: None,
             sign_in_state: Arc::new(RwLock::new(SignInState::PickMode)),
-            codex_home: codex_home_path.clone(),
+            storage_settings: storage_settings.clone(),
             cli_auth_credentials_store_mode: AuthCredentialsStoreMode::File,
             login_status: LoginStatus::NotAuthenticated,
             auth_manager: AuthManager::shared(
-                codex_home_path,
+                storage_settings,
                 false,
                 AuthCredentialsStoreMode::File,
             ),
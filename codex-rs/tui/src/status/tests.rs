@@ -31,7 +31,7 @@ fn test_config(temp_home: &TempDir) -> Config {
 
 fn test_auth_manager(config: &Config) -> AuthManager {
     AuthManager::new(
-        config.codex_home.clone(),
+        config.auth_storage_settings(),
         false,
         config.cli_auth_credentials_store_mode,
     )